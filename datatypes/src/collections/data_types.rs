@@ -451,6 +451,8 @@ impl FeatureCollectionModifications for TypedFeatureCollection {
 
     impl_mod_function_by_forwarding_ref!(fn sort_by_time_asc(&self) -> Result<Self::Output>);
 
+    impl_mod_function_by_forwarding_ref!(fn take(&self, indices: &[usize]) -> Result<Self::Output>);
+
     impl_mod_function_by_forwarding_ref!(fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>);
 }
 
@@ -492,6 +494,8 @@ impl<'c> FeatureCollectionModifications for TypedFeatureCollectionRef<'c> {
 
     impl_mod_function_by_forwarding_ref2!(fn sort_by_time_asc(&self) -> Result<Self::Output>);
 
+    impl_mod_function_by_forwarding_ref2!(fn take(&self, indices: &[usize]) -> Result<Self::Output>);
+
     impl_mod_function_by_forwarding_ref2!(fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>);
 }
 
@@ -40,6 +40,11 @@ pub enum FeatureCollectionError {
         b: usize,
     },
 
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+    },
+
     UnmatchedSchema {
         a: Vec<String>,
         b: Vec<String>,
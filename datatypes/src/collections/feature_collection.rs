@@ -30,6 +30,7 @@ use crate::primitives::{
 };
 use crate::util::arrow::{downcast_array, ArrowTyped};
 use crate::util::helpers::SomeIter;
+use crate::util::memory::{MemoryReservation, MEMORY_ACCOUNTANT};
 use crate::util::Result;
 use crate::{
     collections::{error, IntoGeometryIterator, VectorDataType, VectorDataTyped},
@@ -54,6 +55,10 @@ pub struct FeatureCollection<CollectionType> {
 
     #[serde(skip)]
     collection_type: PhantomData<CollectionType>,
+
+    /// Accounts for the memory of the table, shared between clones
+    #[serde(skip)]
+    memory: Arc<MemoryReservation>,
 }
 
 impl<CollectionType> FeatureCollection<CollectionType> {
@@ -69,10 +74,13 @@ impl<CollectionType> FeatureCollection<CollectionType> {
         table: StructArray,
         types: HashMap<String, FeatureDataType>,
     ) -> Self {
+        let memory = Arc::new(MEMORY_ACCOUNTANT.reserve(table.get_array_memory_size()));
+
         Self {
             table,
             types,
             collection_type: Default::default(),
+            memory,
         }
     }
 }
@@ -168,6 +176,15 @@ pub trait FeatureCollectionModifications {
     /// Sorts the features in this collection by their timestamps ascending.
     fn sort_by_time_asc(&self) -> Result<Self::Output>;
 
+    /// Creates a collection of the features at the given indices in the order of the indices.
+    /// Indices may occur multiple times.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if an index is out of bounds
+    ///
+    fn take(&self, indices: &[usize]) -> Result<Self::Output>;
+
     /// Replaces the current time intervals and returns an updated collection.
     fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>;
 }
@@ -678,6 +695,25 @@ where
         Ok(Self::new_from_internals(table, self.types.clone()))
     }
 
+    fn take(&self, indices: &[usize]) -> Result<Self::Output> {
+        if let Some(&index) = indices.iter().find(|&&index| index >= self.table.len()) {
+            return Err(FeatureCollectionError::IndexOutOfBounds {
+                index,
+                len: self.table.len(),
+            }
+            .into());
+        }
+
+        let indices =
+            arrow::array::UInt32Array::from_iter_values(indices.iter().map(|&index| index as u32));
+
+        let table_ref = arrow::compute::take(&self.table, &indices, None)?;
+
+        let table = StructArray::from(table_ref.data().clone());
+
+        Ok(Self::new_from_internals(table, self.types.clone()))
+    }
+
     fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output> {
         let mut time_intervals_builder = TimeInterval::arrow_builder(time_intervals.len());
 
@@ -1254,6 +1290,65 @@ where
         )
     }
 
+    /// Replaces the geometries of all features and keeps the time intervals and attributes
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the number of geometries does not match the number of features
+    ///
+    pub fn replace_geometries(&self, geometries: Vec<CollectionType>) -> Result<Self> {
+        ensure!(
+            geometries.len() == self.len(),
+            error::UnmatchedLength {
+                a: geometries.len(),
+                b: self.len()
+            }
+        );
+
+        let mut columns = Vec::<Field>::with_capacity(self.table.num_columns());
+        let mut column_values = Vec::<ArrayRef>::with_capacity(self.table.num_columns());
+
+        if CollectionType::IS_GEOMETRY {
+            columns.push(Field::new(
+                Self::GEOMETRY_COLUMN_NAME,
+                CollectionType::arrow_data_type(),
+                false,
+            ));
+            column_values.push(Arc::new(CollectionType::from_vec(geometries)?));
+        }
+
+        columns.push(Field::new(
+            Self::TIME_COLUMN_NAME,
+            TimeInterval::arrow_data_type(),
+            false,
+        ));
+        column_values.push(
+            self.table
+                .column_by_name(Self::TIME_COLUMN_NAME)
+                .expect("The time column should exist because it was added to the collection during construction.")
+                .clone(),
+        );
+
+        for (column_name, column_type) in &self.types {
+            columns.push(Field::new(
+                column_name,
+                column_type.arrow_data_type(),
+                column_type.nullable(),
+            ));
+            column_values.push(
+                self.table
+                    .column_by_name(column_name)
+                    .expect("The attribute column should exist because `types` and `table` are in sync.")
+                    .clone(),
+            );
+        }
+
+        Ok(Self::new_from_internals(
+            struct_array_from_data(columns, column_values, self.table.len())?,
+            self.types.clone(),
+        ))
+    }
+
     /// Checks for name conflicts with reserved names
     pub(super) fn is_reserved_name(name: &str) -> bool {
         name == Self::GEOMETRY_COLUMN_NAME || name == Self::TIME_COLUMN_NAME
@@ -1266,6 +1361,7 @@ impl<CollectionType> Clone for FeatureCollection<CollectionType> {
             table: StructArray::from(self.table.data().clone()),
             types: self.types.clone(),
             collection_type: Default::default(),
+            memory: self.memory.clone(),
         }
     }
 }
@@ -1687,4 +1783,40 @@ mod tests {
             .rename_columns(&[("foo", "baz"), ("bar", "baz")])
             .is_err());
     }
+
+    #[test]
+    fn take() {
+        let collection = DataCollection::from_data(
+            vec![],
+            vec![
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(1, 2).unwrap(),
+                TimeInterval::new(2, 3).unwrap(),
+            ],
+            [("foo".to_string(), FeatureData::Int(vec![1, 2, 3]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            collection.take(&[2, 0, 2]).unwrap(),
+            DataCollection::from_data(
+                vec![],
+                vec![
+                    TimeInterval::new(2, 3).unwrap(),
+                    TimeInterval::new(0, 1).unwrap(),
+                    TimeInterval::new(2, 3).unwrap(),
+                ],
+                [("foo".to_string(), FeatureData::Int(vec![3, 1, 3]))]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            )
+            .unwrap()
+        );
+
+        assert!(collection.take(&[3]).is_err());
+    }
 }
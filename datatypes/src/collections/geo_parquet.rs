@@ -0,0 +1,321 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BinaryArray, TimestampMillisecondArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+
+use crate::collections::{
+    FeatureCollection, FeatureCollectionInfos, IntoGeometryIterator, MultiLineStringCollection,
+    MultiPointCollection, MultiPolygonCollection,
+};
+use crate::primitives::{
+    Coordinate2D, MultiLineStringAccess, MultiPointAccess, MultiPolygonAccess,
+};
+use crate::util::Result;
+
+const GEO_PARQUET_VERSION: &str = "1.0.0-beta.1";
+const GEOMETRY_COLUMN: &str = "geometry";
+const TIME_START_COLUMN: &str = "time_start";
+const TIME_END_COLUMN: &str = "time_end";
+
+const WKB_LITTLE_ENDIAN: u8 = 1;
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTI_POINT: u32 = 4;
+const WKB_MULTI_LINE_STRING: u32 = 5;
+const WKB_MULTI_POLYGON: u32 = 6;
+
+/// Feature collections whose geometries can be encoded as well-known binary (WKB)
+pub trait WkbGeometries {
+    /// The geometry type as named in the GeoParquet metadata
+    const GEOMETRY_TYPE: &'static str;
+
+    fn wkb_geometries(&self) -> BinaryArray;
+}
+
+macro_rules! impl_wkb_geometries {
+    ($collection:ty, $geometry_type:literal, $encode:ident) => {
+        impl WkbGeometries for $collection {
+            const GEOMETRY_TYPE: &'static str = $geometry_type;
+
+            fn wkb_geometries(&self) -> BinaryArray {
+                let geometries: Vec<Vec<u8>> = self
+                    .geometries()
+                    .map(|geometry| {
+                        let mut wkb = Vec::new();
+                        $encode(&mut wkb, &geometry);
+                        wkb
+                    })
+                    .collect();
+
+                BinaryArray::from_iter_values(geometries)
+            }
+        }
+    };
+}
+
+impl_wkb_geometries!(MultiPointCollection, "MultiPoint", write_multi_point);
+impl_wkb_geometries!(
+    MultiLineStringCollection,
+    "MultiLineString",
+    write_multi_line_string
+);
+impl_wkb_geometries!(MultiPolygonCollection, "MultiPolygon", write_multi_polygon);
+
+fn write_header(wkb: &mut Vec<u8>, geometry_type: u32) {
+    wkb.push(WKB_LITTLE_ENDIAN);
+    wkb.extend_from_slice(&geometry_type.to_le_bytes());
+}
+
+fn write_count(wkb: &mut Vec<u8>, count: usize) {
+    wkb.extend_from_slice(&(count as u32).to_le_bytes());
+}
+
+fn write_coordinates(wkb: &mut Vec<u8>, coordinates: &[Coordinate2D]) {
+    write_count(wkb, coordinates.len());
+    for coordinate in coordinates {
+        wkb.extend_from_slice(&coordinate.x.to_le_bytes());
+        wkb.extend_from_slice(&coordinate.y.to_le_bytes());
+    }
+}
+
+fn write_multi_point<M: MultiPointAccess>(wkb: &mut Vec<u8>, multi_point: &M) {
+    write_header(wkb, WKB_MULTI_POINT);
+    write_count(wkb, multi_point.points().len());
+    for point in multi_point.points() {
+        write_header(wkb, WKB_POINT);
+        wkb.extend_from_slice(&point.x.to_le_bytes());
+        wkb.extend_from_slice(&point.y.to_le_bytes());
+    }
+}
+
+fn write_multi_line_string<M: MultiLineStringAccess>(wkb: &mut Vec<u8>, multi_line_string: &M) {
+    write_header(wkb, WKB_MULTI_LINE_STRING);
+    write_count(wkb, multi_line_string.lines().len());
+    for line in multi_line_string.lines() {
+        write_header(wkb, WKB_LINE_STRING);
+        write_coordinates(wkb, line.as_ref());
+    }
+}
+
+fn write_multi_polygon<M: MultiPolygonAccess>(wkb: &mut Vec<u8>, multi_polygon: &M) {
+    write_header(wkb, WKB_MULTI_POLYGON);
+    write_count(wkb, multi_polygon.polygons().len());
+    for polygon in multi_polygon.polygons() {
+        write_header(wkb, WKB_POLYGON);
+        write_count(wkb, polygon.as_ref().len());
+        for ring in polygon.as_ref() {
+            write_coordinates(wkb, ring.as_ref());
+        }
+    }
+}
+
+/// Writes feature collections into a single GeoParquet file.
+///
+/// The geometries are encoded as WKB in the column `geometry`, the time intervals are stored as
+/// timestamps in `time_start` and `time_end` and the attribute columns keep their Arrow representation.
+/// The schema is determined by the first collection, so all collections must have the same columns.
+pub struct GeoParquetWriter<W: Write> {
+    sink: Option<W>,
+    writer: Option<ArrowWriter<W>>,
+    crs: Option<serde_json::Value>,
+}
+
+impl<W: Write> GeoParquetWriter<W> {
+    /// Creates a writer for collections with the given CRS as PROJJSON.
+    /// GeoParquet readers assume `OGC:CRS84` if there is no CRS.
+    pub fn new(sink: W, crs: Option<serde_json::Value>) -> Self {
+        Self {
+            sink: Some(sink),
+            writer: None,
+            crs,
+        }
+    }
+
+    pub fn write<G>(&mut self, collection: &FeatureCollection<G>) -> Result<()>
+    where
+        FeatureCollection<G>: WkbGeometries + FeatureCollectionInfos,
+    {
+        let mut column_names: Vec<&String> = collection.column_names().collect();
+        column_names.sort();
+
+        let mut fields = vec![
+            Field::new(GEOMETRY_COLUMN, DataType::Binary, false),
+            Field::new(
+                TIME_START_COLUMN,
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                TIME_END_COLUMN,
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ];
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(collection.wkb_geometries()),
+            Arc::new(TimestampMillisecondArray::from(
+                collection
+                    .time_intervals()
+                    .iter()
+                    .map(|time| time.start().inner())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(TimestampMillisecondArray::from(
+                collection
+                    .time_intervals()
+                    .iter()
+                    .map(|time| time.end().inner())
+                    .collect::<Vec<_>>(),
+            )),
+        ];
+
+        for column_name in column_names {
+            let column = collection
+                .table
+                .column_by_name(column_name)
+                .expect("must exist since it's in `types`")
+                .clone();
+
+            fields.push(Field::new(column_name, column.data_type().clone(), true));
+            columns.push(column);
+        }
+
+        let schema: SchemaRef = Arc::new(Schema::new(fields));
+
+        if self.writer.is_none() {
+            let sink = self
+                .sink
+                .take()
+                .expect("sink must exist if there is no writer");
+
+            let properties = WriterProperties::builder()
+                .set_key_value_metadata(Some(vec![KeyValue::new(
+                    "geo".to_string(),
+                    self.geo_metadata(<FeatureCollection<G> as WkbGeometries>::GEOMETRY_TYPE)
+                        .to_string(),
+                )]))
+                .build();
+
+            self.writer = Some(ArrowWriter::try_new(
+                sink,
+                schema.clone(),
+                Some(properties),
+            )?);
+        }
+
+        let batch = RecordBatch::try_new(schema, columns)?;
+
+        self.writer
+            .as_mut()
+            .expect("writer was created before")
+            .write(&batch)?;
+
+        Ok(())
+    }
+
+    /// Writes the file footer. A writer without any written collection produces no output.
+    pub fn finish(self) -> Result<()> {
+        if let Some(writer) = self.writer {
+            writer.close()?;
+        }
+
+        Ok(())
+    }
+
+    fn geo_metadata(&self, geometry_type: &str) -> serde_json::Value {
+        let mut column = serde_json::json!({
+            "encoding": "WKB",
+            "geometry_types": [geometry_type],
+        });
+
+        if let Some(crs) = &self.crs {
+            column["crs"] = crs.clone();
+        }
+
+        serde_json::json!({
+            "version": GEO_PARQUET_VERSION,
+            "primary_column": GEOMETRY_COLUMN,
+            "columns": {
+                GEOMETRY_COLUMN: column,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{FeatureData, MultiPoint, TimeInterval};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    #[test]
+    fn it_encodes_wkb() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(1.0, 2.0)]).unwrap(),
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let wkb = collection.wkb_geometries();
+
+        let mut expected = vec![1, 4, 0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0, 0];
+        expected.extend_from_slice(&1.0_f64.to_le_bytes());
+        expected.extend_from_slice(&2.0_f64.to_le_bytes());
+
+        assert_eq!(wkb.value(0), expected.as_slice());
+    }
+
+    #[test]
+    fn it_writes_geo_parquet() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap(); 2],
+            [("foo".to_string(), FeatureData::Float(vec![1., 2.]))].into(),
+        )
+        .unwrap();
+
+        let mut bytes = Vec::new();
+        let mut writer = GeoParquetWriter::new(&mut bytes, None);
+        writer.write(&collection).unwrap();
+        writer.write(&collection).unwrap();
+        writer.finish().unwrap();
+
+        let reader = SerializedFileReader::new(bytes::Bytes::from(bytes)).unwrap();
+        let metadata = reader.metadata().file_metadata();
+
+        assert_eq!(metadata.num_rows(), 4);
+        assert_eq!(
+            metadata
+                .schema_descr()
+                .columns()
+                .iter()
+                .map(|column| column.name().to_string())
+                .collect::<Vec<_>>(),
+            vec!["geometry", "time_start", "time_end", "foo"]
+        );
+
+        let geo: serde_json::Value = serde_json::from_str(
+            metadata
+                .key_value_metadata()
+                .unwrap()
+                .iter()
+                .find(|kv| kv.key == "geo")
+                .unwrap()
+                .value
+                .as_ref()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            geo["columns"]["geometry"]["geometry_types"][0],
+            "MultiPoint"
+        );
+    }
+}
@@ -6,11 +6,13 @@ mod feature_collection;
 mod feature_collection_builder;
 #[macro_use]
 mod geo_feature_collection;
+mod geo_parquet;
 
 mod data_collection;
 mod multi_line_string_collection;
 mod multi_point_collection;
 mod multi_polygon_collection;
+mod spool;
 
 pub(crate) use error::FeatureCollectionError;
 pub(self) use feature_collection::FilterArray;
@@ -30,9 +32,14 @@ pub use data_collection::DataCollection;
 pub use data_types::{
     TypedFeatureCollection, TypedFeatureCollectionRef, VectorDataType, VectorDataTyped,
 };
+pub use geo_parquet::{GeoParquetWriter, WkbGeometries};
 pub use multi_line_string_collection::MultiLineStringCollection;
 pub use multi_point_collection::MultiPointCollection;
 pub use multi_polygon_collection::MultiPolygonCollection;
+pub use spool::{
+    FeatureCollectionSpool, SpooledCollectionsIntoIter, SpooledCollectionsIter,
+    SpooledFeatureCollections,
+};
 
 pub use batch_builder::RawFeatureCollectionBuilder;
 
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use arrow::array::StructArray;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use snafu::ResultExt;
+
+use crate::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use crate::error;
+use crate::primitives::{FeatureDataType, Geometry};
+use crate::util::arrow::ArrowTyped;
+use crate::util::memory::MEMORY_ACCOUNTANT;
+use crate::util::Result;
+
+/// Collects the feature collections of a query result in memory as long as they are smaller than a threshold
+/// and the process is within its memory budget.
+/// Otherwise, all collections are spilled to an Arrow IPC file in the spool directory, so that large results
+/// do not exhaust the memory of the server.
+#[derive(Debug)]
+pub struct FeatureCollectionSpool<CollectionType> {
+    memory_threshold_bytes: usize,
+    directory: PathBuf,
+    collections: Vec<FeatureCollection<CollectionType>>,
+    bytes_in_memory: usize,
+    spill_file: Option<SpillFileWriter>,
+    types: HashMap<String, FeatureDataType>,
+    len: usize,
+}
+
+struct SpillFileWriter {
+    // the writer must be dropped before the file is removed
+    writer: FileWriter<BufWriter<File>>,
+    file: SpillFile,
+}
+
+impl std::fmt::Debug for SpillFileWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpillFileWriter")
+            .field("file", &self.file)
+            .finish()
+    }
+}
+
+/// A temporary file that is removed when it is dropped
+#[derive(Debug)]
+pub struct SpillFile {
+    path: PathBuf,
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // there is nothing left to do if the file cannot be removed, e.g., because the directory was cleared
+        let _result = std::fs::remove_file(&self.path);
+    }
+}
+
+impl<CollectionType> FeatureCollectionSpool<CollectionType>
+where
+    CollectionType: Geometry + ArrowTyped,
+{
+    pub fn new(memory_threshold_bytes: usize, directory: PathBuf) -> Self {
+        Self {
+            memory_threshold_bytes,
+            directory,
+            collections: Vec::new(),
+            bytes_in_memory: 0,
+            spill_file: None,
+            types: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Appends a collection to the spool.
+    /// All collections must have the same columns.
+    pub fn push(&mut self, collection: FeatureCollection<CollectionType>) -> Result<()> {
+        if collection.is_empty() {
+            return Ok(());
+        }
+
+        if self.len == 0 {
+            self.types = collection.types.clone();
+        }
+        self.len += collection.len();
+
+        if let Some(spill_file) = &mut self.spill_file {
+            return spill_file
+                .writer
+                .write(&RecordBatch::from(&collection.table))
+                .context(error::ArrowInternal);
+        }
+
+        self.bytes_in_memory += collection.byte_size();
+        self.collections.push(collection);
+
+        if self.bytes_in_memory > self.memory_threshold_bytes || MEMORY_ACCOUNTANT.is_over_budget()
+        {
+            self.spill()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes all collections in memory to a new spill file, which receives all further collections
+    fn spill(&mut self) -> Result<()> {
+        let file = SpillFile {
+            path: self
+                .directory
+                .join(format!("geoengine-spool-{}.arrow", uuid::Uuid::new_v4())),
+        };
+
+        std::fs::create_dir_all(&self.directory).context(error::SpoolFile)?;
+        let writer = BufWriter::new(File::create(&file.path).context(error::SpoolFile)?);
+
+        let schema = RecordBatch::from(&self.collections[0].table).schema();
+        let mut writer = FileWriter::try_new(writer, &schema).context(error::ArrowInternal)?;
+
+        for collection in self.collections.drain(..) {
+            writer
+                .write(&RecordBatch::from(&collection.table))
+                .context(error::ArrowInternal)?;
+        }
+
+        self.bytes_in_memory = 0;
+        self.spill_file = Some(SpillFileWriter { writer, file });
+
+        Ok(())
+    }
+
+    /// Completes the spool, so that its collections can be read
+    pub fn finish(self) -> Result<SpooledFeatureCollections<CollectionType>> {
+        let storage = if let Some(mut spill_file) = self.spill_file {
+            spill_file.writer.finish().context(error::ArrowInternal)?;
+
+            SpooledStorage::File {
+                file: spill_file.file,
+                types: self.types,
+            }
+        } else {
+            SpooledStorage::Memory(self.collections)
+        };
+
+        Ok(SpooledFeatureCollections {
+            len: self.len,
+            storage,
+        })
+    }
+}
+
+/// The collections of a finished spool, which can be read repeatedly in the order they were pushed
+#[derive(Debug)]
+pub struct SpooledFeatureCollections<CollectionType> {
+    len: usize,
+    storage: SpooledStorage<CollectionType>,
+}
+
+#[derive(Debug)]
+enum SpooledStorage<CollectionType> {
+    Memory(Vec<FeatureCollection<CollectionType>>),
+    File {
+        file: SpillFile,
+        types: HashMap<String, FeatureDataType>,
+    },
+}
+
+impl<CollectionType> SpooledFeatureCollections<CollectionType>
+where
+    CollectionType: Geometry + ArrowTyped,
+{
+    /// The number of features of all collections
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.storage, SpooledStorage::File { .. })
+    }
+
+    pub fn collections(&self) -> Result<SpooledCollectionsIter<CollectionType>> {
+        Ok(match &self.storage {
+            SpooledStorage::Memory(collections) => {
+                SpooledCollectionsIter::Memory(collections.iter())
+            }
+            SpooledStorage::File { file, types } => {
+                let reader = BufReader::new(File::open(&file.path).context(error::SpoolFile)?);
+
+                SpooledCollectionsIter::File {
+                    reader: FileReader::try_new(reader, None).context(error::ArrowInternal)?,
+                    types,
+                }
+            }
+        })
+    }
+
+    /// Reads the collections without keeping the spool, e.g., for consuming it in a stream
+    pub fn into_collections(self) -> Result<SpooledCollectionsIntoIter<CollectionType>> {
+        Ok(match self.storage {
+            SpooledStorage::Memory(collections) => {
+                SpooledCollectionsIntoIter::Memory(collections.into_iter())
+            }
+            SpooledStorage::File { file, types } => {
+                let reader = BufReader::new(File::open(&file.path).context(error::SpoolFile)?);
+
+                SpooledCollectionsIntoIter::File {
+                    reader: FileReader::try_new(reader, None).context(error::ArrowInternal)?,
+                    types,
+                    _file: file,
+                }
+            }
+        })
+    }
+
+    /// Reads the `count` features beginning at `start_index`.
+    /// Reading stops after the last collection of the page.
+    pub fn page(
+        &self,
+        start_index: usize,
+        count: usize,
+    ) -> Result<Vec<FeatureCollection<CollectionType>>> {
+        let end_index = start_index.saturating_add(count);
+
+        let mut page = Vec::new();
+        let mut offset = 0;
+
+        for collection in self.collections()? {
+            if offset >= end_index {
+                break;
+            }
+
+            let collection = collection?;
+            let len = collection.len();
+
+            if offset >= start_index && offset + len <= end_index {
+                page.push(collection);
+            } else if offset + len > start_index {
+                let mask: Vec<bool> = (offset..offset + len)
+                    .map(|index| (start_index..end_index).contains(&index))
+                    .collect();
+                page.push(collection.filter(mask)?);
+            }
+
+            offset += len;
+        }
+
+        Ok(page)
+    }
+}
+
+/// An iterator over spooled collections that reads spilled collections one by one
+pub enum SpooledCollectionsIter<'s, CollectionType> {
+    Memory(std::slice::Iter<'s, FeatureCollection<CollectionType>>),
+    File {
+        reader: FileReader<BufReader<File>>,
+        types: &'s HashMap<String, FeatureDataType>,
+    },
+}
+
+impl<'s, CollectionType> Iterator for SpooledCollectionsIter<'s, CollectionType> {
+    type Item = Result<FeatureCollection<CollectionType>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SpooledCollectionsIter::Memory(collections) => collections.next().cloned().map(Ok),
+            SpooledCollectionsIter::File { reader, types } => reader.next().map(|batch| {
+                let batch = batch.context(error::ArrowInternal)?;
+
+                Ok(FeatureCollection::new_from_internals(
+                    StructArray::from(batch),
+                    (*types).clone(),
+                ))
+            }),
+        }
+    }
+}
+
+/// An iterator that owns the spooled collections and reads spilled collections one by one
+pub enum SpooledCollectionsIntoIter<CollectionType> {
+    Memory(std::vec::IntoIter<FeatureCollection<CollectionType>>),
+    File {
+        reader: FileReader<BufReader<File>>,
+        types: HashMap<String, FeatureDataType>,
+        /// removes the file when the iterator is dropped
+        _file: SpillFile,
+    },
+}
+
+impl<CollectionType> Iterator for SpooledCollectionsIntoIter<CollectionType> {
+    type Item = Result<FeatureCollection<CollectionType>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SpooledCollectionsIntoIter::Memory(collections) => collections.next().map(Ok),
+            SpooledCollectionsIntoIter::File { reader, types, .. } => reader.next().map(|batch| {
+                let batch = batch.context(error::ArrowInternal)?;
+
+                Ok(FeatureCollection::new_from_internals(
+                    StructArray::from(batch),
+                    types.clone(),
+                ))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::MultiPointCollection;
+    use crate::primitives::{FeatureData, FeatureDataRef, MultiPoint, TimeInterval};
+
+    fn collection(offset: i32) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(
+                (offset..offset + 3)
+                    .map(|i| (f64::from(i), f64::from(i)))
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap(),
+            vec![TimeInterval::default(); 3],
+            [(
+                "index".to_string(),
+                FeatureData::Int((offset..offset + 3).map(i64::from).collect()),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    fn indices(collections: &[MultiPointCollection]) -> Vec<i64> {
+        collections
+            .iter()
+            .flat_map(|collection| match collection.data("index").unwrap() {
+                FeatureDataRef::Int(ints) => ints.as_ref().to_vec(),
+                _ => panic!("the index column must contain ints"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_keeps_small_results_in_memory() {
+        let dir = std::env::temp_dir();
+        let mut spool = FeatureCollectionSpool::new(usize::MAX, dir);
+
+        spool.push(collection(0)).unwrap();
+        spool.push(collection(3)).unwrap();
+
+        let spooled = spool.finish().unwrap();
+
+        assert!(!spooled.is_spilled());
+        assert_eq!(spooled.len(), 6);
+        assert_eq!(indices(&spooled.page(2, 3).unwrap()), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn it_spills_large_results() {
+        let dir = std::env::temp_dir().join(format!("spool-test-{}", uuid::Uuid::new_v4()));
+        let mut spool = FeatureCollectionSpool::new(0, dir.clone());
+
+        spool.push(collection(0)).unwrap();
+        spool.push(collection(3)).unwrap();
+        spool.push(collection(6)).unwrap();
+
+        let spooled = spool.finish().unwrap();
+
+        assert!(spooled.is_spilled());
+        assert_eq!(spooled.len(), 9);
+
+        let collections = spooled
+            .collections()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            collections,
+            vec![collection(0), collection(3), collection(6)]
+        );
+
+        assert_eq!(indices(&spooled.page(2, 5).unwrap()), vec![2, 3, 4, 5, 6]);
+        assert_eq!(indices(&spooled.page(7, 5).unwrap()), vec![7, 8]);
+        assert!(spooled.page(9, 5).unwrap().is_empty());
+
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let collections = spooled
+            .into_collections()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(collections.len(), 3);
+
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        std::fs::remove_dir(dir).unwrap();
+    }
+}
@@ -63,6 +63,16 @@ pub enum Error {
         source: arrow::error::ArrowError,
     },
 
+    #[snafu(display("Parquet internal error: {:?}", source))]
+    ParquetInternal {
+        source: parquet::errors::ParquetError,
+    },
+
+    #[snafu(display("Could not access the spool file: {}", source))]
+    SpoolFile {
+        source: std::io::Error,
+    },
+
     #[snafu(display("ProjInternal error: {:?}", source))]
     ProjInternal {
         source: proj::ProjError,
@@ -321,6 +331,12 @@ impl From<arrow::error::ArrowError> for Error {
     }
 }
 
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(source: parquet::errors::ParquetError) -> Self {
+        Error::ParquetInternal { source }
+    }
+}
+
 impl From<proj::ProjError> for Error {
     fn from(source: proj::ProjError) -> Self {
         Error::ProjInternal { source }
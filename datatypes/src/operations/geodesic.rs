@@ -0,0 +1,258 @@
+//! Distances, lengths and areas on the WGS 84 ellipsoid for coordinates in degrees of longitude and latitude.
+
+use crate::primitives::Coordinate2D;
+
+/// semi-major axis of the WGS 84 ellipsoid in meters
+const SEMI_MAJOR_AXIS: f64 = 6_378_137.0;
+/// flattening of the WGS 84 ellipsoid
+const FLATTENING: f64 = 1. / 298.257_223_563;
+
+const MAX_ITERATIONS: usize = 200;
+
+/// Computes the length of the geodesic between two coordinates in meters.
+///
+/// Uses Vincenty's inverse formula, which is accurate to fractions of a millimeter.
+/// For nearly antipodal points, where the formula does not converge,
+/// the distance on the sphere with the authalic radius is returned instead.
+///
+/// # Examples
+///
+/// ```
+/// use geoengine_datatypes::operations::geodesic::geodesic_distance;
+///
+/// let distance = geodesic_distance((0., 0.).into(), (1., 0.).into());
+///
+/// assert!((distance - 111_319.491).abs() < 1e-3);
+/// ```
+pub fn geodesic_distance(from: Coordinate2D, to: Coordinate2D) -> f64 {
+    let semi_minor_axis = SEMI_MAJOR_AXIS * (1. - FLATTENING);
+
+    let longitude_difference = (to.x - from.x).to_radians();
+    let reduced_latitude_from = ((1. - FLATTENING) * from.y.to_radians().tan()).atan();
+    let reduced_latitude_to = ((1. - FLATTENING) * to.y.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = reduced_latitude_from.sin_cos();
+    let (sin_u2, cos_u2) = reduced_latitude_to.sin_cos();
+
+    let mut lambda = longitude_difference;
+
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+        let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        if sin_sigma == 0. {
+            // coincident points
+            return 0.;
+        }
+
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1. - sin_alpha * sin_alpha;
+        let cos_2_sigma_m = if cos_sq_alpha == 0. {
+            // both points on the equator
+            0.
+        } else {
+            cos_sigma - 2. * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+
+        let c = FLATTENING / 16. * cos_sq_alpha * (4. + FLATTENING * (4. - 3. * cos_sq_alpha));
+        let previous_lambda = lambda;
+        lambda = longitude_difference
+            + (1. - c)
+                * FLATTENING
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2_sigma_m + c * cos_sigma * (-1. + 2. * cos_2_sigma_m.powi(2))));
+
+        if (lambda - previous_lambda).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (SEMI_MAJOR_AXIS.powi(2) - semi_minor_axis.powi(2))
+                / semi_minor_axis.powi(2);
+            let a = 1. + u_sq / 16384. * (4096. + u_sq * (-768. + u_sq * (320. - 175. * u_sq)));
+            let b = u_sq / 1024. * (256. + u_sq * (-128. + u_sq * (74. - 47. * u_sq)));
+            let delta_sigma = b
+                * sin_sigma
+                * (cos_2_sigma_m
+                    + b / 4.
+                        * (cos_sigma * (-1. + 2. * cos_2_sigma_m.powi(2))
+                            - b / 6.
+                                * cos_2_sigma_m
+                                * (-3. + 4. * sin_sigma.powi(2))
+                                * (-3. + 4. * cos_2_sigma_m.powi(2))));
+
+            return semi_minor_axis * a * (sigma - delta_sigma);
+        }
+    }
+
+    spherical_distance(from, to)
+}
+
+/// The great circle distance on the sphere with the authalic radius (haversine formula)
+fn spherical_distance(from: Coordinate2D, to: Coordinate2D) -> f64 {
+    let (latitude_from, latitude_to) = (from.y.to_radians(), to.y.to_radians());
+    let haversine = ((latitude_to - latitude_from) / 2.).sin().powi(2)
+        + latitude_from.cos()
+            * latitude_to.cos()
+            * ((to.x - from.x).to_radians() / 2.).sin().powi(2);
+
+    2. * authalic_radius() * haversine.sqrt().min(1.).asin()
+}
+
+/// Computes the length of a line along geodesics in meters
+pub fn geodesic_length(line: &[Coordinate2D]) -> f64 {
+    line.windows(2)
+        .map(|segment| geodesic_distance(segment[0], segment[1]))
+        .sum()
+}
+
+/// Computes the area enclosed by a closed ring in square meters, regardless of its orientation.
+///
+/// The area is computed as the spherical excess on the authalic sphere,
+/// i.e., the sphere with the same surface area as the ellipsoid, after mapping the latitudes to authalic latitudes.
+/// The edges of the ring may cross the antimeridian.
+///
+/// # Examples
+///
+/// ```
+/// use geoengine_datatypes::operations::geodesic::geodesic_ring_area;
+///
+/// let area = geodesic_ring_area(&[
+///     (0., 0.).into(),
+///     (1., 0.).into(),
+///     (1., 1.).into(),
+///     (0., 1.).into(),
+///     (0., 0.).into(),
+/// ]);
+///
+/// // about 12 309 km²
+/// assert!((area / 1e6 - 12_308.8).abs() < 0.1);
+/// ```
+pub fn geodesic_ring_area(ring: &[Coordinate2D]) -> f64 {
+    let spherical_excess: f64 = ring
+        .windows(2)
+        .map(|segment| {
+            let longitude_difference =
+                normalize_radians((segment[1].x - segment[0].x).to_radians());
+            let tan_from = (authalic_latitude(segment[0].y.to_radians()) / 2.).tan();
+            let tan_to = (authalic_latitude(segment[1].y.to_radians()) / 2.).tan();
+
+            2. * ((longitude_difference / 2.).tan() * (tan_from + tan_to))
+                .atan2(1. + tan_from * tan_to)
+        })
+        .sum();
+
+    spherical_excess.abs() * authalic_radius().powi(2)
+}
+
+/// Computes the area of a polygon in square meters, i.e., the area of the exterior ring minus the areas of the holes
+pub fn geodesic_polygon_area<R: AsRef<[Coordinate2D]>>(rings: &[R]) -> f64 {
+    rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let area = geodesic_ring_area(ring.as_ref());
+            if i == 0 {
+                area
+            } else {
+                -area
+            }
+        })
+        .sum()
+}
+
+/// Maps an angle in radians to the interval `[-π, π)`
+fn normalize_radians(angle: f64) -> f64 {
+    (angle + std::f64::consts::PI).rem_euclid(2. * std::f64::consts::PI) - std::f64::consts::PI
+}
+
+fn eccentricity() -> f64 {
+    (FLATTENING * (2. - FLATTENING)).sqrt()
+}
+
+/// The function `q` of the authalic latitude, cf. Snyder (1987), eq. 3-12
+fn authalic_q(latitude: f64) -> f64 {
+    let e = eccentricity();
+    let sin_latitude = latitude.sin();
+
+    (1. - e * e)
+        * (sin_latitude / (1. - (e * sin_latitude).powi(2))
+            - 1. / (2. * e) * ((1. - e * sin_latitude) / (1. + e * sin_latitude)).ln())
+}
+
+fn authalic_latitude(latitude: f64) -> f64 {
+    (authalic_q(latitude) / authalic_q(std::f64::consts::FRAC_PI_2))
+        .clamp(-1., 1.)
+        .asin()
+}
+
+/// The radius of the sphere with the same surface area as the ellipsoid
+fn authalic_radius() -> f64 {
+    SEMI_MAJOR_AXIS * (authalic_q(std::f64::consts::FRAC_PI_2) / 2.).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn degrees(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees + minutes / 60. + seconds / 3600.
+    }
+
+    #[test]
+    fn it_computes_distances() {
+        // Flinders Peak to Buninyong, cf. Vincenty (1975)
+        let distance = geodesic_distance(
+            (degrees(144., 25., 29.5244), -degrees(37., 57., 3.7203)).into(),
+            (degrees(143., 55., 35.3839), -degrees(37., 39., 10.1561)).into(),
+        );
+        assert!((distance - 54_972.271).abs() < 1e-3);
+
+        assert_eq!(geodesic_distance((7., 50.).into(), (7., 50.).into()), 0.);
+
+        // nearly antipodal points fall back to the sphere
+        let distance = geodesic_distance((0., 0.).into(), (179.7, 0.5).into());
+        assert!((distance - 19_950_000.).abs() < 50_000.);
+    }
+
+    #[test]
+    fn it_computes_lengths() {
+        let length = geodesic_length(&[(0., 0.).into(), (1., 0.).into(), (2., 0.).into()]);
+
+        assert!((length - 2. * 111_319.491).abs() < 1e-2);
+    }
+
+    #[test]
+    fn it_computes_areas_across_the_antimeridian() {
+        let area = geodesic_ring_area(&[
+            (179.5, 0.).into(),
+            (-179.5, 0.).into(),
+            (-179.5, 1.).into(),
+            (179.5, 1.).into(),
+            (179.5, 0.).into(),
+        ]);
+
+        assert!((area / 1e6 - 12_308.8).abs() < 0.1);
+    }
+
+    #[test]
+    fn it_subtracts_holes() {
+        let square = |min: f64, max: f64| -> Vec<Coordinate2D> {
+            vec![
+                (min, min).into(),
+                (max, min).into(),
+                (max, max).into(),
+                (min, max).into(),
+                (min, min).into(),
+            ]
+        };
+
+        let area = geodesic_polygon_area(&[square(0., 2.), square(0.5, 1.5)]);
+
+        assert!(
+            (area - (geodesic_ring_area(&square(0., 2.)) - geodesic_ring_area(&square(0.5, 1.5))))
+                .abs()
+                < 1e-6
+        );
+        assert!(area > 0.);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error;
+use crate::operations::image::{Breakpoint, Breakpoints, RgbaColor};
+use crate::util::Result;
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::str::FromStr;
+
+/// Named scientific color ramps for gradient colorizers.
+///
+/// The perceptually uniform ramps are from matplotlib, the others from ColorBrewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorRamp {
+    #[serde(rename = "viridis")]
+    Viridis,
+    #[serde(rename = "magma")]
+    Magma,
+    #[serde(rename = "inferno")]
+    Inferno,
+    #[serde(rename = "plasma")]
+    Plasma,
+    #[serde(rename = "greys")]
+    Greys,
+    #[serde(rename = "blues")]
+    Blues,
+    #[serde(rename = "YlGnBu")]
+    YlGnBu,
+    #[serde(rename = "RdYlGn")]
+    RdYlGn,
+    #[serde(rename = "RdBu")]
+    RdBu,
+    #[serde(rename = "Spectral")]
+    Spectral,
+}
+
+impl ColorRamp {
+    pub const ALL: [ColorRamp; 10] = [
+        ColorRamp::Viridis,
+        ColorRamp::Magma,
+        ColorRamp::Inferno,
+        ColorRamp::Plasma,
+        ColorRamp::Greys,
+        ColorRamp::Blues,
+        ColorRamp::YlGnBu,
+        ColorRamp::RdYlGn,
+        ColorRamp::RdBu,
+        ColorRamp::Spectral,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorRamp::Viridis => "viridis",
+            ColorRamp::Magma => "magma",
+            ColorRamp::Inferno => "inferno",
+            ColorRamp::Plasma => "plasma",
+            ColorRamp::Greys => "greys",
+            ColorRamp::Blues => "blues",
+            ColorRamp::YlGnBu => "YlGnBu",
+            ColorRamp::RdYlGn => "RdYlGn",
+            ColorRamp::RdBu => "RdBu",
+            ColorRamp::Spectral => "Spectral",
+        }
+    }
+
+    /// The colors of the ramp as `0xRRGGBB`, evenly spaced from low to high values
+    fn hex_colors(self) -> &'static [u32] {
+        match self {
+            ColorRamp::Viridis => &[
+                0x44_01_54, 0x47_2d_7b, 0x3b_52_8b, 0x2c_72_8e, 0x21_91_8c, 0x28_ae_80, 0x5e_c9_62,
+                0xad_dc_30, 0xfd_e7_25,
+            ],
+            ColorRamp::Magma => &[
+                0x00_00_04, 0x1c_10_44, 0x4f_12_7b, 0x81_25_81, 0xb5_36_7a, 0xe5_50_64, 0xfb_87_61,
+                0xfe_c2_87, 0xfc_fd_bf,
+            ],
+            ColorRamp::Inferno => &[
+                0x00_00_04, 0x1f_0c_48, 0x55_0f_6d, 0x88_22_6a, 0xba_36_55, 0xe3_59_33, 0xf9_8e_09,
+                0xf9_cb_35, 0xfc_ff_a4,
+            ],
+            ColorRamp::Plasma => &[
+                0x0d_08_87, 0x47_03_9f, 0x73_01_a8, 0x9c_17_9e, 0xbd_37_86, 0xd8_57_6b, 0xed_79_53,
+                0xfb_9f_3a, 0xfd_ca_26, 0xf0_f9_21,
+            ],
+            ColorRamp::Greys => &[
+                0xff_ff_ff, 0xf0_f0_f0, 0xd9_d9_d9, 0xbd_bd_bd, 0x96_96_96, 0x73_73_73, 0x52_52_52,
+                0x25_25_25, 0x00_00_00,
+            ],
+            ColorRamp::Blues => &[
+                0xf7_fb_ff, 0xde_eb_f7, 0xc6_db_ef, 0x9e_ca_e1, 0x6b_ae_d6, 0x42_92_c6, 0x21_71_b5,
+                0x08_51_9c, 0x08_30_6b,
+            ],
+            ColorRamp::YlGnBu => &[
+                0xff_ff_d9, 0xed_f8_b1, 0xc7_e9_b4, 0x7f_cd_bb, 0x41_b6_c4, 0x1d_91_c0, 0x22_5e_a8,
+                0x25_34_94, 0x08_1d_58,
+            ],
+            ColorRamp::RdYlGn => &[
+                0xa5_00_26, 0xd7_30_27, 0xf4_6d_43, 0xfd_ae_61, 0xfe_e0_8b, 0xff_ff_bf, 0xd9_ef_8b,
+                0xa6_d9_6a, 0x66_bd_63, 0x1a_98_50, 0x00_68_37,
+            ],
+            ColorRamp::RdBu => &[
+                0x67_00_1f, 0xb2_18_2b, 0xd6_60_4d, 0xf4_a5_82, 0xfd_db_c7, 0xf7_f7_f7, 0xd1_e5_f0,
+                0x92_c5_de, 0x43_93_c3, 0x21_66_ac, 0x05_30_61,
+            ],
+            ColorRamp::Spectral => &[
+                0x9e_01_42, 0xd5_3e_4f, 0xf4_6d_43, 0xfd_ae_61, 0xfe_e0_8b, 0xff_ff_bf, 0xe6_f5_98,
+                0xab_dd_a4, 0x66_c2_a5, 0x32_88_bd, 0x5e_4f_a2,
+            ],
+        }
+    }
+
+    /// The opaque colors of the ramp from low to high values
+    pub fn colors(self) -> Vec<RgbaColor> {
+        self.hex_colors()
+            .iter()
+            .map(|hex| {
+                let [_, red, green, blue] = hex.to_be_bytes();
+                RgbaColor::new(red, green, blue, 255)
+            })
+            .collect()
+    }
+
+    /// Creates breakpoints that spread the ramp linearly between `min` and `max`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geoengine_datatypes::operations::image::{ColorRamp, RgbaColor};
+    ///
+    /// let breakpoints = ColorRamp::Greys.linear_breakpoints(0., 80.).unwrap();
+    ///
+    /// assert_eq!(breakpoints.len(), 9);
+    /// assert_eq!(*breakpoints[1].value, 10.);
+    /// assert_eq!(breakpoints[8].color, RgbaColor::black());
+    /// ```
+    pub fn linear_breakpoints(self, min: f64, max: f64) -> Result<Breakpoints> {
+        ensure!(
+            min < max,
+            error::Colorizer {
+                details: "A color ramp's min value must be smaller than its max value"
+            }
+        );
+
+        self.breakpoints(|fraction| min + fraction * (max - min))
+    }
+
+    /// Creates breakpoints that spread the ramp logarithmically between `min` and `max`,
+    /// s.t. a logarithmic gradient shows the colors in equal distances
+    pub fn logarithmic_breakpoints(self, min: f64, max: f64) -> Result<Breakpoints> {
+        ensure!(
+            min > 0. && min < max,
+            error::Colorizer {
+                details: "A logarithmic color ramp's min value must be positive and smaller than its max value"
+            }
+        );
+
+        let (log_min, log_max) = (min.ln(), max.ln());
+        self.breakpoints(|fraction| (log_min + fraction * (log_max - log_min)).exp())
+    }
+
+    fn breakpoints(self, value_at: impl Fn(f64) -> f64) -> Result<Breakpoints> {
+        let colors = self.colors();
+        let last = (colors.len() - 1) as f64;
+
+        colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| {
+                let value = NotNan::new(value_at(i as f64 / last)).map_err(|_| {
+                    error::Error::Colorizer {
+                        details: "A color ramp's values must not be NaN".to_string(),
+                    }
+                })?;
+
+                Ok(Breakpoint { value, color })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ColorRamp {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ColorRamp::ALL
+            .into_iter()
+            .find(|ramp| ramp.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| error::Error::Colorizer {
+                details: format!("Unknown color ramp `{}`", s),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_names() {
+        for ramp in ColorRamp::ALL {
+            assert_eq!(ramp.name().parse::<ColorRamp>().unwrap(), ramp);
+            assert_eq!(
+                serde_json::to_value(ramp).unwrap(),
+                serde_json::json!(ramp.name())
+            );
+        }
+
+        assert_eq!("rdylgn".parse::<ColorRamp>().unwrap(), ColorRamp::RdYlGn);
+        assert!("foo".parse::<ColorRamp>().is_err());
+    }
+
+    #[test]
+    fn it_converts_hex_colors() {
+        let colors = ColorRamp::Viridis.colors();
+
+        assert_eq!(colors[0], RgbaColor::new(0x44, 0x01, 0x54, 255));
+        assert_eq!(colors[8], RgbaColor::new(0xfd, 0xe7, 0x25, 255));
+    }
+
+    #[test]
+    fn it_creates_logarithmic_breakpoints() {
+        let breakpoints = ColorRamp::Greys.logarithmic_breakpoints(1., 1e8).unwrap();
+
+        assert_eq!(*breakpoints[0].value, 1.);
+        assert!((*breakpoints[4].value - 1e4).abs() < 1e-6);
+        assert!((*breakpoints[8].value - 1e8).abs() < 1e-2);
+
+        assert!(ColorRamp::Greys.logarithmic_breakpoints(0., 1.).is_err());
+        assert!(ColorRamp::Greys.linear_breakpoints(1., 1.).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{self, Error};
+use crate::operations::image::ColorRamp;
 use crate::operations::image::RgbaTransmutable;
 use crate::raster::Pixel;
 use crate::util::Result;
@@ -11,8 +12,15 @@ use std::str::FromStr;
 
 /// A colorizer specifies a mapping between raster values and an output image
 /// There are different variants that perform different kinds of mapping.
+///
+/// Gradients can also be deserialized from a named `ColorRamp` with `min` and `max` values
+/// instead of `breakpoints`, e.g., `{"type": "linearGradient", "ramp": "viridis", "min": 0, "max": 1, ...}`.
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
-#[serde(rename_all = "camelCase", tag = "type")]
+#[serde(
+    rename_all = "camelCase",
+    tag = "type",
+    try_from = "ColorizerDefinition"
+)]
 pub enum Colorizer {
     #[serde(rename_all = "camelCase")]
     LinearGradient {
@@ -516,6 +524,95 @@ impl TryFrom<(f64, RgbaColor)> for Breakpoint {
     }
 }
 
+/// The deserialization format of a `Colorizer` that allows specifying gradients by a `ColorRamp`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+enum ColorizerDefinition {
+    #[serde(rename_all = "camelCase")]
+    LinearGradient {
+        #[serde(flatten)]
+        gradient: GradientDefinition,
+        no_data_color: RgbaColor,
+        default_color: RgbaColor,
+    },
+    #[serde(rename_all = "camelCase")]
+    LogarithmicGradient {
+        #[serde(flatten)]
+        gradient: GradientDefinition,
+        no_data_color: RgbaColor,
+        default_color: RgbaColor,
+    },
+    #[serde(rename_all = "camelCase")]
+    Palette {
+        colors: Palette,
+        no_data_color: RgbaColor,
+        default_color: RgbaColor,
+    },
+    Rgba,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum GradientDefinition {
+    Breakpoints { breakpoints: Breakpoints },
+    Ramp { ramp: ColorRamp, min: f64, max: f64 },
+}
+
+impl TryFrom<ColorizerDefinition> for Colorizer {
+    type Error = Error;
+
+    fn try_from(definition: ColorizerDefinition) -> Result<Self, Self::Error> {
+        Ok(match definition {
+            ColorizerDefinition::LinearGradient {
+                gradient: GradientDefinition::Breakpoints { breakpoints },
+                no_data_color,
+                default_color,
+            } => Self::LinearGradient {
+                breakpoints,
+                no_data_color,
+                default_color,
+            },
+            ColorizerDefinition::LinearGradient {
+                gradient: GradientDefinition::Ramp { ramp, min, max },
+                no_data_color,
+                default_color,
+            } => Self::linear_gradient(
+                ramp.linear_breakpoints(min, max)?,
+                no_data_color,
+                default_color,
+            )?,
+            ColorizerDefinition::LogarithmicGradient {
+                gradient: GradientDefinition::Breakpoints { breakpoints },
+                no_data_color,
+                default_color,
+            } => Self::LogarithmicGradient {
+                breakpoints,
+                no_data_color,
+                default_color,
+            },
+            ColorizerDefinition::LogarithmicGradient {
+                gradient: GradientDefinition::Ramp { ramp, min, max },
+                no_data_color,
+                default_color,
+            } => Self::logarithmic_gradient(
+                ramp.logarithmic_breakpoints(min, max)?,
+                no_data_color,
+                default_color,
+            )?,
+            ColorizerDefinition::Palette {
+                colors,
+                no_data_color,
+                default_color,
+            } => Self::Palette {
+                colors,
+                no_data_color,
+                default_color,
+            },
+            ColorizerDefinition::Rgba => Self::Rgba,
+        })
+    }
+}
+
 /// A breakpoint is a list of (value, color) tuples.
 ///
 /// It is assumed to be ordered ascending and has at least two entries,
@@ -773,6 +870,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialized_color_ramp() {
+        let colorizer: Colorizer = serde_json::from_value(serde_json::json!({
+            "type": "linearGradient",
+            "ramp": "viridis",
+            "min": 0,
+            "max": 8,
+            "noDataColor": [0, 0, 0, 0],
+            "defaultColor": [0, 0, 0, 0]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            colorizer,
+            Colorizer::linear_gradient(
+                ColorRamp::Viridis.linear_breakpoints(0., 8.).unwrap(),
+                RgbaColor::transparent(),
+                RgbaColor::transparent(),
+            )
+            .unwrap()
+        );
+
+        assert!(serde_json::from_value::<Colorizer>(serde_json::json!({
+            "type": "logarithmicGradient",
+            "ramp": "magma",
+            "min": 0,
+            "max": 8,
+            "noDataColor": [0, 0, 0, 0],
+            "defaultColor": [0, 0, 0, 0]
+        }))
+        .is_err());
+    }
+
     #[test]
     fn it_rescales() {
         let colorizer = Colorizer::linear_gradient(
@@ -1,9 +1,13 @@
+mod color_ramp;
 mod colorizer;
 mod into_lossy;
 mod rgba_transmutable;
 mod to_png;
+mod vector_canvas;
 
+pub use color_ramp::ColorRamp;
 pub use colorizer::{Breakpoint, Breakpoints, Colorizer, Palette, RgbaColor};
 pub use into_lossy::LossyInto;
 pub use rgba_transmutable::RgbaTransmutable;
 pub use to_png::ToPng;
+pub use vector_canvas::VectorCanvas;
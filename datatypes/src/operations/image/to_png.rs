@@ -16,7 +16,7 @@ pub trait ToPng {
     fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>>;
 }
 
-fn image_buffer_to_png_bytes(
+pub(super) fn image_buffer_to_png_bytes(
     image_buffer: ImageBuffer<image::Rgba<u8>, Vec<u8>>,
 ) -> Result<Vec<u8>> {
     let mut buffer = Cursor::new(Vec::new());
@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+
+use image::{Rgba, RgbaImage};
+
+use crate::operations::image::to_png::image_buffer_to_png_bytes;
+use crate::operations::image::RgbaColor;
+use crate::primitives::{AxisAlignedRectangle, BoundingBox2D, Coordinate2D};
+use crate::util::Result;
+
+/// A canvas for rendering vector geometries into an RGBA image.
+///
+/// Coordinates are given in the spatial reference of `bounds`, which is mapped onto the whole image.
+/// Colors are blended over the content that was drawn before.
+pub struct VectorCanvas {
+    image: RgbaImage,
+    bounds: BoundingBox2D,
+    x_scale: f64,
+    y_scale: f64,
+}
+
+impl VectorCanvas {
+    pub fn new(width: u32, height: u32, bounds: BoundingBox2D) -> Self {
+        Self {
+            image: RgbaImage::new(width, height),
+            bounds,
+            x_scale: f64::from(width) / bounds.size_x(),
+            y_scale: f64::from(height) / bounds.size_y(),
+        }
+    }
+
+    /// Draws a circle around `center` with a `radius` and a stroke, both in pixels
+    pub fn draw_point(
+        &mut self,
+        center: Coordinate2D,
+        radius: f64,
+        fill_color: RgbaColor,
+        stroke_width: f64,
+        stroke_color: RgbaColor,
+    ) {
+        let (x, y) = self.pixel_coordinate(center);
+        let half_stroke_width = stroke_width / 2.;
+        let outer_radius = radius + half_stroke_width;
+
+        for (column, row) in self.pixels_within(
+            x - outer_radius,
+            y - outer_radius,
+            x + outer_radius,
+            y + outer_radius,
+        ) {
+            let distance = f64::hypot(f64::from(column) + 0.5 - x, f64::from(row) + 0.5 - y);
+
+            if stroke_width > 0. && (distance - radius).abs() <= half_stroke_width {
+                self.blend(column, row, stroke_color);
+            } else if distance <= radius {
+                self.blend(column, row, fill_color);
+            }
+        }
+    }
+
+    /// Draws a line with a `width` in pixels.
+    /// Lines are at least one pixel wide.
+    pub fn draw_line(&mut self, line: &[Coordinate2D], width: f64, color: RgbaColor) {
+        let line: Vec<(f64, f64)> = line
+            .iter()
+            .map(|&coordinate| self.pixel_coordinate(coordinate))
+            .collect();
+        self.draw_pixel_line(&line, width, color);
+    }
+
+    /// Fills a polygon using the even-odd rule and draws its rings as stroke.
+    /// Rings must be closed.
+    pub fn draw_polygon<R: AsRef<[Coordinate2D]>>(
+        &mut self,
+        rings: &[R],
+        fill_color: RgbaColor,
+        stroke_width: f64,
+        stroke_color: RgbaColor,
+    ) {
+        let rings: Vec<Vec<(f64, f64)>> = rings
+            .iter()
+            .map(|ring| {
+                ring.as_ref()
+                    .iter()
+                    .map(|&coordinate| self.pixel_coordinate(coordinate))
+                    .collect()
+            })
+            .collect();
+
+        let (min_y, max_y) = rings
+            .iter()
+            .flatten()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &(_, y)| {
+                (min.min(y), max.max(y))
+            });
+
+        let first_row = min_y.floor().max(0.) as u32;
+        let last_row = max_y.ceil().min(f64::from(self.image.height())) as u32;
+
+        let mut intersections = Vec::new();
+        for row in first_row..last_row {
+            let y = f64::from(row) + 0.5;
+
+            intersections.clear();
+            for ring in &rings {
+                for edge in ring.windows(2) {
+                    let ((x0, y0), (x1, y1)) = (edge[0], edge[1]);
+                    if (y0 <= y && y < y1) || (y1 <= y && y < y0) {
+                        intersections.push(x0 + (y - y0) * (x1 - x0) / (y1 - y0));
+                    }
+                }
+            }
+            intersections.sort_by(f64::total_cmp);
+
+            for span in intersections.chunks_exact(2) {
+                let first_column = (span[0] - 0.5).ceil().max(0.) as u32;
+                let last_column = (span[1] - 0.5).ceil().min(f64::from(self.image.width())) as u32;
+
+                for column in first_column..last_column {
+                    self.blend(column, row, fill_color);
+                }
+            }
+        }
+
+        if stroke_width > 0. {
+            for ring in rings {
+                self.draw_pixel_line(&ring, stroke_width, stroke_color);
+            }
+        }
+    }
+
+    pub fn to_png(self) -> Result<Vec<u8>> {
+        image_buffer_to_png_bytes(self.image)
+    }
+
+    fn draw_pixel_line(&mut self, line: &[(f64, f64)], width: f64, color: RgbaColor) {
+        let half_width = f64::max(width / 2., 0.5);
+
+        // collect the pixels first s.t. overlapping segments do not blend twice
+        let mut pixels = HashSet::new();
+
+        for segment in line.windows(2) {
+            let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+
+            for (column, row) in self.pixels_within(
+                x0.min(x1) - half_width,
+                y0.min(y1) - half_width,
+                x0.max(x1) + half_width,
+                y0.max(y1) + half_width,
+            ) {
+                let pixel = (f64::from(column) + 0.5, f64::from(row) + 0.5);
+                if distance_to_segment(pixel, (x0, y0), (x1, y1)) <= half_width {
+                    pixels.insert((column, row));
+                }
+            }
+        }
+
+        for (column, row) in pixels {
+            self.blend(column, row, color);
+        }
+    }
+
+    fn pixel_coordinate(&self, coordinate: Coordinate2D) -> (f64, f64) {
+        (
+            (coordinate.x - self.bounds.lower_left().x) * self.x_scale,
+            (self.bounds.upper_right().y - coordinate.y) * self.y_scale,
+        )
+    }
+
+    /// All pixels of the image whose centers may lie in the given pixel rectangle
+    fn pixels_within(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> impl Iterator<Item = (u32, u32)> {
+        let columns =
+            min_x.floor().max(0.) as u32..max_x.ceil().min(f64::from(self.image.width())) as u32;
+        let rows =
+            min_y.floor().max(0.) as u32..max_y.ceil().min(f64::from(self.image.height())) as u32;
+
+        rows.flat_map(move |row| columns.clone().map(move |column| (column, row)))
+    }
+
+    /// Draws `color` over the pixel using alpha compositing
+    fn blend(&mut self, column: u32, row: u32, color: RgbaColor) {
+        let [red, green, blue, alpha] = color.into_inner();
+        let pixel = self.image.get_pixel_mut(column, row);
+        let Rgba([dst_red, dst_green, dst_blue, dst_alpha]) = *pixel;
+
+        let src_alpha = f64::from(alpha) / 255.;
+        let dst_alpha = f64::from(dst_alpha) / 255. * (1. - src_alpha);
+        let out_alpha = src_alpha + dst_alpha;
+
+        if out_alpha <= 0. {
+            return;
+        }
+
+        let channel = |src: u8, dst: u8| {
+            ((f64::from(src) * src_alpha + f64::from(dst) * dst_alpha) / out_alpha)
+                .round()
+                .clamp(0., 255.) as u8
+        };
+
+        *pixel = Rgba([
+            channel(red, dst_red),
+            channel(green, dst_green),
+            channel(blue, dst_blue),
+            (out_alpha * 255.).round().clamp(0., 255.) as u8,
+        ]);
+    }
+}
+
+fn distance_to_segment(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+
+    let t = if length_squared > 0. {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared).clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    f64::hypot(point.0 - (start.0 + t * dx), point.1 - (start.1 + t * dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> VectorCanvas {
+        VectorCanvas::new(
+            10,
+            10,
+            BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+        )
+    }
+
+    fn pixel(canvas: &VectorCanvas, column: u32, row: u32) -> RgbaColor {
+        let Rgba([red, green, blue, alpha]) = *canvas.image.get_pixel(column, row);
+        RgbaColor::new(red, green, blue, alpha)
+    }
+
+    #[test]
+    fn it_draws_points() {
+        let mut canvas = canvas();
+        canvas.draw_point(
+            (5., 5.).into(),
+            2.,
+            RgbaColor::white(),
+            1.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(pixel(&canvas, 5, 4), RgbaColor::white());
+        assert_eq!(pixel(&canvas, 5, 3), RgbaColor::black());
+        assert_eq!(pixel(&canvas, 0, 0), RgbaColor::transparent());
+    }
+
+    #[test]
+    fn it_draws_lines() {
+        let mut canvas = canvas();
+        canvas.draw_line(
+            &[(0., 5.5).into(), (10., 5.5).into()],
+            1.,
+            RgbaColor::black(),
+        );
+
+        for column in 0..10 {
+            assert_eq!(pixel(&canvas, column, 4), RgbaColor::black());
+        }
+        assert_eq!(pixel(&canvas, 0, 3), RgbaColor::transparent());
+        assert_eq!(pixel(&canvas, 0, 5), RgbaColor::transparent());
+    }
+
+    #[test]
+    fn it_fills_polygons_with_holes() {
+        let mut canvas = canvas();
+        canvas.draw_polygon(
+            &[
+                vec![
+                    (1., 1.).into(),
+                    (9., 1.).into(),
+                    (9., 9.).into(),
+                    (1., 9.).into(),
+                    (1., 1.).into(),
+                ],
+                vec![
+                    (4., 4.).into(),
+                    (6., 4.).into(),
+                    (6., 6.).into(),
+                    (4., 6.).into(),
+                    (4., 4.).into(),
+                ],
+            ],
+            RgbaColor::new(255, 0, 0, 255),
+            0.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(pixel(&canvas, 2, 2), RgbaColor::new(255, 0, 0, 255));
+        assert_eq!(pixel(&canvas, 4, 4), RgbaColor::transparent());
+        assert_eq!(pixel(&canvas, 0, 0), RgbaColor::transparent());
+    }
+
+    #[test]
+    fn it_blends_colors() {
+        let mut canvas = canvas();
+        canvas.draw_point(
+            (5., 5.).into(),
+            1.,
+            RgbaColor::white(),
+            0.,
+            RgbaColor::black(),
+        );
+        canvas.draw_point(
+            (5., 5.).into(),
+            1.,
+            RgbaColor::new(0, 0, 0, 128),
+            0.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(pixel(&canvas, 4, 4), RgbaColor::new(127, 127, 127, 255));
+    }
+}
@@ -1,5 +1,13 @@
+pub mod geodesic;
 pub mod image;
 pub mod reproject;
+mod simplify;
 mod spatial_relation;
+mod validity;
 
+pub use simplify::Simplify;
 pub use spatial_relation::Contains;
+pub use validity::{
+    repair_multi_line_string, repair_multi_point, repair_multi_polygon, GeometryIssue,
+    RepairGeometries, RepairGeometry, RepairStatistics, Repaired, ValidateGeometry,
+};
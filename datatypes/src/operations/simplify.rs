@@ -0,0 +1,215 @@
+use geo::algorithm::simplify::Simplify as GeoSimplify;
+
+use crate::collections::{
+    DataCollection, IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection,
+    MultiPolygonCollection,
+};
+use crate::primitives::{
+    Coordinate2D, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPolygon,
+    MultiPolygonAccess, MultiPolygonRef,
+};
+use crate::util::Result;
+
+/// Simplifies geometries by removing vertices with the Douglas-Peucker algorithm.
+/// `tolerance` is the maximum distance of removed vertices to the simplified shape,
+/// given in the units of the spatial reference.
+pub trait Simplify {
+    type Out;
+
+    fn simplify(&self, tolerance: f64) -> Result<Self::Out>;
+}
+
+/// Simplifies a line and keeps the original if it would have less than `min_coordinates`
+fn simplify_coordinates(
+    coordinates: &[Coordinate2D],
+    tolerance: f64,
+    min_coordinates: usize,
+) -> Vec<Coordinate2D> {
+    let line_string = geo::LineString(coordinates.iter().map(Into::into).collect());
+
+    let simplified: Vec<Coordinate2D> = line_string
+        .simplify(&tolerance)
+        .0
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    if simplified.len() < min_coordinates {
+        coordinates.to_vec()
+    } else {
+        simplified
+    }
+}
+
+fn simplify_lines<M: MultiLineStringAccess>(
+    geometry: &M,
+    tolerance: f64,
+) -> Result<MultiLineString> {
+    MultiLineString::new(
+        geometry
+            .lines()
+            .iter()
+            .map(|line| simplify_coordinates(line.as_ref(), tolerance, 2))
+            .collect(),
+    )
+}
+
+fn simplify_polygons<M: MultiPolygonAccess>(geometry: &M, tolerance: f64) -> Result<MultiPolygon> {
+    MultiPolygon::new(
+        geometry
+            .polygons()
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .as_ref()
+                    .iter()
+                    .map(|ring| simplify_coordinates(ring.as_ref(), tolerance, 4))
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+impl Simplify for MultiLineString {
+    type Out = MultiLineString;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiLineString> {
+        simplify_lines(self, tolerance)
+    }
+}
+
+impl<'g> Simplify for MultiLineStringRef<'g> {
+    type Out = MultiLineString;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiLineString> {
+        simplify_lines(self, tolerance)
+    }
+}
+
+impl Simplify for MultiPolygon {
+    type Out = MultiPolygon;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiPolygon> {
+        simplify_polygons(self, tolerance)
+    }
+}
+
+impl<'g> Simplify for MultiPolygonRef<'g> {
+    type Out = MultiPolygon;
+
+    fn simplify(&self, tolerance: f64) -> Result<MultiPolygon> {
+        simplify_polygons(self, tolerance)
+    }
+}
+
+impl Simplify for MultiLineStringCollection {
+    type Out = Self;
+
+    fn simplify(&self, tolerance: f64) -> Result<Self> {
+        let geometries = self
+            .geometries()
+            .map(|geometry| geometry.simplify(tolerance))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.replace_geometries(geometries)
+    }
+}
+
+impl Simplify for MultiPolygonCollection {
+    type Out = Self;
+
+    fn simplify(&self, tolerance: f64) -> Result<Self> {
+        let geometries = self
+            .geometries()
+            .map(|geometry| geometry.simplify(tolerance))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.replace_geometries(geometries)
+    }
+}
+
+/// Points have no vertices that could be removed
+impl Simplify for MultiPointCollection {
+    type Out = Self;
+
+    fn simplify(&self, _tolerance: f64) -> Result<Self> {
+        Ok(self.clone())
+    }
+}
+
+impl Simplify for DataCollection {
+    type Out = Self;
+
+    fn simplify(&self, _tolerance: f64) -> Result<Self> {
+        Ok(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{FeatureCollectionInfos, GeometryCollection};
+    use crate::primitives::{FeatureData, TimeInterval};
+
+    #[test]
+    fn it_simplifies_lines() {
+        let line = MultiLineString::new(vec![vec![
+            (0.0, 0.0).into(),
+            (1.0, 0.1).into(),
+            (2.0, -0.1).into(),
+            (3.0, 5.0).into(),
+        ]])
+        .unwrap();
+
+        assert_eq!(
+            line.simplify(0.5).unwrap(),
+            MultiLineString::new(vec![vec![
+                (0.0, 0.0).into(),
+                (2.0, -0.1).into(),
+                (3.0, 5.0).into(),
+            ]])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_keeps_rings_valid() {
+        let polygon = MultiPolygon::new(vec![vec![vec![
+            (0.0, 0.0).into(),
+            (1.0, 0.0).into(),
+            (1.0, 1.0).into(),
+            (0.0, 1.0).into(),
+            (0.0, 0.0).into(),
+        ]]])
+        .unwrap();
+
+        assert_eq!(polygon.simplify(10.).unwrap(), polygon);
+    }
+
+    #[test]
+    fn it_simplifies_collections() {
+        let collection = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (0.0, 0.0).into(),
+                (1.0, 0.0).into(),
+                (2.0, 0.01).into(),
+                (3.0, 0.0).into(),
+                (3.0, 3.0).into(),
+                (0.0, 0.0).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            [("id".to_string(), FeatureData::Int(vec![42]))].into(),
+        )
+        .unwrap();
+
+        let simplified = collection.simplify(0.1).unwrap();
+
+        assert_eq!(simplified.len(), 1);
+        assert_eq!(simplified.coordinates().len(), 4);
+        assert_eq!(
+            simplified.data("id").unwrap().get_unchecked(0),
+            collection.data("id").unwrap().get_unchecked(0)
+        );
+    }
+}
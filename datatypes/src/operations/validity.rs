@@ -0,0 +1,775 @@
+use std::collections::HashMap;
+
+use crate::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+};
+use crate::primitives::{
+    Coordinate2D, Geometry, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPoint,
+    MultiPointAccess, MultiPointRef, MultiPolygon, MultiPolygonAccess, MultiPolygonRef,
+};
+use crate::util::arrow::ArrowTyped;
+use crate::util::Result;
+
+/// A reason why a geometry is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryIssue {
+    /// The geometry has no points, lines or polygons or a polygon has no rings
+    Empty,
+    /// A coordinate is `NaN` or infinite
+    NonFiniteCoordinate,
+    /// A line has less than two or a ring has less than four coordinates
+    TooFewCoordinates,
+    /// The first and the last coordinate of a ring differ
+    UnclosedRing,
+    /// A ring crosses or touches itself
+    SelfIntersection,
+}
+
+/// Checks geometries for issues that make them invalid w.r.t. the simple feature rules.
+///
+/// Only each ring is checked on its own, i.e., the relations of the rings of a polygon are not checked.
+/// The check for self-intersections compares all pairs of segments of a ring.
+pub trait ValidateGeometry {
+    /// Returns the first issue of the geometry or `None` if it is valid
+    fn validity_issue(&self) -> Option<GeometryIssue>;
+
+    fn is_valid(&self) -> bool {
+        self.validity_issue().is_none()
+    }
+}
+
+/// The result of repairing a geometry
+#[derive(Debug, Clone, PartialEq)]
+pub enum Repaired<G> {
+    /// The geometry was valid and is unchanged
+    Valid(G),
+    /// The geometry was invalid and was repaired
+    Repaired(G),
+    /// The geometry was invalid and nothing valid remains, e.g., if all rings were degenerate
+    Dropped,
+}
+
+impl<G> Repaired<G> {
+    pub fn into_geometry(self) -> Option<G> {
+        match self {
+            Repaired::Valid(geometry) | Repaired::Repaired(geometry) => Some(geometry),
+            Repaired::Dropped => None,
+        }
+    }
+}
+
+/// Repairs invalid geometries.
+///
+/// Non-finite and duplicate consecutive coordinates are removed and unclosed rings are closed.
+/// Self-intersecting rings are split into simple rings at their intersections.
+/// Lines and rings with too few coordinates are dropped, and so are polygons with a dropped exterior ring.
+pub trait RepairGeometry {
+    type Out;
+
+    fn repair(&self) -> Repaired<Self::Out>;
+}
+
+/// The number of features whose geometries were repaired or dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RepairStatistics {
+    pub repaired: usize,
+    pub dropped: usize,
+}
+
+/// Repairs the geometries of a collection and removes the features whose geometries cannot be repaired
+pub trait RepairGeometries: Sized {
+    fn repair_geometries(&self) -> Result<(Self, RepairStatistics)>;
+}
+
+/// Repairs a multi point given by its raw coordinates
+pub fn repair_multi_point(points: Vec<Coordinate2D>) -> Repaired<MultiPoint> {
+    if points_issue(&points).is_none() {
+        return Repaired::Valid(MultiPoint::new_unchecked(points));
+    }
+
+    let points: Vec<Coordinate2D> = points.into_iter().filter(is_finite).collect();
+
+    if points.is_empty() {
+        Repaired::Dropped
+    } else {
+        Repaired::Repaired(MultiPoint::new_unchecked(points))
+    }
+}
+
+/// Repairs a multi line string given by its raw lines
+pub fn repair_multi_line_string(lines: Vec<Vec<Coordinate2D>>) -> Repaired<MultiLineString> {
+    if lines_issue(&lines).is_none() {
+        return Repaired::Valid(MultiLineString::new_unchecked(lines));
+    }
+
+    let lines: Vec<Vec<Coordinate2D>> = lines
+        .into_iter()
+        .map(clean_coordinates)
+        .filter(|line| line.len() >= 2)
+        .collect();
+
+    if lines.is_empty() {
+        Repaired::Dropped
+    } else {
+        Repaired::Repaired(MultiLineString::new_unchecked(lines))
+    }
+}
+
+/// Repairs a multi polygon given by its raw rings
+pub fn repair_multi_polygon(polygons: Vec<Vec<Vec<Coordinate2D>>>) -> Repaired<MultiPolygon> {
+    if polygons_issue(&polygons).is_none() {
+        return Repaired::Valid(MultiPolygon::new_unchecked(polygons));
+    }
+
+    let polygons: Vec<Vec<Vec<Coordinate2D>>> =
+        polygons.into_iter().flat_map(repair_polygon).collect();
+
+    if polygons.is_empty() {
+        Repaired::Dropped
+    } else {
+        Repaired::Repaired(MultiPolygon::new_unchecked(polygons))
+    }
+}
+
+fn is_finite(coordinate: &Coordinate2D) -> bool {
+    coordinate.x.is_finite() && coordinate.y.is_finite()
+}
+
+fn points_issue(points: &[Coordinate2D]) -> Option<GeometryIssue> {
+    if points.is_empty() {
+        return Some(GeometryIssue::Empty);
+    }
+
+    if !points.iter().all(is_finite) {
+        return Some(GeometryIssue::NonFiniteCoordinate);
+    }
+
+    None
+}
+
+fn lines_issue<L: AsRef<[Coordinate2D]>>(lines: &[L]) -> Option<GeometryIssue> {
+    if lines.is_empty() {
+        return Some(GeometryIssue::Empty);
+    }
+
+    lines.iter().find_map(|line| {
+        let line = line.as_ref();
+
+        if !line.iter().all(is_finite) {
+            Some(GeometryIssue::NonFiniteCoordinate)
+        } else if line.len() < 2 {
+            Some(GeometryIssue::TooFewCoordinates)
+        } else {
+            None
+        }
+    })
+}
+
+fn polygons_issue<P, R>(polygons: &[P]) -> Option<GeometryIssue>
+where
+    P: AsRef<[R]>,
+    R: AsRef<[Coordinate2D]>,
+{
+    if polygons.is_empty() {
+        return Some(GeometryIssue::Empty);
+    }
+
+    polygons.iter().find_map(|polygon| {
+        let polygon = polygon.as_ref();
+
+        if polygon.is_empty() {
+            return Some(GeometryIssue::Empty);
+        }
+
+        polygon.iter().find_map(|ring| ring_issue(ring.as_ref()))
+    })
+}
+
+fn ring_issue(ring: &[Coordinate2D]) -> Option<GeometryIssue> {
+    if !ring.iter().all(is_finite) {
+        return Some(GeometryIssue::NonFiniteCoordinate);
+    }
+
+    if ring.first() != ring.last() && ring.len() >= 4 {
+        return Some(GeometryIssue::UnclosedRing);
+    }
+
+    // repeated consecutive coordinates are allowed, but would count as touching segments
+    let ring = clean_coordinates(ring.to_vec());
+
+    if ring.len() < 4 {
+        Some(GeometryIssue::TooFewCoordinates)
+    } else if is_self_intersecting(&ring) {
+        Some(GeometryIssue::SelfIntersection)
+    } else {
+        None
+    }
+}
+
+/// Checks a closed ring for intersections of its segments other than the shared vertices of adjacent segments
+fn is_self_intersecting(ring: &[Coordinate2D]) -> bool {
+    let segments = ring.len() - 1;
+
+    any_overlapping_segment_pair(ring, |i, j| {
+        let intersections = segment_intersections(ring[i], ring[i + 1], ring[j], ring[j + 1]);
+
+        let shared_vertex = if j == i + 1 {
+            Some(ring[j])
+        } else if i == 0 && j == segments - 1 {
+            Some(ring[0])
+        } else {
+            None
+        };
+
+        intersections
+            .iter()
+            .any(|&intersection| Some(intersection) != shared_vertex)
+    })
+}
+
+/// Sweeps over the segments of a closed ring from left to right and calls `visit` for the indices `i < j`
+/// of each pair of segments whose x-ranges overlap, since only these can intersect.
+/// Stops and returns `true` as soon as `visit` returns `true`.
+fn any_overlapping_segment_pair(
+    ring: &[Coordinate2D],
+    mut visit: impl FnMut(usize, usize) -> bool,
+) -> bool {
+    let x_range = |i: usize| {
+        let (a, b) = (ring[i].x, ring[i + 1].x);
+        (a.min(b), a.max(b))
+    };
+
+    let mut order: Vec<usize> = (0..ring.len() - 1).collect();
+    order.sort_unstable_by(|&i, &j| x_range(i).0.total_cmp(&x_range(j).0));
+
+    // the segments that the sweep line currently crosses
+    let mut active: Vec<usize> = Vec::new();
+
+    for j in order {
+        let (min_x, _) = x_range(j);
+        active.retain(|&i| x_range(i).1 >= min_x);
+
+        if active.iter().any(|&i| visit(i.min(j), i.max(j))) {
+            return true;
+        }
+
+        active.push(j);
+    }
+
+    false
+}
+
+/// Computes the intersection points of the segments `a`-`b` and `c`-`d`.
+/// These are two points if the segments are collinear and overlap.
+#[allow(clippy::float_cmp)] // exact comparisons detect intersections at vertices
+fn segment_intersections(
+    a: Coordinate2D,
+    b: Coordinate2D,
+    c: Coordinate2D,
+    d: Coordinate2D,
+) -> Vec<Coordinate2D> {
+    fn cross(u: (f64, f64), v: (f64, f64)) -> f64 {
+        u.0 * v.1 - u.1 * v.0
+    }
+
+    fn within(p: Coordinate2D, a: Coordinate2D, b: Coordinate2D) -> bool {
+        p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+    }
+
+    let r = (b.x - a.x, b.y - a.y);
+    let s = (d.x - c.x, d.y - c.y);
+    let ac = (c.x - a.x, c.y - a.y);
+
+    let denominator = cross(r, s);
+
+    if denominator == 0. {
+        if cross(ac, r) != 0. {
+            // parallel
+            return vec![];
+        }
+
+        let mut intersections: Vec<Coordinate2D> = Vec::with_capacity(2);
+        for (p, x, y) in [(a, c, d), (b, c, d), (c, a, b), (d, a, b)] {
+            if within(p, x, y) && !intersections.contains(&p) {
+                intersections.push(p);
+            }
+        }
+        return intersections;
+    }
+
+    let t = cross(ac, s) / denominator;
+    let u = cross(ac, r) / denominator;
+
+    if !(0. ..=1.).contains(&t) || !(0. ..=1.).contains(&u) {
+        return vec![];
+    }
+
+    // prefer exact vertices to avoid rounding errors
+    let intersection = if t == 0. {
+        a
+    } else if t == 1. {
+        b
+    } else if u == 0. {
+        c
+    } else if u == 1. {
+        d
+    } else {
+        Coordinate2D::new(a.x + t * r.0, a.y + t * r.1)
+    };
+
+    vec![intersection]
+}
+
+/// Removes non-finite and duplicate consecutive coordinates
+fn clean_coordinates(mut coordinates: Vec<Coordinate2D>) -> Vec<Coordinate2D> {
+    coordinates.retain(is_finite);
+    coordinates.dedup();
+    coordinates
+}
+
+/// Repairs a polygon, which may result in multiple polygons if its exterior ring is split.
+/// Holes are assigned to the first polygon that contains them and are dropped otherwise.
+fn repair_polygon(rings: Vec<Vec<Coordinate2D>>) -> Vec<Vec<Vec<Coordinate2D>>> {
+    let mut rings = rings.into_iter();
+
+    let exterior = if let Some(exterior) = rings.next() {
+        exterior
+    } else {
+        return vec![];
+    };
+
+    let mut polygons: Vec<Vec<Vec<Coordinate2D>>> = repair_ring(exterior)
+        .into_iter()
+        .map(|ring| vec![ring])
+        .collect();
+
+    for hole in rings.flat_map(repair_ring) {
+        if let Some(polygon) = polygons.iter_mut().find(|polygon| {
+            hole.iter()
+                .any(|&coordinate| ring_contains(&polygon[0], coordinate))
+        }) {
+            polygon.push(hole);
+        }
+    }
+
+    polygons
+}
+
+/// Repairs a ring, which results in no ring if it is degenerate or in multiple rings if it is self-intersecting
+fn repair_ring(ring: Vec<Coordinate2D>) -> Vec<Vec<Coordinate2D>> {
+    let mut ring = clean_coordinates(ring);
+
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+
+    if ring.len() < 3 {
+        return vec![];
+    }
+
+    ring.push(ring[0]);
+
+    if !is_self_intersecting(&ring) {
+        return vec![ring];
+    }
+
+    split_ring(&node_ring(&ring))
+}
+
+/// Inserts all intersection points of the segments of a closed ring as vertices.
+/// The result is open, i.e., the first coordinate is not repeated at the end.
+fn node_ring(ring: &[Coordinate2D]) -> Vec<Coordinate2D> {
+    let segments = ring.len() - 1;
+
+    let mut segment_vertices: Vec<Vec<Coordinate2D>> =
+        (0..segments).map(|i| vec![ring[i], ring[i + 1]]).collect();
+
+    any_overlapping_segment_pair(ring, |i, j| {
+        for intersection in segment_intersections(ring[i], ring[i + 1], ring[j], ring[j + 1]) {
+            segment_vertices[i].push(intersection);
+            segment_vertices[j].push(intersection);
+        }
+        false
+    });
+
+    let mut noded = Vec::with_capacity(ring.len());
+    for (i, mut vertices) in segment_vertices.into_iter().enumerate() {
+        let start = ring[i];
+        vertices.sort_by(|a, b| {
+            let distance_a = (a.x - start.x).powi(2) + (a.y - start.y).powi(2);
+            let distance_b = (b.x - start.x).powi(2) + (b.y - start.y).powi(2);
+            distance_a.total_cmp(&distance_b)
+        });
+        vertices.dedup();
+        vertices.pop(); // the end is the start of the next segment
+
+        noded.extend(vertices);
+    }
+
+    noded
+}
+
+/// Splits a noded, open ring into simple closed rings at its repeated vertices.
+/// Degenerate rings without area are removed.
+fn split_ring(noded: &[Coordinate2D]) -> Vec<Vec<Coordinate2D>> {
+    fn key(coordinate: Coordinate2D) -> (u64, u64) {
+        // adding zero normalizes negative zeros
+        ((coordinate.x + 0.).to_bits(), (coordinate.y + 0.).to_bits())
+    }
+
+    let mut rings = Vec::new();
+    let mut path: Vec<Coordinate2D> = Vec::with_capacity(noded.len() + 1);
+    let mut positions: HashMap<(u64, u64), usize> = HashMap::new();
+
+    for &coordinate in noded {
+        if let Some(&start) = positions.get(&key(coordinate)) {
+            let mut ring: Vec<Coordinate2D> = path.drain(start..).collect();
+            for removed in &ring[1..] {
+                positions.remove(&key(*removed));
+            }
+            ring.push(coordinate);
+            path.push(coordinate);
+
+            rings.push(ring);
+        } else {
+            positions.insert(key(coordinate), path.len());
+            path.push(coordinate);
+        }
+    }
+
+    if let Some(&first) = path.first() {
+        path.push(first);
+        rings.push(path);
+    }
+
+    rings.retain(|ring| ring.len() >= 4 && signed_area(ring) != 0.);
+
+    rings
+}
+
+/// Computes the signed area of a closed ring with the shoelace formula
+fn signed_area(ring: &[Coordinate2D]) -> f64 {
+    ring.windows(2)
+        .map(|segment| segment[0].x * segment[1].y - segment[1].x * segment[0].y)
+        .sum::<f64>()
+        / 2.
+}
+
+/// Checks if a coordinate lies inside of a closed ring with the even-odd rule
+fn ring_contains(ring: &[Coordinate2D], coordinate: Coordinate2D) -> bool {
+    let mut inside = false;
+
+    for segment in ring.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+
+        if (a.y > coordinate.y) != (b.y > coordinate.y)
+            && coordinate.x < (b.x - a.x) * (coordinate.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+impl ValidateGeometry for MultiPoint {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        points_issue(self.points())
+    }
+}
+
+impl<'g> ValidateGeometry for MultiPointRef<'g> {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        points_issue(self.points())
+    }
+}
+
+impl ValidateGeometry for MultiLineString {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        lines_issue(self.lines())
+    }
+}
+
+impl<'g> ValidateGeometry for MultiLineStringRef<'g> {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        lines_issue(self.lines())
+    }
+}
+
+impl ValidateGeometry for MultiPolygon {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        polygons_issue(self.polygons())
+    }
+}
+
+impl<'g> ValidateGeometry for MultiPolygonRef<'g> {
+    fn validity_issue(&self) -> Option<GeometryIssue> {
+        polygons_issue(self.polygons())
+    }
+}
+
+impl RepairGeometry for MultiPoint {
+    type Out = MultiPoint;
+
+    fn repair(&self) -> Repaired<MultiPoint> {
+        repair_multi_point(self.points().to_vec())
+    }
+}
+
+impl<'g> RepairGeometry for MultiPointRef<'g> {
+    type Out = MultiPoint;
+
+    fn repair(&self) -> Repaired<MultiPoint> {
+        repair_multi_point(self.points().to_vec())
+    }
+}
+
+fn raw_lines<M: MultiLineStringAccess>(geometry: &M) -> Vec<Vec<Coordinate2D>> {
+    geometry
+        .lines()
+        .iter()
+        .map(|line| line.as_ref().to_vec())
+        .collect()
+}
+
+impl RepairGeometry for MultiLineString {
+    type Out = MultiLineString;
+
+    fn repair(&self) -> Repaired<MultiLineString> {
+        repair_multi_line_string(raw_lines(self))
+    }
+}
+
+impl<'g> RepairGeometry for MultiLineStringRef<'g> {
+    type Out = MultiLineString;
+
+    fn repair(&self) -> Repaired<MultiLineString> {
+        repair_multi_line_string(raw_lines(self))
+    }
+}
+
+fn raw_polygons<M: MultiPolygonAccess>(geometry: &M) -> Vec<Vec<Vec<Coordinate2D>>> {
+    geometry
+        .polygons()
+        .iter()
+        .map(|polygon| {
+            polygon
+                .as_ref()
+                .iter()
+                .map(|ring| ring.as_ref().to_vec())
+                .collect()
+        })
+        .collect()
+}
+
+impl RepairGeometry for MultiPolygon {
+    type Out = MultiPolygon;
+
+    fn repair(&self) -> Repaired<MultiPolygon> {
+        repair_multi_polygon(raw_polygons(self))
+    }
+}
+
+impl<'g> RepairGeometry for MultiPolygonRef<'g> {
+    type Out = MultiPolygon;
+
+    fn repair(&self) -> Repaired<MultiPolygon> {
+        repair_multi_polygon(raw_polygons(self))
+    }
+}
+
+fn repair_collection<'a, G>(
+    collection: &'a FeatureCollection<G>,
+) -> Result<(FeatureCollection<G>, RepairStatistics)>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: IntoGeometryIterator<'a>,
+    <FeatureCollection<G> as IntoGeometryIterator<'a>>::GeometryType:
+        ValidateGeometry + RepairGeometry<Out = G>,
+{
+    let mut statistics = RepairStatistics::default();
+
+    if collection.geometries().all(|geometry| geometry.is_valid()) {
+        return Ok((collection.clone(), statistics));
+    }
+
+    let mut geometries = Vec::with_capacity(collection.len());
+    let mut mask = Vec::with_capacity(collection.len());
+
+    for geometry in collection.geometries() {
+        match geometry.repair() {
+            Repaired::Valid(geometry) => {
+                geometries.push(geometry);
+                mask.push(true);
+            }
+            Repaired::Repaired(geometry) => {
+                statistics.repaired += 1;
+                geometries.push(geometry);
+                mask.push(true);
+            }
+            Repaired::Dropped => {
+                statistics.dropped += 1;
+                mask.push(false);
+            }
+        }
+    }
+
+    let collection = if statistics.dropped > 0 {
+        collection.filter(mask)?
+    } else {
+        collection.clone()
+    };
+
+    Ok((collection.replace_geometries(geometries)?, statistics))
+}
+
+impl RepairGeometries for MultiPointCollection {
+    fn repair_geometries(&self) -> Result<(Self, RepairStatistics)> {
+        repair_collection(self)
+    }
+}
+
+impl RepairGeometries for MultiLineStringCollection {
+    fn repair_geometries(&self) -> Result<(Self, RepairStatistics)> {
+        repair_collection(self)
+    }
+}
+
+impl RepairGeometries for MultiPolygonCollection {
+    fn repair_geometries(&self) -> Result<(Self, RepairStatistics)> {
+        repair_collection(self)
+    }
+}
+
+/// Data collections have no geometries that could be invalid
+impl RepairGeometries for DataCollection {
+    fn repair_geometries(&self) -> Result<(Self, RepairStatistics)> {
+        Ok((self.clone(), RepairStatistics::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::GeometryCollection;
+    use crate::primitives::TimeInterval;
+
+    fn ring(coordinates: &[(f64, f64)]) -> Vec<Coordinate2D> {
+        coordinates.iter().copied().map(Into::into).collect()
+    }
+
+    #[test]
+    fn it_detects_issues() {
+        let square = ring(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)]);
+        let bowtie = ring(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.), (0., 0.)]);
+        let unclosed = ring(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.)]);
+
+        assert_eq!(
+            MultiPolygon::new(vec![vec![square]])
+                .unwrap()
+                .validity_issue(),
+            None
+        );
+        assert_eq!(
+            MultiPolygon::new(vec![vec![bowtie]])
+                .unwrap()
+                .validity_issue(),
+            Some(GeometryIssue::SelfIntersection)
+        );
+        assert_eq!(
+            polygons_issue(&[vec![unclosed]]),
+            Some(GeometryIssue::UnclosedRing)
+        );
+        assert_eq!(
+            MultiPoint::new(vec![(f64::NAN, 0.).into()])
+                .unwrap()
+                .validity_issue(),
+            Some(GeometryIssue::NonFiniteCoordinate)
+        );
+    }
+
+    #[test]
+    fn it_detects_intersections_of_distant_segments() {
+        let mut bottom: Vec<(f64, f64)> = (0..=100).map(|x| (f64::from(x), 0.)).collect();
+        bottom.push((100., 10.));
+
+        let mut rectangle = bottom.clone();
+        rectangle.extend([(0., 10.), (0., 0.)]);
+        assert!(!is_self_intersecting(&ring(&rectangle)));
+
+        // the second to last segment crosses the bottom between `x = 54` and `x = 55`
+        let mut crossing = bottom;
+        crossing.extend([(50., -1.), (0., 0.)]);
+        assert!(is_self_intersecting(&ring(&crossing)));
+    }
+
+    #[test]
+    fn it_splits_self_intersecting_rings() {
+        let bowtie = ring(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.), (0., 0.)]);
+
+        let repaired = repair_multi_polygon(vec![vec![bowtie]]);
+
+        assert_eq!(
+            repaired,
+            Repaired::Repaired(
+                MultiPolygon::new(vec![
+                    vec![ring(&[(1., 1.), (2., 2.), (2., 0.), (1., 1.)])],
+                    vec![ring(&[(0., 0.), (1., 1.), (0., 2.), (0., 0.)])],
+                ])
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn it_closes_rings_and_drops_degenerate_ones() {
+        let unclosed = ring(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.)]);
+        let degenerate = ring(&[(0.2, 0.2), (0.4, 0.4), (0.2, 0.2)]);
+
+        assert_eq!(
+            repair_multi_polygon(vec![vec![unclosed, degenerate]]),
+            Repaired::Repaired(
+                MultiPolygon::new(vec![vec![ring(&[
+                    (0., 0.),
+                    (1., 0.),
+                    (1., 1.),
+                    (0., 1.),
+                    (0., 0.)
+                ])]])
+                .unwrap()
+            )
+        );
+
+        assert_eq!(
+            repair_multi_polygon(vec![vec![ring(&[(0., 0.), (1., 1.), (0., 0.)])]]),
+            Repaired::Dropped
+        );
+    }
+
+    #[test]
+    fn it_repairs_collections() {
+        let collection = MultiPointCollection::from_data(
+            vec![
+                MultiPoint::new(vec![(0., 0.).into()]).unwrap(),
+                MultiPoint::new(vec![(f64::NAN, 0.).into(), (1., 1.).into()]).unwrap(),
+                MultiPoint::new(vec![(f64::INFINITY, 0.).into()]).unwrap(),
+            ],
+            vec![TimeInterval::default(); 3],
+            Default::default(),
+        )
+        .unwrap();
+
+        let (repaired, statistics) = collection.repair_geometries().unwrap();
+
+        assert_eq!(
+            statistics,
+            RepairStatistics {
+                repaired: 1,
+                dropped: 1,
+            }
+        );
+        assert_eq!(repaired.len(), 2);
+        assert_eq!(repaired.coordinates(), &[(0., 0.).into(), (1., 1.).into()]);
+    }
+}
@@ -19,6 +19,20 @@ pub struct Histogram {
     min: f64,
     max: f64,
     measurement: Measurement,
+    bucketing: HistogramBucketing,
+}
+
+/// How the range between the min and max value of a histogram is divided into buckets
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistogramBucketing {
+    /// Buckets of equal width
+    #[default]
+    Linear,
+    /// Buckets of equal width on a logarithmic scale, which requires a positive min value
+    Logarithmic,
+    /// Buckets between ascending edges, starting at the min and ending at the max value
+    Edges(Vec<f64>),
 }
 
 impl Histogram {
@@ -29,6 +43,7 @@ impl Histogram {
         measurement: Measurement,
         labels: Option<Vec<String>>,
         counts: Option<Vec<u64>>,
+        bucketing: HistogramBucketing,
     ) -> Result<Self> {
         ensure!(
             number_of_buckets > 0,
@@ -48,6 +63,36 @@ impl Histogram {
                 details: "Histograms max value must be larger than its min value"
             }
         );
+        match &bucketing {
+            HistogramBucketing::Linear => {}
+            HistogramBucketing::Logarithmic => ensure!(
+                min > 0.,
+                error::Plot {
+                    details: "Logarithmic histograms must have a positive min value"
+                }
+            ),
+            HistogramBucketing::Edges(edges) => {
+                ensure!(
+                    edges.len() == number_of_buckets + 1,
+                    error::Plot {
+                        details: "Histogram must have one more bucket edge than buckets"
+                    }
+                );
+                ensure!(
+                    edges.first() == Some(&min) && edges.last() == Some(&max),
+                    error::Plot {
+                        details:
+                            "Histogram bucket edges must start at its min and end at its max value"
+                    }
+                );
+                ensure!(
+                    edges.windows(2).all(|edges| edges[0] < edges[1]),
+                    error::Plot {
+                        details: "Histogram bucket edges must be strictly ascending"
+                    }
+                );
+            }
+        }
         if let Some(labels) = &labels {
             ensure!(
                 labels.len() == number_of_buckets,
@@ -76,6 +121,7 @@ impl Histogram {
             min,
             max,
             measurement,
+            bucketing,
         })
     }
 
@@ -241,33 +287,70 @@ impl Histogram {
             return 0;
         }
 
-        let fraction = (value - self.min) / (self.max - self.min);
+        let fraction = match &self.bucketing {
+            HistogramBucketing::Linear => (value - self.min) / (self.max - self.min),
+            HistogramBucketing::Logarithmic => {
+                (value.ln() - self.min.ln()) / (self.max.ln() - self.min.ln())
+            }
+            HistogramBucketing::Edges(edges) => {
+                // the outer edges are the min and max value, so only the inner edges separate buckets
+                return edges[1..edges.len() - 1].partition_point(|&edge| edge <= value);
+            }
+        };
         let bucket = (fraction * (self.counts.len() as f64)) as usize;
 
         cmp::min(bucket, self.counts.len() - 1)
     }
+
+    /// The edges of the buckets, starting at the min and ending at the max value
+    fn bucket_edges(&self) -> Vec<f64> {
+        let number_of_buckets = self.counts.len();
+
+        match &self.bucketing {
+            HistogramBucketing::Linear => {
+                let step = (self.max - self.min) / (number_of_buckets as f64);
+
+                let mut edges = Vec::with_capacity(number_of_buckets + 1);
+                let mut edge = self.min;
+                edges.push(edge);
+                for _ in 0..number_of_buckets {
+                    edge += step;
+                    edges.push(edge);
+                }
+                edges
+            }
+            HistogramBucketing::Logarithmic => {
+                let (min, max) = (self.min.ln(), self.max.ln());
+
+                (0..=number_of_buckets)
+                    .map(|i| match i {
+                        0 => self.min,
+                        i if i == number_of_buckets => self.max,
+                        i => (min + (max - min) * (i as f64) / (number_of_buckets as f64)).exp(),
+                    })
+                    .collect()
+            }
+            HistogramBucketing::Edges(edges) => edges.clone(),
+        }
+    }
 }
 
 impl Plot for Histogram {
     fn to_vega_embeddable(&self, allow_interactions: bool) -> Result<PlotData> {
-        let mut step = (self.max - self.min) / (self.counts.len() as f64);
-
-        let mut values = Vec::with_capacity(self.counts.len());
-        let mut bin_start = self.min;
-        for &count in &self.counts {
-            let bin_end = bin_start + step;
-            values.push(serde_json::json!({
-                "binStart": bin_start,
-                "binEnd": bin_end,
-                "Frequency": count,
-            }));
-            bin_start = bin_end;
-        }
+        let edges = self.bucket_edges();
 
-        // step in spec must not be 0, so add a fake step
-        if step == 0. {
-            step = 1.;
-        }
+        let values = self
+            .counts
+            .iter()
+            .zip(edges.windows(2))
+            .map(|(&count, bin)| {
+                serde_json::json!({
+                    "binStart": bin[0],
+                    "binEnd": bin[1],
+                    "Frequency": count,
+                })
+            })
+            .collect::<Vec<_>>();
 
         let mut vega_spec = serde_json::json!({
             "$schema": "https://vega.github.io/schema/vega-lite/v4.json",
@@ -280,7 +363,6 @@ impl Plot for Histogram {
                     "field": "binStart",
                     "bin": {
                         "binned": true,
-                        "step": step,
                     },
                     "axis": {
                         "title": self.measurement.to_string(),
@@ -296,6 +378,34 @@ impl Plot for Histogram {
             },
         });
 
+        let x_encoding = vega_spec
+            .pointer_mut("/encoding/x")
+            .and_then(serde_json::Value::as_object_mut)
+            .expect("as defined");
+
+        if self.bucketing == HistogramBucketing::Linear {
+            let mut step = (self.max - self.min) / (self.counts.len() as f64);
+
+            // step in spec must not be 0, so add a fake step
+            if step == 0. {
+                step = 1.;
+            }
+
+            x_encoding["bin"]["step"] = step.into();
+        } else {
+            // unequal buckets are labeled by their edges
+            x_encoding["axis"]["values"] = edges.into();
+        }
+
+        if self.bucketing == HistogramBucketing::Logarithmic {
+            x_encoding.insert(
+                "scale".to_owned(),
+                serde_json::json!({
+                    "type": "log",
+                }),
+            );
+        }
+
         let selection_name = if allow_interactions {
             let name = "range_selection".to_string();
 
@@ -330,6 +440,7 @@ pub struct HistogramBuilder {
     measurement: Measurement,
     labels: Option<Vec<String>>,
     counts: Option<Vec<u64>>,
+    bucketing: HistogramBucketing,
 }
 
 impl HistogramBuilder {
@@ -342,6 +453,7 @@ impl HistogramBuilder {
             measurement,
             labels: None,
             counts: None,
+            bucketing: HistogramBucketing::default(),
         }
     }
 
@@ -370,6 +482,34 @@ impl HistogramBuilder {
         self
     }
 
+    /// Sets how the range of the histogram is divided into buckets
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geoengine_datatypes::plots::{Histogram, HistogramBucketing};
+    /// use geoengine_datatypes::primitives::Measurement;
+    ///
+    /// Histogram::builder(2, 1., 100., Measurement::Unitless)
+    ///     .bucketing(HistogramBucketing::Logarithmic)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// Histogram::builder(2, 0., 100., Measurement::Unitless)
+    ///     .bucketing(HistogramBucketing::Edges(vec![0., 10., 100.]))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// Histogram::builder(2, 0., 100., Measurement::Unitless)
+    ///     .bucketing(HistogramBucketing::Logarithmic)
+    ///     .build()
+    ///     .unwrap_err();
+    /// ```
+    #[must_use]
+    pub fn bucketing(mut self, bucketing: HistogramBucketing) -> Self {
+        self.bucketing = bucketing;
+        self
+    }
+
     /// Builds a histogram out of the collected parameters
     ///
     /// # Examples
@@ -394,6 +534,7 @@ impl HistogramBuilder {
             self.measurement,
             self.labels,
             self.counts,
+            self.bucketing,
         )
     }
 }
@@ -418,6 +559,49 @@ mod tests {
         assert_eq!(histogram.bucket_for_value(0.49), 0);
     }
 
+    #[test]
+    fn bucket_for_value_logarithmic() {
+        let histogram = Histogram::builder(3, 1., 1000., Measurement::Unitless)
+            .bucketing(HistogramBucketing::Logarithmic)
+            .build()
+            .unwrap();
+
+        assert_eq!(histogram.bucket_for_value(1.), 0);
+        assert_eq!(histogram.bucket_for_value(9.), 0);
+        assert_eq!(histogram.bucket_for_value(11.), 1);
+        assert_eq!(histogram.bucket_for_value(999.), 2);
+        assert_eq!(histogram.bucket_for_value(1000.), 2);
+    }
+
+    #[test]
+    fn bucket_for_value_edges() {
+        let histogram = Histogram::builder(3, 0., 100., Measurement::Unitless)
+            .bucketing(HistogramBucketing::Edges(vec![0., 1., 10., 100.]))
+            .build()
+            .unwrap();
+
+        assert_eq!(histogram.bucket_for_value(0.), 0);
+        assert_eq!(histogram.bucket_for_value(0.99), 0);
+        assert_eq!(histogram.bucket_for_value(1.), 1);
+        assert_eq!(histogram.bucket_for_value(50.), 2);
+        assert_eq!(histogram.bucket_for_value(100.), 2);
+    }
+
+    #[test]
+    fn invalid_bucket_edges() {
+        for edges in [
+            vec![0., 10.],
+            vec![1., 10., 100.],
+            vec![0., 10., 10.],
+            vec![0., 20., 10.],
+        ] {
+            assert!(Histogram::builder(2, 0., 10., Measurement::Unitless)
+                .bucketing(HistogramBucketing::Edges(edges))
+                .build()
+                .is_err());
+        }
+    }
+
     #[test]
     fn add_feature_data_number() {
         let mut histogram = Histogram::builder(2, 0., 1., Measurement::Unitless)
@@ -604,6 +788,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn vega_logarithmic() {
+        let histogram = Histogram::builder(2, 1., 100., Measurement::Unitless)
+            .bucketing(HistogramBucketing::Logarithmic)
+            .counts(vec![3, 4])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            histogram.to_vega_embeddable(false).unwrap(),
+            PlotData {
+                vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.json","data":{"values":[{"Frequency":3,"binEnd":10.000000000000002,"binStart":1.0},{"Frequency":4,"binEnd":100.0,"binStart":10.000000000000002}]},"encoding":{"x":{"axis":{"title":"","values":[1.0,10.000000000000002,100.0]},"bin":{"binned":true},"field":"binStart","scale":{"type":"log"}},"x2":{"field":"binEnd"},"y":{"field":"Frequency","type":"quantitative"}},"mark":"bar"}"#.to_owned(),
+                metadata: PlotMetaData::None
+            }
+        );
+    }
+
+    #[test]
+    fn vega_bucket_edges() {
+        let mut histogram = Histogram::builder(3, 0., 100., Measurement::Unitless)
+            .bucketing(HistogramBucketing::Edges(vec![0., 1., 10., 100.]))
+            .build()
+            .unwrap();
+
+        histogram.add_raster_data([Some(0.5), Some(2.), Some(5.), Some(100.), None].into_iter());
+
+        assert_eq!(
+            histogram.to_vega_embeddable(false).unwrap(),
+            PlotData {
+                vega_string: r#"{"$schema":"https://vega.github.io/schema/vega-lite/v4.json","data":{"values":[{"Frequency":1,"binEnd":1.0,"binStart":0.0},{"Frequency":2,"binEnd":10.0,"binStart":1.0},{"Frequency":1,"binEnd":100.0,"binStart":10.0}]},"encoding":{"x":{"axis":{"title":"","values":[0.0,1.0,10.0,100.0]},"bin":{"binned":true},"field":"binStart"},"x2":{"field":"binEnd"},"y":{"field":"Frequency","type":"quantitative"}},"mark":"bar"}"#.to_owned(),
+                metadata: PlotMetaData::None
+            }
+        );
+    }
+
     #[test]
     fn empty_histogram() {
         assert_eq!(
@@ -9,7 +9,7 @@ mod scatter_plot;
 pub use area_line_plot::AreaLineChart;
 pub use bar_chart::BarChart;
 pub use box_plot::{BoxPlot, BoxPlotAttribute};
-pub use histogram::{Histogram, HistogramBuilder};
+pub use histogram::{Histogram, HistogramBucketing, HistogramBuilder};
 pub use histogram2d::{Histogram2D, HistogramDimension};
 pub use multi_line_plot::{DataPoint, MultiLineChart};
 pub use scatter_plot::ScatterPlot;
@@ -25,6 +25,13 @@ pub enum PrimitivesError {
         max: TimeInstance,
         is: i64,
     },
+
+    #[snafu(display("GeoJSON geometries of type {} are not supported", geometry_type))]
+    UnsupportedGeoJsonGeometry {
+        geometry_type: String,
+    },
+    #[snafu(display("GeoJSON positions must consist of at least two numbers"))]
+    InvalidGeoJsonPosition,
 }
 
 impl From<PrimitivesError> for Error {
@@ -37,6 +37,17 @@ impl FeatureDataType {
     pub fn is_numeric(self) -> bool {
         matches!(self, Self::Int | Self::Float)
     }
+
+    /// The type that can hold values of both types.
+    /// Integers widen to floats, all other mixed types fall back to text.
+    #[must_use]
+    pub fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            _ => Self::Text,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
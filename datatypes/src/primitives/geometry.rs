@@ -1,8 +1,11 @@
 use crate::collections::VectorDataType;
-use crate::primitives::{BoundingBox2D, MultiLineString, MultiPoint, MultiPolygon, NoGeometry};
+use crate::primitives::{
+    error, BoundingBox2D, Coordinate2D, MultiLineString, MultiPoint, MultiPolygon, NoGeometry,
+};
 
 use crate::error::Error;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 
@@ -30,3 +33,97 @@ pub enum TypedGeometry {
     MultiLineString(MultiLineString),
     MultiPolygon(MultiPolygon),
 }
+
+impl TryFrom<geojson::Geometry> for TypedGeometry {
+    type Error = Error;
+
+    /// Converts a `GeoJSON` geometry into the corresponding multi geometry.
+    /// Geometry collections are not supported.
+    fn try_from(geometry: geojson::Geometry) -> Result<Self, Self::Error> {
+        fn coordinate(position: &[f64]) -> Result<Coordinate2D, Error> {
+            ensure!(position.len() >= 2, error::InvalidGeoJsonPosition);
+            Ok(Coordinate2D::new(position[0], position[1]))
+        }
+
+        fn line(positions: &[Vec<f64>]) -> Result<Vec<Coordinate2D>, Error> {
+            positions.iter().map(|p| coordinate(p)).collect()
+        }
+
+        fn polygon(rings: &[Vec<Vec<f64>>]) -> Result<Vec<Vec<Coordinate2D>>, Error> {
+            rings.iter().map(|ring| line(ring)).collect()
+        }
+
+        Ok(match geometry.value {
+            geojson::Value::Point(point) => {
+                TypedGeometry::MultiPoint(MultiPoint::new(vec![coordinate(&point)?])?)
+            }
+            geojson::Value::MultiPoint(points) => {
+                TypedGeometry::MultiPoint(MultiPoint::new(line(&points)?)?)
+            }
+            geojson::Value::LineString(line_string) => {
+                TypedGeometry::MultiLineString(MultiLineString::new(vec![line(&line_string)?])?)
+            }
+            geojson::Value::MultiLineString(line_strings) => {
+                TypedGeometry::MultiLineString(MultiLineString::new(
+                    line_strings
+                        .iter()
+                        .map(|l| line(l))
+                        .collect::<Result<_, _>>()?,
+                )?)
+            }
+            geojson::Value::Polygon(rings) => {
+                TypedGeometry::MultiPolygon(MultiPolygon::new(vec![polygon(&rings)?])?)
+            }
+            geojson::Value::MultiPolygon(polygons) => {
+                TypedGeometry::MultiPolygon(MultiPolygon::new(
+                    polygons
+                        .iter()
+                        .map(|p| polygon(p))
+                        .collect::<Result<_, _>>()?,
+                )?)
+            }
+            geojson::Value::GeometryCollection(_) => {
+                return Err(error::PrimitivesError::UnsupportedGeoJsonGeometry {
+                    geometry_type: "GeometryCollection".to_owned(),
+                }
+                .into())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_geojson_geometries() {
+        let point: geojson::Geometry =
+            serde_json::from_str(r#"{"type": "Point", "coordinates": [1.0, 2.0]}"#).unwrap();
+        assert_eq!(
+            TypedGeometry::try_from(point).unwrap(),
+            TypedGeometry::MultiPoint(MultiPoint::new(vec![(1.0, 2.0).into()]).unwrap())
+        );
+
+        let polygon: geojson::Geometry = serde_json::from_str(
+            r#"{"type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TypedGeometry::try_from(polygon).unwrap(),
+            TypedGeometry::MultiPolygon(
+                MultiPolygon::new(vec![vec![vec![
+                    (0.0, 0.0).into(),
+                    (1.0, 0.0).into(),
+                    (1.0, 1.0).into(),
+                    (0.0, 0.0).into(),
+                ]]])
+                .unwrap()
+            )
+        );
+
+        let collection: geojson::Geometry =
+            serde_json::from_str(r#"{"type": "GeometryCollection", "geometries": []}"#).unwrap();
+        assert!(TypedGeometry::try_from(collection).is_err());
+    }
+}
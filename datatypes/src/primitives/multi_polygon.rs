@@ -121,6 +121,25 @@ impl From<&MultiPolygon> for geo::MultiPolygon<f64> {
     }
 }
 
+impl TryFrom<geo::MultiPolygon<f64>> for MultiPolygon {
+    type Error = Error;
+
+    fn try_from(geometry: geo::MultiPolygon<f64>) -> Result<Self, Self::Error> {
+        let polygons: Vec<Polygon> = geometry
+            .into_iter()
+            .map(|polygon| {
+                let (exterior, interiors) = polygon.into_inner();
+                std::iter::once(exterior)
+                    .chain(interiors)
+                    .map(|ring| ring.0.into_iter().map(Into::into).collect())
+                    .collect()
+            })
+            .collect();
+
+        MultiPolygon::new(polygons)
+    }
+}
+
 impl TryFrom<TypedGeometry> for MultiPolygon {
     type Error = Error;
 
@@ -680,4 +699,31 @@ mod tests {
 
         assert!(!approx_eq!(&MultiPolygon, &a, &b, F64Margin::default()));
     }
+
+    #[test]
+    fn geo_round_trip() {
+        let multi_polygon = MultiPolygon::new(vec![vec![
+            vec![
+                (0.0, 0.0).into(),
+                (4.0, 0.0).into(),
+                (4.0, 4.0).into(),
+                (0.0, 0.0).into(),
+            ],
+            vec![
+                (1.0, 0.5).into(),
+                (3.0, 0.5).into(),
+                (3.0, 2.5).into(),
+                (1.0, 0.5).into(),
+            ],
+        ]])
+        .unwrap();
+
+        let geo_multi_polygon: geo::MultiPolygon<f64> = (&multi_polygon).into();
+
+        assert_eq!(
+            MultiPolygon::try_from(geo_multi_polygon).unwrap(),
+            multi_polygon
+        );
+        assert!(MultiPolygon::try_from(geo::MultiPolygon::<f64>(vec![])).is_err());
+    }
 }
@@ -110,6 +110,16 @@ impl RasterDataType {
         }
     }
 
+    /// The number of bytes of a single pixel of this type
+    pub fn byte_size(self) -> usize {
+        match self {
+            RasterDataType::U8 | RasterDataType::I8 => 1,
+            RasterDataType::U16 | RasterDataType::I16 => 2,
+            RasterDataType::U32 | RasterDataType::I32 | RasterDataType::F32 => 4,
+            RasterDataType::U64 | RasterDataType::I64 | RasterDataType::F64 => 8,
+        }
+    }
+
     pub fn from_gdal_data_type(gdal_data_type: GDALDataType::Type) -> Result<Self> {
         match gdal_data_type {
             GDALDataType::GDT_Byte => Ok(Self::U8),
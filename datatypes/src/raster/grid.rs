@@ -4,6 +4,7 @@ use super::{
     GridIndexAccessMut, GridSize, GridSpaceToLinearSpace,
 };
 use crate::error;
+use crate::util::memory::{MemoryReservation, MEMORY_ACCOUNTANT};
 use crate::util::Result;
 use num::Integer;
 use num_traits::Zero;
@@ -282,12 +283,27 @@ where
 pub struct Grid<D, T> {
     pub shape: D,
     pub data: Vec<T>,
+    #[serde(skip)]
+    memory: MemoryReservation,
 }
 
 pub type Grid1D<T> = Grid<GridShape1D, T>;
 pub type Grid2D<T> = Grid<GridShape2D, T>;
 pub type Grid3D<T> = Grid<GridShape3D, T>;
 
+impl<D, T> Grid<D, T> {
+    /// Creates a new `Grid` without checking that the data matches the shape
+    pub(crate) fn new_unchecked(shape: D, data: Vec<T>) -> Self {
+        let memory = MEMORY_ACCOUNTANT.reserve(data.len() * std::mem::size_of::<T>());
+
+        Self {
+            shape,
+            data,
+            memory,
+        }
+    }
+}
+
 impl<D, T> Grid<D, T>
 where
     D: GridSize,
@@ -308,7 +324,7 @@ where
             }
         );
 
-        Ok(Self { shape, data })
+        Ok(Self::new_unchecked(shape, data))
     }
 
     pub fn new_filled(shape: D, fill_value: T) -> Self {
@@ -337,10 +353,7 @@ where
                     .for_each(|c| reversed_data_vec.extend_from_slice(c));
             });
 
-        Grid {
-            data: reversed_data_vec,
-            shape: self.shape.clone(),
-        }
+        Grid::new_unchecked(self.shape.clone(), reversed_data_vec)
     }
 }
 
@@ -494,10 +507,7 @@ where
     type Output = Grid<GridBoundingBox<I>, T>;
 
     fn shift_by_offset(self, offset: GridIdx<I>) -> Self::Output {
-        Grid {
-            shape: self.shift_bounding_box(offset),
-            data: self.data,
-        }
+        Grid::new_unchecked(self.shift_bounding_box(offset), self.data)
     }
 
     fn set_grid_bounds(self, bounds: GridBoundingBox<I>) -> Result<Self::Output> {
@@ -514,6 +524,7 @@ mod tests {
     };
 
     use super::{Grid2D, Grid3D, GridIndexAccess, GridIndexAccessMut};
+    use crate::raster::MapElements;
 
     #[test]
     fn simple_raster_2d() {
@@ -738,4 +749,13 @@ mod tests {
         assert_eq!(g2d_flipped_y.shape, [2, 2, 3].into());
         assert_eq!(g2d_flipped_y.data, vec![2, 2, 2, 1, 1, 1, 4, 4, 4, 3, 3, 3]);
     }
+
+    #[test]
+    fn grid_reserves_memory() {
+        let grid = Grid2D::new([2, 3].into(), vec![1_u16, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(grid.memory.bytes(), 12);
+
+        let grid_f64 = grid.map_elements(f64::from);
+        assert_eq!(grid_f64.memory.bytes(), 48);
+    }
 }
@@ -12,10 +12,8 @@ where
     Out: Copy + 'static,
 {
     fn convert_data_type(self) -> Grid<G, Out> {
-        Grid {
-            shape: self.shape,
-            data: self.data.iter().map(|&pixel| pixel.as_()).collect(),
-        }
+        let data = self.data.iter().map(|&pixel| pixel.as_()).collect();
+        Grid::new_unchecked(self.shape, data)
     }
 }
 
@@ -88,15 +86,13 @@ where
     fn convert_data_type_parallel(self) -> Grid<G, Out> {
         let lowest_dim_size = self.shape.axis_size_x();
 
-        Grid {
-            shape: self.shape,
-            data: self
-                .data
-                .into_par_iter()
-                .with_min_len(lowest_dim_size)
-                .map(AsPrimitive::as_)
-                .collect(),
-        }
+        let data = self
+            .data
+            .into_par_iter()
+            .with_min_len(lowest_dim_size)
+            .map(AsPrimitive::as_)
+            .collect();
+        Grid::new_unchecked(self.shape, data)
     }
 }
 
@@ -44,7 +44,7 @@ where
         let shape = self.shape;
         let data = self.data.into_iter().map(map_fn).collect();
 
-        Grid { shape, data }
+        Grid::new_unchecked(shape, data)
     }
 }
 
@@ -201,7 +201,7 @@ where
             .with_min_len(num_elements_per_thread)
             .map(map_fn)
             .collect_into_vec(data.as_mut());
-        Grid { shape, data }
+        Grid::new_unchecked(shape, data)
     }
 }
 
@@ -50,7 +50,7 @@ where
     type Output = Grid<G, Out>;
 
     fn map_indexed_elements(self, map_fn: F) -> Self::Output {
-        let Grid { shape, data } = self;
+        let Grid { shape, data, .. } = self;
 
         let out_data: Vec<Out> = data
             .into_iter()
@@ -333,7 +333,7 @@ where
     type Output = Grid<G, Out>;
 
     fn map_indexed_elements_parallel(self, map_fn: F) -> Self::Output {
-        let Grid { shape, data } = self;
+        let Grid { shape, data, .. } = self;
         let num_elements_per_thread =
             num::integer::div_ceil(shape.number_of_elements(), rayon::current_num_threads())
                 .max(MIN_ELEMENTS_PER_THREAD);
@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The process-wide accountant for the memory of feature collections and raster grids
+pub static MEMORY_ACCOUNTANT: MemoryAccountant = MemoryAccountant::new();
+
+/// Counts the bytes that are held by data of the process and compares them to an optional budget.
+///
+/// The numbers are estimates: data that is shared between several collections, e.g., after a
+/// filter, is accounted for each of them.
+#[derive(Debug, Default)]
+pub struct MemoryAccountant {
+    allocated_bytes: AtomicUsize,
+    /// zero means that there is no budget
+    budget_bytes: AtomicUsize,
+}
+
+impl MemoryAccountant {
+    pub const fn new() -> Self {
+        Self {
+            allocated_bytes: AtomicUsize::new(0),
+            budget_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Accounts for `bytes` until the returned reservation is dropped
+    pub fn reserve(&'static self, bytes: usize) -> MemoryReservation {
+        self.allocated_bytes.fetch_add(bytes, Ordering::Relaxed);
+
+        MemoryReservation {
+            accountant: self,
+            bytes,
+        }
+    }
+
+    pub fn allocated_bytes(&self) -> usize {
+        self.allocated_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn set_budget_bytes(&self, budget_bytes: Option<usize>) {
+        self.budget_bytes
+            .store(budget_bytes.unwrap_or_default(), Ordering::Relaxed);
+    }
+
+    pub fn budget_bytes(&self) -> Option<usize> {
+        match self.budget_bytes.load(Ordering::Relaxed) {
+            0 => None,
+            budget_bytes => Some(budget_bytes),
+        }
+    }
+
+    pub fn is_over_budget(&self) -> bool {
+        self.budget_bytes()
+            .map_or(false, |budget_bytes| self.allocated_bytes() > budget_bytes)
+    }
+
+    /// The fraction of the budget that is allocated, may exceed `1.0`.
+    /// Without a budget, there is no pressure.
+    pub fn pressure(&self) -> f64 {
+        self.budget_bytes().map_or(0., |budget_bytes| {
+            self.allocated_bytes() as f64 / budget_bytes as f64
+        })
+    }
+}
+
+/// Bytes that are accounted for by a `MemoryAccountant` until the reservation is dropped
+pub struct MemoryReservation {
+    accountant: &'static MemoryAccountant,
+    bytes: usize,
+}
+
+impl MemoryReservation {
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl std::fmt::Debug for MemoryReservation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryReservation")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
+
+/// A clone holds a copy of the data and thus reserves the same amount of bytes
+impl Clone for MemoryReservation {
+    fn clone(&self) -> Self {
+        self.accountant.reserve(self.bytes)
+    }
+}
+
+/// Reservations do not distinguish the data they account for
+impl PartialEq for MemoryReservation {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for MemoryReservation {}
+
+impl Default for MemoryReservation {
+    fn default() -> Self {
+        MEMORY_ACCOUNTANT.reserve(0)
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.accountant
+            .allocated_bytes
+            .fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_accounts_reservations() {
+        static ACCOUNTANT: MemoryAccountant = MemoryAccountant::new();
+
+        assert!(ACCOUNTANT.pressure().abs() < f64::EPSILON);
+
+        ACCOUNTANT.set_budget_bytes(Some(100));
+
+        let reservation = ACCOUNTANT.reserve(50);
+        let other_reservation = ACCOUNTANT.reserve(25);
+
+        assert_eq!(ACCOUNTANT.allocated_bytes(), 75);
+        assert!((ACCOUNTANT.pressure() - 0.75).abs() < f64::EPSILON);
+
+        drop(reservation);
+        assert_eq!(ACCOUNTANT.allocated_bytes(), 25);
+
+        drop(other_reservation);
+        assert_eq!(ACCOUNTANT.allocated_bytes(), 0);
+
+        ACCOUNTANT.set_budget_bytes(None);
+        assert_eq!(ACCOUNTANT.budget_bytes(), None);
+    }
+}
@@ -3,6 +3,7 @@ pub mod arrow;
 pub mod gdal;
 pub mod helpers;
 pub mod identifiers;
+pub mod memory;
 pub mod ranges;
 mod result;
 pub mod well_known_data;
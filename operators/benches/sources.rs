@@ -14,7 +14,7 @@ use geoengine_operators::{
     engine::{ChunkByteSize, MockQueryContext, QueryContext, RasterQueryProcessor},
     mock::MockRasterSourceProcessor,
     source::{GdalMetaDataRegular, GdalSourceProcessor},
-    util::gdal::create_ndvi_meta_data,
+    util::{create_rayon_thread_pool, gdal::create_ndvi_meta_data},
 };
 
 fn setup_gdal_source(
@@ -24,6 +24,7 @@ fn setup_gdal_source(
     GdalSourceProcessor::<u8> {
         tiling_specification,
         meta_data: Box::new(meta_data),
+        io_thread_pool: create_rayon_thread_pool(0),
         _phantom_data: PhantomData,
     }
 }
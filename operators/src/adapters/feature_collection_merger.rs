@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::util::Result;
 use futures::ready;
 use futures::stream::FusedStream;
@@ -5,9 +6,11 @@ use futures::Stream;
 use geoengine_datatypes::collections::{
     FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
 };
-use geoengine_datatypes::primitives::Geometry;
+use geoengine_datatypes::primitives::{FeatureData, FeatureDataRef, FeatureDataType, Geometry};
 use geoengine_datatypes::util::arrow::ArrowTyped;
 use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
@@ -25,6 +28,22 @@ where
     stream: St,
     accum: Option<FeatureCollection<G>>,
     chunk_size_bytes: usize,
+    schema_merging: SchemaMerging,
+    /// The union of all columns seen so far if the schemas are merged
+    columns: HashMap<String, FeatureDataType>,
+}
+
+/// How the merger handles collections with different columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SchemaMerging {
+    /// All collections must have the same columns, otherwise the merge fails
+    #[default]
+    Strict,
+    /// The columns of all collections are unified.
+    /// Missing columns are filled with nulls and columns of different types are widened,
+    /// i.e., integers to floats and all other mixed types to text.
+    Union,
 }
 
 impl<St, G> FeatureCollectionChunkMerger<St, G>
@@ -37,12 +56,22 @@ where
             stream,
             accum: None,
             chunk_size_bytes,
+            schema_merging: SchemaMerging::Strict,
+            columns: HashMap::new(),
         }
     }
 
+    #[must_use]
+    pub fn with_schema_merging(mut self, schema_merging: SchemaMerging) -> Self {
+        self.schema_merging = schema_merging;
+        self
+    }
+
     fn merge_and_proceed(
         accum: &mut Option<FeatureCollection<G>>,
         chunk_size_bytes: usize,
+        schema_merging: SchemaMerging,
+        columns: &mut HashMap<String, FeatureDataType>,
         new_collection: St::Item,
     ) -> Option<Poll<Option<St::Item>>> {
         if new_collection.is_err() {
@@ -52,11 +81,18 @@ where
 
         let new_collection = new_collection.expect("checked");
 
-        let merged_collection = if let Some(old_collection) = accum.take() {
+        let new_collection = match schema_merging {
+            SchemaMerging::Strict => Ok(new_collection),
+            SchemaMerging::Union => Self::unify_columns(accum, columns, &new_collection),
+        };
+
+        let merged_collection = match (accum.take(), new_collection) {
             // TODO: execute on separate thread?
-            old_collection.append(&new_collection)
-        } else {
-            Ok(new_collection)
+            (Some(old_collection), Ok(new_collection)) => {
+                old_collection.append(&new_collection).map_err(Into::into)
+            }
+            (None, new_collection) => new_collection,
+            (Some(_), Err(error)) => Err(error),
         };
 
         match merged_collection {
@@ -69,10 +105,30 @@ where
                 *accum = Some(collection);
                 None
             }
-            Err(error) => Some(Poll::Ready(Some(Err(error.into())))),
+            Err(error) => Some(Poll::Ready(Some(Err(error)))),
         }
     }
 
+    /// Adds the columns of `new_collection` to `columns` and conforms the accumulated and the new collection to them
+    fn unify_columns(
+        accum: &mut Option<FeatureCollection<G>>,
+        columns: &mut HashMap<String, FeatureDataType>,
+        new_collection: &FeatureCollection<G>,
+    ) -> Result<FeatureCollection<G>> {
+        for (name, data_type) in new_collection.column_types() {
+            columns
+                .entry(name)
+                .and_modify(|column_type| *column_type = column_type.widen(data_type))
+                .or_insert(data_type);
+        }
+
+        if let Some(old_collection) = accum.as_mut() {
+            *old_collection = conform_columns(old_collection, columns)?;
+        }
+
+        conform_columns(new_collection, columns)
+    }
+
     fn output_remaining_chunk(accum: &mut Option<FeatureCollection<G>>) -> Poll<Option<St::Item>> {
         match accum.take() {
             Some(last_chunk) if !last_chunk.is_empty() => Poll::Ready(Some(Ok(last_chunk))),
@@ -81,6 +137,73 @@ where
     }
 }
 
+/// Adds missing `columns` as nulls and converts columns to their type in `columns`
+fn conform_columns<G>(
+    collection: &FeatureCollection<G>,
+    columns: &HashMap<String, FeatureDataType>,
+) -> Result<FeatureCollection<G>>
+where
+    G: Geometry + ArrowTyped,
+{
+    let column_types = collection.column_types();
+
+    if &column_types == columns {
+        return Ok(collection.clone());
+    }
+
+    let mut converted_columns = Vec::new();
+    let mut new_columns = Vec::new();
+
+    for (name, &data_type) in columns {
+        match column_types.get(name) {
+            Some(&column_type) if column_type == data_type => {}
+            Some(&column_type) => {
+                converted_columns.push(name.as_str());
+                new_columns.push((
+                    name.as_str(),
+                    convert_data(&collection.data(name)?, column_type, data_type)?,
+                ));
+            }
+            None => new_columns.push((name.as_str(), null_data(data_type, collection.len()))),
+        }
+    }
+
+    Ok(collection
+        .remove_columns(&converted_columns)?
+        .add_columns(&new_columns)?)
+}
+
+/// Converts data to a wider type, i.e., integers to floats or anything to text
+fn convert_data(
+    data: &FeatureDataRef,
+    from: FeatureDataType,
+    to: FeatureDataType,
+) -> Result<FeatureData> {
+    Ok(match (from, to) {
+        (FeatureDataType::Int, FeatureDataType::Float) => {
+            FeatureData::NullableFloat(data.float_options_iter().collect())
+        }
+        (_, FeatureDataType::Text) => FeatureData::NullableText(
+            data.strings_iter()
+                .zip(data.nulls())
+                .map(|(value, is_null)| (!is_null).then_some(value))
+                .collect(),
+        ),
+        (left, right) => return Err(Error::ColumnTypeMismatch { left, right }),
+    })
+}
+
+fn null_data(data_type: FeatureDataType, len: usize) -> FeatureData {
+    match data_type {
+        FeatureDataType::Category => FeatureData::NullableCategory(vec![None; len]),
+        FeatureDataType::Int => FeatureData::NullableInt(vec![None; len]),
+        FeatureDataType::Float => FeatureData::NullableFloat(vec![None; len]),
+        FeatureDataType::Text => FeatureData::NullableText(vec![None; len]),
+        FeatureDataType::Bool => FeatureData::NullableBool(vec![None; len]),
+        FeatureDataType::DateTime => FeatureData::NullableDateTime(vec![None; len]),
+    }
+}
+
 impl<St, G> Stream for FeatureCollectionChunkMerger<St, G>
 where
     St: Stream<Item = Result<FeatureCollection<G>>> + FusedStream,
@@ -93,6 +216,8 @@ where
             mut stream,
             accum,
             chunk_size_bytes,
+            schema_merging,
+            columns,
         } = self.as_mut().project();
 
         let mut output: Option<Poll<Option<St::Item>>> = None;
@@ -105,7 +230,13 @@ where
             let next = ready!(stream.as_mut().poll_next(cx));
 
             output = if let Some(collection) = next {
-                Self::merge_and_proceed(accum, *chunk_size_bytes, collection)
+                Self::merge_and_proceed(
+                    accum,
+                    *chunk_size_bytes,
+                    *schema_merging,
+                    columns,
+                    collection,
+                )
             } else {
                 Some(Self::output_remaining_chunk(accum))
             }
@@ -305,6 +436,83 @@ mod tests {
         );
     }
 
+    fn points_with_columns(columns: &[(&str, FeatureData)]) -> Result<MultiPointCollection> {
+        Ok(MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap()],
+            columns
+                .iter()
+                .map(|(name, data)| (name.to_string(), data.clone()))
+                .collect(),
+        )?)
+    }
+
+    #[tokio::test]
+    async fn union_of_schemas() {
+        let source = futures::stream::iter(vec![
+            points_with_columns(&[
+                ("a", FeatureData::Int(vec![1])),
+                ("c", FeatureData::Int(vec![3])),
+            ]),
+            points_with_columns(&[
+                ("a", FeatureData::Float(vec![2.5])),
+                ("b", FeatureData::Text(vec!["x".to_string()])),
+                ("c", FeatureData::Bool(vec![true])),
+            ]),
+        ]);
+
+        let merged_collections = FeatureCollectionChunkMerger::new(source.fuse(), usize::MAX)
+            .with_schema_merging(SchemaMerging::Union)
+            .collect::<Vec<Result<MultiPointCollection>>>()
+            .await;
+
+        assert_eq!(merged_collections.len(), 1);
+        let collection = merged_collections[0].as_ref().unwrap();
+
+        assert_eq!(
+            collection.column_types(),
+            [
+                ("a".to_string(), FeatureDataType::Float),
+                ("b".to_string(), FeatureDataType::Text),
+                ("c".to_string(), FeatureDataType::Text),
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert_eq!(
+            collection
+                .data("a")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.), Some(2.5)]
+        );
+        assert_eq!(collection.data("b").unwrap().nulls(), vec![true, false]);
+        assert_eq!(
+            collection
+                .data("c")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["3".to_string(), "true".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn strict_schemas() {
+        let source = futures::stream::iter(vec![
+            points_with_columns(&[("a", FeatureData::Int(vec![1]))]),
+            points_with_columns(&[("a", FeatureData::Float(vec![2.5]))]),
+        ]);
+
+        let merged_collections = FeatureCollectionChunkMerger::new(source.fuse(), usize::MAX)
+            .collect::<Vec<Result<MultiPointCollection>>>()
+            .await;
+
+        assert_eq!(merged_collections.len(), 1);
+        assert!(merged_collections[0].is_err());
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn interleaving_pendings() {
         let mut stream_history: Vec<Poll<Option<Result<MultiPointCollection>>>> = vec![
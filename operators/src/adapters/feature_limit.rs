@@ -0,0 +1,183 @@
+use crate::util::Result;
+use futures::ready;
+use futures::stream::FusedStream;
+use futures::Stream;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::Geometry;
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Ends a stream of `FeatureCollection`s after `max_features` features.
+///
+/// The collection that exceeds the limit is cut and the stream is not polled afterwards.
+/// If there were more features than the limit, the stream is marked as truncated.
+#[pin_project(project = FeatureLimitProjection)]
+pub struct FeatureLimit<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped,
+{
+    #[pin]
+    stream: St,
+    remaining_features: usize,
+    truncated: TruncationFlag,
+    terminated: bool,
+}
+
+/// Tells whether a stream with a [`FeatureLimit`] had more features than the limit
+#[derive(Debug, Clone, Default)]
+pub struct TruncationFlag(Arc<AtomicBool>);
+
+impl TruncationFlag {
+    pub fn is_truncated(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<St, G> FeatureLimit<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped,
+{
+    pub fn new(stream: St, max_features: usize) -> Self {
+        Self {
+            stream,
+            remaining_features: max_features,
+            truncated: TruncationFlag::default(),
+            terminated: false,
+        }
+    }
+
+    /// A flag that is set once the stream was truncated
+    pub fn truncation_flag(&self) -> TruncationFlag {
+        self.truncated.clone()
+    }
+}
+
+impl<St, G> Stream for FeatureLimit<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped,
+{
+    type Item = Result<FeatureCollection<G>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let FeatureLimitProjection {
+            stream,
+            remaining_features,
+            truncated,
+            terminated,
+        } = self.project();
+
+        if *terminated {
+            return Poll::Ready(None);
+        }
+
+        let collection = match ready!(stream.poll_next(cx)) {
+            Some(Ok(collection)) => collection,
+            Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+            None => {
+                *terminated = true;
+                return Poll::Ready(None);
+            }
+        };
+
+        if collection.len() <= *remaining_features {
+            *remaining_features -= collection.len();
+            return Poll::Ready(Some(Ok(collection)));
+        }
+
+        // the limit is exceeded, so the remaining features are cut off and the query is stopped
+        truncated.set();
+        *terminated = true;
+
+        if *remaining_features == 0 {
+            return Poll::Ready(None);
+        }
+
+        let indices: Vec<usize> = (0..*remaining_features).collect();
+        *remaining_features = 0;
+
+        Poll::Ready(Some(collection.take(&indices).map_err(Into::into)))
+    }
+}
+
+impl<St, G> FusedStream for FeatureLimit<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{StreamExt, TryStreamExt};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, TimeInterval};
+
+    fn points(n: usize) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many((0..n).map(|i| (i as f64, 0.)).collect::<Vec<_>>()).unwrap(),
+            vec![TimeInterval::default(); n],
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    async fn limit(sizes: &[usize], max_features: usize) -> (Vec<usize>, bool, usize) {
+        let polled = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let stream = {
+            let polled = polled.clone();
+            futures::stream::iter(sizes.to_vec()).map(move |n| {
+                polled.fetch_add(1, Ordering::Relaxed);
+                Ok(points(n))
+            })
+        };
+
+        let limited = FeatureLimit::new(stream, max_features);
+        let truncated = limited.truncation_flag();
+
+        let lengths = limited
+            .map_ok(|collection| collection.len())
+            .try_collect()
+            .await
+            .unwrap();
+
+        (
+            lengths,
+            truncated.is_truncated(),
+            polled.load(Ordering::Relaxed),
+        )
+    }
+
+    #[tokio::test]
+    async fn it_cuts_the_exceeding_collection() {
+        assert_eq!(limit(&[3, 3, 3], 5).await, (vec![3, 2], true, 2));
+    }
+
+    #[tokio::test]
+    async fn it_is_not_truncated_below_the_limit() {
+        assert_eq!(limit(&[3, 3], 6).await, (vec![3, 3], false, 2));
+        assert_eq!(limit(&[3, 0, 3], 10).await, (vec![3, 0, 3], false, 3));
+    }
+
+    #[tokio::test]
+    async fn it_stops_after_the_limit() {
+        assert_eq!(limit(&[3, 3, 3, 3], 3).await, (vec![3], true, 2));
+        assert_eq!(limit(&[3], 0).await, (vec![], true, 1));
+    }
+}
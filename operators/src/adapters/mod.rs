@@ -1,10 +1,12 @@
 mod feature_collection_merger;
+mod feature_limit;
 mod raster_subquery;
 mod raster_time;
 mod raster_time_substream;
 mod sparse_tiles_fill_adapter;
 
-pub use feature_collection_merger::FeatureCollectionChunkMerger;
+pub use feature_collection_merger::{FeatureCollectionChunkMerger, SchemaMerging};
+pub use feature_limit::{FeatureLimit, TruncationFlag};
 pub use raster_subquery::{
     fold_by_coordinate_lookup_future, FoldTileAccu, FoldTileAccuMut, RasterSubQueryAdapter,
     SubQueryTileAggregator, TileReprojectionSubQuery,
@@ -70,6 +72,14 @@ where
     {
         FeatureCollectionChunkMerger::new(self.fuse(), chunk_size_bytes)
     }
+
+    /// Ends the `Stream` after `max_features` features and cuts the collection that exceeds the limit.
+    fn limit_features(self, max_features: usize) -> FeatureLimit<Self, CollectionType>
+    where
+        Self: Sized,
+    {
+        FeatureLimit::new(self, max_features)
+    }
 }
 
 impl<T: ?Sized, CollectionType: Geometry + ArrowTyped + 'static>
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::operations::image::Colorizer;
+use serde::{Deserialize, Serialize};
+
+use super::{OperatorName, ValueStatistics};
+use crate::source::{GdalSource, OgrSource};
+use crate::util::Result;
+
+/// Optional dataset-level metadata that operators can use to derive defaults for parameters,
+/// e.g., the bounds of a histogram or the colorizer of a raster.
+/// It is descriptive only, so operators must not rely on its presence.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuxiliaryMetadata {
+    /// The metadata of the values of a raster dataset
+    #[serde(default)]
+    pub band: Option<ValueMetadata>,
+    /// The metadata of the values of the columns of a vector dataset
+    #[serde(default)]
+    pub columns: HashMap<String, ValueMetadata>,
+    #[serde(default)]
+    pub default_colorizer: Option<Colorizer>,
+}
+
+impl AuxiliaryMetadata {
+    /// Returns the value metadata of the given column or of the raster band if `column` is `None`
+    pub fn value_metadata(&self, column: Option<&str>) -> Option<&ValueMetadata> {
+        match column {
+            Some(column) => self.columns.get(column),
+            None => self.band.as_ref(),
+        }
+    }
+}
+
+/// Metadata of the values of a raster band or a column
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueMetadata {
+    /// The statistics of all values of the dataset
+    #[serde(default)]
+    pub statistics: Option<ValueStatistics>,
+    /// The labels of the classes of a classification, e.g., a land cover map
+    #[serde(default)]
+    pub classes: Option<HashMap<u8, String>>,
+}
+
+#[async_trait]
+pub trait AuxiliaryMetadataProvider {
+    /// Returns the auxiliary metadata of a dataset or `None` if there is none
+    async fn auxiliary_metadata(&self, id: &DataId) -> Result<Option<AuxiliaryMetadata>>;
+}
+
+/// Returns the data that a source operator loads, e.g., of a `GdalSource`, or `None` for all other operators.
+/// Only the statistics of the data of a source operator describe the operator output directly.
+pub fn source_data_id<O: Serialize>(operator: &O) -> Option<DataId> {
+    let operator = serde_json::to_value(operator).ok()?;
+
+    let type_name = operator.get("type")?.as_str()?;
+    if type_name != GdalSource::TYPE_NAME && type_name != OgrSource::TYPE_NAME {
+        return None;
+    }
+
+    serde_json::from_value(operator.get("params")?.get("data")?.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{RasterOperator, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::source::{GdalSourceParameters, OgrSourceParameters};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::dataset::DatasetId;
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_extracts_the_data_of_source_operators() {
+        let data: DataId = DatasetId::new().into();
+
+        let gdal_source = GdalSource {
+            params: GdalSourceParameters { data: data.clone() },
+        }
+        .boxed();
+        assert_eq!(source_data_id(&gdal_source), Some(data.clone()));
+
+        let ogr_source = OgrSource {
+            params: OgrSourceParameters {
+                data: data.clone(),
+                attribute_projection: None,
+                attribute_filters: None,
+                schema_merging: None,
+            },
+        }
+        .boxed();
+        assert_eq!(source_data_id(&ogr_source), Some(data));
+
+        let mock_source =
+            MockFeatureCollectionSource::single(MultiPointCollection::empty()).boxed();
+        assert_eq!(source_data_id(&mock_source), None);
+    }
+}
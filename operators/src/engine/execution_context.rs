@@ -1,7 +1,8 @@
-use super::query::QueryAbortRegistration;
+use super::query::{QueryAbortRegistration, QueryStarted};
 use super::{
-    CreateSpan, InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator,
-    MockQueryContext,
+    AuxiliaryMetadata, AuxiliaryMetadataProvider, CreateSpan, InitializedPlotOperator,
+    InitializedRasterOperator, InitializedVectorOperator, MockQueryContext, OperatorPolicy,
+    PathSandbox, QueryMessages, StatisticsCache, TypedOperator,
 };
 use crate::engine::{
     ChunkByteSize, RasterResultDescriptor, ResultDescriptor, VectorResultDescriptor,
@@ -22,15 +23,20 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use uuid::Uuid;
 
 /// A context that provides certain utility access during operator initialization
 pub trait ExecutionContext: Send
     + Sync
+    + AuxiliaryMetadataProvider
+    + WorkflowProvider
     + MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
     + MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
     + MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
 {
     fn thread_pool(&self) -> &Arc<ThreadPool>;
+    /// A separate pool for blocking IO, e.g., GDAL and OGR reads, so that it does not starve computations
+    fn io_thread_pool(&self) -> &Arc<ThreadPool>;
     fn tiling_specification(&self) -> TilingSpecification;
 
     fn wrap_initialized_raster_operator(
@@ -50,6 +56,22 @@ pub trait ExecutionContext: Send
         op: Box<dyn InitializedPlotOperator>,
         span: CreateSpan,
     ) -> Box<dyn InitializedPlotOperator>;
+
+    /// A cache for statistics of workflow results that operators can use to skip preprocessing passes
+    fn statistics_cache(&self) -> Option<Arc<dyn StatisticsCache>>;
+
+    /// Fails if the operator with the given type name must not be used in this context
+    fn ensure_operator_is_allowed(&self, operator: &str) -> Result<()>;
+
+    /// The files that operators may read in this context
+    fn path_sandbox(&self) -> &PathSandbox;
+}
+
+/// Resolves registered workflows, so that operators can embed them by their id
+#[async_trait]
+pub trait WorkflowProvider {
+    /// Returns the operator of the registered workflow with the given `id`
+    async fn workflow(&self, id: &Uuid) -> Result<TypedOperator>;
 }
 
 #[async_trait]
@@ -82,26 +104,46 @@ where
 
 pub struct MockExecutionContext {
     pub thread_pool: Arc<ThreadPool>,
+    pub io_thread_pool: Arc<ThreadPool>,
     pub meta_data: HashMap<DataId, Box<dyn Any + Send + Sync>>,
     pub tiling_specification: TilingSpecification,
+    pub statistics_cache: Option<Arc<dyn StatisticsCache>>,
+    pub auxiliary_metadata: HashMap<DataId, AuxiliaryMetadata>,
+    pub workflows: HashMap<Uuid, TypedOperator>,
+    pub operator_policy: OperatorPolicy,
+    pub path_sandbox: PathSandbox,
 }
 
 impl TestDefault for MockExecutionContext {
     fn test_default() -> Self {
+        let thread_pool = create_rayon_thread_pool(0);
         Self {
-            thread_pool: create_rayon_thread_pool(0),
+            io_thread_pool: thread_pool.clone(),
+            thread_pool,
             meta_data: HashMap::default(),
             tiling_specification: TilingSpecification::test_default(),
+            statistics_cache: None,
+            auxiliary_metadata: HashMap::default(),
+            workflows: HashMap::default(),
+            operator_policy: OperatorPolicy::default(),
+            path_sandbox: PathSandbox::default(),
         }
     }
 }
 
 impl MockExecutionContext {
     pub fn new_with_tiling_spec(tiling_specification: TilingSpecification) -> Self {
+        let thread_pool = create_rayon_thread_pool(0);
         Self {
-            thread_pool: create_rayon_thread_pool(0),
+            io_thread_pool: thread_pool.clone(),
+            thread_pool,
             meta_data: HashMap::default(),
             tiling_specification,
+            statistics_cache: None,
+            auxiliary_metadata: HashMap::default(),
+            workflows: HashMap::default(),
+            operator_policy: OperatorPolicy::default(),
+            path_sandbox: PathSandbox::default(),
         }
     }
 
@@ -109,10 +151,17 @@ impl MockExecutionContext {
         tiling_specification: TilingSpecification,
         num_threads: usize,
     ) -> Self {
+        let thread_pool = create_rayon_thread_pool(num_threads);
         Self {
-            thread_pool: create_rayon_thread_pool(num_threads),
+            io_thread_pool: thread_pool.clone(),
+            thread_pool,
             meta_data: HashMap::default(),
             tiling_specification,
+            statistics_cache: None,
+            auxiliary_metadata: HashMap::default(),
+            workflows: HashMap::default(),
+            operator_policy: OperatorPolicy::default(),
+            path_sandbox: PathSandbox::default(),
         }
     }
 
@@ -126,6 +175,14 @@ impl MockExecutionContext {
             .insert(data, Box::new(meta_data) as Box<dyn Any + Send + Sync>);
     }
 
+    pub fn add_auxiliary_metadata(&mut self, data: DataId, auxiliary_metadata: AuxiliaryMetadata) {
+        self.auxiliary_metadata.insert(data, auxiliary_metadata);
+    }
+
+    pub fn add_workflow(&mut self, id: Uuid, operator: TypedOperator) {
+        self.workflows.insert(id, operator);
+    }
+
     pub fn mock_query_context(&self, chunk_byte_size: ChunkByteSize) -> MockQueryContext {
         let (abort_registration, abort_trigger) = QueryAbortRegistration::new();
         MockQueryContext {
@@ -133,6 +190,8 @@ impl MockExecutionContext {
             thread_pool: self.thread_pool.clone(),
             abort_registration,
             abort_trigger: Some(abort_trigger),
+            messages: QueryMessages::default(),
+            query_started: QueryStarted::default(),
         }
     }
 }
@@ -142,6 +201,10 @@ impl ExecutionContext for MockExecutionContext {
         &self.thread_pool
     }
 
+    fn io_thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.io_thread_pool
+    }
+
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
@@ -169,6 +232,38 @@ impl ExecutionContext for MockExecutionContext {
     ) -> Box<dyn InitializedPlotOperator> {
         op
     }
+
+    fn statistics_cache(&self) -> Option<Arc<dyn StatisticsCache>> {
+        self.statistics_cache.clone()
+    }
+
+    fn ensure_operator_is_allowed(&self, operator: &str) -> Result<()> {
+        self.operator_policy.ensure_is_allowed(operator)
+    }
+
+    fn path_sandbox(&self) -> &PathSandbox {
+        &self.path_sandbox
+    }
+}
+
+#[async_trait]
+impl AuxiliaryMetadataProvider for MockExecutionContext {
+    async fn auxiliary_metadata(&self, id: &DataId) -> Result<Option<AuxiliaryMetadata>> {
+        Ok(self.auxiliary_metadata.get(id).cloned())
+    }
+}
+
+#[async_trait]
+impl WorkflowProvider for MockExecutionContext {
+    async fn workflow(&self, id: &Uuid) -> Result<TypedOperator> {
+        self.workflows
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::WorkflowReference {
+                workflow: *id,
+                source: "the workflow is not registered".into(),
+            })
+    }
 }
 
 #[async_trait]
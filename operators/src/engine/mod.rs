@@ -1,9 +1,13 @@
+pub use auxiliary_metadata::{
+    source_data_id, AuxiliaryMetadata, AuxiliaryMetadataProvider, ValueMetadata,
+};
 pub use clonable_operator::{
     CloneableInitializedRasterOperator, CloneableInitializedVectorOperator, CloneablePlotOperator,
     CloneableRasterOperator, CloneableVectorOperator,
 };
 pub use execution_context::{
     ExecutionContext, MetaData, MetaDataProvider, MockExecutionContext, StaticMetaData,
+    WorkflowProvider,
 };
 pub use operator::{
     InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator, OperatorData,
@@ -14,8 +18,12 @@ pub use operator_impl::{
     SingleRasterOrVectorSource, SingleRasterSource, SingleVectorMultipleRasterSources,
     SingleVectorSource, SourceOperator,
 };
+pub use operator_policy::OperatorPolicy;
+pub use path_sandbox::{PathSandbox, SandboxedLoadingInfo};
 pub use query::{
-    ChunkByteSize, MockQueryContext, QueryAbortRegistration, QueryAbortTrigger, QueryContext,
+    ChunkByteSize, EnrichedQueryStream, MockQueryContext, QueryAbortRegistration,
+    QueryAbortTrigger, QueryContext, QueryMessage, QueryMessageReceiver, QueryMessages,
+    QueryMetadata, QueryProgress, QueryStarted, QueryStreamItem,
 };
 pub use query_processor::{
     BoxRasterQueryProcessor, PlotQueryProcessor, QueryProcessor, RasterQueryProcessor,
@@ -26,16 +34,23 @@ pub use result_descriptor::{
     PlotResultDescriptor, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
     VectorColumnInfo, VectorResultDescriptor,
 };
+pub use statistics_cache::{
+    InMemoryStatisticsCache, StatisticsCache, StatisticsKey, ValueStatistics,
+};
 use tracing::Span;
 
+mod auxiliary_metadata;
 mod clonable_operator;
 mod execution_context;
 mod operator;
 mod operator_impl;
+mod operator_policy;
+mod path_sandbox;
 mod query;
 #[macro_use]
 mod query_processor;
 mod result_descriptor;
+mod statistics_cache;
 
 #[macro_export]
 macro_rules! call_generic_raster_processor {
@@ -43,6 +43,8 @@ pub trait RasterOperator:
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedRasterOperator>> {
+        context.ensure_operator_is_allowed(self.typetag_name())?;
+
         let span = self.span();
         let op = self._initialize(context).await?;
         Ok(context.wrap_initialized_raster_operator(op, span))
@@ -74,6 +76,8 @@ pub trait VectorOperator:
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
+        context.ensure_operator_is_allowed(self.typetag_name())?;
+
         let span = self.span();
         let op = self._initialize(context).await?;
         Ok(context.wrap_initialized_vector_operator(op, span))
@@ -105,6 +109,8 @@ pub trait PlotOperator:
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedPlotOperator>> {
+        context.ensure_operator_is_allowed(self.typetag_name())?;
+
         let span = self.span();
         let op = self._initialize(context).await?;
         Ok(context.wrap_initialized_plot_operator(op, span))
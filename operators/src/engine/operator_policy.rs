@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::util::Result;
+
+/// Restricts the operators that workflows may use, e.g., to disable expensive or
+/// filesystem-touching operators on public-facing instances.
+///
+/// Operators are given by their type names, e.g., `OgrSource`.
+/// An empty allowlist allows all operators. The denylist takes precedence over the allowlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct OperatorPolicy {
+    #[serde(default)]
+    pub allowlist: HashSet<String>,
+    #[serde(default)]
+    pub denylist: HashSet<String>,
+}
+
+impl OperatorPolicy {
+    pub fn is_allowed(&self, operator: &str) -> bool {
+        (self.allowlist.is_empty() || self.allowlist.contains(operator))
+            && !self.denylist.contains(operator)
+    }
+
+    pub fn ensure_is_allowed(&self, operator: &str) -> Result<()> {
+        if self.is_allowed(operator) {
+            Ok(())
+        } else {
+            Err(Error::OperatorNotAllowed {
+                operator: operator.to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, VectorOperator};
+    use crate::mock::{MockPointSource, MockPointSourceParams};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn it_allows_and_denies_operators() {
+        assert!(OperatorPolicy::default().is_allowed("OgrSource"));
+
+        let policy = OperatorPolicy {
+            allowlist: HashSet::new(),
+            denylist: ["OgrSource".to_string()].into(),
+        };
+        assert!(!policy.is_allowed("OgrSource"));
+        assert!(policy.is_allowed("GdalSource"));
+
+        let policy = OperatorPolicy {
+            allowlist: ["GdalSource".to_string(), "Expression".to_string()].into(),
+            denylist: ["Expression".to_string()].into(),
+        };
+        assert!(policy.is_allowed("GdalSource"));
+        assert!(!policy.is_allowed("Expression"));
+        assert!(!policy.is_allowed("OgrSource"));
+
+        assert!(matches!(
+            policy.ensure_is_allowed("OgrSource"),
+            Err(Error::OperatorNotAllowed { operator }) if operator == "OgrSource"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_denies_operators_during_initialization() {
+        let mut execution_context = MockExecutionContext::test_default();
+        execution_context.operator_policy = OperatorPolicy {
+            allowlist: HashSet::new(),
+            denylist: ["MockPointSource".to_string()].into(),
+        };
+
+        let result = MockPointSource {
+            params: MockPointSourceParams { points: vec![] },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await;
+
+        assert!(matches!(result, Err(Error::OperatorNotAllowed { .. })));
+    }
+}
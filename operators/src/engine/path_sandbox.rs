@@ -0,0 +1,312 @@
+use std::fmt::Debug;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use snafu::{ensure, OptionExt};
+
+use crate::engine::{MetaData, ResultDescriptor};
+use crate::error;
+use crate::util::Result;
+
+/// GDAL's virtual file systems that read from archives, whose paths must be inside the sandbox as well
+const ARCHIVE_VSI_SCHEMES: [&str; 5] = ["vsizip", "vsitar", "vsigzip", "vsi7z", "vsirar"];
+
+/// Restricts the files that operators may read, e.g., the files of GDAL and OGR datasets,
+/// so that workflows cannot access arbitrary files of the server.
+///
+/// Paths must be inside one of the `allowed_roots` after resolving symbolic links and `..`.
+/// Paths of GDAL's virtual file systems, e.g., `/vsicurl/`, and connection strings, e.g., `PG:`,
+/// must use one of the `allowed_schemes`. The archives of `/vsizip/` etc. must be inside of the roots.
+/// A sandbox without roots allows all paths.
+///
+/// Operators that take file paths as parameters, e.g., the `GeoJsonSource`, resolve them against the `data_root`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PathSandbox {
+    #[serde(default)]
+    pub allowed_roots: Vec<PathBuf>,
+    /// The virtual file systems without slashes, e.g., `vsizip`, and the prefixes of connection strings without the colon, e.g., `PG`
+    #[serde(default)]
+    pub allowed_schemes: Vec<String>,
+    /// The directory that the file paths of operator parameters are relative to, if they are supported at all
+    #[serde(default)]
+    pub data_root: Option<PathBuf>,
+}
+
+impl PathSandbox {
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed_roots.is_empty()
+    }
+
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        if self.is_unrestricted() {
+            return true;
+        }
+
+        match scheme_path(path) {
+            Some((scheme, inner_path)) => {
+                self.allowed_schemes.iter().any(|s| s == scheme)
+                    && (!ARCHIVE_VSI_SCHEMES.contains(&scheme) || self.is_allowed(inner_path))
+            }
+            None => self.is_inside_roots(path),
+        }
+    }
+
+    pub fn ensure_is_allowed(&self, path: &Path) -> Result<()> {
+        ensure!(
+            self.is_allowed(path),
+            error::PathNotAllowed {
+                path: path.to_string_lossy().to_string(),
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Resolves the file path of an operator parameter against the `data_root`.
+    ///
+    /// The path must be relative and must stay inside of the data root, i.e., must not contain `..` or symbolic links that leave it.
+    pub fn resolve_data_path(&self, path: &Path) -> Result<PathBuf> {
+        let data_root = self.data_root.as_ref().context(error::NoDataRoot)?;
+
+        let not_allowed = || error::PathNotAllowed {
+            path: path.to_string_lossy().to_string(),
+        };
+
+        ensure!(
+            path.components()
+                .all(|component| matches!(component, Component::Normal(_))),
+            not_allowed()
+        );
+
+        let data_path = data_root.join(path);
+
+        let is_inside_data_root = resolve(&data_path)
+            .zip(data_root.canonicalize().ok())
+            .map_or(false, |(data_path, data_root)| {
+                data_path.starts_with(data_root)
+            });
+        ensure!(is_inside_data_root, not_allowed());
+
+        Ok(data_path)
+    }
+
+    /// Checks the files of the loading infos of the `meta_data` before they are read
+    pub fn sandbox_meta_data<L, R, Q>(
+        &self,
+        meta_data: Box<dyn MetaData<L, R, Q>>,
+    ) -> Box<dyn MetaData<L, R, Q>>
+    where
+        L: SandboxedLoadingInfo + 'static,
+        R: ResultDescriptor + 'static,
+        Q: Send + 'static,
+    {
+        if self.is_unrestricted() {
+            return meta_data;
+        }
+
+        Box::new(SandboxedMetaData {
+            meta_data,
+            sandbox: self.clone(),
+        })
+    }
+
+    fn is_inside_roots(&self, path: &Path) -> bool {
+        let path = if let Some(path) = resolve(path) {
+            path
+        } else {
+            return false;
+        };
+
+        self.allowed_roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .any(|root| path.starts_with(root))
+    }
+}
+
+/// Splits a path of a GDAL virtual file system into its scheme and the remaining path,
+/// or returns the prefix of a connection string, e.g., `PG:dbname=...`, with an empty path
+fn scheme_path(path: &Path) -> Option<(&str, &Path)> {
+    let path = path.to_str()?;
+
+    if let Some(vsi_path) = path.strip_prefix('/').filter(|p| p.starts_with("vsi")) {
+        return Some(match vsi_path.split_once('/') {
+            Some((scheme, inner_path)) => (scheme, Path::new(inner_path)),
+            None => (vsi_path, Path::new("")),
+        });
+    }
+
+    // single letters are drive letters on Windows
+    let (prefix, _) = path.split_once(':')?;
+    (prefix.len() > 1 && prefix.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then_some((prefix, Path::new("")))
+}
+
+/// Resolves symbolic links and `..` of the longest existing ancestor of the path, since files
+/// inside of archives or files that do not exist yet cannot be canonicalized
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+
+    for ancestor in path.ancestors() {
+        if let Ok(canonical_ancestor) = ancestor.canonicalize() {
+            let remainder = path.strip_prefix(ancestor).ok()?;
+
+            if !remainder
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return None;
+            }
+
+            return Some(canonical_ancestor.join(remainder));
+        }
+    }
+
+    None
+}
+
+/// Loading infos that reference files, which are checked against a [`PathSandbox`]
+pub trait SandboxedLoadingInfo: Sized + Send {
+    /// Fails if a file is outside of the sandbox or defers the checks to the reading of the files
+    fn sandboxed(self, sandbox: &PathSandbox) -> Result<Self>;
+}
+
+/// Meta data whose loading infos are checked against a [`PathSandbox`]
+struct SandboxedMetaData<L, R, Q>
+where
+    R: ResultDescriptor,
+{
+    meta_data: Box<dyn MetaData<L, R, Q>>,
+    sandbox: PathSandbox,
+}
+
+impl<L, R, Q> Debug for SandboxedMetaData<L, R, Q>
+where
+    R: ResultDescriptor,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SandboxedMetaData")
+            .field("meta_data", &self.meta_data)
+            .field("sandbox", &self.sandbox)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<L, R, Q> MetaData<L, R, Q> for SandboxedMetaData<L, R, Q>
+where
+    L: SandboxedLoadingInfo + 'static,
+    R: ResultDescriptor + 'static,
+    Q: Send + 'static,
+{
+    async fn loading_info(&self, query: Q) -> Result<L> {
+        self.meta_data
+            .loading_info(query)
+            .await?
+            .sandboxed(&self.sandbox)
+    }
+
+    async fn result_descriptor(&self) -> Result<R> {
+        self.meta_data.result_descriptor().await
+    }
+
+    fn box_clone(&self) -> Box<dyn MetaData<L, R, Q>> {
+        Box::new(Self {
+            meta_data: self.meta_data.clone(),
+            sandbox: self.sandbox.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn it_restricts_paths_to_the_roots() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("raster.tiff"), b"").unwrap();
+        std::fs::write(directory.path().join("secret.txt"), b"").unwrap();
+
+        assert!(PathSandbox::default().is_allowed(&directory.path().join("secret.txt")));
+
+        let sandbox = PathSandbox {
+            allowed_roots: vec![root.clone()],
+            allowed_schemes: vec!["vsizip".to_string(), "PG".to_string()],
+            data_root: None,
+        };
+
+        assert!(sandbox.is_allowed(&root.join("raster.tiff")));
+        assert!(sandbox.is_allowed(&root.join("not_yet_existing/raster.tiff")));
+        assert!(!sandbox.is_allowed(&directory.path().join("secret.txt")));
+        assert!(!sandbox.is_allowed(&root.join("../secret.txt")));
+        assert!(!sandbox.is_allowed(&root.join("not_yet_existing/../../secret.txt")));
+
+        assert!(sandbox.is_allowed(Path::new(&format!(
+            "/vsizip/{}/archive.zip/raster.tiff",
+            root.display()
+        ))));
+        assert!(!sandbox.is_allowed(Path::new(&format!(
+            "/vsizip/{}/archive.zip/raster.tiff",
+            directory.path().display()
+        ))));
+        assert!(!sandbox.is_allowed(Path::new("/vsicurl/https://example.com/raster.tiff")));
+        assert!(sandbox.is_allowed(Path::new("PG:dbname=geoengine")));
+        assert!(!sandbox.is_allowed(Path::new("WFS:https://example.com/wfs")));
+
+        assert!(matches!(
+            sandbox.ensure_is_allowed(&directory.path().join("secret.txt")),
+            Err(Error::PathNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn it_resolves_data_paths_inside_of_the_data_root() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().join("data");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("points.json"), b"").unwrap();
+        std::fs::write(directory.path().join("secret.txt"), b"").unwrap();
+
+        assert!(matches!(
+            PathSandbox::default().resolve_data_path(Path::new("points.json")),
+            Err(Error::NoDataRoot)
+        ));
+
+        let sandbox = PathSandbox {
+            data_root: Some(root.clone()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            sandbox.resolve_data_path(Path::new("points.json")).unwrap(),
+            root.join("points.json")
+        );
+        assert!(matches!(
+            sandbox.resolve_data_path(Path::new("../secret.txt")),
+            Err(Error::PathNotAllowed { .. })
+        ));
+        assert!(matches!(
+            sandbox.resolve_data_path(&directory.path().join("secret.txt")),
+            Err(Error::PathNotAllowed { .. })
+        ));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(directory.path(), root.join("link")).unwrap();
+
+            assert!(matches!(
+                sandbox.resolve_data_path(Path::new("link/secret.txt")),
+                Err(Error::PathNotAllowed { .. })
+            ));
+        }
+    }
+}
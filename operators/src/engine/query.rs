@@ -1,12 +1,18 @@
 use std::{
+    collections::HashMap,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 
 use crate::util::Result;
 use crate::{error, util::create_rayon_thread_pool};
-use futures::Stream;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::{ready, Stream};
+use geoengine_datatypes::primitives::FeatureDataType;
 use geoengine_datatypes::util::test::TestDefault;
 use pin_project::pin_project;
 use rayon::ThreadPool;
@@ -28,6 +34,17 @@ impl ChunkByteSize {
     pub fn bytes(self) -> usize {
         self.0
     }
+
+    /// Shrinks the chunk size under memory pressure, i.e., the fraction of the memory budget that is in use.
+    /// Up to a pressure of `0.5`, the size is unchanged. Then, it decreases linearly until it reaches
+    /// an eighth of the size at full pressure.
+    pub fn adapt_to_memory_pressure(self, pressure: f64) -> Self {
+        const MIN_FACTOR: f64 = 1. / 8.;
+
+        let factor = (2. * (1. - pressure)).clamp(MIN_FACTOR, 1.);
+
+        ChunkByteSize((self.0 as f64 * factor) as usize)
+    }
 }
 
 impl From<usize> for ChunkByteSize {
@@ -49,11 +66,32 @@ impl TestDefault for ChunkByteSize {
 }
 
 pub trait QueryContext: Send + Sync {
+    /// The size of vector data chunks. Implementations may adapt it to the current memory pressure.
     fn chunk_byte_size(&self) -> ChunkByteSize;
     fn thread_pool(&self) -> &Arc<ThreadPool>;
 
     fn abort_registration(&self) -> &QueryAbortRegistration;
     fn abort_trigger(&mut self) -> Result<QueryAbortTrigger>;
+
+    /// The side channel for reporting metadata and progress of the query
+    fn messages(&self) -> &QueryMessages;
+    /// Starts listening to the messages of the query. Messages are discarded until someone listens.
+    fn subscribe_messages(&mut self) -> QueryMessageReceiver;
+
+    /// Tracks whether the query was already started, so that checks only apply to the top-level query
+    fn query_started(&self) -> &QueryStarted;
+}
+
+/// Marks the start of a query. Processors query their sources with the same context,
+/// so only the first call to `start` belongs to the top-level query.
+#[derive(Debug, Default)]
+pub struct QueryStarted(AtomicBool);
+
+impl QueryStarted {
+    /// Marks the query as started and returns whether it was already started before
+    pub fn start(&self) -> bool {
+        self.0.swap(true, Ordering::Relaxed)
+    }
 }
 
 /// This type allow wrapping multiple streams with `QueryAbortWrapper`s that
@@ -107,11 +145,171 @@ impl QueryAbortTrigger {
     }
 }
 
+/// Information about a query that processors report besides their results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum QueryMetadata {
+    /// The columns of the results differ from the result descriptor
+    ColumnsChanged {
+        columns: HashMap<String, FeatureDataType>,
+    },
+    /// A problem that did not abort the query, e.g., features that were dropped
+    Warning { message: String },
+}
+
+/// The progress of a query, e.g., the number of processed tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryProgress {
+    pub processed: usize,
+    pub total: Option<usize>,
+}
+
+/// A message of the side channel of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryMessage {
+    Metadata(QueryMetadata),
+    Progress(QueryProgress),
+}
+
+/// An item of a result stream that is enriched with the messages of the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryStreamItem<T> {
+    Data(T),
+    Metadata(QueryMetadata),
+    Progress(QueryProgress),
+}
+
+impl<T> From<QueryMessage> for QueryStreamItem<T> {
+    fn from(message: QueryMessage) -> Self {
+        match message {
+            QueryMessage::Metadata(metadata) => Self::Metadata(metadata),
+            QueryMessage::Progress(progress) => Self::Progress(progress),
+        }
+    }
+}
+
+/// This type allows processors to report metadata and progress of a query to the corresponding
+/// `QueryMessageReceiver`. It can be cloned to report from spawned tasks.
+#[derive(Debug, Clone, Default)]
+pub struct QueryMessages {
+    sender: Option<UnboundedSender<QueryMessage>>,
+}
+
+impl QueryMessages {
+    pub fn new() -> (Self, QueryMessageReceiver) {
+        let (sender, receiver) = mpsc::unbounded();
+
+        (
+            Self {
+                sender: Some(sender),
+            },
+            QueryMessageReceiver { receiver },
+        )
+    }
+
+    pub fn warn<M: Into<String>>(&self, message: M) {
+        self.metadata(QueryMetadata::Warning {
+            message: message.into(),
+        });
+    }
+
+    pub fn metadata(&self, metadata: QueryMetadata) {
+        self.send(QueryMessage::Metadata(metadata));
+    }
+
+    pub fn progress(&self, processed: usize, total: Option<usize>) {
+        self.send(QueryMessage::Progress(QueryProgress { processed, total }));
+    }
+
+    fn send(&self, message: QueryMessage) {
+        if let Some(sender) = &self.sender {
+            // the receiver may have been dropped, so nobody is interested in the message anymore
+            let _ = sender.unbounded_send(message);
+        }
+    }
+}
+
+/// This type receives the messages that processors report using the corresponding `QueryMessages`.
+pub struct QueryMessageReceiver {
+    receiver: UnboundedReceiver<QueryMessage>,
+}
+
+impl QueryMessageReceiver {
+    /// Interleaves the messages with the results of the `stream`.
+    /// Messages are emitted before the next result and the remaining messages after the last result.
+    pub fn enrich<S, T>(self, stream: S) -> EnrichedQueryStream<S>
+    where
+        S: Stream<Item = Result<T>>,
+    {
+        EnrichedQueryStream {
+            stream,
+            receiver: self.receiver,
+            stream_ended: false,
+        }
+    }
+
+    /// Returns all messages that were reported so far
+    pub fn received(&mut self) -> Vec<QueryMessage> {
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) = self.receiver.try_next() {
+            messages.push(message);
+        }
+        messages
+    }
+}
+
+/// A result stream that is enriched with the messages of the query
+#[pin_project(project = EnrichedQueryStreamProjection)]
+pub struct EnrichedQueryStream<S> {
+    #[pin]
+    stream: S,
+    receiver: UnboundedReceiver<QueryMessage>,
+    stream_ended: bool,
+}
+
+impl<S, T> Stream for EnrichedQueryStream<S>
+where
+    S: Stream<Item = Result<T>>,
+{
+    type Item = Result<QueryStreamItem<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        // the senders are kept by the query context, so the channel is not closed when the stream ends
+        if let Ok(Some(message)) = this.receiver.try_next() {
+            return Poll::Ready(Some(Ok(message.into())));
+        }
+
+        if *this.stream_ended {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.stream.poll_next(cx)) {
+            Some(result) => Poll::Ready(Some(result.map(QueryStreamItem::Data))),
+            None => {
+                *this.stream_ended = true;
+
+                Poll::Ready(
+                    this.receiver
+                        .try_next()
+                        .ok()
+                        .flatten()
+                        .map(|message| Ok(message.into())),
+                )
+            }
+        }
+    }
+}
+
 pub struct MockQueryContext {
     pub chunk_byte_size: ChunkByteSize,
     pub thread_pool: Arc<ThreadPool>,
     pub abort_registration: QueryAbortRegistration,
     pub abort_trigger: Option<QueryAbortTrigger>,
+    pub messages: QueryMessages,
+    pub query_started: QueryStarted,
 }
 
 impl TestDefault for MockQueryContext {
@@ -122,6 +320,8 @@ impl TestDefault for MockQueryContext {
             thread_pool: create_rayon_thread_pool(0),
             abort_registration,
             abort_trigger: Some(abort_trigger),
+            messages: QueryMessages::default(),
+            query_started: QueryStarted::default(),
         }
     }
 }
@@ -134,6 +334,8 @@ impl MockQueryContext {
             thread_pool: create_rayon_thread_pool(0),
             abort_registration,
             abort_trigger: Some(abort_trigger),
+            messages: QueryMessages::default(),
+            query_started: QueryStarted::default(),
         }
     }
 
@@ -147,6 +349,8 @@ impl MockQueryContext {
             thread_pool: create_rayon_thread_pool(num_threads),
             abort_registration,
             abort_trigger: Some(abort_trigger),
+            messages: QueryMessages::default(),
+            query_started: QueryStarted::default(),
         }
     }
 }
@@ -169,4 +373,86 @@ impl QueryContext for MockQueryContext {
             .take()
             .ok_or(error::Error::AbortTriggerAlreadyUsed)
     }
+
+    fn messages(&self) -> &QueryMessages {
+        &self.messages
+    }
+
+    fn subscribe_messages(&mut self) -> QueryMessageReceiver {
+        let (messages, receiver) = QueryMessages::new();
+        self.messages = messages;
+        receiver
+    }
+
+    fn query_started(&self) -> &QueryStarted {
+        &self.query_started
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{stream, StreamExt};
+
+    #[test]
+    fn it_adapts_chunk_sizes_to_memory_pressure() {
+        let chunk_byte_size = ChunkByteSize::new(1024);
+
+        assert_eq!(
+            chunk_byte_size.adapt_to_memory_pressure(0.),
+            chunk_byte_size
+        );
+        assert_eq!(
+            chunk_byte_size.adapt_to_memory_pressure(0.5),
+            chunk_byte_size
+        );
+        assert_eq!(
+            chunk_byte_size.adapt_to_memory_pressure(0.75),
+            ChunkByteSize::new(512)
+        );
+        assert_eq!(
+            chunk_byte_size.adapt_to_memory_pressure(2.),
+            ChunkByteSize::new(128)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_enriches_streams_with_messages() {
+        let mut ctx = MockQueryContext::test_default();
+
+        // discarded, since nobody listens
+        ctx.messages().warn("ignored");
+
+        let receiver = ctx.subscribe_messages();
+
+        let messages = ctx.messages().clone();
+        let results = stream::iter(vec![1_usize, 2]).map(move |value| {
+            messages.progress(value, Some(2));
+            Ok(value)
+        });
+
+        ctx.messages().warn("first");
+
+        let items: Vec<QueryStreamItem<usize>> =
+            receiver.enrich(results).map(Result::unwrap).collect().await;
+
+        assert_eq!(
+            items,
+            vec![
+                QueryStreamItem::Metadata(QueryMetadata::Warning {
+                    message: "first".to_string()
+                }),
+                QueryStreamItem::Data(1),
+                QueryStreamItem::Progress(QueryProgress {
+                    processed: 1,
+                    total: Some(2)
+                }),
+                QueryStreamItem::Data(2),
+                QueryStreamItem::Progress(QueryProgress {
+                    processed: 2,
+                    total: Some(2)
+                }),
+            ]
+        );
+    }
 }
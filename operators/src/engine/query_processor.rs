@@ -1,5 +1,7 @@
 use super::query::QueryContext;
+use crate::error::Error;
 use crate::processing::RasterTypeConversionQueryProcessor;
+use crate::util::coalescing::CoalescingQueryProcessor;
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::stream::BoxStream;
@@ -12,6 +14,7 @@ use geoengine_datatypes::primitives::{
     SpatialPartition2D, VectorQueryRectangle,
 };
 use geoengine_datatypes::raster::Pixel;
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
 use geoengine_datatypes::{collections::MultiPointCollection, raster::RasterTile2D};
 
 /// An instantiation of an operator that produces a stream of results for a query
@@ -27,11 +30,23 @@ pub trait QueryProcessor: Send + Sync {
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>>;
 
+    /// Starts the query unless the tiles and collections of the process exceed the memory budget.
+    /// The budget is only checked at the start of the top-level query, i.e., the sub-queries
+    /// of a running query are not rejected.
     async fn query<'a>(
         &'a self,
         query: QueryRectangle<Self::SpatialBounds>,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let already_started = ctx.query_started().start();
+
+        if !already_started && MEMORY_ACCOUNTANT.is_over_budget() {
+            return Err(Error::MemoryBudgetExceeded {
+                allocated_bytes: MEMORY_ACCOUNTANT.allocated_bytes(),
+                budget_bytes: MEMORY_ACCOUNTANT.budget_bytes().unwrap_or_default(),
+            });
+        }
+
         Ok(Box::pin(
             ctx.abort_registration()
                 .wrap(self._query(query, ctx).await?),
@@ -290,6 +305,38 @@ impl TypedRasterQueryProcessor {
             Self::F64(r) => r,
         }
     }
+
+    pub fn into_u64(self) -> BoxRasterQueryProcessor<u64> {
+        match self {
+            Self::U8(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::U16(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::U32(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::U64(r) => r,
+            Self::I8(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::I16(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::I32(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::I64(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::F32(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+            Self::F64(r) => RasterTypeConversionQueryProcessor::new(r).boxed(),
+        }
+    }
+
+    /// Lets concurrent identical queries share a single execution, see `CoalescingQueryProcessor`
+    #[must_use]
+    pub fn coalesced(self, key: String) -> Self {
+        match self {
+            Self::U8(p) => Self::U8(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::U16(p) => Self::U16(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::U32(p) => Self::U32(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::U64(p) => Self::U64(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::I8(p) => Self::I8(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::I16(p) => Self::I16(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::I32(p) => Self::I32(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::I64(p) => Self::I64(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::F32(p) => Self::F32(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::F64(p) => Self::F64(CoalescingQueryProcessor::new(p, key).boxed()),
+        }
+    }
 }
 
 impl From<Box<dyn RasterQueryProcessor<RasterType = u8>>> for TypedRasterQueryProcessor {
@@ -358,6 +405,21 @@ pub enum TypedVectorQueryProcessor {
 }
 
 impl TypedVectorQueryProcessor {
+    /// Lets concurrent identical queries share a single execution, see `CoalescingQueryProcessor`
+    #[must_use]
+    pub fn coalesced(self, key: String) -> Self {
+        match self {
+            Self::Data(p) => Self::Data(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::MultiPoint(p) => Self::MultiPoint(CoalescingQueryProcessor::new(p, key).boxed()),
+            Self::MultiLineString(p) => {
+                Self::MultiLineString(CoalescingQueryProcessor::new(p, key).boxed())
+            }
+            Self::MultiPolygon(p) => {
+                Self::MultiPolygon(CoalescingQueryProcessor::new(p, key).boxed())
+            }
+        }
+    }
+
     pub fn data(self) -> Option<Box<dyn VectorQueryProcessor<VectorType = DataCollection>>> {
         if let TypedVectorQueryProcessor::Data(p) = self {
             Some(p)
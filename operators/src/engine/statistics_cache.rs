@@ -0,0 +1,272 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{Coordinate2D, SpatialResolution, VectorQueryRectangle};
+use serde::{Deserialize, Serialize};
+
+/// Descriptive statistics of the values of a column or raster band
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValueStatistics {
+    /// The number of non-null values
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// The number of buckets along the larger side of the query bounds.
+/// Queries whose bounds differ by less than a bucket, e.g., slightly panned maps, share their statistics.
+const BUCKETS_PER_EXTENT: f64 = 64.;
+
+/// The range of the exponents of the bucket sizes, which are powers of two
+const MIN_BUCKET_EXPONENT: i32 = -64;
+const MAX_BUCKET_EXPONENT: i32 = 64;
+
+/// The number of buckets per doubling of the spatial resolution
+const RESOLUTION_BUCKETS_PER_OCTAVE: f64 = 4.;
+
+/// Identifies the statistics of a column of a workflow result within a query rectangle.
+/// The workflow is given by its serialized operator, so equal workflows share their statistics.
+/// The spatial bounds and resolution are bucketed, so that similar queries share their statistics, too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StatisticsKey {
+    workflow: String,
+    /// the data that is read by the workflow, for invalidating the statistics if it changes
+    data: Vec<DataId>,
+    column: Option<String>,
+    /// the bucket exponent, the bucketed spatial bounds and the bucketed resolution
+    spatial: [i64; 7],
+    time: (i64, i64),
+}
+
+impl StatisticsKey {
+    pub fn new(
+        workflow: String,
+        data: Vec<DataId>,
+        column: Option<String>,
+        query: &VectorQueryRectangle,
+    ) -> Self {
+        let [exponent, min_x, min_y, max_x, max_y] = bucketed_bounds(
+            query.spatial_bounds.lower_left(),
+            query.spatial_bounds.upper_right(),
+        );
+        let [resolution_x, resolution_y] = bucketed_resolution(query.spatial_resolution);
+
+        Self {
+            workflow,
+            data,
+            column,
+            spatial: [
+                exponent,
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+                resolution_x,
+                resolution_y,
+            ],
+            time: (
+                query.time_interval.start().inner(),
+                query.time_interval.end().inner(),
+            ),
+        }
+    }
+}
+
+/// Snaps the bounds outwards to a grid whose cell size is a power of two,
+/// so that there are about `BUCKETS_PER_EXTENT` cells along the larger side of the bounds
+fn bucketed_bounds(lower_left: Coordinate2D, upper_right: Coordinate2D) -> [i64; 5] {
+    let size = (upper_right.x - lower_left.x).max(upper_right.y - lower_left.y);
+
+    let exponent = if size > 0. && size.is_finite() {
+        ((size / BUCKETS_PER_EXTENT).log2().ceil() as i32)
+            .clamp(MIN_BUCKET_EXPONENT, MAX_BUCKET_EXPONENT)
+    } else {
+        MIN_BUCKET_EXPONENT
+    };
+    let cell_size = 2_f64.powi(exponent);
+
+    [
+        i64::from(exponent),
+        (lower_left.x / cell_size).floor() as i64,
+        (lower_left.y / cell_size).floor() as i64,
+        (upper_right.x / cell_size).ceil() as i64,
+        (upper_right.y / cell_size).ceil() as i64,
+    ]
+}
+
+/// Buckets the resolution on a logarithmic scale
+fn bucketed_resolution(resolution: SpatialResolution) -> [i64; 2] {
+    [
+        (resolution.x.log2() * RESOLUTION_BUCKETS_PER_OCTAVE).round() as i64,
+        (resolution.y.log2() * RESOLUTION_BUCKETS_PER_OCTAVE).round() as i64,
+    ]
+}
+
+/// A cache for statistics of workflow results, e.g., to skip the computation of the bounds of a histogram.
+/// It is provided by the `ExecutionContext`.
+pub trait StatisticsCache: Send + Sync {
+    fn get(&self, key: &StatisticsKey) -> Option<ValueStatistics>;
+
+    fn insert(&self, key: StatisticsKey, statistics: ValueStatistics);
+
+    /// Removes the statistics of all workflows that read the `data`, e.g., because time slices were appended
+    fn invalidate_data(&self, data: &DataId);
+}
+
+#[derive(Debug, Default)]
+struct StatisticsCacheEntries {
+    statistics: HashMap<StatisticsKey, ValueStatistics>,
+    insertion_order: VecDeque<StatisticsKey>,
+}
+
+/// A statistics cache that evicts the oldest entries if its capacity is exceeded
+#[derive(Debug)]
+pub struct InMemoryStatisticsCache {
+    capacity: usize,
+    entries: Mutex<StatisticsCacheEntries>,
+}
+
+impl InMemoryStatisticsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(StatisticsCacheEntries::default()),
+        }
+    }
+}
+
+impl StatisticsCache for InMemoryStatisticsCache {
+    fn get(&self, key: &StatisticsKey) -> Option<ValueStatistics> {
+        self.entries
+            .lock()
+            .expect("statistics cache lock must not be poisoned")
+            .statistics
+            .get(key)
+            .copied()
+    }
+
+    fn insert(&self, key: StatisticsKey, statistics: ValueStatistics) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("statistics cache lock must not be poisoned");
+
+        if entries.statistics.insert(key.clone(), statistics).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+
+        while entries.statistics.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                entries.statistics.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn invalidate_data(&self, data: &DataId) {
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("statistics cache lock must not be poisoned");
+
+        entries.statistics.retain(|key, _| !key.data.contains(data));
+
+        let StatisticsCacheEntries {
+            statistics,
+            insertion_order,
+        } = &mut *entries;
+        insertion_order.retain(|key| statistics.contains_key(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::dataset::DatasetId;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_evicts_the_oldest_statistics() {
+        let cache = InMemoryStatisticsCache::new(1);
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let key = StatisticsKey::new("workflow".to_string(), vec![], None, &query);
+        let other_key = StatisticsKey::new(
+            "workflow".to_string(),
+            vec![],
+            Some("a".to_string()),
+            &query,
+        );
+
+        let statistics = ValueStatistics {
+            count: 1,
+            min: 0.,
+            max: 1.,
+        };
+
+        cache.insert(key.clone(), statistics);
+        assert_eq!(cache.get(&key), Some(statistics));
+        assert_eq!(cache.get(&other_key), None);
+
+        cache.insert(other_key.clone(), statistics);
+        assert_eq!(cache.get(&key), None);
+        assert_eq!(cache.get(&other_key), Some(statistics));
+    }
+
+    #[test]
+    fn it_buckets_similar_queries() {
+        let query = |x: f64, resolution: f64| VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((x, 0.).into(), (x + 64., 64.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::new_unchecked(resolution, resolution),
+        };
+        let key = |query: &VectorQueryRectangle| {
+            StatisticsKey::new("workflow".to_string(), vec![], None, query)
+        };
+
+        assert_eq!(key(&query(0.25, 1.)), key(&query(0.5, 1.01)));
+        assert_ne!(key(&query(0.25, 1.)), key(&query(2., 1.)));
+        assert_ne!(key(&query(0.25, 1.)), key(&query(0.25, 2.)));
+    }
+
+    #[test]
+    fn it_invalidates_the_statistics_of_data() {
+        let cache = InMemoryStatisticsCache::new(10);
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let data: DataId = DatasetId::new().into();
+        let other_data: DataId = DatasetId::new().into();
+
+        let key = StatisticsKey::new("a".to_string(), vec![data.clone()], None, &query);
+        let other_key = StatisticsKey::new("b".to_string(), vec![other_data], None, &query);
+
+        let statistics = ValueStatistics {
+            count: 1,
+            min: 0.,
+            max: 1.,
+        };
+
+        cache.insert(key.clone(), statistics);
+        cache.insert(other_key.clone(), statistics);
+
+        cache.invalidate_data(&data);
+
+        assert_eq!(cache.get(&key), None);
+        assert_eq!(cache.get(&other_key), Some(statistics));
+    }
+}
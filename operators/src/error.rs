@@ -1,7 +1,7 @@
 use crate::util::statistics::StatisticsError;
 use geoengine_datatypes::dataset::DataId;
 use geoengine_datatypes::error::ErrorSource;
-use geoengine_datatypes::primitives::FeatureDataType;
+use geoengine_datatypes::primitives::{FeatureDataType, TimeInterval};
 use snafu::prelude::*;
 use std::ops::Range;
 use std::path::PathBuf;
@@ -37,6 +37,21 @@ pub enum Error {
         details: String,
     },
 
+    #[snafu(display("Could not write CSV: {}", source))]
+    CsvWriter {
+        source: csv::Error,
+    },
+
+    #[snafu(display("GeoJsonSource Error: {}", details))]
+    GeoJsonSource {
+        details: String,
+    },
+
+    #[snafu(display("Invalid inline data: {}", details))]
+    InlineData {
+        details: String,
+    },
+
     #[snafu(display("DataTypeError: {}", source))]
     DataType {
         source: geoengine_datatypes::error::Error,
@@ -161,6 +176,25 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    #[snafu(display("The operator {} is not available on this instance", operator))]
+    OperatorNotAllowed {
+        operator: String,
+    },
+
+    #[snafu(display("The path {} is outside of the sandbox of this instance", path))]
+    PathNotAllowed {
+        path: String,
+    },
+
+    #[snafu(display("There is no data root on this instance for the file paths of operators"))]
+    NoDataRoot,
+
+    #[snafu(display("Cannot resolve the referenced workflow {}: {}", workflow, source))]
+    WorkflowReference {
+        workflow: uuid::Uuid,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     Arrow {
         source: arrow::error::ArrowError,
     },
@@ -228,10 +262,58 @@ pub enum Error {
         limit: usize,
     },
 
+    #[snafu(display(
+        "A cloud-optimized GeoTIFF has no time dimension, but the query contains the time steps {} and {}. Query each time step separately.",
+        first,
+        second
+    ))]
+    CogWithMultipleTimeSteps {
+        first: TimeInterval,
+        second: TimeInterval,
+    },
+
+    #[snafu(display(
+        "The memory of the engine ({} bytes) exceeds its budget of {} bytes. Please retry later.",
+        allocated_bytes,
+        budget_bytes
+    ))]
+    MemoryBudgetExceeded {
+        allocated_bytes: usize,
+        budget_bytes: usize,
+    },
+
     FeatureDataNotAggregatable,
 
     FeatureDataLengthMismatch,
 
+    #[snafu(display(
+        "The value {} of feature {} cannot be aggregated as {:?} without overflow",
+        value,
+        feature_index,
+        data_type
+    ))]
+    AggregationValueOverflow {
+        value: String,
+        feature_index: usize,
+        data_type: FeatureDataType,
+    },
+
+    #[snafu(display(
+        "The sum of the weights of feature {} exceeds the range of the aggregator",
+        feature_index
+    ))]
+    AggregationWeightOverflow {
+        feature_index: usize,
+    },
+
+    #[snafu(display(
+        "The query interval must be finite and contain at most {} time buckets",
+        limit
+    ))]
+    TooManyTimeBuckets {
+        limit: u32,
+    },
+
     OgrSqlQuery,
 
     GdalRasterDataTypeNotSupported,
@@ -293,6 +375,10 @@ pub enum Error {
         source: crate::processing::InterpolationError,
     },
     #[snafu(context(false))]
+    DownsamplingOperator {
+        source: crate::processing::DownsamplingError,
+    },
+    #[snafu(context(false))]
     TimeShift {
         source: crate::processing::TimeShiftError,
     },
@@ -313,7 +399,15 @@ pub enum Error {
 
     QueryCanceled,
 
+    #[snafu(display("The query panicked: {}", message))]
+    QueryPanicked {
+        message: String,
+    },
+
     AbortTriggerAlreadyUsed,
+
+    #[snafu(display("The geometry is invalid and cannot be repaired"))]
+    UnrepairableGeometry,
 }
 
 impl From<crate::adapters::SparseTilesFillAdapterError> for Error {
@@ -0,0 +1,503 @@
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator,
+    OperatorData, OperatorName, PlotOperator, PlotQueryProcessor, PlotResultDescriptor,
+    QueryContext, RasterOperator, SingleVectorMultipleRasterSources, TypedPlotQueryProcessor,
+    VectorOperator, VectorQueryProcessor,
+};
+use crate::error;
+use crate::processing::{
+    stratum_name, FeatureAggregationMethod, RasterVectorJoin, RasterVectorJoinParams,
+    TemporalAggregationMethod,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::PlotQueryRectangle;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{span, Level};
+
+pub const CONFUSION_MATRIX_OPERATOR_NAME: &str = "ConfusionMatrix";
+
+/// A plot that compares a classified raster against reference points and outputs a confusion matrix
+/// together with the derived accuracy measures
+pub type ConfusionMatrix = Operator<ConfusionMatrixParams, ConfusionMatrixSources>;
+
+impl OperatorName for ConfusionMatrix {
+    const TYPE_NAME: &'static str = "ConfusionMatrix";
+}
+
+/// The parameter spec for `ConfusionMatrix`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusionMatrixParams {
+    /// Name of the attribute of the reference points that contains the true class
+    pub column_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusionMatrixSources {
+    /// The classified raster
+    pub classification: Box<dyn RasterOperator>,
+    /// The reference points
+    pub reference: Box<dyn VectorOperator>,
+}
+
+impl OperatorData for ConfusionMatrixSources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<DataId>) {
+        self.classification.data_ids_collect(data_ids);
+        self.reference.data_ids_collect(data_ids);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for ConfusionMatrix {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        let reference = self.sources.reference.initialize(context).await?;
+        let reference_rd = reference.result_descriptor().clone();
+
+        ensure!(
+            reference_rd.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: reference_rd.data_type.to_string(),
+            }
+        );
+        ensure!(
+            reference_rd.columns.contains_key(&self.params.column_name),
+            error::ColumnDoesNotExist {
+                column: self.params.column_name.clone(),
+            }
+        );
+
+        // the name of the joined raster values must not shadow an attribute of the reference points
+        let mut classification_column = "classification".to_string();
+        while reference_rd.columns.contains_key(&classification_column) {
+            classification_column.push('_');
+        }
+
+        let joined = RasterVectorJoin {
+            params: RasterVectorJoinParams {
+                names: vec![classification_column.clone()],
+                feature_aggregation: FeatureAggregationMethod::First,
+                temporal_aggregation: TemporalAggregationMethod::First,
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: self.sources.reference,
+                rasters: vec![self.sources.classification],
+            },
+        }
+        .boxed()
+        .initialize(context)
+        .await?;
+
+        Ok(InitializedConfusionMatrix {
+            result_descriptor: PlotResultDescriptor {
+                spatial_reference: reference_rd.spatial_reference,
+                time: reference_rd.time,
+                bbox: reference_rd.bbox,
+            },
+            joined,
+            reference_column: self.params.column_name,
+            classification_column,
+        }
+        .boxed())
+    }
+
+    span_fn!(ConfusionMatrix);
+}
+
+/// The initialization of `ConfusionMatrix`
+pub struct InitializedConfusionMatrix {
+    result_descriptor: PlotResultDescriptor,
+    joined: Box<dyn InitializedVectorOperator>,
+    reference_column: String,
+    classification_column: String,
+}
+
+impl InitializedPlotOperator for InitializedConfusionMatrix {
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        Ok(TypedPlotQueryProcessor::JsonPlain(
+            ConfusionMatrixQueryProcessor {
+                joined: self
+                    .joined
+                    .query_processor()?
+                    .multi_point()
+                    .expect("checked in `ConfusionMatrix` constructor"),
+                reference_column: self.reference_column.clone(),
+                classification_column: self.classification_column.clone(),
+            }
+            .boxed(),
+        ))
+    }
+}
+
+/// A query processor that counts the agreement of the reference points and the classified raster
+pub struct ConfusionMatrixQueryProcessor {
+    joined: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    reference_column: String,
+    classification_column: String,
+}
+
+#[async_trait]
+impl PlotQueryProcessor for ConfusionMatrixQueryProcessor {
+    type OutputFormat = serde_json::Value;
+
+    fn plot_type(&self) -> &'static str {
+        CONFUSION_MATRIX_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: PlotQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut counts = ConfusionCounts::default();
+
+        let mut stream = self.joined.query(query, ctx).await?;
+        while let Some(collection) = stream.try_next().await? {
+            let reference = collection.data(&self.reference_column)?;
+            let classification = collection.data(&self.classification_column)?;
+
+            for i in 0..collection.len() {
+                match (
+                    stratum_name(reference.get_unchecked(i)),
+                    stratum_name(classification.get_unchecked(i)),
+                ) {
+                    (Some(reference), Some(classification)) => {
+                        counts.add(reference, classification);
+                    }
+                    (Some(_), None) => counts.unclassified += 1,
+                    (None, _) => {}
+                }
+            }
+        }
+
+        serde_json::to_value(&ConfusionMatrixOutput::from(counts)).map_err(Into::into)
+    }
+}
+
+/// The number of points per pair of reference and classified class
+#[derive(Debug, Default)]
+struct ConfusionCounts {
+    counts: BTreeMap<(String, String), usize>,
+    /// Reference points without a raster value
+    unclassified: usize,
+}
+
+impl ConfusionCounts {
+    fn add(&mut self, reference: String, classification: String) {
+        *self.counts.entry((reference, classification)).or_default() += 1;
+    }
+}
+
+/// The confusion matrix with the rows being the reference classes and the columns being the classified classes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfusionMatrixOutput {
+    classes: Vec<String>,
+    matrix: Vec<Vec<usize>>,
+    samples: usize,
+    unclassified: usize,
+    overall_accuracy: Option<f64>,
+    /// The share of the reference points of a class that were classified correctly
+    producer_accuracy: BTreeMap<String, Option<f64>>,
+    /// The share of the points classified as a class that belong to this class
+    user_accuracy: BTreeMap<String, Option<f64>>,
+    kappa: Option<f64>,
+}
+
+impl From<ConfusionCounts> for ConfusionMatrixOutput {
+    fn from(counts: ConfusionCounts) -> Self {
+        let classes: Vec<String> = counts
+            .counts
+            .keys()
+            .flat_map(|(reference, classification)| [reference.clone(), classification.clone()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let matrix: Vec<Vec<usize>> = classes
+            .iter()
+            .map(|reference| {
+                classes
+                    .iter()
+                    .map(|classification| {
+                        counts
+                            .counts
+                            .get(&(reference.clone(), classification.clone()))
+                            .copied()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let row_sums: Vec<usize> = matrix.iter().map(|row| row.iter().sum()).collect();
+        let column_sums: Vec<usize> = (0..classes.len())
+            .map(|j| matrix.iter().map(|row| row[j]).sum())
+            .collect();
+        let correct: usize = (0..classes.len()).map(|i| matrix[i][i]).sum();
+        let samples: usize = row_sums.iter().sum();
+
+        let ratio = |numerator: usize, denominator: usize| {
+            (denominator > 0).then(|| numerator as f64 / denominator as f64)
+        };
+
+        let overall_accuracy = ratio(correct, samples);
+
+        let expected_accuracy = (samples > 0).then(|| {
+            row_sums
+                .iter()
+                .zip(&column_sums)
+                .map(|(&row_sum, &column_sum)| row_sum as f64 * column_sum as f64)
+                .sum::<f64>()
+                / (samples as f64 * samples as f64)
+        });
+
+        let kappa = match (overall_accuracy, expected_accuracy) {
+            (Some(observed), Some(expected)) if expected < 1. => {
+                Some((observed - expected) / (1. - expected))
+            }
+            _ => None,
+        };
+
+        Self {
+            producer_accuracy: classes
+                .iter()
+                .enumerate()
+                .map(|(i, class)| (class.clone(), ratio(matrix[i][i], row_sums[i])))
+                .collect(),
+            user_accuracy: classes
+                .iter()
+                .enumerate()
+                .map(|(i, class)| (class.clone(), ratio(matrix[i][i], column_sums[i])))
+                .collect(),
+            classes,
+            matrix,
+            samples,
+            unclassified: counts.unclassified,
+            overall_accuracy,
+            kappa,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterResultDescriptor,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, Measurement, MultiPoint, SpatialResolution, TimeInterval,
+        VectorQueryRectangle,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn classification() -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                    },
+                    Grid2D::new([3, 2].into(), vec![1, 1, 2, 2, 1, 2])
+                        .unwrap()
+                        .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn reference(labels: Vec<Option<i64>>) -> Box<dyn VectorOperator> {
+        // the pixel centers of the classification, row by row
+        let points = [
+            (0.5, -0.5),
+            (1.5, -0.5),
+            (0.5, -1.5),
+            (1.5, -1.5),
+            (0.5, -2.5),
+            (1.5, -2.5),
+            (5.5, -5.5),
+        ];
+
+        MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(points[..labels.len()].to_vec()).unwrap(),
+                vec![TimeInterval::default(); labels.len()],
+                [("class".to_string(), FeatureData::NullableInt(labels))].into(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn confusion_matrix(reference: Box<dyn VectorOperator>) -> serde_json::Value {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [3, 2].into(),
+        });
+
+        let processor = ConfusionMatrix {
+            params: ConfusionMatrixParams {
+                column_name: "class".to_string(),
+            },
+            sources: ConfusionMatrixSources {
+                classification: classification(),
+                reference,
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .json_plain()
+        .unwrap();
+
+        processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -6.).into(), (6., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn serialization() {
+        let serialized = json!({
+            "type": "ConfusionMatrix",
+            "params": {
+                "columnName": "class",
+            },
+            "sources": {
+                "classification": {
+                    "type": "MockRasterSourceu8",
+                    "params": {
+                        "data": [],
+                        "resultDescriptor": {
+                            "dataType": "U8",
+                            "spatialReference": "EPSG:4326",
+                            "measurement": {
+                                "type": "unitless"
+                            }
+                        }
+                    }
+                },
+                "reference": {
+                    "type": "MockFeatureCollectionSourceMultiPoint",
+                    "params": {
+                        "collections": [],
+                        "spatialReference": "EPSG:4326",
+                        "measurements": {},
+                    }
+                }
+            }
+        })
+        .to_string();
+
+        let deserialized: ConfusionMatrix = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.params,
+            ConfusionMatrixParams {
+                column_name: "class".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_confusion_matrix() {
+        let result = confusion_matrix(reference(vec![
+            Some(1),
+            Some(2),
+            Some(2),
+            Some(2),
+            Some(1),
+            None,
+            Some(1),
+        ]))
+        .await;
+
+        // observed: 4 / 5, expected: (2 * 3 + 3 * 2) / 25
+        let kappa = (0.8 - 12. / 25.) / (1. - 12. / 25.);
+
+        assert_eq!(
+            result,
+            json!({
+                "classes": ["1", "2"],
+                "matrix": [[2, 0], [1, 2]],
+                "samples": 5,
+                "unclassified": 1,
+                "overallAccuracy": 0.8,
+                "producerAccuracy": {
+                    "1": 1.0,
+                    "2": 2. / 3.,
+                },
+                "userAccuracy": {
+                    "1": 2. / 3.,
+                    "2": 1.0,
+                },
+                "kappa": kappa,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_requires_the_class_column() {
+        let execution_context = MockExecutionContext::test_default();
+
+        assert!(ConfusionMatrix {
+            params: ConfusionMatrixParams {
+                column_name: "foo".to_string(),
+            },
+            sources: ConfusionMatrixSources {
+                classification: classification(),
+                reference: reference(vec![Some(1)]),
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::engine::{CreateSpan, QueryProcessor};
+use crate::engine::{
+    source_data_id, CreateSpan, OperatorData, QueryProcessor, StatisticsCache, StatisticsKey,
+    ValueStatistics,
+};
 use crate::error;
 use crate::error::Error;
 use crate::string_token;
@@ -16,7 +19,8 @@ use async_trait::async_trait;
 use float_cmp::approx_eq;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryFutureExt};
-use geoengine_datatypes::plots::{Plot, PlotData};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::plots::{HistogramBucketing, Plot, PlotData};
 use geoengine_datatypes::primitives::{
     AxisAlignedRectangle, BoundingBox2D, DataRef, FeatureDataRef, FeatureDataType, Geometry,
     Measurement, VectorQueryRectangle,
@@ -29,6 +33,7 @@ use geoengine_datatypes::{
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt};
 use std::convert::TryFrom;
+use std::sync::Arc;
 use tracing::{span, Level};
 
 pub const HISTOGRAM_OPERATOR_NAME: &str = "Histogram";
@@ -53,6 +58,10 @@ pub struct HistogramParams {
     pub bounds: HistogramBounds,
     /// If the number of buckets is undefined, it is derived from the square-root choice rule.
     pub buckets: Option<usize>,
+    /// How the buckets are spaced between the bounds (`linear` by default).
+    /// Custom bucket `edges` also define the bounds and the number of buckets.
+    #[serde(default)]
+    pub bucketing: HistogramBucketing,
     /// Whether to create an interactive output (`false` by default)
     #[serde(default)]
     pub interactive: bool,
@@ -69,6 +78,58 @@ pub enum HistogramBounds {
     // TODO: use bounds in measurement if they are available
 }
 
+impl HistogramParams {
+    fn validate_bucketing(&self) -> Result<()> {
+        match &self.bucketing {
+            HistogramBucketing::Linear => {}
+            HistogramBucketing::Logarithmic => {
+                if let HistogramBounds::Values { min, .. } = self.bounds {
+                    ensure!(
+                        min > 0.,
+                        error::InvalidOperatorSpec {
+                            reason: "Histogram with logarithmic buckets must have a positive `min` bound"
+                                .to_string(),
+                        }
+                    );
+                }
+            }
+            HistogramBucketing::Edges(edges) => {
+                ensure!(
+                    edges.len() >= 2
+                        && edges.iter().all(|edge| edge.is_finite())
+                        && edges.windows(2).all(|edges| edges[0] < edges[1]),
+                    error::InvalidOperatorSpec {
+                        reason: "Histogram bucket `edges` must be at least two finite and strictly ascending values"
+                            .to_string(),
+                    }
+                );
+                ensure!(
+                    self.buckets
+                        .map_or(true, |buckets| buckets == edges.len() - 1),
+                    error::InvalidOperatorSpec {
+                        reason:
+                            "Histogram `buckets` must be one less than the number of bucket `edges`"
+                                .to_string(),
+                    }
+                );
+                if let HistogramBounds::Values { min, max } = self.bounds {
+                    ensure!(
+                        approx_eq!(f64, min, edges[0])
+                            && approx_eq!(f64, max, edges[edges.len() - 1]),
+                        error::InvalidOperatorSpec {
+                            reason:
+                                "Histogram `bounds` must match the first and last bucket `edges`"
+                                    .to_string(),
+                        }
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[typetag::serde]
 #[async_trait]
 impl PlotOperator for Histogram {
@@ -76,6 +137,8 @@ impl PlotOperator for Histogram {
         self: Box<Self>,
         context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedPlotOperator>> {
+        self.params.validate_bucketing()?;
+
         Ok(match self.sources.source {
             RasterOrVectorOperator::Raster(raster_source) => {
                 ensure!(
@@ -86,6 +149,10 @@ impl PlotOperator for Histogram {
                     }
                 );
 
+                let statistics =
+                    CachedStatistics::new(context, &raster_source, raster_source.data_ids(), None);
+                let dataset_statistics = dataset_statistics(context, &raster_source, None).await;
+
                 let initialized = raster_source.initialize(context).await?;
 
                 let in_desc = initialized.result_descriptor();
@@ -101,6 +168,8 @@ impl PlotOperator for Histogram {
                     self.params,
                     initialized,
                 )
+                .with_dataset_statistics(dataset_statistics)
+                .with_statistics(statistics)
                 .boxed()
             }
             RasterOrVectorOperator::Vector(vector_source) => {
@@ -113,6 +182,15 @@ impl PlotOperator for Histogram {
                                 .to_string(),
                         })?;
 
+                let statistics = CachedStatistics::new(
+                    context,
+                    &vector_source,
+                    vector_source.data_ids(),
+                    Some(column_name.clone()),
+                );
+                let dataset_statistics =
+                    dataset_statistics(context, &vector_source, Some(column_name.as_str())).await;
+
                 let vector_source = vector_source.initialize(context).await?;
 
                 match vector_source
@@ -142,7 +220,10 @@ impl PlotOperator for Histogram {
 
                 let in_desc = vector_source.result_descriptor().clone();
 
-                InitializedHistogram::new(in_desc.into(), self.params, vector_source).boxed()
+                InitializedHistogram::new(in_desc.into(), self.params, vector_source)
+                    .with_dataset_statistics(dataset_statistics)
+                    .with_statistics(statistics)
+                    .boxed()
             }
         })
     }
@@ -155,8 +236,65 @@ pub struct InitializedHistogram<Op> {
     result_descriptor: PlotResultDescriptor,
     metadata: HistogramMetadataOptions,
     source: Op,
+    bucketing: HistogramBucketing,
     interactive: bool,
     column_name: Option<String>,
+    statistics: Option<CachedStatistics>,
+}
+
+/// Caches the computed bounds of the histogram input in the statistics cache of the execution context
+#[derive(Clone)]
+struct CachedStatistics {
+    cache: Arc<dyn StatisticsCache>,
+    workflow: String,
+    data: Vec<DataId>,
+    column: Option<String>,
+}
+
+impl CachedStatistics {
+    fn new<S: Serialize>(
+        context: &dyn ExecutionContext,
+        source: &S,
+        data: Vec<DataId>,
+        column: Option<String>,
+    ) -> Option<Self> {
+        let cache = context.statistics_cache()?;
+        let workflow = serde_json::to_string(source).ok()?;
+
+        Some(Self {
+            cache,
+            workflow,
+            data,
+            column,
+        })
+    }
+
+    fn key(&self, query: &VectorQueryRectangle) -> StatisticsKey {
+        StatisticsKey::new(
+            self.workflow.clone(),
+            self.data.clone(),
+            self.column.clone(),
+            query,
+        )
+    }
+}
+
+/// Retrieves the statistics of the dataset if the source operator loads it directly.
+/// They are only an optimization, so the histogram computes the bounds itself if the lookup fails.
+async fn dataset_statistics<S: Serialize + Sync>(
+    context: &dyn ExecutionContext,
+    source: &S,
+    column: Option<&str>,
+) -> Option<ValueStatistics> {
+    let data = source_data_id(source)?;
+
+    match context.auxiliary_metadata(&data).await {
+        Ok(metadata) => metadata?.value_metadata(column)?.statistics,
+        Err(error) => {
+            log::warn!("Could not retrieve the statistics of the dataset {data:?}: {error}");
+            None
+        }
+    }
 }
 
 impl<Op> InitializedHistogram<Op> {
@@ -171,17 +309,53 @@ impl<Op> InitializedHistogram<Op> {
             (None, None)
         };
 
-        Self {
-            result_descriptor,
-            metadata: HistogramMetadataOptions {
+        let metadata = if let HistogramBucketing::Edges(edges) = &params.bucketing {
+            // the edges are validated to be non-empty
+            HistogramMetadataOptions {
+                number_of_buckets: Some(edges.len() - 1),
+                min: edges.first().copied(),
+                max: edges.last().copied(),
+            }
+        } else {
+            HistogramMetadataOptions {
                 number_of_buckets: params.buckets,
                 min,
                 max,
-            },
+            }
+        };
+
+        Self {
+            result_descriptor,
+            metadata,
             source,
+            bucketing: params.bucketing,
             interactive: params.interactive,
             column_name: params.column_name,
+            statistics: None,
+        }
+    }
+
+    /// Uses the bounds of the dataset if only the bounds have to be computed.
+    /// They contain all values of any query, but their count is not the number of queried values.
+    fn with_dataset_statistics(mut self, statistics: Option<ValueStatistics>) -> Self {
+        if let (Some(statistics), Some(_), None, None) = (
+            statistics,
+            self.metadata.number_of_buckets,
+            self.metadata.min,
+            self.metadata.max,
+        ) {
+            self.metadata.min = Some(statistics.min);
+            self.metadata.max = Some(statistics.max);
+        }
+        self
+    }
+
+    fn with_statistics(mut self, statistics: Option<CachedStatistics>) -> Self {
+        // the statistics are only needed if the bounds or buckets are computed
+        if HistogramMetadata::try_from(self.metadata).is_err() {
+            self.statistics = statistics;
         }
+        self
     }
 }
 
@@ -191,7 +365,9 @@ impl InitializedPlotOperator for InitializedHistogram<Box<dyn InitializedRasterO
             input: self.source.query_processor()?,
             measurement: self.source.result_descriptor().measurement.clone(),
             metadata: self.metadata,
+            bucketing: self.bucketing.clone(),
             interactive: self.interactive,
+            statistics: self.statistics.clone(),
         };
 
         Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
@@ -214,7 +390,9 @@ impl InitializedPlotOperator for InitializedHistogram<Box<dyn InitializedVectorO
                 .cloned()
                 .into(),
             metadata: self.metadata,
+            bucketing: self.bucketing.clone(),
             interactive: self.interactive,
+            statistics: self.statistics.clone(),
         };
 
         Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
@@ -230,7 +408,9 @@ pub struct HistogramRasterQueryProcessor {
     input: TypedRasterQueryProcessor,
     measurement: Measurement,
     metadata: HistogramMetadataOptions,
+    bucketing: HistogramBucketing,
     interactive: bool,
+    statistics: Option<CachedStatistics>,
 }
 
 /// A query processor that calculates the Histogram about its vector inputs.
@@ -239,7 +419,9 @@ pub struct HistogramVectorQueryProcessor {
     column_name: String,
     measurement: Measurement,
     metadata: HistogramMetadataOptions,
+    bucketing: HistogramBucketing,
     interactive: bool,
+    statistics: Option<CachedStatistics>,
 }
 
 #[async_trait]
@@ -304,8 +486,7 @@ impl HistogramRasterQueryProcessor {
     ) -> Result<HistogramMetadata> {
         async fn process_metadata<T: Pixel>(
             mut input: BoxStream<'_, Result<RasterTile2D<T>>>,
-            metadata: HistogramMetadataOptions,
-        ) -> Result<HistogramMetadata> {
+        ) -> Result<HistogramMetadataInProgress> {
             let mut computed_metadata = HistogramMetadataInProgress::default();
 
             while let Some(tile) = input.next().await {
@@ -317,18 +498,26 @@ impl HistogramRasterQueryProcessor {
                 }
             }
 
-            Ok(metadata.merge_with(computed_metadata.into()))
+            Ok(computed_metadata)
         }
 
         if let Ok(metadata) = HistogramMetadata::try_from(self.metadata) {
             return Ok(metadata);
         }
 
+        if let Some(statistics) = cached_statistics(self.statistics.as_ref(), &query) {
+            return Ok(self.metadata.merge_with(statistics.into()));
+        }
+
         // TODO: compute only number of buckets if possible
 
-        call_on_generic_raster_processor!(&self.input, processor => {
-            process_metadata(processor.query(query.into(), ctx).await?, self.metadata).await
-        })
+        let computed_metadata = call_on_generic_raster_processor!(&self.input, processor => {
+            process_metadata(processor.query(query.into(), ctx).await?).await
+        })?;
+
+        cache_statistics(self.statistics.as_ref(), &query, computed_metadata);
+
+        Ok(self.metadata.merge_with(computed_metadata.into()))
     }
 
     async fn process<'p>(
@@ -343,6 +532,7 @@ impl HistogramRasterQueryProcessor {
             metadata.max,
             self.measurement.clone(),
         )
+        .bucketing(self.bucketing.clone())
         .build()
         .map_err(Error::from)?;
 
@@ -387,8 +577,7 @@ impl HistogramVectorQueryProcessor {
         async fn process_metadata<'m, G>(
             mut input: BoxStream<'m, Result<FeatureCollection<G>>>,
             column_name: &'m str,
-            metadata: HistogramMetadataOptions,
-        ) -> Result<HistogramMetadata>
+        ) -> Result<HistogramMetadataInProgress>
         where
             G: Geometry + 'static,
             FeatureCollection<G>: FeatureCollectionInfos,
@@ -402,18 +591,26 @@ impl HistogramVectorQueryProcessor {
                 computed_metadata.add_vector_batch(feature_data);
             }
 
-            Ok(metadata.merge_with(computed_metadata.into()))
+            Ok(computed_metadata)
         }
 
         if let Ok(metadata) = HistogramMetadata::try_from(self.metadata) {
             return Ok(metadata);
         }
 
+        if let Some(statistics) = cached_statistics(self.statistics.as_ref(), &query) {
+            return Ok(self.metadata.merge_with(statistics.into()));
+        }
+
         // TODO: compute only number of buckets if possible
 
-        call_on_generic_vector_processor!(&self.input, processor => {
-            process_metadata(processor.query(query, ctx).await?, &self.column_name, self.metadata).await
-        })
+        let computed_metadata = call_on_generic_vector_processor!(&self.input, processor => {
+            process_metadata(processor.query(query, ctx).await?, &self.column_name).await
+        })?;
+
+        cache_statistics(self.statistics.as_ref(), &query, computed_metadata);
+
+        Ok(self.metadata.merge_with(computed_metadata.into()))
     }
 
     async fn process<'p>(
@@ -428,6 +625,7 @@ impl HistogramVectorQueryProcessor {
             metadata.max,
             self.measurement.clone(),
         )
+        .bucketing(self.bucketing.clone())
         .build()
         .map_err(Error::from)?;
 
@@ -583,6 +781,46 @@ impl HistogramMetadataInProgress {
     }
 }
 
+impl From<HistogramMetadataInProgress> for ValueStatistics {
+    fn from(metadata: HistogramMetadataInProgress) -> Self {
+        Self {
+            count: metadata.n,
+            min: metadata.min,
+            max: metadata.max,
+        }
+    }
+}
+
+impl From<ValueStatistics> for HistogramMetadataInProgress {
+    fn from(statistics: ValueStatistics) -> Self {
+        Self {
+            n: statistics.count,
+            min: statistics.min,
+            max: statistics.max,
+        }
+    }
+}
+
+fn cached_statistics(
+    statistics: Option<&CachedStatistics>,
+    query: &VectorQueryRectangle,
+) -> Option<HistogramMetadataInProgress> {
+    let statistics = statistics?;
+    statistics.cache.get(&statistics.key(query)).map(Into::into)
+}
+
+fn cache_statistics(
+    statistics: Option<&CachedStatistics>,
+    query: &VectorQueryRectangle,
+    metadata: HistogramMetadataInProgress,
+) {
+    if let Some(statistics) = statistics {
+        statistics
+            .cache
+            .insert(statistics.key(query), metadata.into());
+    }
+}
+
 impl From<HistogramMetadataInProgress> for HistogramMetadata {
     fn from(metadata: HistogramMetadataInProgress) -> Self {
         Self {
@@ -598,8 +836,8 @@ mod tests {
     use super::*;
 
     use crate::engine::{
-        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterOperator,
-        RasterResultDescriptor, StaticMetaData, VectorColumnInfo, VectorOperator,
+        ChunkByteSize, InMemoryStatisticsCache, MockExecutionContext, MockQueryContext,
+        RasterOperator, RasterResultDescriptor, StaticMetaData, VectorColumnInfo, VectorOperator,
         VectorResultDescriptor,
     };
     use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
@@ -614,7 +852,7 @@ mod tests {
     use geoengine_datatypes::raster::{
         EmptyGrid2D, Grid2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
     };
-    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_datatypes::util::Identifier;
     use geoengine_datatypes::{
@@ -633,6 +871,7 @@ mod tests {
                     max: 10.0,
                 },
                 buckets: Some(15),
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
@@ -676,6 +915,7 @@ mod tests {
                 column_name: None,
                 bounds: HistogramBounds::Data(Default::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
@@ -713,6 +953,7 @@ mod tests {
                 column_name: Some("foo".to_string()),
                 bounds: HistogramBounds::Values { min: 0.0, max: 8.0 },
                 buckets: Some(3),
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: mock_raster_source().into(),
@@ -768,6 +1009,7 @@ mod tests {
                 column_name: None,
                 bounds: HistogramBounds::Values { min: 0.0, max: 8.0 },
                 buckets: Some(3),
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: mock_raster_source().into(),
@@ -820,6 +1062,7 @@ mod tests {
                 column_name: None,
                 bounds: HistogramBounds::Data(Default::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: mock_raster_source().into(),
@@ -881,6 +1124,7 @@ mod tests {
                 column_name: Some("foo".to_string()),
                 bounds: HistogramBounds::Values { min: 0.0, max: 8.0 },
                 buckets: Some(3),
+                bucketing: HistogramBucketing::Linear,
                 interactive: true,
             },
             sources: vector_source.into(),
@@ -922,6 +1166,136 @@ mod tests {
         );
     }
 
+    async fn vector_histogram(params: HistogramParams) -> Result<PlotData> {
+        let vector_source = MockFeatureCollectionSource::multiple(vec![
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 8],
+                &[("foo", FeatureData::Int(vec![1, 1, 2, 2, 3, 3, 4, 4]))],
+            )
+            .unwrap(),
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[("foo", FeatureData::Int(vec![5, 6, 7, 8]))],
+            )
+            .unwrap(),
+        ])
+        .boxed();
+
+        let query_processor = Histogram {
+            params,
+            sources: vector_source.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?
+        .query_processor()?
+        .json_vega()
+        .unwrap();
+
+        query_processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn vector_data_logarithmic() {
+        let result = vector_histogram(HistogramParams {
+            column_name: Some("foo".to_string()),
+            bounds: HistogramBounds::Data(Default::default()),
+            buckets: Some(2),
+            bucketing: HistogramBucketing::Logarithmic,
+            interactive: false,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result,
+            geoengine_datatypes::plots::Histogram::builder(2, 1., 8., Measurement::Unitless)
+                .bucketing(HistogramBucketing::Logarithmic)
+                .counts(vec![4, 8])
+                .build()
+                .unwrap()
+                .to_vega_embeddable(false)
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn vector_data_with_bucket_edges() {
+        let edges = vec![0., 2., 4., 8.];
+
+        let result = vector_histogram(HistogramParams {
+            column_name: Some("foo".to_string()),
+            bounds: HistogramBounds::Data(Default::default()),
+            buckets: None,
+            bucketing: HistogramBucketing::Edges(edges.clone()),
+            interactive: false,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result,
+            geoengine_datatypes::plots::Histogram::builder(3, 0., 8., Measurement::Unitless)
+                .bucketing(HistogramBucketing::Edges(edges))
+                .counts(vec![2, 4, 6])
+                .build()
+                .unwrap()
+                .to_vega_embeddable(false)
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_bucketing() {
+        let params = |bounds, buckets, bucketing| HistogramParams {
+            column_name: Some("foo".to_string()),
+            bounds,
+            buckets,
+            bucketing,
+            interactive: false,
+        };
+
+        for params in [
+            params(
+                HistogramBounds::Values { min: 0.0, max: 8.0 },
+                None,
+                HistogramBucketing::Logarithmic,
+            ),
+            params(
+                HistogramBounds::Data(Default::default()),
+                None,
+                HistogramBucketing::Edges(vec![0., 4., 2.]),
+            ),
+            params(
+                HistogramBounds::Data(Default::default()),
+                Some(3),
+                HistogramBucketing::Edges(vec![0., 2., 4.]),
+            ),
+            params(
+                HistogramBounds::Values { min: 0.0, max: 8.0 },
+                None,
+                HistogramBucketing::Edges(vec![0., 2., 4.]),
+            ),
+        ] {
+            assert!(matches!(
+                vector_histogram(params).await,
+                Err(Error::InvalidOperatorSpec { .. })
+            ));
+        }
+    }
+
     #[tokio::test]
     async fn vector_data_with_nulls() {
         let vector_source = MockFeatureCollectionSource::single(
@@ -949,6 +1323,7 @@ mod tests {
                 column_name: Some("foo".to_string()),
                 bounds: HistogramBounds::Data(Default::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: vector_source.into(),
@@ -990,6 +1365,183 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn vector_data_with_cached_statistics() {
+        let vector_source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[("foo", FeatureData::Float(vec![1., 2., 4., 5.]))],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let cache = Arc::new(InMemoryStatisticsCache::new(10));
+        let key = StatisticsKey::new(
+            serde_json::to_string(&vector_source).unwrap(),
+            vector_source.data_ids(),
+            Some("foo".to_string()),
+            &query,
+        );
+
+        // differs from the data to show that the bounds are not computed
+        cache.insert(
+            key.clone(),
+            ValueStatistics {
+                count: 4,
+                min: 0.,
+                max: 8.,
+            },
+        );
+
+        let histogram = Histogram {
+            params: HistogramParams {
+                column_name: Some("foo".to_string()),
+                bounds: HistogramBounds::Data(Default::default()),
+                buckets: None,
+                bucketing: HistogramBucketing::Linear,
+                interactive: false,
+            },
+            sources: vector_source.into(),
+        };
+
+        let mut execution_context = MockExecutionContext::test_default();
+        execution_context.statistics_cache = Some(cache);
+
+        let query_processor = histogram
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_vega()
+            .unwrap();
+
+        let result = query_processor
+            .plot_query(query, &MockQueryContext::new(ChunkByteSize::MIN))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            geoengine_datatypes::plots::Histogram::builder(2, 0., 8., Measurement::Unitless)
+                .counts(vec![2, 2])
+                .build()
+                .unwrap()
+                .to_vega_embeddable(false)
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn vector_data_caches_statistics() {
+        let vector_source = MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[TimeInterval::default(); 4],
+                &[("foo", FeatureData::Float(vec![1., 2., 4., 5.]))],
+            )
+            .unwrap(),
+        )
+        .boxed();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let cache = Arc::new(InMemoryStatisticsCache::new(10));
+        let key = StatisticsKey::new(
+            serde_json::to_string(&vector_source).unwrap(),
+            vector_source.data_ids(),
+            Some("foo".to_string()),
+            &query,
+        );
+
+        let histogram = Histogram {
+            params: HistogramParams {
+                column_name: Some("foo".to_string()),
+                bounds: HistogramBounds::Data(Default::default()),
+                buckets: None,
+                bucketing: HistogramBucketing::Linear,
+                interactive: false,
+            },
+            sources: vector_source.into(),
+        };
+
+        let mut execution_context = MockExecutionContext::test_default();
+        execution_context.statistics_cache = Some(cache.clone());
+
+        histogram
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_vega()
+            .unwrap()
+            .plot_query(query, &MockQueryContext::new(ChunkByteSize::MIN))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.get(&key),
+            Some(ValueStatistics {
+                count: 4,
+                min: 1.,
+                max: 5.,
+            })
+        );
+    }
+
+    #[test]
+    fn it_uses_dataset_statistics_as_bounds() {
+        let statistics = ValueStatistics {
+            count: 10,
+            min: 0.,
+            max: 8.,
+        };
+        let params = |buckets| HistogramParams {
+            column_name: None,
+            bounds: HistogramBounds::Data(Default::default()),
+            buckets,
+            bucketing: HistogramBucketing::Linear,
+            interactive: false,
+        };
+        let result_descriptor = PlotResultDescriptor {
+            spatial_reference: SpatialReferenceOption::Unreferenced,
+            time: None,
+            bbox: None,
+        };
+
+        let histogram = InitializedHistogram::new(result_descriptor, params(Some(4)), ())
+            .with_dataset_statistics(Some(statistics));
+        assert_eq!(
+            HistogramMetadata::try_from(histogram.metadata),
+            Ok(HistogramMetadata {
+                number_of_buckets: 4,
+                min: 0.,
+                max: 8.,
+            })
+        );
+
+        // the number of buckets depends on the number of queried values
+        let histogram = InitializedHistogram::new(result_descriptor, params(None), ())
+            .with_dataset_statistics(Some(statistics));
+        assert!(histogram.metadata.min.is_none());
+        assert!(histogram.metadata.max.is_none());
+    }
+
     #[tokio::test]
     #[allow(clippy::too_many_lines)]
     async fn text_attribute() {
@@ -1046,6 +1598,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1119,6 +1672,7 @@ mod tests {
                 column_name: None,
                 bounds: HistogramBounds::Data(Data::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: MockRasterSource {
@@ -1195,6 +1749,7 @@ mod tests {
                 column_name: Some("foo".to_string()),
                 bounds: HistogramBounds::Data(Default::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: vector_source.into(),
@@ -1258,6 +1813,7 @@ mod tests {
                 column_name: Some("foo".to_string()),
                 bounds: HistogramBounds::Data(Default::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: vector_source.into(),
@@ -1317,6 +1873,7 @@ mod tests {
                 column_name: None,
                 bounds: HistogramBounds::Data(Data::default()),
                 buckets: None,
+                bucketing: HistogramBucketing::Linear,
                 interactive: false,
             },
             sources: MockRasterSource {
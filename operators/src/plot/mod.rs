@@ -1,8 +1,11 @@
 mod box_plot;
 mod class_histogram;
+mod confusion_matrix;
 mod histogram;
+mod raster_percentiles;
 mod scatter_plot;
 mod statistics;
+mod temporal_histogram;
 mod temporal_raster_mean_plot;
 mod temporal_vector_line_plot;
 
@@ -10,14 +13,27 @@ pub use self::class_histogram::{
     ClassHistogram, ClassHistogramParams, ClassHistogramRasterQueryProcessor,
     ClassHistogramVectorQueryProcessor, InitializedClassHistogram,
 };
+pub use self::confusion_matrix::{
+    ConfusionMatrix, ConfusionMatrixParams, ConfusionMatrixQueryProcessor, ConfusionMatrixSources,
+    InitializedConfusionMatrix,
+};
 pub use self::histogram::{
     Histogram, HistogramBounds, HistogramParams, HistogramRasterQueryProcessor,
     HistogramVectorQueryProcessor, InitializedHistogram,
 };
+pub use self::raster_percentiles::{
+    InitializedRasterPercentiles, RasterPercentiles, RasterPercentilesParams,
+    RasterPercentilesQueryProcessor,
+};
 pub use self::statistics::{
     InitializedStatistics, Statistics, StatisticsParams, StatisticsRasterQueryProcessor,
     StatisticsVectorQueryProcessor,
 };
+pub use self::temporal_histogram::{
+    InitializedTemporalHistogram, TemporalHistogram, TemporalHistogramGranularity,
+    TemporalHistogramParams, TemporalHistogramRasterQueryProcessor,
+    TemporalHistogramVectorQueryProcessor,
+};
 pub use self::temporal_raster_mean_plot::{
     InitializedMeanRasterPixelValuesOverTime, MeanRasterPixelValuesOverTime,
     MeanRasterPixelValuesOverTimeParams, MeanRasterPixelValuesOverTimeQueryProcessor,
@@ -0,0 +1,249 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, CreateSpan, ExecutionContext, InitializedPlotOperator,
+    InitializedRasterOperator, Operator, OperatorName, PlotOperator, PlotQueryProcessor,
+    PlotResultDescriptor, QueryContext, QueryProcessor, SingleRasterSource,
+    TypedPlotQueryProcessor,
+};
+use crate::error;
+use crate::util::statistics::{tile_digest, TDigest};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::{TryFutureExt, TryStreamExt};
+use geoengine_datatypes::primitives::VectorQueryRectangle;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+pub const RASTER_PERCENTILES_OPERATOR_NAME: &str = "Raster Percentiles";
+
+/// A plot that estimates percentiles of the pixel values of a raster over the query extent and time.
+///
+/// The percentiles are approximated with t-digests, so that the pixel values do not need to be sorted in memory.
+pub type RasterPercentiles = Operator<RasterPercentilesParams, SingleRasterSource>;
+
+impl OperatorName for RasterPercentiles {
+    const TYPE_NAME: &'static str = "RasterPercentiles";
+}
+
+/// The parameter spec for `RasterPercentiles`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterPercentilesParams {
+    /// The percentiles to estimate, between `0` and `100`
+    pub percentiles: Vec<f64>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for RasterPercentiles {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        ensure!(
+            !self.params.percentiles.is_empty()
+                && self
+                    .params
+                    .percentiles
+                    .iter()
+                    .all(|percentile| (0. ..=100.).contains(percentile)),
+            error::InvalidOperatorSpec {
+                reason: "at least one percentile between 0 and 100 is required".to_string(),
+            }
+        );
+
+        let raster = self.sources.raster.initialize(context).await?;
+
+        let result_descriptor = raster.result_descriptor().clone().into();
+
+        Ok(InitializedRasterPercentiles {
+            result_descriptor,
+            raster,
+            percentiles: self.params.percentiles,
+        }
+        .boxed())
+    }
+
+    span_fn!(RasterPercentiles);
+}
+
+/// The initialization of `RasterPercentiles`
+pub struct InitializedRasterPercentiles {
+    result_descriptor: PlotResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    percentiles: Vec<f64>,
+}
+
+impl InitializedPlotOperator for InitializedRasterPercentiles {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        Ok(TypedPlotQueryProcessor::JsonPlain(
+            RasterPercentilesQueryProcessor {
+                raster: self.raster.query_processor()?.into_f64(),
+                percentiles: self.percentiles.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that estimates the percentiles of its raster input.
+pub struct RasterPercentilesQueryProcessor {
+    raster: BoxRasterQueryProcessor<f64>,
+    percentiles: Vec<f64>,
+}
+
+#[async_trait]
+impl PlotQueryProcessor for RasterPercentilesQueryProcessor {
+    type OutputFormat = serde_json::Value;
+
+    fn plot_type(&self) -> &'static str {
+        RASTER_PERCENTILES_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let digest = self
+            .raster
+            .query(query.into(), ctx)
+            .await?
+            .and_then(move |tile| {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    tile_digest(&tile)
+                })
+                .map_err(Into::into)
+            })
+            .try_fold(TDigest::default(), |mut digest, tile_digest| async move {
+                digest.merge(&tile_digest);
+                Ok(digest)
+            })
+            .await?;
+
+        let output = RasterPercentilesOutput {
+            count: digest.count(),
+            min: digest.min(),
+            max: digest.max(),
+            percentiles: self
+                .percentiles
+                .iter()
+                .map(|&percentile| PercentileOutput {
+                    percentile,
+                    value: digest.quantile(percentile / 100.),
+                })
+                .collect(),
+        };
+
+        serde_json::to_value(&output).map_err(Into::into)
+    }
+}
+
+/// The percentiles output of `RasterPercentiles`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RasterPercentilesOutput {
+    /// The number of valid pixels
+    count: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    percentiles: Vec<PercentileOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PercentileOutput {
+    percentile: f64,
+    value: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterOperator};
+    use crate::util::test::{grid_2x2, mock_raster_source, tile_2x2};
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TilingSpecification;
+    use serde_json::json;
+
+    fn raster_percentiles(percentiles: Vec<f64>) -> RasterPercentiles {
+        RasterPercentiles {
+            params: RasterPercentilesParams { percentiles },
+            sources: mock_raster_source(vec![
+                tile_2x2(
+                    TimeInterval::new_unchecked(0, 10),
+                    grid_2x2(vec![1_u8, 2, 3, 4]),
+                ),
+                tile_2x2(
+                    TimeInterval::new_unchecked(10, 20),
+                    grid_2x2(vec![5_u8, 6, 7, 8]),
+                ),
+            ])
+            .boxed()
+            .into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_estimates_percentiles_over_space_and_time() {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        let processor = raster_percentiles(vec![0., 50., 100.])
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap();
+
+        let result = processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 20),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "count": 8,
+                "min": 1.0,
+                "max": 8.0,
+                "percentiles": [
+                    { "percentile": 0.0, "value": 1.0 },
+                    { "percentile": 50.0, "value": 4.5 },
+                    { "percentile": 100.0, "value": 8.0 },
+                ],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_percentiles() {
+        let execution_context = MockExecutionContext::test_default();
+
+        assert!(raster_percentiles(vec![])
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .is_err());
+        assert!(raster_percentiles(vec![50., 101.])
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .is_err());
+    }
+}
@@ -912,7 +912,7 @@ mod tests {
                     "validCount": 3,
                     "min": 1.0,
                     "max": 6.0,
-                    "mean": 3.333_333_333_333_333,
+                    "mean": 3.333_333_333_333_333_5,
                     "stddev": 2.054_804_667_656_325_6
                 },
                 "bar": {
@@ -920,7 +920,7 @@ mod tests {
                     "validCount": 3,
                     "min": 1.0,
                     "max": 5.0,
-                    "mean": 2.666_666_666_666_667,
+                    "mean": 2.666_666_666_666_666_5,
                     "stddev": 1.699_673_171_197_595
                 },
             })
@@ -1008,7 +1008,7 @@ mod tests {
                     "validCount": 3,
                     "min": 1.0,
                     "max": 6.0,
-                    "mean": 3.333_333_333_333_333,
+                    "mean": 3.333_333_333_333_333_5,
                     "stddev": 2.054_804_667_656_325_6
                 },
             })
@@ -1096,7 +1096,7 @@ mod tests {
                     "validCount": 3,
                     "min": 1.0,
                     "max": 6.0,
-                    "mean": 3.333_333_333_333_333,
+                    "mean": 3.333_333_333_333_333_5,
                     "stddev": 2.054_804_667_656_325_6
                 },
                 "bar": {
@@ -1104,7 +1104,7 @@ mod tests {
                     "validCount": 3,
                     "min": 1.0,
                     "max": 5.0,
-                    "mean": 2.666_666_666_666_667,
+                    "mean": 2.666_666_666_666_666_5,
                     "stddev": 1.699_673_171_197_595
                 },
             })
@@ -0,0 +1,543 @@
+use crate::engine::{CreateSpan, QueryProcessor};
+use crate::error;
+use crate::util::Result;
+use crate::{
+    engine::{
+        ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+        InitializedVectorOperator, Operator, OperatorName, PlotOperator, PlotQueryProcessor,
+        PlotResultDescriptor, QueryContext, SingleRasterOrVectorSource, TypedPlotQueryProcessor,
+        TypedRasterQueryProcessor, TypedVectorQueryProcessor,
+    },
+    util::input::RasterOrVectorOperator,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use geoengine_datatypes::collections::FeatureCollectionInfos;
+use geoengine_datatypes::plots::{BarChart, Plot, PlotData};
+use geoengine_datatypes::primitives::{
+    TimeGranularity, TimeInstance, TimeInterval, TimeStep, TimeStepIter, VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::BTreeMap;
+use tracing::{span, Level};
+
+pub const TEMPORAL_HISTOGRAM_OPERATOR_NAME: &str = "TemporalHistogram";
+
+/// The maximum number of time buckets of a query, e.g., 30 years of days
+const MAX_NUMBER_OF_TIME_BUCKETS: u32 = 11_000;
+
+/// A bar chart of the number of features or valid raster pixels per time bucket.
+///
+/// Features and tiles are counted in the bucket of their start time.
+/// This gives an overview of the available data before running heavier analyses.
+pub type TemporalHistogram = Operator<TemporalHistogramParams, SingleRasterOrVectorSource>;
+
+impl OperatorName for TemporalHistogram {
+    const TYPE_NAME: &'static str = "TemporalHistogram";
+}
+
+/// The parameter spec for `TemporalHistogram`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalHistogramParams {
+    /// The length of the time buckets
+    pub granularity: TemporalHistogramGranularity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemporalHistogramGranularity {
+    Day,
+    Month,
+    Year,
+}
+
+impl TemporalHistogramGranularity {
+    fn time_step(self) -> TimeStep {
+        TimeStep {
+            granularity: match self {
+                Self::Day => TimeGranularity::Days,
+                Self::Month => TimeGranularity::Months,
+                Self::Year => TimeGranularity::Years,
+            },
+            step: 1,
+        }
+    }
+
+    /// Labels the bucket of a time, which sorts chronologically
+    fn label(self, time: TimeInstance) -> Result<String> {
+        let date_time =
+            time.as_date_time()
+                .ok_or(geoengine_datatypes::error::Error::NoDateTimeValid {
+                    time_instance: time,
+                })?;
+
+        Ok(match self {
+            Self::Day => format!(
+                "{:04}-{:02}-{:02}",
+                date_time.year(),
+                date_time.month(),
+                date_time.day()
+            ),
+            Self::Month => format!("{:04}-{:02}", date_time.year(), date_time.month()),
+            Self::Year => format!("{:04}", date_time.year()),
+        })
+    }
+
+    fn axis_label(self) -> String {
+        match self {
+            Self::Day => "Day",
+            Self::Month => "Month",
+            Self::Year => "Year",
+        }
+        .to_string()
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for TemporalHistogram {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        Ok(match self.sources.source {
+            RasterOrVectorOperator::Raster(raster_source) => {
+                let initialized = raster_source.initialize(context).await?;
+
+                InitializedTemporalHistogram {
+                    result_descriptor: initialized.result_descriptor().clone().into(),
+                    granularity: self.params.granularity,
+                    source: initialized,
+                }
+                .boxed()
+            }
+            RasterOrVectorOperator::Vector(vector_source) => {
+                let initialized = vector_source.initialize(context).await?;
+
+                InitializedTemporalHistogram {
+                    result_descriptor: initialized.result_descriptor().clone().into(),
+                    granularity: self.params.granularity,
+                    source: initialized,
+                }
+                .boxed()
+            }
+        })
+    }
+
+    span_fn!(TemporalHistogram);
+}
+
+/// The initialization of `TemporalHistogram`
+pub struct InitializedTemporalHistogram<Op> {
+    result_descriptor: PlotResultDescriptor,
+    granularity: TemporalHistogramGranularity,
+    source: Op,
+}
+
+impl InitializedPlotOperator for InitializedTemporalHistogram<Box<dyn InitializedRasterOperator>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = TemporalHistogramRasterQueryProcessor {
+            input: self.source.query_processor()?,
+            granularity: self.granularity,
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+impl InitializedPlotOperator for InitializedTemporalHistogram<Box<dyn InitializedVectorOperator>> {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let processor = TemporalHistogramVectorQueryProcessor {
+            input: self.source.query_processor()?,
+            granularity: self.granularity,
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// A query processor that counts the valid pixels of its raster input per time bucket.
+pub struct TemporalHistogramRasterQueryProcessor {
+    input: TypedRasterQueryProcessor,
+    granularity: TemporalHistogramGranularity,
+}
+
+/// A query processor that counts the features of its vector input per time bucket.
+pub struct TemporalHistogramVectorQueryProcessor {
+    input: TypedVectorQueryProcessor,
+    granularity: TemporalHistogramGranularity,
+}
+
+#[async_trait]
+impl PlotQueryProcessor for TemporalHistogramRasterQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        TEMPORAL_HISTOGRAM_OPERATOR_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut buckets = TimeBuckets::new(self.granularity, query.time_interval)?;
+
+        call_on_generic_raster_processor!(&self.input, processor => {
+            let mut query = processor.query(query.into(), ctx).await?;
+
+            while let Some(tile) = query.next().await {
+                let tile = tile?;
+
+                match tile.grid_array {
+                    geoengine_datatypes::raster::GridOrEmpty::Grid(g) => {
+                        let valid_pixels = g.validity_mask.data.iter().filter(|valid| **valid).count();
+                        buckets.add(tile.time.start(), valid_pixels as u64)?;
+                    }
+                    geoengine_datatypes::raster::GridOrEmpty::Empty(_) => (), // ignore no data
+                }
+            }
+        });
+
+        buckets.into_chart()
+    }
+}
+
+#[async_trait]
+impl PlotQueryProcessor for TemporalHistogramVectorQueryProcessor {
+    type OutputFormat = PlotData;
+
+    fn plot_type(&self) -> &'static str {
+        TEMPORAL_HISTOGRAM_OPERATOR_NAME
+    }
+
+    async fn plot_query<'p>(
+        &'p self,
+        query: VectorQueryRectangle,
+        ctx: &'p dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let mut buckets = TimeBuckets::new(self.granularity, query.time_interval)?;
+
+        call_on_generic_vector_processor!(&self.input, processor => {
+            let mut query = processor.query(query, ctx).await?;
+
+            while let Some(collection) = query.next().await {
+                for time in collection?.time_intervals() {
+                    buckets.add(time.start(), 1)?;
+                }
+            }
+        });
+
+        buckets.into_chart()
+    }
+}
+
+/// The counts of all time buckets of a query interval, including empty ones
+struct TimeBuckets {
+    granularity: TemporalHistogramGranularity,
+    start: TimeInstance,
+    counts: BTreeMap<String, u64>,
+}
+
+impl TimeBuckets {
+    fn new(granularity: TemporalHistogramGranularity, time_interval: TimeInterval) -> Result<Self> {
+        ensure!(
+            !time_interval.start().is_min() && !time_interval.end().is_max(),
+            error::TooManyTimeBuckets {
+                limit: MAX_NUMBER_OF_TIME_BUCKETS
+            }
+        );
+
+        let time_step = granularity.time_step();
+
+        let start =
+            time_step.snap_relative(TimeInstance::from_millis(0)?, time_interval.start())?;
+        let time_interval = TimeInterval::new(start, time_interval.end())?;
+
+        ensure!(
+            time_step.num_steps_in_interval_ceil(time_interval)? <= MAX_NUMBER_OF_TIME_BUCKETS,
+            error::TooManyTimeBuckets {
+                limit: MAX_NUMBER_OF_TIME_BUCKETS
+            }
+        );
+
+        let counts = TimeStepIter::new_with_interval(time_interval, time_step)?
+            .map(|time| Ok((granularity.label(time)?, 0)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            granularity,
+            start,
+            counts,
+        })
+    }
+
+    /// Adds `count` to the bucket of `time`, data that started before the query interval is added to the first bucket
+    fn add(&mut self, time: TimeInstance, count: u64) -> Result<()> {
+        let label = self.granularity.label(time.max(self.start))?;
+
+        if let Some(bucket) = self.counts.get_mut(&label) {
+            *bucket += count;
+        }
+
+        Ok(())
+    }
+
+    fn into_chart(self) -> Result<PlotData> {
+        let chart = BarChart::new(
+            self.counts,
+            self.granularity.axis_label(),
+            "Frequency".to_string(),
+        );
+
+        Ok(chart.to_vega_embeddable(false)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+        VectorOperator,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, DateTime, FeatureData, Measurement, NoGeometry, SpatialResolution,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, MaskedGrid2D, RasterDataType, RasterTile2D, TileInformation,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn time(year: i32, month: u8, day: u8) -> TimeInstance {
+        DateTime::new_utc(year, month, day, 0, 0, 0).into()
+    }
+
+    fn query(start: TimeInstance, end: TimeInstance) -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+            time_interval: TimeInterval::new(start, end).unwrap(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    fn bar_chart(x_label: &str, bars: &[(&str, u64)]) -> PlotData {
+        BarChart::new(
+            bars.iter()
+                .map(|(label, count)| ((*label).to_string(), *count))
+                .collect(),
+            x_label.to_string(),
+            "Frequency".to_string(),
+        )
+        .to_vega_embeddable(false)
+        .unwrap()
+    }
+
+    fn vector_source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            DataCollection::from_slices(
+                &[] as &[NoGeometry],
+                &[
+                    TimeInterval::new(time(2013, 12, 1), time(2014, 2, 1)).unwrap(),
+                    TimeInterval::new_instant(time(2014, 1, 5)).unwrap(),
+                    TimeInterval::new_instant(time(2014, 1, 20)).unwrap(),
+                    TimeInterval::new_instant(time(2014, 3, 31)).unwrap(),
+                ],
+                &[] as &[(&str, FeatureData)],
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    #[test]
+    fn serialization() {
+        let serialized = json!({
+            "type": "TemporalHistogram",
+            "params": {
+                "granularity": "month",
+            },
+            "sources": {
+                "source": {
+                    "type": "MockFeatureCollectionSourceMultiPoint",
+                    "params": {
+                        "collections": [],
+                        "spatialReference": "EPSG:4326",
+                        "measurements": {},
+                    }
+                }
+            }
+        })
+        .to_string();
+
+        let deserialized: TemporalHistogram = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.params,
+            TemporalHistogramParams {
+                granularity: TemporalHistogramGranularity::Month,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn vector_data_per_month() {
+        let processor = TemporalHistogram {
+            params: TemporalHistogramParams {
+                granularity: TemporalHistogramGranularity::Month,
+            },
+            sources: vector_source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .json_vega()
+        .unwrap();
+
+        let result = processor
+            .plot_query(
+                query(time(2014, 1, 1), time(2014, 4, 1)),
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            bar_chart("Month", &[("2014-01", 3), ("2014-02", 0), ("2014-03", 1)])
+        );
+    }
+
+    #[tokio::test]
+    async fn raster_data_per_year() {
+        let tile = |start: TimeInstance, end: TimeInstance, validity: Vec<bool>| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new(start, end).unwrap(),
+                TileInformation {
+                    global_geo_transform: TestDefault::test_default(),
+                    global_tile_position: [0, 0].into(),
+                    tile_size_in_pixels: [3, 2].into(),
+                },
+                MaskedGrid2D::new(
+                    Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6]).unwrap(),
+                    Grid2D::new([3, 2].into(), validity).unwrap(),
+                )
+                .unwrap()
+                .into(),
+            )
+        };
+
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile(
+                        time(2014, 1, 1),
+                        time(2014, 7, 1),
+                        vec![true, true, true, false, false, false],
+                    ),
+                    tile(time(2014, 7, 1), time(2015, 1, 1), vec![true; 6]),
+                    tile(
+                        time(2016, 1, 1),
+                        time(2017, 1, 1),
+                        vec![false, true, false, false, false, false],
+                    ),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed();
+
+        let processor = TemporalHistogram {
+            params: TemporalHistogramParams {
+                granularity: TemporalHistogramGranularity::Year,
+            },
+            sources: raster_source.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .json_vega()
+        .unwrap();
+
+        let result = processor
+            .plot_query(
+                query(time(2014, 3, 1), time(2017, 1, 1)),
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            bar_chart("Year", &[("2014", 9), ("2015", 0), ("2016", 1)])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_too_many_buckets() {
+        let processor = TemporalHistogram {
+            params: TemporalHistogramParams {
+                granularity: TemporalHistogramGranularity::Day,
+            },
+            sources: vector_source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .json_vega()
+        .unwrap();
+
+        let unbounded = processor
+            .plot_query(
+                query(TimeInstance::MIN, TimeInstance::MAX),
+                &MockQueryContext::test_default(),
+            )
+            .await;
+        assert!(matches!(
+            unbounded,
+            Err(error::Error::TooManyTimeBuckets { .. })
+        ));
+
+        let too_long = processor
+            .plot_query(
+                query(time(1900, 1, 1), time(2000, 1, 1)),
+                &MockQueryContext::test_default(),
+            )
+            .await;
+        assert!(matches!(
+            too_long,
+            Err(error::Error::TooManyTimeBuckets { .. })
+        ));
+    }
+}
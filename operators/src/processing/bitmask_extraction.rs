@@ -0,0 +1,579 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{Measurement, RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromIndexFnParallel, FromPrimitive, GridIndexAccess, GridOrEmpty, GridShapeAccess,
+    Pixel, RasterDataType, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::adapters::{QueryWrapper, RasterTimeAdapter};
+use crate::engine::{
+    BoxRasterQueryProcessor, CreateSpan, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Extracts a range of bits from an integer raster, e.g., the cloud flags of a quality band.
+///
+/// The extracted bits are either output as values or used to mask another raster.
+pub type BitmaskExtraction = Operator<BitmaskExtractionParams, BitmaskExtractionSources>;
+
+impl OperatorName for BitmaskExtraction {
+    const TYPE_NAME: &'static str = "BitmaskExtraction";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitmaskExtractionParams {
+    /// The position of the first bit, starting at zero for the least significant bit
+    pub start_bit: u8,
+    /// The number of bits to extract
+    #[serde(default = "default_bit_count")]
+    pub bit_count: u8,
+    #[serde(default)]
+    pub output: BitmaskOutput,
+}
+
+fn default_bit_count() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum BitmaskOutput {
+    /// Outputs the value of the extracted bits as unsigned integer
+    #[default]
+    Bits,
+    /// Outputs the `raster` source, where pixels whose extracted bits equal one of the `invalidValues` are no-data.
+    /// Pixels with no-data flags are no-data as well.
+    #[serde(rename_all = "camelCase")]
+    Mask { invalid_values: Vec<u64> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitmaskExtractionSources {
+    /// The integer raster that contains the bit flags
+    pub flags: Box<dyn RasterOperator>,
+    /// The raster that is masked by the `Mask` output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raster: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorData for BitmaskExtractionSources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<DataId>) {
+        self.flags.data_ids_collect(data_ids);
+        if let Some(raster) = &self.raster {
+            raster.data_ids_collect(data_ids);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BitRange {
+    start_bit: u8,
+    mask: u64,
+}
+
+impl BitRange {
+    fn new(start_bit: u8, bit_count: u8) -> Self {
+        let mask = if bit_count >= 64 {
+            u64::MAX
+        } else {
+            (1 << bit_count) - 1
+        };
+
+        Self { start_bit, mask }
+    }
+
+    fn extract(self, value: u64) -> u64 {
+        (value >> self.start_bit) & self.mask
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for BitmaskExtraction {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let flags = self.sources.flags.initialize(context).await?;
+        let flags_rd = flags.result_descriptor();
+
+        ensure!(
+            !matches!(
+                flags_rd.data_type,
+                RasterDataType::F32 | RasterDataType::F64
+            ),
+            error::InvalidType {
+                expected: "integer raster".to_string(),
+                found: format!("{:?}", flags_rd.data_type),
+            }
+        );
+
+        let bits = flags_rd.data_type.byte_size() * 8;
+        ensure!(
+            self.params.bit_count > 0
+                && usize::from(self.params.start_bit) + usize::from(self.params.bit_count) <= bits,
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "the bits must lie within the {} bits of the `flags` raster",
+                    bits
+                ),
+            }
+        );
+
+        let (raster, result_descriptor) = match (&self.params.output, self.sources.raster) {
+            (BitmaskOutput::Bits, None) => {
+                let data_type = match self.params.bit_count {
+                    0..=8 => RasterDataType::U8,
+                    9..=16 => RasterDataType::U16,
+                    17..=32 => RasterDataType::U32,
+                    _ => RasterDataType::U64,
+                };
+
+                let result_descriptor = RasterResultDescriptor {
+                    data_type,
+                    measurement: Measurement::Unitless,
+                    ..flags_rd.clone()
+                };
+
+                (None, result_descriptor)
+            }
+            (BitmaskOutput::Mask { .. }, Some(raster)) => {
+                let raster = raster.initialize(context).await?;
+                let raster_rd = raster.result_descriptor();
+
+                ensure!(
+                    flags_rd.spatial_reference == raster_rd.spatial_reference,
+                    error::AllSourcesMustHaveSameSpatialReference
+                );
+
+                let result_descriptor = raster_rd.clone();
+
+                (Some(raster), result_descriptor)
+            }
+            (BitmaskOutput::Bits, Some(_)) => {
+                return Err(error::Error::InvalidOperatorSpec {
+                    reason: "the `raster` source requires the `mask` output".to_string(),
+                })
+            }
+            (BitmaskOutput::Mask { .. }, None) => {
+                return Err(error::Error::InvalidOperatorSpec {
+                    reason: "the `mask` output requires a `raster` source".to_string(),
+                })
+            }
+        };
+
+        Ok(InitializedBitmaskExtraction {
+            result_descriptor,
+            flags,
+            raster,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(BitmaskExtraction);
+}
+
+pub struct InitializedBitmaskExtraction {
+    result_descriptor: RasterResultDescriptor,
+    flags: Box<dyn InitializedRasterOperator>,
+    raster: Option<Box<dyn InitializedRasterOperator>>,
+    params: BitmaskExtractionParams,
+}
+
+impl InitializedRasterOperator for InitializedBitmaskExtraction {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let flags = self.flags.query_processor()?.into_u64();
+        let bit_range = BitRange::new(self.params.start_bit, self.params.bit_count);
+
+        Ok(match (&self.params.output, &self.raster) {
+            (BitmaskOutput::Mask { invalid_values }, Some(raster)) => {
+                call_on_generic_raster_processor!(raster.query_processor()?, raster => {
+                    BitmaskApplicationProcessor::new(raster, flags, bit_range, invalid_values.clone())
+                        .boxed()
+                        .into()
+                })
+            }
+            _ => match self.result_descriptor.data_type {
+                RasterDataType::U8 => BitmaskExtractionProcessor::<u8>::new(flags, bit_range)
+                    .boxed()
+                    .into(),
+                RasterDataType::U16 => BitmaskExtractionProcessor::<u16>::new(flags, bit_range)
+                    .boxed()
+                    .into(),
+                RasterDataType::U32 => BitmaskExtractionProcessor::<u32>::new(flags, bit_range)
+                    .boxed()
+                    .into(),
+                _ => BitmaskExtractionProcessor::<u64>::new(flags, bit_range)
+                    .boxed()
+                    .into(),
+            },
+        })
+    }
+}
+
+/// Outputs the value of the extracted bits
+pub struct BitmaskExtractionProcessor<TO> {
+    flags: BoxRasterQueryProcessor<u64>,
+    bit_range: BitRange,
+    _out: PhantomData<TO>,
+}
+
+impl<TO> BitmaskExtractionProcessor<TO>
+where
+    TO: Pixel,
+{
+    fn new(flags: BoxRasterQueryProcessor<u64>, bit_range: BitRange) -> Self {
+        Self {
+            flags,
+            bit_range,
+            _out: PhantomData,
+        }
+    }
+
+    fn extract(tile: &RasterTile2D<u64>, bit_range: BitRange) -> RasterTile2D<TO> {
+        let grid_shape = tile.grid_shape();
+
+        let grid_array = if tile.is_empty() {
+            GridOrEmpty::Empty(EmptyGrid::new(grid_shape))
+        } else {
+            GridOrEmpty::from_index_fn_parallel(&grid_shape, |lin_idx: usize| {
+                tile.get_at_grid_index_unchecked(lin_idx)
+                    .map(|value| TO::from_(bit_range.extract(value)))
+            })
+        };
+
+        RasterTile2D::new_with_properties(
+            tile.time,
+            tile.tile_position,
+            tile.global_geo_transform,
+            grid_array,
+            tile.properties.clone(),
+        )
+    }
+}
+
+#[async_trait]
+impl<TO> QueryProcessor for BitmaskExtractionProcessor<TO>
+where
+    TO: Pixel,
+{
+    type Output = RasterTile2D<TO>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let bit_range = self.bit_range;
+
+        let stream = self
+            .flags
+            .query(query, ctx)
+            .await?
+            .and_then(move |tile| async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    Self::extract(&tile, bit_range)
+                })
+                .await
+                .map_err(Into::into)
+            });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Masks a raster by the extracted bits
+pub struct BitmaskApplicationProcessor<T>
+where
+    T: Pixel,
+{
+    raster: BoxRasterQueryProcessor<T>,
+    flags: BoxRasterQueryProcessor<u64>,
+    bit_range: BitRange,
+    invalid_values: Vec<u64>,
+}
+
+impl<T> BitmaskApplicationProcessor<T>
+where
+    T: Pixel,
+{
+    fn new(
+        raster: BoxRasterQueryProcessor<T>,
+        flags: BoxRasterQueryProcessor<u64>,
+        bit_range: BitRange,
+        invalid_values: Vec<u64>,
+    ) -> Self {
+        Self {
+            raster,
+            flags,
+            bit_range,
+            invalid_values,
+        }
+    }
+
+    fn apply(
+        raster: RasterTile2D<T>,
+        flags: &RasterTile2D<u64>,
+        bit_range: BitRange,
+        invalid_values: &[u64],
+    ) -> RasterTile2D<T> {
+        let grid_shape = raster.grid_shape();
+
+        let grid_array = if raster.is_empty() || flags.is_empty() {
+            GridOrEmpty::Empty(EmptyGrid::new(grid_shape))
+        } else {
+            GridOrEmpty::from_index_fn_parallel(&grid_shape, |lin_idx: usize| {
+                let flag = flags.get_at_grid_index_unchecked(lin_idx)?;
+
+                if invalid_values.contains(&bit_range.extract(flag)) {
+                    None
+                } else {
+                    raster.get_at_grid_index_unchecked(lin_idx)
+                }
+            })
+        };
+
+        RasterTile2D::new_with_properties(
+            raster.time,
+            raster.tile_position,
+            raster.global_geo_transform,
+            grid_array,
+            raster.properties.clone(),
+        )
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for BitmaskApplicationProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let bit_range = self.bit_range;
+
+        let stream = RasterTimeAdapter::new(
+            QueryWrapper {
+                p: &self.raster,
+                ctx,
+            },
+            QueryWrapper {
+                p: &self.flags,
+                ctx,
+            },
+            query,
+        )
+        .and_then(move |(raster, flags)| async move {
+            let invalid_values = self.invalid_values.clone();
+
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::apply(raster, &flags, bit_range, &invalid_values)
+            })
+            .await
+            .map_err(Into::into)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{
+        Grid2D, GridOrEmpty2D, MaskedGrid2D, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn source<T: Pixel>(
+        data_type: RasterDataType,
+        grid: GridOrEmpty2D<T>,
+    ) -> Box<dyn RasterOperator>
+    where
+        MockRasterSource<T>: RasterOperator,
+    {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                    },
+                    grid,
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn processor(operator: BitmaskExtraction) -> Result<TypedRasterQueryProcessor> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        operator
+            .boxed()
+            .initialize(&execution_context)
+            .await?
+            .query_processor()
+    }
+
+    async fn query<T: Pixel>(processor: BoxRasterQueryProcessor<T>) -> Vec<Option<T>> {
+        let tiles: Vec<RasterTile2D<T>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+
+        (0..4)
+            .map(|i| tiles[0].get_at_grid_index_unchecked(i))
+            .collect()
+    }
+
+    fn flags() -> Box<dyn RasterOperator> {
+        source(
+            RasterDataType::U16,
+            MaskedGrid2D::new(
+                Grid2D::new([2, 2].into(), vec![0b0000_u16, 0b0100, 0b1100, 0b1000]).unwrap(),
+                Grid2D::new([2, 2].into(), vec![true, true, true, false]).unwrap(),
+            )
+            .unwrap()
+            .into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn it_extracts_bits() {
+        let processor = processor(BitmaskExtraction {
+            params: BitmaskExtractionParams {
+                start_bit: 2,
+                bit_count: 2,
+                output: BitmaskOutput::Bits,
+            },
+            sources: BitmaskExtractionSources {
+                flags: flags(),
+                raster: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            query(processor.get_u8().unwrap()).await,
+            vec![Some(0), Some(1), Some(3), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_masks_a_raster() {
+        let processor = processor(BitmaskExtraction {
+            params: BitmaskExtractionParams {
+                start_bit: 3,
+                bit_count: 1,
+                output: BitmaskOutput::Mask {
+                    invalid_values: vec![1],
+                },
+            },
+            sources: BitmaskExtractionSources {
+                flags: flags(),
+                raster: Some(source(
+                    RasterDataType::F32,
+                    Grid2D::new([2, 2].into(), vec![0.1_f32, 0.2, 0.3, 0.4])
+                        .unwrap()
+                        .into(),
+                )),
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            query(processor.get_f32().unwrap()).await,
+            vec![Some(0.1), Some(0.2), None, None]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_bit_range() {
+        assert!(processor(BitmaskExtraction {
+            params: BitmaskExtractionParams {
+                start_bit: 15,
+                bit_count: 2,
+                output: BitmaskOutput::Bits,
+            },
+            sources: BitmaskExtractionSources {
+                flags: flags(),
+                raster: None,
+            },
+        })
+        .await
+        .is_err());
+
+        assert!(processor(BitmaskExtraction {
+            params: BitmaskExtractionParams {
+                start_bit: 0,
+                bit_count: 1,
+                output: BitmaskOutput::Mask {
+                    invalid_values: vec![1],
+                },
+            },
+            sources: BitmaskExtractionSources {
+                flags: flags(),
+                raster: None,
+            },
+        })
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,528 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    Measurement, RasterQueryRectangle, SpatialPartition2D, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromIndexFnParallel, FromPrimitive, GridIndexAccess, GridOrEmpty, GridShapeAccess,
+    Pixel, RasterDataType, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::adapters::{QueryWrapper, RasterArrayTimeAdapter};
+use crate::engine::{
+    BoxRasterQueryProcessor, CreateSpan, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Compares two raster time steps pixel by pixel.
+///
+/// The time steps are either taken from a single source at two time instants or from two sources.
+/// Pixels that are no-data in either of the time steps are no-data in the output.
+pub type ChangeDetection = Operator<ChangeDetectionParams, ChangeDetectionSources>;
+
+impl OperatorName for ChangeDetection {
+    const TYPE_NAME: &'static str = "ChangeDetection";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionParams {
+    pub output: ChangeDetectionOutput,
+    /// The time instants that are compared.
+    /// If they are omitted, the time steps of the `before` and `after` sources that are valid at the same time are compared.
+    #[serde(default)]
+    pub time_instants: Option<ChangeDetectionInstants>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionInstants {
+    pub before: TimeInstance,
+    pub after: TimeInstance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ChangeDetectionOutput {
+    /// `after - before`
+    Difference,
+    /// `after / before`, no-data if `before` is zero
+    Ratio,
+    /// Classifies the difference into decrease, no change and increase
+    #[serde(rename_all = "camelCase")]
+    Categories {
+        /// Differences below this threshold are a decrease
+        decrease_threshold: f64,
+        /// Differences above this threshold are an increase
+        increase_threshold: f64,
+    },
+}
+
+/// The classes of the `Categories` output
+pub const NO_CHANGE_CLASS: u8 = 0;
+pub const DECREASE_CLASS: u8 = 1;
+pub const INCREASE_CLASS: u8 = 2;
+
+impl ChangeDetectionOutput {
+    fn change(&self, before: f64, after: f64) -> Option<f64> {
+        match *self {
+            ChangeDetectionOutput::Difference => Some(after - before),
+            ChangeDetectionOutput::Ratio => (before != 0.).then(|| after / before),
+            ChangeDetectionOutput::Categories {
+                decrease_threshold,
+                increase_threshold,
+            } => {
+                let difference = after - before;
+                let class = if difference < decrease_threshold {
+                    DECREASE_CLASS
+                } else if difference > increase_threshold {
+                    INCREASE_CLASS
+                } else {
+                    NO_CHANGE_CLASS
+                };
+                Some(f64::from(class))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionSources {
+    pub before: Box<dyn RasterOperator>,
+    /// The raster of the later time step. If it is omitted, both time steps are taken from `before`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorData for ChangeDetectionSources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<geoengine_datatypes::dataset::DataId>) {
+        self.before.data_ids_collect(data_ids);
+        if let Some(after) = &self.after {
+            after.data_ids_collect(data_ids);
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for ChangeDetection {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            self.sources.after.is_some() || self.params.time_instants.is_some(),
+            error::InvalidOperatorSpec {
+                reason: "`timeInstants` are required if there is no `after` source".to_string(),
+            }
+        );
+
+        if let ChangeDetectionOutput::Categories {
+            decrease_threshold,
+            increase_threshold,
+        } = self.params.output
+        {
+            ensure!(
+                decrease_threshold <= increase_threshold,
+                error::InvalidOperatorSpec {
+                    reason: "`decreaseThreshold` must not be larger than `increaseThreshold`"
+                        .to_string(),
+                }
+            );
+        }
+
+        let before = self.sources.before.initialize(context).await?;
+        let after = match self.sources.after {
+            Some(after) => Some(after.initialize(context).await?),
+            None => None,
+        };
+
+        let before_rd = before.result_descriptor();
+
+        let mut bbox = before_rd.bbox;
+        if let Some(after) = &after {
+            let after_rd = after.result_descriptor();
+
+            ensure!(
+                before_rd.spatial_reference == after_rd.spatial_reference,
+                error::AllSourcesMustHaveSameSpatialReference
+            );
+
+            // the output is only valid where both sources have data
+            bbox = match (bbox, after_rd.bbox) {
+                (Some(a), Some(b)) => a.intersection(&b),
+                (a, b) => a.or(b),
+            };
+        }
+
+        let (data_type, measurement) = match self.params.output {
+            ChangeDetectionOutput::Difference => {
+                (RasterDataType::F64, before_rd.measurement.clone())
+            }
+            ChangeDetectionOutput::Ratio => (RasterDataType::F64, Measurement::Unitless),
+            ChangeDetectionOutput::Categories { .. } => (
+                RasterDataType::U8,
+                Measurement::classification(
+                    "change".to_string(),
+                    [
+                        (NO_CHANGE_CLASS, "no change".to_string()),
+                        (DECREASE_CLASS, "decrease".to_string()),
+                        (INCREASE_CLASS, "increase".to_string()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            ),
+        };
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type,
+            spatial_reference: before_rd.spatial_reference,
+            measurement,
+            time: if self.params.time_instants.is_some() {
+                None
+            } else {
+                before_rd.time
+            },
+            bbox,
+            resolution: before_rd.resolution,
+        };
+
+        Ok(InitializedChangeDetection {
+            result_descriptor,
+            before,
+            after,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(ChangeDetection);
+}
+
+pub struct InitializedChangeDetection {
+    result_descriptor: RasterResultDescriptor,
+    before: Box<dyn InitializedRasterOperator>,
+    after: Option<Box<dyn InitializedRasterOperator>>,
+    params: ChangeDetectionParams,
+}
+
+impl InitializedRasterOperator for InitializedChangeDetection {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let before = self.before.query_processor()?.into_f64();
+        let after = match &self.after {
+            Some(after) => Some(after.query_processor()?.into_f64()),
+            None => None,
+        };
+
+        Ok(match self.params.output {
+            ChangeDetectionOutput::Difference | ChangeDetectionOutput::Ratio => {
+                TypedRasterQueryProcessor::F64(
+                    ChangeDetectionProcessor::<f64>::new(before, after, self.params.clone())
+                        .boxed(),
+                )
+            }
+            ChangeDetectionOutput::Categories { .. } => TypedRasterQueryProcessor::U8(
+                ChangeDetectionProcessor::<u8>::new(before, after, self.params.clone()).boxed(),
+            ),
+        })
+    }
+}
+
+pub struct ChangeDetectionProcessor<TO> {
+    before: BoxRasterQueryProcessor<f64>,
+    after: Option<BoxRasterQueryProcessor<f64>>,
+    params: ChangeDetectionParams,
+    _out: PhantomData<TO>,
+}
+
+impl<TO> ChangeDetectionProcessor<TO>
+where
+    TO: Pixel,
+{
+    pub fn new(
+        before: BoxRasterQueryProcessor<f64>,
+        after: Option<BoxRasterQueryProcessor<f64>>,
+        params: ChangeDetectionParams,
+    ) -> Self {
+        Self {
+            before,
+            after,
+            params,
+            _out: PhantomData,
+        }
+    }
+
+    fn compare(
+        output: ChangeDetectionOutput,
+        time: TimeInterval,
+        before: &RasterTile2D<f64>,
+        after: &RasterTile2D<f64>,
+    ) -> RasterTile2D<TO> {
+        let grid_shape = before.grid_shape();
+
+        let grid_array = if before.is_empty() || after.is_empty() {
+            GridOrEmpty::Empty(EmptyGrid::new(grid_shape))
+        } else {
+            GridOrEmpty::from_index_fn_parallel(&grid_shape, |lin_idx: usize| {
+                match (
+                    before.get_at_grid_index_unchecked(lin_idx),
+                    after.get_at_grid_index_unchecked(lin_idx),
+                ) {
+                    (Some(before), Some(after)) => output.change(before, after).map(TO::from_),
+                    _ => None,
+                }
+            })
+        };
+
+        RasterTile2D::new(
+            time,
+            before.tile_position,
+            before.global_geo_transform,
+            grid_array,
+        )
+    }
+}
+
+#[async_trait]
+impl<TO> QueryProcessor for ChangeDetectionProcessor<TO>
+where
+    TO: Pixel,
+{
+    type Output = RasterTile2D<TO>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let after = self.after.as_ref().unwrap_or(&self.before);
+        let output = self.params.output;
+
+        let tile_pairs: BoxStream<'a, Result<[RasterTile2D<f64>; 2]>> =
+            if let Some(instants) = self.params.time_instants {
+                // each instant yields a single time step for every spatial tile, so the streams align
+                let before_query = RasterQueryRectangle {
+                    time_interval: TimeInterval::new_instant(instants.before)?,
+                    ..query
+                };
+                let after_query = RasterQueryRectangle {
+                    time_interval: TimeInterval::new_instant(instants.after)?,
+                    ..query
+                };
+
+                let before_stream = self.before.query(before_query, ctx).await?;
+                let after_stream = after.query(after_query, ctx).await?;
+
+                before_stream
+                    .zip(after_stream)
+                    .map(|(before, after)| {
+                        // the result is the same for the whole query
+                        let mut before = before?;
+                        before.time = query.time_interval;
+                        Ok([before, after?])
+                    })
+                    .boxed()
+            } else {
+                RasterArrayTimeAdapter::new(
+                    [
+                        QueryWrapper {
+                            p: &self.before,
+                            ctx,
+                        },
+                        QueryWrapper { p: after, ctx },
+                    ],
+                    query,
+                )
+                .boxed()
+            };
+
+        let stream = tile_pairs.and_then(move |[before, after]| async move {
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::compare(output, before.time, &before, &after)
+            })
+            .await
+            .map_err(Into::into)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::util::test::{grid_2x2, masked_2x2, mock_raster_source, tile_2x2};
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::raster::TilingSpecification;
+
+    fn source(tiles: Vec<RasterTile2D<i32>>) -> Box<dyn RasterOperator> {
+        mock_raster_source(tiles).boxed()
+    }
+
+    async fn processor(operator: ChangeDetection) -> TypedRasterQueryProcessor {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        operator
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+    }
+
+    async fn query<T: Pixel>(
+        processor: BoxRasterQueryProcessor<T>,
+        time_interval: TimeInterval,
+    ) -> Vec<Option<T>> {
+        let tiles: Vec<RasterTile2D<T>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into())
+                        .unwrap(),
+                    time_interval,
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].time, time_interval);
+
+        (0..4)
+            .map(|i| tiles[0].get_at_grid_index_unchecked(i))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_compares_two_instants_of_a_source() {
+        let operator = ChangeDetection {
+            params: ChangeDetectionParams {
+                output: ChangeDetectionOutput::Difference,
+                time_instants: Some(ChangeDetectionInstants {
+                    before: TimeInstance::from_millis_unchecked(0),
+                    after: TimeInstance::from_millis_unchecked(10),
+                }),
+            },
+            sources: ChangeDetectionSources {
+                before: source(vec![
+                    tile_2x2(
+                        TimeInterval::new_unchecked(0, 10),
+                        grid_2x2(vec![1, 2, 3, 4]),
+                    ),
+                    tile_2x2(
+                        TimeInterval::new_unchecked(10, 20),
+                        grid_2x2(vec![2, 2, 1, 8]),
+                    ),
+                ]),
+                after: None,
+            },
+        };
+
+        assert_eq!(
+            query(
+                processor(operator).await.get_f64().unwrap(),
+                TimeInterval::new_unchecked(0, 20)
+            )
+            .await,
+            vec![Some(1.), Some(0.), Some(-2.), Some(4.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_categorizes_changes_of_two_sources() {
+        let time = TimeInterval::new_unchecked(0, 10);
+
+        let operator = ChangeDetection {
+            params: ChangeDetectionParams {
+                output: ChangeDetectionOutput::Categories {
+                    decrease_threshold: -1.,
+                    increase_threshold: 1.,
+                },
+                time_instants: None,
+            },
+            sources: ChangeDetectionSources {
+                before: source(vec![tile_2x2(time, grid_2x2(vec![5, 5, 5, 5]))]),
+                after: Some(source(vec![tile_2x2(
+                    time,
+                    masked_2x2(vec![1, 5, 9, 0], vec![true, true, true, false]),
+                )])),
+            },
+        };
+
+        assert_eq!(
+            query(processor(operator).await.get_u8().unwrap(), time).await,
+            vec![
+                Some(DECREASE_CLASS),
+                Some(NO_CHANGE_CLASS),
+                Some(INCREASE_CLASS),
+                None
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_outputs_no_data_for_zero_ratios() {
+        let time = TimeInterval::new_unchecked(0, 10);
+
+        let operator = ChangeDetection {
+            params: ChangeDetectionParams {
+                output: ChangeDetectionOutput::Ratio,
+                time_instants: None,
+            },
+            sources: ChangeDetectionSources {
+                before: source(vec![tile_2x2(time, grid_2x2(vec![0, 1, 2, 4]))]),
+                after: Some(source(vec![tile_2x2(time, grid_2x2(vec![1, 1, 1, 1]))])),
+            },
+        };
+
+        assert_eq!(
+            query(processor(operator).await.get_f64().unwrap(), time).await,
+            vec![None, Some(1.), Some(0.5), Some(0.25)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_requires_time_instants_for_a_single_source() {
+        let operator = ChangeDetection {
+            params: ChangeDetectionParams {
+                output: ChangeDetectionOutput::Difference,
+                time_instants: None,
+            },
+            sources: ChangeDetectionSources {
+                before: source(vec![]),
+                after: None,
+            },
+        };
+
+        assert!(operator
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .is_err());
+    }
+}
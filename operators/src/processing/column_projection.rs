@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionModifications};
+use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// The columns that remain after the projection.
+/// Since the columns of feature collections are unordered, the order of the listed columns is irrelevant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ColumnSelection {
+    All,
+    Keep { columns: Vec<String> },
+    Drop { columns: Vec<String> },
+}
+
+impl Default for ColumnSelection {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+/// Selects the attribute columns of a feature collection and renames them.
+/// The renaming is applied after the selection, i.e., it refers to the remaining columns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnProjectionParams {
+    #[serde(default)]
+    pub selection: ColumnSelection,
+    /// Maps old column names to new ones
+    #[serde(default)]
+    pub rename: HashMap<String, String>,
+}
+
+pub type ColumnProjection = Operator<ColumnProjectionParams, SingleVectorSource>;
+
+impl OperatorName for ColumnProjection {
+    const TYPE_NAME: &'static str = "ColumnProjection";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ColumnProjection {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        let selected: Vec<&String> = match &self.params.selection {
+            ColumnSelection::All => vec![],
+            ColumnSelection::Keep { columns } | ColumnSelection::Drop { columns } => {
+                columns.iter().collect()
+            }
+        };
+        for column in selected.into_iter().chain(self.params.rename.keys()) {
+            ensure!(
+                source_descriptor.columns.contains_key(column),
+                error::ColumnDoesNotExist {
+                    column: column.clone(),
+                }
+            );
+        }
+
+        let removed_columns: Vec<String> = source_descriptor
+            .columns
+            .keys()
+            .filter(|column| match &self.params.selection {
+                ColumnSelection::All => false,
+                ColumnSelection::Keep { columns } => !columns.contains(column),
+                ColumnSelection::Drop { columns } => columns.contains(column),
+            })
+            .cloned()
+            .collect();
+
+        ensure!(
+            self.params
+                .rename
+                .keys()
+                .all(|column| !removed_columns.contains(column)),
+            error::InvalidOperatorSpec {
+                reason: "only selected columns can be renamed",
+            }
+        );
+
+        let result_descriptor = source_descriptor.map_columns(|columns| {
+            columns
+                .iter()
+                .filter(|(name, _)| !removed_columns.contains(name))
+                .map(|(name, info)| {
+                    (
+                        self.params.rename.get(name).unwrap_or(name).clone(),
+                        info.clone(),
+                    )
+                })
+                .collect()
+        });
+
+        let output_columns: HashSet<&String> = result_descriptor.columns.keys().collect();
+        ensure!(
+            output_columns.len() == source_descriptor.columns.len() - removed_columns.len(),
+            error::DuplicateOutputColumns
+        );
+
+        Ok(InitializedColumnProjection {
+            result_descriptor,
+            vector_source,
+            removed_columns,
+            renamings: self.params.rename.into_iter().collect(),
+        }
+        .boxed())
+    }
+
+    span_fn!(ColumnProjection);
+}
+
+pub struct InitializedColumnProjection {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    removed_columns: Vec<String>,
+    renamings: Vec<(String, String)>,
+}
+
+impl InitializedVectorOperator for InitializedColumnProjection {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => ColumnProjectionProcessor::new(
+                source,
+                self.removed_columns.clone(),
+                self.renamings.clone(),
+            )
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct ColumnProjectionProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    removed_columns: Vec<String>,
+    renamings: Vec<(String, String)>,
+}
+
+impl<G> ColumnProjectionProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        removed_columns: Vec<String>,
+        renamings: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            removed_columns,
+            renamings,
+        }
+    }
+
+    fn project(&self, collection: &FeatureCollection<G>) -> Result<FeatureCollection<G>> {
+        let removed_columns: Vec<&str> = self.removed_columns.iter().map(String::as_str).collect();
+
+        collection
+            .remove_columns(&removed_columns)?
+            .rename_columns(&self.renamings)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for ColumnProjectionProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| self.project(&collection?));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::primitives::{
+        FeatureData, FeatureDataType, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            [
+                ("id".to_string(), FeatureData::Int(vec![1, 2])),
+                (
+                    "name".to_string(),
+                    FeatureData::Text(vec!["a".to_string(), "b".to_string()]),
+                ),
+                ("value".to_string(), FeatureData::Float(vec![0.5, 1.5])),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_drops_and_renames_columns() {
+        let projection = ColumnProjection {
+            params: ColumnProjectionParams {
+                selection: ColumnSelection::Drop {
+                    columns: vec!["value".to_string()],
+                },
+                rename: [("name".to_string(), "label".to_string())].into(),
+            },
+            sources: MockFeatureCollectionSource::single(collection())
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let mut columns: Vec<_> = projection
+            .result_descriptor()
+            .columns
+            .keys()
+            .cloned()
+            .collect();
+        columns.sort();
+        assert_eq!(columns, vec!["id".to_string(), "label".to_string()]);
+
+        let processor = projection.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].column_types(),
+            [
+                ("id".to_string(), FeatureDataType::Int),
+                ("label".to_string(), FeatureDataType::Text),
+            ]
+            .into()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_conflicting_names() {
+        let projection = ColumnProjection {
+            params: ColumnProjectionParams {
+                selection: ColumnSelection::Keep {
+                    columns: vec!["id".to_string(), "name".to_string()],
+                },
+                rename: [("name".to_string(), "id".to_string())].into(),
+            },
+            sources: MockFeatureCollectionSource::single(collection())
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            projection,
+            Err(error::Error::DuplicateOutputColumns)
+        ));
+    }
+}
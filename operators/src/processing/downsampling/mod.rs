@@ -0,0 +1,741 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::adapters::{FoldTileAccu, RasterSubQueryAdapter, SubQueryTileAggregator};
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, TryFutureExt};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Coordinate2D, RasterQueryRectangle, SpatialPartition2D,
+    SpatialPartitioned, SpatialResolution, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    FromIndexFnParallel, GridIdx, GridIdx2D, GridIndexAccess, GridOrEmpty, GridSize, Pixel,
+    RasterTile2D, TileInformation, TilingSpecification,
+};
+use geoengine_datatypes::util::gdal::ResamplingMethod;
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use tracing::{span, Level};
+
+/// The parameter spec for `Downsampling`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownsamplingParams {
+    /// One of `NEAREST`, `AVERAGE` or `BILINEAR`
+    pub sampling_method: ResamplingMethod,
+    /// The resolution of the output, which must not be finer than the resolution of the source
+    pub output_resolution: SpatialResolution,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum DownsamplingError {
+    #[snafu(display("The resolution of the source is unknown."))]
+    UnknownSourceResolution,
+
+    #[snafu(display(
+        "The output resolution {:?} is finer than the source resolution {:?}.",
+        output,
+        input
+    ))]
+    OutputResolutionTooFine {
+        input: SpatialResolution,
+        output: SpatialResolution,
+    },
+
+    #[snafu(display("The sampling method {} is not supported for downsampling.", method))]
+    UnsupportedSamplingMethod { method: ResamplingMethod },
+}
+
+/// Resamples a raster to a coarser resolution, regardless of the resolution of the query.
+pub type Downsampling = Operator<DownsamplingParams, SingleRasterSource>;
+
+impl OperatorName for Downsampling {
+    const TYPE_NAME: &'static str = "Downsampling";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Downsampling {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            matches!(
+                self.params.sampling_method,
+                ResamplingMethod::Nearest | ResamplingMethod::Average | ResamplingMethod::Bilinear
+            ),
+            error::UnsupportedSamplingMethod {
+                method: self.params.sampling_method,
+            }
+        );
+
+        let raster_source = self.sources.raster.initialize(context).await?;
+        let in_descriptor = raster_source.result_descriptor();
+
+        let input_resolution = in_descriptor
+            .resolution
+            .ok_or(DownsamplingError::UnknownSourceResolution)?;
+        let output_resolution = self.params.output_resolution;
+
+        ensure!(
+            output_resolution.x >= input_resolution.x && output_resolution.y >= input_resolution.y,
+            error::OutputResolutionTooFine {
+                input: input_resolution,
+                output: output_resolution,
+            }
+        );
+
+        let out_descriptor = RasterResultDescriptor {
+            spatial_reference: in_descriptor.spatial_reference,
+            data_type: in_descriptor.data_type,
+            measurement: in_descriptor.measurement.clone(),
+            bbox: in_descriptor.bbox,
+            time: in_descriptor.time,
+            resolution: Some(output_resolution),
+        };
+
+        let initialized_operator = InitializedDownsampling {
+            result_descriptor: out_descriptor,
+            raster_source,
+            sampling_method: self.params.sampling_method,
+            input_resolution,
+            output_resolution,
+            tiling_specification: context.tiling_specification(),
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+
+    span_fn!(Downsampling);
+}
+
+pub struct InitializedDownsampling {
+    result_descriptor: RasterResultDescriptor,
+    raster_source: Box<dyn InitializedRasterOperator>,
+    sampling_method: ResamplingMethod,
+    input_resolution: SpatialResolution,
+    output_resolution: SpatialResolution,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedDownsampling {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.raster_source.query_processor()?;
+
+        let res = call_on_generic_raster_processor!(
+            source_processor, p => match self.sampling_method {
+                ResamplingMethod::Nearest => DownsamplingProcessor::<_, _, NearestDownsampling>::new(
+                        p,
+                        self.input_resolution,
+                        self.output_resolution,
+                        self.tiling_specification,
+                    ).boxed()
+                    .into(),
+                ResamplingMethod::Average => DownsamplingProcessor::<_, _, AverageDownsampling>::new(
+                        p,
+                        self.input_resolution,
+                        self.output_resolution,
+                        self.tiling_specification,
+                    ).boxed()
+                    .into(),
+                ResamplingMethod::Bilinear => DownsamplingProcessor::<_, _, BilinearDownsampling>::new(
+                        p,
+                        self.input_resolution,
+                        self.output_resolution,
+                        self.tiling_specification,
+                    ).boxed()
+                    .into(),
+                ResamplingMethod::Cubic | ResamplingMethod::CubicSpline | ResamplingMethod::Lanczos => {
+                    unreachable!("checked in `Downsampling` constructor")
+                }
+            }
+        );
+
+        Ok(res)
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct DownsamplingProcessor<Q, P, M>
+where
+    Q: RasterQueryProcessor<RasterType = P>,
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    source: Q,
+    input_resolution: SpatialResolution,
+    output_resolution: SpatialResolution,
+    tiling_specification: TilingSpecification,
+    sampling: PhantomData<M>,
+}
+
+impl<Q, P, M> DownsamplingProcessor<Q, P, M>
+where
+    Q: RasterQueryProcessor<RasterType = P>,
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    pub fn new(
+        source: Q,
+        input_resolution: SpatialResolution,
+        output_resolution: SpatialResolution,
+        tiling_specification: TilingSpecification,
+    ) -> Self {
+        Self {
+            source,
+            input_resolution,
+            output_resolution,
+            tiling_specification,
+            sampling: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Q, P, M> QueryProcessor for DownsamplingProcessor<Q, P, M>
+where
+    Q: QueryProcessor<Output = RasterTile2D<P>, SpatialBounds = SpatialPartition2D>,
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    type Output = RasterTile2D<P>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the output tiles are always produced in the output resolution
+        let query = RasterQueryRectangle {
+            spatial_resolution: self.output_resolution,
+            ..query
+        };
+
+        if self.output_resolution == self.input_resolution {
+            return self.source.query(query, ctx).await;
+        }
+
+        let sub_query = DownsamplingSubQuery::<P, M> {
+            input_resolution: self.input_resolution,
+            _phantom: PhantomData,
+        };
+
+        Ok(RasterSubQueryAdapter::<'a, P, _, _>::new(
+            &self.source,
+            query,
+            self.tiling_specification,
+            ctx,
+            sub_query,
+        )
+        .filter_and_fill())
+    }
+}
+
+/// Queries the input tiles of an output tile and folds them one by one into its pixels,
+/// so that the input is never held in its full resolution
+#[derive(Debug, Clone)]
+pub struct DownsamplingSubQuery<P, M> {
+    input_resolution: SpatialResolution,
+    _phantom: PhantomData<(P, M)>,
+}
+
+impl<'a, P, M> SubQueryTileAggregator<'a, P> for DownsamplingSubQuery<P, M>
+where
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    type FoldFuture = BoxFuture<'static, Result<DownsamplingAccu<P, M>>>;
+    type FoldMethod = fn(DownsamplingAccu<P, M>, RasterTile2D<P>) -> Self::FoldFuture;
+    type TileAccu = DownsamplingAccu<P, M>;
+    type TileAccuFuture = BoxFuture<'a, Result<Self::TileAccu>>;
+
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        pool: &Arc<ThreadPool>,
+    ) -> Self::TileAccuFuture {
+        let pool = pool.clone();
+        crate::util::spawn_blocking(move || {
+            DownsamplingAccu::new(tile_info, query_rect.time_interval, pool)
+        })
+        .map_err(From::from)
+        .boxed()
+    }
+
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        _query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+    ) -> Result<Option<RasterQueryRectangle>> {
+        let mut spatial_bounds = tile_info.spatial_partition();
+
+        if M::NEEDS_NEIGHBORS {
+            let enlarge: Coordinate2D = (self.input_resolution.x, -self.input_resolution.y).into();
+            spatial_bounds = SpatialPartition2D::new(
+                spatial_bounds.upper_left() - enlarge,
+                spatial_bounds.lower_right() + enlarge,
+            )?;
+        }
+
+        Ok(Some(RasterQueryRectangle {
+            spatial_bounds,
+            time_interval: TimeInterval::new_instant(start_time)?,
+            spatial_resolution: self.input_resolution,
+        }))
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        fold_downsampling_accu
+    }
+}
+
+fn fold_downsampling_accu<P, M>(
+    mut accu: DownsamplingAccu<P, M>,
+    tile: RasterTile2D<P>,
+) -> BoxFuture<'static, Result<DownsamplingAccu<P, M>>>
+where
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    crate::util::spawn_blocking_with_thread_pool(accu.pool.clone(), move || {
+        accu.add_tile(&tile);
+        accu
+    })
+    .map_err(From::from)
+    .boxed()
+}
+
+/// The states of the pixels of an output tile
+#[derive(Debug, Clone)]
+pub struct DownsamplingAccu<P, M>
+where
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    output_info: TileInformation,
+    time: TimeInterval,
+    pixels: Vec<M::PixelAccu>,
+    pool: Arc<ThreadPool>,
+    _phantom_pixel_type: PhantomData<P>,
+}
+
+impl<P, M> DownsamplingAccu<P, M>
+where
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    fn new(output_info: TileInformation, time: TimeInterval, pool: Arc<ThreadPool>) -> Self {
+        Self {
+            output_info,
+            time,
+            pixels: vec![
+                M::PixelAccu::default();
+                output_info.tile_size_in_pixels.number_of_elements()
+            ],
+            pool,
+            _phantom_pixel_type: PhantomData,
+        }
+    }
+
+    fn add_tile(&mut self, input: &RasterTile2D<P>) {
+        // the time is not known when the accu is created
+        self.time = input.time;
+
+        if input.is_empty() {
+            return;
+        }
+
+        let geometry = PixelGeometry::new(&input.tile_information(), &self.output_info);
+        M::add_tile(&mut self.pixels, &geometry, input);
+    }
+
+    fn into_raster_tile(self) -> RasterTile2D<P> {
+        let width = self.output_info.tile_size_in_pixels.axis_size_x();
+        let pixels = self.pixels;
+
+        let map_fn = |g_idx: GridIdx2D| {
+            let GridIdx([y_idx, x_idx]) = g_idx;
+            M::value(&pixels[y_idx as usize * width + x_idx as usize])
+        };
+
+        let out_data =
+            GridOrEmpty::from_index_fn_parallel(&self.output_info.tile_size_in_pixels, map_fn);
+
+        RasterTile2D::new(
+            self.time,
+            self.output_info.global_tile_position,
+            self.output_info.global_geo_transform,
+            out_data,
+        )
+    }
+}
+
+#[async_trait]
+impl<P, M> FoldTileAccu for DownsamplingAccu<P, M>
+where
+    P: Pixel,
+    M: DownsamplingMethod<P>,
+{
+    type RasterType = P;
+
+    async fn into_tile(self) -> Result<RasterTile2D<Self::RasterType>> {
+        crate::util::spawn_blocking_with_thread_pool(self.pool.clone(), move || {
+            self.into_raster_tile()
+        })
+        .await
+        .map_err(From::from)
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.pool
+    }
+}
+
+/// Maps between the pixel coordinates of an input tile and of an output tile,
+/// where `(0, 0)` is the upper left corner of a tile and the first coordinate is the row
+#[derive(Debug, Clone, Copy)]
+pub struct PixelGeometry {
+    /// The upper left corner of the output tile in input pixel coordinates
+    output_origin: [f64; 2],
+    /// The size of an output pixel in input pixels
+    scale: [f64; 2],
+    output_shape: [usize; 2],
+    input_shape: [usize; 2],
+}
+
+impl PixelGeometry {
+    fn new(input: &TileInformation, output: &TileInformation) -> Self {
+        let in_upper_left = input.spatial_partition().upper_left();
+        let in_x_size = input.global_geo_transform.x_pixel_size();
+        let in_y_size = input.global_geo_transform.y_pixel_size();
+
+        let out_upper_left = output.spatial_partition().upper_left();
+        let out_x_size = output.global_geo_transform.x_pixel_size();
+        let out_y_size = output.global_geo_transform.y_pixel_size();
+
+        Self {
+            output_origin: [
+                (out_upper_left.y - in_upper_left.y) / in_y_size,
+                (out_upper_left.x - in_upper_left.x) / in_x_size,
+            ],
+            scale: [out_y_size / in_y_size, out_x_size / in_x_size],
+            output_shape: [
+                output.tile_size_in_pixels.axis_size_y(),
+                output.tile_size_in_pixels.axis_size_x(),
+            ],
+            input_shape: [
+                input.tile_size_in_pixels.axis_size_y(),
+                input.tile_size_in_pixels.axis_size_x(),
+            ],
+        }
+    }
+
+    /// The center of the output pixel with the linear index `idx` in input pixel coordinates
+    fn output_center(&self, idx: usize) -> (f64, f64) {
+        let width = self.output_shape[1];
+        (
+            self.output_origin[0] + ((idx / width) as f64 + 0.5) * self.scale[0],
+            self.output_origin[1] + ((idx % width) as f64 + 0.5) * self.scale[1],
+        )
+    }
+
+    /// The linear index of the output pixel that contains the input pixel coordinate, if it is inside the output tile
+    fn output_pixel(&self, y: f64, x: f64) -> Option<usize> {
+        let out_y = ((y - self.output_origin[0]) / self.scale[0]).floor();
+        let out_x = ((x - self.output_origin[1]) / self.scale[1]).floor();
+        let [height, width] = self.output_shape;
+
+        (out_y >= 0. && out_x >= 0. && out_y < height as f64 && out_x < width as f64)
+            .then(|| out_y as usize * width + out_x as usize)
+    }
+}
+
+fn input_value<P: Pixel>(input: &RasterTile2D<P>, y_idx: isize, x_idx: isize) -> Option<P> {
+    input
+        .get_at_grid_index(GridIdx2D::new([y_idx, x_idx]))
+        .ok()
+        .flatten()
+}
+
+/// Computes the output pixels incrementally from the input tiles that cover them
+pub trait DownsamplingMethod<P: Pixel>: Debug + Clone + Send + Sync + 'static {
+    /// Whether the output pixels at the border of a tile need the input pixels of neighboring tiles
+    const NEEDS_NEIGHBORS: bool;
+
+    /// The state of an output pixel
+    type PixelAccu: Debug + Clone + Default + Send + Sync;
+
+    /// Adds the pixels of a non-empty `input` tile to the states of the output pixels
+    fn add_tile(pixels: &mut [Self::PixelAccu], geometry: &PixelGeometry, input: &RasterTile2D<P>);
+
+    fn value(pixel: &Self::PixelAccu) -> Option<P>;
+}
+
+/// Takes the value of the input pixel that contains the center of the output pixel
+#[derive(Clone, Debug)]
+pub struct NearestDownsampling {}
+
+impl<P: Pixel> DownsamplingMethod<P> for NearestDownsampling {
+    const NEEDS_NEIGHBORS: bool = false;
+
+    type PixelAccu = Option<P>;
+
+    fn add_tile(pixels: &mut [Self::PixelAccu], geometry: &PixelGeometry, input: &RasterTile2D<P>) {
+        for (idx, pixel) in pixels.iter_mut().enumerate() {
+            let (y, x) = geometry.output_center(idx);
+
+            if let Some(value) = input_value(input, y.floor() as isize, x.floor() as isize) {
+                *pixel = Some(value);
+            }
+        }
+    }
+
+    fn value(pixel: &Self::PixelAccu) -> Option<P> {
+        *pixel
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AverageAccu {
+    sum: f64,
+    count: u32,
+}
+
+/// Averages the valid input pixels whose centers lie inside of the output pixel
+#[derive(Clone, Debug)]
+pub struct AverageDownsampling {}
+
+impl<P: Pixel> DownsamplingMethod<P> for AverageDownsampling {
+    const NEEDS_NEIGHBORS: bool = false;
+
+    type PixelAccu = AverageAccu;
+
+    fn add_tile(pixels: &mut [Self::PixelAccu], geometry: &PixelGeometry, input: &RasterTile2D<P>) {
+        let [height, width] = geometry.input_shape;
+
+        for y_idx in 0..height {
+            for x_idx in 0..width {
+                let value = if let Some(value) = input_value(input, y_idx as isize, x_idx as isize)
+                {
+                    value
+                } else {
+                    continue;
+                };
+
+                if let Some(idx) = geometry.output_pixel(y_idx as f64 + 0.5, x_idx as f64 + 0.5) {
+                    let value: f64 = value.as_();
+                    pixels[idx].sum += value;
+                    pixels[idx].count += 1;
+                }
+            }
+        }
+    }
+
+    fn value(pixel: &Self::PixelAccu) -> Option<P> {
+        (pixel.count > 0).then(|| P::from_(pixel.sum / f64::from(pixel.count)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BilinearAccu {
+    weighted_sum: f64,
+    corners: u8,
+}
+
+/// Interpolates the value at the center of the output pixel between the centers of the four surrounding input pixels
+#[derive(Clone, Debug)]
+pub struct BilinearDownsampling {}
+
+impl<P: Pixel> DownsamplingMethod<P> for BilinearDownsampling {
+    const NEEDS_NEIGHBORS: bool = true;
+
+    type PixelAccu = BilinearAccu;
+
+    fn add_tile(pixels: &mut [Self::PixelAccu], geometry: &PixelGeometry, input: &RasterTile2D<P>) {
+        for (idx, pixel) in pixels.iter_mut().enumerate() {
+            let (y, x) = geometry.output_center(idx);
+            let (y, x) = (y - 0.5, x - 0.5);
+            let (y_idx, x_idx) = (y.floor() as isize, x.floor() as isize);
+            let (y_fraction, x_fraction) = (y - y.floor(), x - x.floor());
+
+            // the corners may lie in different input tiles
+            for (y_offset, y_weight) in [(0, 1. - y_fraction), (1, y_fraction)] {
+                for (x_offset, x_weight) in [(0, 1. - x_fraction), (1, x_fraction)] {
+                    if let Some(value) = input_value(input, y_idx + y_offset, x_idx + x_offset) {
+                        let value: f64 = value.as_();
+                        pixel.weighted_sum += y_weight * x_weight * value;
+                        pixel.corners += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn value(pixel: &Self::PixelAccu) -> Option<P> {
+        (pixel.corners == 4).then(|| P::from_(pixel.weighted_sum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use geoengine_datatypes::{
+        primitives::{Measurement, TimeInterval},
+        raster::{GeoTransform, Grid2D, RasterDataType},
+        spatial_reference::SpatialReference,
+        util::test::TestDefault,
+    };
+
+    use crate::{
+        engine::{MockExecutionContext, MockQueryContext},
+        mock::{MockRasterSource, MockRasterSourceParams},
+    };
+
+    fn downsampling(sampling_method: ResamplingMethod, output_resolution: f64) -> Downsampling {
+        // test raster:
+        // || 1 | 3 || 2 | 2 ||
+        // || 5 | 7 || 4 | 4 ||
+        // || 0 | 0 || 9 | 9 ||
+        // || 4 | 4 || 9 | 9 ||
+        let tile = |position: [isize; 2], values: Vec<u8>| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(0, 10),
+                TileInformation {
+                    global_tile_position: position.into(),
+                    tile_size_in_pixels: [2, 2].into(),
+                    global_geo_transform: TestDefault::test_default(),
+                },
+                GridOrEmpty::from(Grid2D::new([2, 2].into(), values).unwrap()),
+            )
+        };
+
+        Downsampling {
+            params: DownsamplingParams {
+                sampling_method,
+                output_resolution: SpatialResolution::new_unchecked(
+                    output_resolution,
+                    output_resolution,
+                ),
+            },
+            sources: SingleRasterSource {
+                raster: MockRasterSource {
+                    params: MockRasterSourceParams {
+                        data: vec![
+                            tile([-2, 0], vec![1, 3, 5, 7]),
+                            tile([-2, 1], vec![2, 2, 4, 4]),
+                            tile([-1, 0], vec![0, 0, 4, 4]),
+                            tile([-1, 1], vec![9, 9, 9, 9]),
+                        ],
+                        result_descriptor: RasterResultDescriptor {
+                            data_type: RasterDataType::U8,
+                            spatial_reference: SpatialReference::epsg_4326().into(),
+                            measurement: Measurement::Unitless,
+                            time: None,
+                            bbox: None,
+                            resolution: Some(SpatialResolution::one()),
+                        },
+                    },
+                }
+                .boxed(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_downsamples_independent_of_the_query_resolution() {
+        let exe_ctx = MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+            (0., 0.).into(),
+            [2, 2].into(),
+        ));
+
+        for (sampling_method, expected) in [
+            (ResamplingMethod::Nearest, vec![7, 4, 4, 9]),
+            (ResamplingMethod::Average, vec![4, 3, 2, 9]),
+            (ResamplingMethod::Bilinear, vec![4, 3, 2, 9]),
+        ] {
+            let operator = downsampling(sampling_method, 2.)
+                .boxed()
+                .initialize(&exe_ctx)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                operator.result_descriptor().resolution,
+                Some(SpatialResolution::new_unchecked(2., 2.))
+            );
+
+            let processor = operator.query_processor().unwrap().get_u8().unwrap();
+
+            let query_rect = RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (4., 0.).into()),
+                time_interval: TimeInterval::new_unchecked(0, 10),
+                spatial_resolution: SpatialResolution::zero_point_five(),
+            };
+
+            let result: Vec<RasterTile2D<u8>> = processor
+                .query(query_rect, &MockQueryContext::test_default())
+                .await
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+                .await;
+
+            assert_eq!(
+                result,
+                vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 10),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                        global_geo_transform: GeoTransform::new((0., 0.).into(), 2., -2.),
+                    },
+                    GridOrEmpty::from(Grid2D::new([2, 2].into(), expected).unwrap()),
+                )],
+                "{sampling_method}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rejects_upsampling_and_unsupported_methods() {
+        let exe_ctx = MockExecutionContext::test_default();
+
+        assert!(matches!(
+            downsampling(ResamplingMethod::Nearest, 0.5)
+                .boxed()
+                .initialize(&exe_ctx)
+                .await,
+            Err(crate::error::Error::DownsamplingOperator {
+                source: DownsamplingError::OutputResolutionTooFine { .. }
+            })
+        ));
+        assert!(matches!(
+            downsampling(ResamplingMethod::Cubic, 2.)
+                .boxed()
+                .initialize(&exe_ctx)
+                .await,
+            Err(crate::error::Error::DownsamplingOperator {
+                source: DownsamplingError::UnsupportedSamplingMethod { .. }
+            })
+        ));
+    }
+}
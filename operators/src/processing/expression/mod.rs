@@ -1,4 +1,4 @@
-use self::{codegen::ExpressionAst, compiled::LinkedExpression, parser::ExpressionParser};
+use self::{compiled::LinkedExpression, parser::ExpressionParser};
 use crate::{
     engine::{
         CreateSpan, ExecutionContext, InitializedRasterOperator, Operator, OperatorData,
@@ -17,6 +17,7 @@ use geoengine_datatypes::{
 };
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
+use std::sync::Arc;
 use tracing::{span, Level};
 
 pub use self::error::ExpressionError;
@@ -291,6 +292,11 @@ impl RasterOperator for Expression {
             resolution,
         };
 
+        // compile the expression once, since invoking the compiler for each query processor is expensive
+        let expression = Arc::new(
+            crate::util::spawn_blocking(move || LinkedExpression::new(&expression)).await??,
+        );
+
         let initialized_operator = InitializedExpression {
             result_descriptor,
             sources,
@@ -311,7 +317,7 @@ impl OperatorName for Expression {
 pub struct InitializedExpression {
     result_descriptor: RasterResultDescriptor,
     sources: ExpressionInitializedSources,
-    expression: ExpressionAst,
+    expression: Arc<LinkedExpression>,
     map_no_data: bool,
 }
 
@@ -348,7 +354,7 @@ impl InitializedRasterOperator for InitializedExpression {
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
         let output_type = self.result_descriptor().data_type;
 
-        let expression = LinkedExpression::new(&self.expression)?;
+        let expression = self.expression.clone();
 
         let query_processors: Vec<TypedRasterQueryProcessor> = self
             .sources
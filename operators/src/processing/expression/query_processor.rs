@@ -35,10 +35,10 @@ impl<TO, Sources> ExpressionQueryProcessor<TO, Sources>
 where
     TO: Pixel,
 {
-    pub fn new(program: LinkedExpression, sources: Sources, map_no_data: bool) -> Self {
+    pub fn new(program: Arc<LinkedExpression>, sources: Sources, map_no_data: bool) -> Self {
         Self {
             sources,
-            program: Arc::new(program),
+            program,
             phantom_data: PhantomData::default(),
             map_no_data,
         }
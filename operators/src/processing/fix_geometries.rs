@@ -0,0 +1,226 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::FeatureCollection;
+use geoengine_datatypes::operations::{RepairGeometries, RepairStatistics};
+use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryMessages, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::util::Result;
+
+/// Repairs invalid geometries, e.g., self-intersecting or unclosed polygon rings.
+///
+/// Features whose geometries cannot be repaired are removed.
+/// The number of repaired and removed features is reported as a warning of the query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixGeometriesParams {}
+
+pub type FixGeometries = Operator<FixGeometriesParams, SingleVectorSource>;
+
+impl OperatorName for FixGeometries {
+    const TYPE_NAME: &'static str = "FixGeometries";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for FixGeometries {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        Ok(InitializedFixGeometries {
+            result_descriptor: vector_source.result_descriptor().clone(),
+            vector_source,
+        }
+        .boxed())
+    }
+
+    span_fn!(FixGeometries);
+}
+
+pub struct InitializedFixGeometries {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+}
+
+impl InitializedVectorOperator for InitializedFixGeometries {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => FixGeometriesProcessor::new(source).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct FixGeometriesProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+}
+
+impl<G> FixGeometriesProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: RepairGeometries,
+{
+    pub fn new(source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+        }
+    }
+
+    fn repair(
+        collection: &FeatureCollection<G>,
+        messages: &QueryMessages,
+    ) -> Result<FeatureCollection<G>> {
+        let (collection, statistics) = collection.repair_geometries()?;
+
+        let RepairStatistics { repaired, dropped } = statistics;
+        if repaired > 0 {
+            messages.warn(format!(
+                "{} feature(s) had invalid geometries that were repaired",
+                repaired
+            ));
+        }
+        if dropped > 0 {
+            messages.warn(format!(
+                "{} feature(s) were dropped because their geometries could not be repaired",
+                dropped
+            ));
+        }
+
+        Ok(collection)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for FixGeometriesProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: RepairGeometries,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let messages = ctx.messages().clone();
+
+        let stream = self.source.query(query, ctx).await?.map(move |collection| {
+            collection.and_then(|collection| Self::repair(&collection, &messages))
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, QueryMessage, QueryMetadata};
+    use crate::mock::MockFeatureCollectionSource;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::{
+        FeatureCollectionInfos, IntoGeometryIterator, MultiPolygonCollection,
+    };
+    use geoengine_datatypes::primitives::{
+        Coordinate2D, MultiPolygon, MultiPolygonAccess, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn polygon(coordinates: &[(f64, f64)]) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![coordinates
+            .iter()
+            .copied()
+            .map(Coordinate2D::from)
+            .collect()]])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_repairs_geometries() {
+        let collection = MultiPolygonCollection::from_data(
+            vec![
+                polygon(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)]),
+                polygon(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.), (0., 0.)]),
+                polygon(&[(0., 0.), (1., 1.), (1., 1.), (0., 0.)]),
+            ],
+            vec![TimeInterval::default(); 3],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = FixGeometries {
+            params: FixGeometriesParams {},
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_polygon()
+        .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (3., 3.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let mut ctx = MockQueryContext::test_default();
+        let mut messages = ctx.subscribe_messages();
+
+        let result: Vec<MultiPolygonCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 2);
+        assert_eq!(
+            result[0]
+                .geometries()
+                .map(|geometry| geometry.polygons().len())
+                .sum::<usize>(),
+            3
+        );
+
+        assert_eq!(
+            messages.received(),
+            vec![
+                QueryMessage::Metadata(QueryMetadata::Warning {
+                    message: "1 feature(s) had invalid geometries that were repaired".to_string()
+                }),
+                QueryMessage::Metadata(QueryMetadata::Warning {
+                    message:
+                        "1 feature(s) were dropped because their geometries could not be repaired"
+                            .to_string()
+                }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, TimeInstance, TimeInterval, TimeStep,
+};
+use geoengine_datatypes::raster::{
+    FromIndexFnParallel, FromPrimitive, GridIdx2D, GridIndexAccess, GridOrEmpty, GridShapeAccess,
+    Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    BoxRasterQueryProcessor, CreateSpan, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Replaces no-data pixels with values of the temporally neighboring time steps, e.g.,
+/// to clean up cloud-masked satellite series before aggregating them.
+///
+/// Only time steps that start at most `maxGap` before or after the time step of a pixel are used.
+/// The operator buffers the tiles of the time steps within `maxGap` of the time step it produces.
+pub type GapFill = Operator<GapFillParams, SingleRasterSource>;
+
+impl OperatorName for GapFill {
+    const TYPE_NAME: &'static str = "GapFill";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GapFillParams {
+    pub method: GapFillMethod,
+    pub max_gap: TimeStep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GapFillMethod {
+    /// The value of the latest previous time step that has data
+    Previous,
+    /// The value of the earliest next time step that has data
+    Next,
+    /// The value of the previous or next time step that is closer, preferring the previous one
+    Nearest,
+    /// Linear interpolation between the previous and the next time step.
+    /// Pixels without data in both directions remain no-data.
+    Linear,
+}
+
+impl GapFillMethod {
+    /// Computes the value at `time` from the previous and next values and their time steps' starts
+    fn fill<T: Pixel>(
+        self,
+        time: TimeInstance,
+        previous: Option<(TimeInstance, T)>,
+        next: Option<(TimeInstance, T)>,
+    ) -> Option<T> {
+        match self {
+            GapFillMethod::Previous => previous.map(|(_, value)| value),
+            GapFillMethod::Next => next.map(|(_, value)| value),
+            GapFillMethod::Nearest => match (previous, next) {
+                (Some((previous_time, previous)), Some((next_time, next))) => {
+                    if time.inner() - previous_time.inner() <= next_time.inner() - time.inner() {
+                        Some(previous)
+                    } else {
+                        Some(next)
+                    }
+                }
+                (previous, next) => previous.or(next).map(|(_, value)| value),
+            },
+            GapFillMethod::Linear => {
+                let ((previous_time, previous), (next_time, next)) = (previous?, next?);
+
+                let previous: f64 = previous.as_();
+                let next: f64 = next.as_();
+                let weight = (time.inner() - previous_time.inner()) as f64
+                    / (next_time.inner() - previous_time.inner()) as f64;
+
+                Some(T::from_(previous + (next - previous) * weight))
+            }
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for GapFill {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            self.params.max_gap.step > 0,
+            error::InvalidOperatorSpec {
+                reason: "`maxGap` must be positive".to_string(),
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        Ok(InitializedGapFill {
+            result_descriptor: source.result_descriptor().clone(),
+            source,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(GapFill);
+}
+
+pub struct InitializedGapFill {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    params: GapFillParams,
+}
+
+impl InitializedRasterOperator for InitializedGapFill {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(call_on_generic_raster_processor!(
+            self.source.query_processor()?,
+            source => TypedRasterQueryProcessor::from(GapFillProcessor::new(source, self.params).boxed())
+        ))
+    }
+}
+
+pub struct GapFillProcessor<T> {
+    source: BoxRasterQueryProcessor<T>,
+    params: GapFillParams,
+}
+
+impl<T> GapFillProcessor<T>
+where
+    T: Pixel,
+{
+    pub fn new(source: BoxRasterQueryProcessor<T>, params: GapFillParams) -> Self {
+        Self { source, params }
+    }
+}
+
+/// The spatial tiles of one time step
+struct TimeStepTiles<T> {
+    time: TimeInterval,
+    tiles: Vec<RasterTile2D<T>>,
+}
+
+impl<T> TimeStepTiles<T> {
+    fn tile_at(&self, index: usize, position: GridIdx2D) -> Option<&RasterTile2D<T>> {
+        // the source returns the spatial tiles of all time steps in the same order
+        match self.tiles.get(index) {
+            Some(tile) if tile.tile_position == position => Some(tile),
+            _ => self
+                .tiles
+                .iter()
+                .find(|tile| tile.tile_position == position),
+        }
+    }
+}
+
+/// A time step of the query with its neighboring time steps within the `max_gap`, nearest first
+type TimeStepWithNeighbors<T> = (
+    Arc<TimeStepTiles<T>>,
+    Vec<Arc<TimeStepTiles<T>>>,
+    Vec<Arc<TimeStepTiles<T>>>,
+);
+
+/// Buffers the time steps of the source that are within the `max_gap` of the time steps of the query
+struct GapFillWindow<T> {
+    query_time: TimeInterval,
+    max_gap: TimeStep,
+    /// complete time steps in temporal order
+    steps: VecDeque<Arc<TimeStepTiles<T>>>,
+    /// the index of the next time step to output
+    pending: usize,
+    /// the time step whose tiles are arriving
+    current: Option<TimeStepTiles<T>>,
+    source_exhausted: bool,
+}
+
+impl<T> GapFillWindow<T> {
+    fn new(query_time: TimeInterval, max_gap: TimeStep) -> Self {
+        Self {
+            query_time,
+            max_gap,
+            steps: VecDeque::new(),
+            pending: 0,
+            current: None,
+            source_exhausted: false,
+        }
+    }
+
+    fn push(&mut self, tile: RasterTile2D<T>) {
+        match &mut self.current {
+            Some(current) if current.time == tile.time => current.tiles.push(tile),
+            _ => {
+                self.complete_current_step();
+                self.current = Some(TimeStepTiles {
+                    time: tile.time,
+                    tiles: vec![tile],
+                });
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.complete_current_step();
+        self.source_exhausted = true;
+    }
+
+    fn complete_current_step(&mut self) {
+        if let Some(step) = self.current.take() {
+            self.steps.push_back(Arc::new(step));
+        }
+    }
+
+    /// Returns the next time step of the query as soon as all time steps within its `max_gap` arrived
+    fn next_ready(&mut self) -> Option<TimeStepWithNeighbors<T>> {
+        while let Some(step) = self.steps.get(self.pending).cloned() {
+            if !step.time.intersects(&self.query_time) {
+                self.pending += 1;
+                self.evict();
+                continue;
+            }
+
+            let earliest = saturating_sub(step.time.start(), self.max_gap);
+            let latest = saturating_add(step.time.start(), self.max_gap);
+
+            let later_steps_arrived = self.source_exhausted
+                || self
+                    .current
+                    .as_ref()
+                    .map_or(false, |current| current.time.start() > latest);
+            if !later_steps_arrived {
+                return None;
+            }
+
+            let previous = self
+                .steps
+                .range(..self.pending)
+                .rev()
+                .take_while(|previous| previous.time.start() >= earliest)
+                .cloned()
+                .collect();
+            let next = self
+                .steps
+                .range(self.pending + 1..)
+                .take_while(|next| next.time.start() <= latest)
+                .cloned()
+                .collect();
+
+            self.pending += 1;
+            self.evict();
+
+            return Some((step, previous, next));
+        }
+
+        None
+    }
+
+    /// Removes the time steps that are not within the `max_gap` of the remaining time steps
+    fn evict(&mut self) {
+        let next_start = self
+            .steps
+            .get(self.pending)
+            .map(|step| step.time.start())
+            .or_else(|| self.current.as_ref().map(|current| current.time.start()));
+
+        while self.pending > 0 {
+            let needed = match (self.steps.front(), next_start) {
+                (Some(front), Some(next_start)) => {
+                    front.time.start() >= saturating_sub(next_start, self.max_gap)
+                }
+                _ => false,
+            };
+
+            if needed {
+                break;
+            }
+
+            self.steps.pop_front();
+            self.pending -= 1;
+        }
+    }
+}
+
+fn saturating_sub(time: TimeInstance, step: TimeStep) -> TimeInstance {
+    (time - step).unwrap_or(TimeInstance::MIN)
+}
+
+fn saturating_add(time: TimeInstance, step: TimeStep) -> TimeInstance {
+    (time + step).unwrap_or(TimeInstance::MAX)
+}
+
+/// Fills the no-data pixels of the tiles of `step` with the `previous` and `next` time steps, nearest first
+fn fill_time_step<T: Pixel>(
+    step: &TimeStepTiles<T>,
+    previous: &[Arc<TimeStepTiles<T>>],
+    next: &[Arc<TimeStepTiles<T>>],
+    method: GapFillMethod,
+) -> Vec<RasterTile2D<T>> {
+    step.tiles
+        .iter()
+        .enumerate()
+        .map(|(index, tile)| {
+            let previous = neighbor_tiles(previous, index, tile.tile_position);
+            let next = neighbor_tiles(next, index, tile.tile_position);
+
+            fill_tile(tile, &previous, &next, method)
+        })
+        .collect()
+}
+
+fn neighbor_tiles<T>(
+    steps: &[Arc<TimeStepTiles<T>>],
+    index: usize,
+    position: GridIdx2D,
+) -> Vec<&RasterTile2D<T>> {
+    steps
+        .iter()
+        .filter_map(|step| step.tile_at(index, position))
+        .filter(|tile| !tile.is_empty())
+        .collect()
+}
+
+fn fill_tile<T: Pixel>(
+    tile: &RasterTile2D<T>,
+    previous: &[&RasterTile2D<T>],
+    next: &[&RasterTile2D<T>],
+    method: GapFillMethod,
+) -> RasterTile2D<T> {
+    if previous.is_empty() && next.is_empty() {
+        return tile.clone();
+    }
+
+    let time = tile.time.start();
+
+    let value_of = |tiles: &[&RasterTile2D<T>], lin_idx: usize| {
+        tiles.iter().find_map(|tile| {
+            tile.get_at_grid_index_unchecked(lin_idx)
+                .map(|value| (tile.time.start(), value))
+        })
+    };
+
+    let grid_array = GridOrEmpty::from_index_fn_parallel(&tile.grid_shape(), |lin_idx: usize| {
+        tile.get_at_grid_index_unchecked(lin_idx)
+            .or_else(|| method.fill(time, value_of(previous, lin_idx), value_of(next, lin_idx)))
+    });
+
+    RasterTile2D::new(
+        tile.time,
+        tile.tile_position,
+        tile.global_geo_transform,
+        grid_array,
+    )
+}
+
+#[async_trait]
+impl<T> QueryProcessor for GapFillProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let max_gap = self.params.max_gap;
+        let method = self.params.method;
+
+        let source_query = RasterQueryRectangle {
+            time_interval: TimeInterval::new(
+                saturating_sub(query.time_interval.start(), max_gap),
+                saturating_add(query.time_interval.end(), max_gap),
+            )?,
+            ..query
+        };
+
+        // the source returns the spatial tiles of one time step after another
+        let source = self.source.raster_query(source_query, ctx).await?;
+        let window = GapFillWindow::new(query.time_interval, max_gap);
+
+        let stream = stream::unfold(
+            (source, window),
+            move |(mut source, mut window)| async move {
+                loop {
+                    if let Some((step, previous, next)) = window.next_ready() {
+                        let tiles: Result<Vec<RasterTile2D<T>>> =
+                            crate::util::spawn_blocking_with_thread_pool(
+                                ctx.thread_pool().clone(),
+                                move || fill_time_step(&step, &previous, &next, method),
+                            )
+                            .await
+                            .map_err(Into::into);
+
+                        return Some((tiles, (source, window)));
+                    }
+
+                    if window.source_exhausted {
+                        return None;
+                    }
+
+                    match source.next().await {
+                        Some(Ok(tile)) => window.push(tile),
+                        Some(Err(error)) => return Some((Err(error), (source, window))),
+                        None => window.finish(),
+                    }
+                }
+            },
+        );
+
+        Ok(stream
+            .map_ok(|tiles| stream::iter(tiles.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::util::test::{masked_2x2, mock_raster_source, tile_2x2};
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeGranularity};
+    use geoengine_datatypes::raster::{EmptyGrid, TilingSpecification};
+
+    fn series() -> Vec<RasterTile2D<i32>> {
+        vec![
+            tile_2x2(
+                TimeInterval::new_unchecked(0, 10),
+                masked_2x2(vec![10, 20, 0, 0], vec![true, true, false, false]),
+            ),
+            tile_2x2(
+                TimeInterval::new_unchecked(10, 20),
+                masked_2x2(vec![1, 0, 0, 0], vec![true, false, false, false]),
+            ),
+            tile_2x2(
+                TimeInterval::new_unchecked(20, 30),
+                GridOrEmpty::Empty(EmptyGrid::new([2, 2].into())),
+            ),
+            tile_2x2(
+                TimeInterval::new_unchecked(30, 40),
+                masked_2x2(vec![0, 50, 80, 0], vec![false, true, true, false]),
+            ),
+        ]
+    }
+
+    fn millis(step: u32) -> TimeStep {
+        TimeStep {
+            granularity: TimeGranularity::Millis,
+            step,
+        }
+    }
+
+    async fn fill(method: GapFillMethod, max_gap: TimeStep) -> Vec<Option<i32>> {
+        let operator = GapFill {
+            params: GapFillParams { method, max_gap },
+            sources: mock_raster_source(series()).boxed().into(),
+        };
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        let processor = operator
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_i32()
+            .unwrap();
+
+        // only the second time step
+        let tiles: Vec<RasterTile2D<i32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::new_unchecked(10, 20),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].time, TimeInterval::new_unchecked(10, 20));
+
+        (0..4)
+            .map(|i| tiles[0].get_at_grid_index_unchecked(i))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_fills_gaps_with_neighbors() {
+        assert_eq!(
+            fill(GapFillMethod::Previous, millis(20)).await,
+            vec![Some(1), Some(20), None, None]
+        );
+        assert_eq!(
+            fill(GapFillMethod::Next, millis(20)).await,
+            vec![Some(1), Some(50), Some(80), None]
+        );
+        assert_eq!(
+            fill(GapFillMethod::Nearest, millis(20)).await,
+            vec![Some(1), Some(20), Some(80), None]
+        );
+        assert_eq!(
+            fill(GapFillMethod::Linear, millis(20)).await,
+            vec![Some(1), Some(30), None, None]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_respects_the_maximum_gap() {
+        assert_eq!(
+            fill(GapFillMethod::Nearest, millis(10)).await,
+            vec![Some(1), Some(20), None, None]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_saturates_gaps_at_the_beginning_and_end_of_time() {
+        assert_eq!(
+            fill(
+                GapFillMethod::Nearest,
+                TimeStep {
+                    granularity: TimeGranularity::Years,
+                    step: 1_000_000,
+                },
+            )
+            .await,
+            vec![Some(1), Some(20), Some(80), None]
+        );
+    }
+}
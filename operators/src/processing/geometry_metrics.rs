@@ -0,0 +1,754 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionModifications, IntoGeometryIterator, VectorDataType,
+};
+use geoengine_datatypes::operations::geodesic::{
+    geodesic_distance, geodesic_length, geodesic_polygon_area,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureData, FeatureDataType, Geometry, Measurement,
+    MultiLineString, MultiLineStringAccess, MultiPoint, MultiPointAccess, MultiPolygon,
+    MultiPolygonAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// A geometric measure of a feature.
+/// Lengths and areas are computed in the units of the spatial reference
+/// or in meters on the WGS 84 ellipsoid for geographic coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GeometryMetric {
+    /// The length of all lines
+    Length,
+    /// The length of all lines divided by the straight distance between their start and end points
+    Sinuosity,
+    /// The area of all polygons without their holes
+    Area,
+    /// The length of all rings of all polygons
+    Perimeter,
+    CentroidX,
+    CentroidY,
+}
+
+impl GeometryMetric {
+    fn supports(self, data_type: VectorDataType) -> bool {
+        match self {
+            Self::Length | Self::Sinuosity => data_type == VectorDataType::MultiLineString,
+            Self::Area | Self::Perimeter => data_type == VectorDataType::MultiPolygon,
+            Self::CentroidX | Self::CentroidY => data_type != VectorDataType::Data,
+        }
+    }
+
+    fn measurement(self, space: MetricSpace) -> Measurement {
+        let continuous = |measurement: &str, unit: &str| {
+            Measurement::continuous(
+                measurement.to_string(),
+                (space == MetricSpace::Geodesic).then(|| unit.to_string()),
+            )
+        };
+
+        match self {
+            Self::Length | Self::Perimeter => continuous("length", "m"),
+            Self::Area => continuous("area", "m²"),
+            Self::Sinuosity => Measurement::Unitless,
+            Self::CentroidX => Measurement::continuous("x".to_string(), None),
+            Self::CentroidY => Measurement::continuous("y".to_string(), None),
+        }
+    }
+}
+
+/// Computes lengths and areas either in the plane of the coordinates or on the WGS 84 ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricSpace {
+    Planar,
+    Geodesic,
+}
+
+impl MetricSpace {
+    fn distance(self, from: Coordinate2D, to: Coordinate2D) -> f64 {
+        match self {
+            Self::Planar => from.euclidean_distance(&to),
+            Self::Geodesic => geodesic_distance(from, to),
+        }
+    }
+
+    fn line_length(self, line: &[Coordinate2D]) -> f64 {
+        match self {
+            Self::Planar => line
+                .windows(2)
+                .map(|segment| segment[0].euclidean_distance(&segment[1]))
+                .sum(),
+            Self::Geodesic => geodesic_length(line),
+        }
+    }
+
+    /// The area of the exterior ring minus the areas of the holes
+    fn polygon_area<R: AsRef<[Coordinate2D]>>(self, rings: &[R]) -> f64 {
+        match self {
+            Self::Planar => rings
+                .iter()
+                .enumerate()
+                .map(|(i, ring)| {
+                    let area = ring_moments(ring.as_ref()).0.abs();
+                    if i == 0 {
+                        area
+                    } else {
+                        -area
+                    }
+                })
+                .sum(),
+            Self::Geodesic => geodesic_polygon_area(rings),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeometryMetricColumn {
+    pub column: String,
+    pub metric: GeometryMetric,
+}
+
+/// Adds columns with geometric measures of the features
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeometryMetricsParams {
+    pub columns: Vec<GeometryMetricColumn>,
+}
+
+pub type GeometryMetrics = Operator<GeometryMetricsParams, SingleVectorSource>;
+
+impl OperatorName for GeometryMetrics {
+    const TYPE_NAME: &'static str = "GeometryMetrics";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GeometryMetrics {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(!self.params.columns.is_empty(), error::EmptyInput);
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let in_desc = vector_source.result_descriptor();
+
+        for column in &self.params.columns {
+            ensure!(
+                column.metric.supports(in_desc.data_type),
+                error::InvalidOperatorSpec {
+                    reason: format!(
+                        "the metric {:?} is not defined for {} geometries",
+                        column.metric, in_desc.data_type
+                    ),
+                }
+            );
+        }
+
+        // computing lengths and areas in degrees would silently produce meaningless values
+        let space = if in_desc.spatial_reference
+            == SpatialReferenceOption::from(SpatialReference::epsg_4326())
+        {
+            MetricSpace::Geodesic
+        } else {
+            MetricSpace::Planar
+        };
+
+        let output_names: HashSet<&String> =
+            self.params.columns.iter().map(|c| &c.column).collect();
+        ensure!(
+            output_names.len() == self.params.columns.len()
+                && output_names
+                    .iter()
+                    .all(|name| !in_desc.columns.contains_key(*name)),
+            error::DuplicateOutputColumns
+        );
+
+        let result_descriptor = in_desc.map_columns(|columns| {
+            let mut columns = columns.clone();
+            for column in &self.params.columns {
+                columns.insert(
+                    column.column.clone(),
+                    VectorColumnInfo {
+                        data_type: FeatureDataType::Float,
+                        measurement: column.metric.measurement(space),
+                    },
+                );
+            }
+            columns
+        });
+
+        Ok(InitializedGeometryMetrics {
+            result_descriptor,
+            vector_source,
+            columns: self.params.columns,
+            space,
+        }
+        .boxed())
+    }
+
+    span_fn!(GeometryMetrics);
+}
+
+pub struct InitializedGeometryMetrics {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    columns: Vec<GeometryMetricColumn>,
+    space: MetricSpace,
+}
+
+impl InitializedVectorOperator for InitializedGeometryMetrics {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(match self.vector_source.query_processor()? {
+            TypedVectorQueryProcessor::MultiPoint(source) => TypedVectorQueryProcessor::MultiPoint(
+                GeometryMetricsProcessor::new(source, self.columns.clone(), self.space).boxed(),
+            ),
+            TypedVectorQueryProcessor::MultiLineString(source) => {
+                TypedVectorQueryProcessor::MultiLineString(
+                    GeometryMetricsProcessor::new(source, self.columns.clone(), self.space).boxed(),
+                )
+            }
+            TypedVectorQueryProcessor::MultiPolygon(source) => {
+                TypedVectorQueryProcessor::MultiPolygon(
+                    GeometryMetricsProcessor::new(source, self.columns.clone(), self.space).boxed(),
+                )
+            }
+            TypedVectorQueryProcessor::Data(_) => {
+                return Err(error::Error::InvalidVectorType {
+                    expected: "a geometry type".to_owned(),
+                    found: "Data".to_owned(),
+                })
+            }
+        })
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Geometries whose features can be measured
+pub trait MeasurableGeometry: Geometry + ArrowTyped + Sized {
+    /// Computes the metric for all features of the collection.
+    /// Features without a meaningful value, e.g., closed lines for the sinuosity, result in `None`.
+    fn measure(
+        collection: &FeatureCollection<Self>,
+        metric: GeometryMetric,
+        space: MetricSpace,
+    ) -> Vec<Option<f64>>;
+}
+
+impl MeasurableGeometry for MultiPoint {
+    fn measure(
+        collection: &FeatureCollection<Self>,
+        metric: GeometryMetric,
+        space: MetricSpace,
+    ) -> Vec<Option<f64>> {
+        collection
+            .geometries()
+            .map(|multi_point| {
+                let points = multi_point.points();
+                let sum = points
+                    .iter()
+                    .fold(Coordinate2D::new(0., 0.), |sum, &point| sum + point);
+                let mean = sum / points.len() as f64;
+
+                match metric {
+                    GeometryMetric::CentroidX => Some(mean.x),
+                    GeometryMetric::CentroidY => Some(mean.y),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl MeasurableGeometry for MultiLineString {
+    fn measure(
+        collection: &FeatureCollection<Self>,
+        metric: GeometryMetric,
+        space: MetricSpace,
+    ) -> Vec<Option<f64>> {
+        collection
+            .geometries()
+            .map(|multi_line_string| {
+                let lines = multi_line_string.lines();
+
+                match metric {
+                    GeometryMetric::Length => Some(
+                        lines
+                            .iter()
+                            .map(|line| space.line_length(line.as_ref()))
+                            .sum(),
+                    ),
+                    GeometryMetric::Sinuosity => {
+                        let length: f64 = lines
+                            .iter()
+                            .map(|line| space.line_length(line.as_ref()))
+                            .sum();
+                        let straight_length: f64 = lines
+                            .iter()
+                            .map(|line| {
+                                let line = line.as_ref();
+                                space.distance(line[0], line[line.len() - 1])
+                            })
+                            .sum();
+
+                        (straight_length > 0.).then(|| length / straight_length)
+                    }
+                    GeometryMetric::CentroidX | GeometryMetric::CentroidY => {
+                        let centroid = lines_centroid(lines.iter().map(AsRef::as_ref));
+                        Some(coordinate_component(centroid, metric))
+                    }
+                    GeometryMetric::Area | GeometryMetric::Perimeter => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl MeasurableGeometry for MultiPolygon {
+    fn measure(
+        collection: &FeatureCollection<Self>,
+        metric: GeometryMetric,
+        space: MetricSpace,
+    ) -> Vec<Option<f64>> {
+        collection
+            .geometries()
+            .map(|multi_polygon| {
+                let polygons = multi_polygon.polygons();
+                let rings = || {
+                    polygons
+                        .iter()
+                        .flat_map(|polygon| polygon.as_ref().iter().map(AsRef::as_ref))
+                };
+
+                match metric {
+                    GeometryMetric::Area => Some(
+                        polygons
+                            .iter()
+                            .map(|polygon| space.polygon_area(polygon.as_ref()))
+                            .sum(),
+                    ),
+                    GeometryMetric::Perimeter => {
+                        Some(rings().map(|ring| space.line_length(ring)).sum())
+                    }
+                    GeometryMetric::CentroidX | GeometryMetric::CentroidY => {
+                        let centroid =
+                            polygons_centroid(polygons).unwrap_or_else(|| lines_centroid(rings()));
+                        Some(coordinate_component(centroid, metric))
+                    }
+                    GeometryMetric::Length | GeometryMetric::Sinuosity => None,
+                }
+            })
+            .collect()
+    }
+}
+
+fn coordinate_component(coordinate: Coordinate2D, metric: GeometryMetric) -> f64 {
+    if metric == GeometryMetric::CentroidX {
+        coordinate.x
+    } else {
+        coordinate.y
+    }
+}
+
+/// The centroid of the segments of the lines, weighted by their lengths.
+/// Falls back to the mean of the vertices if the lines have no length.
+fn lines_centroid<'l>(lines: impl Iterator<Item = &'l [Coordinate2D]> + Clone) -> Coordinate2D {
+    let mut weighted_sum = Coordinate2D::new(0., 0.);
+    let mut total_length = 0.;
+
+    for segment in lines.clone().flat_map(|line| line.windows(2)) {
+        let length = segment[0].euclidean_distance(&segment[1]);
+        weighted_sum = weighted_sum + (segment[0] + segment[1]) * (length / 2.);
+        total_length += length;
+    }
+
+    if total_length > 0. {
+        return weighted_sum / total_length;
+    }
+
+    let vertices: Vec<Coordinate2D> = lines.flatten().copied().collect();
+    vertices
+        .iter()
+        .fold(Coordinate2D::new(0., 0.), |sum, &vertex| sum + vertex)
+        / vertices.len() as f64
+}
+
+/// The signed area and the area-weighted centroid sum of a ring (shoelace formula)
+fn ring_moments(ring: &[Coordinate2D]) -> (f64, Coordinate2D) {
+    let mut area = 0.;
+    let mut centroid_sum = Coordinate2D::new(0., 0.);
+
+    for segment in ring.windows(2) {
+        let cross = segment[0].x * segment[1].y - segment[1].x * segment[0].y;
+        area += cross;
+        centroid_sum = centroid_sum + (segment[0] + segment[1]) * cross;
+    }
+
+    (area / 2., centroid_sum / 6.)
+}
+
+/// The area-weighted centroid of the polygons or `None` if they have no area
+fn polygons_centroid<P, R>(polygons: &[P]) -> Option<Coordinate2D>
+where
+    P: AsRef<[R]>,
+    R: AsRef<[Coordinate2D]>,
+{
+    let mut total_area = 0.;
+    let mut centroid_sum = Coordinate2D::new(0., 0.);
+
+    for polygon in polygons {
+        for (i, ring) in polygon.as_ref().iter().enumerate() {
+            let (area, ring_centroid_sum) = ring_moments(ring.as_ref());
+
+            // normalize the orientation, s.t. exterior rings add and holes subtract
+            let sign = if (area >= 0.) == (i == 0) { 1. } else { -1. };
+
+            total_area += sign * area;
+            centroid_sum = centroid_sum + ring_centroid_sum * sign;
+        }
+    }
+
+    (total_area > 0.).then(|| centroid_sum / total_area)
+}
+
+pub struct GeometryMetricsProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    columns: Vec<GeometryMetricColumn>,
+    space: MetricSpace,
+}
+
+impl<G> GeometryMetricsProcessor<G>
+where
+    G: MeasurableGeometry + Sync + Send,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        columns: Vec<GeometryMetricColumn>,
+        space: MetricSpace,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            columns,
+            space,
+        }
+    }
+
+    fn measure(&self, collection: &FeatureCollection<G>) -> Result<FeatureCollection<G>> {
+        let new_columns: Vec<(&str, FeatureData)> = self
+            .columns
+            .iter()
+            .map(|c| {
+                (
+                    c.column.as_str(),
+                    FeatureData::NullableFloat(G::measure(collection, c.metric, self.space)),
+                )
+            })
+            .collect();
+
+        collection.add_columns(&new_columns).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for GeometryMetricsProcessor<G>
+where
+    G: MeasurableGeometry + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| self.measure(&collection?));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{
+        FeatureCollectionInfos, MultiLineStringCollection, MultiPointCollection,
+        MultiPolygonCollection,
+    };
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn query() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    fn values<G: Geometry + ArrowTyped>(
+        collection: &FeatureCollection<G>,
+        column: &str,
+    ) -> Vec<Option<f64>> {
+        collection
+            .data(column)
+            .unwrap()
+            .float_options_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_measures_lines() {
+        let collection = MultiLineStringCollection::from_data(
+            vec![
+                MultiLineString::new(vec![vec![
+                    (0., 0.).into(),
+                    (3., 4.).into(),
+                    (6., 0.).into(),
+                ]])
+                .unwrap(),
+                MultiLineString::new(vec![vec![
+                    (0., 0.).into(),
+                    (1., 0.).into(),
+                    (1., 1.).into(),
+                    (0., 0.).into(),
+                ]])
+                .unwrap(),
+            ],
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = GeometryMetrics {
+            params: GeometryMetricsParams {
+                columns: vec![
+                    GeometryMetricColumn {
+                        column: "length".to_string(),
+                        metric: GeometryMetric::Length,
+                    },
+                    GeometryMetricColumn {
+                        column: "sinuosity".to_string(),
+                        metric: GeometryMetric::Sinuosity,
+                    },
+                    GeometryMetricColumn {
+                        column: "x".to_string(),
+                        metric: GeometryMetric::CentroidX,
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::with_collections_and_sref(
+                vec![collection],
+                SpatialReference::new(SpatialReferenceAuthority::Epsg, 32632),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_line_string()
+        .unwrap();
+
+        let result: Vec<MultiLineStringCollection> = processor
+            .query(query(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            values(&result[0], "length"),
+            vec![Some(10.), Some(2. + 2_f64.sqrt())]
+        );
+        assert_eq!(values(&result[0], "sinuosity"), vec![Some(10. / 6.), None]);
+        assert_eq!(values(&result[0], "x")[0], Some(3.));
+    }
+
+    #[tokio::test]
+    async fn it_measures_polygons() {
+        let collection = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![
+                vec![
+                    (0., 0.).into(),
+                    (4., 0.).into(),
+                    (4., 4.).into(),
+                    (0., 4.).into(),
+                    (0., 0.).into(),
+                ],
+                vec![
+                    (2., 2.).into(),
+                    (3., 2.).into(),
+                    (3., 3.).into(),
+                    (2., 3.).into(),
+                    (2., 2.).into(),
+                ],
+            ]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = GeometryMetrics {
+            params: GeometryMetricsParams {
+                columns: vec![
+                    GeometryMetricColumn {
+                        column: "area".to_string(),
+                        metric: GeometryMetric::Area,
+                    },
+                    GeometryMetricColumn {
+                        column: "perimeter".to_string(),
+                        metric: GeometryMetric::Perimeter,
+                    },
+                    GeometryMetricColumn {
+                        column: "x".to_string(),
+                        metric: GeometryMetric::CentroidX,
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::with_collections_and_sref(
+                vec![collection],
+                SpatialReference::new(SpatialReferenceAuthority::Epsg, 32632),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_polygon()
+        .unwrap();
+
+        let result: Vec<MultiPolygonCollection> = processor
+            .query(query(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(values(&result[0], "area"), vec![Some(15.)]);
+        assert_eq!(values(&result[0], "perimeter"), vec![Some(20.)]);
+
+        // (16 * 2 - 1 * 2.5) / 15
+        let x = values(&result[0], "x")[0].unwrap();
+        assert!((x - 29.5 / 15.).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unsupported_metrics() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1)]).unwrap(),
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let result = GeometryMetrics {
+            params: GeometryMetricsParams {
+                columns: vec![GeometryMetricColumn {
+                    column: "area".to_string(),
+                    metric: GeometryMetric::Area,
+                }],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_measures_geodesic_lengths() {
+        let collection = MultiLineStringCollection::from_data(
+            vec![MultiLineString::new(vec![vec![(0., 0.).into(), (1., 0.).into()]]).unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let operator = GeometryMetrics {
+            params: GeometryMetricsParams {
+                columns: vec![GeometryMetricColumn {
+                    column: "length".to_string(),
+                    metric: GeometryMetric::Length,
+                }],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().column_measurement("length"),
+            Some(&Measurement::continuous(
+                "length".to_string(),
+                Some("m".to_string())
+            ))
+        );
+
+        let processor = operator
+            .query_processor()
+            .unwrap()
+            .multi_line_string()
+            .unwrap();
+
+        let result: Vec<MultiLineStringCollection> = processor
+            .query(query(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let length = values(&result[0], "length")[0].unwrap();
+        assert!((length - 111_319.491).abs() < 1e-3);
+    }
+}
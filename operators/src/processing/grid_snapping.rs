@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, FeatureCollectionModifications, IntoGeometryIterator,
+    MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, MultiPoint, MultiPointAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::GeoTransform;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Snaps points to the center of the raster cell they fall into.
+///
+/// This stabilizes the results of raster vector joins at coarse resolutions, since all points of a
+/// cell are then located at the same position.
+pub type GridSnapping = Operator<GridSnappingParams, SingleVectorSource>;
+
+impl OperatorName for GridSnapping {
+    const TYPE_NAME: &'static str = "GridSnapping";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSnappingParams {
+    /// The grid to snap to. If it is not set, the grid of the query resolution with its origin at `(0, 0)` is used.
+    #[serde(default)]
+    pub geo_transform: Option<GeoTransform>,
+    /// Keep only the first point per cell and time interval
+    #[serde(default)]
+    pub deduplicate: bool,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GridSnapping {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        if let Some(geo_transform) = self.params.geo_transform {
+            ensure!(
+                geo_transform.x_pixel_size() > 0. && geo_transform.y_pixel_size() < 0.,
+                error::InvalidOperatorSpec {
+                    reason: "the x pixel size must be positive and the y pixel size negative"
+                        .to_string(),
+                }
+            );
+        }
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let result_descriptor = vector_source.result_descriptor().clone();
+
+        ensure!(
+            result_descriptor.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: result_descriptor.data_type.to_string(),
+            }
+        );
+
+        Ok(InitializedGridSnapping {
+            result_descriptor,
+            vector_source,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(GridSnapping);
+}
+
+pub struct InitializedGridSnapping {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: GridSnappingParams,
+}
+
+impl InitializedVectorOperator for InitializedGridSnapping {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self.vector_source.query_processor()?.multi_point().ok_or(
+            error::Error::InvalidVectorType {
+                expected: "MultiPoint".to_owned(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            },
+        )?;
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            GridSnappingProcessor {
+                source,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct GridSnappingProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    params: GridSnappingParams,
+}
+
+/// A grid cell `[y, x]` together with the start and end of a time interval
+type CellKey = ([isize; 2], i64, i64);
+
+impl GridSnappingProcessor {
+    /// Snaps all points of the collection and removes features that have no points left.
+    /// If `cells` is given, points whose cell and time interval were already seen are removed.
+    fn snap(
+        collection: &MultiPointCollection,
+        geo_transform: GeoTransform,
+        mut cells: Option<&mut HashSet<CellKey>>,
+    ) -> Result<MultiPointCollection> {
+        let mut keep = Vec::with_capacity(collection.len());
+        let mut geometries = Vec::with_capacity(collection.len());
+
+        for (points, time) in collection.geometries().zip(collection.time_intervals()) {
+            let mut snapped: Vec<Coordinate2D> = Vec::with_capacity(points.points().len());
+
+            for coordinate in points.points() {
+                let cell = geo_transform.coordinate_to_grid_idx_2d(*coordinate);
+
+                if let Some(cells) = cells.as_mut() {
+                    if !cells.insert((cell.0, time.start().inner(), time.end().inner())) {
+                        continue;
+                    }
+                }
+
+                snapped.push(geo_transform.grid_idx_to_pixel_center_coordinate_2d(cell));
+            }
+
+            keep.push(!snapped.is_empty());
+            if !snapped.is_empty() {
+                geometries.push(MultiPoint::new(snapped)?);
+            }
+        }
+
+        let collection = if keep.iter().all(|keep| *keep) {
+            collection.clone()
+        } else {
+            collection.filter(keep)?
+        };
+
+        collection
+            .replace_geometries(geometries)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for GridSnappingProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let geo_transform = self.params.geo_transform.unwrap_or_else(|| {
+            GeoTransform::new(
+                Coordinate2D::new(0., 0.),
+                query.spatial_resolution.x,
+                -query.spatial_resolution.y,
+            )
+        });
+
+        // points of a cell may occur in different chunks, so the seen cells are kept for the whole stream
+        let mut cells = self.params.deduplicate.then(HashSet::new);
+
+        let stream = self.source.query(query, ctx).await?.map(move |collection| {
+            collection.and_then(|collection| Self::snap(&collection, geo_transform, cells.as_mut()))
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPolygonCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPolygon, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn points(coordinates: Vec<(f64, f64)>, values: Vec<i64>) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(coordinates).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 10); values.len()],
+            [("value".to_string(), FeatureData::Int(values))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn snap(
+        params: GridSnappingParams,
+        collections: Vec<MultiPointCollection>,
+    ) -> Result<Vec<MultiPointCollection>> {
+        let operator = GridSnapping {
+            params,
+            sources: SingleVectorSource {
+                vector: MockFeatureCollectionSource::multiple(collections).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        let processor = operator.query_processor()?.multi_point().unwrap();
+
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::new(2., 2.).unwrap(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    fn coordinates(collection: &MultiPointCollection) -> Vec<Vec<Coordinate2D>> {
+        collection
+            .geometries()
+            .map(|points| points.points().to_vec())
+            .collect()
+    }
+
+    fn values(collection: &MultiPointCollection) -> Vec<Option<f64>> {
+        collection
+            .data("value")
+            .unwrap()
+            .float_options_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_snaps_to_cell_centers() {
+        let result = snap(
+            GridSnappingParams {
+                geo_transform: Some(GeoTransform::new((0., 0.).into(), 1., -1.)),
+                deduplicate: false,
+            },
+            vec![points(
+                vec![(0.2, 0.3), (0.7, 0.9), (-1.5, 2.2)],
+                vec![1, 2, 3],
+            )],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            coordinates(&result[0]),
+            vec![
+                vec![Coordinate2D::new(0.5, 0.5)],
+                vec![Coordinate2D::new(0.5, 0.5)],
+                vec![Coordinate2D::new(-1.5, 2.5)],
+            ]
+        );
+        assert_eq!(values(&result[0]), vec![Some(1.), Some(2.), Some(3.)]);
+    }
+
+    #[tokio::test]
+    async fn it_deduplicates_points_across_chunks() {
+        let result = snap(
+            GridSnappingParams {
+                geo_transform: None,
+                deduplicate: true,
+            },
+            vec![
+                points(vec![(0.5, 0.5), (1.5, 1.5), (3.0, 0.5)], vec![1, 2, 3]),
+                points(vec![(1.0, 1.0), (-0.5, 0.5)], vec![4, 5]),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            coordinates(&result[0]),
+            vec![
+                vec![Coordinate2D::new(1., 1.)],
+                vec![Coordinate2D::new(3., 1.)]
+            ]
+        );
+        assert_eq!(values(&result[0]), vec![Some(1.), Some(3.)]);
+        assert_eq!(
+            coordinates(&result[1]),
+            vec![vec![Coordinate2D::new(-1., 1.)]]
+        );
+        assert_eq!(values(&result[1]), vec![Some(5.)]);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_non_point_input() {
+        let polygons = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (0., 0.).into(),
+                (1., 0.).into(),
+                (1., 1.).into(),
+                (0., 0.).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let result = GridSnapping {
+            params: GridSnappingParams {
+                geo_transform: None,
+                deduplicate: false,
+            },
+            sources: SingleVectorSource {
+                vector: MockFeatureCollectionSource::single(polygons).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(result, Err(error::Error::InvalidType { .. })));
+    }
+}
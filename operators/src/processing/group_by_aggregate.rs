@@ -0,0 +1,544 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataType, FeatureDataValue, Geometry, Measurement,
+    TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use super::temporal_dissolve::{append_column_keys, feature_data_from_values};
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Aggregates the values of features with equal values in the group columns.
+///
+/// The result is a `DataCollection` with one row per group that contains the group columns and
+/// the aggregated columns. The time of a row is the extent of the time intervals of its group.
+/// The source is aggregated chunk by chunk, so only the aggregates of the groups are kept in memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupByAggregateParams {
+    #[serde(default)]
+    pub group_columns: Vec<String>,
+    pub aggregates: Vec<ColumnAggregate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnAggregate {
+    pub column: String,
+    pub aggregation: GroupAggregation,
+    pub output_column: String,
+}
+
+/// Aggregates the values of a group. Null values are ignored, so `Count` counts the non-null values.
+/// Groups without values result in null, except for `Count` and `Sum`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupAggregation {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl GroupAggregation {
+    fn column_info(self, input: &VectorColumnInfo) -> VectorColumnInfo {
+        match self {
+            Self::Count => VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            },
+            Self::Sum | Self::Mean | Self::Min | Self::Max => VectorColumnInfo {
+                data_type: FeatureDataType::Float,
+                measurement: input.measurement.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Accumulator {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn result(&self, aggregation: GroupAggregation) -> FeatureDataValue {
+        let non_empty =
+            |value: f64| FeatureDataValue::NullableFloat((self.count > 0).then_some(value));
+
+        match aggregation {
+            GroupAggregation::Count => FeatureDataValue::Int(self.count as i64),
+            GroupAggregation::Sum => FeatureDataValue::NullableFloat(Some(self.sum)),
+            GroupAggregation::Mean => non_empty(self.sum / self.count as f64),
+            GroupAggregation::Min => non_empty(self.min),
+            GroupAggregation::Max => non_empty(self.max),
+        }
+    }
+}
+
+/// The group values and the aggregates of a group
+struct Group {
+    values: Vec<FeatureDataValue>,
+    time: TimeInterval,
+    accumulators: Vec<Accumulator>,
+}
+
+pub type GroupByAggregate = Operator<GroupByAggregateParams, SingleVectorSource>;
+
+impl OperatorName for GroupByAggregate {
+    const TYPE_NAME: &'static str = "GroupByAggregate";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GroupByAggregate {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            !self.params.aggregates.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "at least one aggregate must be given",
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        let mut columns =
+            HashMap::with_capacity(self.params.group_columns.len() + self.params.aggregates.len());
+
+        let mut group_columns = Vec::with_capacity(self.params.group_columns.len());
+        for column in self.params.group_columns {
+            let column_info = if let Some(column_info) = source_descriptor.columns.get(&column) {
+                column_info
+            } else {
+                return Err(error::Error::ColumnDoesNotExist { column });
+            };
+
+            columns.insert(column.clone(), column_info.clone());
+            group_columns.push((column, column_info.data_type));
+        }
+
+        for aggregate in &self.params.aggregates {
+            let column_info =
+                if let Some(column_info) = source_descriptor.columns.get(&aggregate.column) {
+                    column_info
+                } else {
+                    return Err(error::Error::ColumnDoesNotExist {
+                        column: aggregate.column.clone(),
+                    });
+                };
+
+            ensure!(
+                aggregate.aggregation == GroupAggregation::Count
+                    || column_info.data_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` is not numeric", aggregate.column),
+                }
+            );
+
+            ensure!(
+                columns
+                    .insert(
+                        aggregate.output_column.clone(),
+                        aggregate.aggregation.column_info(column_info),
+                    )
+                    .is_none(),
+                error::InvalidOperatorSpec {
+                    reason: format!("duplicate output column `{}`", aggregate.output_column),
+                }
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: source_descriptor.spatial_reference,
+            columns,
+            time: source_descriptor.time,
+            bbox: None,
+        };
+
+        Ok(InitializedGroupByAggregate {
+            result_descriptor,
+            vector_source,
+            group_columns,
+            aggregates: self.params.aggregates,
+        }
+        .boxed())
+    }
+
+    span_fn!(GroupByAggregate);
+}
+
+pub struct InitializedGroupByAggregate {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    group_columns: Vec<(String, FeatureDataType)>,
+    aggregates: Vec<ColumnAggregate>,
+}
+
+impl InitializedVectorOperator for InitializedGroupByAggregate {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(call_on_generic_vector_processor!(
+            self.vector_source.query_processor()?,
+            source => TypedVectorQueryProcessor::Data(
+                GroupByAggregateProcessor::new(
+                    source,
+                    self.group_columns.clone(),
+                    self.aggregates.clone(),
+                )
+                .boxed()
+            )
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct GroupByAggregateProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    group_columns: Vec<(String, FeatureDataType)>,
+    aggregates: Vec<ColumnAggregate>,
+}
+
+impl<G> GroupByAggregateProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        group_columns: Vec<(String, FeatureDataType)>,
+        aggregates: Vec<ColumnAggregate>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            group_columns,
+            aggregates,
+        }
+    }
+
+    /// Adds the features of the collection to the aggregates of their groups.
+    /// New groups are appended, so the groups are ordered by their first occurrence.
+    fn aggregate(
+        &self,
+        collection: &FeatureCollection<G>,
+        group_indices: &mut HashMap<String, usize>,
+        groups: &mut Vec<Group>,
+    ) -> Result<()> {
+        let mut keys = vec![String::new(); collection.len()];
+        append_column_keys(
+            &mut keys,
+            collection,
+            self.group_columns.iter().map(|(column, _)| column),
+        )?;
+
+        let mut values = vec![Vec::with_capacity(self.group_columns.len()); collection.len()];
+        for (column, _) in &self.group_columns {
+            let data = collection.data(column)?;
+            for (index, values) in values.iter_mut().enumerate() {
+                values.push(data.get_unchecked(index));
+            }
+        }
+
+        let time_intervals = collection.time_intervals();
+
+        let mut feature_groups = Vec::with_capacity(collection.len());
+        for ((key, values), time) in keys.into_iter().zip(values).zip(time_intervals) {
+            let group = *group_indices.entry(key).or_insert_with(|| {
+                groups.push(Group {
+                    values,
+                    time: *time,
+                    accumulators: vec![Accumulator::default(); self.aggregates.len()],
+                });
+                groups.len() - 1
+            });
+
+            groups[group].time = groups[group].time.extend(time);
+            feature_groups.push(group);
+        }
+
+        for (aggregate_index, aggregate) in self.aggregates.iter().enumerate() {
+            let data = collection.data(&aggregate.column)?;
+
+            if aggregate.aggregation == GroupAggregation::Count {
+                for (&group, is_null) in feature_groups.iter().zip(data.nulls()) {
+                    if !is_null {
+                        groups[group].accumulators[aggregate_index].add(0.);
+                    }
+                }
+                continue;
+            }
+
+            for (&group, value) in feature_groups.iter().zip(data.float_options_iter()) {
+                if let Some(value) = value {
+                    groups[group].accumulators[aggregate_index].add(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_collection(&self, groups: &[Group]) -> Result<DataCollection> {
+        let mut data = HashMap::with_capacity(self.group_columns.len() + self.aggregates.len());
+
+        for (column_index, (column, data_type)) in self.group_columns.iter().enumerate() {
+            data.insert(
+                column.clone(),
+                feature_data_from_values(
+                    *data_type,
+                    groups
+                        .iter()
+                        .map(|group| group.values[column_index].clone()),
+                ),
+            );
+        }
+
+        for (aggregate_index, aggregate) in self.aggregates.iter().enumerate() {
+            let data_type = if aggregate.aggregation == GroupAggregation::Count {
+                FeatureDataType::Int
+            } else {
+                FeatureDataType::Float
+            };
+
+            data.insert(
+                aggregate.output_column.clone(),
+                feature_data_from_values(
+                    data_type,
+                    groups.iter().map(|group| {
+                        group.accumulators[aggregate_index].result(aggregate.aggregation)
+                    }),
+                ),
+            );
+        }
+
+        let time_intervals = groups.iter().map(|group| group.time).collect();
+
+        DataCollection::from_data(vec![], time_intervals, data).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for GroupByAggregateProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = DataCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut source = self.source.query(query, ctx).await?;
+
+        let mut group_indices = HashMap::new();
+        let mut groups = Vec::new();
+        while let Some(collection) = source.try_next().await? {
+            self.aggregate(&collection, &mut group_indices, &mut groups)?;
+        }
+
+        let result = self.to_collection(&groups);
+
+        Ok(stream::once(async move { result }).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection(countries: &[&str], areas: &[Option<f64>]) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0); areas.len()]).unwrap(),
+            (0..areas.len() as i64)
+                .map(|t| TimeInterval::new_unchecked(t, t + 1))
+                .collect(),
+            [
+                (
+                    "country".to_string(),
+                    FeatureData::Text(countries.iter().map(ToString::to_string).collect()),
+                ),
+                (
+                    "area".to_string(),
+                    FeatureData::NullableFloat(areas.to_vec()),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    fn aggregate(aggregation: GroupAggregation, output_column: &str) -> ColumnAggregate {
+        ColumnAggregate {
+            column: "area".to_string(),
+            aggregation,
+            output_column: output_column.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_groups() {
+        let operator = GroupByAggregate {
+            params: GroupByAggregateParams {
+                group_columns: vec!["country".to_string()],
+                aggregates: vec![
+                    aggregate(GroupAggregation::Count, "count"),
+                    aggregate(GroupAggregation::Sum, "sum"),
+                    aggregate(GroupAggregation::Mean, "mean"),
+                    aggregate(GroupAggregation::Min, "min"),
+                    aggregate(GroupAggregation::Max, "max"),
+                ],
+            },
+            sources: MockFeatureCollectionSource::multiple(vec![
+                collection(&["de", "fr", "de"], &[Some(1.), Some(5.), None]),
+                collection(&["fr", "de", "it"], &[Some(3.), Some(2.), None]),
+            ])
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(operator.result_descriptor().data_type, VectorDataType::Data);
+
+        let processor = operator.query_processor().unwrap().data().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<DataCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        let expected = DataCollection::from_data(
+            vec![],
+            vec![
+                TimeInterval::new_unchecked(0, 3),
+                TimeInterval::new_unchecked(0, 2),
+                TimeInterval::new_unchecked(2, 3),
+            ],
+            [
+                (
+                    "country".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("de".to_string()),
+                        Some("fr".to_string()),
+                        Some("it".to_string()),
+                    ]),
+                ),
+                (
+                    "count".to_string(),
+                    FeatureData::NullableInt(vec![Some(2), Some(2), Some(0)]),
+                ),
+                (
+                    "sum".to_string(),
+                    FeatureData::NullableFloat(vec![Some(3.), Some(8.), Some(0.)]),
+                ),
+                (
+                    "mean".to_string(),
+                    FeatureData::NullableFloat(vec![Some(1.5), Some(4.), None]),
+                ),
+                (
+                    "min".to_string(),
+                    FeatureData::NullableFloat(vec![Some(1.), Some(3.), None]),
+                ),
+                (
+                    "max".to_string(),
+                    FeatureData::NullableFloat(vec![Some(2.), Some(5.), None]),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+        assert_eq!(result[0], expected);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_non_numeric_sums() {
+        let operator = GroupByAggregate {
+            params: GroupByAggregateParams {
+                group_columns: vec![],
+                aggregates: vec![ColumnAggregate {
+                    column: "country".to_string(),
+                    aggregation: GroupAggregation::Sum,
+                    output_column: "sum".to_string(),
+                }],
+            },
+            sources: MockFeatureCollectionSource::single(collection(&["de"], &[Some(1.)]))
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            operator,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
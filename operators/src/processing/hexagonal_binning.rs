@@ -0,0 +1,584 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+    MultiPointCollection, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, Measurement, MultiPointAccess,
+    MultiPolygon, TimeInterval, VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Aggregates points into a grid of hexagonal cells and outputs the cells as polygons.
+///
+/// The size of the cells is given in pixels, i.e., it depends on the resolution of the query.
+/// Only cells that contain at least one point are part of the output.
+pub type HexagonalBinning = Operator<HexagonalBinningParams, SingleVectorSource>;
+
+impl OperatorName for HexagonalBinning {
+    const TYPE_NAME: &'static str = "HexagonalBinning";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexagonalBinningParams {
+    /// The distance from the center of a cell to its corners in pixels
+    pub cell_size_px: f64,
+    /// The output column for the number of points per cell
+    pub count_column: String,
+    /// Maps output column names to the aggregate of a numeric input column
+    #[serde(default)]
+    pub column_aggregates: HashMap<String, HexagonalBinningAggregate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexagonalBinningAggregate {
+    pub column_name: String,
+    pub aggregate_type: HexagonalBinningAggregateType,
+}
+
+/// Aggregates the values of the points of a cell. Null values are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HexagonalBinningAggregateType {
+    Min,
+    Max,
+    Sum,
+    Mean,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for HexagonalBinning {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params.cell_size_px > 0.0,
+            error::InputMustBeGreaterThanZero {
+                scope: "HexagonalBinning",
+                name: "cell_size_px"
+            }
+        );
+        ensure!(!self.params.count_column.is_empty(), error::EmptyInput);
+        ensure!(
+            !self
+                .params
+                .column_aggregates
+                .contains_key(&self.params.count_column),
+            error::DuplicateOutputColumns
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let in_desc = vector_source.result_descriptor();
+
+        ensure!(
+            in_desc.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: in_desc.data_type.to_string(),
+            }
+        );
+
+        let mut columns = HashMap::with_capacity(self.params.column_aggregates.len() + 1);
+        columns.insert(
+            self.params.count_column.clone(),
+            VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            },
+        );
+
+        for (output_column, aggregate) in &self.params.column_aggregates {
+            let input_column = in_desc.columns.get(&aggregate.column_name).ok_or_else(|| {
+                error::Error::MissingInputColumn {
+                    name: aggregate.column_name.clone(),
+                }
+            })?;
+
+            ensure!(
+                input_column.data_type.is_numeric(),
+                error::InvalidType {
+                    expected: "numeric column".to_string(),
+                    found: format!("{:?}", input_column.data_type),
+                }
+            );
+
+            columns.insert(
+                output_column.clone(),
+                VectorColumnInfo {
+                    data_type: FeatureDataType::Float,
+                    measurement: input_column.measurement.clone(),
+                },
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: in_desc.spatial_reference,
+            columns,
+            time: in_desc.time,
+            bbox: in_desc.bbox,
+        };
+
+        Ok(InitializedHexagonalBinning {
+            result_descriptor,
+            vector_source,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(HexagonalBinning);
+}
+
+pub struct InitializedHexagonalBinning {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: HexagonalBinningParams,
+}
+
+impl InitializedVectorOperator for InitializedHexagonalBinning {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self.vector_source.query_processor()?.multi_point().ok_or(
+            error::Error::InvalidVectorType {
+                expected: "MultiPoint".to_owned(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            },
+        )?;
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            HexagonalBinningProcessor {
+                source,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct HexagonalBinningProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    params: HexagonalBinningParams,
+}
+
+/// A pointy-top hexagon grid with its origin at `(0, 0)`, s.t. cells are stable for a resolution
+#[derive(Debug, Clone, Copy)]
+struct HexagonGrid {
+    size: f64,
+}
+
+impl HexagonGrid {
+    /// Returns the axial coordinates `(q, r)` of the cell that contains the coordinate
+    fn cell(self, coordinate: Coordinate2D) -> (i64, i64) {
+        let q = (3_f64.sqrt() / 3. * coordinate.x - coordinate.y / 3.) / self.size;
+        let r = (2. / 3. * coordinate.y) / self.size;
+
+        // round in cube coordinates and fix the component with the largest rounding error
+        let s = -q - r;
+        let (mut rounded_q, mut rounded_r, rounded_s) = (q.round(), r.round(), s.round());
+        let (diff_q, diff_r, diff_s) = (
+            (rounded_q - q).abs(),
+            (rounded_r - r).abs(),
+            (rounded_s - s).abs(),
+        );
+
+        if diff_q > diff_r && diff_q > diff_s {
+            rounded_q = -rounded_r - rounded_s;
+        } else if diff_r > diff_s {
+            rounded_r = -rounded_q - rounded_s;
+        }
+
+        (rounded_q as i64, rounded_r as i64)
+    }
+
+    fn hexagon(self, (q, r): (i64, i64)) -> Result<MultiPolygon> {
+        let center_x = self.size * 3_f64.sqrt() * (q as f64 + r as f64 / 2.);
+        let center_y = self.size * 1.5 * r as f64;
+
+        let mut ring: Vec<Coordinate2D> = (0..6)
+            .map(|corner| {
+                let angle = (60. * f64::from(corner) + 30.).to_radians();
+                Coordinate2D::new(
+                    center_x + self.size * angle.cos(),
+                    center_y + self.size * angle.sin(),
+                )
+            })
+            .collect();
+        ring.push(ring[0]);
+
+        MultiPolygon::new(vec![vec![ring]]).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ValueAggregate {
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: usize,
+}
+
+impl Default for ValueAggregate {
+    fn default() -> Self {
+        Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.,
+            count: 0,
+        }
+    }
+}
+
+impl ValueAggregate {
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn value(&self, aggregate_type: HexagonalBinningAggregateType) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        Some(match aggregate_type {
+            HexagonalBinningAggregateType::Min => self.min,
+            HexagonalBinningAggregateType::Max => self.max,
+            HexagonalBinningAggregateType::Sum => self.sum,
+            HexagonalBinningAggregateType::Mean => self.sum / self.count as f64,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Cell {
+    count: usize,
+    time: TimeInterval,
+    /// aggregates of the input columns in the order of `HexBins::input_columns`
+    aggregates: Vec<ValueAggregate>,
+}
+
+/// The non-empty cells of a query, ordered by their axial coordinates
+struct HexBins {
+    grid: HexagonGrid,
+    bounds: BoundingBox2D,
+    input_columns: Vec<String>,
+    cells: BTreeMap<(i64, i64), Cell>,
+}
+
+impl HexBins {
+    fn add_collection(&mut self, collection: &MultiPointCollection) -> Result<()> {
+        let columns = self
+            .input_columns
+            .iter()
+            .map(|column| Ok(collection.data(column)?.float_options_iter().collect()))
+            .collect::<Result<Vec<Vec<Option<f64>>>>>()?;
+
+        for (feature_index, (points, time)) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .enumerate()
+        {
+            for coordinate in points.points() {
+                if !self.bounds.contains_coordinate(coordinate) {
+                    continue;
+                }
+
+                let cell = self
+                    .cells
+                    .entry(self.grid.cell(*coordinate))
+                    .or_insert_with(|| Cell {
+                        count: 0,
+                        time: *time,
+                        aggregates: vec![ValueAggregate::default(); columns.len()],
+                    });
+
+                cell.count += 1;
+                cell.time = cell.time.extend(time);
+
+                for (aggregate, values) in cell.aggregates.iter_mut().zip(&columns) {
+                    if let Some(value) = values[feature_index] {
+                        aggregate.add(value);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_collection(self, params: &HexagonalBinningParams) -> Result<MultiPolygonCollection> {
+        let mut builder = MultiPolygonCollection::builder();
+
+        builder.add_column(params.count_column.clone(), FeatureDataType::Int)?;
+        for output_column in params.column_aggregates.keys() {
+            builder.add_column(output_column.clone(), FeatureDataType::Float)?;
+        }
+
+        let mut builder = builder.finish_header();
+
+        for (cell_index, cell) in self.cells {
+            builder.push_geometry(self.grid.hexagon(cell_index)?);
+            builder.push_time_interval(cell.time);
+            builder.push_data(
+                &params.count_column,
+                FeatureDataValue::Int(cell.count as i64),
+            )?;
+
+            for (output_column, aggregate) in &params.column_aggregates {
+                let input_index = self
+                    .input_columns
+                    .iter()
+                    .position(|column| column == &aggregate.column_name)
+                    .expect("all input columns are aggregated");
+
+                builder.push_data(
+                    output_column,
+                    FeatureDataValue::NullableFloat(
+                        cell.aggregates[input_index].value(aggregate.aggregate_type),
+                    ),
+                )?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for HexagonalBinningProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let joint_resolution = f64::max(query.spatial_resolution.x, query.spatial_resolution.y);
+
+        let mut input_columns: Vec<String> = self
+            .params
+            .column_aggregates
+            .values()
+            .map(|aggregate| aggregate.column_name.clone())
+            .collect();
+        input_columns.sort();
+        input_columns.dedup();
+
+        let hex_bins = HexBins {
+            grid: HexagonGrid {
+                size: self.params.cell_size_px * joint_resolution,
+            },
+            bounds: query.spatial_bounds,
+            input_columns,
+            cells: BTreeMap::new(),
+        };
+
+        // cells may contain points of all input chunks, so we aggregate the whole stream
+        let hex_bins = self
+            .source
+            .query(query, ctx)
+            .await?
+            .try_fold(hex_bins, |mut hex_bins, collection| async move {
+                hex_bins.add_collection(&collection)?;
+                Ok(hex_bins)
+            })
+            .await?;
+
+        let collection = hex_bins.into_collection(&self.params)?;
+
+        Ok(stream::once(async move { Ok(collection) }).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, MultiPolygonAccess, SpatialResolution,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn points() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (0.1, 0.1), (3.0, 0.0)]).unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 10),
+                TimeInterval::new_unchecked(5, 20),
+                TimeInterval::new_unchecked(0, 10),
+            ],
+            [(
+                "value".to_string(),
+                FeatureData::NullableFloat(vec![Some(1.), Some(3.), Some(5.)]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn bin(params: HexagonalBinningParams) -> Result<MultiPolygonCollection> {
+        let operator = HexagonalBinning {
+            params,
+            sources: SingleVectorSource {
+                vector: MockFeatureCollectionSource::single(points()).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        let processor = operator.query_processor()?.multi_polygon().unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::new(0.5, 0.5).unwrap(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(collections.len(), 1);
+
+        Ok(collections.into_iter().next().unwrap())
+    }
+
+    #[tokio::test]
+    async fn it_counts_points_per_cell() {
+        let result = bin(HexagonalBinningParams {
+            cell_size_px: 2.,
+            count_column: "count".to_string(),
+            column_aggregates: HashMap::new(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result
+                .data("count")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(1.)]
+        );
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(0, 20),
+                TimeInterval::new_unchecked(0, 10)
+            ]
+        );
+
+        let hexagon = result.geometries().next().unwrap();
+        let ring = hexagon.polygons()[0][0];
+        assert_eq!(ring.len(), 7);
+        assert!((ring[0].x - 3_f64.sqrt() / 2.).abs() < 1e-9);
+        assert!((ring[0].y - 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_attributes() {
+        let result = bin(HexagonalBinningParams {
+            cell_size_px: 2.,
+            count_column: "count".to_string(),
+            column_aggregates: [
+                (
+                    "mean".to_string(),
+                    HexagonalBinningAggregate {
+                        column_name: "value".to_string(),
+                        aggregate_type: HexagonalBinningAggregateType::Mean,
+                    },
+                ),
+                (
+                    "max".to_string(),
+                    HexagonalBinningAggregate {
+                        column_name: "value".to_string(),
+                        aggregate_type: HexagonalBinningAggregateType::Max,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result
+                .data("mean")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(2.), Some(5.)]
+        );
+        assert_eq!(
+            result
+                .data("max")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(3.), Some(5.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_params() {
+        assert!(bin(HexagonalBinningParams {
+            cell_size_px: 0.,
+            count_column: "count".to_string(),
+            column_aggregates: HashMap::new(),
+        })
+        .await
+        .is_err());
+
+        assert!(bin(HexagonalBinningParams {
+            cell_size_px: 2.,
+            count_column: "count".to_string(),
+            column_aggregates: [(
+                "count".to_string(),
+                HexagonalBinningAggregate {
+                    column_name: "value".to_string(),
+                    aggregate_type: HexagonalBinningAggregateType::Sum,
+                },
+            )]
+            .into_iter()
+            .collect(),
+        })
+        .await
+        .is_err());
+    }
+}
@@ -1,32 +1,90 @@
+mod bitmask_extraction;
+mod change_detection;
 mod circle_merging_quadtree;
+mod column_projection;
 mod column_range_filter;
+mod downsampling;
 mod expression;
+mod fix_geometries;
+mod gap_fill;
+mod geometry_metrics;
+mod grid_snapping;
+mod group_by_aggregate;
+mod hexagonal_binning;
 mod interpolation;
 mod map_query;
 mod meteosat;
 mod neighborhood_aggregate;
+mod overlay;
 mod point_in_polygon;
+mod random_point_sampling;
 mod raster_scaling;
+mod raster_stretch;
 mod raster_type_conversion;
 mod raster_vector_join;
 mod reprojection;
+mod sort;
+mod temporal_dissolve;
 mod temporal_raster_aggregation;
+mod time_attribute_extraction;
+mod time_clip;
 mod time_projection;
 mod time_shift;
+mod top_k;
 mod vector_join;
+mod zonal_statistics;
 
+pub use bitmask_extraction::{
+    BitmaskExtraction, BitmaskExtractionParams, BitmaskExtractionSources, BitmaskOutput,
+};
+pub use change_detection::{
+    ChangeDetection, ChangeDetectionInstants, ChangeDetectionOutput, ChangeDetectionParams,
+    ChangeDetectionSources,
+};
+pub use column_projection::{ColumnProjection, ColumnProjectionParams, ColumnSelection};
+pub use downsampling::{Downsampling, DownsamplingError, DownsamplingParams};
 pub use expression::{Expression, ExpressionError, ExpressionParams, ExpressionSources};
+pub use fix_geometries::{FixGeometries, FixGeometriesParams};
+pub use gap_fill::{GapFill, GapFillMethod, GapFillParams};
+pub use geometry_metrics::{
+    GeometryMetric, GeometryMetricColumn, GeometryMetrics, GeometryMetricsParams,
+};
+pub use grid_snapping::{GridSnapping, GridSnappingParams};
+pub use group_by_aggregate::{
+    ColumnAggregate, GroupAggregation, GroupByAggregate, GroupByAggregateParams,
+};
+pub use hexagonal_binning::{
+    HexagonalBinning, HexagonalBinningAggregate, HexagonalBinningAggregateType,
+    HexagonalBinningParams,
+};
 pub use interpolation::{Interpolation, InterpolationError, InterpolationParams};
 pub use neighborhood_aggregate::{
     NeighborhoodAggregate, NeighborhoodAggregateError, NeighborhoodAggregateParams,
 };
+pub use overlay::{Overlay, OverlayAttributes, OverlayOperation, OverlayParams, OverlaySources};
 pub use point_in_polygon::{
     PointInPolygonFilter, PointInPolygonFilterParams, PointInPolygonFilterSource,
     PointInPolygonTester,
 };
+pub(crate) use random_point_sampling::stratum_name;
+pub use random_point_sampling::{
+    RandomPointSampling, RandomPointSamplingParams, RandomPointSamplingSources, Stratification,
+};
+pub use raster_stretch::{RasterStretch, RasterStretchParams, StretchMethod};
 pub use raster_type_conversion::{RasterTypeConversionParams, RasterTypeConversionQueryProcessor};
+pub use raster_vector_join::{
+    FeatureAggregationMethod, RasterVectorJoin, RasterVectorJoinParams, TemporalAggregationMethod,
+};
 pub use reprojection::{
     InitializedRasterReprojection, InitializedVectorReprojection, Reprojection, ReprojectionParams,
 };
+pub use sort::{NullsOrder, Sort, SortColumn, SortOrder, SortParams};
+pub use temporal_dissolve::{DissolveAggregation, TemporalDissolve, TemporalDissolveParams};
+pub use time_attribute_extraction::{
+    TimeAttribute, TimeAttributeColumn, TimeAttributeExtraction, TimeAttributeExtractionParams,
+};
+pub use time_clip::{TimeClip, TimeClipParams, TimeClipWindow};
 pub use time_projection::{TimeProjection, TimeProjectionError, TimeProjectionParams};
 pub use time_shift::{TimeShift, TimeShiftError, TimeShiftParams};
+pub use top_k::{TopK, TopKParams};
+pub use zonal_statistics::{ZonalStatistics, ZonalStatisticsParams, ZonalStatisticsSources};
@@ -1,4 +1,5 @@
 use super::{error, NeighborhoodAggregateError};
+use crate::util::math::NeumaierSum;
 use crate::util::number_statistics::NumberStatistics;
 use geoengine_datatypes::raster::{Grid2D, GridShape2D, GridSize, Pixel};
 use num::Integer;
@@ -117,17 +118,17 @@ impl AggregateFunction for Sum {
     {
         debug_assert!(!value_options.is_empty(), "No values given to aggregate");
 
-        let mut sum = 0.;
+        let mut sum = NeumaierSum::default();
 
         for value in value_options {
             if let Some(v) = value {
-                sum += v;
+                sum.add(*v);
             } else {
                 return None;
             }
         }
 
-        Some(sum.as_())
+        Some(sum.value().as_())
     }
 }
 
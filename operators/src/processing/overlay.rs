@@ -0,0 +1,750 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geo::{BooleanOps, BoundingRect};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, FeatureCollectionModifications, IntoGeometryIterator,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataType, MultiPolygon, TimeInterval, VectorQueryRectangle,
+};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{RTree, AABB};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorData, OperatorName,
+    QueryContext, QueryProcessor, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
+};
+use crate::error;
+use crate::processing::temporal_dissolve::{feature_data_from_values, null_value};
+use crate::processing::vector_join::translation_table;
+use crate::util::Result;
+
+/// Computes boolean overlays between the features of two polygon collections,
+/// e.g., to combine the criteria of a suitability analysis.
+///
+/// Only pairs of features with intersecting time intervals are overlaid and the resulting
+/// features are valid in the intersection of both time intervals.
+/// The parts of a feature that do not intersect the other side are split into the time intervals
+/// in which the same features of the other side are valid.
+/// Both inputs are loaded into memory, so the result is a single collection.
+pub type Overlay = Operator<OverlayParams, OverlaySources>;
+
+impl OperatorName for Overlay {
+    const TYPE_NAME: &'static str = "Overlay";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayParams {
+    pub operation: OverlayOperation,
+    #[serde(default)]
+    pub attributes: OverlayAttributes,
+    /// which suffix to use if columns have conflicting names?
+    /// the default is "right"
+    pub right_column_suffix: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayOperation {
+    /// The intersections of all pairs of left and right features
+    Intersection,
+    /// The intersections of all pairs of left and right features as well as the parts of the
+    /// features of each side that do not intersect any feature of the other side
+    Union,
+    /// The parts of the left features that do not intersect any right feature
+    Difference,
+}
+
+/// The attributes of the resulting features. Missing attributes, e.g., of the right feature of a
+/// left feature's part that does not intersect any right feature, are null.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayAttributes {
+    /// The attributes of the left and the right feature
+    #[default]
+    Both,
+    /// Only the attributes of the left feature
+    Left,
+    /// Only the attributes of the right feature
+    Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlaySources {
+    pub left: Box<dyn VectorOperator>,
+    pub right: Box<dyn VectorOperator>,
+}
+
+impl OperatorData for OverlaySources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<DataId>) {
+        self.left.data_ids_collect(data_ids);
+        self.right.data_ids_collect(data_ids);
+    }
+}
+
+/// A column of an input that is part of the output
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutputColumn {
+    input_name: String,
+    output_name: String,
+    data_type: FeatureDataType,
+}
+
+/// The columns of the inputs that are part of the output
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutputColumns {
+    left: Vec<OutputColumn>,
+    right: Vec<OutputColumn>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Overlay {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let left = self.sources.left.initialize(context).await?;
+        let right = self.sources.right.initialize(context).await?;
+
+        let left_rd = left.result_descriptor();
+        let right_rd = right.result_descriptor();
+
+        for rd in [left_rd, right_rd] {
+            ensure!(
+                rd.data_type == VectorDataType::MultiPolygon,
+                error::InvalidType {
+                    expected: VectorDataType::MultiPolygon.to_string(),
+                    found: rd.data_type.to_string(),
+                }
+            );
+        }
+
+        ensure!(
+            left_rd.spatial_reference == right_rd.spatial_reference,
+            error::InvalidSpatialReference {
+                expected: left_rd.spatial_reference,
+                found: right_rd.spatial_reference,
+            }
+        );
+
+        let left_columns: Vec<OutputColumn> = match self.params.attributes {
+            OverlayAttributes::Both | OverlayAttributes::Left => left_rd
+                .columns
+                .iter()
+                .map(|(name, column)| OutputColumn {
+                    input_name: name.clone(),
+                    output_name: name.clone(),
+                    data_type: column.data_type,
+                })
+                .collect(),
+            OverlayAttributes::Right => vec![],
+        };
+
+        let right_columns: Vec<OutputColumn> = match self.params.attributes {
+            OverlayAttributes::Both | OverlayAttributes::Right => {
+                let right_column_suffix = self
+                    .params
+                    .right_column_suffix
+                    .as_ref()
+                    .map_or("right", String::as_str);
+                let column_translation_table = translation_table(
+                    left_columns.iter().map(|column| &column.output_name),
+                    right_rd.columns.keys(),
+                    right_column_suffix,
+                );
+
+                right_rd
+                    .columns
+                    .iter()
+                    .map(|(name, column)| OutputColumn {
+                        input_name: name.clone(),
+                        output_name: column_translation_table[name].clone(),
+                        data_type: column.data_type,
+                    })
+                    .collect()
+            }
+            OverlayAttributes::Left => vec![],
+        };
+
+        let mut columns = HashMap::with_capacity(left_columns.len() + right_columns.len());
+        for column in &left_columns {
+            columns.insert(
+                column.output_name.clone(),
+                left_rd.columns[&column.input_name].clone(),
+            );
+        }
+        for column in &right_columns {
+            columns.insert(
+                column.output_name.clone(),
+                right_rd.columns[&column.input_name].clone(),
+            );
+        }
+
+        let (time, bbox) = match self.params.operation {
+            OverlayOperation::Intersection | OverlayOperation::Difference => {
+                (left_rd.time, left_rd.bbox)
+            }
+            OverlayOperation::Union => (
+                left_rd
+                    .time
+                    .zip(right_rd.time)
+                    .map(|(left, right)| left.extend(&right)),
+                left_rd
+                    .bbox
+                    .zip(right_rd.bbox)
+                    .map(|(mut left, right)| left.extend(&right)),
+            ),
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: left_rd.spatial_reference,
+            columns,
+            time,
+            bbox,
+        };
+
+        Ok(InitializedOverlay {
+            result_descriptor,
+            left,
+            right,
+            operation: self.params.operation,
+            columns: OutputColumns {
+                left: left_columns,
+                right: right_columns,
+            },
+        }
+        .boxed())
+    }
+
+    span_fn!(Overlay);
+}
+
+pub struct InitializedOverlay {
+    result_descriptor: VectorResultDescriptor,
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+    operation: OverlayOperation,
+    columns: OutputColumns,
+}
+
+impl InitializedVectorOperator for InitializedOverlay {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let left = self
+            .left
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `Overlay` constructor");
+        let right = self
+            .right
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `Overlay` constructor");
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            OverlayProcessor {
+                left,
+                right,
+                operation: self.operation,
+                columns: self.columns.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct OverlayProcessor {
+    left: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    right: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    operation: OverlayOperation,
+    columns: OutputColumns,
+}
+
+/// The features of the overlay, given by their geometries, time intervals, and the indices of the input features
+#[derive(Default)]
+struct OverlayFeatures {
+    geometries: Vec<MultiPolygon>,
+    time_intervals: Vec<TimeInterval>,
+    left_indices: Vec<Option<usize>>,
+    right_indices: Vec<Option<usize>>,
+}
+
+impl OverlayFeatures {
+    fn push(
+        &mut self,
+        geometry: geo::MultiPolygon<f64>,
+        time_interval: TimeInterval,
+        left_index: Option<usize>,
+        right_index: Option<usize>,
+    ) -> Result<()> {
+        // the parts may vanish, e.g., if a feature is covered completely
+        if geometry.0.is_empty() {
+            return Ok(());
+        }
+
+        self.geometries.push(MultiPolygon::try_from(geometry)?);
+        self.time_intervals.push(time_interval);
+        self.left_indices.push(left_index);
+        self.right_indices.push(right_index);
+
+        Ok(())
+    }
+
+    /// Creates the collection of the features with the attributes of the input features
+    fn into_collection(
+        self,
+        left: &MultiPolygonCollection,
+        right: &MultiPolygonCollection,
+        columns: &OutputColumns,
+    ) -> Result<MultiPolygonCollection> {
+        let mut data = HashMap::with_capacity(columns.left.len() + columns.right.len());
+        for column in &columns.left {
+            data.insert(
+                column.output_name.clone(),
+                column_data(left, column, &self.left_indices)?,
+            );
+        }
+        for column in &columns.right {
+            data.insert(
+                column.output_name.clone(),
+                column_data(right, column, &self.right_indices)?,
+            );
+        }
+
+        MultiPolygonCollection::from_data(self.geometries, self.time_intervals, data)
+            .map_err(Into::into)
+    }
+}
+
+fn overlay(
+    left: &MultiPolygonCollection,
+    right: &MultiPolygonCollection,
+    operation: OverlayOperation,
+    columns: &OutputColumns,
+) -> Result<MultiPolygonCollection> {
+    let left_geometries = geo_multi_polygons(left);
+    let right_geometries = geo_multi_polygons(right);
+    let right_tree = RTree::bulk_load(
+        right_geometries
+            .iter()
+            .enumerate()
+            .filter_map(|(right_index, right_geometry)| {
+                let bounds = right_geometry.bounding_rect()?;
+                Some(GeomWithData::new(
+                    Rectangle::from_corners(
+                        [bounds.min().x, bounds.min().y],
+                        [bounds.max().x, bounds.max().y],
+                    ),
+                    right_index,
+                ))
+            })
+            .collect(),
+    );
+    let left_time_intervals = left.time_intervals();
+    let right_time_intervals = right.time_intervals();
+
+    let mut features = OverlayFeatures::default();
+
+    // the features of the other side that may intersect a feature
+    let mut left_partners: Vec<Vec<usize>> = vec![Vec::new(); left.len()];
+    let mut right_partners: Vec<Vec<usize>> = vec![Vec::new(); right.len()];
+
+    for (left_index, left_geometry) in left_geometries.iter().enumerate() {
+        let left_bounds = if let Some(left_bounds) = left_geometry.bounding_rect() {
+            left_bounds
+        } else {
+            continue;
+        };
+
+        // the right features whose bounds intersect, in the order of the collection
+        let mut right_indices: Vec<usize> = right_tree
+            .locate_in_envelope_intersecting(&AABB::from_corners(
+                [left_bounds.min().x, left_bounds.min().y],
+                [left_bounds.max().x, left_bounds.max().y],
+            ))
+            .map(|right| right.data)
+            .collect();
+        right_indices.sort_unstable();
+
+        for right_index in right_indices {
+            let time_interval = if let Some(time_interval) =
+                left_time_intervals[left_index].intersect(&right_time_intervals[right_index])
+            {
+                time_interval
+            } else {
+                continue;
+            };
+
+            left_partners[left_index].push(right_index);
+            right_partners[right_index].push(left_index);
+
+            if operation != OverlayOperation::Difference {
+                features.push(
+                    left_geometry.intersection(&right_geometries[right_index]),
+                    time_interval,
+                    Some(left_index),
+                    Some(right_index),
+                )?;
+            }
+        }
+    }
+
+    if operation != OverlayOperation::Intersection {
+        for (left_index, left_geometry) in left_geometries.iter().enumerate() {
+            for (remainder, time_interval) in remainders(
+                left_geometry,
+                left_time_intervals[left_index],
+                &left_partners[left_index],
+                &right_geometries,
+                right_time_intervals,
+            ) {
+                features.push(remainder, time_interval, Some(left_index), None)?;
+            }
+        }
+    }
+
+    if operation == OverlayOperation::Union {
+        for (right_index, right_geometry) in right_geometries.iter().enumerate() {
+            for (remainder, time_interval) in remainders(
+                right_geometry,
+                right_time_intervals[right_index],
+                &right_partners[right_index],
+                &left_geometries,
+                left_time_intervals,
+            ) {
+                features.push(remainder, time_interval, None, Some(right_index))?;
+            }
+        }
+    }
+
+    features.into_collection(left, right, columns)
+}
+
+fn geo_multi_polygons(collection: &MultiPolygonCollection) -> Vec<geo::MultiPolygon<f64>> {
+    collection
+        .geometries()
+        .map(|geometry| (&MultiPolygon::from(geometry)).into())
+        .collect()
+}
+
+/// Removes the partners from a feature, separately for each time interval in which the same partners are valid
+fn remainders(
+    geometry: &geo::MultiPolygon<f64>,
+    time_interval: TimeInterval,
+    partners: &[usize],
+    partner_geometries: &[geo::MultiPolygon<f64>],
+    partner_time_intervals: &[TimeInterval],
+) -> Vec<(geo::MultiPolygon<f64>, TimeInterval)> {
+    let mut instants = vec![time_interval.start(), time_interval.end()];
+    for &partner in partners {
+        let partner_time_interval = partner_time_intervals[partner];
+        instants.extend(
+            [partner_time_interval.start(), partner_time_interval.end()]
+                .into_iter()
+                .filter(|&instant| {
+                    instant > time_interval.start() && instant < time_interval.end()
+                }),
+        );
+    }
+    instants.sort_unstable();
+    instants.dedup();
+
+    let time_intervals: Vec<TimeInterval> = if instants.len() < 2 {
+        vec![time_interval]
+    } else {
+        instants
+            .windows(2)
+            .map(|instants| TimeInterval::new_unchecked(instants[0], instants[1]))
+            .collect()
+    };
+
+    // merge adjacent time intervals with the same partners
+    let mut parts: Vec<(Vec<usize>, TimeInterval)> = Vec::new();
+    for time_interval in time_intervals {
+        let valid_partners: Vec<usize> = partners
+            .iter()
+            .copied()
+            .filter(|&partner| covers(partner_time_intervals[partner], time_interval))
+            .collect();
+
+        match parts.last_mut() {
+            Some((last_partners, last_time_interval)) if *last_partners == valid_partners => {
+                *last_time_interval =
+                    TimeInterval::new_unchecked(last_time_interval.start(), time_interval.end());
+            }
+            _ => parts.push((valid_partners, time_interval)),
+        }
+    }
+
+    parts
+        .into_iter()
+        .map(|(valid_partners, time_interval)| {
+            let remainder = difference(
+                geometry,
+                valid_partners
+                    .iter()
+                    .map(|&partner| &partner_geometries[partner]),
+            );
+            (remainder, time_interval)
+        })
+        .collect()
+}
+
+fn covers(time_interval: TimeInterval, other: TimeInterval) -> bool {
+    time_interval.start() <= other.start() && other.end() <= time_interval.end()
+}
+
+/// Removes the `others` from the `geometry`
+fn difference<'g>(
+    geometry: &geo::MultiPolygon<f64>,
+    others: impl Iterator<Item = &'g geo::MultiPolygon<f64>>,
+) -> geo::MultiPolygon<f64> {
+    let others = others.fold(None, |union: Option<geo::MultiPolygon<f64>>, other| {
+        Some(match union {
+            Some(union) => union.union(other),
+            None => other.clone(),
+        })
+    });
+
+    match others {
+        Some(others) => geometry.difference(&others),
+        None => geometry.clone(),
+    }
+}
+
+/// Takes the values of a column for the given features, which are null if there is no feature
+fn column_data(
+    collection: &MultiPolygonCollection,
+    column: &OutputColumn,
+    indices: &[Option<usize>],
+) -> Result<FeatureData> {
+    let data = if collection.is_empty() {
+        None
+    } else {
+        Some(collection.data(&column.input_name)?)
+    };
+
+    Ok(feature_data_from_values(
+        column.data_type,
+        indices.iter().map(|index| match (index, &data) {
+            (Some(index), Some(data)) => data.get_unchecked(*index),
+            _ => null_value(column.data_type),
+        }),
+    ))
+}
+
+/// Collects all collections of a stream into a single collection
+async fn collect(
+    stream: BoxStream<'_, Result<MultiPolygonCollection>>,
+) -> Result<MultiPolygonCollection> {
+    let collections: Vec<MultiPolygonCollection> = stream.try_collect().await?;
+
+    let mut collections = collections.into_iter();
+    if let Some(first) = collections.next() {
+        collections
+            .try_fold(first, |merged, collection| merged.append(&collection))
+            .map_err(Into::into)
+    } else {
+        Ok(MultiPolygonCollection::empty())
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for OverlayProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let left = collect(self.left.query(query, ctx).await?).await?;
+        let right = collect(self.right.query(query, ctx).await?).await?;
+
+        let operation = self.operation;
+        let columns = self.columns.clone();
+
+        let collection =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                overlay(&left, &right, operation, &columns)
+            })
+            .await??;
+
+        Ok(stream::once(async { Ok(collection) }).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geo::Area;
+    use geoengine_datatypes::primitives::{Coordinate2D, FeatureDataValue, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn rectangle(x_min: f64, x_max: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![[
+            (x_min, 0.),
+            (x_max, 0.),
+            (x_max, 2.),
+            (x_min, 2.),
+            (x_min, 0.),
+        ]
+        .into_iter()
+        .map(Coordinate2D::from)
+        .collect()]])
+        .unwrap()
+    }
+
+    async fn overlay(operation: OverlayOperation) -> MultiPolygonCollection {
+        let left = MultiPolygonCollection::from_data(
+            vec![rectangle(0., 2.), rectangle(4., 6.)],
+            vec![TimeInterval::default(); 2],
+            [("a".to_string(), FeatureData::Int(vec![1, 2]))].into(),
+        )
+        .unwrap();
+        let right = MultiPolygonCollection::from_data(
+            vec![rectangle(1., 3.)],
+            vec![TimeInterval::default()],
+            [("a".to_string(), FeatureData::Int(vec![10]))].into(),
+        )
+        .unwrap();
+
+        let processor = Overlay {
+            params: OverlayParams {
+                operation,
+                attributes: OverlayAttributes::Both,
+                right_column_suffix: Some("_right".to_string()),
+            },
+            sources: OverlaySources {
+                left: MockFeatureCollectionSource::single(left).boxed(),
+                right: MockFeatureCollectionSource::single(right).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_polygon()
+        .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (6., 2.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPolygonCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        result.into_iter().next().unwrap()
+    }
+
+    fn areas(collection: &MultiPolygonCollection) -> Vec<f64> {
+        geo_multi_polygons(collection)
+            .iter()
+            .map(Area::unsigned_area)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_intersects() {
+        let result = overlay(OverlayOperation::Intersection).await;
+
+        assert_eq!(areas(&result), vec![2.]);
+        assert_eq!(
+            result.data("a").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableInt(Some(1))
+        );
+        assert_eq!(
+            result.data("a_right").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableInt(Some(10))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_difference() {
+        let result = overlay(OverlayOperation::Difference).await;
+
+        assert_eq!(areas(&result), vec![2., 4.]);
+        assert_eq!(result.data("a_right").unwrap().nulls(), vec![true, true]);
+    }
+
+    #[test]
+    fn it_splits_the_difference_by_the_time_of_the_other_side() {
+        let left = MultiPolygonCollection::from_data(
+            vec![rectangle(0., 2.)],
+            vec![TimeInterval::new_unchecked(0, 10)],
+            HashMap::new(),
+        )
+        .unwrap();
+        let right = MultiPolygonCollection::from_data(
+            vec![rectangle(1., 3.)],
+            vec![TimeInterval::new_unchecked(0, 5)],
+            HashMap::new(),
+        )
+        .unwrap();
+
+        let result = super::overlay(
+            &left,
+            &right,
+            OverlayOperation::Difference,
+            &OutputColumns {
+                left: vec![],
+                right: vec![],
+            },
+        )
+        .unwrap();
+
+        assert_eq!(areas(&result), vec![2., 4.]);
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(0, 5),
+                TimeInterval::new_unchecked(5, 10)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_unites() {
+        let result = overlay(OverlayOperation::Union).await;
+
+        assert_eq!(areas(&result), vec![2., 2., 4., 2.]);
+        assert_eq!(
+            result.data("a").unwrap().nulls(),
+            vec![false, false, false, true]
+        );
+        assert_eq!(
+            result.data("a_right").unwrap().nulls(),
+            vec![false, true, true, false]
+        );
+    }
+}
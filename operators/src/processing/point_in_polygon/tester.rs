@@ -1,7 +1,9 @@
 use geoengine_datatypes::{
     collections::{FeatureCollectionInfos, GeometryCollection, MultiPolygonCollection},
-    primitives::{Coordinate2D, TimeInterval},
+    primitives::{AxisAlignedRectangle, BoundingBox2D, Coordinate2D, TimeInterval},
 };
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
 
 /// Creates a context to check points against polygons
 ///
@@ -15,6 +17,7 @@ pub struct PointInPolygonTester<'a> {
     time_intervals: &'a [TimeInterval],
     constants: Vec<f64>,
     multiples: Vec<f64>,
+    index: MultiPolygonIndex,
 }
 
 impl<'a> PointInPolygonTester<'a> {
@@ -27,6 +30,9 @@ impl<'a> PointInPolygonTester<'a> {
 
         let (constants, multiples) = Self::precalculate_polygons(ring_offsets, coordinates);
 
+        let index =
+            MultiPolygonIndex::new(feature_offsets, polygon_offsets, ring_offsets, coordinates);
+
         Self {
             feature_offsets,
             polygon_offsets,
@@ -35,6 +41,7 @@ impl<'a> PointInPolygonTester<'a> {
             time_intervals,
             constants,
             multiples,
+            index,
         }
     }
 
@@ -181,36 +188,24 @@ impl<'a> PointInPolygonTester<'a> {
         false
     }
 
-    fn multi_polygon_contains_coordinate_iter<'p>(
+    /// Checks the multi polygons whose bounding boxes contain the coordinate and whose time intervals intersect the `time_interval`
+    fn candidates_containing_coordinate<'p>(
         &'p self,
         coordinate: &'p Coordinate2D,
         time_interval: &'p TimeInterval,
-    ) -> impl Iterator<Item = bool> + 'p {
-        let polygon_offsets = self.polygon_offsets;
-        let ring_offsets = self.ring_offsets;
-
-        let time_intervals = self.time_intervals;
-
-        two_tuple_windows(self.feature_offsets.iter().map(|&c| c as usize))
-            .zip(time_intervals)
-            .map(
-                move |(
-                    (multi_polygon_start_index, multi_polygon_end_index),
-                    multi_polygon_time_interval,
-                )| {
-                    if !multi_polygon_time_interval.intersects(time_interval) {
-                        return false;
-                    }
-
-                    self.check_multipolygons_contain_coordinate(
+    ) -> impl Iterator<Item = usize> + 'p {
+        self.index
+            .candidates(coordinate)
+            .filter(move |&feature_index| {
+                self.time_intervals[feature_index].intersects(time_interval)
+                    && self.check_multipolygons_contain_coordinate(
                         coordinate,
-                        polygon_offsets,
-                        ring_offsets,
-                        multi_polygon_start_index,
-                        multi_polygon_end_index,
+                        self.polygon_offsets,
+                        self.ring_offsets,
+                        self.feature_offsets[feature_index] as usize,
+                        self.feature_offsets[feature_index + 1] as usize,
                     )
-                },
-            )
+            })
     }
 
     /// Is the coordinate contained in any polygon of the collection?
@@ -226,8 +221,9 @@ impl<'a> PointInPolygonTester<'a> {
         coordinate: &Coordinate2D,
         time_interval: &TimeInterval,
     ) -> bool {
-        self.multi_polygon_contains_coordinate_iter(coordinate, time_interval)
-            .any(std::convert::identity)
+        self.candidates_containing_coordinate(coordinate, time_interval)
+            .next()
+            .is_some()
     }
 
     pub fn multi_polygons_containing_coordinate(
@@ -235,8 +231,63 @@ impl<'a> PointInPolygonTester<'a> {
         coordinate: &Coordinate2D,
         time_interval: &TimeInterval,
     ) -> Vec<bool> {
-        self.multi_polygon_contains_coordinate_iter(coordinate, time_interval)
-            .collect()
+        let mut contained = vec![false; self.time_intervals.len()];
+
+        for feature_index in self.candidates_containing_coordinate(coordinate, time_interval) {
+            contained[feature_index] = true;
+        }
+
+        contained
+    }
+}
+
+/// An R-tree of the bounding boxes of the multi polygons of a collection
+/// that finds the candidates for containing a coordinate without checking all rings
+struct MultiPolygonIndex {
+    tree: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+}
+
+impl MultiPolygonIndex {
+    fn new(
+        feature_offsets: &[i32],
+        polygon_offsets: &[i32],
+        ring_offsets: &[i32],
+        coordinates: &[Coordinate2D],
+    ) -> Self {
+        let bboxes = two_tuple_windows(feature_offsets.iter().map(|&c| c as usize))
+            .enumerate()
+            .filter_map(
+                |(feature_index, (multi_polygon_start_index, multi_polygon_end_index))| {
+                    let coordinate_start_index =
+                        ring_offsets[polygon_offsets[multi_polygon_start_index] as usize] as usize;
+                    let coordinate_end_index =
+                        ring_offsets[polygon_offsets[multi_polygon_end_index] as usize] as usize;
+
+                    let bbox = BoundingBox2D::from_coord_ref_iter(
+                        &coordinates[coordinate_start_index..coordinate_end_index],
+                    )?;
+
+                    Some(GeomWithData::new(
+                        Rectangle::from_corners(
+                            [bbox.lower_left().x, bbox.lower_left().y],
+                            [bbox.upper_right().x, bbox.upper_right().y],
+                        ),
+                        feature_index,
+                    ))
+                },
+            )
+            .collect();
+
+        Self {
+            tree: RTree::bulk_load(bboxes),
+        }
+    }
+
+    /// The multi polygons whose bounding boxes contain the `coordinate`
+    fn candidates(&self, coordinate: &Coordinate2D) -> impl Iterator<Item = usize> + '_ {
+        self.tree
+            .locate_all_at_point(&[coordinate.x, coordinate.y])
+            .map(|bbox| bbox.data)
     }
 }
 
@@ -331,4 +382,69 @@ mod tests {
             vec![false]
         );
     }
+
+    #[test]
+    fn it_finds_the_candidates_with_the_index() {
+        let square = |x: f64, y: f64| {
+            MultiPolygon::new(vec![vec![vec![
+                Coordinate2D::new(x, y),
+                Coordinate2D::new(x + 1., y),
+                Coordinate2D::new(x + 1., y + 1.),
+                Coordinate2D::new(x, y + 1.),
+                Coordinate2D::new(x, y),
+            ]]])
+            .unwrap()
+        };
+
+        let collection = MultiPolygonCollection::from_data(
+            vec![
+                square(0., 0.),
+                square(5., 5.),
+                square(9., 9.),
+                square(0.5, 0.5),
+                square(20., 0.),
+            ],
+            vec![Default::default(); 5],
+            Default::default(),
+        )
+        .unwrap();
+
+        let tester = PointInPolygonTester::new(&collection);
+
+        let mut candidates = tester
+            .index
+            .candidates(&Coordinate2D::new(0.75, 0.75))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        assert_eq!(candidates, vec![0, 3]);
+
+        assert_eq!(
+            tester
+                .index
+                .candidates(&Coordinate2D::new(-1., 0.5))
+                .count(),
+            0
+        );
+
+        assert_eq!(
+            tester.multi_polygons_containing_coordinate(
+                &Coordinate2D::new(0.75, 0.75),
+                &Default::default()
+            ),
+            vec![true, false, false, true, false]
+        );
+        assert_eq!(
+            tester.multi_polygons_containing_coordinate(
+                &Coordinate2D::new(20.5, 0.5),
+                &Default::default()
+            ),
+            vec![false, false, false, false, true]
+        );
+        assert!(tester
+            .any_polygon_contains_coordinate(&Coordinate2D::new(5.5, 5.5), &Default::default()));
+        assert!(!tester
+            .any_polygon_contains_coordinate(&Coordinate2D::new(7.5, 7.5), &Default::default()));
+        assert!(!tester
+            .any_polygon_contains_coordinate(&Coordinate2D::new(30., 0.5), &Default::default()));
+    }
 }
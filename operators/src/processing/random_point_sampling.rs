@@ -0,0 +1,789 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, IntoGeometryIterator, MultiPointCollection, MultiPolygonCollection,
+    VectorDataType,
+};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureData, FeatureDataType, FeatureDataValue, Measurement,
+    MultiPoint, MultiPolygonAccess, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{GridIdx2D, GridIndexAccess, GridShapeAccess, RasterTile2D};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    TypedVectorQueryProcessor, VectorColumnInfo, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
+};
+use crate::error;
+use crate::processing::point_in_polygon::PointInPolygonTester;
+use crate::util::Result;
+
+/// The column of the output that contains the stratum of each point
+pub const STRATUM_COLUMN: &str = "stratum";
+
+/// How often a point is drawn from the bounding box of a polygon until it lies inside of the polygon
+const MAX_ATTEMPTS_PER_POINT: usize = 1000;
+
+/// The maximum number of points per stratum, since all points are emitted as a single collection
+pub const MAX_POINTS_PER_STRATUM: usize = 1_000_000;
+
+/// Generates random sample points within polygons, e.g., for extracting training data.
+///
+/// The points are distributed uniformly w.r.t. the area of the polygons of a stratum.
+/// Equal queries with the same seed produce the same points.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomPointSamplingParams {
+    /// The number of points per stratum, at most `MAX_POINTS_PER_STRATUM`
+    pub points_per_stratum: usize,
+    /// Overrides `points_per_stratum` for single strata, keyed by the stratum, e.g., `"forest"` or `"3"`
+    #[serde(default)]
+    pub stratum_points: HashMap<String, usize>,
+    #[serde(default)]
+    pub stratification: Stratification,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Stratification {
+    /// All polygons form a single stratum
+    #[default]
+    None,
+    /// Each value of the polygon attribute `column` forms a stratum
+    #[serde(rename_all = "camelCase")]
+    Attribute { column: String },
+    /// Each value of the `classes` raster forms a stratum.
+    /// The points are placed in pixels whose centers lie inside of the polygons.
+    /// Only the first time step of the raster within the query is used.
+    Raster,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomPointSamplingSources {
+    pub polygons: Box<dyn VectorOperator>,
+    /// The raster for the `Raster` stratification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classes: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorData for RandomPointSamplingSources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<DataId>) {
+        self.polygons.data_ids_collect(data_ids);
+        if let Some(classes) = &self.classes {
+            classes.data_ids_collect(data_ids);
+        }
+    }
+}
+
+pub type RandomPointSampling = Operator<RandomPointSamplingParams, RandomPointSamplingSources>;
+
+impl OperatorName for RandomPointSampling {
+    const TYPE_NAME: &'static str = "RandomPointSampling";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for RandomPointSampling {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params.points_per_stratum <= MAX_POINTS_PER_STRATUM
+                && self
+                    .params
+                    .stratum_points
+                    .values()
+                    .all(|&points| points <= MAX_POINTS_PER_STRATUM),
+            error::InvalidOperatorSpec {
+                reason: format!("at most {MAX_POINTS_PER_STRATUM} points per stratum are allowed"),
+            }
+        );
+
+        let polygons = self.sources.polygons.initialize(context).await?;
+        let polygons_rd = polygons.result_descriptor();
+
+        ensure!(
+            polygons_rd.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: polygons_rd.data_type.to_string(),
+            }
+        );
+
+        let classes = match (&self.params.stratification, self.sources.classes) {
+            (Stratification::Raster, Some(classes)) => {
+                let classes = classes.initialize(context).await?;
+
+                ensure!(
+                    polygons_rd.spatial_reference == classes.result_descriptor().spatial_reference,
+                    error::InvalidSpatialReference {
+                        expected: polygons_rd.spatial_reference,
+                        found: classes.result_descriptor().spatial_reference,
+                    }
+                );
+
+                Some(classes)
+            }
+            (Stratification::Raster, None) => {
+                return Err(error::Error::InvalidOperatorSpec {
+                    reason: "the raster stratification requires a `classes` source".to_string(),
+                })
+            }
+            (_, Some(_)) => {
+                return Err(error::Error::InvalidOperatorSpec {
+                    reason: "the `classes` source requires the raster stratification".to_string(),
+                })
+            }
+            (Stratification::Attribute { column }, None) => {
+                ensure!(
+                    polygons_rd.columns.contains_key(column),
+                    error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    }
+                );
+                None
+            }
+            (Stratification::None, None) => None,
+        };
+
+        let mut columns = HashMap::new();
+        if self.params.stratification != Stratification::None {
+            columns.insert(
+                STRATUM_COLUMN.to_string(),
+                VectorColumnInfo {
+                    data_type: FeatureDataType::Text,
+                    measurement: Measurement::Unitless,
+                },
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: polygons_rd.spatial_reference,
+            columns,
+            time: polygons_rd.time,
+            bbox: polygons_rd.bbox,
+        };
+
+        Ok(InitializedRandomPointSampling {
+            result_descriptor,
+            polygons,
+            classes,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(RandomPointSampling);
+}
+
+pub struct InitializedRandomPointSampling {
+    result_descriptor: VectorResultDescriptor,
+    polygons: Box<dyn InitializedVectorOperator>,
+    classes: Option<Box<dyn InitializedRasterOperator>>,
+    params: RandomPointSamplingParams,
+}
+
+impl InitializedVectorOperator for InitializedRandomPointSampling {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let polygons = self
+            .polygons
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `RandomPointSampling` constructor");
+
+        let classes = match &self.classes {
+            Some(classes) => Some(classes.query_processor()?.into_f64()),
+            None => None,
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            RandomPointSamplingProcessor {
+                polygons,
+                classes,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// The features of a stratum or, for the raster stratification, its pixels with the feature they lie in
+enum Stratum {
+    Features(Vec<usize>),
+    Pixels(Vec<(Coordinate2D, usize)>),
+}
+
+/// Samples the polygons of the whole source stream.
+/// Since strata may span multiple chunks, the polygons are buffered and the points are emitted as a single collection.
+pub struct RandomPointSamplingProcessor {
+    polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    classes: Option<Box<dyn RasterQueryProcessor<RasterType = f64>>>,
+    params: RandomPointSamplingParams,
+}
+
+impl RandomPointSamplingProcessor {
+    fn feature_strata(
+        &self,
+        polygons: &MultiPolygonCollection,
+    ) -> Result<BTreeMap<String, Stratum>> {
+        let mut strata = BTreeMap::new();
+
+        match &self.params.stratification {
+            Stratification::Attribute { column } => {
+                let data = polygons.data(column)?;
+                for feature in 0..polygons.len() {
+                    if let Some(stratum) = stratum_name(data.get_unchecked(feature)) {
+                        match strata.entry(stratum).or_insert(Stratum::Features(vec![])) {
+                            Stratum::Features(features) => features.push(feature),
+                            Stratum::Pixels(_) => unreachable!("only features are inserted"),
+                        }
+                    }
+                }
+            }
+            Stratification::None | Stratification::Raster => {
+                strata.insert(
+                    String::new(),
+                    Stratum::Features((0..polygons.len()).collect()),
+                );
+            }
+        }
+
+        Ok(strata)
+    }
+
+    /// Assigns the pixel centers of the first time step of the class raster to the polygons that contain them
+    async fn pixel_strata(
+        classes: &dyn RasterQueryProcessor<RasterType = f64>,
+        polygons: &MultiPolygonCollection,
+        bboxes: &[Option<BoundingBox2D>],
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<(BTreeMap<String, Stratum>, Coordinate2D)> {
+        let tester = PointInPolygonTester::new(polygons);
+
+        let tiles: Vec<RasterTile2D<f64>> = classes
+            .raster_query(query.into(), ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        let first_time = tiles.first().map(|tile| tile.time);
+
+        let mut strata = BTreeMap::new();
+        let mut pixel_size = Coordinate2D::new(0., 0.);
+
+        for tile in tiles.iter().filter(|tile| Some(tile.time) == first_time) {
+            let geo_transform = tile.tile_geo_transform();
+            pixel_size = Coordinate2D::new(
+                geo_transform.x_pixel_size().abs(),
+                geo_transform.y_pixel_size().abs(),
+            );
+
+            let [rows, columns] = tile.grid_shape_array();
+            for y in 0..rows {
+                for x in 0..columns {
+                    let grid_idx = GridIdx2D::new([y as isize, x as isize]);
+
+                    let value = match tile.get_at_grid_index(grid_idx) {
+                        Ok(Some(value)) => value,
+                        _ => continue,
+                    };
+
+                    let center = geo_transform.grid_idx_to_pixel_center_coordinate_2d(grid_idx);
+
+                    let feature = (0..polygons.len()).find(|&feature| {
+                        bboxes[feature].map_or(false, |bbox| bbox.contains_coordinate(&center))
+                            && tester.multi_polygon_contains_coordinate(center, feature)
+                    });
+
+                    if let Some(feature) = feature {
+                        match strata
+                            .entry(stratum_name(FeatureDataValue::Float(value)).unwrap_or_default())
+                            .or_insert(Stratum::Pixels(vec![]))
+                        {
+                            Stratum::Pixels(pixels) => pixels.push((center, feature)),
+                            Stratum::Features(_) => unreachable!("only pixels are inserted"),
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok((strata, pixel_size))
+    }
+}
+
+/// The name of the stratum of an attribute value or `None` for null values.
+/// Integral floats are written as integers, so that they match integer names.
+pub(crate) fn stratum_name(value: FeatureDataValue) -> Option<String> {
+    match value {
+        FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)) => Some(v),
+        FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => Some(v.to_string()),
+        FeatureDataValue::Category(v) | FeatureDataValue::NullableCategory(Some(v)) => {
+            Some(v.to_string())
+        }
+        FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => {
+            // classes are usually integers that were converted to floats
+            if v.fract() == 0. && v.abs() < i64::MAX as f64 {
+                Some((v as i64).to_string())
+            } else {
+                Some(v.to_string())
+            }
+        }
+        FeatureDataValue::Bool(v) | FeatureDataValue::NullableBool(Some(v)) => Some(v.to_string()),
+        FeatureDataValue::DateTime(v) | FeatureDataValue::NullableDateTime(Some(v)) => {
+            Some(v.as_rfc3339_with_millis())
+        }
+        FeatureDataValue::NullableText(None)
+        | FeatureDataValue::NullableInt(None)
+        | FeatureDataValue::NullableCategory(None)
+        | FeatureDataValue::NullableFloat(None)
+        | FeatureDataValue::NullableBool(None)
+        | FeatureDataValue::NullableDateTime(None) => None,
+    }
+}
+
+/// The area of a ring using the shoelace formula
+fn ring_area(ring: &[Coordinate2D]) -> f64 {
+    ring.windows(2)
+        .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+        .sum::<f64>()
+        .abs()
+        / 2.
+}
+
+/// The area and the bounding box of each feature
+fn areas_and_bboxes(polygons: &MultiPolygonCollection) -> (Vec<f64>, Vec<Option<BoundingBox2D>>) {
+    polygons
+        .geometries()
+        .map(|multi_polygon| {
+            let area = multi_polygon
+                .polygons()
+                .iter()
+                .map(|polygon| {
+                    let mut rings = polygon.iter();
+                    let exterior = rings.next().map_or(0., |ring| ring_area(ring));
+                    exterior - rings.map(|ring| ring_area(ring)).sum::<f64>()
+                })
+                .sum::<f64>();
+
+            let bbox = BoundingBox2D::from_coord_ref_iter(
+                multi_polygon
+                    .polygons()
+                    .iter()
+                    .filter_map(|polygon| polygon.first())
+                    .flat_map(|exterior| exterior.iter()),
+            );
+
+            (area, bbox)
+        })
+        .unzip()
+}
+
+#[async_trait]
+impl QueryProcessor for RandomPointSamplingProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<MultiPolygonCollection> =
+            self.polygons.query(query, ctx).await?.try_collect().await?;
+
+        let mut collections = collections.into_iter();
+        let polygons = if let Some(first) = collections.next() {
+            collections.try_fold(first, |merged, collection| merged.append(&collection))?
+        } else {
+            return Ok(stream::empty().boxed());
+        };
+
+        let (areas, bboxes) = areas_and_bboxes(&polygons);
+
+        let (strata, pixel_size) = match &self.classes {
+            Some(classes) => {
+                Self::pixel_strata(classes.as_ref(), &polygons, &bboxes, query, ctx).await?
+            }
+            None => (self.feature_strata(&polygons)?, Coordinate2D::new(0., 0.)),
+        };
+
+        let tester = PointInPolygonTester::new(&polygons);
+        let mut rng = StdRng::seed_from_u64(self.params.seed);
+
+        let mut points = Vec::new();
+        let mut time_intervals = Vec::new();
+        let mut stratum_names = Vec::new();
+
+        for (name, stratum) in &strata {
+            let count = self
+                .params
+                .stratum_points
+                .get(name)
+                .copied()
+                .unwrap_or(self.params.points_per_stratum);
+
+            let samples = match stratum {
+                Stratum::Features(features) => {
+                    sample_features(&tester, features, &areas, &bboxes, count, &mut rng)
+                }
+                Stratum::Pixels(pixels) => {
+                    sample_pixels(&tester, pixels, pixel_size, count, &mut rng)
+                }
+            };
+
+            for (coordinate, feature) in samples {
+                points.push(MultiPoint::new(vec![coordinate])?);
+                time_intervals.push(polygons.time_intervals()[feature]);
+                stratum_names.push(name.clone());
+            }
+        }
+
+        let columns = if self.params.stratification == Stratification::None {
+            HashMap::new()
+        } else {
+            [(STRATUM_COLUMN.to_string(), FeatureData::Text(stratum_names))].into()
+        };
+
+        let result =
+            MultiPointCollection::from_data(points, time_intervals, columns).map_err(Into::into);
+
+        Ok(stream::once(async move { result }).boxed())
+    }
+}
+
+/// Draws `count` points from the `features`, each with a probability proportional to its area
+fn sample_features(
+    tester: &PointInPolygonTester,
+    features: &[usize],
+    areas: &[f64],
+    bboxes: &[Option<BoundingBox2D>],
+    count: usize,
+    rng: &mut StdRng,
+) -> Vec<(Coordinate2D, usize)> {
+    let total_area: f64 = features.iter().map(|&feature| areas[feature]).sum();
+    if features.is_empty() || total_area <= 0. {
+        return vec![];
+    }
+
+    let mut samples = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let mut remaining = rng.gen_range(0.0..total_area);
+        let feature = features
+            .iter()
+            .copied()
+            .find(|&feature| {
+                if remaining < areas[feature] {
+                    true
+                } else {
+                    remaining -= areas[feature];
+                    false
+                }
+            })
+            .unwrap_or(features[features.len() - 1]);
+
+        let bbox = if let Some(bbox) = bboxes[feature] {
+            bbox
+        } else {
+            continue;
+        };
+
+        for _ in 0..MAX_ATTEMPTS_PER_POINT {
+            let coordinate = Coordinate2D::new(
+                rng.gen_range(bbox.lower_left().x..=bbox.upper_right().x),
+                rng.gen_range(bbox.lower_left().y..=bbox.upper_right().y),
+            );
+
+            if tester.multi_polygon_contains_coordinate(coordinate, feature) {
+                samples.push((coordinate, feature));
+                break;
+            }
+        }
+    }
+
+    samples
+}
+
+/// Draws `count` pixels and places each point randomly inside of its pixel.
+/// If the point would leave the polygon, the pixel center is used instead.
+fn sample_pixels(
+    tester: &PointInPolygonTester,
+    pixels: &[(Coordinate2D, usize)],
+    pixel_size: Coordinate2D,
+    count: usize,
+    rng: &mut StdRng,
+) -> Vec<(Coordinate2D, usize)> {
+    if pixels.is_empty() {
+        return vec![];
+    }
+
+    (0..count)
+        .map(|_| {
+            let (center, feature) = pixels[rng.gen_range(0..pixels.len())];
+
+            let coordinate = Coordinate2D::new(
+                center.x + rng.gen_range(-0.5..0.5) * pixel_size.x,
+                center.y + rng.gen_range(-0.5..0.5) * pixel_size.y,
+            );
+
+            if tester.multi_polygon_contains_coordinate(coordinate, feature) {
+                (coordinate, feature)
+            } else {
+                (center, feature)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::source::{ConstantRasterSource, ConstantRasterSourceParameters};
+    use geoengine_datatypes::collections::GeometryCollection;
+    use geoengine_datatypes::primitives::{MultiPolygon, SpatialPartition2D, SpatialResolution};
+    use geoengine_datatypes::raster::RasterDataType;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn square(x: f64, y: f64, size: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+            (x, y).into(),
+        ]]])
+        .unwrap()
+    }
+
+    fn polygons() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_data(
+            vec![square(0., 0., 4.), square(10., 10., 2.)],
+            vec![TimeInterval::new(0, 10).unwrap(); 2],
+            [(
+                "landcover".to_string(),
+                FeatureData::Text(vec!["forest".to_string(), "water".to_string()]),
+            )]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    async fn sample(
+        params: RandomPointSamplingParams,
+        classes: Option<Box<dyn RasterOperator>>,
+    ) -> MultiPointCollection {
+        let operator = RandomPointSampling {
+            params,
+            sources: RandomPointSamplingSources {
+                polygons: MockFeatureCollectionSource::single(polygons()).boxed(),
+                classes,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (12., 12.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query, &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        collections.into_iter().next().unwrap()
+    }
+
+    fn coordinates(collection: &MultiPointCollection) -> Vec<Coordinate2D> {
+        collection.coordinates().to_vec()
+    }
+
+    #[tokio::test]
+    async fn it_samples_reproducibly_within_polygons() {
+        let params = RandomPointSamplingParams {
+            points_per_stratum: 50,
+            stratum_points: HashMap::new(),
+            stratification: Stratification::None,
+            seed: 42,
+        };
+
+        let points = sample(params.clone(), None).await;
+        assert_eq!(points.len(), 50);
+        assert!(points.column_names().next().is_none());
+
+        let tester = PointInPolygonTester::new(&polygons());
+        for coordinate in coordinates(&points) {
+            assert!(
+                tester.multi_polygon_contains_coordinate(coordinate, 0)
+                    || tester.multi_polygon_contains_coordinate(coordinate, 1)
+            );
+        }
+        assert_eq!(
+            points.time_intervals()[0],
+            TimeInterval::new(0, 10).unwrap()
+        );
+
+        assert_eq!(
+            coordinates(&sample(params.clone(), None).await),
+            coordinates(&points)
+        );
+        assert_ne!(
+            coordinates(&sample(RandomPointSamplingParams { seed: 7, ..params }, None).await),
+            coordinates(&points)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_too_many_points() {
+        let result = RandomPointSampling {
+            params: RandomPointSamplingParams {
+                points_per_stratum: 5,
+                stratum_points: [("water".to_string(), usize::MAX)].into(),
+                stratification: Stratification::Attribute {
+                    column: "landcover".to_string(),
+                },
+                seed: 42,
+            },
+            sources: RandomPointSamplingSources {
+                polygons: MockFeatureCollectionSource::single(polygons()).boxed(),
+                classes: None,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_stratifies_by_attribute() {
+        let points = sample(
+            RandomPointSamplingParams {
+                points_per_stratum: 5,
+                stratum_points: [("water".to_string(), 3)].into(),
+                stratification: Stratification::Attribute {
+                    column: "landcover".to_string(),
+                },
+                seed: 42,
+            },
+            None,
+        )
+        .await;
+
+        assert_eq!(points.len(), 8);
+
+        let strata: Vec<String> = points
+            .data(STRATUM_COLUMN)
+            .unwrap()
+            .strings_iter()
+            .collect();
+        assert_eq!(strata.iter().filter(|s| *s == "forest").count(), 5);
+        assert_eq!(strata.iter().filter(|s| *s == "water").count(), 3);
+
+        for (coordinate, stratum) in coordinates(&points).into_iter().zip(&strata) {
+            if stratum == "forest" {
+                assert!(coordinate.x <= 4. && coordinate.y <= 4.);
+            } else {
+                assert!(coordinate.x >= 10. && coordinate.y >= 10.);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_stratifies_by_raster_classes() {
+        let classes = ConstantRasterSource {
+            params: ConstantRasterSourceParameters {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                extent: Some(SpatialPartition2D::new((0., 4.).into(), (4., 0.).into()).unwrap()),
+                time: TimeInterval::default(),
+                resolution: Some(SpatialResolution::one()),
+                measurement: Measurement::Unitless,
+                value: 3.,
+            },
+        }
+        .boxed();
+
+        let points = sample(
+            RandomPointSamplingParams {
+                points_per_stratum: 10,
+                stratum_points: HashMap::new(),
+                stratification: Stratification::Raster,
+                seed: 42,
+            },
+            Some(classes),
+        )
+        .await;
+
+        assert_eq!(points.len(), 10);
+
+        let strata: Vec<String> = points
+            .data(STRATUM_COLUMN)
+            .unwrap()
+            .strings_iter()
+            .collect();
+        assert!(strata.iter().all(|s| s == "3"));
+
+        for coordinate in coordinates(&points) {
+            assert!(coordinate.x <= 4. && coordinate.y <= 4.);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_requires_classes_for_raster_stratification() {
+        assert!(RandomPointSampling {
+            params: RandomPointSamplingParams {
+                points_per_stratum: 10,
+                stratum_points: HashMap::new(),
+                stratification: Stratification::Raster,
+                seed: 42,
+            },
+            sources: RandomPointSamplingSources {
+                polygons: MockFeatureCollectionSource::single(polygons()).boxed(),
+                classes: None,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .is_err());
+    }
+}
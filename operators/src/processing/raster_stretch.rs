@@ -0,0 +1,420 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    Measurement, RasterQueryRectangle, SpatialPartition2D, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromIndexFnParallel, GridIndexAccess, GridOrEmpty, GridShapeAccess, GridSize,
+    RasterDataType, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    BoxRasterQueryProcessor, CreateSpan, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::statistics::{tile_digest, TDigest};
+use crate::util::Result;
+
+/// Stretches the contrast of a raster to the values `0` to `255` for visualization.
+///
+/// The stretch is computed from the pixels of the query extent, separately for each time step.
+/// Percentiles are approximated with t-digests.
+/// Thus, the output depends on the query and should not be used for further analysis.
+pub type RasterStretch = Operator<RasterStretchParams, SingleRasterSource>;
+
+impl OperatorName for RasterStretch {
+    const TYPE_NAME: &'static str = "RasterStretch";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterStretchParams {
+    pub method: StretchMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum StretchMethod {
+    /// Maps the values between the `lower` and `upper` percentile linearly and clips the remaining values
+    #[serde(rename_all = "camelCase")]
+    Percentile { lower: f64, upper: f64 },
+    /// Maps each value to its cumulative frequency, so that the output values are distributed uniformly
+    Equalization,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterStretch {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        if let StretchMethod::Percentile { lower, upper } = self.params.method {
+            ensure!(
+                (0. ..=100.).contains(&lower) && (0. ..=100.).contains(&upper) && lower < upper,
+                error::InvalidOperatorSpec {
+                    reason: "the percentiles must be between 0 and 100 with `lower` < `upper`"
+                        .to_string(),
+                }
+            );
+        }
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::U8,
+            measurement: Measurement::Unitless,
+            ..source.result_descriptor().clone()
+        };
+
+        Ok(InitializedRasterStretch {
+            result_descriptor,
+            source,
+            method: self.params.method,
+        }
+        .boxed())
+    }
+
+    span_fn!(RasterStretch);
+}
+
+pub struct InitializedRasterStretch {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    method: StretchMethod,
+}
+
+impl InitializedRasterOperator for InitializedRasterStretch {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(TypedRasterQueryProcessor::U8(
+            RasterStretchProcessor {
+                source: self.source.query_processor()?.into_f64(),
+                method: self.method,
+            }
+            .boxed(),
+        ))
+    }
+}
+
+pub struct RasterStretchProcessor {
+    source: BoxRasterQueryProcessor<f64>,
+    method: StretchMethod,
+}
+
+/// Maps the valid values of a tile with `stretch` from `[0, 1]` to `[0, 255]`
+fn stretch_tile<F>(tile: &RasterTile2D<f64>, stretch: Option<F>) -> RasterTile2D<u8>
+where
+    F: Fn(f64) -> f64 + Sync,
+{
+    let grid_shape = tile.grid_shape();
+
+    let grid_array = match stretch {
+        Some(stretch) if !tile.is_empty() => {
+            GridOrEmpty::from_index_fn_parallel(&grid_shape, |lin_idx: usize| {
+                let value = tile.get_at_grid_index_unchecked(lin_idx)?;
+                Some((stretch(value) * 255.).round() as u8)
+            })
+        }
+        _ => GridOrEmpty::Empty(EmptyGrid::new(grid_shape)),
+    };
+
+    RasterTile2D::new_with_properties(
+        tile.time,
+        tile.tile_position,
+        tile.global_geo_transform,
+        grid_array,
+        tile.properties.clone(),
+    )
+}
+
+/// Maps the values between `min` and `max` linearly to `[0, 1]` and clips the remaining values
+fn stretch_linearly(value: f64, (min, max): (f64, f64)) -> f64 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0., 1.)
+    } else if value < min {
+        0.
+    } else {
+        1.
+    }
+}
+
+/// The sorted values of a time step, from which the cumulative frequencies are computed
+fn sorted_values(tiles: &[RasterTile2D<f64>]) -> Vec<f64> {
+    let mut values: Vec<f64> = tiles
+        .iter()
+        .filter(|tile| !tile.is_empty())
+        .flat_map(|tile| {
+            let number_of_pixels = tile.grid_shape().number_of_elements();
+            (0..number_of_pixels).filter_map(|lin_idx| tile.get_at_grid_index_unchecked(lin_idx))
+        })
+        .filter(|value| !value.is_nan())
+        .collect();
+
+    values.sort_unstable_by(f64::total_cmp);
+
+    values
+}
+
+impl RasterStretchProcessor {
+    /// Stretches the percentiles in two passes over the source.
+    /// The first pass estimates the percentiles of each time step with t-digests, so that no tiles need to be buffered.
+    async fn stretch_percentiles<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+        lower: f64,
+        upper: f64,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<u8>>>> {
+        let ranges: Vec<(TimeInterval, Option<(f64, f64)>)> = self
+            .source
+            .query(query, ctx)
+            .await?
+            .and_then(move |tile| {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    (tile.time, tile_digest(&tile))
+                })
+                .map_err(Into::into)
+            })
+            .try_fold(
+                Vec::<(TimeInterval, TDigest)>::new(),
+                |mut digests, (time, digest)| async move {
+                    match digests.last_mut() {
+                        Some((last_time, last_digest)) if *last_time == time => {
+                            last_digest.merge(&digest);
+                        }
+                        _ => digests.push((time, digest)),
+                    }
+                    Ok(digests)
+                },
+            )
+            .await?
+            .into_iter()
+            .map(|(time, digest)| {
+                let range = digest
+                    .quantile(lower / 100.)
+                    .zip(digest.quantile(upper / 100.));
+                (time, range)
+            })
+            .collect();
+
+        let stream = self.source.query(query, ctx).await?.and_then(move |tile| {
+            let range = ranges
+                .iter()
+                .find(|(time, _)| *time == tile.time)
+                .and_then(|(_, range)| *range);
+
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                stretch_tile(
+                    &tile,
+                    range.map(|range| move |value| stretch_linearly(value, range)),
+                )
+            })
+            .map_err(Into::into)
+        });
+
+        Ok(stream.boxed())
+    }
+
+    async fn equalize<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<u8>>>> {
+        // the cumulative frequencies depend on all tiles of a time step, so the whole query result is buffered
+        let tiles: Vec<RasterTile2D<f64>> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let stretched =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                let mut stretched = Vec::with_capacity(tiles.len());
+
+                for time_step in time_steps(&tiles) {
+                    let values = sorted_values(time_step);
+                    let rank = |value: f64| {
+                        values.partition_point(|&v| v <= value) as f64 / values.len() as f64
+                    };
+
+                    stretched.extend(
+                        time_step
+                            .iter()
+                            .map(|tile| stretch_tile(tile, (!values.is_empty()).then_some(rank))),
+                    );
+                }
+
+                stretched
+            })
+            .await?;
+
+        Ok(stream::iter(stretched.into_iter().map(Ok)).boxed())
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for RasterStretchProcessor {
+    type Output = RasterTile2D<u8>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        match self.method {
+            StretchMethod::Percentile { lower, upper } => {
+                self.stretch_percentiles(query, ctx, lower, upper).await
+            }
+            StretchMethod::Equalization => self.equalize(query, ctx).await,
+        }
+    }
+}
+
+/// Splits the tiles of a raster stream into the consecutive runs of tiles that belong to the same time step
+fn time_steps(tiles: &[RasterTile2D<f64>]) -> Vec<&[RasterTile2D<f64>]> {
+    let mut time_steps = Vec::new();
+    let mut start = 0;
+
+    for i in 1..=tiles.len() {
+        if i == tiles.len() || tiles[i].time != tiles[start].time {
+            time_steps.push(&tiles[start..i]);
+            start = i;
+        }
+    }
+
+    time_steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::raster::{Grid2D, TileInformation, TilingSpecification};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    async fn stretch(method: StretchMethod, tiles: Vec<(TimeInterval, Vec<i32>)>) -> Vec<Vec<u8>> {
+        let source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles
+                    .into_iter()
+                    .map(|(time, values)| {
+                        RasterTile2D::new_with_tile_info(
+                            time,
+                            TileInformation {
+                                global_geo_transform: TestDefault::test_default(),
+                                global_tile_position: [0, 0].into(),
+                                tile_size_in_pixels: [2, 2].into(),
+                            },
+                            Grid2D::new([2, 2].into(), values).unwrap().into(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::I32,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed();
+
+        let processor = RasterStretch {
+            params: RasterStretchParams { method },
+            sources: SingleRasterSource { raster: source },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        ))
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 20),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        tiles
+            .iter()
+            .map(|tile| {
+                (0..4)
+                    .map(|i| tile.get_at_grid_index_unchecked(i).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_stretches_percentiles_per_time_step() {
+        assert_eq!(
+            stretch(
+                StretchMethod::Percentile {
+                    lower: 0.,
+                    upper: 100.
+                },
+                vec![
+                    (TimeInterval::new_unchecked(0, 10), vec![1, 2, 3, 4]),
+                    (TimeInterval::new_unchecked(10, 20), vec![10, 20, 30, 40]),
+                ]
+            )
+            .await,
+            vec![vec![0, 85, 170, 255], vec![0, 85, 170, 255]]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_clips_values_outside_of_the_percentiles() {
+        assert_eq!(
+            stretch(
+                StretchMethod::Percentile {
+                    lower: 40.,
+                    upper: 60.
+                },
+                vec![(TimeInterval::new_unchecked(0, 20), vec![1, 2, 3, 4])]
+            )
+            .await,
+            vec![vec![0, 0, 255, 255]]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_equalizes_histograms() {
+        assert_eq!(
+            stretch(
+                StretchMethod::Equalization,
+                vec![(TimeInterval::new_unchecked(0, 20), vec![1, 1, 1, 10])]
+            )
+            .await,
+            vec![vec![191, 191, 191, 255]]
+        );
+    }
+}
@@ -4,7 +4,7 @@ use futures::{StreamExt, TryStreamExt};
 use geoengine_datatypes::collections::{
     FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
 };
-use geoengine_datatypes::raster::{GridIndexAccess, Pixel, RasterDataType};
+use geoengine_datatypes::raster::{GridIndexAccess, Pixel};
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
 use crate::engine::{
@@ -18,7 +18,9 @@ use crate::processing::raster_vector_join::aggregator::{
 use crate::processing::raster_vector_join::TemporalAggregationMethod;
 use crate::util::Result;
 use async_trait::async_trait;
-use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, VectorQueryRectangle};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, Geometry, VectorQueryRectangle,
+};
 
 use super::util::{CoveredPixels, FeatureTimeSpanIter, PixelCoverCreator};
 use super::{create_feature_aggregator, FeatureAggregationMethod};
@@ -61,8 +63,11 @@ where
         query: VectorQueryRectangle,
         ctx: &dyn QueryContext,
     ) -> Result<FeatureCollection<G>> {
-        let mut temporal_aggregator =
-            Self::create_aggregator::<P>(collection.len(), temporal_aggregation);
+        let mut temporal_aggregator = Self::create_aggregator::<P>(
+            collection.len(),
+            feature_aggreation,
+            temporal_aggregation,
+        );
 
         let collection = collection.sort_by_time_asc()?;
 
@@ -115,7 +120,7 @@ where
                         if let Ok(pixel) = raster.get_at_grid_index(grid_idx) {
                             // finally, attach value to feature
                             if let Some(data) = pixel {
-                                feature_aggregator.add_value(feature_index, data, 1);
+                                feature_aggregator.add_value(feature_index, data, 1)?;
                             } else {
                                 // TODO: weigh by area?
                                 feature_aggregator.add_null(feature_index);
@@ -149,26 +154,21 @@ where
             .map_err(Into::into)
     }
 
+    /// Creates the aggregator over time for the values of the `feature_aggregation`
     fn create_aggregator<P: Pixel>(
         number_of_features: usize,
+        feature_aggregation: FeatureAggregationMethod,
         aggregation: TemporalAggregationMethod,
     ) -> TypedAggregator {
         match aggregation {
-            TemporalAggregationMethod::First => match P::TYPE {
-                RasterDataType::U8
-                | RasterDataType::U16
-                | RasterDataType::U32
-                | RasterDataType::U64
-                | RasterDataType::I8
-                | RasterDataType::I16
-                | RasterDataType::I32
-                | RasterDataType::I64 => {
-                    FirstValueIntAggregator::new(number_of_features).into_typed()
-                }
-                RasterDataType::F32 | RasterDataType::F64 => {
-                    FirstValueFloatAggregator::new(number_of_features).into_typed()
+            TemporalAggregationMethod::First => {
+                match feature_aggregation.output_data_type(P::TYPE) {
+                    FeatureDataType::Int => {
+                        FirstValueIntAggregator::new(number_of_features).into_typed()
+                    }
+                    _ => FirstValueFloatAggregator::new(number_of_features).into_typed(),
                 }
-            },
+            }
             TemporalAggregationMethod::Mean => {
                 MeanValueAggregator::new(number_of_features).into_typed()
             }
@@ -220,7 +220,7 @@ mod tests {
     use crate::mock::{MockRasterSource, MockRasterSourceParams};
     use geoengine_datatypes::collections::{MultiPointCollection, MultiPolygonCollection};
     use geoengine_datatypes::primitives::MultiPolygon;
-    use geoengine_datatypes::raster::{Grid2D, RasterTile2D, TileInformation};
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D, TileInformation};
     use geoengine_datatypes::spatial_reference::SpatialReference;
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_datatypes::{
@@ -1,8 +1,9 @@
 use crate::error;
 use crate::error::Error;
+use crate::util::math::NeumaierSum;
 use crate::util::Result;
 use geoengine_datatypes::primitives::{FeatureData, FeatureDataType};
-use geoengine_datatypes::raster::Pixel;
+use geoengine_datatypes::raster::{Pixel, RasterDataType};
 use num_traits::AsPrimitive;
 use snafu::ensure;
 
@@ -13,7 +14,9 @@ pub trait Aggregator {
     fn new(number_of_features: usize) -> Self;
 
     // TODO: add values for slice
-    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64)
+    /// Adds a value to the aggregate of a feature.
+    /// Fails if the value or the accumulated weights do not fit into the aggregator.
+    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64) -> Result<()>
     where
         P: Pixel + AsPrimitive<Self::Output>;
 
@@ -21,7 +24,7 @@ pub trait Aggregator {
 
     fn feature_data_type() -> FeatureDataType;
 
-    fn data(&self) -> &[Self::Output];
+    fn data(&self) -> Vec<Self::Output>;
 
     fn nulls(&self) -> &[bool];
 
@@ -42,22 +45,42 @@ pub enum TypedAggregator {
     FirstValueFloat(FirstValueFloatAggregator),
     FirstValueInt(FirstValueIntAggregator),
     MeanNumber(MeanValueAggregator),
+    MinNumber(MinValueAggregator),
+    MaxNumber(MaxValueAggregator),
+    SumNumber(SumAggregator),
+    MedianNumber(MedianAggregator),
+    StdDevNumber(StdDevAggregator),
 }
 
 impl TypedAggregator {
-    pub fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64)
+    pub fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64) -> Result<()>
     where
         P: Pixel + AsPrimitive<f64> + AsPrimitive<i64>,
     {
         match self {
             TypedAggregator::FirstValueFloat(aggregator) => {
-                aggregator.add_value(feature_idx, pixel, weight);
+                aggregator.add_value(feature_idx, pixel, weight)
             }
             TypedAggregator::FirstValueInt(aggregator) => {
-                aggregator.add_value(feature_idx, pixel, weight);
+                aggregator.add_value(feature_idx, pixel, weight)
             }
             TypedAggregator::MeanNumber(aggregator) => {
-                aggregator.add_value(feature_idx, pixel, weight);
+                aggregator.add_value(feature_idx, pixel, weight)
+            }
+            TypedAggregator::MinNumber(aggregator) => {
+                aggregator.add_value(feature_idx, pixel, weight)
+            }
+            TypedAggregator::MaxNumber(aggregator) => {
+                aggregator.add_value(feature_idx, pixel, weight)
+            }
+            TypedAggregator::SumNumber(aggregator) => {
+                aggregator.add_value(feature_idx, pixel, weight)
+            }
+            TypedAggregator::MedianNumber(aggregator) => {
+                aggregator.add_value(feature_idx, pixel, weight)
+            }
+            TypedAggregator::StdDevNumber(aggregator) => {
+                aggregator.add_value(feature_idx, pixel, weight)
             }
         }
     }
@@ -67,6 +90,11 @@ impl TypedAggregator {
             TypedAggregator::FirstValueFloat(aggregator) => aggregator.add_null(feature_idx),
             TypedAggregator::FirstValueInt(aggregator) => aggregator.add_null(feature_idx),
             TypedAggregator::MeanNumber(aggregator) => aggregator.add_null(feature_idx),
+            TypedAggregator::MinNumber(aggregator) => aggregator.add_null(feature_idx),
+            TypedAggregator::MaxNumber(aggregator) => aggregator.add_null(feature_idx),
+            TypedAggregator::SumNumber(aggregator) => aggregator.add_null(feature_idx),
+            TypedAggregator::MedianNumber(aggregator) => aggregator.add_null(feature_idx),
+            TypedAggregator::StdDevNumber(aggregator) => aggregator.add_null(feature_idx),
         }
     }
 
@@ -81,6 +109,21 @@ impl TypedAggregator {
             TypedAggregator::MeanNumber(aggregator) => {
                 FeatureData::NullableFloat(aggregator.into_data())
             }
+            TypedAggregator::MinNumber(aggregator) => {
+                FeatureData::NullableFloat(aggregator.into_data())
+            }
+            TypedAggregator::MaxNumber(aggregator) => {
+                FeatureData::NullableFloat(aggregator.into_data())
+            }
+            TypedAggregator::SumNumber(aggregator) => {
+                FeatureData::NullableFloat(aggregator.into_data())
+            }
+            TypedAggregator::MedianNumber(aggregator) => {
+                FeatureData::NullableFloat(aggregator.into_data())
+            }
+            TypedAggregator::StdDevNumber(aggregator) => {
+                FeatureData::NullableFloat(aggregator.into_data())
+            }
         }
     }
 
@@ -90,6 +133,11 @@ impl TypedAggregator {
             TypedAggregator::FirstValueFloat(aggregator) => aggregator.nulls(),
             TypedAggregator::FirstValueInt(aggregator) => aggregator.nulls(),
             TypedAggregator::MeanNumber(aggregator) => aggregator.nulls(),
+            TypedAggregator::MinNumber(aggregator) => aggregator.nulls(),
+            TypedAggregator::MaxNumber(aggregator) => aggregator.nulls(),
+            TypedAggregator::SumNumber(aggregator) => aggregator.nulls(),
+            TypedAggregator::MedianNumber(aggregator) => aggregator.nulls(),
+            TypedAggregator::StdDevNumber(aggregator) => aggregator.nulls(),
         }
     }
 
@@ -99,6 +147,11 @@ impl TypedAggregator {
             TypedAggregator::FirstValueFloat(aggregator) => aggregator.is_satisfied(),
             TypedAggregator::FirstValueInt(aggregator) => aggregator.is_satisfied(),
             TypedAggregator::MeanNumber(aggregator) => aggregator.is_satisfied(),
+            TypedAggregator::MinNumber(aggregator) => aggregator.is_satisfied(),
+            TypedAggregator::MaxNumber(aggregator) => aggregator.is_satisfied(),
+            TypedAggregator::SumNumber(aggregator) => aggregator.is_satisfied(),
+            TypedAggregator::MedianNumber(aggregator) => aggregator.is_satisfied(),
+            TypedAggregator::StdDevNumber(aggregator) => aggregator.is_satisfied(),
         }
     }
 
@@ -107,10 +160,47 @@ impl TypedAggregator {
             TypedAggregator::FirstValueFloat(a) => a.add_feature_data(data, weight),
             TypedAggregator::FirstValueInt(a) => a.add_feature_data(data, weight),
             TypedAggregator::MeanNumber(a) => a.add_feature_data(data, weight),
+            TypedAggregator::MinNumber(a) => a.add_feature_data(data, weight),
+            TypedAggregator::MaxNumber(a) => a.add_feature_data(data, weight),
+            TypedAggregator::SumNumber(a) => a.add_feature_data(data, weight),
+            TypedAggregator::MedianNumber(a) => a.add_feature_data(data, weight),
+            TypedAggregator::StdDevNumber(a) => a.add_feature_data(data, weight),
         }
     }
 }
 
+/// Adds the values of nullable numeric `data` to the features of the `aggregator`
+fn add_nullable_feature_data<A>(aggregator: &mut A, data: FeatureData, weight: u64) -> Result<()>
+where
+    A: Aggregator,
+    i64: AsPrimitive<A::Output>,
+    f64: AsPrimitive<A::Output>,
+{
+    match data {
+        FeatureData::NullableInt(values) => {
+            for (i, &value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    aggregator.add_value(i, value, weight)?;
+                } else {
+                    aggregator.add_null(i);
+                }
+            }
+        }
+        FeatureData::NullableFloat(values) => {
+            for (i, &value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    aggregator.add_value(i, value, weight)?;
+                } else {
+                    aggregator.add_null(i);
+                }
+            }
+        }
+        _ => return Err(Error::FeatureDataNotAggregatable),
+    }
+
+    Ok(())
+}
+
 pub type FirstValueFloatAggregator = FirstValueAggregator<f64>;
 pub type FirstValueIntAggregator = FirstValueAggregator<i64>;
 
@@ -137,18 +227,25 @@ where
         }
     }
 
-    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, _weight: u64)
+    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, _weight: u64) -> Result<()>
     where
         P: Pixel + AsPrimitive<Self::Output>,
     {
         if self.not_pristine[feature_idx] {
-            return;
+            return Ok(());
         }
 
-        self.values[feature_idx] = pixel.as_();
+        self.values[feature_idx] =
+            T::checked_from_pixel(pixel).ok_or_else(|| Error::AggregationValueOverflow {
+                value: format!("{pixel:?}"),
+                feature_index: feature_idx,
+                data_type: T::feature_data_type(),
+            })?;
 
         self.not_pristine[feature_idx] = true;
         self.number_of_pristine_values -= 1;
+
+        Ok(())
     }
 
     fn add_null(&mut self, feature_idx: usize) {
@@ -166,8 +263,8 @@ where
         T::feature_data_type()
     }
 
-    fn data(&self) -> &[Self::Output] {
-        &self.values
+    fn data(&self) -> Vec<Self::Output> {
+        self.values.clone()
     }
 
     fn nulls(&self) -> &[bool] {
@@ -196,34 +293,16 @@ where
             error::FeatureDataLengthMismatch
         );
 
-        match data {
-            geoengine_datatypes::primitives::FeatureData::NullableInt(values) => {
-                for (i, &value) in values.iter().enumerate() {
-                    if let Some(value) = value {
-                        self.add_value(i, T::from_(value), weight);
-                    } else {
-                        self.add_null(i);
-                    }
-                }
-            }
-            geoengine_datatypes::primitives::FeatureData::NullableFloat(values) => {
-                for (i, &value) in values.iter().enumerate() {
-                    if let Some(value) = value {
-                        self.add_value(i, T::from_(value), weight);
-                    } else {
-                        self.add_null(i);
-                    }
-                }
-            }
-            _ => return Err(Error::FeatureDataNotAggregatable),
-        }
-
-        Ok(())
+        add_nullable_feature_data(self, data, weight)
     }
 }
 
-pub trait FirstValueOutputType {
+pub trait FirstValueOutputType: Copy + 'static {
     fn feature_data_type() -> FeatureDataType;
+    /// Converts the pixel value or returns `None` if it does not fit into the output type
+    fn checked_from_pixel<P>(pixel: P) -> Option<Self>
+    where
+        P: Pixel + AsPrimitive<Self>;
     fn typed_aggregator(aggregator: FirstValueAggregator<Self>) -> TypedAggregator
     where
         Self: Sized;
@@ -234,6 +313,22 @@ impl FirstValueOutputType for i64 {
         FeatureDataType::Int
     }
 
+    fn checked_from_pixel<P>(pixel: P) -> Option<Self>
+    where
+        P: Pixel + AsPrimitive<Self>,
+    {
+        match P::TYPE {
+            RasterDataType::U64 => i64::try_from(AsPrimitive::<u64>::as_(pixel)).ok(),
+            RasterDataType::F32 | RasterDataType::F64 => {
+                let value: f64 = pixel.as_();
+                // `i64::MAX` is not representable as `f64`, so the upper bound is exclusive
+                (value.is_finite() && value >= i64::MIN as f64 && value < -(i64::MIN as f64))
+                    .then(|| value as i64)
+            }
+            _ => Some(pixel.as_()),
+        }
+    }
+
     fn typed_aggregator(aggregator: FirstValueAggregator<Self>) -> TypedAggregator {
         TypedAggregator::FirstValueInt(aggregator)
     }
@@ -244,15 +339,25 @@ impl FirstValueOutputType for f64 {
         FeatureDataType::Float
     }
 
+    fn checked_from_pixel<P>(pixel: P) -> Option<Self>
+    where
+        P: Pixel + AsPrimitive<Self>,
+    {
+        Some(pixel.as_())
+    }
+
     fn typed_aggregator(aggregator: FirstValueAggregator<Self>) -> TypedAggregator {
         TypedAggregator::FirstValueFloat(aggregator)
     }
 }
 
-/// Aggregation function that calculates the weighted mean
+/// Aggregation function that calculates the weighted mean.
+///
+/// The weighted values are summed up with compensation, so the means do not drift for large numbers of pixels.
 pub struct MeanValueAggregator {
     means: Vec<f64>,
-    sum_weights: Vec<f64>,
+    weighted_sums: Vec<NeumaierSum>,
+    sum_weights: Vec<u64>,
     null: Vec<bool>,
     number_of_non_null_values: usize,
 }
@@ -263,30 +368,37 @@ impl Aggregator for MeanValueAggregator {
     fn new(number_of_features: usize) -> Self {
         Self {
             means: vec![0.; number_of_features],
-            sum_weights: vec![0.; number_of_features],
+            weighted_sums: vec![NeumaierSum::default(); number_of_features],
+            sum_weights: vec![0; number_of_features],
             null: vec![false; number_of_features],
             number_of_non_null_values: number_of_features,
         }
     }
 
-    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64)
+    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64) -> Result<()>
     where
         P: Pixel + AsPrimitive<Self::Output>,
     {
         debug_assert!(weight > 0, "weights must be positive and non-zero");
 
         if self.null[feature_idx] {
-            return;
+            return Ok(());
         }
 
+        self.sum_weights[feature_idx] = self.sum_weights[feature_idx].checked_add(weight).ok_or(
+            Error::AggregationWeightOverflow {
+                feature_index: feature_idx,
+            },
+        )?;
+        let sum_weights: f64 = self.sum_weights[feature_idx].as_();
+
         let value: f64 = pixel.as_();
         let weight: f64 = weight.as_();
 
-        let old_mean = self.means[feature_idx];
-        let old_normalized_weight = self.sum_weights[feature_idx] / weight;
+        self.weighted_sums[feature_idx].add(value * weight);
+        self.means[feature_idx] = self.weighted_sums[feature_idx].value() / sum_weights;
 
-        self.sum_weights[feature_idx] += weight;
-        self.means[feature_idx] += (value - old_mean) / (old_normalized_weight + 1.);
+        Ok(())
     }
 
     fn add_null(&mut self, feature_idx: usize) {
@@ -302,8 +414,8 @@ impl Aggregator for MeanValueAggregator {
         FeatureDataType::Float
     }
 
-    fn data(&self) -> &[Self::Output] {
-        &self.means
+    fn data(&self) -> Vec<Self::Output> {
+        self.means.clone()
     }
 
     fn nulls(&self) -> &[bool] {
@@ -332,45 +444,267 @@ impl Aggregator for MeanValueAggregator {
             error::FeatureDataLengthMismatch
         );
 
-        match data {
-            geoengine_datatypes::primitives::FeatureData::NullableInt(values) => {
-                for (i, &value) in values.iter().enumerate() {
-                    if let Some(value) = value {
-                        self.add_value(i, value, weight);
-                    } else {
-                        self.add_null(i);
-                    }
-                }
+        add_nullable_feature_data(self, data, weight)
+    }
+}
+
+pub type MinValueAggregator = StatisticAggregator<MinStatistic>;
+pub type MaxValueAggregator = StatisticAggregator<MaxStatistic>;
+pub type SumAggregator = StatisticAggregator<SumStatistic>;
+pub type MedianAggregator = StatisticAggregator<MedianStatistic>;
+pub type StdDevAggregator = StatisticAggregator<StdDevStatistic>;
+
+/// Aggregation function that calculates a [`Statistic`] of the values of each feature.
+///
+/// Features without values have no outcome, i.e., `NaN` as data and `None` as output.
+pub struct StatisticAggregator<S> {
+    statistics: Vec<S>,
+    null: Vec<bool>,
+    number_of_non_null_values: usize,
+}
+
+impl<S> Aggregator for StatisticAggregator<S>
+where
+    S: Statistic,
+{
+    type Output = f64;
+
+    fn new(number_of_features: usize) -> Self {
+        Self {
+            statistics: vec![S::default(); number_of_features],
+            null: vec![false; number_of_features],
+            number_of_non_null_values: number_of_features,
+        }
+    }
+
+    fn add_value<P>(&mut self, feature_idx: usize, pixel: P, weight: u64) -> Result<()>
+    where
+        P: Pixel + AsPrimitive<Self::Output>,
+    {
+        debug_assert!(weight > 0, "weights must be positive and non-zero");
+
+        if self.null[feature_idx] {
+            return Ok(());
+        }
+
+        self.statistics[feature_idx].add(pixel.as_(), weight).ok_or(
+            Error::AggregationWeightOverflow {
+                feature_index: feature_idx,
+            },
+        )
+    }
+
+    fn add_null(&mut self, feature_idx: usize) {
+        if self.null[feature_idx] {
+            return;
+        }
+
+        self.null[feature_idx] = true;
+        self.number_of_non_null_values -= 1;
+    }
+
+    fn feature_data_type() -> FeatureDataType {
+        FeatureDataType::Float
+    }
+
+    fn data(&self) -> Vec<Self::Output> {
+        self.statistics
+            .iter()
+            .map(|statistic| statistic.value().unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    fn nulls(&self) -> &[bool] {
+        &self.null
+    }
+
+    fn into_data(self) -> Vec<Option<Self::Output>> {
+        self.statistics
+            .into_iter()
+            .zip(self.null)
+            .map(|(statistic, is_null)| if is_null { None } else { statistic.value() })
+            .collect()
+    }
+
+    fn into_typed(self) -> TypedAggregator {
+        S::typed_aggregator(self)
+    }
+
+    fn is_satisfied(&self) -> bool {
+        self.number_of_non_null_values == 0
+    }
+
+    fn add_feature_data(&mut self, data: FeatureData, weight: u64) -> Result<()> {
+        ensure!(
+            data.len() == self.statistics.len(),
+            error::FeatureDataLengthMismatch
+        );
+
+        add_nullable_feature_data(self, data, weight)
+    }
+}
+
+/// A statistic of the values of a feature that is updated value by value
+pub trait Statistic: Default + Clone + Send + Sync + 'static {
+    /// Adds a `value` that occurs `weight` times.
+    /// Returns `None` if the accumulated weights do not fit into the statistic.
+    fn add(&mut self, value: f64, weight: u64) -> Option<()>;
+
+    /// The statistic of the added values or `None` if there are none
+    fn value(&self) -> Option<f64>;
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MinStatistic(Option<f64>);
+
+impl Statistic for MinStatistic {
+    fn add(&mut self, value: f64, _weight: u64) -> Option<()> {
+        self.0 = Some(self.0.map_or(value, |min| min.min(value)));
+        Some(())
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.0
+    }
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator {
+        TypedAggregator::MinNumber(aggregator)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MaxStatistic(Option<f64>);
+
+impl Statistic for MaxStatistic {
+    fn add(&mut self, value: f64, _weight: u64) -> Option<()> {
+        self.0 = Some(self.0.map_or(value, |max| max.max(value)));
+        Some(())
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.0
+    }
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator {
+        TypedAggregator::MaxNumber(aggregator)
+    }
+}
+
+/// The weighted sum, which is summed up with compensation like the mean
+#[derive(Debug, Clone, Default)]
+pub struct SumStatistic(Option<NeumaierSum>);
+
+impl Statistic for SumStatistic {
+    fn add(&mut self, value: f64, weight: u64) -> Option<()> {
+        let weight: f64 = weight.as_();
+        self.0
+            .get_or_insert_with(NeumaierSum::default)
+            .add(value * weight);
+        Some(())
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.0.as_ref().map(NeumaierSum::value)
+    }
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator {
+        TypedAggregator::SumNumber(aggregator)
+    }
+}
+
+/// The weighted median, which is the mean of the two middle values for an even total weight.
+///
+/// All values are kept until the median is requested.
+#[derive(Debug, Clone, Default)]
+pub struct MedianStatistic {
+    values: Vec<(f64, u64)>,
+    sum_weights: u64,
+}
+
+impl Statistic for MedianStatistic {
+    fn add(&mut self, value: f64, weight: u64) -> Option<()> {
+        self.sum_weights = self.sum_weights.checked_add(weight)?;
+        self.values.push((value, weight));
+        Some(())
+    }
+
+    fn value(&self) -> Option<f64> {
+        let mut values = self.values.clone();
+        values.sort_unstable_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let sum_weights = u128::from(self.sum_weights);
+        let mut cumulative_weights = 0_u128;
+
+        for (i, &(value, weight)) in values.iter().enumerate() {
+            cumulative_weights += u128::from(weight);
+
+            if 2 * cumulative_weights == sum_weights {
+                // the median is between this and the next value
+                return values.get(i + 1).map(|&(next, _)| (value + next) / 2.);
             }
-            geoengine_datatypes::primitives::FeatureData::NullableFloat(values) => {
-                for (i, &value) in values.iter().enumerate() {
-                    if let Some(value) = value {
-                        self.add_value(i, value, weight);
-                    } else {
-                        self.add_null(i);
-                    }
-                }
+            if 2 * cumulative_weights > sum_weights {
+                return Some(value);
             }
-            _ => return Err(Error::FeatureDataNotAggregatable),
         }
 
-        Ok(())
+        None
+    }
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator {
+        TypedAggregator::MedianNumber(aggregator)
+    }
+}
+
+/// The weighted population standard deviation, which is updated incrementally (West, 1979)
+#[derive(Debug, Clone, Default)]
+pub struct StdDevStatistic {
+    sum_weights: u64,
+    mean: f64,
+    sum_squared_deviations: f64,
+}
+
+impl Statistic for StdDevStatistic {
+    fn add(&mut self, value: f64, weight: u64) -> Option<()> {
+        self.sum_weights = self.sum_weights.checked_add(weight)?;
+
+        let sum_weights: f64 = self.sum_weights.as_();
+        let weight: f64 = weight.as_();
+
+        let delta = value - self.mean;
+        self.mean += delta * weight / sum_weights;
+        self.sum_squared_deviations += weight * delta * (value - self.mean);
+
+        Some(())
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.sum_weights > 0).then(|| {
+            let sum_weights: f64 = self.sum_weights.as_();
+            (self.sum_squared_deviations / sum_weights).sqrt()
+        })
+    }
+
+    fn typed_aggregator(aggregator: StatisticAggregator<Self>) -> TypedAggregator {
+        TypedAggregator::StdDevNumber(aggregator)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::{BigRational, ToPrimitive};
 
     #[test]
     #[allow(clippy::float_cmp)]
     fn fist_value_f64() {
         let mut aggregator = FirstValueFloatAggregator::new(2);
 
-        aggregator.add_value(0, 1, 1);
-        aggregator.add_value(0, 2, 1);
+        aggregator.add_value(0, 1, 1).unwrap();
+        aggregator.add_value(0, 2, 1).unwrap();
 
-        aggregator.add_value(1, 10, 1);
+        aggregator.add_value(1, 10, 1).unwrap();
 
         assert_eq!(aggregator.data(), &[1., 10.]);
     }
@@ -379,35 +713,160 @@ mod tests {
     fn fist_value_i64() {
         let mut aggregator = FirstValueIntAggregator::new(2);
 
-        aggregator.add_value(0, 2., 1);
-        aggregator.add_value(0, 0., 1);
+        aggregator.add_value(0, 2., 1).unwrap();
+        aggregator.add_value(0, 0., 1).unwrap();
 
-        aggregator.add_value(1, 4., 1);
+        aggregator.add_value(1, 4., 1).unwrap();
 
         assert_eq!(aggregator.data(), &[2, 4]);
     }
 
+    #[test]
+    fn fist_value_i64_overflow() {
+        let mut aggregator = FirstValueIntAggregator::new(3);
+
+        aggregator.add_value(0, i64::MAX as u64, 1).unwrap();
+
+        assert!(matches!(
+            aggregator.add_value(1, u64::MAX, 1),
+            Err(Error::AggregationValueOverflow {
+                feature_index: 1,
+                data_type: FeatureDataType::Int,
+                ..
+            })
+        ));
+        assert!(matches!(
+            aggregator.add_value(1, 1e19, 1),
+            Err(Error::AggregationValueOverflow { .. })
+        ));
+        assert!(matches!(
+            aggregator.add_value(2, f64::NAN, 1),
+            Err(Error::AggregationValueOverflow { .. })
+        ));
+
+        assert_eq!(aggregator.data(), &[i64::MAX, 0, 0]);
+        assert!(!aggregator.is_satisfied());
+    }
+
     #[test]
     #[allow(clippy::float_cmp)]
     fn mean() {
         let mut aggregator = MeanValueAggregator::new(2);
 
         for i in 1..=10 {
-            aggregator.add_value(0, i, 1);
-            aggregator.add_value(1, i, i);
+            aggregator.add_value(0, i, 1).unwrap();
+            aggregator.add_value(1, i, i).unwrap();
         }
 
         assert_eq!(aggregator.data(), &[5.5, 385. / 55.]);
     }
 
+    #[test]
+    fn mean_of_large_counts() {
+        // the values repeat, so the exact mean of one period is the exact mean of all values
+        let period: Vec<(f64, u64)> = (0..100)
+            .map(|k| (1e8 + f64::from(k) * 0.01, u64::from(k % 7 + 1)))
+            .collect();
+
+        let exact_mean = {
+            let (sum, weights) = period.iter().fold(
+                (BigRational::from_integer(0.into()), 0_u64),
+                |(sum, weights), &(value, weight)| {
+                    (
+                        sum + BigRational::from_float(value).unwrap()
+                            * BigRational::from_integer(weight.into()),
+                        weights + weight,
+                    )
+                },
+            );
+            (sum / BigRational::from_integer(weights.into()))
+                .to_f64()
+                .unwrap()
+        };
+
+        let mut aggregator = MeanValueAggregator::new(1);
+        for _ in 0..100_000 {
+            for &(value, weight) in &period {
+                aggregator.add_value(0, value, weight).unwrap();
+            }
+        }
+
+        let mean = aggregator.data()[0];
+        assert!(
+            (mean - exact_mean).abs() <= f64::EPSILON * exact_mean,
+            "{mean} != {exact_mean}"
+        );
+    }
+
+    #[test]
+    fn mean_weight_overflow() {
+        let mut aggregator = MeanValueAggregator::new(1);
+
+        aggregator.add_value(0, 1, u64::MAX - 1).unwrap();
+        aggregator.add_value(0, 1, 1).unwrap();
+
+        assert!(matches!(
+            aggregator.add_value(0, 1, 1),
+            Err(Error::AggregationWeightOverflow { feature_index: 0 })
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn statistics() {
+        let mut min = MinValueAggregator::new(2);
+        let mut max = MaxValueAggregator::new(2);
+        let mut sum = SumAggregator::new(2);
+        let mut median = MedianAggregator::new(2);
+        let mut std_dev = StdDevAggregator::new(2);
+
+        for (value, weight) in [(4, 1), (1, 2), (9, 1), (6, 1)] {
+            min.add_value(0, value, weight).unwrap();
+            max.add_value(0, value, weight).unwrap();
+            sum.add_value(0, value, weight).unwrap();
+            median.add_value(0, value, weight).unwrap();
+            std_dev.add_value(0, value, weight).unwrap();
+        }
+
+        // the values are 1, 1, 4, 6, 9 with a mean of 4.2
+        assert_eq!(min.data()[0], 1.);
+        assert_eq!(max.data()[0], 9.);
+        assert_eq!(sum.data()[0], 21.);
+        assert_eq!(median.data()[0], 4.);
+        assert!((std_dev.data()[0] - 3.059_411_708_155_671).abs() < 1e-12);
+
+        // there are no values for the second feature
+        assert_eq!(
+            median.into_typed().into_data(),
+            FeatureData::NullableFloat(vec![Some(4.), None])
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn median_of_even_weights() {
+        let mut aggregator = MedianAggregator::new(1);
+
+        for value in [4, 1, 3, 2] {
+            aggregator.add_value(0, value, 1).unwrap();
+        }
+
+        assert_eq!(aggregator.data(), &[2.5]);
+
+        aggregator.add_null(0);
+
+        assert!(aggregator.is_satisfied());
+        assert_eq!(aggregator.into_data(), vec![None]);
+    }
+
     #[test]
     fn typed() {
         let mut aggregator = FirstValueIntAggregator::new(2).into_typed();
 
-        aggregator.add_value(0, 2., 1);
-        aggregator.add_value(0, 0., 1);
+        aggregator.add_value(0, 2., 1).unwrap();
+        aggregator.add_value(0, 0., 1).unwrap();
 
-        aggregator.add_value(1, 4., 1);
+        aggregator.add_value(1, 4., 1).unwrap();
 
         if let TypedAggregator::FirstValueInt(ref aggregator) = aggregator {
             assert_eq!(aggregator.data(), &[2, 4]);
@@ -427,15 +886,15 @@ mod tests {
 
         assert!(!aggregator.is_satisfied());
 
-        aggregator.add_value(0, 2., 1);
+        aggregator.add_value(0, 2., 1).unwrap();
 
         assert!(!aggregator.is_satisfied());
 
-        aggregator.add_value(1, 0., 1);
+        aggregator.add_value(1, 0., 1).unwrap();
 
         assert!(aggregator.is_satisfied());
 
-        aggregator.add_value(1, 4., 1);
+        aggregator.add_value(1, 4., 1).unwrap();
 
         assert!(aggregator.is_satisfied());
     }
@@ -458,7 +917,7 @@ mod tests {
     fn value_then_null() {
         let mut aggregator = FirstValueIntAggregator::new(1).into_typed();
 
-        aggregator.add_value(0, 1337, 1);
+        aggregator.add_value(0, 1337, 1).unwrap();
         aggregator.add_null(0);
 
         assert_eq!(
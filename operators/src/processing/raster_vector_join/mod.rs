@@ -8,7 +8,7 @@ use crate::engine::{
     OperatorName, SingleVectorMultipleRasterSources, TypedVectorQueryProcessor, VectorColumnInfo,
     VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
 };
-use crate::error::{self, Error};
+use crate::error;
 use crate::processing::raster_vector_join::non_aggregated::RasterVectorJoinProcessor;
 use crate::util::Result;
 
@@ -23,7 +23,8 @@ use snafu::ensure;
 use tracing::{span, Level};
 
 use self::aggregator::{
-    Aggregator, FirstValueFloatAggregator, FirstValueIntAggregator, MeanValueAggregator,
+    Aggregator, FirstValueFloatAggregator, FirstValueIntAggregator, MaxValueAggregator,
+    MeanValueAggregator, MedianAggregator, MinValueAggregator, StdDevAggregator, SumAggregator,
     TypedAggregator,
 };
 
@@ -59,6 +60,32 @@ pub struct RasterVectorJoinParams {
 pub enum FeatureAggregationMethod {
     First,
     Mean,
+    Min,
+    Max,
+    Median,
+    Sum,
+    /// The population standard deviation
+    StdDev,
+}
+
+impl FeatureAggregationMethod {
+    /// The data type of the aggregated values of a raster with the given data type
+    pub fn output_data_type(self, raster_data_type: RasterDataType) -> FeatureDataType {
+        match (self, raster_data_type) {
+            (
+                FeatureAggregationMethod::First,
+                RasterDataType::U8
+                | RasterDataType::U16
+                | RasterDataType::U32
+                | RasterDataType::U64
+                | RasterDataType::I8
+                | RasterDataType::I16
+                | RasterDataType::I32
+                | RasterDataType::I64,
+            ) => FeatureDataType::Int,
+            _ => FeatureDataType::Float,
+        }
+    }
 }
 
 /// How to aggregate the values over time
@@ -141,19 +168,9 @@ impl VectorOperator for RasterVectorJoin {
             let mut columns = columns.clone();
             for (i, new_column_name) in params.names.iter().enumerate() {
                 let feature_data_type = match params.temporal_aggregation {
-                    TemporalAggregationMethod::First | TemporalAggregationMethod::None => {
-                        match raster_sources[i].result_descriptor().data_type {
-                            RasterDataType::U8
-                            | RasterDataType::U16
-                            | RasterDataType::U32
-                            | RasterDataType::U64
-                            | RasterDataType::I8
-                            | RasterDataType::I16
-                            | RasterDataType::I32
-                            | RasterDataType::I64 => FeatureDataType::Int,
-                            RasterDataType::F32 | RasterDataType::F64 => FeatureDataType::Float,
-                        }
-                    }
+                    TemporalAggregationMethod::First | TemporalAggregationMethod::None => params
+                        .feature_aggregation
+                        .output_data_type(raster_sources[i].result_descriptor().data_type),
                     TemporalAggregationMethod::Mean => FeatureDataType::Float,
                 };
                 columns.insert(
@@ -242,7 +259,27 @@ impl InitializedVectorOperator for InitializedRasterVectorJoin {
                     }
                 })
             }
-            TypedVectorQueryProcessor::MultiLineString(_) => return Err(Error::NotYetImplemented),
+            TypedVectorQueryProcessor::MultiLineString(lines) => {
+                TypedVectorQueryProcessor::MultiLineString(match self.state.temporal_aggregation {
+                    TemporalAggregationMethod::None => RasterVectorJoinProcessor::new(
+                        lines,
+                        typed_raster_processors,
+                        self.state.names.clone(),
+                        self.state.feature_aggregation,
+                    )
+                    .boxed(),
+                    TemporalAggregationMethod::First | TemporalAggregationMethod::Mean => {
+                        RasterVectorAggregateJoinProcessor::new(
+                            lines,
+                            typed_raster_processors,
+                            self.state.names.clone(),
+                            self.state.feature_aggregation,
+                            self.state.temporal_aggregation,
+                        )
+                        .boxed()
+                    }
+                })
+            }
         })
     }
 }
@@ -266,6 +303,11 @@ pub fn create_feature_aggregator<P: Pixel>(
             }
         },
         FeatureAggregationMethod::Mean => MeanValueAggregator::new(number_of_features).into_typed(),
+        FeatureAggregationMethod::Min => MinValueAggregator::new(number_of_features).into_typed(),
+        FeatureAggregationMethod::Max => MaxValueAggregator::new(number_of_features).into_typed(),
+        FeatureAggregationMethod::Median => MedianAggregator::new(number_of_features).into_typed(),
+        FeatureAggregationMethod::Sum => SumAggregator::new(number_of_features).into_typed(),
+        FeatureAggregationMethod::StdDev => StdDevAggregator::new(number_of_features).into_typed(),
     }
 }
 
@@ -277,6 +319,7 @@ mod tests {
     use crate::engine::{
         ChunkByteSize, MockExecutionContext, MockQueryContext, QueryProcessor, RasterOperator,
     };
+    use crate::error::Error;
     use crate::mock::MockFeatureCollectionSource;
     use crate::source::{GdalSource, GdalSourceParameters};
     use crate::util::gdal::add_ndvi_dataset;
@@ -217,7 +217,7 @@ where
                 };
 
                 if let Some(data) = value {
-                    aggregator.add_value(feature_index, data, 1);
+                    aggregator.add_value(feature_index, data, 1)?;
                 } else {
                     aggregator.add_null(feature_index);
                 }
@@ -284,8 +284,12 @@ mod tests {
     use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
     use crate::source::{GdalSource, GdalSourceParameters};
     use crate::util::gdal::add_ndvi_dataset;
-    use geoengine_datatypes::collections::{MultiPointCollection, MultiPolygonCollection};
-    use geoengine_datatypes::primitives::{BoundingBox2D, DateTime, FeatureData, MultiPolygon};
+    use geoengine_datatypes::collections::{
+        MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+    };
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, DateTime, FeatureData, MultiLineString, MultiPolygon,
+    };
     use geoengine_datatypes::primitives::{Measurement, SpatialResolution};
     use geoengine_datatypes::primitives::{MultiPoint, TimeInterval};
     use geoengine_datatypes::raster::{
@@ -1020,4 +1024,135 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    #[allow(clippy::too_many_lines)]
+    async fn lines() {
+        let tile = |time: TimeInterval, global_tile_position: [isize; 2], values: Vec<u8>| {
+            RasterTile2D::new_with_tile_info(
+                time,
+                TileInformation {
+                    global_geo_transform: TestDefault::test_default(),
+                    global_tile_position: global_tile_position.into(),
+                    tile_size_in_pixels: [3, 2].into(),
+                },
+                Grid2D::new([3, 2].into(), values).unwrap().into(),
+            )
+        };
+
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile(
+                        TimeInterval::new(0, 10).unwrap(),
+                        [0, 0],
+                        vec![6, 5, 4, 3, 2, 1],
+                    ),
+                    tile(
+                        TimeInterval::new(0, 10).unwrap(),
+                        [0, 1],
+                        vec![60, 50, 40, 30, 20, 10],
+                    ),
+                    tile(
+                        TimeInterval::new(10, 20).unwrap(),
+                        [0, 0],
+                        vec![1, 2, 3, 4, 5, 6],
+                    ),
+                    tile(
+                        TimeInterval::new(10, 20).unwrap(),
+                        [0, 1],
+                        vec![10, 20, 30, 40, 50, 60],
+                    ),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed();
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [3, 2].into()),
+        );
+
+        let raster = raster_source
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap();
+
+        // crosses the pixels (0, 0), (0, 1), (1, 1) of the first tile and (1, 0), (2, 0), (2, 1) of the second tile
+        let line =
+            MultiLineString::new(vec![vec![(0.5, -0.5).into(), (3.5, -2.5).into()]]).unwrap();
+
+        let lines = MockFeatureCollectionSource::single(
+            MultiLineStringCollection::from_data(
+                vec![line.clone()],
+                vec![TimeInterval::default(); 1],
+                Default::default(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_line_string()
+        .unwrap();
+
+        let processor = RasterVectorJoinProcessor::new(
+            lines,
+            vec![raster],
+            vec!["foo".to_owned()],
+            FeatureAggregationMethod::Mean,
+        );
+
+        let mut result = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0.0, -3.0).into(), (4.0, 0.0).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 20),
+                    spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                },
+                &MockQueryContext::new(ChunkByteSize::MAX),
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<MultiLineStringCollection>>()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        let result = result.remove(0);
+
+        assert_eq!(
+            result,
+            MultiLineStringCollection::from_slices(
+                &[line.clone(), line],
+                &[
+                    TimeInterval::new(0, 10).unwrap(),
+                    TimeInterval::new(10, 20).unwrap()
+                ],
+                &[(
+                    "foo",
+                    FeatureData::Float(vec![
+                        (6. + 5. + 3. + 40. + 20. + 10.) / 6.,
+                        (1. + 2. + 4. + 30. + 50. + 60.) / 6.
+                    ])
+                )],
+            )
+            .unwrap()
+        );
+    }
 }
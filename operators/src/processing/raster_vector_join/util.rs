@@ -1,8 +1,11 @@
 use std::iter::Enumerate;
 
 use geoengine_datatypes::collections::{FeatureCollection, GeometryRandomAccess};
-use geoengine_datatypes::primitives::{Geometry, MultiPoint, MultiPointAccess, MultiPolygon};
-use geoengine_datatypes::raster::{GridContains, GridShapeAccess};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, Geometry, MultiLineString, MultiLineStringAccess, MultiPoint, MultiPointAccess,
+    MultiPolygon,
+};
+use geoengine_datatypes::raster::{GeoTransform, GridContains, GridShapeAccess};
 use geoengine_datatypes::{
     primitives::TimeInterval,
     raster::{GridIdx2D, Pixel, RasterTile2D},
@@ -185,6 +188,162 @@ impl CoveredPixels<MultiPolygon> for MultiPolygonCoveredPixels {
     }
 }
 
+pub struct MultiLineStringCoveredPixels {
+    collection: FeatureCollection<MultiLineString>,
+}
+
+impl CoveredPixels<MultiLineString> for MultiLineStringCoveredPixels {
+    fn initialize(collection: FeatureCollection<MultiLineString>) -> Self {
+        Self { collection }
+    }
+
+    fn covered_pixels<P: Pixel>(
+        &self,
+        feature_index: usize,
+        raster: &RasterTile2D<P>,
+    ) -> Vec<GridIdx2D> {
+        let geometry = if let Some(geometry) = self.collection.geometry_at(feature_index) {
+            geometry
+        } else {
+            return vec![];
+        };
+
+        let geo_transform = raster.tile_information().tile_geo_transform();
+        let [height, width] = raster.grid_shape_array();
+
+        // pixels that are traversed by several segments are only covered once
+        let mut covered = vec![false; height * width];
+
+        for line in geometry.lines() {
+            let line = line.as_ref();
+
+            if let [coordinate] = line {
+                let idx = geo_transform.coordinate_to_grid_idx_2d(*coordinate);
+                if raster.grid_shape().contains(&idx) {
+                    let [row, col] = *idx.inner();
+                    covered[row as usize * width + col as usize] = true;
+                }
+            }
+
+            for segment in line.windows(2) {
+                for [row, col] in
+                    traverse_segment(&geo_transform, segment[0], segment[1], [height, width])
+                {
+                    covered[row * width + col] = true;
+                }
+            }
+        }
+
+        covered
+            .iter()
+            .enumerate()
+            .filter(|(_, covered)| **covered)
+            .map(|(i, _)| [(i / width) as isize, (i % width) as isize].into())
+            .collect()
+    }
+
+    fn collection_ref(&self) -> &FeatureCollection<MultiLineString> {
+        &self.collection
+    }
+
+    fn collection(self) -> FeatureCollection<MultiLineString> {
+        self.collection
+    }
+}
+
+/// Returns the `[row, col]` indices of all pixels of a grid with the `shape` that the segment from `start` to `end` passes through.
+///
+/// The segment is clipped to the grid and then traversed from pixel boundary to pixel boundary,
+/// so that the pixels of adjacent tiles are consistent for segments that cross tiles.
+fn traverse_segment(
+    geo_transform: &GeoTransform,
+    start: Coordinate2D,
+    end: Coordinate2D,
+    [height, width]: [usize; 2],
+) -> Vec<[usize; 2]> {
+    let to_grid = |c: Coordinate2D| {
+        [
+            (c.x - geo_transform.origin_coordinate.x) / geo_transform.x_pixel_size(),
+            (c.y - geo_transform.origin_coordinate.y) / geo_transform.y_pixel_size(),
+        ]
+    };
+
+    let [x0, y0] = to_grid(start);
+    let [x1, y1] = to_grid(end);
+    let (dx, dy) = (x1 - x0, y1 - y0);
+
+    // Liang-Barsky clipping of the segment's parameter range to the grid
+    let (mut t_start, mut t_end) = (0_f64, 1_f64);
+    for (p, q) in [
+        (-dx, x0),
+        (dx, width as f64 - x0),
+        (-dy, y0),
+        (dy, height as f64 - y0),
+    ] {
+        if p == 0. {
+            if q < 0. {
+                return vec![];
+            }
+        } else if p < 0. {
+            t_start = t_start.max(q / p);
+        } else {
+            t_end = t_end.min(q / p);
+        }
+    }
+
+    if t_start > t_end {
+        return vec![];
+    }
+
+    let cell = |t: f64| {
+        [
+            ((y0 + t * dy).floor() as isize).clamp(0, height as isize - 1),
+            ((x0 + t * dx).floor() as isize).clamp(0, width as isize - 1),
+        ]
+    };
+
+    let [mut row, mut col] = cell(t_start);
+    let end_cell = cell(t_end);
+
+    // the parameters at which the segment crosses the next vertical and horizontal pixel boundaries
+    let boundary_crossing = |position: f64, cell: isize, delta: f64| {
+        if delta > 0. {
+            ((cell + 1) as f64 - position) / delta
+        } else if delta < 0. {
+            (cell as f64 - position) / delta
+        } else {
+            f64::INFINITY
+        }
+    };
+
+    let mut t_max_x = boundary_crossing(x0, col, dx);
+    let mut t_max_y = boundary_crossing(y0, row, dy);
+    let (t_delta_x, t_delta_y) = (1. / dx.abs(), 1. / dy.abs());
+    let (step_x, step_y) = (dx.signum() as isize, dy.signum() as isize);
+
+    let mut pixels = vec![[row as usize, col as usize]];
+
+    // every step moves one pixel closer to the end, so the number of steps is bounded
+    let max_steps = (end_cell[0] - row).abs() + (end_cell[1] - col).abs();
+    for _ in 0..max_steps {
+        if t_max_x < t_max_y {
+            col += step_x;
+            t_max_x += t_delta_x;
+        } else {
+            row += step_y;
+            t_max_y += t_delta_y;
+        }
+
+        if row < 0 || col < 0 || row >= height as isize || col >= width as isize {
+            break;
+        }
+
+        pixels.push([row as usize, col as usize]);
+    }
+
+    pixels
+}
+
 /// Creates a new calculator for for pixels covered by a given `feature_collection`'s geometries.
 pub trait PixelCoverCreator<G: Geometry> {
     type C: CoveredPixels<G>;
@@ -200,6 +359,14 @@ impl PixelCoverCreator<MultiPoint> for FeatureCollection<MultiPoint> {
     }
 }
 
+impl PixelCoverCreator<MultiLineString> for FeatureCollection<MultiLineString> {
+    type C = MultiLineStringCoveredPixels;
+
+    fn create_covered_pixels(self) -> Self::C {
+        MultiLineStringCoveredPixels::initialize(self)
+    }
+}
+
 impl PixelCoverCreator<MultiPolygon> for FeatureCollection<MultiPolygon> {
     type C = MultiPolygonCoveredPixels;
 
@@ -0,0 +1,739 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    FeatureCollectionSpool, SpooledCollectionsIntoIter, SpooledFeatureCollections,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataValue, Geometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// The bytes of input features that are sorted in memory.
+/// Larger inputs are sorted in runs that are spilled to disk and merged afterwards.
+const RUN_BYTE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The number of features per collection of spilled runs and of the merged output
+const MERGE_CHUNK_SIZE: usize = 8192;
+
+/// Sorts the features by the values of one or more columns.
+///
+/// The sort is stable, i.e., features with equal values keep their input order.
+/// All chunks of the source are sorted as a whole, so the result of a query is emitted
+/// after the source stream is consumed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortParams {
+    /// The columns to sort by, the first column has the highest priority
+    pub columns: Vec<SortColumn>,
+    /// Only emits the first features of the sorted result, e.g., for top-k analyses
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortColumn {
+    pub column: String,
+    #[serde(default)]
+    pub order: SortOrder,
+    #[serde(default)]
+    pub nulls: NullsOrder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Whether null values are sorted before or after all other values, independent of the sort order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum NullsOrder {
+    First,
+    #[default]
+    Last,
+}
+
+impl SortColumn {
+    fn compare(&self, a: &FeatureDataValue, b: &FeatureDataValue) -> Ordering {
+        let nulls_first = match self.nulls {
+            NullsOrder::First => Ordering::Less,
+            NullsOrder::Last => Ordering::Greater,
+        };
+
+        match (is_null(a), is_null(b)) {
+            (true, true) => Ordering::Equal,
+            (true, false) => nulls_first,
+            (false, true) => nulls_first.reverse(),
+            (false, false) => match self.order {
+                SortOrder::Ascending => compare_values(a, b),
+                SortOrder::Descending => compare_values(a, b).reverse(),
+            },
+        }
+    }
+}
+
+fn is_null(value: &FeatureDataValue) -> bool {
+    matches!(
+        value,
+        FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None)
+    )
+}
+
+/// Compares two non-null values of the same column, `NaN` is greater than all other floats
+fn compare_values(a: &FeatureDataValue, b: &FeatureDataValue) -> Ordering {
+    match (a, b) {
+        (
+            FeatureDataValue::Category(a) | FeatureDataValue::NullableCategory(Some(a)),
+            FeatureDataValue::Category(b) | FeatureDataValue::NullableCategory(Some(b)),
+        ) => a.cmp(b),
+        (
+            FeatureDataValue::Int(a) | FeatureDataValue::NullableInt(Some(a)),
+            FeatureDataValue::Int(b) | FeatureDataValue::NullableInt(Some(b)),
+        ) => a.cmp(b),
+        (
+            FeatureDataValue::Float(a) | FeatureDataValue::NullableFloat(Some(a)),
+            FeatureDataValue::Float(b) | FeatureDataValue::NullableFloat(Some(b)),
+        ) => a.total_cmp(b),
+        (
+            FeatureDataValue::Text(a) | FeatureDataValue::NullableText(Some(a)),
+            FeatureDataValue::Text(b) | FeatureDataValue::NullableText(Some(b)),
+        ) => a.cmp(b),
+        (
+            FeatureDataValue::Bool(a) | FeatureDataValue::NullableBool(Some(a)),
+            FeatureDataValue::Bool(b) | FeatureDataValue::NullableBool(Some(b)),
+        ) => a.cmp(b),
+        (
+            FeatureDataValue::DateTime(a) | FeatureDataValue::NullableDateTime(Some(a)),
+            FeatureDataValue::DateTime(b) | FeatureDataValue::NullableDateTime(Some(b)),
+        ) => a.cmp(b),
+        _ => Ordering::Equal,
+    }
+}
+
+pub(crate) fn compare_keys(
+    columns: &[SortColumn],
+    a: &[FeatureDataValue],
+    b: &[FeatureDataValue],
+) -> Ordering {
+    columns
+        .iter()
+        .zip(a.iter().zip(b))
+        .map(|(column, (a, b))| column.compare(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Extracts the values of the sort columns for each feature
+pub(crate) fn sort_keys<G>(
+    collection: &FeatureCollection<G>,
+    columns: &[SortColumn],
+) -> Result<Vec<Vec<FeatureDataValue>>>
+where
+    G: Geometry + ArrowTyped,
+{
+    let mut keys = vec![Vec::with_capacity(columns.len()); collection.len()];
+
+    for column in columns {
+        let data = collection.data(&column.column)?;
+
+        for (index, key) in keys.iter_mut().enumerate() {
+            key.push(data.get_unchecked(index));
+        }
+    }
+
+    Ok(keys)
+}
+
+pub type Sort = Operator<SortParams, SingleVectorSource>;
+
+impl OperatorName for Sort {
+    const TYPE_NAME: &'static str = "Sort";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Sort {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            !self.params.columns.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "at least one column must be given",
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        for column in &self.params.columns {
+            ensure!(
+                vector_source
+                    .result_descriptor()
+                    .columns
+                    .contains_key(&column.column),
+                error::ColumnDoesNotExist {
+                    column: column.column.clone(),
+                }
+            );
+        }
+
+        Ok(InitializedSort {
+            result_descriptor: vector_source.result_descriptor().clone(),
+            vector_source,
+            columns: self.params.columns,
+            limit: self.params.limit,
+        }
+        .boxed())
+    }
+
+    span_fn!(Sort);
+}
+
+pub struct InitializedSort {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    columns: Vec<SortColumn>,
+    limit: Option<usize>,
+}
+
+impl InitializedVectorOperator for InitializedSort {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => SortProcessor::new(
+                source,
+                self.columns.clone(),
+                self.limit,
+                RUN_BYTE_SIZE,
+            )
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Sorts the features of all chunks of the source stream.
+/// If the input exceeds `run_byte_size`, it is sorted in runs that are spilled to temporary files
+/// and merged, so that the memory is bounded by the run size and one chunk per run.
+pub struct SortProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    columns: Vec<SortColumn>,
+    limit: Option<usize>,
+    run_byte_size: usize,
+}
+
+impl<G> SortProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        columns: Vec<SortColumn>,
+        limit: Option<usize>,
+        run_byte_size: usize,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            columns,
+            limit,
+            run_byte_size,
+        }
+    }
+
+    /// Sorts the collections as one run, which is truncated to the limit
+    fn sort_run(
+        &self,
+        collections: Vec<FeatureCollection<G>>,
+    ) -> Result<Option<FeatureCollection<G>>> {
+        let mut collections = collections.into_iter();
+        let merged = if let Some(first) = collections.next() {
+            collections.try_fold(first, |merged, collection| merged.append(&collection))?
+        } else {
+            return Ok(None);
+        };
+
+        let keys = sort_keys(&merged, &self.columns)?;
+
+        let mut indices: Vec<usize> = (0..merged.len()).collect();
+        indices.sort_by(|&a, &b| compare_keys(&self.columns, &keys[a], &keys[b]));
+
+        if let Some(limit) = self.limit {
+            indices.truncate(limit);
+        }
+
+        Ok(Some(merged.take(&indices)?))
+    }
+
+    /// Writes a sorted run in chunks to a temporary file
+    fn spill_run(run: &FeatureCollection<G>) -> Result<SpooledFeatureCollections<G>> {
+        let mut spool = FeatureCollectionSpool::new(0, std::env::temp_dir());
+
+        for start in (0..run.len()).step_by(MERGE_CHUNK_SIZE) {
+            let indices: Vec<usize> = (start..run.len().min(start + MERGE_CHUNK_SIZE)).collect();
+            spool.push(run.take(&indices)?)?;
+        }
+
+        Ok(spool.finish()?)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for SortProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut source = self.source.query(query, ctx).await?;
+
+        let mut runs = Vec::new();
+        let mut buffer = Vec::new();
+        let mut buffered_bytes = 0;
+
+        while let Some(collection) = source.try_next().await? {
+            buffered_bytes += collection.byte_size();
+            buffer.push(collection);
+
+            if buffered_bytes > self.run_byte_size {
+                if let Some(run) = self.sort_run(std::mem::take(&mut buffer))? {
+                    runs.push(Self::spill_run(&run)?);
+                }
+                buffered_bytes = 0;
+            }
+        }
+
+        let last_run = self.sort_run(buffer)?;
+
+        if runs.is_empty() {
+            return Ok(match last_run {
+                Some(run) => stream::once(async move { Ok(run) }).boxed(),
+                None => stream::empty().boxed(),
+            });
+        }
+
+        if let Some(run) = last_run {
+            let mut spool = FeatureCollectionSpool::new(usize::MAX, std::env::temp_dir());
+            spool.push(run)?;
+            runs.push(spool.finish()?);
+        }
+
+        let merger = SortedRunMerger::new(runs, self.columns.clone(), self.limit)?;
+
+        Ok(stream::iter(merger).boxed())
+    }
+}
+
+/// The current chunk of a sorted run and the position of its next feature
+struct RunCursor<G> {
+    collections: SpooledCollectionsIntoIter<G>,
+    chunk: FeatureCollection<G>,
+    keys: Vec<Vec<FeatureDataValue>>,
+    position: usize,
+}
+
+impl<G> RunCursor<G>
+where
+    G: Geometry + ArrowTyped,
+{
+    fn new(run: SpooledFeatureCollections<G>, columns: &[SortColumn]) -> Result<Option<Self>> {
+        let mut cursor = Self {
+            collections: run.into_collections()?,
+            chunk: FeatureCollection::empty(),
+            keys: Vec::new(),
+            position: 0,
+        };
+
+        Ok(cursor.advance(columns)?.then_some(cursor))
+    }
+
+    /// Loads the next chunk of the run, returns `false` if the run is exhausted
+    fn advance(&mut self, columns: &[SortColumn]) -> Result<bool> {
+        for chunk in self.collections.by_ref() {
+            let chunk = chunk?;
+
+            if chunk.is_empty() {
+                continue;
+            }
+
+            self.keys = sort_keys(&chunk, columns)?;
+            self.chunk = chunk;
+            self.position = 0;
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn key(&self) -> &[FeatureDataValue] {
+        &self.keys[self.position]
+    }
+}
+
+/// Merges sorted runs into sorted chunks.
+/// Features with equal values are taken from earlier runs first, which keeps the sort stable.
+struct SortedRunMerger<G> {
+    runs: Vec<RunCursor<G>>,
+    columns: Vec<SortColumn>,
+    remaining: Option<usize>,
+}
+
+impl<G> SortedRunMerger<G>
+where
+    G: Geometry + ArrowTyped,
+{
+    fn new(
+        runs: Vec<SpooledFeatureCollections<G>>,
+        columns: Vec<SortColumn>,
+        limit: Option<usize>,
+    ) -> Result<Self> {
+        let mut cursors = Vec::with_capacity(runs.len());
+        for run in runs {
+            cursors.extend(RunCursor::new(run, &columns)?);
+        }
+
+        Ok(Self {
+            runs: cursors,
+            columns,
+            remaining: limit,
+        })
+    }
+
+    fn min_run(&self) -> usize {
+        let mut min_run = 0;
+
+        for run in 1..self.runs.len() {
+            if compare_keys(
+                &self.columns,
+                self.runs[run].key(),
+                self.runs[min_run].key(),
+            )
+            .is_lt()
+            {
+                min_run = run;
+            }
+        }
+
+        min_run
+    }
+
+    /// Merges the next chunk, which ends early if the chunk of a run is exhausted
+    fn next_chunk(&mut self) -> Result<Option<FeatureCollection<G>>> {
+        let max_len = self.remaining.map_or(MERGE_CHUNK_SIZE, |remaining| {
+            remaining.min(MERGE_CHUNK_SIZE)
+        });
+
+        if self.runs.is_empty() || max_len == 0 {
+            return Ok(None);
+        }
+
+        let mut selected: Vec<(usize, usize)> = Vec::with_capacity(max_len);
+        let exhausted_run = loop {
+            let run = self.min_run();
+            let cursor = &mut self.runs[run];
+
+            selected.push((run, cursor.position));
+            cursor.position += 1;
+
+            if cursor.position == cursor.chunk.len() {
+                break Some(run);
+            }
+            if selected.len() == max_len {
+                break None;
+            }
+        };
+
+        let output = self.take_selected(&selected)?;
+
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= output.len();
+        }
+
+        if let Some(run) = exhausted_run {
+            if !self.runs[run].advance(&self.columns)? {
+                self.runs.remove(run);
+            }
+        }
+
+        Ok(Some(output))
+    }
+
+    /// Collects the selected features of the current chunks of the runs in the selected order
+    fn take_selected(&self, selected: &[(usize, usize)]) -> Result<FeatureCollection<G>> {
+        let mut involved_runs: Vec<usize> = selected.iter().map(|&(run, _)| run).collect();
+        involved_runs.sort_unstable();
+        involved_runs.dedup();
+
+        let mut offsets = vec![0; self.runs.len()];
+        let mut combined: Option<FeatureCollection<G>> = None;
+
+        for run in involved_runs {
+            let chunk = &self.runs[run].chunk;
+
+            combined = Some(match combined {
+                Some(combined) => {
+                    offsets[run] = combined.len();
+                    combined.append(chunk)?
+                }
+                None => chunk.clone(),
+            });
+        }
+
+        let combined = combined.expect("at least one feature is selected");
+
+        let indices: Vec<usize> = selected
+            .iter()
+            .map(|&(run, position)| offsets[run] + position)
+            .collect();
+
+        Ok(combined.take(&indices)?)
+    }
+}
+
+impl<G> Iterator for SortedRunMerger<G>
+where
+    G: Geometry + ArrowTyped,
+{
+    type Item = Result<FeatureCollection<G>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_chunk().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection(names: &[&str], values: &[Option<i64>]) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0); names.len()]).unwrap(),
+            vec![TimeInterval::default(); names.len()],
+            [
+                (
+                    "name".to_string(),
+                    FeatureData::Text(names.iter().map(ToString::to_string).collect()),
+                ),
+                (
+                    "value".to_string(),
+                    FeatureData::NullableInt(values.to_vec()),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    fn names(collections: &[MultiPointCollection]) -> Vec<String> {
+        collections
+            .iter()
+            .flat_map(|collection| {
+                collection
+                    .data("name")
+                    .unwrap()
+                    .strings_iter()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn query() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_sorts_by_multiple_columns() {
+        let operator = Sort {
+            params: SortParams {
+                columns: vec![
+                    SortColumn {
+                        column: "value".to_string(),
+                        order: SortOrder::Descending,
+                        nulls: NullsOrder::First,
+                    },
+                    SortColumn {
+                        column: "name".to_string(),
+                        order: SortOrder::Ascending,
+                        nulls: NullsOrder::Last,
+                    },
+                ],
+                limit: None,
+            },
+            sources: MockFeatureCollectionSource::multiple(vec![
+                collection(&["b", "a", "c"], &[Some(1), Some(2), None]),
+                collection(&["e", "d"], &[Some(1), Some(2)]),
+            ])
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query(), &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(names(&result), vec!["c", "a", "d", "b", "e"]);
+    }
+
+    #[tokio::test]
+    async fn it_merges_spilled_runs() {
+        let source = MockFeatureCollectionSource::multiple(vec![
+            collection(&["a", "b"], &[Some(3), Some(1)]),
+            collection(&["c", "d"], &[Some(2), Some(1)]),
+            collection(&["e", "f"], &[None, Some(3)]),
+        ])
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_point()
+        .unwrap();
+
+        let processor = SortProcessor::new(
+            source,
+            vec![SortColumn {
+                column: "value".to_string(),
+                order: SortOrder::Ascending,
+                nulls: NullsOrder::Last,
+            }],
+            None,
+            0,
+        );
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query(), &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        // equal values keep their input order
+        assert_eq!(names(&result), vec!["b", "d", "c", "a", "f", "e"]);
+    }
+
+    #[tokio::test]
+    async fn it_limits_the_result() {
+        let source = MockFeatureCollectionSource::multiple(vec![
+            collection(&["a", "b"], &[Some(3), Some(1)]),
+            collection(&["c", "d"], &[Some(2), Some(4)]),
+        ])
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_point()
+        .unwrap();
+
+        let processor = SortProcessor::new(
+            source,
+            vec![SortColumn {
+                column: "value".to_string(),
+                order: SortOrder::Descending,
+                nulls: NullsOrder::Last,
+            }],
+            Some(3),
+            0,
+        );
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query(), &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(names(&result), vec!["d", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unknown_columns() {
+        let operator = Sort {
+            params: SortParams {
+                columns: vec![SortColumn {
+                    column: "foo".to_string(),
+                    order: SortOrder::Ascending,
+                    nulls: NullsOrder::Last,
+                }],
+                limit: None,
+            },
+            sources: MockFeatureCollectionSource::single(collection(&["a"], &[Some(1)]))
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            operator,
+            Err(error::Error::ColumnDoesNotExist { .. })
+        ));
+    }
+}
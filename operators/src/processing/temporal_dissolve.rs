@@ -0,0 +1,621 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    IntoGeometryOptionsIterator,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataRef, FeatureDataType, FeatureDataValue, Geometry,
+    Measurement, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Merges features with the same geometry and key attributes whose time intervals overlap or touch.
+///
+/// The merged feature is valid for the union of the time intervals.
+/// Key columns are retained, the values of all other columns are aggregated
+/// as specified and columns without an aggregation are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalDissolveParams {
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+    /// Maps column names to the aggregation of their values
+    #[serde(default)]
+    pub aggregations: HashMap<String, DissolveAggregation>,
+}
+
+/// Aggregates the values of merged features.
+/// `First` and `Last` refer to the temporal order of the features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DissolveAggregation {
+    First,
+    Last,
+    Min,
+    Max,
+    Sum,
+    Mean,
+    Count,
+}
+
+impl DissolveAggregation {
+    fn requires_numeric_input(self) -> bool {
+        !matches!(self, Self::First | Self::Last | Self::Count)
+    }
+
+    fn column_info(self, input: &VectorColumnInfo) -> VectorColumnInfo {
+        match self {
+            Self::First | Self::Last => input.clone(),
+            Self::Min | Self::Max | Self::Sum | Self::Mean => VectorColumnInfo {
+                data_type: FeatureDataType::Float,
+                measurement: input.measurement.clone(),
+            },
+            Self::Count => VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            },
+        }
+    }
+
+    /// Aggregates the values of each group of row indices.
+    /// Null values are ignored and groups without values result in null.
+    fn aggregate(
+        self,
+        data_type: FeatureDataType,
+        data: &FeatureDataRef,
+        groups: &[Vec<usize>],
+    ) -> FeatureData {
+        let floats = |f: fn(&[f64]) -> f64| {
+            let values: Vec<Option<f64>> = data.float_options_iter().collect();
+            FeatureData::NullableFloat(
+                groups
+                    .iter()
+                    .map(|group| {
+                        let group_values: Vec<f64> =
+                            group.iter().filter_map(|&i| values[i]).collect();
+                        if group_values.is_empty() {
+                            None
+                        } else {
+                            Some(f(&group_values))
+                        }
+                    })
+                    .collect(),
+            )
+        };
+
+        match self {
+            Self::First => feature_data_from_values(
+                data_type,
+                groups.iter().map(|group| data.get_unchecked(group[0])),
+            ),
+            Self::Last => feature_data_from_values(
+                data_type,
+                groups
+                    .iter()
+                    .map(|group| data.get_unchecked(group[group.len() - 1])),
+            ),
+            Self::Min => floats(|v| v.iter().copied().fold(f64::INFINITY, f64::min)),
+            Self::Max => floats(|v| v.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            Self::Sum => floats(|v| v.iter().sum()),
+            Self::Mean => floats(|v| v.iter().sum::<f64>() / v.len() as f64),
+            Self::Count => {
+                let nulls = data.nulls();
+                FeatureData::Int(
+                    groups
+                        .iter()
+                        .map(|group| group.iter().filter(|&&i| !nulls[i]).count() as i64)
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+/// The null value of the given type
+pub(crate) fn null_value(data_type: FeatureDataType) -> FeatureDataValue {
+    match data_type {
+        FeatureDataType::Category => FeatureDataValue::NullableCategory(None),
+        FeatureDataType::Int => FeatureDataValue::NullableInt(None),
+        FeatureDataType::Float => FeatureDataValue::NullableFloat(None),
+        FeatureDataType::Text => FeatureDataValue::NullableText(None),
+        FeatureDataType::Bool => FeatureDataValue::NullableBool(None),
+        FeatureDataType::DateTime => FeatureDataValue::NullableDateTime(None),
+    }
+}
+
+/// Appends the values of the `columns` to the key of each feature.
+/// The values are separated by a null character, so equal keys denote equal column values.
+pub(crate) fn append_column_keys<'c, G>(
+    keys: &mut [String],
+    collection: &FeatureCollection<G>,
+    columns: impl IntoIterator<Item = &'c String>,
+) -> Result<()>
+where
+    G: Geometry + ArrowTyped,
+{
+    for column in columns {
+        let data = collection.data(column)?;
+        for (key, value) in keys.iter_mut().zip(data.json_values()) {
+            key.push('\u{0}');
+            key.push_str(&value.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects single values of a column into a nullable `FeatureData` of the given type
+pub(crate) fn feature_data_from_values(
+    data_type: FeatureDataType,
+    values: impl Iterator<Item = FeatureDataValue>,
+) -> FeatureData {
+    match data_type {
+        FeatureDataType::Category => FeatureData::NullableCategory(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::Category(v) => Some(v),
+                    FeatureDataValue::NullableCategory(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+        FeatureDataType::Int => FeatureData::NullableInt(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::Int(v) => Some(v),
+                    FeatureDataValue::NullableInt(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+        FeatureDataType::Float => FeatureData::NullableFloat(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::Float(v) => Some(v),
+                    FeatureDataValue::NullableFloat(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+        FeatureDataType::Text => FeatureData::NullableText(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::Text(v) => Some(v),
+                    FeatureDataValue::NullableText(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+        FeatureDataType::Bool => FeatureData::NullableBool(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::Bool(v) => Some(v),
+                    FeatureDataValue::NullableBool(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+        FeatureDataType::DateTime => FeatureData::NullableDateTime(
+            values
+                .map(|v| match v {
+                    FeatureDataValue::DateTime(v) => Some(v),
+                    FeatureDataValue::NullableDateTime(v) => v,
+                    _ => None,
+                })
+                .collect(),
+        ),
+    }
+}
+
+pub type TemporalDissolve = Operator<TemporalDissolveParams, SingleVectorSource>;
+
+impl OperatorName for TemporalDissolve {
+    const TYPE_NAME: &'static str = "TemporalDissolve";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TemporalDissolve {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_columns = &vector_source.result_descriptor().columns;
+
+        let mut aggregations = Vec::with_capacity(self.params.aggregations.len());
+        for (column, aggregation) in self.params.aggregations {
+            let column_info = if let Some(column_info) = source_columns.get(&column) {
+                column_info
+            } else {
+                return Err(error::Error::ColumnDoesNotExist { column });
+            };
+
+            ensure!(
+                !self.params.key_columns.contains(&column),
+                error::InvalidOperatorSpec {
+                    reason: "key columns cannot be aggregated",
+                }
+            );
+            ensure!(
+                !aggregation.requires_numeric_input() || column_info.data_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("column `{}` is not numeric", column),
+                }
+            );
+
+            aggregations.push((column, column_info.data_type, aggregation));
+        }
+
+        for column in &self.params.key_columns {
+            ensure!(
+                source_columns.contains_key(column),
+                error::ColumnDoesNotExist {
+                    column: column.clone(),
+                }
+            );
+        }
+
+        let result_descriptor = vector_source.result_descriptor().map_columns(|columns| {
+            columns
+                .iter()
+                .filter_map(|(name, info)| {
+                    if self.params.key_columns.contains(name) {
+                        Some((name.clone(), info.clone()))
+                    } else {
+                        aggregations
+                            .iter()
+                            .find(|(column, _, _)| column == name)
+                            .map(|(_, _, aggregation)| {
+                                (name.clone(), aggregation.column_info(info))
+                            })
+                    }
+                })
+                .collect()
+        });
+
+        Ok(InitializedTemporalDissolve {
+            result_descriptor,
+            vector_source,
+            key_columns: self.params.key_columns,
+            aggregations,
+        }
+        .boxed())
+    }
+
+    span_fn!(TemporalDissolve);
+}
+
+pub struct InitializedTemporalDissolve {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    key_columns: Vec<String>,
+    aggregations: Vec<(String, FeatureDataType, DissolveAggregation)>,
+}
+
+impl InitializedVectorOperator for InitializedTemporalDissolve {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TemporalDissolveProcessor::new(
+                source,
+                self.key_columns.clone(),
+                self.aggregations.clone(),
+            )
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Dissolves the features of all chunks of the source stream.
+/// Since matching features may reside in different chunks, the whole result is buffered and
+/// emitted as a single collection.
+pub struct TemporalDissolveProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    key_columns: Vec<String>,
+    aggregations: Vec<(String, FeatureDataType, DissolveAggregation)>,
+}
+
+impl<G> TemporalDissolveProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+    for<'i> FeatureCollection<G>: IntoGeometryOptionsIterator<'i>,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        key_columns: Vec<String>,
+        aggregations: Vec<(String, FeatureDataType, DissolveAggregation)>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            key_columns,
+            aggregations,
+        }
+    }
+
+    /// Computes a key per feature that consists of its geometry and its key column values
+    fn feature_keys(&self, collection: &FeatureCollection<G>) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = collection
+            .geometry_options()
+            .map(|geometry| {
+                geometry.map_or_else(String::new, |geometry| {
+                    geojson::Geometry::from(geometry).to_string()
+                })
+            })
+            .collect();
+
+        append_column_keys(&mut keys, collection, &self.key_columns)?;
+
+        Ok(keys)
+    }
+
+    fn dissolve(&self, collection: &FeatureCollection<G>) -> Result<FeatureCollection<G>> {
+        let time_intervals = collection.time_intervals();
+
+        let mut features_by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, key) in self.feature_keys(collection)?.into_iter().enumerate() {
+            features_by_key.entry(key).or_default().push(index);
+        }
+
+        // runs of features with contiguous time, identified by their first feature
+        let mut runs: Vec<(Vec<usize>, TimeInterval)> = Vec::new();
+        for mut features in features_by_key.into_values() {
+            features.sort_by_key(|&i| (time_intervals[i].start(), time_intervals[i].end()));
+
+            let mut current: Option<(Vec<usize>, TimeInterval)> = None;
+            for index in features {
+                let time = time_intervals[index];
+                current = match current {
+                    Some((mut run, run_time)) => {
+                        if let Ok(union) = run_time.union(&time) {
+                            run.push(index);
+                            Some((run, union))
+                        } else {
+                            runs.push((run, run_time));
+                            Some((vec![index], time))
+                        }
+                    }
+                    None => Some((vec![index], time)),
+                };
+            }
+            runs.extend(current);
+        }
+
+        // keep the input order of the first features
+        runs.sort_by_key(|(run, _)| run[0]);
+
+        let mut mask = vec![false; collection.len()];
+        for (run, _) in &runs {
+            mask[run[0]] = true;
+        }
+
+        let run_times: Vec<TimeInterval> = runs.iter().map(|(_, time)| *time).collect();
+        let run_features: Vec<Vec<usize>> = runs.into_iter().map(|(run, _)| run).collect();
+
+        let mut aggregated_columns = Vec::with_capacity(self.aggregations.len());
+        for (column, data_type, aggregation) in &self.aggregations {
+            let data = collection.data(column)?;
+            aggregated_columns.push((
+                column.as_str(),
+                aggregation.aggregate(*data_type, &data, &run_features),
+            ));
+        }
+
+        let removed_columns: Vec<&str> = collection
+            .column_names()
+            .filter(|column| !self.key_columns.contains(*column))
+            .map(String::as_str)
+            .collect();
+
+        collection
+            .filter(mask)?
+            .replace_time(&run_times)?
+            .remove_columns(&removed_columns)?
+            .add_columns(&aggregated_columns)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TemporalDissolveProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    for<'i> FeatureCollection<G>: IntoGeometryOptionsIterator<'i>,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<FeatureCollection<G>> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let mut collections = collections.into_iter();
+        let merged = if let Some(first) = collections.next() {
+            collections.try_fold(first, |merged, collection| merged.append(&collection))?
+        } else {
+            return Ok(stream::empty().boxed());
+        };
+
+        let result = self.dissolve(&merged);
+
+        Ok(stream::once(async move { result }).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_dissolves_contiguous_features() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 1.0)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 10).unwrap(),
+                TimeInterval::new(10, 20).unwrap(),
+                TimeInterval::new(30, 40).unwrap(),
+                TimeInterval::new(0, 10).unwrap(),
+            ],
+            [
+                (
+                    "value".to_string(),
+                    FeatureData::Float(vec![1., 3., 5., 7.]),
+                ),
+                ("dropped".to_string(), FeatureData::Int(vec![0, 0, 0, 0])),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+        let operator = TemporalDissolve {
+            params: TemporalDissolveParams {
+                key_columns: vec![],
+                aggregations: [("value".to_string(), DissolveAggregation::Mean)].into(),
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.0), (0.0, 0.0), (1.0, 1.0)]).unwrap(),
+                vec![
+                    TimeInterval::new(0, 20).unwrap(),
+                    TimeInterval::new(30, 40).unwrap(),
+                    TimeInterval::new(0, 10).unwrap(),
+                ],
+                [(
+                    "value".to_string(),
+                    FeatureData::NullableFloat(vec![Some(2.), Some(5.), Some(7.)])
+                )]
+                .into(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_separates_features_by_key() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (0.0, 0.0)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 10).unwrap(),
+                TimeInterval::new(5, 20).unwrap(),
+            ],
+            [
+                (
+                    "name".to_string(),
+                    FeatureData::Text(vec!["a".to_string(), "b".to_string()]),
+                ),
+                ("value".to_string(), FeatureData::Int(vec![1, 2])),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+        let dissolve = TemporalDissolveProcessor::new(
+            MockFeatureCollectionSource::single(collection.clone())
+                .boxed()
+                .initialize(&MockExecutionContext::test_default())
+                .await
+                .unwrap()
+                .query_processor()
+                .unwrap()
+                .multi_point()
+                .unwrap(),
+            vec!["name".to_string()],
+            vec![(
+                "value".to_string(),
+                FeatureDataType::Int,
+                DissolveAggregation::Last,
+            )],
+        );
+
+        let result = dissolve.dissolve(&collection).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.time_intervals(), collection.time_intervals());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_aggregating_text_numerically() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("name".to_string(), FeatureData::Text(vec!["a".to_string()]))].into(),
+        )
+        .unwrap();
+
+        let operator = TemporalDissolve {
+            params: TemporalDissolveParams {
+                key_columns: vec![],
+                aggregations: [("name".to_string(), DissolveAggregation::Sum)].into(),
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            operator,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
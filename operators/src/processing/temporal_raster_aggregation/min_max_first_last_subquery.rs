@@ -76,6 +76,41 @@ impl NoDataIgnoringAccFunction for MaxIgnoreNoDataAccFunction {
     }
 }
 
+pub struct SumAccFunction {}
+
+impl AccFunction for SumAccFunction {
+    fn acc<T: Pixel>(acc: Option<T>, value: Option<T>) -> Option<T> {
+        match (acc, value) {
+            (Some(a), Some(v)) => Some(saturating_add(a, v)),
+            _ => None,
+        }
+    }
+}
+
+pub struct SumIgnoreNoDataAccFunction {}
+
+impl NoDataIgnoringAccFunction for SumIgnoreNoDataAccFunction {
+    fn acc_ignore_no_data<T: Pixel>(acc: Option<T>, value: Option<T>) -> Option<T> {
+        match (acc, value) {
+            (Some(a), Some(v)) => Some(saturating_add(a, v)),
+            (Some(a), None) => Some(a),
+            (None, Some(v)) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Adds the values in their pixel type, saturating at its bounds instead of overflowing
+fn saturating_add<T: Pixel>(a: T, b: T) -> T {
+    if b > T::zero() && a > T::max_value() - b {
+        T::max_value()
+    } else if b < T::zero() && a < T::min_value() - b {
+        T::min_value()
+    } else {
+        a + b
+    }
+}
+
 pub struct LastValidAccFunction {}
 
 impl NoDataIgnoringAccFunction for LastValidAccFunction {
@@ -10,11 +10,12 @@ use crate::{
         TypedRasterQueryProcessor,
     },
     error,
+    processing::RasterTypeConversionQueryProcessor,
     util::Result,
 };
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartition2D, TimeInstance};
-use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use geoengine_datatypes::raster::{Pixel, RasterDataType, RasterTile2D};
 use geoengine_datatypes::{primitives::TimeStep, raster::TilingSpecification};
 use log::debug;
 use serde::{Deserialize, Serialize};
@@ -28,8 +29,8 @@ use super::mean_aggregation_subquery::{
 use super::min_max_first_last_subquery::{
     first_tile_fold_future, fold_future, last_tile_fold_future, no_data_ignoring_fold_future,
     FirstValidAccFunction, LastValidAccFunction, MaxAccFunction, MaxIgnoreNoDataAccFunction,
-    MinAccFunction, MinIgnoreNoDataAccFunction, TemporalRasterAggregationSubQuery,
-    TemporalRasterAggregationSubQueryNoDataOnly,
+    MinAccFunction, MinIgnoreNoDataAccFunction, SumAccFunction, SumIgnoreNoDataAccFunction,
+    TemporalRasterAggregationSubQuery, TemporalRasterAggregationSubQueryNoDataOnly,
 };
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -56,6 +57,14 @@ pub enum Aggregation {
     Last { ignore_no_data: bool },
     #[serde(rename_all = "camelCase")]
     Mean { ignore_no_data: bool },
+    /// Sums the values in the `outputType`, which defaults to `F64`.
+    /// The sum saturates at the bounds of the output type.
+    #[serde(rename_all = "camelCase")]
+    Sum {
+        ignore_no_data: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_type: Option<RasterDataType>,
+    },
 }
 
 pub type TemporalRasterAggregation =
@@ -81,6 +90,11 @@ impl RasterOperator for TemporalRasterAggregation {
             &self.params
         );
 
+        let mut result_descriptor = source.result_descriptor().clone();
+        if let Aggregation::Sum { output_type, .. } = self.params.aggregation {
+            result_descriptor.data_type = output_type.unwrap_or(RasterDataType::F64);
+        }
+
         let initialized_operator = InitializedTemporalRasterAggregation {
             aggregation_type: self.params.aggregation,
             window: self.params.window,
@@ -88,7 +102,7 @@ impl RasterOperator for TemporalRasterAggregation {
                 .params
                 .window_reference
                 .unwrap_or(TimeInstance::EPOCH_START),
-            result_descriptor: source.result_descriptor().clone(),
+            result_descriptor,
             source,
             tiling_specification: context.tiling_specification(),
         };
@@ -114,7 +128,17 @@ impl InitializedRasterOperator for InitializedTemporalRasterAggregation {
     }
 
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
-        let source_processor = self.source.query_processor()?;
+        let mut source_processor = self.source.query_processor()?;
+
+        // the sum is computed in the output type, so that it does not saturate at the bounds of the input type
+        let output_type = self.result_descriptor.data_type;
+        if output_type != self.source.result_descriptor().data_type {
+            source_processor = call_on_generic_raster_processor!(source_processor, p => {
+                call_generic_raster_processor!(output_type,
+                    RasterTypeConversionQueryProcessor::create_boxed(p)
+                )
+            });
+        }
 
         let res = call_on_generic_raster_processor!(
             source_processor, p =>
@@ -284,6 +308,20 @@ where
                 .create_subquery_mean(mean_tile_fold_future::<P>, ignore_no_data)
                 .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
                 .expect("no tiles must be skipped in Aggregation::Mean")),
+            Aggregation::Sum {
+                ignore_no_data: true,
+                ..
+            } => Ok(self
+                .create_subquery(no_data_ignoring_fold_future::<P, SumIgnoreNoDataAccFunction>)
+                .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
+                .expect("no tiles must be skipped in Aggregation::Sum")),
+            Aggregation::Sum {
+                ignore_no_data: false,
+                ..
+            } => Ok(self
+                .create_subquery(fold_future::<P, SumAccFunction>)
+                .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
+                .expect("no tiles must be skipped in Aggregation::Sum")),
         }
     }
 }
@@ -1442,6 +1480,106 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_sum() {
+        let mrs = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: make_raster(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed();
+
+        let agg = TemporalRasterAggregation {
+            params: TemporalRasterAggregationParameters {
+                aggregation: Aggregation::Sum {
+                    ignore_no_data: false,
+                    output_type: None,
+                },
+                window: TimeStep {
+                    granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
+                    step: 20,
+                },
+                window_reference: None,
+            },
+            sources: SingleRasterSource { raster: mrs },
+        }
+        .boxed();
+
+        let exe_ctx = MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+            (0., 0.).into(),
+            [3, 2].into(),
+        ));
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 40),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let query_ctx = MockQueryContext::test_default();
+
+        let qp = agg
+            .initialize(&exe_ctx)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_f64()
+            .unwrap();
+
+        let result = qp
+            .query(query_rect, &query_ctx)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(result.len(), 4);
+
+        for (tile, (time, position)) in result.iter().zip([
+            (TimeInterval::new_unchecked(0, 20), [-1, 0]),
+            (TimeInterval::new_unchecked(0, 20), [-1, 1]),
+            (TimeInterval::new_unchecked(20, 40), [-1, 0]),
+            (TimeInterval::new_unchecked(20, 40), [-1, 1]),
+        ]) {
+            assert_eq!(
+                tile.as_ref().unwrap(),
+                &RasterTile2D::new_with_tile_info(
+                    time,
+                    TileInformation {
+                        global_tile_position: position.into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                        global_geo_transform: TestDefault::test_default(),
+                    },
+                    GridOrEmpty::from(Grid2D::new([3, 2].into(), vec![13.; 6]).unwrap()),
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_sum_saturates_at_the_output_type() {
+        use super::super::min_max_first_last_subquery::AccFunction;
+
+        assert_eq!(SumAccFunction::acc(Some(200_u8), Some(100)), Some(u8::MAX));
+        assert_eq!(SumAccFunction::acc(Some(-120_i8), Some(-10)), Some(i8::MIN));
+        assert_eq!(
+            SumAccFunction::acc(Some(i64::MAX - 1), Some(1)),
+            Some(i64::MAX)
+        );
+        assert_eq!(
+            SumAccFunction::acc(Some(u64::MAX - 1), Some(2)),
+            Some(u64::MAX)
+        );
+        assert_eq!(SumAccFunction::acc(Some(1.5_f64), Some(2.)), Some(3.5));
+    }
+
     #[tokio::test]
     async fn test_query_not_aligned_with_window_reference() {
         let raster_tiles = make_raster();
@@ -0,0 +1,355 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, ContinuousMeasurement, DateTime, FeatureData, FeatureDataType, Geometry,
+    Measurement, TimeInstance, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// A value that is derived from the time interval of a feature.
+/// Calendar values refer to the start of the interval in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeAttribute {
+    Start,
+    End,
+    /// The length of the interval in milliseconds
+    Duration,
+    Year,
+    Month,
+    Day,
+    DayOfYear,
+    Hour,
+}
+
+impl TimeAttribute {
+    fn data_type(self) -> FeatureDataType {
+        match self {
+            TimeAttribute::Start | TimeAttribute::End => FeatureDataType::DateTime,
+            _ => FeatureDataType::Int,
+        }
+    }
+
+    fn measurement(self) -> Measurement {
+        match self {
+            TimeAttribute::Duration => Measurement::Continuous(ContinuousMeasurement {
+                measurement: "duration".to_string(),
+                unit: Some("ms".to_string()),
+            }),
+            _ => Measurement::Unitless,
+        }
+    }
+
+    /// Computes the attribute for all time intervals.
+    /// Unbounded intervals result in missing values for durations and calendar values.
+    fn extract(self, time_intervals: &[TimeInterval]) -> FeatureData {
+        let calendar_value = |f: fn(&DateTime) -> i64| {
+            FeatureData::NullableInt(
+                time_intervals
+                    .iter()
+                    .map(|time| bounded(time.start()).and_then(TimeInstance::as_date_time))
+                    .map(|date_time| date_time.as_ref().map(f))
+                    .collect(),
+            )
+        };
+
+        match self {
+            TimeAttribute::Start => FeatureData::NullableDateTime(
+                time_intervals.iter().map(|t| bounded(t.start())).collect(),
+            ),
+            TimeAttribute::End => FeatureData::NullableDateTime(
+                time_intervals.iter().map(|t| bounded(t.end())).collect(),
+            ),
+            TimeAttribute::Duration => FeatureData::NullableInt(
+                time_intervals
+                    .iter()
+                    .map(|time| {
+                        bounded(time.start())
+                            .zip(bounded(time.end()))
+                            .map(|(start, end)| end.inner() - start.inner())
+                    })
+                    .collect(),
+            ),
+            TimeAttribute::Year => calendar_value(|d| i64::from(d.year())),
+            TimeAttribute::Month => calendar_value(|d| i64::from(d.month())),
+            TimeAttribute::Day => calendar_value(|d| i64::from(d.day())),
+            TimeAttribute::DayOfYear => calendar_value(|d| i64::from(d.day_of_year())),
+            TimeAttribute::Hour => calendar_value(|d| i64::from(d.hour())),
+        }
+    }
+}
+
+/// Filters out the bounds of the time domain that stand for an unbounded interval
+fn bounded(time_instance: TimeInstance) -> Option<TimeInstance> {
+    if time_instance.is_min() || time_instance.is_max() {
+        None
+    } else {
+        Some(time_instance)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeAttributeColumn {
+    pub column: String,
+    pub attribute: TimeAttribute,
+}
+
+/// Adds columns with values that are derived from the time intervals of the features
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeAttributeExtractionParams {
+    pub columns: Vec<TimeAttributeColumn>,
+}
+
+pub type TimeAttributeExtraction = Operator<TimeAttributeExtractionParams, SingleVectorSource>;
+
+impl OperatorName for TimeAttributeExtraction {
+    const TYPE_NAME: &'static str = "TimeAttributeExtraction";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TimeAttributeExtraction {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(!self.params.columns.is_empty(), error::EmptyInput);
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let output_names: HashSet<&String> =
+            self.params.columns.iter().map(|c| &c.column).collect();
+        ensure!(
+            output_names.len() == self.params.columns.len()
+                && output_names.iter().all(|name| !vector_source
+                    .result_descriptor()
+                    .columns
+                    .contains_key(*name)),
+            error::DuplicateOutputColumns
+        );
+
+        let result_descriptor = vector_source.result_descriptor().map_columns(|columns| {
+            let mut columns = columns.clone();
+            for column in &self.params.columns {
+                columns.insert(
+                    column.column.clone(),
+                    VectorColumnInfo {
+                        data_type: column.attribute.data_type(),
+                        measurement: column.attribute.measurement(),
+                    },
+                );
+            }
+            columns
+        });
+
+        Ok(InitializedTimeAttributeExtraction {
+            result_descriptor,
+            vector_source,
+            columns: self.params.columns,
+        }
+        .boxed())
+    }
+
+    span_fn!(TimeAttributeExtraction);
+}
+
+pub struct InitializedTimeAttributeExtraction {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    columns: Vec<TimeAttributeColumn>,
+}
+
+impl InitializedVectorOperator for InitializedTimeAttributeExtraction {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TimeAttributeExtractionProcessor::new(source, self.columns.clone()).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeAttributeExtractionProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    columns: Vec<TimeAttributeColumn>,
+}
+
+impl<G> TimeAttributeExtractionProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        columns: Vec<TimeAttributeColumn>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            columns,
+        }
+    }
+
+    fn extract(&self, collection: &FeatureCollection<G>) -> Result<FeatureCollection<G>> {
+        let time_intervals = collection.time_intervals();
+
+        let new_columns: Vec<(&str, FeatureData)> = self
+            .columns
+            .iter()
+            .map(|c| (c.column.as_str(), c.attribute.extract(time_intervals)))
+            .collect();
+
+        collection.add_columns(&new_columns).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TimeAttributeExtractionProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| self.extract(&collection?));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{FeatureDataValue, MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_extracts_time_attributes() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![
+                TimeInterval::new(
+                    DateTime::new_utc(2020, 3, 15, 12, 0, 0),
+                    DateTime::new_utc(2020, 3, 16, 12, 0, 0),
+                )
+                .unwrap(),
+                TimeInterval::default(),
+            ],
+            Default::default(),
+        )
+        .unwrap();
+
+        let operator = TimeAttributeExtraction {
+            params: TimeAttributeExtractionParams {
+                columns: vec![
+                    TimeAttributeColumn {
+                        column: "start".to_string(),
+                        attribute: TimeAttribute::Start,
+                    },
+                    TimeAttributeColumn {
+                        column: "duration".to_string(),
+                        attribute: TimeAttribute::Duration,
+                    },
+                    TimeAttributeColumn {
+                        column: "month".to_string(),
+                        attribute: TimeAttribute::Month,
+                    },
+                ],
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().column_data_type("start"),
+            Some(FeatureDataType::DateTime)
+        );
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        let durations = result[0].data("duration").unwrap();
+        assert_eq!(
+            durations.get_unchecked(0),
+            FeatureDataValue::NullableInt(Some(86_400_000))
+        );
+        assert_eq!(
+            durations.get_unchecked(1),
+            FeatureDataValue::NullableInt(None)
+        );
+    }
+
+    #[test]
+    fn it_extracts_calendar_values() {
+        let time = TimeInterval::new_instant(DateTime::new_utc(2021, 12, 31, 23, 0, 0)).unwrap();
+
+        assert_eq!(
+            TimeAttribute::Year.extract(&[time]),
+            FeatureData::NullableInt(vec![Some(2021)])
+        );
+        assert_eq!(
+            TimeAttribute::DayOfYear.extract(&[time]),
+            FeatureData::NullableInt(vec![Some(365)])
+        );
+        assert_eq!(
+            TimeAttribute::Hour.extract(&[TimeInterval::default()]),
+            FeatureData::NullableInt(vec![None])
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Geometry, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, ResultDescriptor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::util::Result;
+
+/// The time interval that the time intervals of the features are clipped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TimeClipWindow {
+    /// The time interval of the query
+    Query,
+    /// A fixed interval in which the features are valid, independent of the query
+    Validity { interval: TimeInterval },
+}
+
+/// Clips the time intervals of the features to a window and drops the features outside of it.
+///
+/// Sources return all features whose time intervals intersect the query, e.g., a feature that is
+/// valid for a year is part of the result of a query for a day. This makes the temporal semantics
+/// of downstream operators, e.g., joins, explicit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeClipParams {
+    pub window: TimeClipWindow,
+}
+
+pub type TimeClip = Operator<TimeClipParams, SingleVectorSource>;
+
+impl OperatorName for TimeClip {
+    const TYPE_NAME: &'static str = "TimeClip";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TimeClip {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let result_descriptor =
+            match self.params.window {
+                TimeClipWindow::Query => vector_source.result_descriptor().clone(),
+                TimeClipWindow::Validity { interval } => vector_source
+                    .result_descriptor()
+                    .map_time(|time| match time {
+                        Some(time) => time.intersect(&interval),
+                        None => Some(interval),
+                    }),
+            };
+
+        Ok(InitializedTimeClip {
+            result_descriptor,
+            vector_source,
+            window: self.params.window,
+        }
+        .boxed())
+    }
+
+    span_fn!(TimeClip);
+}
+
+pub struct InitializedTimeClip {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    window: TimeClipWindow,
+}
+
+impl InitializedVectorOperator for InitializedTimeClip {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TimeClipProcessor::new(source, self.window).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TimeClipProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    window: TimeClipWindow,
+}
+
+impl<G> TimeClipProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        window: TimeClipWindow,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            window,
+        }
+    }
+}
+
+/// Clips the time intervals of the features to the `window` and drops the features that do not intersect it
+fn clip<G>(collection: &FeatureCollection<G>, window: TimeInterval) -> Result<FeatureCollection<G>>
+where
+    G: Geometry + ArrowTyped,
+{
+    let clipped: Vec<Option<TimeInterval>> = collection
+        .time_intervals()
+        .iter()
+        .map(|time| time.intersect(&window))
+        .collect();
+
+    let keep: Vec<bool> = clipped.iter().map(Option::is_some).collect();
+    let time_intervals: Vec<TimeInterval> = clipped.into_iter().flatten().collect();
+
+    collection
+        .filter(keep)?
+        .replace_time(&time_intervals)
+        .map_err(Into::into)
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TimeClipProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let (window, source_query) = match self.window {
+            TimeClipWindow::Query => (query.time_interval, query),
+            TimeClipWindow::Validity { interval } => {
+                // there are no valid features outside of the window
+                if let Some(time_interval) = query.time_interval.intersect(&interval) {
+                    (
+                        interval,
+                        VectorQueryRectangle {
+                            time_interval,
+                            ..query
+                        },
+                    )
+                } else {
+                    let empty = Ok(FeatureCollection::empty());
+                    return Ok(stream::once(async { empty }).boxed());
+                }
+            }
+        };
+
+        let stream = self
+            .source
+            .query(source_query, ctx)
+            .await?
+            .map(move |collection| clip(&collection?, window));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn points() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 10),
+                TimeInterval::new_unchecked(5, 15),
+                TimeInterval::new_unchecked(20, 30),
+            ],
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    async fn clip_points(window: TimeClipWindow, query_time: TimeInterval) -> MultiPointCollection {
+        let operator = TimeClip {
+            params: TimeClipParams { window },
+            sources: MockFeatureCollectionSource::single(points()).boxed().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (3., 3.).into()).unwrap(),
+            time_interval: query_time,
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let result: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        result.into_iter().next().unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_clips_to_the_query() {
+        let result = clip_points(TimeClipWindow::Query, TimeInterval::new_unchecked(8, 12)).await;
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(8, 10),
+                TimeInterval::new_unchecked(8, 12)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_clips_to_the_validity() {
+        let result = clip_points(
+            TimeClipWindow::Validity {
+                interval: TimeInterval::new_unchecked(12, 25),
+            },
+            TimeInterval::default(),
+        )
+        .await;
+
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(12, 15),
+                TimeInterval::new_unchecked(20, 25)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_returns_nothing_outside_of_the_validity() {
+        let result = clip_points(
+            TimeClipWindow::Validity {
+                interval: TimeInterval::new_unchecked(12, 25),
+            },
+            TimeInterval::new_unchecked(30, 40),
+        )
+        .await;
+
+        assert!(result.is_empty());
+    }
+}
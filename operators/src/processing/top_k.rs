@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{future, stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use super::sort::{compare_keys, sort_keys};
+use super::temporal_dissolve::append_column_keys;
+use super::SortColumn;
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, Operator, OperatorName, QueryContext,
+    QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Keeps the first `k` features, optionally per group of features with equal values in the group columns.
+///
+/// If `orderBy` is given, these are the `k` first features w.r.t. the sort order, e.g., the largest values.
+/// Otherwise, the first features of the stream are kept, i.e., the operator acts as a limit.
+/// Only the current candidates are buffered, so the memory is bounded by the number of groups times `k`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopKParams {
+    pub k: usize,
+    #[serde(default)]
+    pub group_columns: Vec<String>,
+    #[serde(default)]
+    pub order_by: Vec<SortColumn>,
+}
+
+pub type TopK = Operator<TopKParams, SingleVectorSource>;
+
+impl OperatorName for TopK {
+    const TYPE_NAME: &'static str = "TopK";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TopK {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params.k > 0,
+            error::InvalidOperatorSpec {
+                reason: "k must be greater than zero",
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let source_columns = &vector_source.result_descriptor().columns;
+        for column in self
+            .params
+            .group_columns
+            .iter()
+            .chain(self.params.order_by.iter().map(|column| &column.column))
+        {
+            ensure!(
+                source_columns.contains_key(column),
+                error::ColumnDoesNotExist {
+                    column: column.clone(),
+                }
+            );
+        }
+
+        Ok(InitializedTopK {
+            result_descriptor: vector_source.result_descriptor().clone(),
+            vector_source,
+            k: self.params.k,
+            group_columns: self.params.group_columns,
+            order_by: self.params.order_by,
+        }
+        .boxed())
+    }
+
+    span_fn!(TopK);
+}
+
+pub struct InitializedTopK {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    k: usize,
+    group_columns: Vec<String>,
+    order_by: Vec<SortColumn>,
+}
+
+impl InitializedVectorOperator for InitializedTopK {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TopKProcessor::new(
+                source,
+                self.k,
+                self.group_columns.clone(),
+                self.order_by.clone(),
+            )
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct TopKProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    k: usize,
+    group_columns: Vec<String>,
+    order_by: Vec<SortColumn>,
+}
+
+impl<G> TopKProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    pub fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        k: usize,
+        group_columns: Vec<String>,
+        order_by: Vec<SortColumn>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            k,
+            group_columns,
+            order_by,
+        }
+    }
+
+    /// Computes the groups of the features in the order of their first occurrence.
+    /// Each group contains the indices of its first `k` features w.r.t. the sort order.
+    fn top_k_groups(&self, collection: &FeatureCollection<G>) -> Result<Vec<Vec<usize>>> {
+        let mut group_keys = vec![String::new(); collection.len()];
+        append_column_keys(&mut group_keys, collection, &self.group_columns)?;
+
+        let mut group_indices: HashMap<String, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (index, key) in group_keys.into_iter().enumerate() {
+            let group = *group_indices.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(index);
+        }
+
+        let sort_keys = sort_keys(collection, &self.order_by)?;
+        for group in &mut groups {
+            // stable, so equal features keep their input order
+            group.sort_by(|&a, &b| compare_keys(&self.order_by, &sort_keys[a], &sort_keys[b]));
+            group.truncate(self.k);
+        }
+
+        Ok(groups)
+    }
+
+    /// Reduces the collection to the candidates of all groups, keeping their input order
+    fn retain_candidates(&self, collection: &FeatureCollection<G>) -> Result<FeatureCollection<G>> {
+        let mut indices: Vec<usize> = self
+            .top_k_groups(collection)?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if indices.len() == collection.len() {
+            return Ok(collection.clone());
+        }
+
+        indices.sort_unstable();
+
+        Ok(collection.take(&indices)?)
+    }
+
+    /// Truncates the stream after the first `k` features
+    fn limit_stream<'a>(
+        &'a self,
+        source: BoxStream<'a, Result<FeatureCollection<G>>>,
+    ) -> BoxStream<'a, Result<FeatureCollection<G>>> {
+        source
+            .scan(self.k, |remaining, collection| {
+                if *remaining == 0 {
+                    return future::ready(None);
+                }
+
+                let collection = collection.and_then(|collection| {
+                    if collection.len() <= *remaining {
+                        *remaining -= collection.len();
+                        return Ok(collection);
+                    }
+
+                    let indices: Vec<usize> = (0..*remaining).collect();
+                    *remaining = 0;
+                    Ok(collection.take(&indices)?)
+                });
+
+                future::ready(Some(collection))
+            })
+            .boxed()
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TopKProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut source = self.source.query(query, ctx).await?;
+
+        if self.group_columns.is_empty() && self.order_by.is_empty() {
+            return Ok(self.limit_stream(source));
+        }
+
+        let mut candidates: Option<FeatureCollection<G>> = None;
+        while let Some(collection) = source.try_next().await? {
+            let combined = match candidates {
+                Some(candidates) => candidates.append(&collection)?,
+                None => collection,
+            };
+
+            candidates = Some(self.retain_candidates(&combined)?);
+        }
+
+        let candidates = if let Some(candidates) = candidates {
+            candidates
+        } else {
+            return Ok(stream::empty().boxed());
+        };
+
+        let indices: Vec<usize> = self
+            .top_k_groups(&candidates)?
+            .into_iter()
+            .flatten()
+            .collect();
+        let result = candidates.take(&indices).map_err(Into::into);
+
+        Ok(stream::once(async move { result }).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::processing::{NullsOrder, SortOrder};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection(countries: &[&str], areas: &[f64]) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0); areas.len()]).unwrap(),
+            vec![TimeInterval::default(); areas.len()],
+            [
+                (
+                    "country".to_string(),
+                    FeatureData::Text(countries.iter().map(ToString::to_string).collect()),
+                ),
+                ("area".to_string(), FeatureData::Float(areas.to_vec())),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    async fn query(operator: TopK) -> Vec<MultiPointCollection> {
+        let processor = operator
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    fn areas(collections: &[MultiPointCollection]) -> Vec<String> {
+        collections
+            .iter()
+            .flat_map(|collection| {
+                collection
+                    .data("area")
+                    .unwrap()
+                    .strings_iter()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_largest_features_per_group() {
+        let result = query(TopK {
+            params: TopKParams {
+                k: 2,
+                group_columns: vec!["country".to_string()],
+                order_by: vec![SortColumn {
+                    column: "area".to_string(),
+                    order: SortOrder::Descending,
+                    nulls: NullsOrder::Last,
+                }],
+            },
+            sources: MockFeatureCollectionSource::multiple(vec![
+                collection(&["de", "fr", "de"], &[1., 5., 3.]),
+                collection(&["fr", "de", "fr"], &[2., 4., 6.]),
+                collection(&["de", "it"], &[2., 7.]),
+            ])
+            .boxed()
+            .into(),
+        })
+        .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(areas(&result), vec!["6", "5", "4", "3", "7"]);
+    }
+
+    #[tokio::test]
+    async fn it_limits_the_stream() {
+        let result = query(TopK {
+            params: TopKParams {
+                k: 4,
+                group_columns: vec![],
+                order_by: vec![],
+            },
+            sources: MockFeatureCollectionSource::multiple(vec![
+                collection(&["de", "fr", "de"], &[1., 5., 3.]),
+                collection(&["fr", "de", "fr"], &[2., 4., 6.]),
+                collection(&["de", "it"], &[2., 7.]),
+            ])
+            .boxed()
+            .into(),
+        })
+        .await;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(areas(&result), vec!["1", "5", "3", "2"]);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_zero_k() {
+        let operator = TopK {
+            params: TopKParams {
+                k: 0,
+                group_columns: vec![],
+                order_by: vec![],
+            },
+            sources: MockFeatureCollectionSource::single(collection(&["de"], &[1.]))
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            operator,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Geometry, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+
+use super::JoinKind;
+use crate::engine::{QueryContext, QueryProcessor, VectorQueryProcessor};
+use crate::processing::temporal_dissolve::{
+    append_column_keys, feature_data_from_values, null_value,
+};
+use crate::util::Result;
+
+/// Implements an equi-join on one or more key columns between a `FeatureCollection` stream and a
+/// `DataCollection` stream, e.g., to enrich features with a lookup table.
+///
+/// The `DataCollection` is loaded into a hash table, so it should be small compared to the features.
+/// Null keys never match and features only match rows with an intersecting time interval.
+pub struct LookupJoinProcessor<G> {
+    left_processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    right_processor: Box<dyn VectorQueryProcessor<VectorType = DataCollection>>,
+    left_columns: Vec<String>,
+    right_columns: Vec<String>,
+    join_kind: JoinKind,
+    right_translation_table: HashMap<String, String>,
+}
+
+/// The rows of the right side indexed by their key values
+struct LookupTable {
+    right: DataCollection,
+    rows: HashMap<String, Vec<usize>>,
+}
+
+impl<G> LookupJoinProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    pub fn new(
+        left_processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        right_processor: Box<dyn VectorQueryProcessor<VectorType = DataCollection>>,
+        left_columns: Vec<String>,
+        right_columns: Vec<String>,
+        join_kind: JoinKind,
+        right_translation_table: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            left_processor,
+            right_processor,
+            left_columns,
+            right_columns,
+            join_kind,
+            right_translation_table,
+        }
+    }
+
+    fn lookup_table(&self, right: DataCollection) -> Result<LookupTable> {
+        let mut rows: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (index, key) in keys(&right, &self.right_columns)?.into_iter().enumerate() {
+            if let Some(key) = key {
+                rows.entry(key).or_default().push(index);
+            }
+        }
+
+        Ok(LookupTable { right, rows })
+    }
+
+    fn join(
+        &self,
+        left: &FeatureCollection<G>,
+        lookup_table: &LookupTable,
+    ) -> Result<FeatureCollection<G>> {
+        let left_time_intervals = left.time_intervals();
+        let right_time_intervals = lookup_table.right.time_intervals();
+
+        let mut left_indices = Vec::with_capacity(left.len());
+        let mut right_indices: Vec<Option<usize>> = Vec::with_capacity(left.len());
+        let mut time_intervals = Vec::with_capacity(left.len());
+
+        for (left_index, key) in keys(left, &self.left_columns)?.into_iter().enumerate() {
+            let left_time = left_time_intervals[left_index];
+
+            let matches: Vec<(usize, TimeInterval)> = key
+                .and_then(|key| lookup_table.rows.get(&key))
+                .map(|rows| {
+                    rows.iter()
+                        .filter_map(|&right_index| {
+                            left_time
+                                .intersect(&right_time_intervals[right_index])
+                                .map(|time| (right_index, time))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if matches.is_empty() && self.join_kind == JoinKind::Left {
+                left_indices.push(left_index);
+                right_indices.push(None);
+                time_intervals.push(left_time);
+            }
+
+            for (right_index, time) in matches {
+                left_indices.push(left_index);
+                right_indices.push(Some(right_index));
+                time_intervals.push(time);
+            }
+        }
+
+        let mut right_columns = Vec::with_capacity(self.right_translation_table.len());
+        for (column, data_type) in lookup_table.right.column_types() {
+            let data = lookup_table.right.data(&column)?;
+
+            right_columns.push((
+                self.right_translation_table[&column].as_str(),
+                feature_data_from_values(
+                    data_type,
+                    right_indices.iter().map(|right_index| {
+                        right_index.map_or_else(
+                            || null_value(data_type),
+                            |right_index| data.get_unchecked(right_index),
+                        )
+                    }),
+                ),
+            ));
+        }
+
+        left.take(&left_indices)?
+            .replace_time(&time_intervals)?
+            .add_columns(&right_columns)
+            .map_err(Into::into)
+    }
+}
+
+/// Computes the key of each feature, which is `None` if any key value is null
+fn keys<T>(collection: &FeatureCollection<T>, columns: &[String]) -> Result<Vec<Option<String>>>
+where
+    T: Geometry + ArrowTyped,
+{
+    let mut keys = vec![String::new(); collection.len()];
+    append_column_keys(&mut keys, collection, columns)?;
+
+    let mut has_nulls = vec![false; collection.len()];
+    for column in columns {
+        for (has_null, is_null) in has_nulls.iter_mut().zip(collection.data(column)?.nulls()) {
+            *has_null |= is_null;
+        }
+    }
+
+    Ok(keys
+        .into_iter()
+        .zip(has_nulls)
+        .map(|(key, has_null)| (!has_null).then_some(key))
+        .collect())
+}
+
+#[async_trait]
+impl<G> QueryProcessor for LookupJoinProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let right_collections: Vec<DataCollection> = self
+            .right_processor
+            .query(query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut right_collections = right_collections.into_iter();
+        let right = if let Some(first) = right_collections.next() {
+            right_collections.try_fold(first, |merged, collection| merged.append(&collection))?
+        } else {
+            DataCollection::empty()
+        };
+
+        let lookup_table = self.lookup_table(right)?;
+
+        let result_stream = self
+            .left_processor
+            .query(query, ctx)
+            .await?
+            .map(move |left| left.and_then(|left| self.join(&left, &lookup_table)));
+
+        Ok(result_stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::processing::vector_join::util::translation_table;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{FeatureData, MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    async fn join(
+        left: MultiPointCollection,
+        right: DataCollection,
+        join_kind: JoinKind,
+    ) -> Vec<MultiPointCollection> {
+        let execution_context = MockExecutionContext::test_default();
+
+        let translation_table =
+            translation_table(left.column_names(), right.column_names(), "_right");
+
+        let left = MockFeatureCollectionSource::single(left)
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+        let right = MockFeatureCollectionSource::single(right)
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .data()
+            .unwrap();
+
+        let processor = LookupJoinProcessor::new(
+            left,
+            right,
+            vec!["country".to_string(), "year".to_string()],
+            vec!["country".to_string(), "year".to_string()],
+            join_kind,
+            translation_table,
+        );
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    fn features() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "country".to_string(),
+                    FeatureData::Text(vec!["de".to_string(), "fr".to_string(), "de".to_string()]),
+                ),
+                ("year".to_string(), FeatureData::Int(vec![2020, 2020, 2021])),
+                ("value".to_string(), FeatureData::Int(vec![1, 2, 3])),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    fn lookup_table() -> DataCollection {
+        DataCollection::from_data(
+            vec![],
+            vec![TimeInterval::default(); 2],
+            [
+                (
+                    "country".to_string(),
+                    FeatureData::Text(vec!["de".to_string(), "de".to_string()]),
+                ),
+                ("year".to_string(), FeatureData::Int(vec![2021, 2020])),
+                (
+                    "value".to_string(),
+                    FeatureData::Text(vec!["b".to_string(), "a".to_string()]),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn inner_join() {
+        let result = join(features(), lookup_table(), JoinKind::Inner).await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.0), (2.0, 2.0)]).unwrap(),
+                vec![TimeInterval::default(); 2],
+                [
+                    (
+                        "country".to_string(),
+                        FeatureData::Text(vec!["de".to_string(), "de".to_string()]),
+                    ),
+                    ("year".to_string(), FeatureData::Int(vec![2020, 2021])),
+                    ("value".to_string(), FeatureData::Int(vec![1, 3])),
+                    (
+                        "country_right".to_string(),
+                        FeatureData::NullableText(vec![
+                            Some("de".to_string()),
+                            Some("de".to_string())
+                        ]),
+                    ),
+                    (
+                        "year_right".to_string(),
+                        FeatureData::NullableInt(vec![Some(2020), Some(2021)]),
+                    ),
+                    (
+                        "value_right".to_string(),
+                        FeatureData::NullableText(vec![
+                            Some("a".to_string()),
+                            Some("b".to_string())
+                        ]),
+                    ),
+                ]
+                .into(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn left_join() {
+        let result = join(features(), lookup_table(), JoinKind::Left).await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 3);
+        assert_eq!(
+            result[0].data("value_right").unwrap().nulls(),
+            vec![false, true, false]
+        );
+    }
+}
@@ -13,11 +13,13 @@ use crate::error;
 use crate::util::Result;
 
 use self::equi_data_join::EquiGeoToDataJoinProcessor;
-use crate::processing::vector_join::util::translation_table;
+use self::lookup_join::LookupJoinProcessor;
+pub(crate) use crate::processing::vector_join::util::translation_table;
 use async_trait::async_trait;
 use std::collections::HashMap;
 
 mod equi_data_join;
+mod lookup_join;
 mod util;
 
 /// The vector join operator requires two inputs and the join type.
@@ -61,6 +63,26 @@ pub enum VectorJoinType {
         /// the default is "right"
         right_column_suffix: Option<String>,
     },
+    /// An equi-join on one or more key columns between a `FeatureCollection` and a `DataCollection`,
+    /// e.g., to enrich features with the attributes of a lookup table
+    Lookup {
+        left_columns: Vec<String>,
+        right_columns: Vec<String>,
+        #[serde(default)]
+        join_kind: JoinKind,
+        /// which suffix to use if columns have conflicting names?
+        /// the default is "right"
+        right_column_suffix: Option<String>,
+    },
+}
+
+/// Whether features without a matching row are dropped or kept with null values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum JoinKind {
+    #[default]
+    Inner,
+    Left,
 }
 
 #[typetag::serde]
@@ -110,6 +132,54 @@ impl VectorOperator for VectorJoin {
                     }
                 );
             }
+            VectorJoinType::Lookup {
+                left_columns,
+                right_columns,
+                ..
+            } => {
+                let left_rd = left.result_descriptor();
+                let right_rd = right.result_descriptor();
+
+                ensure!(
+                    !left_columns.is_empty() && left_columns.len() == right_columns.len(),
+                    error::InvalidOperatorSpec {
+                        reason: "the same positive number of left and right columns must be given",
+                    }
+                );
+
+                for (left_column, right_column) in left_columns.iter().zip(right_columns) {
+                    let left_type = if let Some(column) = left_rd.columns.get(left_column) {
+                        column.data_type
+                    } else {
+                        return Err(error::Error::ColumnDoesNotExist {
+                            column: left_column.clone(),
+                        });
+                    };
+                    let right_type = if let Some(column) = right_rd.columns.get(right_column) {
+                        column.data_type
+                    } else {
+                        return Err(error::Error::ColumnDoesNotExist {
+                            column: right_column.clone(),
+                        });
+                    };
+
+                    ensure!(
+                        left_type == right_type,
+                        error::ColumnTypeMismatch {
+                            left: left_type,
+                            right: right_type,
+                        }
+                    );
+                }
+
+                ensure!(
+                    right_rd.data_type == VectorDataType::Data,
+                    error::InvalidType {
+                        expected: VectorDataType::Data.to_string(),
+                        found: right.result_descriptor().data_type.to_string(),
+                    }
+                );
+            }
         }
 
         // TODO: find out if column prefixes are the same for more than one join type and generify
@@ -117,6 +187,10 @@ impl VectorOperator for VectorJoin {
             VectorJoinType::EquiGeoToData {
                 right_column_suffix,
                 ..
+            }
+            | VectorJoinType::Lookup {
+                right_column_suffix,
+                ..
             } => {
                 let right_column_suffix: &str =
                     right_column_suffix.as_ref().map_or("right", String::as_str);
@@ -225,6 +299,31 @@ impl InitializedVectorOperator for InitializedVectorJoin {
                     }
                 })
             }
+            VectorJoinType::Lookup {
+                left_columns,
+                right_columns,
+                join_kind,
+                right_column_suffix: _right_column_suffix,
+            } => {
+                let right_processor = self
+                    .right
+                    .query_processor()?
+                    .data()
+                    .expect("checked in constructor");
+
+                Ok(map_typed_query_processor!(
+                    self.left.query_processor()?,
+                    left_processor => LookupJoinProcessor::new(
+                        left_processor,
+                        right_processor,
+                        left_columns.clone(),
+                        right_columns.clone(),
+                        *join_kind,
+                        self.state.column_translation_table.clone(),
+                    )
+                    .boxed()
+                ))
+            }
         }
     }
 
@@ -345,4 +444,46 @@ mod tests {
             Err(error::Error::ColumnDoesNotExist { column }) if column == "foo"
         ));
     }
+
+    #[tokio::test]
+    async fn it_checks_lookup_column_types() {
+        let operator = VectorJoin {
+            params: VectorJoinParams {
+                join_type: VectorJoinType::Lookup {
+                    left_columns: vec!["join_column".to_string()],
+                    right_columns: vec!["join_column".to_string()],
+                    join_kind: JoinKind::Left,
+                    right_column_suffix: None,
+                },
+            },
+            sources: VectorJoinSources {
+                left: MockFeatureCollectionSource::single(
+                    MultiPointCollection::from_slices(
+                        &[(0.0, 0.1)],
+                        &[TimeInterval::default()],
+                        &[("join_column", FeatureData::Int(vec![5]))],
+                    )
+                    .unwrap(),
+                )
+                .boxed(),
+                right: MockFeatureCollectionSource::single(
+                    DataCollection::from_slices(
+                        &[] as &[NoGeometry],
+                        &[TimeInterval::default()],
+                        &[("join_column", FeatureData::Text(vec!["5".to_string()]))],
+                    )
+                    .unwrap(),
+                )
+                .boxed(),
+            },
+        };
+
+        assert!(matches!(
+            operator
+                .boxed()
+                .initialize(&MockExecutionContext::test_default())
+                .await,
+            Err(error::Error::ColumnTypeMismatch { .. })
+        ));
+    }
 }
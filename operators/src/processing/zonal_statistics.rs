@@ -0,0 +1,592 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, FeatureCollectionModifications, GeometryCollection,
+    IntoGeometryIterator, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, FeatureData, FeatureDataType, Measurement,
+    MultiPolygonAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    GeoTransform, GridIdx, GridIdx2D, GridIndexAccess, GridShapeAccess, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    TypedVectorQueryProcessor, VectorColumnInfo, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
+};
+use crate::error;
+use crate::processing::point_in_polygon::PointInPolygonTesterWithCollection;
+use crate::util::statistics::TDigest;
+use crate::util::Result;
+
+/// Computes statistics of the pixels of a raster within each polygon, e.g., the mean elevation of a catchment.
+///
+/// A pixel belongs to a polygon if its center lies inside of the polygon.
+/// The pixels of all time steps of the raster that are valid during the time of a polygon are combined.
+/// No-data pixels are ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZonalStatisticsParams {
+    /// The prefix of the output columns, e.g., `elevation_` for `elevation_count`, `elevation_mean` etc.
+    pub column_prefix: String,
+    /// The percentiles to estimate, between `0` and `100`, e.g., `50` for the `p50` column
+    #[serde(default)]
+    pub percentiles: Vec<f64>,
+}
+
+impl ZonalStatisticsParams {
+    /// The names of the output columns in the order of [`ZoneStatistics::columns`]
+    fn column_names(&self) -> Vec<String> {
+        ["count", "mean", "min", "max", "stdDev"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(
+                self.percentiles
+                    .iter()
+                    .map(|percentile| format!("p{percentile}")),
+            )
+            .map(|statistic| format!("{}{statistic}", self.column_prefix))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZonalStatisticsSources {
+    pub polygons: Box<dyn VectorOperator>,
+    pub raster: Box<dyn RasterOperator>,
+}
+
+impl OperatorData for ZonalStatisticsSources {
+    fn data_ids_collect(&self, data_ids: &mut Vec<DataId>) {
+        self.polygons.data_ids_collect(data_ids);
+        self.raster.data_ids_collect(data_ids);
+    }
+}
+
+pub type ZonalStatistics = Operator<ZonalStatisticsParams, ZonalStatisticsSources>;
+
+impl OperatorName for ZonalStatistics {
+    const TYPE_NAME: &'static str = "ZonalStatistics";
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ZonalStatistics {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params
+                .percentiles
+                .iter()
+                .all(|percentile| (0. ..=100.).contains(percentile)),
+            error::InvalidOperatorSpec {
+                reason: "the percentiles must be between 0 and 100".to_string(),
+            }
+        );
+
+        let polygons = self.sources.polygons.initialize(context).await?;
+        let polygons_rd = polygons.result_descriptor();
+
+        ensure!(
+            polygons_rd.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: polygons_rd.data_type.to_string(),
+            }
+        );
+
+        let raster = self.sources.raster.initialize(context).await?;
+        let raster_rd = raster.result_descriptor();
+
+        ensure!(
+            polygons_rd.spatial_reference == raster_rd.spatial_reference,
+            error::InvalidSpatialReference {
+                expected: polygons_rd.spatial_reference,
+                found: raster_rd.spatial_reference,
+            }
+        );
+
+        let column_names = self.params.column_names();
+        let unique_names: HashSet<&String> = column_names.iter().collect();
+        ensure!(
+            unique_names.len() == column_names.len()
+                && column_names
+                    .iter()
+                    .all(|name| !polygons_rd.columns.contains_key(name)),
+            error::DuplicateOutputColumns
+        );
+
+        let result_descriptor = polygons_rd.map_columns(|columns| {
+            let mut columns = columns.clone();
+            for (i, name) in column_names.iter().enumerate() {
+                let column = if i == 0 {
+                    VectorColumnInfo {
+                        data_type: FeatureDataType::Int,
+                        measurement: Measurement::Unitless,
+                    }
+                } else {
+                    VectorColumnInfo {
+                        data_type: FeatureDataType::Float,
+                        measurement: raster_rd.measurement.clone(),
+                    }
+                };
+                columns.insert(name.clone(), column);
+            }
+            columns
+        });
+
+        Ok(InitializedZonalStatistics {
+            result_descriptor,
+            polygons,
+            raster,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(ZonalStatistics);
+}
+
+pub struct InitializedZonalStatistics {
+    result_descriptor: VectorResultDescriptor,
+    polygons: Box<dyn InitializedVectorOperator>,
+    raster: Box<dyn InitializedRasterOperator>,
+    params: ZonalStatisticsParams,
+}
+
+impl InitializedVectorOperator for InitializedZonalStatistics {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let polygons = self
+            .polygons
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `ZonalStatistics` constructor");
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            ZonalStatisticsProcessor {
+                polygons,
+                raster: self.raster.query_processor()?.into_f64(),
+                column_names: self.params.column_names(),
+                percentiles: self.params.percentiles.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Adds the zonal statistics to each chunk of polygons.
+/// The raster is queried for the extent of each chunk and its tiles are folded one after another,
+/// so that neither the tiles nor the pixels of a polygon are kept in memory.
+pub struct ZonalStatisticsProcessor {
+    polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    raster: Box<dyn RasterQueryProcessor<RasterType = f64>>,
+    column_names: Vec<String>,
+    percentiles: Vec<f64>,
+}
+
+impl ZonalStatisticsProcessor {
+    async fn process_collection(
+        &self,
+        collection: MultiPolygonCollection,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<MultiPolygonCollection> {
+        let mut zones = Zones::new(collection, !self.percentiles.is_empty());
+
+        if !zones.statistics.is_empty() {
+            let raster_query = VectorQueryRectangle {
+                spatial_bounds: zones
+                    .polygons
+                    .collection()
+                    .bbox()
+                    .and_then(|bbox| bbox.intersection(&query.spatial_bounds))
+                    .unwrap_or(query.spatial_bounds),
+                time_interval: query.time_interval,
+                spatial_resolution: query.spatial_resolution,
+            };
+
+            zones = self
+                .raster
+                .raster_query(raster_query.into(), ctx)
+                .await?
+                .try_fold(zones, |zones, tile| {
+                    crate::util::spawn_blocking_with_thread_pool(
+                        ctx.thread_pool().clone(),
+                        move || zones.add_tile(&tile),
+                    )
+                    .map_err(Into::into)
+                })
+                .await?;
+        }
+
+        let columns = ZoneStatistics::columns(&zones.statistics, &self.percentiles);
+        let columns: Vec<(&str, FeatureData)> = self
+            .column_names
+            .iter()
+            .map(String::as_str)
+            .zip(columns)
+            .collect();
+
+        zones
+            .polygons
+            .collection()
+            .add_columns(&columns)
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for ZonalStatisticsProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .polygons
+            .query(query, ctx)
+            .await?
+            .and_then(move |collection| self.process_collection(collection, query, ctx));
+
+        Ok(stream.boxed())
+    }
+}
+
+/// The polygons of a chunk with the statistics of the pixels folded so far
+struct Zones {
+    polygons: PointInPolygonTesterWithCollection,
+    bboxes: Vec<Option<BoundingBox2D>>,
+    statistics: Vec<ZoneStatistics>,
+}
+
+impl Zones {
+    /// Creates the statistics of the polygons, which only estimate percentiles if `with_percentiles` is set
+    fn new(collection: MultiPolygonCollection, with_percentiles: bool) -> Self {
+        let bboxes = collection
+            .geometries()
+            .map(|multi_polygon| {
+                BoundingBox2D::from_coord_ref_iter(
+                    multi_polygon
+                        .polygons()
+                        .iter()
+                        .filter_map(|polygon| polygon.first())
+                        .flat_map(|exterior| exterior.iter()),
+                )
+            })
+            .collect();
+
+        Self {
+            statistics: vec![ZoneStatistics::new(with_percentiles); collection.len()],
+            polygons: PointInPolygonTesterWithCollection::new(collection),
+            bboxes,
+        }
+    }
+
+    /// Adds the valid pixels of the tile whose centers lie inside of the polygons that are valid at the time of the tile
+    fn add_tile(mut self, tile: &RasterTile2D<f64>) -> Self {
+        if tile.grid_array.is_empty() {
+            return self;
+        }
+
+        let geo_transform = tile.tile_geo_transform();
+        let shape = tile.grid_shape_array();
+        let tester = self.polygons.tester();
+        let time_intervals = self.polygons.collection().time_intervals();
+
+        for (feature, statistics) in self.statistics.iter_mut().enumerate() {
+            let bbox = match self.bboxes[feature] {
+                Some(bbox) if time_intervals[feature].intersects(&tile.time) => bbox,
+                _ => continue,
+            };
+
+            let (rows, columns) = if let Some(window) = pixel_window(&geo_transform, &bbox, shape) {
+                window
+            } else {
+                continue;
+            };
+
+            for y in rows {
+                for x in columns.clone() {
+                    let grid_idx = GridIdx2D::new([y, x]);
+                    let center = geo_transform.grid_idx_to_pixel_center_coordinate_2d(grid_idx);
+
+                    if !bbox.contains_coordinate(&center)
+                        || !tester.multi_polygon_contains_coordinate(center, feature)
+                    {
+                        continue;
+                    }
+
+                    if let Ok(Some(value)) = tile.get_at_grid_index(grid_idx) {
+                        statistics.add(value);
+                    }
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// The rows and columns of the tile that overlap the bounding box or `None` if they do not overlap
+fn pixel_window(
+    geo_transform: &GeoTransform,
+    bbox: &BoundingBox2D,
+    [rows, columns]: [usize; 2],
+) -> Option<(RangeInclusive<isize>, RangeInclusive<isize>)> {
+    let GridIdx([y_0, x_0]) = geo_transform.coordinate_to_grid_idx_2d(bbox.upper_left());
+    let GridIdx([y_1, x_1]) = geo_transform.coordinate_to_grid_idx_2d(bbox.lower_right());
+
+    let rows = y_0.min(y_1).max(0)..=y_0.max(y_1).min(rows as isize - 1);
+    let columns = x_0.min(x_1).max(0)..=x_0.max(x_1).min(columns as isize - 1);
+
+    (!rows.is_empty() && !columns.is_empty()).then_some((rows, columns))
+}
+
+/// The statistics of the pixels of a single polygon.
+/// The mean and the variance are computed with Welford's algorithm, the percentiles are estimated with a t-digest.
+#[derive(Debug, Clone)]
+struct ZoneStatistics {
+    /// Only present if percentiles are requested
+    digest: Option<TDigest>,
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl ZoneStatistics {
+    fn new(with_percentiles: bool) -> Self {
+        Self {
+            digest: with_percentiles.then(TDigest::default),
+            count: 0,
+            mean: 0.,
+            m2: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+
+        if let Some(digest) = &mut self.digest {
+            digest.add(value);
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// The population standard deviation
+    fn std_dev(&self) -> Option<f64> {
+        (self.count > 0).then(|| (self.m2 / self.count as f64).sqrt())
+    }
+
+    /// The columns of the count, mean, min, max, standard deviation and percentiles of the polygons
+    fn columns(statistics: &[Self], percentiles: &[f64]) -> Vec<FeatureData> {
+        let column = |f: &dyn Fn(&Self) -> Option<f64>| {
+            FeatureData::NullableFloat(statistics.iter().map(f).collect())
+        };
+
+        let mut columns = vec![
+            FeatureData::Int(statistics.iter().map(|s| s.count as i64).collect()),
+            column(&Self::mean),
+            column(&Self::min),
+            column(&Self::max),
+            column(&Self::std_dev),
+        ];
+
+        for percentile in percentiles {
+            columns.push(column(&|s: &Self| {
+                s.digest
+                    .as_ref()
+                    .and_then(|digest| digest.quantile(percentile / 100.))
+            }));
+        }
+
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::util::test::{grid_2x2, mock_raster_source, tile_2x2};
+    use geoengine_datatypes::primitives::{MultiPolygon, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TilingSpecification;
+    use std::collections::HashMap;
+
+    fn rectangle(x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x_min, y_min).into(),
+            (x_max, y_min).into(),
+            (x_max, y_max).into(),
+            (x_min, y_max).into(),
+            (x_min, y_min).into(),
+        ]]])
+        .unwrap()
+    }
+
+    fn zonal_statistics(percentiles: Vec<f64>) -> ZonalStatistics {
+        let polygons = MultiPolygonCollection::from_data(
+            vec![rectangle(0., -2., 1., 0.), rectangle(5., 5., 6., 6.)],
+            vec![TimeInterval::new_unchecked(0, 20); 2],
+            HashMap::new(),
+        )
+        .unwrap();
+
+        ZonalStatistics {
+            params: ZonalStatisticsParams {
+                column_prefix: "zs_".to_string(),
+                percentiles,
+            },
+            sources: ZonalStatisticsSources {
+                polygons: MockFeatureCollectionSource::single(polygons).boxed(),
+                raster: mock_raster_source(vec![
+                    tile_2x2(
+                        TimeInterval::new_unchecked(0, 10),
+                        grid_2x2(vec![1_u8, 2, 3, 4]),
+                    ),
+                    tile_2x2(
+                        TimeInterval::new_unchecked(10, 20),
+                        grid_2x2(vec![5_u8, 6, 7, 8]),
+                    ),
+                ])
+                .boxed(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_computes_statistics_per_polygon() {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        let operator = zonal_statistics(vec![50.])
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().columns.get("zs_count"),
+            Some(&VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            })
+        );
+
+        let processor = operator.query_processor().unwrap().multi_polygon().unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -2.).into(), (6., 6.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 20),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        let collection = &collections[0];
+
+        let values = |column: &str| -> Vec<Option<f64>> {
+            collection
+                .data(column)
+                .unwrap()
+                .float_options_iter()
+                .collect()
+        };
+
+        // the first polygon covers the pixels 1 and 3 of the first and 5 and 7 of the second time step
+        assert_eq!(values("zs_count"), vec![Some(4.), Some(0.)]);
+        assert_eq!(values("zs_mean"), vec![Some(4.), None]);
+        assert_eq!(values("zs_min"), vec![Some(1.), None]);
+        assert_eq!(values("zs_max"), vec![Some(7.), None]);
+        assert_eq!(values("zs_stdDev"), vec![Some(5_f64.sqrt()), None]);
+        assert_eq!(values("zs_p50"), vec![Some(4.), None]);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_percentiles_and_columns() {
+        let execution_context = MockExecutionContext::test_default();
+
+        assert!(zonal_statistics(vec![101.])
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .is_err());
+        assert!(matches!(
+            zonal_statistics(vec![50., 50.])
+                .boxed()
+                .initialize(&execution_context)
+                .await,
+            Err(error::Error::DuplicateOutputColumns)
+        ));
+    }
+
+    #[test]
+    fn it_only_estimates_percentiles_on_demand() {
+        let mut statistics = ZoneStatistics::new(false);
+        for value in [3., 1., f64::NAN, 2.] {
+            statistics.add(value);
+        }
+
+        assert!(statistics.digest.is_none());
+        assert_eq!(statistics.count, 3);
+        assert_eq!(statistics.min(), Some(1.));
+        assert_eq!(statistics.max(), Some(3.));
+        assert_eq!(statistics.mean(), Some(2.));
+        assert_eq!(ZoneStatistics::new(false).min(), None);
+    }
+}
@@ -159,8 +159,12 @@ impl OperatorData for CsvSourceParameters {
 impl VectorOperator for CsvSource {
     async fn _initialize(
         self: Box<Self>,
-        _context: &dyn crate::engine::ExecutionContext,
+        context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
+        context
+            .path_sandbox()
+            .ensure_is_allowed(&self.params.file_path)?;
+
         let initialized_source = InitializedCsvSource {
             result_descriptor: VectorResultDescriptor {
                 data_type: VectorDataType::MultiPoint, // TODO: get as user input
@@ -7,7 +7,7 @@ use geoengine_datatypes::primitives::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    engine::{MetaData, RasterResultDescriptor},
+    engine::{MetaData, PathSandbox, RasterResultDescriptor, SandboxedLoadingInfo},
     error::Error,
     util::Result,
 };
@@ -447,6 +447,11 @@ pub enum GdalLoadingInfoTemporalSliceIterator {
     },
     Dynamic(DynamicGdalLoadingInfoPartIterator),
     NetCdfCf(NetCdfCfGdalLoadingInfoPartIterator),
+    /// Checks the files of the slices against the sandbox before they are read
+    Sandboxed {
+        parts: Box<GdalLoadingInfoTemporalSliceIterator>,
+        sandbox: PathSandbox,
+    },
 }
 
 impl Iterator for GdalLoadingInfoTemporalSliceIterator {
@@ -457,10 +462,31 @@ impl Iterator for GdalLoadingInfoTemporalSliceIterator {
             GdalLoadingInfoTemporalSliceIterator::Static { parts } => parts.next().map(Result::Ok),
             GdalLoadingInfoTemporalSliceIterator::Dynamic(iter) => iter.next(),
             GdalLoadingInfoTemporalSliceIterator::NetCdfCf(iter) => iter.next(),
+            GdalLoadingInfoTemporalSliceIterator::Sandboxed { parts, sandbox } => {
+                parts.next().map(|part| {
+                    let part = part?;
+                    if let Some(params) = &part.params {
+                        sandbox.ensure_is_allowed(&params.file_path)?;
+                    }
+                    Ok(part)
+                })
+            }
         }
     }
 }
 
+impl SandboxedLoadingInfo for GdalLoadingInfo {
+    fn sandboxed(self, sandbox: &PathSandbox) -> Result<Self> {
+        // the slices may be generated lazily, so they are checked when they are read
+        Ok(Self {
+            info: GdalLoadingInfoTemporalSliceIterator::Sandboxed {
+                parts: Box::new(self.info),
+                sandbox: sandbox.clone(),
+            },
+        })
+    }
+}
+
 /// one temporal slice of the dataset that requires reading from exactly one Gdal dataset
 #[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -1,6 +1,6 @@
 use crate::adapters::SparseTilesFillAdapter;
 use crate::engine::{CreateSpan, MetaData, OperatorData, OperatorName, QueryProcessor};
-use crate::util::gdal::gdal_open_dataset_ex;
+use crate::util::gdal_dataset_pool::GDAL_DATASET_POOL;
 use crate::util::input::float_option_with_nan;
 use crate::util::TemporaryGdalThreadLocalConfigOptions;
 use crate::{
@@ -43,12 +43,14 @@ pub use loading_info::{
 };
 use log::debug;
 use num::FromPrimitive;
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::marker::PhantomData;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 use tracing::{span, Level};
 
@@ -406,6 +408,8 @@ where
 {
     pub tiling_specification: TilingSpecification,
     pub meta_data: GdalMetaData,
+    /// The pool for reading the files, so that blocking reads do not starve computations
+    pub io_thread_pool: Arc<ThreadPool>,
     pub _phantom_data: PhantomData<T>,
 }
 
@@ -416,11 +420,12 @@ impl GdalRasterLoader {
     /// A method to async load single tiles from a GDAL dataset.
     ///
     async fn load_tile_data_async<T: Pixel + GdalType + FromPrimitive>(
+        io_thread_pool: Arc<ThreadPool>,
         dataset_params: GdalDatasetParameters,
         tile_information: TileInformation,
         tile_time: TimeInterval,
     ) -> Result<RasterTile2D<T>> {
-        crate::util::spawn_blocking(move || {
+        crate::util::spawn_blocking_with_thread_pool(io_thread_pool, move || {
             Self::load_tile_data(&dataset_params, tile_information, tile_time)
         })
         .await
@@ -428,6 +433,7 @@ impl GdalRasterLoader {
     }
 
     async fn load_tile_async<T: Pixel + GdalType + FromPrimitive>(
+        io_thread_pool: Arc<ThreadPool>,
         dataset_params: Option<GdalDatasetParameters>,
         tile_information: TileInformation,
         tile_time: TimeInterval,
@@ -443,7 +449,7 @@ impl GdalRasterLoader {
                     "Loading tile {:?}, from {:?}, band: {}",
                     &tile_information, ds.file_path, ds.rasterband_channel
                 );
-                Self::load_tile_data_async(ds, tile_information, tile_time).await
+                Self::load_tile_data_async(io_thread_pool, ds, tile_information, tile_time).await
             }
             Some(_) => {
                 debug!("Skipping tile not in query rect {:?}", &tile_information);
@@ -489,13 +495,14 @@ impl GdalRasterLoader {
             .as_ref()
             .map(|config_options| TemporaryGdalThreadLocalConfigOptions::new(config_options));
 
-        let dataset_result = gdal_open_dataset_ex(
+        let dataset_result = GDAL_DATASET_POOL.get(
             &dataset_params.file_path,
             DatasetOptions {
                 open_flags: GdalOpenFlags::GDAL_OF_RASTER,
                 open_options: options.as_deref(),
                 ..DatasetOptions::default()
             },
+            dataset_params.gdal_config_options.as_deref(),
         );
 
         if dataset_result.is_err() {
@@ -540,9 +547,17 @@ impl GdalRasterLoader {
         query: RasterQueryRectangle,
         info: GdalLoadingInfoTemporalSlice,
         tiling_strategy: TilingStrategy,
+        io_thread_pool: Arc<ThreadPool>,
     ) -> impl Stream<Item = impl Future<Output = Result<RasterTile2D<T>>>> {
         stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds)).map(
-            move |tile| GdalRasterLoader::load_tile_async(info.params.clone(), tile, info.time),
+            move |tile| {
+                GdalRasterLoader::load_tile_async(
+                    io_thread_pool.clone(),
+                    info.params.clone(),
+                    tile,
+                    info.time,
+                )
+            },
         )
     }
 
@@ -553,11 +568,17 @@ impl GdalRasterLoader {
         loading_info_stream: S,
         query: RasterQueryRectangle,
         tiling_strategy: TilingStrategy,
+        io_thread_pool: Arc<ThreadPool>,
     ) -> impl Stream<Item = Result<RasterTile2D<T>>> {
         loading_info_stream
             .map_ok(move |info| {
-                GdalRasterLoader::temporal_slice_tile_future_stream(query, info, tiling_strategy)
-                    .map(Result::Ok)
+                GdalRasterLoader::temporal_slice_tile_future_stream(
+                    query,
+                    info,
+                    tiling_strategy,
+                    io_thread_pool.clone(),
+                )
+                .map(Result::Ok)
             })
             .try_flatten()
             .try_buffered(16) // TODO: make this configurable
@@ -645,8 +666,12 @@ where
 
         let source_stream = stream::iter(loading_iter);
 
-        let source_stream =
-            GdalRasterLoader::loading_info_to_tile_stream(source_stream, query, tiling_strategy);
+        let source_stream = GdalRasterLoader::loading_info_to_tile_stream(
+            source_stream,
+            query,
+            tiling_strategy,
+            self.io_thread_pool.clone(),
+        );
 
         // use SparseTilesFillAdapter to fill all the gaps
         let filled_stream = SparseTilesFillAdapter::new(
@@ -672,7 +697,9 @@ impl RasterOperator for GdalSource {
         self: Box<Self>,
         context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedRasterOperator>> {
-        let meta_data: GdalMetaData = context.meta_data(&self.params.data).await?;
+        let meta_data: GdalMetaData = context
+            .path_sandbox()
+            .sandbox_meta_data(context.meta_data(&self.params.data).await?);
 
         debug!("Initializing GdalSource for {:?}.", &self.params.data);
 
@@ -680,6 +707,7 @@ impl RasterOperator for GdalSource {
             result_descriptor: meta_data.result_descriptor().await?,
             meta_data,
             tiling_specification: context.tiling_specification(),
+            io_thread_pool: context.io_thread_pool().clone(),
         };
 
         Ok(op.boxed())
@@ -692,6 +720,7 @@ pub struct InitializedGdalSourceOperator {
     pub meta_data: GdalMetaData,
     pub result_descriptor: RasterResultDescriptor,
     pub tiling_specification: TilingSpecification,
+    pub io_thread_pool: Arc<ThreadPool>,
 }
 
 impl InitializedRasterOperator for InitializedGdalSourceOperator {
@@ -705,6 +734,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -713,6 +743,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -721,6 +752,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -739,6 +771,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -747,6 +780,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -760,6 +794,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -768,6 +803,7 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
                 GdalSourceProcessor {
                     tiling_specification: self.tiling_specification,
                     meta_data: self.meta_data.clone(),
+                    io_thread_pool: self.io_thread_pool.clone(),
                     _phantom_data: PhantomData,
                 }
                 .boxed(),
@@ -1003,8 +1039,11 @@ fn create_no_data_tile<T: Pixel>(
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GdalMetadataMapping {
+    #[serde(alias = "sourceKey")]
     pub source_key: RasterPropertiesKey,
+    #[serde(alias = "targetKey")]
     pub target_key: RasterPropertiesKey,
+    #[serde(alias = "targetType")]
     pub target_type: RasterPropertiesEntryType,
 }
 
@@ -1076,8 +1115,9 @@ fn properties_from_band(properties: &mut RasterProperties, gdal_dataset: &GdalRa
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::engine::{MockExecutionContext, MockQueryContext, PathSandbox};
     use crate::test_data;
+    use crate::util::create_rayon_thread_pool;
     use crate::util::gdal::add_ndvi_dataset;
     use crate::util::Result;
 
@@ -1490,6 +1530,32 @@ mod tests {
         assert_eq!(x.inner_grid.data, &[1; 64]);
     }
 
+    #[tokio::test]
+    async fn it_rejects_files_outside_of_the_sandbox() {
+        let sandbox_root = tempfile::tempdir().unwrap();
+
+        let mut exe_ctx = MockExecutionContext::test_default();
+        exe_ctx.path_sandbox = PathSandbox {
+            allowed_roots: vec![sandbox_root.path().to_path_buf()],
+            allowed_schemes: vec![],
+            data_root: None,
+        };
+        let query_ctx = MockQueryContext::test_default();
+        let id = add_ndvi_dataset(&mut exe_ctx);
+
+        let c = query_gdal_source(
+            &exe_ctx,
+            &query_ctx,
+            id,
+            [256, 256].into(),
+            SpatialPartition2D::new_unchecked((-180., 90.).into(), (180., -90.).into()),
+            TimeInterval::new_unchecked(1_388_534_400_000, 1_388_534_400_001),
+        )
+        .await;
+
+        assert!(matches!(c.first(), Some(Err(Error::PathNotAllowed { .. }))));
+    }
+
     #[tokio::test]
     async fn test_query_single_time_slice() {
         let mut exe_ctx = MockExecutionContext::test_default();
@@ -1681,7 +1747,13 @@ mod tests {
         let time_interval = TimeInterval::new_unchecked(1_388_534_400_000, 1_391_212_800_000); // 2014-01-01 - 2014-01-15
         let params = None;
 
-        let tile = GdalRasterLoader::load_tile_async::<f64>(params, tile_info, time_interval).await;
+        let tile = GdalRasterLoader::load_tile_async::<f64>(
+            create_rayon_thread_pool(0),
+            params,
+            tile_info,
+            time_interval,
+        )
+        .await;
 
         assert!(tile.is_ok());
 
@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollection, FeatureCollectionRowBuilder, VectorDataType,
+};
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, DateTime, FeatureDataType, FeatureDataValue, Geometry, Measurement,
+    MultiLineString, MultiPoint, MultiPolygon, NoGeometry, TimeInstance, TimeInterval,
+    TypedGeometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tracing::{span, Level};
+
+use super::ogr_source::FeatureCollectionBuilderGeometryHandler;
+use super::OgrSourceDurationSpec;
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedVectorOperator, OperatorData, OperatorName,
+    QueryContext, QueryProcessor, SourceOperator, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::util::Result;
+
+/// Parameters for the [`GeoJsonSource`] operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoJsonSourceParameters {
+    pub data: GeoJsonSourceData,
+    #[serde(default)]
+    pub time: GeoJsonSourceTime,
+}
+
+/// The location of the `GeoJSON` content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum GeoJsonSourceData {
+    /// A file path relative to the data root of the instance
+    File { path: PathBuf },
+    /// `GeoJSON` that is embedded in the workflow. Only suitable for small data.
+    Inline { content: serde_json::Value },
+}
+
+/// The mapping of feature properties to the time of the features.
+///
+/// Time values are either ISO 8601 strings or Unix timestamps in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum GeoJsonSourceTime {
+    None,
+    #[serde(rename_all = "camelCase")]
+    Start {
+        start_field: String,
+        duration: OgrSourceDurationSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    StartEnd {
+        start_field: String,
+        end_field: String,
+    },
+}
+
+impl Default for GeoJsonSourceTime {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// A source that reads `GeoJSON` features directly, i.e., without using OGR
+pub type GeoJsonSource = SourceOperator<GeoJsonSourceParameters>;
+
+impl OperatorName for GeoJsonSource {
+    const TYPE_NAME: &'static str = "GeoJsonSource";
+}
+
+impl OperatorData for GeoJsonSourceParameters {
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GeoJsonSource {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let content = match self.params.data {
+            GeoJsonSourceData::File { path } => {
+                let path = context.path_sandbox().resolve_data_path(&path)?;
+                context.path_sandbox().ensure_is_allowed(&path)?;
+
+                let content = crate::util::spawn_blocking(move || std::fs::read_to_string(path))
+                    .await?
+                    .map_err(|e| Error::GeoJsonSource {
+                        details: e.to_string(),
+                    })?;
+
+                serde_json::from_str(&content).map_err(|e| Error::GeoJsonSource {
+                    details: e.to_string(),
+                })?
+            }
+            GeoJsonSourceData::Inline { content } => content,
+        };
+
+        let features = Arc::new(GeoJsonFeatures::parse(content, &self.params.time)?);
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: features.data_type,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: features
+                .columns
+                .iter()
+                .map(|(name, data_type)| {
+                    (
+                        name.clone(),
+                        VectorColumnInfo {
+                            data_type: *data_type,
+                            measurement: Measurement::Unitless,
+                        },
+                    )
+                })
+                .collect(),
+            time: None,
+            bbox: None,
+        };
+
+        Ok(InitializedGeoJsonSource {
+            result_descriptor,
+            features,
+        }
+        .boxed())
+    }
+
+    span_fn!(GeoJsonSource);
+}
+
+pub struct InitializedGeoJsonSource {
+    result_descriptor: VectorResultDescriptor,
+    features: Arc<GeoJsonFeatures>,
+}
+
+impl InitializedVectorOperator for InitializedGeoJsonSource {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(self.features.clone().query_processor())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// The parsed features of a `GeoJSON` document with their inferred column types
+#[derive(Debug)]
+pub(super) struct GeoJsonFeatures {
+    pub data_type: VectorDataType,
+    pub columns: HashMap<String, FeatureDataType>,
+    pub features: Vec<GeoJsonFeature>,
+}
+
+#[derive(Debug)]
+pub(super) struct GeoJsonFeature {
+    pub geometry: TypedGeometry,
+    pub time: TimeInterval,
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+impl GeoJsonFeatures {
+    pub fn query_processor(self: Arc<Self>) -> TypedVectorQueryProcessor {
+        match self.data_type {
+            VectorDataType::Data => TypedVectorQueryProcessor::Data(
+                GeoJsonSourceProcessor::<NoGeometry>::new(self).boxed(),
+            ),
+            VectorDataType::MultiPoint => TypedVectorQueryProcessor::MultiPoint(
+                GeoJsonSourceProcessor::<MultiPoint>::new(self).boxed(),
+            ),
+            VectorDataType::MultiLineString => TypedVectorQueryProcessor::MultiLineString(
+                GeoJsonSourceProcessor::<MultiLineString>::new(self).boxed(),
+            ),
+            VectorDataType::MultiPolygon => TypedVectorQueryProcessor::MultiPolygon(
+                GeoJsonSourceProcessor::<MultiPolygon>::new(self).boxed(),
+            ),
+        }
+    }
+
+    fn parse(content: serde_json::Value, time: &GeoJsonSourceTime) -> Result<Self> {
+        let geojson: geojson::GeoJson =
+            serde_json::from_value(content).map_err(|e| Error::GeoJsonSource {
+                details: e.to_string(),
+            })?;
+
+        let features = match geojson {
+            geojson::GeoJson::FeatureCollection(collection) => collection.features,
+            geojson::GeoJson::Feature(feature) => vec![feature],
+            geojson::GeoJson::Geometry(geometry) => vec![geojson::Feature {
+                geometry: Some(geometry),
+                ..Default::default()
+            }],
+        };
+
+        let mut data_type = None;
+        let mut columns = HashMap::<String, FeatureDataType>::new();
+        let mut parsed_features = Vec::with_capacity(features.len());
+
+        for feature in features {
+            let properties = feature.properties.unwrap_or_default();
+
+            let geometry = match feature.geometry {
+                Some(geometry) => TypedGeometry::try_from(geometry).context(error::DataType)?,
+                None => TypedGeometry::Data(NoGeometry),
+            };
+
+            let feature_data_type = geometry_data_type(&geometry);
+            if *data_type.get_or_insert(feature_data_type) != feature_data_type {
+                return Err(Error::GeoJsonSource {
+                    details: "all features must have the same geometry type".to_owned(),
+                });
+            }
+
+            for (name, value) in &properties {
+                let value_type = if let Some(value_type) = json_data_type(value) {
+                    value_type
+                } else {
+                    // null values do not determine the column type
+                    columns.entry(name.clone()).or_insert(FeatureDataType::Text);
+                    continue;
+                };
+
+                columns
+                    .entry(name.clone())
+                    .and_modify(|column_type| {
+                        *column_type = column_type.widen(value_type);
+                    })
+                    .or_insert(value_type);
+            }
+
+            parsed_features.push(GeoJsonFeature {
+                geometry,
+                time: feature_time(&properties, time)?,
+                properties,
+            });
+        }
+
+        Ok(Self {
+            data_type: data_type.unwrap_or(VectorDataType::Data),
+            columns,
+            features: parsed_features,
+        })
+    }
+}
+
+pub(super) fn geometry_data_type(geometry: &TypedGeometry) -> VectorDataType {
+    match geometry {
+        TypedGeometry::Data(_) => VectorDataType::Data,
+        TypedGeometry::MultiPoint(_) => VectorDataType::MultiPoint,
+        TypedGeometry::MultiLineString(_) => VectorDataType::MultiLineString,
+        TypedGeometry::MultiPolygon(_) => VectorDataType::MultiPolygon,
+    }
+}
+
+pub(super) fn json_data_type(value: &serde_json::Value) -> Option<FeatureDataType> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(_) => Some(FeatureDataType::Bool),
+        serde_json::Value::Number(number) if number.is_i64() => Some(FeatureDataType::Int),
+        serde_json::Value::Number(_) => Some(FeatureDataType::Float),
+        serde_json::Value::String(_)
+        | serde_json::Value::Array(_)
+        | serde_json::Value::Object(_) => Some(FeatureDataType::Text),
+    }
+}
+
+fn json_feature_data_value(
+    value: Option<&serde_json::Value>,
+    data_type: FeatureDataType,
+) -> FeatureDataValue {
+    let value = value.filter(|v| !v.is_null());
+
+    match data_type {
+        FeatureDataType::Int => FeatureDataValue::NullableInt(value.and_then(|v| v.as_i64())),
+        FeatureDataType::Float => FeatureDataValue::NullableFloat(value.and_then(|v| v.as_f64())),
+        FeatureDataType::Bool => FeatureDataValue::NullableBool(value.and_then(|v| v.as_bool())),
+        _ => FeatureDataValue::NullableText(value.map(|v| match v {
+            serde_json::Value::String(s) => s.clone(),
+            v => v.to_string(),
+        })),
+    }
+}
+
+fn feature_time(
+    properties: &serde_json::Map<String, serde_json::Value>,
+    time: &GeoJsonSourceTime,
+) -> Result<TimeInterval> {
+    match time {
+        GeoJsonSourceTime::None => Ok(TimeInterval::default()),
+        GeoJsonSourceTime::Start {
+            start_field,
+            duration,
+        } => {
+            let start = time_instance(properties, start_field)?;
+            TimeInterval::new(start, (start + *duration)?).context(error::DataType)
+        }
+        GeoJsonSourceTime::StartEnd {
+            start_field,
+            end_field,
+        } => TimeInterval::new(
+            time_instance(properties, start_field)?,
+            time_instance(properties, end_field)?,
+        )
+        .context(error::DataType),
+    }
+}
+
+fn time_instance(
+    properties: &serde_json::Map<String, serde_json::Value>,
+    field: &str,
+) -> Result<TimeInstance> {
+    match properties.get(field) {
+        Some(serde_json::Value::String(s)) => {
+            DateTime::from_str(s)
+                .map(Into::into)
+                .map_err(|e| Error::GeoJsonSource {
+                    details: format!("invalid time `{s}` in field `{field}`: {e}"),
+                })
+        }
+        Some(serde_json::Value::Number(n)) if n.is_i64() => {
+            TimeInstance::from_millis(n.as_i64().unwrap_or_default()).context(error::DataType)
+        }
+        _ => Err(Error::GeoJsonSource {
+            details: format!("missing time in field `{field}`"),
+        }),
+    }
+}
+
+struct GeoJsonSourceProcessor<G> {
+    features: Arc<GeoJsonFeatures>,
+    _geometry: std::marker::PhantomData<G>,
+}
+
+impl<G> GeoJsonSourceProcessor<G> {
+    fn new(features: Arc<GeoJsonFeatures>) -> Self {
+        Self {
+            features,
+            _geometry: Default::default(),
+        }
+    }
+
+    fn build_chunks(
+        &self,
+        query: VectorQueryRectangle,
+        chunk_byte_size: usize,
+    ) -> Result<Vec<FeatureCollection<G>>>
+    where
+        G: Geometry + ArrowTyped,
+        FeatureCollectionRowBuilder<G>: FeatureCollectionBuilderGeometryHandler<G>,
+    {
+        let new_builder = || -> Result<FeatureCollectionRowBuilder<G>> {
+            let mut builder = FeatureCollection::<G>::builder();
+            for (name, data_type) in &self.features.columns {
+                builder.add_column(name.clone(), *data_type)?;
+            }
+            Ok(builder.finish_header())
+        };
+
+        let mut chunks = Vec::new();
+        let mut builder = new_builder()?;
+
+        for feature in &self.features.features {
+            if !feature.time.intersects(&query.time_interval) {
+                continue;
+            }
+
+            let geometry = G::try_from(feature.geometry.clone())?;
+            if !geometry.intersects_bbox(&query.spatial_bounds) {
+                continue;
+            }
+
+            builder.push_generic_geometry(geometry);
+            builder.push_time_interval(feature.time);
+            for (name, data_type) in &self.features.columns {
+                builder.push_data(
+                    name,
+                    json_feature_data_value(feature.properties.get(name), *data_type),
+                )?;
+            }
+            builder.finish_row();
+
+            if builder.byte_size() >= chunk_byte_size {
+                chunks.push(std::mem::replace(&mut builder, new_builder()?).build()?);
+            }
+        }
+
+        if chunks.is_empty() || !builder.is_empty() {
+            chunks.push(builder.build()?);
+        }
+
+        Ok(chunks)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for GeoJsonSourceProcessor<G>
+where
+    G: Geometry + ArrowTyped + 'static,
+    FeatureCollectionRowBuilder<G>: FeatureCollectionBuilderGeometryHandler<G>,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let chunks = self.build_chunks(query, ctx.chunk_byte_size().into())?;
+
+        Ok(stream::iter(chunks.into_iter().map(Ok)).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn points() -> serde_json::Value {
+        json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [1.0, 1.0]},
+                "properties": {"name": "a", "count": 1, "value": 1, "start": "2020-01-01T00:00:00Z"}
+            }, {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [2.0, 2.0]},
+                "properties": {"name": "b", "count": null, "value": 2.5, "start": "2021-01-01T00:00:00Z"}
+            }, {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [50.0, 50.0]},
+                "properties": {"name": "c", "count": 3, "value": "high", "start": "2020-06-01T00:00:00Z"}
+            }]
+        })
+    }
+
+    #[tokio::test]
+    async fn it_infers_column_types() {
+        let source = GeoJsonSource {
+            params: GeoJsonSourceParameters {
+                data: GeoJsonSourceData::Inline { content: points() },
+                time: GeoJsonSourceTime::None,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let descriptor = source.result_descriptor();
+        assert_eq!(descriptor.data_type, VectorDataType::MultiPoint);
+        assert_eq!(
+            descriptor.column_data_type("name"),
+            Some(FeatureDataType::Text)
+        );
+        assert_eq!(
+            descriptor.column_data_type("count"),
+            Some(FeatureDataType::Int)
+        );
+        assert_eq!(
+            descriptor.column_data_type("value"),
+            Some(FeatureDataType::Text)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_filters_by_bbox_and_time() {
+        let source = GeoJsonSource {
+            params: GeoJsonSourceParameters {
+                data: GeoJsonSourceData::Inline { content: points() },
+                time: GeoJsonSourceTime::Start {
+                    start_field: "start".to_owned(),
+                    duration: OgrSourceDurationSpec::Infinite,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = source.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+            time_interval: TimeInterval::new_instant(
+                DateTime::from_str("2020-07-01T00:00:00Z").unwrap(),
+            )
+            .unwrap(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 1);
+        assert_eq!(
+            collections[0]
+                .data("name")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reads_features_without_geometries() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        serde_json::to_writer(
+            &mut file,
+            &json!({
+                "type": "FeatureCollection",
+                "features": [{"type": "Feature", "geometry": null, "properties": {"flag": true}}]
+            }),
+        )
+        .unwrap();
+
+        let source = GeoJsonSource {
+            params: GeoJsonSourceParameters {
+                data: GeoJsonSourceData::File {
+                    path: file.path().into(),
+                },
+                time: GeoJsonSourceTime::None,
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(source.result_descriptor().data_type, VectorDataType::Data);
+        assert_eq!(
+            source.result_descriptor().column_data_type("flag"),
+            Some(FeatureDataType::Bool)
+        );
+    }
+
+    #[test]
+    fn operator() {
+        let operator: Box<dyn VectorOperator> = serde_json::from_value(json!({
+            "type": "GeoJsonSource",
+            "params": {
+                "data": {"type": "inline", "content": points()},
+                "time": {"type": "startEnd", "startField": "start", "endField": "end"}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&operator).unwrap()["params"]["time"],
+            json!({"type": "startEnd", "startField": "start", "endField": "end"})
+        );
+    }
+}
@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    Coordinate2D, FeatureDataType, Measurement, NoGeometry, SpatialPartition2D, SpatialResolution,
+    TimeInterval, TypedGeometry,
+};
+use geoengine_datatypes::raster::{
+    GeoTransform, GridIdx, GridIdx2D, RasterDataType, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use super::geojson_source::{geometry_data_type, json_data_type, GeoJsonFeature, GeoJsonFeatures};
+use super::synthetic_raster::{GeneratedRasterProcessor, PixelGenerator};
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator,
+    OperatorData, OperatorName, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    SourceOperator, TypedRasterQueryProcessor, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Parameters for the [`InlineVectorSource`] operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineVectorSourceParameters {
+    pub spatial_reference: SpatialReference,
+    #[serde(default)]
+    pub columns: HashMap<String, FeatureDataType>,
+    pub features: Vec<InlineFeature>,
+}
+
+/// A feature that is embedded in the workflow.
+/// Properties that are not listed in the columns are ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineFeature {
+    pub geometry: Option<geojson::Geometry>,
+    #[serde(default)]
+    pub time: TimeInterval,
+    #[serde(default)]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A source for a small set of features that is stored in the workflow itself,
+/// e.g., geometries a user has drawn on the map.
+pub type InlineVectorSource = SourceOperator<InlineVectorSourceParameters>;
+
+impl OperatorName for InlineVectorSource {
+    const TYPE_NAME: &'static str = "InlineVectorSource";
+}
+
+impl OperatorData for InlineVectorSourceParameters {
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for InlineVectorSource {
+    async fn _initialize(
+        self: Box<Self>,
+        _context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let params = self.params;
+
+        let mut data_type = None;
+        let mut features = Vec::with_capacity(params.features.len());
+
+        for feature in params.features {
+            let geometry = match feature.geometry {
+                Some(geometry) => TypedGeometry::try_from(geometry)?,
+                None => TypedGeometry::Data(NoGeometry),
+            };
+
+            let feature_data_type = geometry_data_type(&geometry);
+            ensure!(
+                *data_type.get_or_insert(feature_data_type) == feature_data_type,
+                error::InlineData {
+                    details: "all features must have the same geometry type",
+                }
+            );
+
+            for (column, column_type) in &params.columns {
+                let value_type = feature.properties.get(column).and_then(json_data_type);
+                ensure!(
+                    matches!(
+                        (value_type, column_type),
+                        (None, _)
+                            | (Some(FeatureDataType::Int), FeatureDataType::Float)
+                            | (_, FeatureDataType::Text)
+                    ) || value_type == Some(*column_type),
+                    error::InlineData {
+                        details: format!(
+                            "value of column `{column}` is not of type {column_type:?}"
+                        ),
+                    }
+                );
+            }
+
+            features.push(GeoJsonFeature {
+                geometry,
+                time: feature.time,
+                properties: feature.properties,
+            });
+        }
+
+        let features = GeoJsonFeatures {
+            data_type: data_type.unwrap_or(VectorDataType::Data),
+            columns: params.columns,
+            features,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: features.data_type,
+            spatial_reference: params.spatial_reference.into(),
+            columns: features
+                .columns
+                .iter()
+                .map(|(name, data_type)| {
+                    (
+                        name.clone(),
+                        VectorColumnInfo {
+                            data_type: *data_type,
+                            measurement: Measurement::Unitless,
+                        },
+                    )
+                })
+                .collect(),
+            time: None,
+            bbox: None,
+        };
+
+        Ok(InitializedInlineVectorSource {
+            result_descriptor,
+            features: Arc::new(features),
+        }
+        .boxed())
+    }
+
+    span_fn!(InlineVectorSource);
+}
+
+pub struct InitializedInlineVectorSource {
+    result_descriptor: VectorResultDescriptor,
+    features: Arc<GeoJsonFeatures>,
+}
+
+impl InitializedVectorOperator for InitializedInlineVectorSource {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(self.features.clone().query_processor())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Parameters for the [`InlineRasterSource`] operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineRasterSourceParameters {
+    pub data_type: RasterDataType,
+    pub spatial_reference: SpatialReference,
+    /// The upper left corner of the grid
+    pub origin: Coordinate2D,
+    pub resolution: SpatialResolution,
+    /// The pixel values row by row from top to bottom. `null` marks missing values.
+    pub values: Vec<Vec<Option<f64>>>,
+    #[serde(default)]
+    pub time: TimeInterval,
+    #[serde(default)]
+    pub measurement: Measurement,
+}
+
+/// A source for a small grid of pixel values that is stored in the workflow itself
+pub type InlineRasterSource = SourceOperator<InlineRasterSourceParameters>;
+
+impl OperatorName for InlineRasterSource {
+    const TYPE_NAME: &'static str = "InlineRasterSource";
+}
+
+impl OperatorData for InlineRasterSourceParameters {
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for InlineRasterSource {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        let width = params.values.first().map_or(0, Vec::len);
+        ensure!(
+            width > 0 && params.values.iter().all(|row| row.len() == width),
+            error::InlineData {
+                details: "the values must form a non-empty grid with rows of equal length",
+            }
+        );
+        ensure!(
+            params.resolution.x > 0. && params.resolution.y > 0.,
+            error::InlineData {
+                details: "the resolution must be positive",
+            }
+        );
+
+        let geo_transform =
+            GeoTransform::new(params.origin, params.resolution.x, -params.resolution.y);
+        let bbox = SpatialPartition2D::new(
+            params.origin,
+            geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d(
+                [params.values.len() as isize, width as isize].into(),
+            ),
+        )?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: params.data_type,
+            spatial_reference: params.spatial_reference.into(),
+            measurement: params.measurement,
+            time: Some(params.time),
+            bbox: Some(bbox),
+            resolution: Some(params.resolution),
+        };
+
+        Ok(InitializedInlineRasterSource {
+            result_descriptor,
+            grid: Arc::new(InlineGrid {
+                geo_transform,
+                bbox,
+                time: params.time,
+                values: params.values,
+            }),
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+
+    span_fn!(InlineRasterSource);
+}
+
+pub struct InitializedInlineRasterSource {
+    result_descriptor: RasterResultDescriptor,
+    grid: Arc<InlineGrid>,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedInlineRasterSource {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(call_generic_raster_processor!(
+            self.result_descriptor.data_type,
+            GeneratedRasterProcessor::new(self.grid.clone(), self.tiling_specification).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug)]
+struct InlineGrid {
+    geo_transform: GeoTransform,
+    bbox: SpatialPartition2D,
+    time: TimeInterval,
+    values: Vec<Vec<Option<f64>>>,
+}
+
+impl PixelGenerator for InlineGrid {
+    fn bbox(&self) -> Option<SpatialPartition2D> {
+        Some(self.bbox)
+    }
+
+    fn time(&self) -> TimeInterval {
+        self.time
+    }
+
+    fn value_at(&self, center: Coordinate2D, _index: GridIdx2D) -> Option<f64> {
+        let GridIdx([y, x]) = self.geo_transform.coordinate_to_grid_idx_2d(center);
+
+        let row = self.values.get(usize::try_from(y).ok()?)?;
+        *row.get(usize::try_from(x).ok()?)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use futures::StreamExt;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPolygonCollection};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, RasterQueryRectangle, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::raster::{GridOrEmpty, GridShape, MaskedGrid2D, RasterTile2D};
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn it_embeds_features() {
+        let operator: Box<dyn VectorOperator> = serde_json::from_value(json!({
+            "type": "InlineVectorSource",
+            "params": {
+                "spatialReference": "EPSG:4326",
+                "columns": {"label": "text"},
+                "features": [{
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+                    },
+                    "properties": {"label": "sketch"}
+                }]
+            }
+        }))
+        .unwrap();
+
+        let processor = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_polygon()
+            .unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (2., 2.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 1);
+        assert_eq!(
+            collections[0]
+                .data("label")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["sketch".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_mismatching_column_types() {
+        let operator = InlineVectorSource {
+            params: InlineVectorSourceParameters {
+                spatial_reference: SpatialReference::epsg_4326(),
+                columns: [("count".to_owned(), FeatureDataType::Int)].into(),
+                features: vec![InlineFeature {
+                    geometry: None,
+                    time: TimeInterval::default(),
+                    properties: json!({"count": "many"}).as_object().unwrap().clone(),
+                }],
+            },
+        }
+        .boxed();
+
+        assert!(operator
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_embeds_rasters() {
+        let operator = InlineRasterSource {
+            params: InlineRasterSourceParameters {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                origin: (0., 2.).into(),
+                resolution: SpatialResolution::one(),
+                values: vec![vec![Some(1.), Some(2.)], vec![None, Some(4.)]],
+                time: TimeInterval::default(),
+                measurement: Measurement::Unitless,
+            },
+        }
+        .boxed();
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        let processor = operator
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (4., 0.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(
+            tiles[0].grid_array,
+            GridOrEmpty::Grid(
+                MaskedGrid2D::new(
+                    geoengine_datatypes::raster::Grid::new(
+                        GridShape::new([2, 2]),
+                        vec![1, 2, 0, 4]
+                    )
+                    .unwrap(),
+                    geoengine_datatypes::raster::Grid::new(
+                        GridShape::new([2, 2]),
+                        vec![true, true, false, true]
+                    )
+                    .unwrap()
+                )
+                .unwrap()
+            )
+        );
+        assert!(tiles[1].is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 mod csv;
 mod gdal_source;
+mod geojson_source;
+mod inline_data;
 mod ogr_source;
+mod synthetic_raster;
+mod workflow_reference;
 
 pub use self::csv::{
     CsvGeometrySpecification, CsvSource, CsvSourceParameters, CsvSourceStream, CsvTimeSpecification,
@@ -12,8 +16,20 @@ pub use self::gdal_source::{
     GdalSourceError, GdalSourceParameters, GdalSourceProcessor, GdalSourceTimePlaceholder,
     TimeReference,
 };
+pub use self::geojson_source::{
+    GeoJsonSource, GeoJsonSourceData, GeoJsonSourceParameters, GeoJsonSourceTime,
+};
+pub use self::inline_data::{
+    InlineFeature, InlineRasterSource, InlineRasterSourceParameters, InlineVectorSource,
+    InlineVectorSourceParameters,
+};
 pub use self::ogr_source::{
     AttributeFilter, CsvHeader, FormatSpecifics, OgrSource, OgrSourceColumnSpec, OgrSourceDataset,
     OgrSourceDatasetTimeType, OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceParameters,
     OgrSourceProcessor, OgrSourceTimeFormat, UnixTimeStampType,
 };
+pub use self::synthetic_raster::{
+    ConstantRasterSource, ConstantRasterSourceParameters, GradientDirection,
+    SyntheticRasterPattern, SyntheticRasterSource, SyntheticRasterSourceParameters,
+};
+pub use self::workflow_reference::{WorkflowReference, WorkflowReferenceParameters};
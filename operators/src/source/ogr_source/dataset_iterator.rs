@@ -1,6 +1,7 @@
 // generated code of `_OgrDatasetIterator` needs this lint for the `peeked` field
 #![allow(clippy::option_option)]
 
+use super::encoding::shapefile_encoding;
 use super::{AttributeFilter, CsvHeader, FeaturesProvider, FormatSpecifics, OgrSourceDataset};
 use crate::error::{self};
 use crate::util::gdal::gdal_open_dataset_ex;
@@ -170,10 +171,16 @@ impl OgrDatasetIterator {
         if Self::is_csv(dataset_info) {
             Self::open_csv_dataset(dataset_info)
         } else {
+            // the shapefile driver converts the attributes to UTF-8
+            let encoding_option =
+                shapefile_encoding(dataset_info).map(|encoding| format!("ENCODING={encoding}"));
+            let open_options = encoding_option.as_deref().map(|option| [option]);
+
             gdal_open_dataset_ex(
                 &dataset_info.file_name,
                 DatasetOptions {
                     open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
+                    open_options: open_options.as_ref().map(<[&str; 1]>::as_slice),
                     ..Default::default()
                 },
             )
@@ -0,0 +1,168 @@
+use super::OgrSourceDataset;
+use gdal::errors::GdalError;
+use gdal::vector::Feature;
+use std::ffi::{CStr, CString, OsStr};
+use std::path::Path;
+
+const UTF8: &str = "UTF-8";
+
+/// The encoding that a shapefile driver should convert the attributes from.
+/// This is the configured encoding or the one of the shapefile's `.cpg` file.
+///
+/// Returns `None` if the dataset is not a shapefile or if the encoding is unknown.
+/// Then, the driver uses its default encoding.
+pub fn shapefile_encoding(dataset_info: &OgrSourceDataset) -> Option<String> {
+    if !is_shapefile(&dataset_info.file_name) {
+        return None;
+    }
+
+    if let Some(encoding) = &dataset_info.encoding {
+        return Some(encoding.clone());
+    }
+
+    let code_page = std::fs::read_to_string(dataset_info.file_name.with_extension("cpg")).ok()?;
+
+    encoding_of_code_page(code_page.trim())
+}
+
+/// The encoding that text attributes must be converted from after they were read, since the driver does not convert them.
+/// Returns `None` if the attributes are already UTF-8.
+pub fn attribute_encoding(dataset_info: &OgrSourceDataset) -> Option<&str> {
+    if is_shapefile(&dataset_info.file_name) {
+        return None;
+    }
+
+    dataset_info
+        .encoding
+        .as_deref()
+        .filter(|encoding| !is_utf8(encoding))
+}
+
+fn is_shapefile(file_name: &Path) -> bool {
+    file_name
+        .extension()
+        .and_then(OsStr::to_str)
+        .map_or(false, |extension| {
+            extension.eq_ignore_ascii_case("shp") || extension.eq_ignore_ascii_case("dbf")
+        })
+}
+
+fn is_utf8(encoding: &str) -> bool {
+    encoding.eq_ignore_ascii_case("UTF-8") || encoding.eq_ignore_ascii_case("UTF8")
+}
+
+/// Maps the content of a `.cpg` file to an encoding name, cf. the code pages that GDAL understands
+fn encoding_of_code_page(code_page: &str) -> Option<String> {
+    if code_page.is_empty() {
+        return None;
+    }
+
+    if is_utf8(code_page) {
+        return Some(UTF8.to_string());
+    }
+
+    // e.g. `1252`
+    if code_page.chars().all(|c| c.is_ascii_digit()) {
+        return Some(format!("CP{code_page}"));
+    }
+
+    // e.g. `88591` or `8859-1`
+    let normalized = code_page.replace(['-', ' ', '_'], "").to_ascii_uppercase();
+    let iso_part = normalized
+        .strip_prefix("ISO")
+        .unwrap_or(&normalized)
+        .strip_prefix("8859");
+    if let Some(part) = iso_part {
+        if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("ISO-8859-{part}"));
+        }
+    }
+
+    Some(code_page.to_string())
+}
+
+/// Reads a text field as raw bytes and converts it from `encoding` to UTF-8
+pub fn recoded_text_field(
+    feature: &Feature,
+    field_name: &str,
+    encoding: &str,
+) -> Result<Option<String>, GdalError> {
+    let c_field_name = CString::new(field_name)?;
+
+    let raw_text = unsafe {
+        let field_index = gdal_sys::OGR_F_GetFieldIndex(feature.c_feature(), c_field_name.as_ptr());
+        if field_index < 0 {
+            return Err(GdalError::InvalidFieldName {
+                field_name: field_name.to_string(),
+                method_name: "OGR_F_GetFieldIndex",
+            });
+        }
+
+        if gdal_sys::OGR_F_IsFieldSetAndNotNull(feature.c_feature(), field_index) == 0 {
+            return Ok(None);
+        }
+
+        CStr::from_ptr(gdal_sys::OGR_F_GetFieldAsString(
+            feature.c_feature(),
+            field_index,
+        ))
+    };
+
+    Ok(Some(recode(raw_text, encoding)?))
+}
+
+/// Converts `text` from `encoding` to UTF-8.
+/// Invalid characters are replaced, which GDAL reports as a warning.
+fn recode(text: &CStr, encoding: &str) -> Result<String, GdalError> {
+    let c_encoding = CString::new(encoding)?;
+    let c_utf8 = CString::new(UTF8)?;
+
+    unsafe {
+        let recoded = gdal_sys::CPLRecode(text.as_ptr(), c_encoding.as_ptr(), c_utf8.as_ptr());
+
+        if recoded.is_null() {
+            return Ok(text.to_string_lossy().into_owned());
+        }
+
+        let result = CStr::from_ptr(recoded).to_string_lossy().into_owned();
+        gdal_sys::VSIFree(recoded.cast());
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_maps_code_pages() {
+        assert_eq!(encoding_of_code_page("UTF-8"), Some("UTF-8".to_string()));
+        assert_eq!(encoding_of_code_page("utf8"), Some("UTF-8".to_string()));
+        assert_eq!(encoding_of_code_page("1252"), Some("CP1252".to_string()));
+        assert_eq!(
+            encoding_of_code_page("88591"),
+            Some("ISO-8859-1".to_string())
+        );
+        assert_eq!(
+            encoding_of_code_page("ISO 8859-15"),
+            Some("ISO-8859-15".to_string())
+        );
+        assert_eq!(encoding_of_code_page("KOI8-R"), Some("KOI8-R".to_string()));
+        assert_eq!(encoding_of_code_page(""), None);
+    }
+
+    #[test]
+    fn it_recodes_latin1() {
+        let latin1 = CString::new(vec![b'M', 0xFC, b'n', b's', b't', b'e', b'r']).unwrap();
+
+        assert_eq!(recode(&latin1, "ISO-8859-1").unwrap(), "Münster");
+    }
+
+    #[test]
+    fn it_detects_shapefiles() {
+        assert!(is_shapefile(Path::new("foo/bar.shp")));
+        assert!(is_shapefile(Path::new("foo/bar.DBF")));
+        assert!(!is_shapefile(Path::new("foo/bar.gpkg")));
+    }
+}
@@ -1,4 +1,5 @@
 mod dataset_iterator;
+mod encoding;
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
@@ -20,6 +21,7 @@ use gdal::vector::{Feature, FieldValue, Layer, LayerAccess, LayerCaps, OGRwkbGeo
 use log::debug;
 use pin_project::pin_project;
 use postgres_protocol::escape::{escape_identifier, escape_literal};
+use rayon::ThreadPool;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use tokio::sync::Mutex;
@@ -37,23 +39,29 @@ use geoengine_datatypes::primitives::{
 };
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
-use crate::engine::{CreateSpan, OperatorData, OperatorName, QueryProcessor};
+use crate::engine::{CreateSpan, OperatorData, OperatorName, QueryMessages, QueryProcessor};
 use crate::error::Error;
 use crate::util::input::StringOrNumberRange;
 use crate::util::Result;
 use crate::{
+    adapters::{FeatureCollectionChunkMerger, SchemaMerging},
     engine::{
-        InitializedVectorOperator, MetaData, QueryContext, SourceOperator,
-        TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+        InitializedVectorOperator, MetaData, PathSandbox, QueryContext, SandboxedLoadingInfo,
+        SourceOperator, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+        VectorResultDescriptor,
     },
     error,
 };
 use async_trait::async_trait;
 use gdal::errors::GdalError;
 use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::operations::{
+    repair_multi_line_string, repair_multi_point, repair_multi_polygon, RepairStatistics, Repaired,
+};
 use std::convert::{TryFrom, TryInto};
 
 use self::dataset_iterator::OgrDatasetIterator;
+use self::encoding::{attribute_encoding, recoded_text_field};
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -61,6 +69,10 @@ pub struct OgrSourceParameters {
     pub data: DataId,
     pub attribute_projection: Option<Vec<String>>,
     pub attribute_filters: Option<Vec<AttributeFilter>>,
+    /// How chunks whose columns differ are merged into the output collections.
+    /// If set, the source's output is merged into chunks of the query's chunk size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_merging: Option<SchemaMerging>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -92,6 +104,8 @@ impl OperatorName for OgrSource {
 ///    (result: empty collection), but has better performance for wfs requests (optional, false if not provided)
 ///  - `on_error`: specify the type of error handling
 ///  - `provenance`: specify the provenance of a file
+///  - `encoding`: the character encoding of text attributes, e.g., `ISO-8859-1`, that are converted to UTF-8.
+///    Shapefiles use the encoding of their `.cpg` file if it is not provided, all other files UTF-8.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OgrSourceDataset {
@@ -109,6 +123,8 @@ pub struct OgrSourceDataset {
     pub on_error: OgrSourceErrorSpec,
     pub sql_query: Option<String>,
     pub attribute_query: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
 }
 
 impl OgrSourceDataset {
@@ -119,6 +135,13 @@ impl OgrSourceDataset {
     }
 }
 
+impl SandboxedLoadingInfo for OgrSourceDataset {
+    fn sandboxed(self, sandbox: &PathSandbox) -> Result<Self> {
+        sandbox.ensure_is_allowed(&self.file_name)?;
+        Ok(self)
+    }
+}
+
 /// The type of the time attribute(s):
 ///  - "none": no time information is mapped
 ///  - "start": only start information is mapped. duration has to specified in the duration attribute
@@ -297,18 +320,21 @@ impl CsvHeader {
 /// Specify the type of error handling
 ///  - "ignore": invalid column values are kept as null, missing/invalid geom features are skipped
 ///  - "abort": invalid column values and missing/invalid geoms result in abort
+///  - "repair": like "ignore", but invalid geoms, e.g., self-intersecting polygons, are repaired and
+///    only features whose geoms cannot be repaired are skipped
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OgrSourceErrorSpec {
     Ignore,
     Abort,
+    Repair,
 }
 
 impl OgrSourceErrorSpec {
     /// handle the given error depending on the spec
     fn on_error<T>(self, error: error::Error) -> Result<Option<T>> {
         match self {
-            OgrSourceErrorSpec::Ignore => Ok(None),
+            OgrSourceErrorSpec::Ignore | OgrSourceErrorSpec::Repair => Ok(None),
             OgrSourceErrorSpec::Abort => Err(error),
         }
     }
@@ -339,11 +365,13 @@ pub struct OgrSourceState {
     dataset_information:
         Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
     attribute_filters: Vec<AttributeFilter>,
+    schema_merging: Option<SchemaMerging>,
 }
 
 pub struct InitializedOgrSource {
     result_descriptor: VectorResultDescriptor,
     state: OgrSourceState,
+    io_thread_pool: Arc<ThreadPool>,
 }
 
 #[typetag::serde]
@@ -357,7 +385,9 @@ impl VectorOperator for OgrSource {
 
         let info: Box<
             dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
-        > = context.meta_data(&self.params.data).await?;
+        > = context
+            .path_sandbox()
+            .sandbox_meta_data(context.meta_data(&self.params.data).await?);
 
         let result_descriptor = info.result_descriptor().await?;
 
@@ -396,7 +426,9 @@ impl VectorOperator for OgrSource {
             state: OgrSourceState {
                 dataset_information: info,
                 attribute_filters: self.params.attribute_filters.unwrap_or_default(),
+                schema_merging: self.params.schema_merging,
             },
+            io_thread_pool: context.io_thread_pool().clone(),
         };
 
         Ok(initialized_source.boxed())
@@ -432,28 +464,36 @@ impl InitializedVectorOperator for InitializedOgrSource {
                 OgrSourceProcessor::new(
                     self.state.dataset_information.clone(),
                     self.state.attribute_filters.clone(),
+                    self.io_thread_pool.clone(),
                 )
+                .with_schema_merging(self.state.schema_merging)
                 .boxed(),
             ),
             VectorDataType::MultiPoint => TypedVectorQueryProcessor::MultiPoint(
                 OgrSourceProcessor::new(
                     self.state.dataset_information.clone(),
                     self.state.attribute_filters.clone(),
+                    self.io_thread_pool.clone(),
                 )
+                .with_schema_merging(self.state.schema_merging)
                 .boxed(),
             ),
             VectorDataType::MultiLineString => TypedVectorQueryProcessor::MultiLineString(
                 OgrSourceProcessor::new(
                     self.state.dataset_information.clone(),
                     self.state.attribute_filters.clone(),
+                    self.io_thread_pool.clone(),
                 )
+                .with_schema_merging(self.state.schema_merging)
                 .boxed(),
             ),
             VectorDataType::MultiPolygon => TypedVectorQueryProcessor::MultiPolygon(
                 OgrSourceProcessor::new(
                     self.state.dataset_information.clone(),
                     self.state.attribute_filters.clone(),
+                    self.io_thread_pool.clone(),
                 )
+                .with_schema_merging(self.state.schema_merging)
                 .boxed(),
             ),
         })
@@ -471,6 +511,8 @@ where
     dataset_information:
         Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
     attribute_filters: Vec<AttributeFilter>,
+    schema_merging: Option<SchemaMerging>,
+    io_thread_pool: Arc<ThreadPool>,
     _collection_type: PhantomData<FeatureCollection<G>>,
 }
 
@@ -483,13 +525,23 @@ where
             dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
         >,
         attribute_filters: Vec<AttributeFilter>,
+        io_thread_pool: Arc<ThreadPool>,
     ) -> Self {
         Self {
             dataset_information,
             attribute_filters,
+            schema_merging: None,
+            io_thread_pool,
             _collection_type: Default::default(),
         }
     }
+
+    /// Merges the output chunks with the given `SchemaMerging` instead of emitting them as they are read
+    #[must_use]
+    pub fn with_schema_merging(mut self, schema_merging: Option<SchemaMerging>) -> Self {
+        self.schema_merging = schema_merging;
+        self
+    }
 }
 
 #[async_trait]
@@ -506,14 +558,24 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
-        Ok(OgrSourceStream::new(
+        let chunk_byte_size: usize = ctx.chunk_byte_size().into();
+
+        let stream = OgrSourceStream::new(
             self.dataset_information.loading_info(query).await?,
             query,
-            ctx.chunk_byte_size().into(),
+            chunk_byte_size,
             self.attribute_filters.clone(),
+            ctx.messages().clone(),
+            self.io_thread_pool.clone(),
         )
-        .await?
-        .boxed())
+        .await?;
+
+        Ok(match self.schema_merging {
+            Some(schema_merging) => FeatureCollectionChunkMerger::new(stream, chunk_byte_size)
+                .with_schema_merging(schema_merging)
+                .boxed(),
+            None => stream.boxed(),
+        })
     }
 }
 
@@ -533,6 +595,8 @@ where
         Arc<Box<dyn Fn(FieldValue) -> Result<TimeInstance> + Send + Sync + 'static>>,
     query_rectangle: VectorQueryRectangle,
     chunk_byte_size: usize,
+    messages: QueryMessages,
+    io_thread_pool: Arc<ThreadPool>,
     #[pin]
     future: Option<BoxFuture<'static, Result<FeatureCollection<G>>>>,
     has_ended: bool,
@@ -731,8 +795,10 @@ where
         query_rectangle: VectorQueryRectangle,
         chunk_byte_size: usize,
         attribute_filters: Vec<AttributeFilter>,
+        messages: QueryMessages,
+        io_thread_pool: Arc<ThreadPool>,
     ) -> Result<Self> {
-        crate::util::spawn_blocking(move || {
+        crate::util::spawn_blocking_with_thread_pool(io_thread_pool.clone(), move || {
             let dataset_iterator =
                 OgrDatasetIterator::new(&dataset_information, &query_rectangle, attribute_filters)?;
 
@@ -753,6 +819,8 @@ where
                 time_extractor: Arc::new(time_extractor),
                 time_attribute_parser: Arc::new(time_attribute_parser),
                 chunk_byte_size,
+                messages,
+                io_thread_pool,
                 future: None,
                 has_ended: false,
                 prestine: true,
@@ -771,10 +839,14 @@ where
         time_extractor: Arc<TimeExtractorType>,
         time_attribute_parser: Arc<Box<dyn Fn(FieldValue) -> Result<TimeInstance> + Send + Sync>>,
         chunk_byte_size: usize,
+        messages: QueryMessages,
+        io_thread_pool: Arc<ThreadPool>,
     ) -> Result<FeatureCollection<G>> {
-        crate::util::spawn_blocking(move || {
+        crate::util::spawn_blocking_with_thread_pool(io_thread_pool, move || {
             let mut dataset_iterator = dataset_iterator.blocking_lock();
 
+            let mut statistics = RepairStatistics::default();
+            let mut replaced_texts = 0;
             let batch_result = Self::compute_batch(
                 &mut dataset_iterator,
                 feature_collection_builder,
@@ -784,8 +856,29 @@ where
                 time_extractor.as_ref(),
                 time_attribute_parser.as_ref(),
                 chunk_byte_size,
+                &mut statistics,
+                &mut replaced_texts,
             );
 
+            if statistics.repaired > 0 {
+                messages.warn(format!(
+                    "{} feature(s) had invalid geometries that were repaired",
+                    statistics.repaired
+                ));
+            }
+            if statistics.dropped > 0 {
+                messages.warn(format!(
+                    "{} feature(s) were dropped because they could not be read",
+                    statistics.dropped
+                ));
+            }
+            if replaced_texts > 0 {
+                messages.warn(format!(
+                    "{} text attribute(s) contained invalid characters that were replaced",
+                    replaced_texts
+                ));
+            }
+
             let batch_result = if let Some(rename) = dataset_information
                 .columns
                 .as_ref()
@@ -1008,8 +1101,11 @@ where
         time_extractor: &dyn Fn(&Feature) -> Result<TimeInterval>,
         time_attribute_parser: &dyn Fn(FieldValue) -> Result<TimeInstance>,
         chunk_byte_size: usize,
+        statistics: &mut RepairStatistics,
+        replaced_texts: &mut usize,
     ) -> Result<FeatureCollection<G>> {
         let was_spatial_filtered_by_ogr = feature_iterator.was_spatial_filtered_by_ogr();
+        let attribute_encoding = attribute_encoding(dataset_information);
 
         let mut builder = feature_collection_builder.finish_header();
 
@@ -1030,9 +1126,15 @@ where
                 &feature,
                 dataset_information.force_ogr_time_filter,
                 was_spatial_filtered_by_ogr,
+                &mut statistics.repaired,
+                attribute_encoding,
+                replaced_texts,
             ) {
                 match dataset_information.on_error {
-                    OgrSourceErrorSpec::Ignore => continue,
+                    OgrSourceErrorSpec::Ignore | OgrSourceErrorSpec::Repair => {
+                        statistics.dropped += 1;
+                        continue;
+                    }
                     OgrSourceErrorSpec::Abort => return Err(error),
                 }
             }
@@ -1162,6 +1264,9 @@ where
         feature: &Feature,
         was_time_filtered_by_ogr: bool,
         was_spatial_filtered_by_ogr: bool,
+        repaired_features: &mut usize,
+        attribute_encoding: Option<&str>,
+        replaced_texts: &mut usize,
     ) -> Result<()> {
         let time_interval = time_extractor(feature)?;
 
@@ -1170,9 +1275,22 @@ where
             return Ok(());
         }
 
-        let geometry: G = match <G as TryFromOgrGeometry>::try_from(
-            feature.geometry_by_index(0).map_err(Into::into),
-        ) {
+        let ogr_geometry = feature.geometry_by_index(0).map_err(Into::into);
+        let geometry = if error_spec == OgrSourceErrorSpec::Repair {
+            match <G as TryFromOgrGeometry>::try_from_repaired(ogr_geometry) {
+                Ok(Repaired::Valid(g)) => Ok(g),
+                Ok(Repaired::Repaired(g)) => {
+                    *repaired_features += 1;
+                    Ok(g)
+                }
+                Ok(Repaired::Dropped) => Err(Error::UnrepairableGeometry),
+                Err(e) => Err(e),
+            }
+        } else {
+            <G as TryFromOgrGeometry>::try_from(ogr_geometry)
+        };
+
+        let geometry: G = match geometry {
             Ok(g) => g,
             Err(Error::Gdal {
                 source: GdalError::InvalidFieldIndex { method_name, index },
@@ -1198,7 +1316,20 @@ where
         builder.push_time_interval(time_interval);
 
         for (column, data_type) in data_types {
-            let field = feature.field(column);
+            let field = match (attribute_encoding, data_type) {
+                (Some(encoding), FeatureDataType::Text) => {
+                    recoded_text_field(feature, column, encoding)
+                        .map(|text| text.map(FieldValue::StringValue))
+                }
+                _ => feature.field(column),
+            };
+
+            if let Ok(Some(FieldValue::StringValue(text))) = &field {
+                if text.contains(char::REPLACEMENT_CHARACTER) {
+                    *replaced_texts += 1;
+                }
+            }
+
             let value =
                 Self::convert_field_value(*data_type, field, time_attribute_parser, error_spec)?;
             builder.push_data(column, value)?;
@@ -1268,6 +1399,8 @@ where
                 this.time_extractor.clone(),
                 this.time_attribute_parser.clone(),
                 *this.chunk_byte_size,
+                this.messages.clone(),
+                this.io_thread_pool.clone(),
             );
 
             // …and store it
@@ -1290,95 +1423,102 @@ where
 // use `TryFrom` in `datatypes` if this is used on more than one occasion
 pub trait TryFromOgrGeometry: Sized {
     fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self>;
+
+    /// Converts the geometry and repairs it if it is invalid, e.g., if a polygon ring is unclosed
+    fn try_from_repaired(geometry: Result<&gdal::vector::Geometry>) -> Result<Repaired<Self>>;
+}
+
+fn ogr_coordinates(geometry: &gdal::vector::Geometry) -> Vec<Coordinate2D> {
+    geometry
+        .get_point_vec()
+        .into_iter()
+        .map(|(x, y, _z)| Coordinate2D::new(x, y))
+        .collect()
+}
+
+fn ogr_point_coordinates(geometry: &gdal::vector::Geometry) -> Result<Vec<Coordinate2D>> {
+    fn coordinate(geometry: &gdal::vector::Geometry) -> Coordinate2D {
+        let (x, y, _) = geometry.get_point(0);
+        Coordinate2D::new(x, y)
+    }
+
+    match geometry.geometry_type() {
+        OGRwkbGeometryType::wkbPoint => Ok(vec![coordinate(geometry)]),
+        OGRwkbGeometryType::wkbMultiPoint => Ok((0..geometry.geometry_count())
+            .map(|i| coordinate(&unsafe { geometry.get_unowned_geometry(i) }))
+            .collect()),
+        _ => Err(Error::InvalidType {
+            expected: format!("{:?}", VectorDataType::MultiPoint),
+            found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+        }),
+    }
+}
+
+fn ogr_line_coordinates(geometry: &gdal::vector::Geometry) -> Result<Vec<Vec<Coordinate2D>>> {
+    match geometry.geometry_type() {
+        OGRwkbGeometryType::wkbLineString => Ok(vec![ogr_coordinates(geometry)]),
+        OGRwkbGeometryType::wkbMultiLineString => Ok((0..geometry.geometry_count())
+            .map(|i| ogr_coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
+            .collect()),
+        _ => Err(Error::InvalidType {
+            expected: format!("{:?}", VectorDataType::MultiPoint),
+            found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+        }),
+    }
+}
+
+fn ogr_polygon_coordinates(
+    geometry: &gdal::vector::Geometry,
+) -> Result<Vec<Vec<Vec<Coordinate2D>>>> {
+    fn rings(geometry: &gdal::vector::Geometry) -> Vec<Vec<Coordinate2D>> {
+        let ring_count = geometry.geometry_count();
+        (0..ring_count)
+            .map(|i| ogr_coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
+            .collect()
+    }
+
+    match geometry.geometry_type() {
+        OGRwkbGeometryType::wkbPolygon => Ok(vec![rings(geometry)]),
+        OGRwkbGeometryType::wkbMultiPolygon => Ok((0..geometry.geometry_count())
+            .map(|i| rings(&unsafe { geometry.get_unowned_geometry(i) }))
+            .collect()),
+        _ => Err(Error::InvalidType {
+            expected: format!("{:?}", VectorDataType::MultiPoint),
+            found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
+        }),
+    }
 }
 
 /// Implement direct conversions from OGR geometries to our geometries
 /// Unfortunately, we cannot convert to `geo`'s geometries since the implementation panics on unknown types.
 impl TryFromOgrGeometry for MultiPoint {
     fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinate(geometry: &gdal::vector::Geometry) -> Coordinate2D {
-            let (x, y, _) = geometry.get_point(0);
-            Coordinate2D::new(x, y)
-        }
-
-        let geometry = geometry?;
-
-        match geometry.geometry_type() {
-            OGRwkbGeometryType::wkbPoint => Ok(MultiPoint::new(vec![coordinate(geometry)])?),
-            OGRwkbGeometryType::wkbMultiPoint => {
-                let coordinates = (0..geometry.geometry_count())
-                    .map(|i| coordinate(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect();
+        Ok(MultiPoint::new(ogr_point_coordinates(geometry?)?)?)
+    }
 
-                Ok(MultiPoint::new(coordinates)?)
-            }
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+    fn try_from_repaired(geometry: Result<&gdal::vector::Geometry>) -> Result<Repaired<Self>> {
+        Ok(repair_multi_point(ogr_point_coordinates(geometry?)?))
     }
 }
 
 impl TryFromOgrGeometry for MultiLineString {
     fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinates(geometry: &gdal::vector::Geometry) -> Vec<Coordinate2D> {
-            geometry
-                .get_point_vec()
-                .into_iter()
-                .map(|(x, y, _z)| Coordinate2D::new(x, y))
-                .collect()
-        }
-
-        let geometry = geometry?;
+        Ok(MultiLineString::new(ogr_line_coordinates(geometry?)?)?)
+    }
 
-        match geometry.geometry_type() {
-            OGRwkbGeometryType::wkbLineString => {
-                Ok(MultiLineString::new(vec![coordinates(geometry)])?)
-            }
-            OGRwkbGeometryType::wkbMultiLineString => Ok(MultiLineString::new(
-                (0..geometry.geometry_count())
-                    .map(|i| coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect(),
-            )?),
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+    fn try_from_repaired(geometry: Result<&gdal::vector::Geometry>) -> Result<Repaired<Self>> {
+        Ok(repair_multi_line_string(ogr_line_coordinates(geometry?)?))
     }
 }
 
 impl TryFromOgrGeometry for MultiPolygon {
     fn try_from(geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
-        fn coordinates(geometry: &gdal::vector::Geometry) -> Vec<Coordinate2D> {
-            geometry
-                .get_point_vec()
-                .into_iter()
-                .map(|(x, y, _z)| Coordinate2D::new(x, y))
-                .collect()
-        }
-        fn rings(geometry: &gdal::vector::Geometry) -> Vec<Vec<Coordinate2D>> {
-            let ring_count = geometry.geometry_count();
-            (0..ring_count)
-                .map(|i| coordinates(&unsafe { geometry.get_unowned_geometry(i) }))
-                .collect()
-        }
-
-        let geometry = geometry?;
+        Ok(MultiPolygon::new(ogr_polygon_coordinates(geometry?)?)?)
+    }
 
-        match geometry.geometry_type() {
-            OGRwkbGeometryType::wkbPolygon => Ok(MultiPolygon::new(vec![rings(geometry)])?),
-            OGRwkbGeometryType::wkbMultiPolygon => Ok(MultiPolygon::new(
-                (0..geometry.geometry_count())
-                    .map(|i| rings(&unsafe { geometry.get_unowned_geometry(i) }))
-                    .collect(),
-            )?),
-            _ => Err(Error::InvalidType {
-                expected: format!("{:?}", VectorDataType::MultiPoint),
-                found: format!("{:?}", OgrSource::ogr_geometry_type(geometry)),
-            }),
-        }
+    /// Repairs the raw rings since unclosed rings cannot be represented by a `MultiPolygon`
+    fn try_from_repaired(geometry: Result<&gdal::vector::Geometry>) -> Result<Repaired<Self>> {
+        Ok(repair_multi_polygon(ogr_polygon_coordinates(geometry?)?))
     }
 }
 
@@ -1386,6 +1526,10 @@ impl TryFromOgrGeometry for NoGeometry {
     fn try_from(_geometry: Result<&gdal::vector::Geometry>) -> Result<Self> {
         Ok(NoGeometry)
     }
+
+    fn try_from_repaired(_geometry: Result<&gdal::vector::Geometry>) -> Result<Repaired<Self>> {
+        Ok(Repaired::Valid(NoGeometry))
+    }
 }
 
 pub trait FeatureCollectionBuilderGeometryHandler<G>
@@ -1432,10 +1576,12 @@ mod tests {
     use super::*;
 
     use crate::engine::{
-        ChunkByteSize, MockExecutionContext, MockQueryContext, StaticMetaData, VectorColumnInfo,
+        ChunkByteSize, MockExecutionContext, MockQueryContext, QueryMessage, QueryMetadata,
+        StaticMetaData, VectorColumnInfo,
     };
     use crate::source::ogr_source::FormatSpecifics::Csv;
     use crate::test_data;
+    use crate::util::create_rayon_thread_pool;
     use futures::{StreamExt, TryStreamExt};
     use geoengine_datatypes::collections::{
         DataCollection, FeatureCollectionInfos, GeometryCollection, MultiPointCollection,
@@ -1488,6 +1634,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let serialized_spec = serde_json::to_value(&spec).unwrap();
@@ -1605,6 +1752,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -1619,7 +1767,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -1656,6 +1808,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -1670,7 +1823,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -1701,6 +1858,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
         let info = StaticMetaData {
             loading_info: dataset_information,
@@ -1714,7 +1872,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -1750,6 +1912,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
         let info = StaticMetaData {
             loading_info: dataset_information,
@@ -1763,9 +1926,14 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
-        let context = MockQueryContext::new(ChunkByteSize::MAX);
+        let mut context = MockQueryContext::new(ChunkByteSize::MAX);
+        let mut messages = context.subscribe_messages();
         let query = query_processor
             .query(
                 VectorQueryRectangle {
@@ -1791,6 +1959,111 @@ mod tests {
             )?
         );
 
+        assert_eq!(
+            messages.received(),
+            vec![QueryMessage::Metadata(QueryMetadata::Warning {
+                message: "1 feature(s) were dropped because they could not be read".to_string()
+            })]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn on_error_repair() -> Result<()> {
+        let dataset_information = OgrSourceDataset {
+            file_name: test_data!("vector/data/invalid_polygons.json").into(),
+            layer_name: "invalid_polygons".to_string(),
+            data_type: None,
+            time: OgrSourceDatasetTimeType::None,
+            default_geometry: None,
+            columns: None,
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Repair,
+            sql_query: None,
+            attribute_query: None,
+            encoding: None,
+        };
+        let info = StaticMetaData {
+            loading_info: dataset_information,
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPolygon,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: Default::default(),
+                time: None,
+                bbox: None,
+            },
+            phantom: Default::default(),
+        };
+
+        let query_processor = OgrSourceProcessor::<MultiPolygon>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
+
+        let mut context = MockQueryContext::new(ChunkByteSize::MAX);
+        let mut messages = context.subscribe_messages();
+        let query = query_processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (5., 5.).into())?,
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::new(1., 1.)?,
+                },
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<MultiPolygonCollection> = query.try_collect().await?;
+
+        assert_eq!(result.len(), 1);
+
+        assert_eq!(
+            result[0],
+            MultiPolygonCollection::from_data(
+                vec![
+                    MultiPolygon::new(vec![vec![vec![
+                        (0.0, 0.0).into(),
+                        (1.0, 0.0).into(),
+                        (1.0, 1.0).into(),
+                        (0.0, 1.0).into(),
+                        (0.0, 0.0).into(),
+                    ]]])?,
+                    MultiPolygon::new(vec![
+                        vec![vec![
+                            (3.0, 1.0).into(),
+                            (4.0, 2.0).into(),
+                            (4.0, 0.0).into(),
+                            (3.0, 1.0).into(),
+                        ]],
+                        vec![vec![
+                            (2.0, 0.0).into(),
+                            (3.0, 1.0).into(),
+                            (2.0, 2.0).into(),
+                            (2.0, 0.0).into(),
+                        ]],
+                    ])?,
+                ],
+                vec![Default::default(); 2],
+                HashMap::new(),
+            )?
+        );
+
+        assert_eq!(
+            messages.received(),
+            vec![
+                QueryMessage::Metadata(QueryMetadata::Warning {
+                    message: "1 feature(s) had invalid geometries that were repaired".to_string()
+                }),
+                QueryMessage::Metadata(QueryMetadata::Warning {
+                    message: "1 feature(s) were dropped because they could not be read".to_string()
+                }),
+            ]
+        );
+
         Ok(())
     }
 
@@ -1813,6 +2086,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1830,6 +2104,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -1909,6 +2184,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1926,6 +2202,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -2008,6 +2285,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -2025,6 +2303,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -2119,6 +2398,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -2175,6 +2455,7 @@ mod tests {
                 data: id.clone(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -2329,6 +2610,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -2346,6 +2628,7 @@ mod tests {
                 data: id.clone(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -3507,6 +3790,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -3546,7 +3830,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<NoGeometry>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<NoGeometry>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -3625,6 +3913,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -3664,7 +3953,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -3734,6 +4027,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3751,6 +4045,7 @@ mod tests {
                 data: id.clone(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -3963,6 +4258,85 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn it_merges_chunks_with_schema_merging() -> Result<()> {
+        let id: DataId = DatasetId::new().into();
+        let mut exe_ctx = MockExecutionContext::test_default();
+        exe_ctx.add_meta_data::<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>(
+            id.clone(),
+            Box::new(StaticMetaData {
+                loading_info: OgrSourceDataset {
+                    file_name: test_data!("vector/data/ne_10m_ports/ne_10m_ports.shp").into(),
+                    layer_name: "ne_10m_ports".to_string(),
+                    data_type: Some(VectorDataType::MultiPoint),
+                    time: OgrSourceDatasetTimeType::None,
+                    default_geometry: None,
+                    columns: None,
+                    force_ogr_time_filter: false,
+                    force_ogr_spatial_filter: false,
+                    on_error: OgrSourceErrorSpec::Ignore,
+                    sql_query: None,
+                    attribute_query: None,
+                    encoding: None,
+                },
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::MultiPoint,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    columns: Default::default(),
+                    time: None,
+                    bbox: None,
+                },
+                phantom: Default::default(),
+            }),
+        );
+
+        let params: OgrSourceParameters = serde_json::from_value(json!({
+            "data": id,
+            "attributeProjection": null,
+            "attributeFilters": null,
+            "schemaMerging": "union",
+        }))
+        .unwrap();
+        assert_eq!(params.schema_merging, Some(SchemaMerging::Union));
+
+        let query_processor = OgrSource { params }
+            .boxed()
+            .initialize(&exe_ctx)
+            .await?
+            .query_processor()?
+            .multi_point()
+            .unwrap();
+
+        let context = MockQueryContext::new((1_000).into());
+        let query = query_processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new(
+                        (-9.45, 47.64).into(),
+                        (10.00, 63.43).into(),
+                    )?,
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::new(1., 1.)?,
+                },
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<MultiPointCollection> = query.try_collect().await?;
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(
+            result
+                .iter()
+                .map(FeatureCollectionInfos::len)
+                .sum::<usize>(),
+            99
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn empty() {
         let id: DataId = DatasetId::new().into();
@@ -3982,6 +4356,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -3999,6 +4374,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4069,6 +4445,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPolygon,
@@ -4086,6 +4463,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4168,6 +4546,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4203,6 +4582,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4300,6 +4680,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4326,6 +4707,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4421,6 +4803,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4447,6 +4830,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4542,6 +4926,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4568,6 +4953,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4659,6 +5045,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4685,6 +5072,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4780,6 +5168,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4815,6 +5204,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -4905,6 +5295,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4931,6 +5322,7 @@ mod tests {
                 data: id,
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed()
@@ -5019,6 +5411,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5058,7 +5451,11 @@ mod tests {
             phantom: Default::default(),
         };
 
-        let query_processor = OgrSourceProcessor::<NoGeometry>::new(Box::new(info), vec![]);
+        let query_processor = OgrSourceProcessor::<NoGeometry>::new(
+            Box::new(info),
+            vec![],
+            create_rayon_thread_pool(0),
+        );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
         let query = query_processor
@@ -5135,6 +5532,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5183,6 +5581,7 @@ mod tests {
                 )],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5251,6 +5650,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5297,6 +5697,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Int(2..=2)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5365,6 +5766,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5411,6 +5813,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Float(5.4..=5.4)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5483,6 +5886,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5529,6 +5933,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Int(2..=2)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5597,6 +6002,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5646,6 +6052,7 @@ mod tests {
                 ],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5723,6 +6130,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5778,6 +6186,7 @@ mod tests {
                     keep_nulls: false,
                 },
             ],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5846,6 +6255,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: Some("\"c\" = 'foo'".to_string()),
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5892,6 +6302,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Int(1..=1)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5959,6 +6370,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -5998,6 +6410,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Float(75.0..=75.0)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -6048,6 +6461,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -6090,6 +6504,7 @@ mod tests {
                 ],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -6140,6 +6555,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: Some("\"name\" = 'Bangkok'".to_string()),
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -6179,6 +6595,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Float(50.0..=50.0)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
@@ -6229,6 +6646,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         };
 
         let info = StaticMetaData {
@@ -6268,6 +6686,7 @@ mod tests {
                 ranges: vec![StringOrNumberRange::Float(75.0..=76.0)],
                 keep_nulls: false,
             }],
+            create_rayon_thread_pool(0),
         );
 
         let context = MockQueryContext::new(ChunkByteSize::MAX);
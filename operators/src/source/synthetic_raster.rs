@@ -0,0 +1,516 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::dataset::DataId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Coordinate2D, Measurement, RasterQueryRectangle, SpatialPartition2D,
+    SpatialPartitioned, SpatialResolution, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, FromIndexFn, FromPrimitive, GridIdx, GridIdx2D, GridOrEmpty, Pixel, RasterDataType,
+    RasterTile2D, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tracing::{span, Level};
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, OperatorData, OperatorName,
+    QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    SourceOperator, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+
+/// Computes pixel values from the location of the pixels
+pub(super) trait PixelGenerator: Send + Sync {
+    /// The area that contains data. Pixels outside of it are empty.
+    fn bbox(&self) -> Option<SpatialPartition2D>;
+
+    fn time(&self) -> TimeInterval;
+
+    /// The value of the pixel with the `center` coordinate and the `index` in the global pixel grid of the query
+    fn value_at(&self, center: Coordinate2D, index: GridIdx2D) -> Option<f64>;
+}
+
+/// A processor that produces tiles for the query using the tiling specification of the execution context
+pub(super) struct GeneratedRasterProcessor<T> {
+    generator: Arc<dyn PixelGenerator>,
+    tiling_specification: TilingSpecification,
+    _pixel: PhantomData<T>,
+}
+
+impl<T> GeneratedRasterProcessor<T> {
+    pub fn new(
+        generator: Arc<dyn PixelGenerator>,
+        tiling_specification: TilingSpecification,
+    ) -> Self {
+        Self {
+            generator,
+            tiling_specification,
+            _pixel: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for GeneratedRasterProcessor<T>
+where
+    T: Pixel + Default + FromPrimitive<f64>,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let has_data = self.generator.time().intersects(&query.time_interval);
+        let time = if has_data {
+            self.generator.time()
+        } else {
+            query.time_interval
+        };
+
+        let tiles = tiling_strategy
+            .tile_information_iterator(query.spatial_bounds)
+            .map(move |tile_info| {
+                let intersects_bbox = self
+                    .generator
+                    .bbox()
+                    .map_or(true, |bbox| tile_info.spatial_partition().intersects(&bbox));
+
+                let data = if has_data && intersects_bbox {
+                    let tile_geo_transform = tile_info.tile_geo_transform();
+
+                    GridOrEmpty::from_index_fn(&tile_info.tile_size_in_pixels, |idx: GridIdx2D| {
+                        self.generator
+                            .value_at(
+                                tile_geo_transform.grid_idx_to_pixel_center_coordinate_2d(idx),
+                                tile_info.local_to_global_pixel_idx(idx),
+                            )
+                            .map(T::from_)
+                    })
+                } else {
+                    GridOrEmpty::Empty(EmptyGrid::new(tile_info.tile_size_in_pixels))
+                };
+
+                Ok(RasterTile2D::new_with_tile_info(time, tile_info, data))
+            });
+
+        Ok(stream::iter(tiles).boxed())
+    }
+}
+
+/// The direction in which the values of a [`SyntheticRasterPattern::Gradient`] increase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GradientDirection {
+    /// From west to east
+    Horizontal,
+    /// From north to south
+    Vertical,
+}
+
+/// The pattern of the values of a [`SyntheticRasterSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyntheticRasterPattern {
+    Constant {
+        value: f64,
+    },
+    /// Values that increase linearly from `start` to `end` across the extent
+    Gradient {
+        start: f64,
+        end: f64,
+        direction: GradientDirection,
+    },
+    /// Uniformly distributed values in `[min, max)`.
+    /// The values are derived from the seed and the pixel position, so a query is reproducible.
+    Noise {
+        min: f64,
+        max: f64,
+        seed: u64,
+    },
+}
+
+/// Parameters for the [`SyntheticRasterSource`] and [`ConstantRasterSource`] operators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntheticRasterSourceParameters {
+    pub data_type: RasterDataType,
+    pub spatial_reference: SpatialReference,
+    /// The area that contains data. If it is not set, the raster is unbounded.
+    #[serde(default)]
+    pub extent: Option<SpatialPartition2D>,
+    #[serde(default)]
+    pub time: TimeInterval,
+    #[serde(default)]
+    pub resolution: Option<SpatialResolution>,
+    #[serde(default)]
+    pub measurement: Measurement,
+    pub pattern: SyntheticRasterPattern,
+}
+
+/// A source that generates pixel values, e.g., for masks or test inputs
+pub type SyntheticRasterSource = SourceOperator<SyntheticRasterSourceParameters>;
+
+impl OperatorName for SyntheticRasterSource {
+    const TYPE_NAME: &'static str = "SyntheticRasterSource";
+}
+
+impl OperatorData for SyntheticRasterSourceParameters {
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for SyntheticRasterSource {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        if let SyntheticRasterPattern::Gradient { .. } = params.pattern {
+            ensure!(
+                params.extent.is_some(),
+                error::InvalidOperatorSpec {
+                    reason: "a gradient requires an extent",
+                }
+            );
+        }
+
+        if let SyntheticRasterPattern::Noise { min, max, .. } = params.pattern {
+            ensure!(
+                min <= max,
+                error::InvalidOperatorSpec {
+                    reason: "the minimum of the noise must not exceed its maximum",
+                }
+            );
+        }
+
+        Ok(InitializedSyntheticRasterSource {
+            result_descriptor: RasterResultDescriptor {
+                data_type: params.data_type,
+                spatial_reference: params.spatial_reference.into(),
+                measurement: params.measurement,
+                time: Some(params.time),
+                bbox: params.extent,
+                resolution: params.resolution,
+            },
+            generator: Arc::new(SyntheticPixelGenerator {
+                extent: params.extent,
+                time: params.time,
+                pattern: params.pattern,
+            }),
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+
+    span_fn!(SyntheticRasterSource);
+}
+
+/// Parameters for the [`ConstantRasterSource`] operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstantRasterSourceParameters {
+    pub data_type: RasterDataType,
+    pub spatial_reference: SpatialReference,
+    #[serde(default)]
+    pub extent: Option<SpatialPartition2D>,
+    #[serde(default)]
+    pub time: TimeInterval,
+    #[serde(default)]
+    pub resolution: Option<SpatialResolution>,
+    #[serde(default)]
+    pub measurement: Measurement,
+    pub value: f64,
+}
+
+/// A source that produces the same value for every pixel
+pub type ConstantRasterSource = SourceOperator<ConstantRasterSourceParameters>;
+
+impl OperatorName for ConstantRasterSource {
+    const TYPE_NAME: &'static str = "ConstantRasterSource";
+}
+
+impl OperatorData for ConstantRasterSourceParameters {
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for ConstantRasterSource {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let params = self.params;
+
+        SyntheticRasterSource {
+            params: SyntheticRasterSourceParameters {
+                data_type: params.data_type,
+                spatial_reference: params.spatial_reference,
+                extent: params.extent,
+                time: params.time,
+                resolution: params.resolution,
+                measurement: params.measurement,
+                pattern: SyntheticRasterPattern::Constant {
+                    value: params.value,
+                },
+            },
+        }
+        .boxed()
+        .initialize(context)
+        .await
+    }
+
+    span_fn!(ConstantRasterSource);
+}
+
+pub struct InitializedSyntheticRasterSource {
+    result_descriptor: RasterResultDescriptor,
+    generator: Arc<SyntheticPixelGenerator>,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedSyntheticRasterSource {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(call_generic_raster_processor!(
+            self.result_descriptor.data_type,
+            GeneratedRasterProcessor::new(self.generator.clone(), self.tiling_specification)
+                .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug)]
+struct SyntheticPixelGenerator {
+    extent: Option<SpatialPartition2D>,
+    time: TimeInterval,
+    pattern: SyntheticRasterPattern,
+}
+
+impl PixelGenerator for SyntheticPixelGenerator {
+    fn bbox(&self) -> Option<SpatialPartition2D> {
+        self.extent
+    }
+
+    fn time(&self) -> TimeInterval {
+        self.time
+    }
+
+    fn value_at(&self, center: Coordinate2D, index: GridIdx2D) -> Option<f64> {
+        if let Some(extent) = self.extent {
+            if !extent.contains_coordinate(&center) {
+                return None;
+            }
+        }
+
+        Some(match self.pattern {
+            SyntheticRasterPattern::Constant { value } => value,
+            SyntheticRasterPattern::Gradient {
+                start,
+                end,
+                direction,
+            } => {
+                let extent = self.extent?;
+                let fraction = match direction {
+                    GradientDirection::Horizontal => {
+                        (center.x - extent.upper_left().x) / extent.size_x()
+                    }
+                    GradientDirection::Vertical => {
+                        (extent.upper_left().y - center.y) / extent.size_y()
+                    }
+                };
+                start + (end - start) * fraction
+            }
+            SyntheticRasterPattern::Noise { min, max, seed } => {
+                let GridIdx([y, x]) = index;
+                let hash = split_mix_64(seed ^ split_mix_64(((y as u64) << 32) ^ x as u64));
+                // use the upper 53 bits for a uniformly distributed value in [0, 1)
+                let fraction = (hash >> 11) as f64 / (1_u64 << 53) as f64;
+                min + (max - min) * fraction
+            }
+        })
+    }
+}
+
+/// The `SplitMix64` mixing function that turns a number into a pseudo random number
+fn split_mix_64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use geoengine_datatypes::raster::GridShape2D;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    async fn query_tiles<T: Pixel>(
+        processor: Box<dyn RasterQueryProcessor<RasterType = T>>,
+        spatial_bounds: SpatialPartition2D,
+    ) -> Vec<RasterTile2D<T>> {
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds,
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_produces_constant_tiles() {
+        let operator = ConstantRasterSource {
+            params: ConstantRasterSourceParameters {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326(),
+                extent: Some(SpatialPartition2D::new((0., 2.).into(), (2., 0.).into()).unwrap()),
+                time: TimeInterval::default(),
+                resolution: None,
+                measurement: Measurement::Unitless,
+                value: 42.,
+            },
+        }
+        .boxed();
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), GridShape2D::new([2, 2])),
+        );
+
+        let processor = operator
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let tiles = query_tiles(
+            processor,
+            SpatialPartition2D::new((0., 2.).into(), (4., 0.).into()).unwrap(),
+        )
+        .await;
+
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(
+            tiles[0]
+                .grid_array
+                .clone()
+                .into_materialized_masked_grid()
+                .inner_grid
+                .data,
+            vec![42; 4]
+        );
+        assert!(tiles[1].is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_produces_gradients_and_noise() {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), GridShape2D::new([2, 2])),
+        );
+        let extent = SpatialPartition2D::new((0., 2.).into(), (2., 0.).into()).unwrap();
+
+        let gradient = SyntheticRasterSource {
+            params: SyntheticRasterSourceParameters {
+                data_type: RasterDataType::F64,
+                spatial_reference: SpatialReference::epsg_4326(),
+                extent: Some(extent),
+                time: TimeInterval::default(),
+                resolution: None,
+                measurement: Measurement::Unitless,
+                pattern: SyntheticRasterPattern::Gradient {
+                    start: 0.,
+                    end: 4.,
+                    direction: GradientDirection::Horizontal,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_f64()
+        .unwrap();
+
+        let tiles = query_tiles(gradient, extent).await;
+        assert_eq!(
+            tiles[0]
+                .grid_array
+                .clone()
+                .into_materialized_masked_grid()
+                .inner_grid
+                .data,
+            vec![1., 3., 1., 3.]
+        );
+
+        let noise = || async {
+            SyntheticRasterSource {
+                params: SyntheticRasterSourceParameters {
+                    data_type: RasterDataType::F64,
+                    spatial_reference: SpatialReference::epsg_4326(),
+                    extent: None,
+                    time: TimeInterval::default(),
+                    resolution: None,
+                    measurement: Measurement::Unitless,
+                    pattern: SyntheticRasterPattern::Noise {
+                        min: -1.,
+                        max: 1.,
+                        seed: 7,
+                    },
+                },
+            }
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_f64()
+            .unwrap()
+        };
+
+        let first = query_tiles(noise().await, extent).await;
+        let second = query_tiles(noise().await, extent).await;
+        assert_eq!(first, second);
+
+        let values = first[0]
+            .grid_array
+            .clone()
+            .into_materialized_masked_grid()
+            .inner_grid
+            .data;
+        assert!(values.iter().all(|v| (-1. ..1.).contains(v)));
+    }
+}
@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DataId;
+use serde::{Deserialize, Serialize};
+use tracing::{span, Level};
+use uuid::Uuid;
+
+use crate::engine::{
+    CreateSpan, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator,
+    OperatorData, OperatorName, RasterOperator, SourceOperator, VectorOperator,
+};
+use crate::util::Result;
+
+/// Parameters for the [`WorkflowReference`] operator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowReferenceParameters {
+    /// The id of the registered workflow
+    pub workflow: Uuid,
+}
+
+/// Embeds another registered workflow as a source, e.g., a preprocessing chain that many workflows share.
+///
+/// The workflow is resolved by the execution context during initialization, so it must be a raster workflow
+/// if the reference is used as a raster source and a vector workflow if it is used as a vector source.
+/// Since workflow ids are derived from their content, references cannot form cycles.
+pub type WorkflowReference = SourceOperator<WorkflowReferenceParameters>;
+
+impl OperatorName for WorkflowReference {
+    const TYPE_NAME: &'static str = "WorkflowReference";
+}
+
+impl OperatorData for WorkflowReferenceParameters {
+    // the data of the referenced workflow is only known after loading it,
+    // so the workflow registry resolves the references when collecting the data of a workflow
+    fn data_ids_collect(&self, _data_ids: &mut Vec<DataId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for WorkflowReference {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let operator = context
+            .workflow(&self.params.workflow)
+            .await?
+            .get_raster()?;
+
+        operator.initialize(context).await
+    }
+
+    span_fn!(WorkflowReference);
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for WorkflowReference {
+    async fn _initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let operator = context
+            .workflow(&self.params.workflow)
+            .await?
+            .get_vector()?;
+
+        operator.initialize(context).await
+    }
+
+    span_fn!(WorkflowReference);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, TypedVectorQueryProcessor};
+    use crate::error::Error;
+    use crate::mock::{MockPointSource, MockPointSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, SpatialResolution, TimeInterval, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn reference(workflow: Uuid) -> WorkflowReference {
+        WorkflowReference {
+            params: WorkflowReferenceParameters { workflow },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_embeds_vector_workflows() {
+        let workflow = Uuid::new_v4();
+
+        let mut execution_context = MockExecutionContext::test_default();
+        execution_context.add_workflow(
+            workflow,
+            MockPointSource {
+                params: MockPointSourceParams {
+                    points: vec![(0.0, 0.1).into(), (1.0, 1.1).into()],
+                },
+            }
+            .boxed()
+            .into(),
+        );
+
+        let operator: Box<dyn VectorOperator> = serde_json::from_value(json!({
+            "type": "WorkflowReference",
+            "params": {
+                "workflow": workflow
+            }
+        }))
+        .unwrap();
+
+        let processor = match operator
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+        {
+            TypedVectorQueryProcessor::MultiPoint(processor) => processor,
+            _ => panic!("expected a point workflow"),
+        };
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_requires_the_type_of_the_source() {
+        let workflow = Uuid::new_v4();
+
+        let mut execution_context = MockExecutionContext::test_default();
+        execution_context.add_workflow(
+            workflow,
+            MockPointSource {
+                params: MockPointSourceParams { points: vec![] },
+            }
+            .boxed()
+            .into(),
+        );
+
+        let result = RasterOperator::boxed(reference(workflow))
+            .initialize(&execution_context)
+            .await;
+        assert!(matches!(result, Err(Error::InvalidOperatorType { .. })));
+    }
+
+    #[tokio::test]
+    async fn it_fails_for_unknown_workflows() {
+        let result = VectorOperator::boxed(reference(Uuid::new_v4()))
+            .initialize(&MockExecutionContext::test_default())
+            .await;
+
+        assert!(matches!(result, Err(Error::WorkflowReference { .. })));
+    }
+}
@@ -1,6 +1,8 @@
 use futures::{future::BoxFuture, Future, FutureExt};
-use log::debug;
+use log::{debug, error};
 use rayon::ThreadPool;
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 use tracing::{span, Level};
@@ -68,7 +70,7 @@ pub async fn abortable_query_execution<F: Future<Output = Result<T>> + Send, T>(
     abort_future: BoxFuture<'_, ()>,
     abort_trigger: QueryAbortTrigger,
 ) -> F::Output {
-    let execution: BoxFuture<F::Output> = Box::pin(execution);
+    let execution: BoxFuture<F::Output> = Box::pin(catch_panic(execution));
 
     let (result, _, _) = futures::future::select_all([
         execution,
@@ -82,5 +84,64 @@ pub async fn abortable_query_execution<F: Future<Output = Result<T>> + Send, T>(
         return Err(error::Error::QueryCanceled);
     }
 
+    if matches!(result, Err(error::Error::QueryPanicked { .. })) {
+        // stop the remaining parts of the query, e.g., spawned tasks of other operators
+        abort_trigger.abort();
+    }
+
     result
 }
+
+/// Executes a future and converts a panic into an `Error::QueryPanicked`, e.g., of an operator that
+/// reached an `unreachable!()` path, so that the panic does not take down the whole server.
+pub async fn catch_panic<F, T, E>(execution: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<error::Error>,
+{
+    // the state of a panicked query is dropped, so it cannot be observed in a broken state
+    match AssertUnwindSafe(execution).catch_unwind().await {
+        Ok(result) => result,
+        Err(panic) => {
+            let message = panic_message(panic.as_ref());
+            error!("Query panicked: {}", message);
+            Err(error::Error::QueryPanicked { message }.into())
+        }
+    }
+}
+
+/// Extracts the message of a panic payload, which is a `&str` or `String` for `panic!` with a message
+pub fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockQueryContext, QueryContext};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_converts_panics_into_errors() {
+        let mut ctx = MockQueryContext::test_default();
+        let abort_trigger = ctx.abort_trigger().unwrap();
+
+        let result: Result<()> = abortable_query_execution(
+            futures::future::lazy(|_| -> Result<()> { panic!("operator failed") }),
+            futures::future::pending().boxed(),
+            abort_trigger,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::QueryPanicked { message }) if message == "operator failed"
+        ));
+    }
+}
@@ -0,0 +1,653 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{Future, StreamExt};
+use geoengine_datatypes::primitives::QueryRectangle;
+use serde::Serialize;
+use tokio::sync::{watch, Notify};
+
+use crate::engine::{QueryContext, QueryProcessor};
+use crate::util::Result;
+
+lazy_static::lazy_static! {
+    /// The query streams that are currently computed and can be joined by identical queries
+    static ref SHARED_QUERY_STREAMS: SharedQueryStreams = SharedQueryStreams::default();
+}
+
+/// The maximum number of items of a query stream that are kept for the queries that joined it.
+/// If a query produces more items, the joined queries execute themselves.
+const MAX_SHARED_ITEMS: usize = 256;
+
+/// Lets concurrent identical queries await the result of a single execution.
+///
+/// The first query for a key executes it, all queries for the same key that arrive in the meantime wait for its result.
+/// Results are not kept after the execution finished.
+/// If the execution fails or is canceled, the waiting queries execute themselves, since errors cannot be shared.
+#[derive(Debug)]
+pub struct QueryCoalescer<K, V> {
+    in_flight: Mutex<InFlightQueries<K, V>>,
+}
+
+#[derive(Debug)]
+struct InFlightQueries<K, V> {
+    executions: HashMap<K, (u64, watch::Receiver<Option<V>>)>,
+    next_execution_id: u64,
+}
+
+impl<K, V> Default for QueryCoalescer<K, V> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(InFlightQueries {
+                executions: HashMap::new(),
+                next_execution_id: 0,
+            }),
+        }
+    }
+}
+
+impl<K, V> QueryCoalescer<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executes the query or waits for a running execution of the same `key`
+    pub async fn execute<F, Fut, E>(&self, key: K, query: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        match self.join_or_start(key) {
+            Execution::Waiting(mut receiver) => {
+                loop {
+                    if let Some(result) = receiver.borrow().as_ref() {
+                        return Ok(result.clone());
+                    }
+
+                    if receiver.changed().await.is_err() {
+                        // the execution failed or was dropped
+                        break;
+                    }
+                }
+
+                query().await
+            }
+            Execution::Leading(sender, _guard) => {
+                let result = query().await;
+
+                if let Ok(result) = &result {
+                    // there might be no waiting queries
+                    let _ = sender.send(Some(result.clone()));
+                }
+
+                result
+            }
+        }
+    }
+
+    fn join_or_start(&self, key: K) -> Execution<'_, K, V> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .expect("in-flight queries must be accessible");
+
+        if let Some((_, receiver)) = in_flight.executions.get(&key) {
+            return Execution::Waiting(receiver.clone());
+        }
+
+        let execution_id = in_flight.next_execution_id;
+        in_flight.next_execution_id = in_flight.next_execution_id.wrapping_add(1);
+
+        let (sender, receiver) = watch::channel(None);
+        in_flight
+            .executions
+            .insert(key.clone(), (execution_id, receiver));
+
+        Execution::Leading(
+            sender,
+            ExecutionGuard {
+                coalescer: self,
+                key,
+                execution_id,
+            },
+        )
+    }
+
+    /// The number of running executions
+    pub fn len(&self) -> usize {
+        self.in_flight
+            .lock()
+            .expect("in-flight queries must be accessible")
+            .executions
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+enum Execution<'c, K, V>
+where
+    K: Hash + Eq,
+{
+    Waiting(watch::Receiver<Option<V>>),
+    Leading(watch::Sender<Option<V>>, ExecutionGuard<'c, K, V>),
+}
+
+/// Removes the execution from the in-flight queries when it finished or was dropped
+struct ExecutionGuard<'c, K, V>
+where
+    K: Hash + Eq,
+{
+    coalescer: &'c QueryCoalescer<K, V>,
+    key: K,
+    execution_id: u64,
+}
+
+impl<K, V> Drop for ExecutionGuard<'_, K, V>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        // do not panic while dropping
+        let mut in_flight = match self.coalescer.in_flight.lock() {
+            Ok(in_flight) => in_flight,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if matches!(in_flight.executions.get(&self.key), Some((id, _)) if *id == self.execution_id)
+        {
+            in_flight.executions.remove(&self.key);
+        }
+    }
+}
+
+/// A query processor that lets concurrent identical queries share the stream of a single execution.
+///
+/// The first query executes the processor and keeps the items of its stream, identical queries that arrive in the
+/// meantime replay the kept items and then wait for the next ones. Queries are identical if their key, query
+/// rectangle and chunk size are equal. The items are not kept after the execution finished.
+/// If the execution fails, is canceled or produces more than `MAX_SHARED_ITEMS` items, the joined queries execute
+/// themselves and skip the items they already returned, since processors produce their items in a deterministic order.
+pub struct CoalescingQueryProcessor<P> {
+    processor: P,
+    key: String,
+}
+
+impl<P> CoalescingQueryProcessor<P> {
+    /// Wraps the `processor`, the `key` must identify its workflow, e.g., the workflow id and the query's
+    /// spatial reference
+    pub fn new(processor: P, key: String) -> Self {
+        Self { processor, key }
+    }
+}
+
+#[async_trait]
+impl<P, T> QueryProcessor for CoalescingQueryProcessor<P>
+where
+    P: QueryProcessor<Output = T>,
+    P::SpatialBounds: Serialize + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    type Output = T;
+    type SpatialBounds = P::SpatialBounds;
+
+    async fn _query<'a>(
+        &'a self,
+        query: QueryRectangle<Self::SpatialBounds>,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let key = (
+            TypeId::of::<T>(),
+            format!(
+                "{}:{}:{}",
+                self.key,
+                usize::from(ctx.chunk_byte_size()),
+                serde_json::to_string(&query)?
+            ),
+        );
+
+        match SHARED_QUERY_STREAMS.join_or_start::<T>(key) {
+            SharedExecution::Joined(shared) => {
+                Ok(follow_shared_query(shared, &self.processor, query, ctx))
+            }
+            SharedExecution::Leading(guard) => {
+                // if the query fails, the guard aborts the execution for the joined queries
+                let stream = self.processor.query(query, ctx).await?;
+
+                Ok(lead_shared_query(stream, guard))
+            }
+        }
+    }
+}
+
+/// Keeps the items of the `stream` for the queries that joined it
+fn lead_shared_query<T>(
+    stream: BoxStream<'_, Result<T>>,
+    guard: SharedQueryGuard<T>,
+) -> BoxStream<'_, Result<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    stream::unfold((stream, guard), |(mut stream, guard)| async move {
+        match stream.next().await {
+            Some(Ok(item)) => {
+                guard.shared.push(&item);
+                Some((Ok(item), (stream, guard)))
+            }
+            Some(Err(error)) => {
+                guard.shared.finish(SharedQueryStatus::Aborted);
+                Some((Err(error), (stream, guard)))
+            }
+            None => {
+                guard.shared.finish(SharedQueryStatus::Finished);
+                None
+            }
+        }
+    })
+    .boxed()
+}
+
+enum FollowerState<'a, T> {
+    Joined {
+        shared: Arc<SharedQueryStream<T>>,
+        returned: usize,
+    },
+    Executing(BoxStream<'a, Result<T>>),
+    Done,
+}
+
+/// Replays the items of the shared execution or executes the query itself if the shared execution is aborted
+fn follow_shared_query<'a, P, T>(
+    shared: Arc<SharedQueryStream<T>>,
+    processor: &'a P,
+    query: QueryRectangle<P::SpatialBounds>,
+    ctx: &'a dyn QueryContext,
+) -> BoxStream<'a, Result<T>>
+where
+    P: QueryProcessor<Output = T>,
+    T: Clone + Send + Sync + 'static,
+{
+    let state = FollowerState::Joined {
+        shared,
+        returned: 0,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            match state {
+                FollowerState::Joined { shared, returned } => {
+                    let notifier = Arc::clone(&shared);
+                    // register for changes before looking at the items to not miss any
+                    let changed = notifier.changed.notified();
+
+                    match shared.item(returned) {
+                        SharedItem::Item(item) => {
+                            let returned = returned + 1;
+                            return Some((Ok(item), FollowerState::Joined { shared, returned }));
+                        }
+                        SharedItem::Pending => {
+                            changed.await;
+                            state = FollowerState::Joined { shared, returned };
+                        }
+                        SharedItem::Finished => return None,
+                        SharedItem::Aborted => match processor.query(query, ctx).await {
+                            Ok(stream) => {
+                                state = FollowerState::Executing(stream.skip(returned).boxed());
+                            }
+                            Err(error) => return Some((Err(error), FollowerState::Done)),
+                        },
+                    }
+                }
+                FollowerState::Executing(mut stream) => {
+                    return stream
+                        .next()
+                        .await
+                        .map(|item| (item, FollowerState::Executing(stream)));
+                }
+                FollowerState::Done => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SharedQueryStatus {
+    Running,
+    Finished,
+    Aborted,
+}
+
+enum SharedItem<T> {
+    Item(T),
+    Pending,
+    Finished,
+    Aborted,
+}
+
+/// The items of a running query that are shared with the identical queries
+struct SharedQueryStream<T> {
+    state: Mutex<SharedQueryState<T>>,
+    changed: Notify,
+}
+
+struct SharedQueryState<T> {
+    items: Vec<T>,
+    status: SharedQueryStatus,
+}
+
+impl<T> SharedQueryStream<T>
+where
+    T: Clone,
+{
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(SharedQueryState {
+                items: Vec::new(),
+                status: SharedQueryStatus::Running,
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SharedQueryState<T>> {
+        // do not panic, e.g., while dropping
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn push(&self, item: &T) {
+        {
+            let mut state = self.lock();
+
+            if state.status != SharedQueryStatus::Running {
+                return;
+            }
+
+            if state.items.len() < MAX_SHARED_ITEMS {
+                state.items.push(item.clone());
+            } else {
+                state.status = SharedQueryStatus::Aborted;
+                state.items = Vec::new();
+            }
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    fn finish(&self, status: SharedQueryStatus) {
+        {
+            let mut state = self.lock();
+
+            if state.status == SharedQueryStatus::Running {
+                state.status = status;
+            }
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    fn item(&self, index: usize) -> SharedItem<T> {
+        let state = self.lock();
+
+        if let Some(item) = state.items.get(index) {
+            return SharedItem::Item(item.clone());
+        }
+
+        match state.status {
+            SharedQueryStatus::Running => SharedItem::Pending,
+            SharedQueryStatus::Finished => SharedItem::Finished,
+            SharedQueryStatus::Aborted => SharedItem::Aborted,
+        }
+    }
+}
+
+/// The key of a shared query, the type id distinguishes the outputs of the processors
+type SharedQueryKey = (TypeId, String);
+
+#[derive(Default)]
+struct SharedQueryStreams {
+    in_flight: Mutex<InFlightStreams>,
+}
+
+#[derive(Default)]
+struct InFlightStreams {
+    streams: HashMap<SharedQueryKey, (u64, Arc<dyn Any + Send + Sync>)>,
+    next_execution_id: u64,
+}
+
+enum SharedExecution<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    Joined(Arc<SharedQueryStream<T>>),
+    Leading(SharedQueryGuard<T>),
+}
+
+impl SharedQueryStreams {
+    fn lock(&self) -> std::sync::MutexGuard<'_, InFlightStreams> {
+        // do not panic, e.g., while dropping
+        match self.in_flight.lock() {
+            Ok(in_flight) => in_flight,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn join_or_start<T>(&self, key: SharedQueryKey) -> SharedExecution<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let mut in_flight = self.lock();
+
+        if let Some((_, shared)) = in_flight.streams.get(&key) {
+            if let Ok(shared) = Arc::clone(shared).downcast::<SharedQueryStream<T>>() {
+                return SharedExecution::Joined(shared);
+            }
+        }
+
+        let execution_id = in_flight.next_execution_id;
+        in_flight.next_execution_id = in_flight.next_execution_id.wrapping_add(1);
+
+        let shared = Arc::new(SharedQueryStream::new());
+        in_flight
+            .streams
+            .insert(key.clone(), (execution_id, shared.clone()));
+
+        SharedExecution::Leading(SharedQueryGuard {
+            key,
+            execution_id,
+            shared,
+        })
+    }
+
+    fn remove(&self, key: &SharedQueryKey, execution_id: u64) {
+        let mut in_flight = self.lock();
+
+        if matches!(in_flight.streams.get(key), Some((id, _)) if *id == execution_id) {
+            in_flight.streams.remove(key);
+        }
+    }
+}
+
+/// Removes the shared query when its execution finished or was dropped and aborts it for the joined queries if it did not finish
+struct SharedQueryGuard<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    key: SharedQueryKey,
+    execution_id: u64,
+    shared: Arc<SharedQueryStream<T>>,
+}
+
+impl<T> Drop for SharedQueryGuard<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        SHARED_QUERY_STREAMS.remove(&self.key, self.execution_id);
+        self.shared.finish(SharedQueryStatus::Aborted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MockQueryContext;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingQueryProcessor {
+        executions: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl QueryProcessor for CountingQueryProcessor {
+        type Output = u32;
+        type SpatialBounds = BoundingBox2D;
+
+        async fn _query<'a>(
+            &'a self,
+            _query: QueryRectangle<Self::SpatialBounds>,
+            _ctx: &'a dyn QueryContext,
+        ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            Ok(stream::iter((0..5).map(Ok)).boxed())
+        }
+    }
+
+    fn query_rectangle(x: f64) -> QueryRectangle<BoundingBox2D> {
+        QueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (x, 1.).into()),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_shares_the_streams_of_identical_queries() {
+        let processor = CoalescingQueryProcessor::new(
+            CountingQueryProcessor {
+                executions: AtomicUsize::new(0),
+            },
+            "it_shares_the_streams_of_identical_queries".to_string(),
+        );
+        let ctx = MockQueryContext::test_default();
+
+        let leader = processor.query(query_rectangle(1.), &ctx).await.unwrap();
+        let follower = processor.query(query_rectangle(1.), &ctx).await.unwrap();
+        let other = processor.query(query_rectangle(2.), &ctx).await.unwrap();
+
+        let (leader, follower, other) = tokio::join!(
+            leader.try_collect::<Vec<_>>(),
+            follower.try_collect::<Vec<_>>(),
+            other.try_collect::<Vec<_>>()
+        );
+
+        assert_eq!(leader.unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(follower.unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(other.unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(processor.processor.executions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn it_executes_joined_queries_if_the_shared_query_is_dropped() {
+        let processor = CoalescingQueryProcessor::new(
+            CountingQueryProcessor {
+                executions: AtomicUsize::new(0),
+            },
+            "it_executes_joined_queries_if_the_shared_query_is_dropped".to_string(),
+        );
+        let ctx = MockQueryContext::test_default();
+
+        let mut leader = processor.query(query_rectangle(1.), &ctx).await.unwrap();
+        let mut follower = processor.query(query_rectangle(1.), &ctx).await.unwrap();
+
+        assert_eq!(leader.next().await.unwrap().unwrap(), 0);
+        assert_eq!(follower.next().await.unwrap().unwrap(), 0);
+
+        drop(leader);
+
+        let rest: Vec<u32> = follower.try_collect().await.unwrap();
+
+        assert_eq!(rest, vec![1, 2, 3, 4]);
+        assert_eq!(processor.processor.executions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn it_executes_concurrent_queries_once() {
+        let coalescer = QueryCoalescer::<u32, u32>::new();
+        let executions = &AtomicUsize::new(0);
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let leader = coalescer.execute(1, || async move {
+            executions.fetch_add(1, Ordering::SeqCst);
+            released.await.unwrap();
+            Ok::<_, ()>(42)
+        });
+        let follower = coalescer.execute(1, || async move {
+            executions.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(0)
+        });
+        let other = coalescer.execute(2, || async move {
+            executions.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(7)
+        });
+
+        let (leader, follower, other, _) = tokio::join!(leader, follower, other, async {
+            release.send(()).unwrap();
+        });
+
+        assert_eq!(leader, Ok(42));
+        assert_eq!(follower, Ok(42));
+        assert_eq!(other, Ok(7));
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+        assert!(coalescer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_executes_again_after_a_failure() {
+        let coalescer = QueryCoalescer::<u32, u32>::new();
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let leader = coalescer.execute(1, || async move {
+            released.await.unwrap();
+            Err("failed")
+        });
+        let follower = coalescer.execute(1, || async move { Ok::<_, &str>(42) });
+
+        let (leader, follower, _) = tokio::join!(leader, follower, async {
+            release.send(()).unwrap();
+        });
+
+        assert_eq!(leader, Err("failed"));
+        assert_eq!(follower, Ok(42));
+        assert!(coalescer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_keep_results() {
+        let coalescer = QueryCoalescer::<u32, u32>::new();
+
+        assert_eq!(
+            coalescer.execute(1, || async move { Ok::<_, ()>(1) }).await,
+            Ok(1)
+        );
+        assert_eq!(
+            coalescer.execute(1, || async move { Ok::<_, ()>(2) }).await,
+            Ok(2)
+        );
+    }
+}
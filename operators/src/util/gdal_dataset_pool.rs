@@ -0,0 +1,428 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use gdal::{Dataset, DatasetOptions};
+
+use crate::util::gdal::gdal_open_dataset_ex;
+use crate::util::{safe_lock_mutex, Result};
+
+lazy_static::lazy_static! {
+    /// The process-wide pool of open GDAL datasets, e.g., for the files of the `GdalSource`
+    pub static ref GDAL_DATASET_POOL: GdalDatasetPool =
+        GdalDatasetPool::new(GdalDatasetPoolConfig::default());
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdalDatasetPoolConfig {
+    /// Disables pooling, i.e., datasets are closed after use
+    pub enabled: bool,
+    /// The maximum number of idle datasets for the same file and options
+    pub max_idle_datasets_per_path: usize,
+    /// The maximum number of idle datasets over all files, the least recently used ones are closed first
+    pub max_idle_datasets: usize,
+    /// Idle datasets are closed after this time, so that they do not hold resources forever
+    pub max_idle_time: Duration,
+    /// Open options that are added to those of the datasets, e.g., `NUM_THREADS=2`
+    pub open_options: Vec<String>,
+}
+
+impl Default for GdalDatasetPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_idle_datasets_per_path: 4,
+            max_idle_datasets: 256,
+            max_idle_time: Duration::from_secs(60),
+            open_options: vec![],
+        }
+    }
+}
+
+/// The usage of a `GdalDatasetPool`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GdalDatasetPoolMetrics {
+    /// The number of datasets that were opened
+    pub opened: usize,
+    /// The number of requests that were served by an idle dataset
+    pub reused: usize,
+    /// The number of idle datasets that were closed because the pool was full or they were idle for too long
+    pub evicted: usize,
+    /// The number of idle datasets that were closed because their file changed or vanished
+    pub unhealthy: usize,
+    pub idle: usize,
+    pub in_use: usize,
+}
+
+/// The file and everything that influences how it is opened
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DatasetKey {
+    path: PathBuf,
+    open_flags: u32,
+    open_options: Vec<String>,
+    allowed_drivers: Vec<String>,
+    config_options: Vec<(String, String)>,
+}
+
+struct IdleDataset {
+    dataset: Dataset,
+    modified: Option<SystemTime>,
+    idle_since: Instant,
+}
+
+#[derive(Default)]
+struct PoolState {
+    idle: HashMap<DatasetKey, Vec<IdleDataset>>,
+    /// The keys of idle datasets, from the least to the most recently returned one
+    lru: VecDeque<DatasetKey>,
+}
+
+impl PoolState {
+    fn idle_count(&self) -> usize {
+        self.lru.len()
+    }
+
+    fn remove_from_lru(&mut self, key: &DatasetKey) {
+        if let Some(position) = self.lru.iter().rposition(|lru_key| lru_key == key) {
+            self.lru.remove(position);
+        }
+    }
+
+    fn pop_least_recently_used(&mut self) -> Option<IdleDataset> {
+        let key = self.lru.pop_front()?;
+        let datasets = self.idle.get_mut(&key)?;
+        let dataset = (!datasets.is_empty()).then(|| datasets.remove(0));
+        if datasets.is_empty() {
+            self.idle.remove(&key);
+        }
+        dataset
+    }
+}
+
+/// A bounded pool of open GDAL datasets, so that files are not opened and closed for each tile.
+///
+/// Datasets are handed out exclusively, since a GDAL dataset must not be used by several threads at once.
+/// If there is no idle dataset for a file, another one is opened, so that requests never wait for the pool.
+pub struct GdalDatasetPool {
+    config: RwLock<GdalDatasetPoolConfig>,
+    state: Mutex<PoolState>,
+    opened: AtomicUsize,
+    reused: AtomicUsize,
+    evicted: AtomicUsize,
+    unhealthy: AtomicUsize,
+    in_use: AtomicUsize,
+}
+
+impl GdalDatasetPool {
+    pub fn new(config: GdalDatasetPoolConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            state: Mutex::default(),
+            opened: AtomicUsize::new(0),
+            reused: AtomicUsize::new(0),
+            evicted: AtomicUsize::new(0),
+            unhealthy: AtomicUsize::new(0),
+            in_use: AtomicUsize::new(0),
+        }
+    }
+
+    /// Replaces the configuration and closes all idle datasets, since they were opened with the old options
+    pub fn configure(&self, config: GdalDatasetPoolConfig) {
+        *self.config.write().unwrap_or_else(PoisonError::into_inner) = config;
+        self.clear();
+    }
+
+    fn config(&self) -> GdalDatasetPoolConfig {
+        self.config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Closes all idle datasets
+    pub fn clear(&self) {
+        let mut state = safe_lock_mutex(&self.state);
+        let closed = state.idle_count();
+        *state = PoolState::default();
+        self.evicted.fetch_add(closed, Ordering::Relaxed);
+    }
+
+    pub fn metrics(&self) -> GdalDatasetPoolMetrics {
+        GdalDatasetPoolMetrics {
+            opened: self.opened.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            evicted: self.evicted.load(Ordering::Relaxed),
+            unhealthy: self.unhealthy.load(Ordering::Relaxed),
+            idle: safe_lock_mutex(&self.state).idle_count(),
+            in_use: self.in_use.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns an idle dataset for the file and options or opens a new one.
+    /// The `config_options` must already be set for the current thread, they only distinguish the datasets.
+    pub fn get(
+        &self,
+        path: &Path,
+        dataset_options: DatasetOptions,
+        config_options: Option<&[(String, String)]>,
+    ) -> Result<PooledDataset<'_>> {
+        let config = self.config();
+
+        let mut open_options: Vec<String> = dataset_options
+            .open_options
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        for option in &config.open_options {
+            let name = option.split('=').next().unwrap_or_default();
+            if !open_options
+                .iter()
+                .any(|o| o.split('=').next() == Some(name))
+            {
+                open_options.push(option.clone());
+            }
+        }
+
+        let key = DatasetKey {
+            path: path.to_owned(),
+            open_flags: dataset_options.open_flags.bits(),
+            open_options,
+            allowed_drivers: dataset_options
+                .allowed_drivers
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            config_options: config_options.map(<[_]>::to_vec).unwrap_or_default(),
+        };
+
+        let idle_dataset = if config.enabled {
+            self.take_idle(&key, &config)
+        } else {
+            None
+        };
+
+        let (dataset, modified) = if let Some(idle_dataset) = idle_dataset {
+            self.reused.fetch_add(1, Ordering::Relaxed);
+            (idle_dataset.dataset, idle_dataset.modified)
+        } else {
+            let open_options: Vec<&str> = key.open_options.iter().map(String::as_str).collect();
+            let modified = modification_time(path);
+            let dataset = gdal_open_dataset_ex(
+                path,
+                DatasetOptions {
+                    open_options: (!open_options.is_empty()).then(|| open_options.as_slice()),
+                    ..dataset_options
+                },
+            )?;
+            self.opened.fetch_add(1, Ordering::Relaxed);
+            (dataset, modified)
+        };
+
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+
+        Ok(PooledDataset {
+            pool: self,
+            key: config.enabled.then_some(key),
+            dataset: Some(dataset),
+            modified,
+        })
+    }
+
+    /// Takes the most recently returned healthy dataset for the key and closes stale ones
+    fn take_idle(&self, key: &DatasetKey, config: &GdalDatasetPoolConfig) -> Option<IdleDataset> {
+        let mut state = safe_lock_mutex(&self.state);
+
+        self.evict_expired(&mut state, config.max_idle_time);
+
+        while let Some(idle_dataset) = state.idle.get_mut(key).and_then(Vec::pop) {
+            state.remove_from_lru(key);
+
+            if is_healthy(&key.path, idle_dataset.modified) {
+                if state.idle.get(key).map_or(false, Vec::is_empty) {
+                    state.idle.remove(key);
+                }
+                return Some(idle_dataset);
+            }
+
+            self.unhealthy.fetch_add(1, Ordering::Relaxed);
+        }
+
+        state.idle.remove(key);
+
+        None
+    }
+
+    fn evict_expired(&self, state: &mut PoolState, max_idle_time: Duration) {
+        while let Some(key) = state.lru.front() {
+            let expired = state
+                .idle
+                .get(key)
+                .and_then(|datasets| datasets.first())
+                .map_or(true, |dataset| dataset.idle_since.elapsed() > max_idle_time);
+
+            if !expired {
+                break;
+            }
+
+            if state.pop_least_recently_used().is_some() {
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn give_back(&self, key: DatasetKey, dataset: Dataset, modified: Option<SystemTime>) {
+        let config = self.config();
+        let mut state = safe_lock_mutex(&self.state);
+
+        let idle_for_key = state.idle.get(&key).map_or(0, Vec::len);
+        if idle_for_key >= config.max_idle_datasets_per_path || config.max_idle_datasets == 0 {
+            self.evicted.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        while state.idle_count() >= config.max_idle_datasets {
+            if state.pop_least_recently_used().is_some() {
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        state.lru.push_back(key.clone());
+        state.idle.entry(key).or_default().push(IdleDataset {
+            dataset,
+            modified,
+            idle_since: Instant::now(),
+        });
+    }
+}
+
+/// The modification time of local files, `None` for virtual file systems or if it is not available
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    if path.starts_with("/vsi") {
+        return None;
+    }
+
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// A dataset is healthy if its file was not changed or removed since it was opened
+fn is_healthy(path: &Path, modified: Option<SystemTime>) -> bool {
+    match modified {
+        Some(modified) => modification_time(path) == Some(modified),
+        None => true,
+    }
+}
+
+/// A dataset that is returned to the pool when it is dropped
+pub struct PooledDataset<'p> {
+    pool: &'p GdalDatasetPool,
+    key: Option<DatasetKey>,
+    dataset: Option<Dataset>,
+    modified: Option<SystemTime>,
+}
+
+impl Deref for PooledDataset<'_> {
+    type Target = Dataset;
+
+    fn deref(&self) -> &Self::Target {
+        self.dataset.as_ref().expect("only taken on drop")
+    }
+}
+
+impl Drop for PooledDataset<'_> {
+    fn drop(&mut self) {
+        self.pool.in_use.fetch_sub(1, Ordering::Relaxed);
+
+        if let (Some(key), Some(dataset)) = (self.key.take(), self.dataset.take()) {
+            self.pool.give_back(key, dataset, self.modified);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data;
+    use gdal::GdalOpenFlags;
+
+    fn raster_options() -> DatasetOptions<'static> {
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_RASTER,
+            ..DatasetOptions::default()
+        }
+    }
+
+    #[test]
+    fn it_reuses_datasets() {
+        let pool = GdalDatasetPool::new(GdalDatasetPoolConfig::default());
+        let path = test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF");
+
+        let first = pool.get(path, raster_options(), None).unwrap();
+        let second = pool.get(path, raster_options(), None).unwrap();
+        assert_eq!(pool.metrics().in_use, 2);
+        assert_eq!(first.raster_size(), second.raster_size());
+
+        drop(first);
+        drop(second);
+
+        let third = pool.get(path, raster_options(), None).unwrap();
+        assert_eq!(third.raster_size(), (3600, 1800));
+
+        assert_eq!(
+            pool.metrics(),
+            GdalDatasetPoolMetrics {
+                opened: 2,
+                reused: 1,
+                evicted: 0,
+                unhealthy: 0,
+                idle: 1,
+                in_use: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn it_bounds_the_idle_datasets() {
+        let pool = GdalDatasetPool::new(GdalDatasetPoolConfig {
+            max_idle_datasets_per_path: 1,
+            ..GdalDatasetPoolConfig::default()
+        });
+        let path = test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF");
+
+        let first = pool.get(path, raster_options(), None).unwrap();
+        let second = pool.get(path, raster_options(), None).unwrap();
+        drop(first);
+        drop(second);
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics.idle, 1);
+        assert_eq!(metrics.evicted, 1);
+    }
+
+    #[test]
+    fn it_closes_datasets_of_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ndvi.tiff");
+        std::fs::copy(
+            test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF"),
+            &path,
+        )
+        .unwrap();
+
+        let pool = GdalDatasetPool::new(GdalDatasetPoolConfig::default());
+
+        drop(pool.get(&path, raster_options(), None).unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(pool.get(&path, raster_options(), None).is_err());
+        assert_eq!(pool.metrics().unhealthy, 1);
+        assert_eq!(pool.metrics().in_use, 0);
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::ops::{Add, BitAnd, BitOr, BitXor, Shr};
 
 /// From `num_integer`.
@@ -15,9 +16,81 @@ where
     (a & b) + ((a ^ b) >> 1)
 }
 
+/// A sum of floating point numbers that compensates the rounding errors of the additions
+/// (Kahan-Babuška-Neumaier summation).
+///
+/// The error of the sum does not grow with the number of values, so results are stable for large counts
+/// and do not depend on the magnitude of the values that were added before.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NeumaierSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl NeumaierSum {
+    #[inline]
+    pub fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+
+        if !sum.is_finite() {
+            // there is nothing to compensate for infinite or NaN sums
+            self.sum = sum;
+            return;
+        }
+
+        // the lower-order bits of the smaller operand are lost in the addition
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - sum) + value;
+        } else {
+            self.compensation += (value - sum) + self.sum;
+        }
+
+        self.sum = sum;
+    }
+
+    #[inline]
+    pub fn value(&self) -> f64 {
+        if self.sum.is_finite() {
+            self.sum + self.compensation
+        } else {
+            self.sum
+        }
+    }
+}
+
+impl FromIterator<f64> for NeumaierSum {
+    fn from_iter<T: IntoIterator<Item = f64>>(iter: T) -> Self {
+        let mut sum = Self::default();
+        for value in iter {
+            sum.add(value);
+        }
+        sum
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::{BigRational, ToPrimitive};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Sums the values without any rounding and rounds the result once
+    fn exact_sum(values: &[f64]) -> f64 {
+        values
+            .iter()
+            .map(|&value| BigRational::from_float(value).unwrap())
+            .fold(BigRational::from_integer(0.into()), |sum, value| {
+                sum + value
+            })
+            .to_f64()
+            .unwrap()
+    }
+
+    /// The difference of two floats in units in the last place
+    fn ulps(a: f64, b: f64) -> u64 {
+        (a.to_bits() as i64).abs_diff(b.to_bits() as i64)
+    }
 
     #[test]
     fn average_floor_checks() {
@@ -28,4 +101,58 @@ mod tests {
 
         assert_eq!(average_floor(i64::MIN, i64::MAX), -1);
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn neumaier_sum_compensates_cancellation() {
+        let values = [1.0, 1e100, 1.0, -1e100];
+
+        assert_eq!(values.iter().sum::<f64>(), 0.);
+        assert_eq!(values.into_iter().collect::<NeumaierSum>().value(), 2.);
+    }
+
+    #[test]
+    fn neumaier_sum_matches_exact_sum_for_many_values() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let values: Vec<f64> = (0..100_000)
+            .map(|_| rng.gen_range(-1.0..1.0) * 10_f64.powi(rng.gen_range(-8..8)))
+            .collect();
+
+        let exact = exact_sum(&values);
+        let sum = values.iter().copied().collect::<NeumaierSum>().value();
+
+        assert!(ulps(sum, exact) <= 1, "{sum} != {exact}");
+
+        let reversed = values
+            .iter()
+            .rev()
+            .copied()
+            .collect::<NeumaierSum>()
+            .value();
+        assert!(ulps(reversed, exact) <= 1, "{reversed} != {exact}");
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn neumaier_sum_of_large_counts() {
+        let mut sum = NeumaierSum::default();
+        for _ in 0..10_000_000 {
+            sum.add(0.1);
+        }
+
+        assert_eq!(sum.value(), 1_000_000.);
+        assert_ne!((0..10_000_000).map(|_| 0.1).sum::<f64>(), sum.value());
+    }
+
+    #[test]
+    fn neumaier_sum_propagates_non_finite_values() {
+        let mut sum = NeumaierSum::default();
+        sum.add(1.);
+        sum.add(f64::INFINITY);
+        sum.add(1.);
+        assert_eq!(sum.value(), f64::INFINITY);
+
+        sum.add(f64::NAN);
+        assert!(sum.value().is_nan());
+    }
 }
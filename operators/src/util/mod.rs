@@ -1,16 +1,27 @@
 mod async_util;
+pub mod coalescing;
 pub mod gdal;
+pub mod gdal_dataset_pool;
 pub mod input;
 pub mod math;
 pub mod number_statistics;
+pub mod query_splitting;
+pub mod raster_sampling;
 pub mod raster_stream_to_geotiff;
+pub mod raster_stream_to_netcdf;
 pub mod raster_stream_to_png;
+pub mod raster_stream_to_zarr;
 mod rayon;
 pub mod statistics;
 pub mod stream_zip;
 pub mod string_token;
 pub mod sunpos;
 mod temporary_gdal_thread_local_config_options;
+#[cfg(test)]
+pub mod test;
+pub mod vector_stream_to_csv;
+pub mod vector_stream_to_geo_parquet;
+pub mod vector_stream_to_spool;
 
 use crate::error::Error;
 use std::collections::HashSet;
@@ -18,7 +29,8 @@ use std::ops::Deref;
 use std::sync::{Mutex, MutexGuard};
 
 pub use self::async_util::{
-    abortable_query_execution, spawn, spawn_blocking, spawn_blocking_with_thread_pool,
+    abortable_query_execution, catch_panic, panic_message, spawn, spawn_blocking,
+    spawn_blocking_with_thread_pool,
 };
 pub use self::rayon::create_rayon_thread_pool;
 pub(crate) use self::temporary_gdal_thread_local_config_options::TemporaryGdalThreadLocalConfigOptions;
@@ -1,17 +1,19 @@
+use crate::util::math::NeumaierSum;
 use num_traits::AsPrimitive;
 use serde::{Deserialize, Serialize};
 
 /// This struct provides some basic number statistics.
 ///
 /// All operations run in constant time.
+/// The sums are compensated, so the results do not drift for large numbers of values.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct NumberStatistics {
     min_value: f64,
     max_value: f64,
     value_count: usize,
     value_nan_count: usize,
-    mean_value: f64,
-    m2: f64,
+    sum: NeumaierSum,
+    m2: NeumaierSum,
 }
 
 impl Default for NumberStatistics {
@@ -21,8 +23,8 @@ impl Default for NumberStatistics {
             max_value: f64::MIN,
             value_count: 0,
             value_nan_count: 0,
-            mean_value: 0.0,
-            m2: 0.0,
+            sum: NeumaierSum::default(),
+            m2: NeumaierSum::default(),
         }
     }
 }
@@ -43,12 +45,13 @@ impl NumberStatistics {
         self.min_value = f64::min(self.min_value, value);
         self.max_value = f64::max(self.max_value, value);
 
-        // Welford's algorithm
+        // Welford's algorithm with the mean derived from the compensated sum
+        let old_mean = self.mean_value();
         self.value_count += 1;
-        let delta = value - self.mean_value;
-        self.mean_value += delta / (self.value_count as f64);
-        let delta2 = value - self.mean_value;
-        self.m2 += delta * delta2;
+        self.sum.add(value);
+        let delta = value - old_mean;
+        let delta2 = value - self.mean_value();
+        self.m2.add(delta * delta2);
     }
 
     #[inline]
@@ -61,6 +64,15 @@ impl NumberStatistics {
         self.value_nan_count += batch_size;
     }
 
+    #[inline]
+    fn mean_value(&self) -> f64 {
+        if self.value_count > 0 {
+            self.sum.value() / (self.value_count as f64)
+        } else {
+            0.
+        }
+    }
+
     pub fn count(&self) -> usize {
         self.value_count
     }
@@ -87,7 +99,7 @@ impl NumberStatistics {
 
     pub fn mean(&self) -> f64 {
         if self.value_count > 0 {
-            self.mean_value
+            self.mean_value()
         } else {
             f64::NAN
         }
@@ -95,7 +107,7 @@ impl NumberStatistics {
 
     pub fn var(&self) -> f64 {
         if self.value_count > 0 {
-            self.m2 / (self.value_count as f64)
+            self.m2.value() / (self.value_count as f64)
         } else {
             f64::NAN
         }
@@ -103,7 +115,7 @@ impl NumberStatistics {
 
     pub fn std_dev(&self) -> f64 {
         if self.value_count > 1 {
-            f64::sqrt(self.m2 / (self.value_count as f64))
+            f64::sqrt(self.m2.value() / (self.value_count as f64))
         } else {
             f64::NAN
         }
@@ -111,7 +123,7 @@ impl NumberStatistics {
 
     pub fn sample_std_dev(&self) -> f64 {
         if self.value_count > 1 {
-            f64::sqrt(self.m2 / ((self.value_count - 1) as f64))
+            f64::sqrt(self.m2.value() / ((self.value_count - 1) as f64))
         } else {
             f64::NAN
         }
@@ -121,6 +133,7 @@ impl NumberStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::{BigRational, ToPrimitive};
 
     #[test]
     #[allow(clippy::float_cmp)]
@@ -152,4 +165,50 @@ mod tests {
         assert_eq!(number_statistics.count(), 1);
         assert_eq!(number_statistics.nan_count(), 2);
     }
+
+    #[test]
+    fn large_counts_with_offset() {
+        // the values repeat, so the exact statistics of one period are the exact statistics of all values
+        let period: Vec<f64> = (0..1000).map(|k| 1e9 + f64::from(k) * 0.1).collect();
+
+        let exact_values: Vec<BigRational> = period
+            .iter()
+            .map(|&value| BigRational::from_float(value).unwrap())
+            .collect();
+        let count = BigRational::from_integer(period.len().into());
+        let exact_mean = exact_values
+            .iter()
+            .fold(BigRational::from_integer(0.into()), |sum, value| {
+                sum + value
+            })
+            / &count;
+        let exact_var = exact_values
+            .iter()
+            .map(|value| (value - &exact_mean) * (value - &exact_mean))
+            .fold(BigRational::from_integer(0.into()), |sum, value| {
+                sum + value
+            })
+            / &count;
+
+        let mut number_statistics = NumberStatistics::default();
+        for _ in 0..1000 {
+            for &value in &period {
+                number_statistics.add(value);
+            }
+        }
+
+        let exact_mean = exact_mean.to_f64().unwrap();
+        let exact_var = exact_var.to_f64().unwrap();
+
+        assert!(
+            (number_statistics.mean() - exact_mean).abs() <= f64::EPSILON * exact_mean,
+            "{} != {exact_mean}",
+            number_statistics.mean()
+        );
+        assert!(
+            (number_statistics.var() - exact_var).abs() <= 1e-10 * exact_var,
+            "{} != {exact_var}",
+            number_statistics.var()
+        );
+    }
 }
@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, SpatialPartitioned,
+};
+use geoengine_datatypes::raster::{
+    GridBounds, GridIdx, Pixel, RasterTile2D, TileInformation, TilingSpecification,
+};
+
+use crate::engine::{QueryContext, QueryProcessor, RasterQueryProcessor};
+use crate::util::Result;
+
+/// Splits the spatial bounds of a raster query into blocks of whole tiles,
+/// s.t. each block covers at most `max_tiles_per_query` tiles per time step.
+/// The blocks are ordered row by row and each tile of the original query is part of exactly one block.
+pub fn split_raster_query(
+    query: RasterQueryRectangle,
+    tiling_specification: TilingSpecification,
+    max_tiles_per_query: usize,
+) -> Vec<RasterQueryRectangle> {
+    let tiling_strategy =
+        tiling_specification.strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+    let tile_grid = tiling_strategy.tile_grid_box(query.spatial_bounds);
+    let GridIdx([min_row, min_col]) = tile_grid.min_index();
+    let GridIdx([max_row, max_col]) = tile_grid.max_index();
+
+    let num_cols = (max_col - min_col + 1) as usize;
+    let max_tiles_per_query = max_tiles_per_query.max(1);
+
+    let block_width = num_cols.min(max_tiles_per_query) as isize;
+    let block_height = (max_tiles_per_query / block_width as usize).max(1) as isize;
+
+    let tile_partition = |row: isize, col: isize| {
+        TileInformation::new(
+            [row, col].into(),
+            tiling_strategy.tile_size_in_pixels,
+            tiling_strategy.geo_transform,
+        )
+        .spatial_partition()
+    };
+
+    let mut sub_queries = Vec::new();
+
+    for block_min_row in (min_row..=max_row).step_by(block_height as usize) {
+        let block_max_row = (block_min_row + block_height - 1).min(max_row);
+
+        for block_min_col in (min_col..=max_col).step_by(block_width as usize) {
+            let block_max_col = (block_min_col + block_width - 1).min(max_col);
+
+            let block = SpatialPartition2D::new_unchecked(
+                tile_partition(block_min_row, block_min_col).upper_left(),
+                tile_partition(block_max_row, block_max_col).lower_right(),
+            );
+
+            if let Some(spatial_bounds) = block.intersection(&query.spatial_bounds) {
+                sub_queries.push(RasterQueryRectangle {
+                    spatial_bounds,
+                    time_interval: query.time_interval,
+                    spatial_resolution: query.spatial_resolution,
+                });
+            }
+        }
+    }
+
+    sub_queries
+}
+
+/// A processor that splits large queries into sub-queries of at most `max_tiles_per_query` tiles
+/// and emits the tiles of one sub-query after another.
+/// Up to `max_parallel_queries` sub-queries are computed at once; with a value greater than one,
+/// the tiles of sub-queries that finished ahead of their turn are buffered in memory.
+///
+/// As the splitting is spatial, the tiles of queries with multiple time steps are ordered
+/// by sub-query first and by time second.
+pub struct SplitRasterQueryProcessor<T> {
+    source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    tiling_specification: TilingSpecification,
+    max_tiles_per_query: usize,
+    max_parallel_queries: usize,
+}
+
+impl<T> SplitRasterQueryProcessor<T> {
+    pub fn new(
+        source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+        tiling_specification: TilingSpecification,
+        max_tiles_per_query: usize,
+        max_parallel_queries: usize,
+    ) -> Self {
+        Self {
+            source,
+            tiling_specification,
+            max_tiles_per_query,
+            max_parallel_queries: max_parallel_queries.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for SplitRasterQueryProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let sub_queries =
+            split_raster_query(query, self.tiling_specification, self.max_tiles_per_query);
+
+        if sub_queries.len() <= 1 {
+            return self.source.raster_query(query, ctx).await;
+        }
+
+        if self.max_parallel_queries == 1 {
+            let stream = stream::iter(sub_queries)
+                .then(move |sub_query| self.source.raster_query(sub_query, ctx))
+                .try_flatten();
+
+            return Ok(stream.boxed());
+        }
+
+        let stream = stream::iter(sub_queries)
+            .map(move |sub_query| async move {
+                self.source
+                    .raster_query(sub_query, ctx)
+                    .await?
+                    .try_collect::<Vec<_>>()
+                    .await
+            })
+            .buffered(self.max_parallel_queries)
+            .map_ok(|tiles| stream::iter(tiles.into_iter().map(Ok)))
+            .try_flatten();
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{Grid2D, GridIdx2D, GridShape2D, RasterDataType};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn it_splits_queries_into_tile_blocks() {
+        let tiling_specification =
+            TilingSpecification::new((0., 0.).into(), GridShape2D::new([2, 2]));
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((0., 6.).into(), (5., 0.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        // 3 columns and 3 rows of tiles
+        let sub_queries = split_raster_query(query, tiling_specification, 6);
+        assert_eq!(
+            sub_queries
+                .iter()
+                .map(|q| q.spatial_bounds)
+                .collect::<Vec<_>>(),
+            vec![
+                SpatialPartition2D::new((0., 6.).into(), (5., 2.).into()).unwrap(),
+                SpatialPartition2D::new((0., 2.).into(), (5., 0.).into()).unwrap(),
+            ]
+        );
+
+        let sub_queries = split_raster_query(query, tiling_specification, 2);
+        assert_eq!(sub_queries.len(), 6);
+        assert_eq!(
+            sub_queries[1].spatial_bounds,
+            SpatialPartition2D::new((4., 6.).into(), (5., 4.).into()).unwrap()
+        );
+
+        assert_eq!(split_raster_query(query, tiling_specification, 9).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_emits_all_tiles_of_the_sub_queries() {
+        let tile_size_in_pixels = GridShape2D::new([2, 2]);
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), tile_size_in_pixels),
+        );
+
+        let tile_positions: Vec<GridIdx2D> = vec![
+            [-2, 0].into(),
+            [-2, 1].into(),
+            [-1, 0].into(),
+            [-1, 1].into(),
+        ];
+
+        let tiles: Vec<RasterTile2D<u8>> = tile_positions
+            .iter()
+            .zip(0_u8..)
+            .map(|(&global_tile_position, value)| {
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position,
+                        tile_size_in_pixels,
+                    },
+                    Grid2D::new(tile_size_in_pixels, vec![value; 4])
+                        .unwrap()
+                        .into(),
+                )
+            })
+            .collect();
+
+        let ctx = MockQueryContext::test_default();
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((0., 4.).into(), (4., 0.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        for max_parallel_queries in [1, 3] {
+            let source = MockRasterSource {
+                params: MockRasterSourceParams {
+                    data: tiles.clone(),
+                    result_descriptor: RasterResultDescriptor {
+                        data_type: RasterDataType::U8,
+                        spatial_reference: SpatialReference::epsg_4326().into(),
+                        measurement: Measurement::Unitless,
+                        bbox: None,
+                        time: None,
+                        resolution: None,
+                    },
+                },
+            }
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+            let processor = SplitRasterQueryProcessor::new(
+                source,
+                execution_context.tiling_specification,
+                1,
+                max_parallel_queries,
+            );
+
+            let result: Vec<RasterTile2D<u8>> = processor
+                .raster_query(query, &ctx)
+                .await
+                .unwrap()
+                .try_collect()
+                .await
+                .unwrap();
+
+            assert_eq!(
+                result
+                    .iter()
+                    .map(|tile| tile.tile_position)
+                    .collect::<Vec<_>>(),
+                tile_positions
+            );
+        }
+    }
+}
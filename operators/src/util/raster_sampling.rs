@@ -0,0 +1,254 @@
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, RasterQueryRectangle, SpatialPartition2D, SpatialPartitioned, SpatialResolution,
+    TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    GridIdx2D, GridIndexAccess, Pixel, RasterTile2D, TileInformation, TilingSpecification,
+};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{QueryContext, RasterQueryProcessor};
+use crate::util::Result;
+
+/// The value of a raster at a coordinate during a time interval.
+/// The value is missing if the pixel is no data or outside the raster.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterSample {
+    pub time: TimeInterval,
+    pub value: Option<f64>,
+}
+
+/// Samples a raster at single coordinates by querying only the pixel that contains each coordinate.
+/// Returns the samples of each time step for every coordinate.
+pub async fn sample_raster_at_coordinates<T>(
+    processor: &dyn RasterQueryProcessor<RasterType = T>,
+    coordinates: &[Coordinate2D],
+    time_interval: TimeInterval,
+    spatial_resolution: SpatialResolution,
+    query_ctx: &dyn QueryContext,
+) -> Result<Vec<Vec<RasterSample>>>
+where
+    T: Pixel,
+{
+    let mut samples = Vec::with_capacity(coordinates.len());
+
+    for &coordinate in coordinates {
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked(
+                coordinate,
+                Coordinate2D::new(
+                    coordinate.x + spatial_resolution.x,
+                    coordinate.y - spatial_resolution.y,
+                ),
+            ),
+            time_interval,
+            spatial_resolution,
+        };
+
+        let tiles: Vec<RasterTile2D<T>> = processor
+            .raster_query(query, query_ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut coordinate_samples: Vec<RasterSample> = Vec::new();
+        for tile in tiles {
+            if !tile.spatial_partition().contains_coordinate(&coordinate)
+                || coordinate_samples
+                    .last()
+                    .map_or(false, |sample| sample.time == tile.time)
+            {
+                continue;
+            }
+
+            coordinate_samples.push(RasterSample {
+                time: tile.time,
+                value: sample_tile(&tile, coordinate),
+            });
+        }
+
+        samples.push(coordinate_samples);
+    }
+
+    Ok(samples)
+}
+
+/// Queries the single tile at a position of the tiling grid that is defined by the tiling specification and the
+/// spatial resolution. Returns the tile of the first time step of the time interval or `None` if there is none.
+pub async fn query_raster_tile<T>(
+    processor: &dyn RasterQueryProcessor<RasterType = T>,
+    tile_position: GridIdx2D,
+    time_interval: TimeInterval,
+    spatial_resolution: SpatialResolution,
+    tiling_specification: TilingSpecification,
+    query_ctx: &dyn QueryContext,
+) -> Result<Option<RasterTile2D<T>>>
+where
+    T: Pixel,
+{
+    let tiling_strategy =
+        tiling_specification.strategy(spatial_resolution.x, -spatial_resolution.y);
+    let tile_information = TileInformation::new(
+        tile_position,
+        tiling_strategy.tile_size_in_pixels,
+        tiling_strategy.geo_transform,
+    );
+
+    let query = RasterQueryRectangle {
+        spatial_bounds: tile_information.spatial_partition(),
+        time_interval,
+        spatial_resolution,
+    };
+
+    let mut tiles = processor.raster_query(query, query_ctx).await?;
+
+    while let Some(tile) = tiles.next().await {
+        let tile = tile?;
+        if tile.tile_position == tile_position {
+            return Ok(Some(tile));
+        }
+    }
+
+    Ok(None)
+}
+
+fn sample_tile<T: Pixel>(tile: &RasterTile2D<T>, coordinate: Coordinate2D) -> Option<f64> {
+    let grid_idx = tile
+        .tile_information()
+        .tile_geo_transform()
+        .coordinate_to_grid_idx_2d(coordinate);
+
+    tile.get_at_grid_index(grid_idx)
+        .ok()
+        .flatten()
+        .map(AsPrimitive::as_)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, RasterOperator};
+    use crate::source::{
+        GradientDirection, SyntheticRasterPattern, SyntheticRasterSource,
+        SyntheticRasterSourceParameters,
+    };
+    use geoengine_datatypes::primitives::Measurement;
+    use geoengine_datatypes::raster::{GridShape2D, RasterDataType, TilingSpecification};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_samples_coordinates() {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), GridShape2D::new([2, 2])),
+        );
+
+        let processor = SyntheticRasterSource {
+            params: SyntheticRasterSourceParameters {
+                data_type: RasterDataType::F64,
+                spatial_reference: SpatialReference::epsg_4326(),
+                extent: Some(SpatialPartition2D::new((0., 2.).into(), (2., 0.).into()).unwrap()),
+                time: TimeInterval::default(),
+                resolution: None,
+                measurement: Measurement::Unitless,
+                pattern: SyntheticRasterPattern::Gradient {
+                    start: 0.,
+                    end: 4.,
+                    direction: GradientDirection::Horizontal,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_f64()
+        .unwrap();
+
+        let samples = sample_raster_at_coordinates(
+            processor.as_ref(),
+            &[(0.5, 1.5).into(), (1.5, 0.5).into(), (5., 1.).into()],
+            TimeInterval::default(),
+            SpatialResolution::one(),
+            &execution_context.mock_query_context(TestDefault::test_default()),
+        )
+        .await
+        .unwrap();
+
+        let values: Vec<Vec<Option<f64>>> = samples
+            .iter()
+            .map(|samples| samples.iter().map(|sample| sample.value).collect())
+            .collect();
+
+        assert_eq!(values, vec![vec![Some(1.)], vec![Some(3.)], vec![None]]);
+    }
+
+    #[tokio::test]
+    async fn it_queries_a_single_tile() {
+        let tiling_specification =
+            TilingSpecification::new((0., 0.).into(), GridShape2D::new([2, 2]));
+        let execution_context = MockExecutionContext::new_with_tiling_spec(tiling_specification);
+
+        let processor = SyntheticRasterSource {
+            params: SyntheticRasterSourceParameters {
+                data_type: RasterDataType::F64,
+                spatial_reference: SpatialReference::epsg_4326(),
+                extent: Some(SpatialPartition2D::new((0., 2.).into(), (4., 0.).into()).unwrap()),
+                time: TimeInterval::default(),
+                resolution: None,
+                measurement: Measurement::Unitless,
+                pattern: SyntheticRasterPattern::Gradient {
+                    start: 0.,
+                    end: 4.,
+                    direction: GradientDirection::Horizontal,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_f64()
+        .unwrap();
+
+        let query_ctx = execution_context.mock_query_context(TestDefault::test_default());
+
+        let tile = query_raster_tile(
+            processor.as_ref(),
+            [-1, 1].into(),
+            TimeInterval::default(),
+            SpatialResolution::one(),
+            tiling_specification,
+            &query_ctx,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(tile.tile_position, [-1, 1].into());
+        assert_eq!(
+            tile.tile_information().spatial_partition(),
+            SpatialPartition2D::new((2., 2.).into(), (4., 0.).into()).unwrap()
+        );
+
+        let empty = query_raster_tile(
+            processor.as_ref(),
+            [5, 5].into(),
+            TimeInterval::default(),
+            SpatialResolution::one(),
+            tiling_specification,
+            &query_ctx,
+        )
+        .await
+        .unwrap();
+
+        assert!(empty.unwrap().is_empty());
+    }
+}
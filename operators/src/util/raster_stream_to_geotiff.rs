@@ -5,6 +5,7 @@ use crate::{
     error::Error,
 };
 use futures::future::BoxFuture;
+use futures::stream::BoxStream;
 use futures::{StreamExt, TryFutureExt};
 use gdal::raster::{Buffer, GdalType, RasterCreationOption};
 use gdal::{Dataset, Driver};
@@ -105,7 +106,11 @@ where
     })
     .await?;
 
-    let tile_stream = processor.raster_query(query_rect, &query_ctx).await?;
+    let mut tile_stream = processor.raster_query(query_rect, &query_ctx).await?;
+    if gdal_tiff_options.as_cog {
+        tile_stream = single_time_step(tile_stream);
+    }
+
     let dataset_writer = tile_stream
         .enumerate()
         .fold(
@@ -136,6 +141,32 @@ where
     abortable_query_execution(written, conn_closed, query_abort_trigger).await?
 }
 
+/// Fails as soon as the `stream` contains tiles of a second time step
+fn single_time_step<'a, P: Pixel>(
+    stream: BoxStream<'a, Result<RasterTile2D<P>>>,
+) -> BoxStream<'a, Result<RasterTile2D<P>>> {
+    let mut first_time = None;
+
+    stream
+        .map(move |tile| {
+            let tile = tile?;
+
+            match first_time {
+                None => first_time = Some(tile.time),
+                Some(first) if first != tile.time => {
+                    return Err(Error::CogWithMultipleTimeSteps {
+                        first,
+                        second: tile.time,
+                    })
+                }
+                Some(_) => (),
+            }
+
+            Ok(tile)
+        })
+        .boxed()
+}
+
 const COG_BLOCK_SIZE: &str = "512";
 const COMPRESSION_FORMAT: &str = "LZW";
 const COMPRESSION_LEVEL: &str = "9"; // maximum compression
@@ -542,6 +573,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -597,6 +629,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -648,6 +681,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -703,6 +737,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -761,6 +796,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -808,6 +844,53 @@ mod tests {
         // TODO: check programmatically that intermediate file is gone
     }
 
+    #[tokio::test]
+    async fn cloud_optimized_geotiff_rejects_multiple_time_steps() {
+        let ctx = MockQueryContext::test_default();
+        let tiling_specification =
+            TilingSpecification::new(Coordinate2D::default(), [600, 600].into());
+
+        let metadata = create_ndvi_meta_data();
+
+        let gdal_source = GdalSourceProcessor::<u8> {
+            tiling_specification,
+            meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
+            _phantom_data: PhantomData,
+        };
+
+        let query_bbox = SpatialPartition2D::new((-10., 80.).into(), (50., 20.).into()).unwrap();
+
+        // January and February
+        let bytes = raster_stream_to_geotiff_bytes(
+            gdal_source.boxed(),
+            RasterQueryRectangle {
+                spatial_bounds: query_bbox,
+                time_interval: TimeInterval::new(1_388_534_400_000, 1_391_212_800_000 + 1000)
+                    .unwrap(),
+                spatial_resolution: SpatialResolution::new_unchecked(
+                    query_bbox.size_x() / 600.,
+                    query_bbox.size_y() / 600.,
+                ),
+            },
+            ctx,
+            GdalGeoTiffDatasetMetadata {
+                no_data_value: Some(0.),
+                spatial_reference: SpatialReference::epsg_4326(),
+            },
+            GdalGeoTiffOptions {
+                as_cog: true,
+                compression_num_threads: GdalCompressionNumThreads::NumThreads(1),
+                force_big_tiff: false,
+            },
+            None,
+            Box::pin(futures::future::pending()),
+        )
+        .await;
+
+        assert!(matches!(bytes, Err(Error::CogWithMultipleTimeSteps { .. })));
+    }
+
     #[tokio::test]
     async fn geotiff_from_stream_limit() {
         let ctx = MockQueryContext::test_default();
@@ -819,6 +902,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -864,6 +948,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(metadata),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
@@ -0,0 +1,504 @@
+use std::convert::TryInto;
+use std::path::{Path, PathBuf};
+
+use futures::future::BoxFuture;
+use futures::{TryFutureExt, TryStreamExt};
+use gdal::raster::{Buffer, GdalType, RasterCreationOption};
+use gdal::{Dataset, Driver, Metadata};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Measurement, RasterQueryRectangle, SpatialPartitioned, TimeInterval,
+};
+use geoengine_datatypes::raster::{GeoTransform, GridSize, Pixel, RasterTile2D};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use num_traits::AsPrimitive;
+
+use crate::engine::{QueryContext, RasterQueryProcessor};
+use crate::error::{self, Error};
+use crate::util::Result;
+
+use super::abortable_query_execution;
+
+const CF_TIME_UNITS: &str = "milliseconds since 1970-01-01 00:00:00";
+const INTERMEDIATE_FILE_SUFFIX: &str = "GEO-ENGINE-TMP";
+
+#[derive(Debug, Clone)]
+pub struct NetCdfCfDatasetMetadata {
+    /// The name of the data variable
+    pub variable_name: String,
+    /// The fill value of the variable, defaults to the minimum value of the data type
+    pub no_data_value: Option<f64>,
+    pub spatial_reference: SpatialReference,
+    /// Determines the `units`, `long_name` and flag attributes of the variable
+    pub measurement: Measurement,
+}
+
+/// Writes the tiles of a raster query into a CF-compliant NetCDF-4 file with a `time` dimension.
+///
+/// The time coordinate is the start of each time step and the CRS is written as grid mapping variable.
+/// As the number of time steps is unknown until the stream ends, each time step is written to a
+/// temporary file as its tiles arrive and the files are combined into the NetCDF file at the end.
+#[allow(clippy::too_many_arguments)]
+pub async fn raster_stream_to_netcdf<P, C: QueryContext + 'static>(
+    file_path: &Path,
+    processor: Box<dyn RasterQueryProcessor<RasterType = P>>,
+    query_rect: RasterQueryRectangle,
+    mut query_ctx: C,
+    netcdf_metadata: NetCdfCfDatasetMetadata,
+    tile_limit: Option<usize>,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<()>
+where
+    P: Pixel + GdalType,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let file_path = file_path.to_owned();
+
+    let written = async move {
+        let writer = NetCdfTimeSeriesWriter::<P>::new(file_path, query_rect, netcdf_metadata);
+
+        let tile_stream = processor.raster_query(query_rect, &query_ctx).await?;
+
+        let mut tile_index = 0;
+        let writer = tile_stream
+            .try_fold(writer, |writer, tile| {
+                let limit_exceeded = tile_limit.map_or(false, |limit| tile_index >= limit);
+                tile_index += 1;
+
+                async move {
+                    if limit_exceeded {
+                        return Err(Error::TileLimitExceeded {
+                            limit: tile_limit.expect("limit exist because it is exceeded"),
+                        });
+                    }
+
+                    crate::util::spawn_blocking(move || -> Result<NetCdfTimeSeriesWriter<P>> {
+                        let mut writer = writer;
+                        writer.write_tile(tile)?;
+                        Ok(writer)
+                    })
+                    .await?
+                }
+            })
+            .await?;
+
+        crate::util::spawn_blocking(move || writer.finish())
+            .map_err(|source| error::Error::TokioJoin { source })
+            .await?
+    };
+
+    abortable_query_execution(written, conn_closed, query_abort_trigger).await
+}
+
+/// A time step that is written to a temporary file until all time steps are combined
+#[derive(Debug)]
+struct TimeStepFile {
+    time: TimeInterval,
+    path: PathBuf,
+    dataset: Dataset,
+}
+
+#[derive(Debug)]
+struct NetCdfTimeSeriesWriter<P: Pixel> {
+    file_path: PathBuf,
+    metadata: NetCdfCfDatasetMetadata,
+    geo_transform: GeoTransform,
+    width: usize,
+    height: usize,
+    fill_value: P,
+    query_time: TimeInterval,
+    /// the files of the time steps, ordered by time
+    time_steps: Vec<TimeStepFile>,
+    /// the number of created time step files for naming them uniquely
+    num_created_files: usize,
+}
+
+impl<P: Pixel + GdalType> NetCdfTimeSeriesWriter<P> {
+    fn new(
+        file_path: PathBuf,
+        query_rect: RasterQueryRectangle,
+        metadata: NetCdfCfDatasetMetadata,
+    ) -> Self {
+        let x_pixel_size = query_rect.spatial_resolution.x;
+        let y_pixel_size = query_rect.spatial_resolution.y;
+
+        let fill_value = metadata
+            .no_data_value
+            .map_or_else(P::min_value, |no_data_value| P::from_(no_data_value));
+
+        Self {
+            file_path,
+            geo_transform: GeoTransform::new(
+                query_rect.spatial_bounds.upper_left(),
+                x_pixel_size,
+                -y_pixel_size,
+            ),
+            width: (query_rect.spatial_bounds.size_x() / x_pixel_size).ceil() as usize,
+            height: (query_rect.spatial_bounds.size_y() / y_pixel_size).ceil() as usize,
+            fill_value,
+            query_time: query_rect.time_interval,
+            metadata,
+            time_steps: Vec::new(),
+            num_created_files: 0,
+        }
+    }
+
+    /// Returns the index of the file of the time step `time` and creates it if it does not exist yet
+    fn time_step_index(&mut self, time: TimeInterval) -> Result<usize> {
+        if let Some(index) = self.time_steps.iter().position(|step| step.time == time) {
+            return Ok(index);
+        }
+
+        let path = self.file_path.with_extension(format!(
+            "{}.{INTERMEDIATE_FILE_SUFFIX}.tiff",
+            self.num_created_files
+        ));
+        self.num_created_files += 1;
+
+        let dataset = Driver::get_by_name("GTiff")?.create_with_band_type::<P, _>(
+            &path,
+            self.width as isize,
+            self.height as isize,
+            1,
+        )?;
+        // blocks that are never written are filled with the no-data value
+        dataset
+            .rasterband(1)?
+            .set_no_data_value(self.fill_value.as_())?;
+
+        let index = self
+            .time_steps
+            .partition_point(|step| step.time.start() < time.start());
+        self.time_steps.insert(
+            index,
+            TimeStepFile {
+                time,
+                path,
+                dataset,
+            },
+        );
+
+        Ok(index)
+    }
+
+    fn write_tile(&mut self, tile: RasterTile2D<P>) -> Result<()> {
+        let index = self.time_step_index(tile.time)?;
+
+        if tile.is_empty() {
+            return Ok(());
+        }
+
+        let tile_info = tile.tile_information();
+        let tile_upper_left = tile_info.spatial_partition().upper_left();
+        let tile_width = tile_info.tile_size_in_pixels.axis_size_x();
+        let tile_height = tile_info.tile_size_in_pixels.axis_size_y();
+
+        let offset_x = ((tile_upper_left.x - self.geo_transform.origin_coordinate.x)
+            / self.geo_transform.x_pixel_size())
+        .round() as isize;
+        let offset_y = ((tile_upper_left.y - self.geo_transform.origin_coordinate.y)
+            / self.geo_transform.y_pixel_size())
+        .round() as isize;
+
+        // the part of the tile that is inside of the output
+        let x_start = offset_x.max(0);
+        let y_start = offset_y.max(0);
+        let x_end = (offset_x + tile_width as isize).min(self.width as isize);
+        let y_end = (offset_y + tile_height as isize).min(self.height as isize);
+
+        if x_start >= x_end || y_start >= y_end {
+            return Ok(());
+        }
+
+        let fill_value = self.fill_value;
+        let grid = tile.into_materialized_tile().grid_array;
+
+        let pixels: Vec<P> = grid
+            .masked_element_deref_iterator()
+            .enumerate()
+            .filter_map(|(tile_pixel_index, value)| {
+                let x = offset_x + (tile_pixel_index % tile_width) as isize;
+                let y = offset_y + (tile_pixel_index / tile_width) as isize;
+
+                (x >= x_start && x < x_end && y >= y_start && y < y_end)
+                    .then_some(value.unwrap_or(fill_value))
+            })
+            .collect();
+
+        let window_size = ((x_end - x_start) as usize, (y_end - y_start) as usize);
+
+        self.time_steps[index].dataset.rasterband(1)?.write(
+            (x_start, y_start),
+            window_size,
+            &Buffer::new(window_size, pixels),
+        )?;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        if self.time_steps.is_empty() {
+            self.time_step_index(self.query_time)?;
+        }
+
+        // closes the files of the time steps
+        let time_steps: Vec<(TimeInterval, PathBuf)> = self
+            .time_steps
+            .drain(..)
+            .map(|step| (step.time, step.path))
+            .collect();
+
+        let written = self.write_netcdf(&time_steps);
+
+        for (_, path) in &time_steps {
+            let _ = std::fs::remove_file(path);
+        }
+
+        written
+    }
+
+    /// Combines the files of the `time_steps` into the NetCDF file
+    fn write_netcdf(&self, time_steps: &[(TimeInterval, PathBuf)]) -> Result<()> {
+        // a virtual dataset with one band per time step that reads from the files
+        let vrt_driver = Driver::get_by_name("VRT")?;
+        let mut dataset = vrt_driver.create_with_band_type::<P, _>(
+            "",
+            self.width as isize,
+            self.height as isize,
+            time_steps.len() as isize,
+        )?;
+
+        dataset.set_spatial_ref(&self.metadata.spatial_reference.try_into()?)?;
+        dataset.set_geo_transform(&self.geo_transform.into())?;
+
+        // the driver creates the `time` dimension and its variable from these items
+        let time_values: Vec<String> = time_steps
+            .iter()
+            .map(|(time, _)| time.start().inner().to_string())
+            .collect();
+        dataset.set_metadata_item("NETCDF_DIM_EXTRA", "{time}", "")?;
+        dataset.set_metadata_item(
+            "NETCDF_DIM_time_DEF",
+            &format!("{{{},6}}", time_values.len()),
+            "",
+        )?;
+        dataset.set_metadata_item(
+            "NETCDF_DIM_time_VALUES",
+            &format!("{{{}}}", time_values.join(",")),
+            "",
+        )?;
+        dataset.set_metadata_item("time#standard_name", "time", "")?;
+        dataset.set_metadata_item("time#units", CF_TIME_UNITS, "")?;
+        dataset.set_metadata_item("time#calendar", "proleptic_gregorian", "")?;
+        dataset.set_metadata_item("NC_GLOBAL#Conventions", "CF-1.7", "")?;
+
+        let fill_value: f64 = self.fill_value.as_();
+
+        for (band_index, (time_value, (_, path))) in time_values.iter().zip(time_steps).enumerate()
+        {
+            let mut band = dataset.rasterband(band_index as isize + 1)?;
+            band.set_metadata_item(
+                "source_0",
+                &format!(
+                    "<SimpleSource><SourceFilename relativeToVRT=\"0\">{}</SourceFilename><SourceBand>1</SourceBand></SimpleSource>",
+                    path.display()
+                ),
+                "new_vrt_sources",
+            )?;
+            band.set_no_data_value(fill_value)?;
+            band.set_metadata_item("NETCDF_VARNAME", &self.metadata.variable_name, "")?;
+            band.set_metadata_item("NETCDF_DIM_time", time_value, "")?;
+
+            for (key, value) in measurement_attributes(&self.metadata.measurement) {
+                band.set_metadata_item(key, &value, "")?;
+            }
+        }
+
+        let netcdf_driver = Driver::get_by_name("netCDF")?;
+        dataset.create_copy(
+            &netcdf_driver,
+            &self.file_path,
+            &[
+                RasterCreationOption {
+                    key: "FORMAT",
+                    value: "NC4",
+                },
+                RasterCreationOption {
+                    key: "COMPRESS",
+                    value: "DEFLATE",
+                },
+            ],
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<P: Pixel> Drop for NetCdfTimeSeriesWriter<P> {
+    fn drop(&mut self) {
+        // removes the files of the time steps if the writing was aborted
+        for step in &self.time_steps {
+            let _ = std::fs::remove_file(&step.path);
+        }
+    }
+}
+
+/// The CF attributes of the data variable that describe the measurement
+fn measurement_attributes(measurement: &Measurement) -> Vec<(&'static str, String)> {
+    match measurement {
+        Measurement::Unitless => vec![],
+        Measurement::Continuous(measurement) => {
+            let mut attributes = vec![("long_name", measurement.measurement.clone())];
+            if let Some(unit) = &measurement.unit {
+                attributes.push(("units", unit.clone()));
+            }
+            attributes
+        }
+        Measurement::Classification(measurement) => {
+            let mut classes: Vec<(&u8, &String)> = measurement.classes.iter().collect();
+            classes.sort();
+
+            vec![
+                ("long_name", measurement.measurement.clone()),
+                (
+                    "flag_values",
+                    classes
+                        .iter()
+                        .map(|(value, _)| value.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                (
+                    "flag_meanings",
+                    classes
+                        .iter()
+                        .map(|(_, name)| name.replace(' ', "_"))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{SpatialPartition2D, SpatialResolution};
+    use geoengine_datatypes::raster::{
+        Grid2D, GridShape2D, RasterDataType, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_writes_netcdf_time_series() {
+        let tile_size_in_pixels = GridShape2D::new([2, 2]);
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), tile_size_in_pixels),
+        );
+
+        let tiles: Vec<RasterTile2D<u8>> = [0, 1000]
+            .into_iter()
+            .map(|time| {
+                RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(time, time + 1000),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels,
+                    },
+                    Grid2D::new(tile_size_in_pixels, vec![1, 2, 3, 4])
+                        .unwrap()
+                        .into(),
+                )
+            })
+            .collect();
+
+        let processor = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    bbox: None,
+                    time: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("raster.nc");
+
+        raster_stream_to_netcdf(
+            &file_path,
+            processor,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into()).unwrap(),
+                time_interval: TimeInterval::new_unchecked(0, 2000),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            MockQueryContext::test_default(),
+            NetCdfCfDatasetMetadata {
+                variable_name: "data".to_string(),
+                no_data_value: Some(0.),
+                spatial_reference: SpatialReference::epsg_4326(),
+                measurement: Measurement::continuous("temperature".to_string(), Some("K".into())),
+            },
+            None,
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        // the files of the time steps are removed
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let dataset = Dataset::open(&file_path).unwrap();
+
+        assert_eq!(dataset.raster_count(), 2);
+        assert_eq!(
+            dataset.metadata_item("time#units", "").as_deref(),
+            Some(CF_TIME_UNITS)
+        );
+
+        let band = dataset.rasterband(2).unwrap();
+        assert_eq!(band.metadata_item("units", "").as_deref(), Some("K"));
+        assert_eq!(
+            band.read_as::<u8>((0, 0), (2, 2), (2, 2), None)
+                .unwrap()
+                .data,
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn it_describes_classifications() {
+        let measurement = Measurement::classification(
+            "land cover".to_string(),
+            [(2, "open water".to_string()), (1, "forest".to_string())].into(),
+        );
+
+        assert_eq!(
+            measurement_attributes(&measurement),
+            vec![
+                ("long_name", "land cover".to_string()),
+                ("flag_values", "1 2".to_string()),
+                ("flag_meanings", "forest open_water".to_string()),
+            ]
+        );
+    }
+}
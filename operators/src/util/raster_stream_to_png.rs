@@ -119,6 +119,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification,
             meta_data: Box::new(create_ndvi_meta_data()),
+            io_thread_pool: ctx.thread_pool.clone(),
             _phantom_data: PhantomData,
         };
 
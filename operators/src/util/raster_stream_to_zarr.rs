@@ -0,0 +1,372 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use futures::future::BoxFuture;
+use futures::{TryFutureExt, TryStreamExt};
+use geoengine_datatypes::primitives::{RasterQueryRectangle, TimeInterval};
+use geoengine_datatypes::raster::{
+    GeoTransform, GridBounds, GridIdx, GridIdx2D, GridShape2D, GridSize, NoDataValueGrid, Pixel,
+    RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use num_traits::AsPrimitive;
+use serde_json::json;
+
+use crate::engine::{QueryContext, RasterQueryProcessor};
+use crate::error;
+use crate::util::Result;
+
+use super::abortable_query_execution;
+
+/// The name of the array inside the Zarr group
+const ZARR_ARRAY_NAME: &str = "data";
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZarrDatasetMetadata {
+    /// The fill value of the array, defaults to the minimum value of the data type
+    pub no_data_value: Option<f64>,
+    pub spatial_reference: SpatialReference,
+}
+
+/// Writes the tiles of a raster query into a Zarr (v2) store at `store_path`.
+///
+/// The store is a group with a three-dimensional array `data` of the axes `time`, `y` and `x`.
+/// Each chunk holds exactly one tile of one time step, so the array covers all tiles that intersect the query.
+/// Empty tiles are not written and thus are read as the fill value.
+/// The chunks are stored uncompressed with `.` as dimension separator.
+pub async fn raster_stream_to_zarr<P, C: QueryContext + 'static>(
+    store_path: &Path,
+    processor: Box<dyn RasterQueryProcessor<RasterType = P>>,
+    query_rect: RasterQueryRectangle,
+    mut query_ctx: C,
+    zarr_metadata: ZarrDatasetMetadata,
+    tiling_specification: TilingSpecification,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<()>
+where
+    P: Pixel,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let store_path = store_path.to_owned();
+
+    let written = async move {
+        let store_writer = crate::util::spawn_blocking(move || {
+            ZarrStoreWriter::<P>::new(&store_path, query_rect, zarr_metadata, tiling_specification)
+        })
+        .await??;
+
+        let tile_stream = processor.raster_query(query_rect, &query_ctx).await?;
+
+        let store_writer = tile_stream
+            .try_fold(store_writer, |mut store_writer, tile| async move {
+                crate::util::spawn_blocking(move || -> Result<ZarrStoreWriter<P>> {
+                    store_writer.write_tile(tile)?;
+                    Ok(store_writer)
+                })
+                .await?
+            })
+            .await?;
+
+        crate::util::spawn_blocking(move || store_writer.finish())
+            .map_err(|source| error::Error::TokioJoin { source })
+            .await?
+    };
+
+    abortable_query_execution(written, conn_closed, query_abort_trigger).await
+}
+
+#[derive(Debug)]
+struct ZarrStoreWriter<P: Pixel> {
+    array_path: PathBuf,
+    store_path: PathBuf,
+    /// global position of the upper left tile of the array
+    origin_tile: GridIdx2D,
+    /// number of tiles in y and x direction
+    tile_grid_shape: GridShape2D,
+    tile_size_in_pixels: GridShape2D,
+    geo_transform: GeoTransform,
+    fill_value: P,
+    spatial_reference: SpatialReference,
+    time_steps: Vec<TimeInterval>,
+}
+
+impl<P: Pixel> ZarrStoreWriter<P> {
+    fn new(
+        store_path: &Path,
+        query_rect: RasterQueryRectangle,
+        zarr_metadata: ZarrDatasetMetadata,
+        tiling_specification: TilingSpecification,
+    ) -> Result<Self> {
+        let tiling_strategy = tiling_specification.strategy(
+            query_rect.spatial_resolution.x,
+            -query_rect.spatial_resolution.y,
+        );
+        let tile_grid = tiling_strategy.tile_grid_box(query_rect.spatial_bounds);
+
+        let origin_tile = tile_grid.min_index();
+        let geo_transform = TileInformation::new(
+            origin_tile,
+            tiling_strategy.tile_size_in_pixels,
+            tiling_strategy.geo_transform,
+        )
+        .tile_geo_transform();
+
+        let array_path = store_path.join(ZARR_ARRAY_NAME);
+        fs::create_dir_all(&array_path)?;
+
+        write_json(&store_path.join(".zgroup"), &json!({ "zarr_format": 2 }))?;
+
+        Ok(Self {
+            array_path,
+            store_path: store_path.to_owned(),
+            origin_tile,
+            tile_grid_shape: GridShape2D::new(tile_grid.axis_size()),
+            tile_size_in_pixels: tiling_strategy.tile_size_in_pixels,
+            geo_transform,
+            fill_value: zarr_metadata
+                .no_data_value
+                .map_or_else(P::min_value, |no_data_value| P::from_(no_data_value)),
+            spatial_reference: zarr_metadata.spatial_reference,
+            time_steps: Vec::new(),
+        })
+    }
+
+    fn write_tile(&mut self, tile: RasterTile2D<P>) -> Result<()> {
+        // the order of tiles depends on the processor, so we cannot simply count the time steps
+        let time_index = if let Some(index) = self.time_steps.iter().position(|t| *t == tile.time) {
+            index
+        } else {
+            self.time_steps.push(tile.time);
+            self.time_steps.len() - 1
+        };
+
+        if tile.is_empty() {
+            return Ok(());
+        }
+
+        let GridIdx([row, col]) = tile.tile_position - self.origin_tile;
+        let chunk_path = self
+            .array_path
+            .join(format!("{}.{}.{}", time_index, row, col));
+
+        let grid = NoDataValueGrid::from_masked_grid(
+            &tile.into_materialized_tile().grid_array,
+            self.fill_value,
+        );
+
+        fs::write(chunk_path, pixels_to_le_bytes(&grid.inner_grid.data))?;
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        let [tile_rows, tile_cols] = self.tile_size_in_pixels.shape_array;
+
+        write_json(
+            &self.array_path.join(".zarray"),
+            &json!({
+                "zarr_format": 2,
+                "shape": [
+                    self.time_steps.len(),
+                    self.tile_grid_shape.axis_size_y() * tile_rows,
+                    self.tile_grid_shape.axis_size_x() * tile_cols,
+                ],
+                "chunks": [1, tile_rows, tile_cols],
+                "dtype": zarr_data_type(P::TYPE),
+                "compressor": null,
+                "fill_value": zarr_fill_value(self.fill_value),
+                "order": "C",
+                "filters": null,
+                "dimension_separator": ".",
+            }),
+        )?;
+
+        write_json(
+            &self.array_path.join(".zattrs"),
+            &json!({
+                // dimension names as used by `xarray`
+                "_ARRAY_DIMENSIONS": ["time", "y", "x"],
+                "time": self.time_steps,
+            }),
+        )?;
+
+        let origin_coordinate = self.geo_transform.origin_coordinate;
+
+        write_json(
+            &self.store_path.join(".zattrs"),
+            &json!({
+                "spatial_reference": self.spatial_reference.to_string(),
+                // in GDAL order
+                "geo_transform": [
+                    origin_coordinate.x,
+                    self.geo_transform.x_pixel_size(),
+                    0.,
+                    origin_coordinate.y,
+                    0.,
+                    self.geo_transform.y_pixel_size(),
+                ],
+            }),
+        )
+    }
+}
+
+fn write_json(path: &Path, value: &serde_json::Value) -> Result<()> {
+    fs::write(path, serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
+/// The Zarr data type of the pixel type in little endian
+fn zarr_data_type(data_type: RasterDataType) -> &'static str {
+    match data_type {
+        RasterDataType::U8 => "|u1",
+        RasterDataType::U16 => "<u2",
+        RasterDataType::U32 => "<u4",
+        RasterDataType::U64 => "<u8",
+        RasterDataType::I8 => "|i1",
+        RasterDataType::I16 => "<i2",
+        RasterDataType::I32 => "<i4",
+        RasterDataType::I64 => "<i8",
+        RasterDataType::F32 => "<f4",
+        RasterDataType::F64 => "<f8",
+    }
+}
+
+/// Zarr encodes non-finite fill values as strings
+fn zarr_fill_value<P: Pixel>(fill_value: P) -> serde_json::Value {
+    match P::TYPE {
+        RasterDataType::F32 | RasterDataType::F64 => {
+            let value: f64 = fill_value.as_();
+            if value.is_nan() {
+                json!("NaN")
+            } else if value.is_infinite() {
+                json!(if value > 0. { "Infinity" } else { "-Infinity" })
+            } else {
+                json!(value)
+            }
+        }
+        RasterDataType::U64 => json!(AsPrimitive::<u64>::as_(fill_value)),
+        _ => json!(AsPrimitive::<i64>::as_(fill_value)),
+    }
+}
+
+fn pixels_to_le_bytes<P: Pixel>(pixels: &[P]) -> Vec<u8> {
+    fn to_bytes<P, T, const N: usize>(pixels: &[P], to_le_bytes: fn(T) -> [u8; N]) -> Vec<u8>
+    where
+        P: AsPrimitive<T>,
+        T: Copy + 'static,
+    {
+        pixels
+            .iter()
+            .flat_map(|pixel| to_le_bytes(pixel.as_()))
+            .collect()
+    }
+
+    match P::TYPE {
+        RasterDataType::U8 => to_bytes(pixels, u8::to_le_bytes),
+        RasterDataType::U16 => to_bytes(pixels, u16::to_le_bytes),
+        RasterDataType::U32 => to_bytes(pixels, u32::to_le_bytes),
+        RasterDataType::U64 => to_bytes(pixels, u64::to_le_bytes),
+        RasterDataType::I8 => to_bytes(pixels, i8::to_le_bytes),
+        RasterDataType::I16 => to_bytes(pixels, i16::to_le_bytes),
+        RasterDataType::I32 => to_bytes(pixels, i32::to_le_bytes),
+        RasterDataType::I64 => to_bytes(pixels, i64::to_le_bytes),
+        RasterDataType::F32 => to_bytes(pixels, f32::to_le_bytes),
+        RasterDataType::F64 => to_bytes(pixels, f64::to_le_bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D, SpatialResolution};
+    use geoengine_datatypes::raster::Grid2D;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_writes_zarr_stores() {
+        let tile_size_in_pixels = GridShape2D::new([2, 2]);
+        let tiling_specification = TilingSpecification::new((0., 0.).into(), tile_size_in_pixels);
+        let execution_context = MockExecutionContext::new_with_tiling_spec(tiling_specification);
+
+        let tiles: Vec<RasterTile2D<u8>> = [0, 1]
+            .into_iter()
+            .flat_map(|time| {
+                [0, 1].into_iter().map(move |col| {
+                    RasterTile2D::new_with_tile_info(
+                        TimeInterval::new_unchecked(time, time + 1),
+                        TileInformation {
+                            global_geo_transform: TestDefault::test_default(),
+                            global_tile_position: [-1, col].into(),
+                            tile_size_in_pixels,
+                        },
+                        Grid2D::new(tile_size_in_pixels, vec![col as u8 + 1; 4])
+                            .unwrap()
+                            .into(),
+                    )
+                })
+            })
+            .collect();
+
+        let processor = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    bbox: None,
+                    time: None,
+                    resolution: None,
+                },
+            },
+        }
+        .boxed()
+        .initialize(&execution_context)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .get_u8()
+        .unwrap();
+
+        let store = tempfile::tempdir().unwrap();
+
+        raster_stream_to_zarr(
+            store.path(),
+            processor,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (4., 0.).into()).unwrap(),
+                time_interval: TimeInterval::new_unchecked(0, 2),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            MockQueryContext::test_default(),
+            ZarrDatasetMetadata {
+                no_data_value: Some(0.),
+                spatial_reference: SpatialReference::epsg_4326(),
+            },
+            tiling_specification,
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        let zarray: serde_json::Value =
+            serde_json::from_slice(&fs::read(store.path().join("data/.zarray")).unwrap()).unwrap();
+
+        assert_eq!(zarray["shape"], json!([2, 2, 4]));
+        assert_eq!(zarray["chunks"], json!([1, 2, 2]));
+        assert_eq!(zarray["dtype"], "|u1");
+        assert_eq!(zarray["fill_value"], 0);
+
+        assert_eq!(
+            fs::read(store.path().join("data/1.0.1")).unwrap(),
+            vec![2; 4]
+        );
+        assert!(store.path().join(".zgroup").exists());
+    }
+}
@@ -1,3 +1,4 @@
+use geoengine_datatypes::raster::{GridOrEmpty, RasterTile2D};
 use num_traits::AsPrimitive;
 use snafu::Snafu;
 use std::marker::PhantomData;
@@ -485,9 +486,224 @@ impl PSquareHistogramBucket {
     }
 }
 
+/// A centroid of a [`TDigest`], i.e., the mean of `weight` samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Approximate quantile estimation with a merging t-digest
+///
+/// The t-digest summarizes a sample by a bounded number of weighted centroids. The centroids near
+/// the tails of the distribution are kept small, so that extreme quantiles are estimated accurately,
+/// while the centroids in the middle may grow large. In contrast to the P^2 algorithm, the
+/// quantile does not need to be known in advance and digests of partial samples can be merged,
+/// e.g., the digests of the tiles of a raster.
+///
+/// For further details, see
+///
+/// T. Dunning and O. Ertl, Computing Extremely Accurate Quantiles Using t-Digests, 2019.
+/// <https://arxiv.org/abs/1902.04023>
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(100.)
+    }
+}
+
+impl TDigest {
+    /// Creates a new, empty digest. The `compression` bounds the number of centroids.
+    /// A higher compression results in more accurate estimates but requires more memory.
+    ///
+    /// # Panics
+    /// If the compression is not positive.
+    pub fn new(compression: f64) -> Self {
+        assert!(compression > 0., "the compression must be positive");
+
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Adds a sample to the digest. `NaN` values are ignored.
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(value);
+
+        if self.buffer.len() as f64 >= 5. * self.compression {
+            self.compress();
+        }
+    }
+
+    /// Adds the samples of another digest to this digest.
+    pub fn merge(&mut self, other: &TDigest) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+        self.compress();
+    }
+
+    /// Returns the number of samples.
+    pub fn count(&self) -> u64 {
+        let weight: f64 = self.centroids.iter().map(|c| c.weight).sum();
+        weight as u64 + self.buffer.len() as u64
+    }
+
+    /// Returns the minimum of the samples or `None` if the digest is empty.
+    pub fn min(&self) -> Option<f64> {
+        (self.count() > 0).then_some(self.min)
+    }
+
+    /// Returns the maximum of the samples or `None` if the digest is empty.
+    pub fn max(&self) -> Option<f64> {
+        (self.count() > 0).then_some(self.max)
+    }
+
+    /// Estimates the `q`-quantile, with `q` in `[0, 1]`, or returns `None` if the digest is empty.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if !self.buffer.is_empty() {
+            let mut compressed = self.clone();
+            compressed.compress();
+            return compressed.quantile(q);
+        }
+
+        if self.centroids.is_empty() {
+            return None;
+        }
+
+        if q <= 0. {
+            return Some(self.min);
+        }
+        if q >= 1. {
+            return Some(self.max);
+        }
+
+        let total: f64 = self.centroids.iter().map(|c| c.weight).sum();
+        let target = q * total;
+
+        // interpolate between the centers of the centroids, with the extrema at the ends
+        let mut previous = (0., self.min);
+        let mut cumulative = 0.;
+        for centroid in self.centroids.iter().chain(std::iter::once(&Centroid {
+            mean: self.max,
+            weight: 0.,
+        })) {
+            let center = if centroid.weight > 0. {
+                cumulative + centroid.weight / 2.
+            } else {
+                total
+            };
+
+            if target <= center {
+                let (previous_center, previous_mean) = previous;
+                if center <= previous_center {
+                    return Some(centroid.mean);
+                }
+                let fraction = (target - previous_center) / (center - previous_center);
+                return Some(previous_mean + fraction * (centroid.mean - previous_mean));
+            }
+
+            previous = (center, centroid.mean);
+            cumulative += centroid.weight;
+        }
+
+        Some(self.max)
+    }
+
+    /// Merges the buffered samples into the centroids, such that the centroids respect the size limit of the `k_1` scale function
+    fn compress(&mut self) {
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1. }),
+        );
+
+        if centroids.is_empty() {
+            return;
+        }
+
+        centroids.sort_unstable_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+
+        let mut merged = Vec::with_capacity(centroids.len().min(self.compression as usize * 2));
+        let mut centroids = centroids.into_iter();
+        let mut current = centroids.next().expect("checked for emptiness");
+        let mut weight_so_far = 0.;
+        let mut limit = total * self.q_limit(0.);
+
+        for next in centroids {
+            if weight_so_far + current.weight + next.weight <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                limit = total * self.q_limit(weight_so_far / total);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+    }
+
+    /// The largest quantile up to which a centroid that starts at quantile `q` may extend
+    fn q_limit(&self, q: f64) -> f64 {
+        let delta = self.compression;
+        let k = delta / (2. * std::f64::consts::PI) * (2. * q - 1.).asin() + 1.;
+
+        if k >= delta / 4. {
+            1.
+        } else {
+            ((k * 2. * std::f64::consts::PI / delta).sin() + 1.) / 2.
+        }
+    }
+}
+
+impl FromIterator<f64> for TDigest {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut digest = Self::default();
+        for value in iter {
+            digest.add(value);
+        }
+        digest
+    }
+}
+
+/// The digest of the valid pixel values of a tile
+pub fn tile_digest(tile: &RasterTile2D<f64>) -> TDigest {
+    match &tile.grid_array {
+        GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().flatten().collect(),
+        GridOrEmpty::Empty(_) => TDigest::default(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::statistics::{PSquareHistogram, PSquareQuantileEstimator};
+    use crate::util::statistics::{PSquareHistogram, PSquareQuantileEstimator, TDigest};
     use rand::seq::SliceRandom;
 
     #[test]
@@ -735,4 +951,58 @@ mod tests {
 
         assert_eq!(samples + 1, estimator.sample_count());
     }
+
+    #[test]
+    fn t_digest_estimates_quantiles() {
+        let mut values: Vec<f64> = (0..10_000).map(f64::from).collect();
+        values.shuffle(&mut rand::thread_rng());
+
+        let mut digest = TDigest::default();
+        for &value in &values {
+            digest.add(value);
+        }
+
+        assert_eq!(digest.count(), 10_000);
+        assert_eq!(digest.min(), Some(0.));
+        assert_eq!(digest.max(), Some(9999.));
+        assert_eq!(digest.quantile(0.), Some(0.));
+        assert_eq!(digest.quantile(1.), Some(9999.));
+
+        for q in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99] {
+            let estimate = digest.quantile(q).unwrap();
+            assert!((estimate - q * 9999.).abs() < 50., "{q}: {estimate}");
+        }
+    }
+
+    #[test]
+    fn t_digest_merges() {
+        let mut lower = TDigest::default();
+        let mut upper = TDigest::default();
+        for value in 0..1000 {
+            lower.add(f64::from(value));
+            upper.add(f64::from(value + 1000));
+        }
+
+        lower.merge(&upper);
+
+        assert_eq!(lower.count(), 2000);
+        assert_eq!(lower.min(), Some(0.));
+        assert_eq!(lower.max(), Some(1999.));
+        assert!((lower.quantile(0.5).unwrap() - 1000.).abs() < 10.);
+    }
+
+    #[test]
+    fn t_digest_interpolates_small_samples() {
+        let mut digest = TDigest::default();
+        assert_eq!(digest.quantile(0.5), None);
+        assert_eq!(digest.min(), None);
+
+        for value in [4., 2., f64::NAN, 3., 1.] {
+            digest.add(value);
+        }
+
+        assert_eq!(digest.count(), 4);
+        assert!((digest.quantile(0.4).unwrap() - 2.1).abs() < 1e-10);
+        assert!((digest.quantile(0.5).unwrap() - 2.5).abs() < 1e-10);
+    }
 }
@@ -0,0 +1,54 @@
+use geoengine_datatypes::primitives::{Measurement, TimeInterval};
+use geoengine_datatypes::raster::{
+    Grid2D, GridOrEmpty2D, MaskedGrid2D, Pixel, RasterTile2D, TileInformation,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_datatypes::util::test::TestDefault;
+
+use crate::engine::RasterResultDescriptor;
+use crate::mock::{MockRasterSource, MockRasterSourceParams};
+
+/// A `2x2` tile at the origin of a `2x2` tiling with the default geo transform
+pub fn tile_2x2<T: Pixel>(time: TimeInterval, grid_array: GridOrEmpty2D<T>) -> RasterTile2D<T> {
+    RasterTile2D::new_with_tile_info(
+        time,
+        TileInformation {
+            global_geo_transform: TestDefault::test_default(),
+            global_tile_position: [0, 0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        },
+        grid_array,
+    )
+}
+
+/// A `2x2` grid of valid `values`
+pub fn grid_2x2<T: Pixel>(values: Vec<T>) -> GridOrEmpty2D<T> {
+    Grid2D::new([2, 2].into(), values).unwrap().into()
+}
+
+/// A `2x2` grid whose `values` are no data where the `validity` is `false`
+pub fn masked_2x2<T: Pixel>(values: Vec<T>, validity: Vec<bool>) -> GridOrEmpty2D<T> {
+    MaskedGrid2D::new(
+        Grid2D::new([2, 2].into(), values).unwrap(),
+        Grid2D::new([2, 2].into(), validity).unwrap(),
+    )
+    .unwrap()
+    .into()
+}
+
+/// A unitless `EPSG:4326` raster source that emits the `tiles`
+pub fn mock_raster_source<T: Pixel>(tiles: Vec<RasterTile2D<T>>) -> MockRasterSource<T> {
+    MockRasterSource {
+        params: MockRasterSourceParams {
+            data: tiles,
+            result_descriptor: RasterResultDescriptor {
+                data_type: T::TYPE,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                measurement: Measurement::Unitless,
+                time: None,
+                bbox: None,
+                resolution: None,
+            },
+        },
+    }
+}
@@ -0,0 +1,502 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, IntoGeometryIterator,
+    MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+    SpooledFeatureCollections,
+};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, FeatureDataRef, FeatureDataValue, Geometry, MultiLineStringAccess,
+    MultiPointAccess, MultiPolygonAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use snafu::ResultExt;
+
+use crate::engine::{QueryContext, VectorQueryProcessor};
+use crate::error;
+use crate::util::Result;
+
+use super::abortable_query_execution;
+use super::vector_stream_to_spool::{finish_export_file, temporary_export_file};
+
+/// Collections whose geometries can be written as WKT
+pub trait WktGeometries {
+    /// Returns the WKT representation of the geometry of each feature
+    fn wkt_geometries(&self) -> Vec<String>;
+}
+
+impl WktGeometries for MultiPointCollection {
+    fn wkt_geometries(&self) -> Vec<String> {
+        self.geometries()
+            .map(|multi_point| match multi_point.points() {
+                [point] => format!("POINT ({})", wkt_coordinate(point)),
+                points => format!(
+                    "MULTIPOINT ({})",
+                    points
+                        .iter()
+                        .map(|point| format!("({})", wkt_coordinate(point)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })
+            .collect()
+    }
+}
+
+impl WktGeometries for MultiLineStringCollection {
+    fn wkt_geometries(&self) -> Vec<String> {
+        self.geometries()
+            .map(|multi_line_string| match multi_line_string.lines() {
+                [line] => format!("LINESTRING {}", wkt_coordinates(line.as_ref())),
+                lines => format!(
+                    "MULTILINESTRING ({})",
+                    lines
+                        .iter()
+                        .map(|line| wkt_coordinates(line.as_ref()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })
+            .collect()
+    }
+}
+
+impl WktGeometries for MultiPolygonCollection {
+    fn wkt_geometries(&self) -> Vec<String> {
+        self.geometries()
+            .map(|multi_polygon| match multi_polygon.polygons() {
+                [polygon] => format!("POLYGON {}", wkt_polygon(polygon.as_ref())),
+                polygons => format!(
+                    "MULTIPOLYGON ({})",
+                    polygons
+                        .iter()
+                        .map(|polygon| wkt_polygon(polygon.as_ref()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })
+            .collect()
+    }
+}
+
+fn wkt_polygon<R: AsRef<[Coordinate2D]>>(rings: &[R]) -> String {
+    format!(
+        "({})",
+        rings
+            .iter()
+            .map(|ring| wkt_coordinates(ring.as_ref()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn wkt_coordinate(coordinate: &Coordinate2D) -> String {
+    format!("{} {}", coordinate.x, coordinate.y)
+}
+
+fn wkt_coordinates(coordinates: &[Coordinate2D]) -> String {
+    format!(
+        "({})",
+        coordinates
+            .iter()
+            .map(wkt_coordinate)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn csv_value(value: FeatureDataValue) -> String {
+    match value {
+        FeatureDataValue::Category(v) | FeatureDataValue::NullableCategory(Some(v)) => {
+            v.to_string()
+        }
+        FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => v.to_string(),
+        FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => v.to_string(),
+        FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)) => v,
+        FeatureDataValue::Bool(v) | FeatureDataValue::NullableBool(Some(v)) => v.to_string(),
+        FeatureDataValue::DateTime(v) | FeatureDataValue::NullableDateTime(Some(v)) => {
+            v.as_rfc3339_with_millis()
+        }
+        FeatureDataValue::NullableCategory(None)
+        | FeatureDataValue::NullableInt(None)
+        | FeatureDataValue::NullableFloat(None)
+        | FeatureDataValue::NullableText(None)
+        | FeatureDataValue::NullableBool(None)
+        | FeatureDataValue::NullableDateTime(None) => String::new(),
+    }
+}
+
+/// Queries the processor and writes all resulting features as rows of a CSV file.
+/// The columns are the geometry as WKT, the start and end of the time interval and the given attribute `columns`.
+/// Null values are written as empty fields.
+pub async fn vector_stream_to_csv_bytes<G, C: QueryContext + 'static>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    mut query_ctx: C,
+    columns: &[String],
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: WktGeometries + FeatureCollectionInfos,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let written = async {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        write_csv_header(&mut writer, columns)?;
+
+        let mut stream = processor.query(query_rect, &query_ctx).await?;
+        while let Some(collection) = stream.try_next().await? {
+            write_csv_rows(&mut writer, &collection, columns)?;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|error| csv::Error::from(error.into_error()))
+            .context(error::CsvWriter)
+    };
+
+    abortable_query_execution(written, conn_closed, query_abort_trigger).await
+}
+
+/// Writes the spooled features as rows of a CSV file like `vector_stream_to_csv_bytes`.
+/// The file is a temporary file in `directory`, see `temporary_export_file`.
+pub fn spooled_collections_to_csv_file<G>(
+    spooled: SpooledFeatureCollections<G>,
+    columns: &[String],
+    directory: &Path,
+) -> Result<File>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: WktGeometries + FeatureCollectionInfos,
+{
+    let mut writer = csv::Writer::from_writer(temporary_export_file(directory)?);
+
+    write_csv_header(&mut writer, columns)?;
+
+    for collection in spooled.into_collections()? {
+        write_csv_rows(&mut writer, &collection?, columns)?;
+    }
+
+    let file = writer
+        .into_inner()
+        .map_err(|error| csv::Error::from(error.into_error()))
+        .context(error::CsvWriter)?;
+
+    finish_export_file(file)
+}
+
+fn write_csv_header<W: Write>(writer: &mut csv::Writer<W>, columns: &[String]) -> Result<()> {
+    writer
+        .write_record(
+            ["geometry", "start", "end"]
+                .into_iter()
+                .chain(columns.iter().map(String::as_str)),
+        )
+        .context(error::CsvWriter)
+}
+
+fn write_csv_rows<G, W: Write>(
+    writer: &mut csv::Writer<W>,
+    collection: &FeatureCollection<G>,
+    columns: &[String],
+) -> Result<()>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: WktGeometries + FeatureCollectionInfos,
+{
+    let data = columns
+        .iter()
+        .map(|column| collection.data(column))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (i, (geometry, time)) in collection
+        .wkt_geometries()
+        .into_iter()
+        .zip(collection.time_intervals())
+        .enumerate()
+    {
+        let mut record = vec![
+            geometry,
+            time.start().as_rfc3339_with_millis(),
+            time.end().as_rfc3339_with_millis(),
+        ];
+        record.extend(data.iter().map(|data| csv_value(data.get_unchecked(i))));
+
+        writer.write_record(record).context(error::CsvWriter)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the header of a table with the start and end of the time interval and the given attribute `columns`
+pub fn table_csv_header(columns: &[String]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer
+        .write_record(
+            ["start", "end"]
+                .into_iter()
+                .chain(columns.iter().map(String::as_str)),
+        )
+        .context(error::CsvWriter)?;
+
+    writer
+        .into_inner()
+        .map_err(|error| csv::Error::from(error.into_error()))
+        .context(error::CsvWriter)
+}
+
+/// Writes the features of a data collection as CSV rows of a table with the columns of [`table_csv_header`].
+/// Null values are written as empty fields.
+pub fn data_collection_to_csv_rows(
+    collection: &DataCollection,
+    columns: &[String],
+) -> Result<Vec<u8>> {
+    let data = columns
+        .iter()
+        .map(|column| collection.data(column))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+
+    for (i, time) in collection.time_intervals().iter().enumerate() {
+        let mut record = vec![
+            time.start().as_rfc3339_with_millis(),
+            time.end().as_rfc3339_with_millis(),
+        ];
+        record.extend(data.iter().map(|data| csv_value(data.get_unchecked(i))));
+
+        writer.write_record(record).context(error::CsvWriter)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|error| csv::Error::from(error.into_error()))
+        .context(error::CsvWriter)
+}
+
+/// Converts the features of a data collection to JSON arrays with the columns of [`table_csv_header`]
+pub fn data_collection_to_json_rows(
+    collection: &DataCollection,
+    columns: &[String],
+) -> Result<Vec<serde_json::Value>> {
+    let data = columns
+        .iter()
+        .map(|column| collection.data(column))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut values = data
+        .iter()
+        .map(FeatureDataRef::json_values)
+        .collect::<Vec<_>>();
+
+    Ok(collection
+        .time_intervals()
+        .iter()
+        .map(|time| {
+            let mut row = vec![
+                serde_json::Value::String(time.start().as_rfc3339_with_millis()),
+                serde_json::Value::String(time.end().as_rfc3339_with_millis()),
+            ];
+            row.extend(
+                values
+                    .iter_mut()
+                    .map(|values| values.next().unwrap_or(serde_json::Value::Null)),
+            );
+
+            serde_json::Value::Array(row)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::FeatureCollectionSpool;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, MultiPoint, MultiPolygon, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn it_writes_wkt() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![vec![(0.0, 0.1)], vec![(1.0, 1.1), (2.0, 2.1)]]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            points.wkt_geometries(),
+            vec!["POINT (0 0.1)", "MULTIPOINT ((1 1.1), (2 2.1))"]
+        );
+
+        let polygons = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (0.0, 0.0).into(),
+                (1.0, 0.0).into(),
+                (0.0, 1.0).into(),
+                (0.0, 0.0).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            polygons.wkt_geometries(),
+            vec!["POLYGON ((0 0, 1 0, 0 1, 0 0))"]
+        );
+    }
+
+    #[test]
+    fn it_writes_tables() {
+        let collection = DataCollection::from_data(
+            vec![],
+            vec![TimeInterval::new(0, 1000).unwrap(); 2],
+            [
+                (
+                    "count".to_string(),
+                    FeatureData::NullableInt(vec![Some(1), None]),
+                ),
+                (
+                    "name".to_string(),
+                    FeatureData::Text(vec!["a".to_string(), "b, c".to_string()]),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap();
+        let columns = ["count".to_string(), "name".to_string()];
+
+        let csv = [
+            table_csv_header(&columns).unwrap(),
+            data_collection_to_csv_rows(&collection, &columns).unwrap(),
+        ]
+        .concat();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "start,end,count,name\n\
+            1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,1,a\n\
+            1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,,\"b, c\"\n"
+        );
+
+        assert_eq!(
+            data_collection_to_json_rows(&collection, &columns).unwrap(),
+            vec![
+                serde_json::json!([
+                    "1970-01-01T00:00:00.000Z",
+                    "1970-01-01T00:00:01.000Z",
+                    1,
+                    "a"
+                ]),
+                serde_json::json!([
+                    "1970-01-01T00:00:00.000Z",
+                    "1970-01-01T00:00:01.000Z",
+                    null,
+                    "b, c"
+                ]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_writes_csv_files() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1000).unwrap(); 2],
+            [
+                (
+                    "label".to_string(),
+                    FeatureData::Text(vec!["forest".to_string(), "water".to_string()]),
+                ),
+                (
+                    "ndvi".to_string(),
+                    FeatureData::NullableFloat(vec![Some(0.5), None]),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let bytes = vector_stream_to_csv_bytes(
+            processor,
+            VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            MockQueryContext::test_default(),
+            &["ndvi".to_string(), "label".to_string()],
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "geometry,start,end,ndvi,label\n\
+            POINT (0 0.1),1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,0.5,forest\n\
+            POINT (1 1.1),1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,,water\n"
+        );
+    }
+
+    #[test]
+    fn it_writes_spooled_csv_files() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1000).unwrap(); 2],
+            [(
+                "label".to_string(),
+                FeatureData::Text(vec!["forest".to_string(), "water".to_string()]),
+            )]
+            .into(),
+        )
+        .unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+
+        // a threshold of zero spills the collections to a file
+        let mut spool = FeatureCollectionSpool::new(0, directory.path().to_path_buf());
+        spool.push(collection).unwrap();
+        let spooled = spool.finish().unwrap();
+        assert!(spooled.is_spilled());
+
+        let mut file =
+            spooled_collections_to_csv_file(spooled, &["label".to_string()], directory.path())
+                .unwrap();
+
+        let mut csv = String::new();
+        std::io::Read::read_to_string(&mut file, &mut csv).unwrap();
+
+        assert_eq!(
+            csv,
+            "geometry,start,end,label\n\
+            POINT (0 0.1),1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,forest\n\
+            POINT (1 1.1),1970-01-01T00:00:00.000Z,1970-01-01T00:00:01.000Z,water\n"
+        );
+    }
+}
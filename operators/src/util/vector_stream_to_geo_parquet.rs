@@ -0,0 +1,134 @@
+use std::fs::File;
+use std::path::Path;
+
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, GeoParquetWriter, SpooledFeatureCollections,
+    WkbGeometries,
+};
+use geoengine_datatypes::primitives::{Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+
+use crate::engine::{QueryContext, VectorQueryProcessor};
+use crate::util::Result;
+
+use super::abortable_query_execution;
+use super::vector_stream_to_spool::{finish_export_file, temporary_export_file};
+
+/// Queries the processor and writes all resulting collections into a GeoParquet file.
+/// `crs` is the PROJJSON of the spatial reference of the collections.
+pub async fn vector_stream_to_geo_parquet_bytes<G, C: QueryContext + 'static>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    mut query_ctx: C,
+    crs: Option<serde_json::Value>,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: WkbGeometries + FeatureCollectionInfos,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let written = async {
+        let mut bytes = Vec::new();
+        let mut writer = GeoParquetWriter::new(&mut bytes, crs);
+        let mut is_empty = true;
+
+        let mut stream = processor.query(query_rect, &query_ctx).await?;
+        while let Some(collection) = stream.try_next().await? {
+            writer.write(&collection)?;
+            is_empty = false;
+        }
+
+        // write an empty file with the geometry column instead of no file at all
+        if is_empty {
+            writer.write(&FeatureCollection::<G>::empty())?;
+        }
+
+        writer.finish()?;
+
+        Ok(bytes)
+    };
+
+    abortable_query_execution(written, conn_closed, query_abort_trigger).await
+}
+
+/// Writes the spooled collections as a GeoParquet file like `vector_stream_to_geo_parquet_bytes`.
+/// The file is a temporary file in `directory`, see `temporary_export_file`.
+pub fn spooled_collections_to_geo_parquet_file<G>(
+    spooled: SpooledFeatureCollections<G>,
+    crs: Option<serde_json::Value>,
+    directory: &Path,
+) -> Result<File>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: WkbGeometries + FeatureCollectionInfos,
+{
+    let mut file = temporary_export_file(directory)?;
+    let mut writer = GeoParquetWriter::new(&mut file, crs);
+    let mut is_empty = true;
+
+    for collection in spooled.into_collections()? {
+        writer.write(&collection?)?;
+        is_empty = false;
+    }
+
+    if is_empty {
+        writer.write(&FeatureCollection::<G>::empty())?;
+    }
+
+    writer.finish()?;
+
+    finish_export_file(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_writes_parquet_files() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let bytes = vector_stream_to_geo_parquet_bytes(
+            processor,
+            VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            MockQueryContext::test_default(),
+            None,
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(&bytes[..4], b"PAR1");
+        assert_eq!(&bytes[bytes.len() - 4..], b"PAR1");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek};
+use std::path::{Path, PathBuf};
+
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionSpool, SpooledFeatureCollections,
+};
+use geoengine_datatypes::primitives::{Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+
+use crate::engine::{QueryContext, VectorQueryProcessor};
+use crate::util::Result;
+
+use super::abortable_query_execution;
+
+/// Queries the processor and collects all resulting collections in a spool.
+/// If the collections exceed `memory_threshold_bytes`, they are spilled to Arrow IPC files in `directory`.
+pub async fn vector_stream_to_spool<G, C: QueryContext + 'static>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    mut query_ctx: C,
+    memory_threshold_bytes: usize,
+    directory: PathBuf,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<SpooledFeatureCollections<G>>
+where
+    G: Geometry + ArrowTyped + 'static,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let spooled = async {
+        let mut spool = FeatureCollectionSpool::new(memory_threshold_bytes, directory);
+
+        let mut stream = processor.query(query_rect, &query_ctx).await?;
+        while let Some(collection) = stream.try_next().await? {
+            spool.push(collection)?;
+        }
+
+        Ok(spool.finish()?)
+    };
+
+    abortable_query_execution(spooled, conn_closed, query_abort_trigger).await
+}
+
+/// Creates a temporary file in the spool `directory` for writing an export of spooled collections.
+/// The file has no name and is removed as soon as it is closed.
+pub fn temporary_export_file(directory: &Path) -> Result<BufWriter<File>> {
+    Ok(BufWriter::new(tempfile::tempfile_in(directory)?))
+}
+
+/// Flushes the written export file and rewinds it, e.g., for streaming it as a response
+pub fn finish_export_file(writer: BufWriter<File>) -> Result<File> {
+    let mut file = writer.into_inner().map_err(|error| error.into_error())?;
+    file.rewind()?;
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_spools_collections() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection.clone())
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+
+        let spooled = vector_stream_to_spool(
+            processor,
+            VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap(),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            },
+            MockQueryContext::test_default(),
+            0,
+            directory.path().to_path_buf(),
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        assert!(spooled.is_spilled());
+        assert_eq!(spooled.len(), 2);
+        assert_eq!(
+            spooled
+                .collections()
+                .unwrap()
+                .collect::<geoengine_datatypes::util::Result<Vec<_>>>()
+                .unwrap(),
+            vec![collection]
+        );
+    }
+}
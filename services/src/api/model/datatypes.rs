@@ -786,6 +786,72 @@ impl<'de> Deserialize<'de> for TimeInstance {
     }
 }
 
+/// A time granularity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeGranularity {
+    Millis,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Months,
+    Years,
+}
+
+impl From<geoengine_datatypes::primitives::TimeGranularity> for TimeGranularity {
+    fn from(value: geoengine_datatypes::primitives::TimeGranularity) -> Self {
+        match value {
+            geoengine_datatypes::primitives::TimeGranularity::Millis => Self::Millis,
+            geoengine_datatypes::primitives::TimeGranularity::Seconds => Self::Seconds,
+            geoengine_datatypes::primitives::TimeGranularity::Minutes => Self::Minutes,
+            geoengine_datatypes::primitives::TimeGranularity::Hours => Self::Hours,
+            geoengine_datatypes::primitives::TimeGranularity::Days => Self::Days,
+            geoengine_datatypes::primitives::TimeGranularity::Months => Self::Months,
+            geoengine_datatypes::primitives::TimeGranularity::Years => Self::Years,
+        }
+    }
+}
+
+impl From<TimeGranularity> for geoengine_datatypes::primitives::TimeGranularity {
+    fn from(value: TimeGranularity) -> Self {
+        match value {
+            TimeGranularity::Millis => Self::Millis,
+            TimeGranularity::Seconds => Self::Seconds,
+            TimeGranularity::Minutes => Self::Minutes,
+            TimeGranularity::Hours => Self::Hours,
+            TimeGranularity::Days => Self::Days,
+            TimeGranularity::Months => Self::Months,
+            TimeGranularity::Years => Self::Years,
+        }
+    }
+}
+
+/// A step in time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TimeStep {
+    pub granularity: TimeGranularity,
+    pub step: u32,
+}
+
+impl From<geoengine_datatypes::primitives::TimeStep> for TimeStep {
+    fn from(value: geoengine_datatypes::primitives::TimeStep) -> Self {
+        Self {
+            granularity: value.granularity.into(),
+            step: value.step,
+        }
+    }
+}
+
+impl From<TimeStep> for geoengine_datatypes::primitives::TimeStep {
+    fn from(value: TimeStep) -> Self {
+        Self {
+            granularity: value.granularity.into(),
+            step: value.step,
+        }
+    }
+}
+
 /// Stores time intervals in ms in close-open semantic [start, end)
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub struct TimeInterval {
@@ -889,6 +955,13 @@ impl From<geoengine_datatypes::operations::image::RgbaColor> for RgbaColor {
     }
 }
 
+impl From<RgbaColor> for geoengine_datatypes::operations::image::RgbaColor {
+    fn from(color: RgbaColor) -> Self {
+        let [red, green, blue, alpha] = color.0;
+        Self::new(red, green, blue, alpha)
+    }
+}
+
 /// A container type for breakpoints that specify a value to color mapping
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct Breakpoint {
@@ -918,10 +991,26 @@ impl From<geoengine_datatypes::operations::image::Breakpoint> for Breakpoint {
     }
 }
 
+impl From<Breakpoint> for geoengine_datatypes::operations::image::Breakpoint {
+    fn from(breakpoint: Breakpoint) -> Self {
+        Self {
+            value: breakpoint.value,
+            color: breakpoint.color.into(),
+        }
+    }
+}
+
 /// A colorizer specifies a mapping between raster values and an output image
 /// There are different variants that perform different kinds of mapping.
+///
+/// Gradients can also be specified by a named color ramp, e.g., `"ramp": "viridis"` with `min` and `max` values
+/// instead of `breakpoints`.
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, ToSchema)]
-#[serde(rename_all = "camelCase", tag = "type")]
+#[serde(
+    rename_all = "camelCase",
+    tag = "type",
+    from = "geoengine_datatypes::operations::image::Colorizer"
+)]
 pub enum Colorizer {
     #[serde(rename_all = "camelCase")]
     LinearGradient {
@@ -985,6 +1074,47 @@ impl From<geoengine_datatypes::operations::image::Colorizer> for Colorizer {
     }
 }
 
+impl TryFrom<Colorizer> for geoengine_datatypes::operations::image::Colorizer {
+    type Error = geoengine_datatypes::error::Error;
+
+    fn try_from(v: Colorizer) -> Result<Self, Self::Error> {
+        match v {
+            Colorizer::LinearGradient {
+                breakpoints,
+                no_data_color,
+                default_color,
+            } => Self::linear_gradient(
+                breakpoints.into_iter().map(Into::into).collect(),
+                no_data_color.into(),
+                default_color.into(),
+            ),
+            Colorizer::LogarithmicGradient {
+                breakpoints,
+                no_data_color,
+                default_color,
+            } => Self::logarithmic_gradient(
+                breakpoints.into_iter().map(Into::into).collect(),
+                no_data_color.into(),
+                default_color.into(),
+            ),
+            Colorizer::Palette {
+                colors,
+                no_data_color,
+                default_color,
+            } => Self::palette(
+                colors
+                    .0
+                    .into_iter()
+                    .map(|(value, color)| (value, color.into()))
+                    .collect(),
+                no_data_color.into(),
+                default_color.into(),
+            ),
+            Colorizer::Rgba => Ok(Self::Rgba),
+        }
+    }
+}
+
 /// A map from value to color
 ///
 /// It is assumed that is has at least one and at most 256 entries.
@@ -0,0 +1,448 @@
+//! Versioning of the API.
+//!
+//! All routes are available under a version prefix, e.g., `/v2/workflow`, and, for compatibility, without one.
+//! Unversioned and `v1` requests keep the legacy behavior and announce their deprecation in the response headers.
+//! `v2` responses use camel case for the fields that were serialized in snake case by earlier versions.
+//! Requests of all versions may use either name, since the models accept the camel case names as aliases.
+
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use actix_http::uri::{PathAndQuery, Uri};
+use actix_web::body::{BodySize, BoxBody, MessageBody};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::web::Bytes;
+use actix_web::HttpMessage;
+use chrono::NaiveDate;
+use futures::future::LocalBoxFuture;
+use serde_json::Value;
+
+/// The versions of the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/v1",
+            ApiVersion::V2 => "/v2",
+        }
+    }
+
+    /// Splits the version prefix off a request path, e.g., `/v2/workflow` into `V2` and `/workflow`
+    pub fn from_path(path: &str) -> Option<(ApiVersion, &str)> {
+        [ApiVersion::V1, ApiVersion::V2]
+            .into_iter()
+            .find_map(|version| {
+                let rest = path.strip_prefix(version.prefix())?;
+                if rest.is_empty() {
+                    Some((version, "/"))
+                } else if rest.starts_with('/') {
+                    Some((version, rest))
+                } else {
+                    None
+                }
+            })
+    }
+
+    pub fn is_deprecated(self) -> bool {
+        self < Self::LATEST
+    }
+
+    /// Whether requests and responses of this version use the camel case names of renamed fields
+    fn uses_camel_case_fields(self) -> bool {
+        self >= ApiVersion::V2
+    }
+}
+
+/// A field of response bodies that older versions of the API serialize in snake case
+struct RenamedField {
+    /// The prefixes of the routes that respond with the field, where `*` matches any path segment
+    routes: &'static [&'static str],
+    /// The location of the object that contains the field, where `*` stands for all elements of an array
+    path: &'static [&'static str],
+    legacy: &'static str,
+    latest: &'static str,
+}
+
+/// The routes that schedule a task and respond with its id
+const TASK_RESPONSE_ROUTES: &[&str] = &[
+    "/dataset/*/validate",
+    "/workflow/*/rasterExport",
+    "/workflow/*/subscribe",
+    "/ebv/overviews",
+];
+
+/// The renamed fields of the task responses, i.e., of a task id, a task status or a list of them
+const RENAMED_FIELDS: [RenamedField; 6] = [
+    RenamedField {
+        routes: TASK_RESPONSE_ROUTES,
+        path: &[],
+        legacy: "task_id",
+        latest: "taskId",
+    },
+    RenamedField {
+        routes: &["/tasks/*/status"],
+        path: &[],
+        legacy: "pct_complete",
+        latest: "pctComplete",
+    },
+    RenamedField {
+        routes: &["/tasks/*/status"],
+        path: &[],
+        legacy: "time_estimate",
+        latest: "timeEstimate",
+    },
+    RenamedField {
+        routes: &["/tasks/list"],
+        path: &["*"],
+        legacy: "task_id",
+        latest: "taskId",
+    },
+    RenamedField {
+        routes: &["/tasks/list"],
+        path: &["*"],
+        legacy: "pct_complete",
+        latest: "pctComplete",
+    },
+    RenamedField {
+        routes: &["/tasks/list"],
+        path: &["*"],
+        legacy: "time_estimate",
+        latest: "timeEstimate",
+    },
+];
+
+/// Checks whether the request `path` starts with the segments of the `route` prefix
+fn matches_route(route: &str, path: &str) -> bool {
+    let mut segments = path.split('/');
+
+    route.split('/').all(|expected| {
+        segments
+            .next()
+            .map_or(false, |segment| expected == "*" || segment == expected)
+    })
+}
+
+/// The renamed fields of the responses of the route with the request `path`
+fn renamed_fields(path: &str) -> Vec<&'static RenamedField> {
+    RENAMED_FIELDS
+        .iter()
+        .filter(|field| field.routes.iter().any(|route| matches_route(route, path)))
+        .collect()
+}
+
+/// Larger response bodies are never renamed, so that they are not buffered
+const MAX_RENAMED_BODY_SIZE: u64 = 1024 * 1024;
+
+fn rename_field(value: &mut Value, path: &[&str], legacy: &str, latest: &str) {
+    match (path.split_first(), value) {
+        (None, Value::Object(object)) => {
+            if object.contains_key(latest) {
+                return;
+            }
+            if let Some(field) = object.remove(legacy) {
+                object.insert(latest.to_string(), field);
+            }
+        }
+        (Some((&"*", path)), Value::Array(values)) => {
+            for value in values {
+                rename_field(value, path, legacy, latest);
+            }
+        }
+        (Some((key, path)), Value::Object(object)) => {
+            if let Some(value) = object.get_mut(*key) {
+                rename_field(value, path, legacy, latest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Renames the `fields` of a JSON body or returns `None` if it is no valid JSON
+fn rename_json_fields(body: &[u8], fields: &[&RenamedField]) -> Option<Bytes> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+
+    for field in fields {
+        rename_field(&mut value, field.path, field.legacy, field.latest);
+    }
+
+    serde_json::to_vec(&value).ok().map(Bytes::from)
+}
+
+fn is_json(content_type: Option<&HeaderValue>) -> bool {
+    content_type
+        .and_then(|content_type| content_type.to_str().ok())
+        .map_or(false, |content_type| {
+            content_type.starts_with("application/json")
+        })
+}
+
+/// A middleware that resolves the version prefix of requests, adds deprecation headers and renames the fields of responses
+#[derive(Debug, Clone, Default)]
+pub struct ApiVersioning {
+    sunset: Option<NaiveDate>,
+}
+
+impl ApiVersioning {
+    /// `sunset` is the date after which deprecated versions will be removed
+    pub fn new(sunset: Option<NaiveDate>) -> Self {
+        Self { sunset }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiVersioning
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    type Transform = ApiVersioningMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiVersioningMiddleware {
+            service: Rc::new(service),
+            sunset: self.sunset,
+        }))
+    }
+}
+
+pub struct ApiVersioningMiddleware<S> {
+    service: Rc<S>,
+    sunset: Option<NaiveDate>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiVersioningMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let sunset = self.sunset;
+
+        Box::pin(async move {
+            let version = strip_version_prefix(&mut req);
+            let path = req.path().to_string();
+
+            let fields = if version.map_or(false, ApiVersion::uses_camel_case_fields) {
+                renamed_fields(&path)
+            } else {
+                vec![]
+            };
+
+            let mut response = service.call(req).await?.map_into_boxed_body();
+
+            let is_small = matches!(
+                response.response().body().size(),
+                BodySize::Sized(size) if size <= MAX_RENAMED_BODY_SIZE
+            );
+
+            if !fields.is_empty()
+                && is_small
+                && is_json(response.headers().get(header::CONTENT_TYPE))
+            {
+                let (request, response_parts) = response.into_parts();
+                let (mut response_parts, body) = response_parts.into_parts();
+
+                let body = actix_web::body::to_bytes(body)
+                    .await
+                    .map_err(actix_web::error::ErrorInternalServerError)?;
+                let body = rename_json_fields(&body, &fields).unwrap_or(body);
+
+                response_parts.headers_mut().remove(header::CONTENT_LENGTH);
+                response =
+                    ServiceResponse::new(request, response_parts.set_body(BoxBody::new(body)));
+            }
+
+            if version.map_or(true, ApiVersion::is_deprecated) {
+                add_deprecation_headers(&mut response, &path, sunset);
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Removes the version prefix from the request path, so that all versions share the same routes
+fn strip_version_prefix(req: &mut ServiceRequest) -> Option<ApiVersion> {
+    let (version, path_and_query) = {
+        let (version, path) = ApiVersion::from_path(req.path())?;
+
+        let path_and_query = match req.query_string() {
+            "" => path.to_string(),
+            query => format!("{}?{}", path, query),
+        };
+
+        (version, path_and_query)
+    };
+
+    let mut parts = req.uri().clone().into_parts();
+    parts.path_and_query = PathAndQuery::from_str(&path_and_query).ok();
+
+    if let Ok(uri) = Uri::from_parts(parts) {
+        req.match_info_mut().get_mut().update(&uri);
+        req.head_mut().uri = uri;
+    }
+
+    req.extensions_mut().insert(version);
+
+    Some(version)
+}
+
+fn add_deprecation_headers(
+    response: &mut ServiceResponse<BoxBody>,
+    path: &str,
+    sunset: Option<NaiveDate>,
+) {
+    let headers = response.headers_mut();
+
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_static("true"),
+    );
+
+    if let Some(sunset) = sunset {
+        let http_date = sunset.format("%a, %d %b %Y 00:00:00 GMT").to_string();
+        if let Ok(value) = HeaderValue::from_str(&http_date) {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+    }
+
+    let successor = format!(
+        "<{}{}>; rel=\"successor-version\"",
+        ApiVersion::LATEST.prefix(),
+        path
+    );
+    if let Ok(value) = HeaderValue::from_str(&successor) {
+        headers.insert(header::LINK, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use serde_json::json;
+
+    #[test]
+    fn it_splits_the_version_prefix() {
+        assert_eq!(
+            ApiVersion::from_path("/v2/workflow/123"),
+            Some((ApiVersion::V2, "/workflow/123"))
+        );
+        assert_eq!(ApiVersion::from_path("/v1"), Some((ApiVersion::V1, "/")));
+        assert_eq!(ApiVersion::from_path("/v20/workflow"), None);
+        assert_eq!(ApiVersion::from_path("/workflow"), None);
+    }
+
+    #[actix_rt::test]
+    async fn it_renames_fields_for_the_latest_version() {
+        let app = test::init_service(App::new().wrap(ApiVersioning::new(None)).route(
+            "/tasks/list",
+            web::get().to(|| async {
+                HttpResponse::Ok().json(json!([{
+                    "task_id": "abc",
+                    "pct_complete": "50.00%",
+                    "info": { "task_id": "user data" }
+                }]))
+            }),
+        ))
+        .await;
+
+        let req = test::TestRequest::get().uri("/v2/tasks/list").to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+        assert!(res.headers().get("deprecation").is_none());
+
+        // only the fields at the known paths are renamed
+        let body: Value = test::read_body_json(res).await;
+        assert_eq!(
+            body,
+            json!([{
+                "taskId": "abc",
+                "pctComplete": "50.00%",
+                "info": { "task_id": "user data" }
+            }])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn it_only_renames_the_fields_of_task_routes() {
+        let app = test::init_service(App::new().wrap(ApiVersioning::new(None)).route(
+            "/dataset/{dataset}/auxiliaryMetadata",
+            web::get().to(|| async {
+                HttpResponse::Ok().json(json!({ "task_id": "user data", "pct_complete": 1 }))
+            }),
+        ))
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/v2/dataset/abc/auxiliaryMetadata")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+
+        let body: Value = test::read_body_json(res).await;
+        assert_eq!(body, json!({ "task_id": "user data", "pct_complete": 1 }));
+    }
+
+    #[test]
+    fn it_matches_route_prefixes() {
+        assert!(matches_route("/tasks/*/status", "/tasks/abc/status"));
+        assert!(matches_route("/ebv/overviews", "/ebv/overviews/all"));
+        assert!(!matches_route("/tasks/*/status", "/tasks/list"));
+        assert!(!matches_route("/tasks/list", "/tasks/listing"));
+        assert!(!matches_route("/workflow/*/subscribe", "/workflow/abc"));
+    }
+
+    #[actix_rt::test]
+    async fn it_announces_the_deprecation_of_legacy_versions() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ApiVersioning::new(NaiveDate::from_ymd_opt(2023, 6, 30)))
+                .route(
+                    "/info",
+                    web::get().to(|| async { HttpResponse::Ok().json(json!({ "task_id": 1 })) }),
+                ),
+        )
+        .await;
+
+        for uri in ["/info", "/v1/info"] {
+            let req = test::TestRequest::get().uri(uri).to_request();
+            let res = test::call_service(&app, req).await;
+
+            assert_eq!(res.status(), 200);
+            assert_eq!(res.headers().get("deprecation").unwrap(), "true");
+            assert_eq!(
+                res.headers().get("sunset").unwrap(),
+                "Fri, 30 Jun 2023 00:00:00 GMT"
+            );
+            assert_eq!(
+                res.headers().get(header::LINK).unwrap(),
+                "</v2/info>; rel=\"successor-version\""
+            );
+
+            let body: Value = test::read_body_json(res).await;
+            assert_eq!(body, json!({ "task_id": 1 }));
+        }
+    }
+}
@@ -1,38 +1,74 @@
 use crate::api::model::datatypes::{
     BoundingBox2D, Breakpoint, ClassificationMeasurement, Colorizer, ContinuousMeasurement,
-    Coordinate2D, DataId, DataProviderId, DatasetId, ExternalDataId, FeatureDataType, LayerId,
-    Measurement, Palette, RasterDataType, RasterQueryRectangle, RgbaColor, SpatialPartition2D,
-    SpatialReference, SpatialReferenceAuthority, SpatialReferenceOption, SpatialResolution,
-    TimeInstance, TimeInterval, VectorDataType,
+    Coordinate2D, DataId, DataProviderId, DatasetId, DateTime, ExternalDataId, FeatureDataType,
+    LayerId, Measurement, Palette, RasterDataType, RasterQueryRectangle, RgbaColor,
+    SpatialPartition2D, SpatialReference, SpatialReferenceAuthority, SpatialReferenceOption,
+    SpatialResolution, TimeGranularity, TimeInstance, TimeInterval, TimeStep, VectorDataType,
+    VectorQueryRectangle,
 };
 use crate::api::model::operators::{
     PlotResultDescriptor, RasterResultDescriptor, TypedOperator, TypedResultDescriptor,
     VectorColumnInfo, VectorResultDescriptor,
 };
 use crate::contexts::{SessionId, SimpleSession};
-use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::datasets::listing::{DatasetListing, Provenance, ProvenanceOutput};
+use crate::datasets::storage::{
+    AddDataset, AppendTimeSlices, AutoCreateDataset, BulkCreateDatasetResult, BulkCreateDatasets,
+    BulkCreateDatasetsReport, CreateDataset, Dataset, DatasetDefinition, MetaDataSuggestion,
+};
 use crate::datasets::upload::UploadId;
 use crate::handlers;
-use crate::handlers::tasks::TaskAbortOptions;
+use crate::handlers::datasets::{DatasetUsage, DatasetUsageReport};
+use crate::handlers::layers::ProviderSecrets;
+use crate::handlers::plots::WrappedPlotOutput;
+use crate::handlers::spatial_references::{AxisOrder, SpatialReferenceSpecification};
+use crate::handlers::tasks::{TaskAbortOptions, TaskResponse};
 use crate::handlers::wcs::CoverageResponse;
 use crate::handlers::wfs::{CollectionType, Coordinates, Feature, FeatureType, GeoJson};
 use crate::handlers::wms::MapResponse;
-use crate::handlers::workflows::{RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult};
+use crate::handlers::workflows::{
+    FeatureExport, FeatureExportFormat, FeatureExportRaster, QueryEstimate,
+    RasterCoordinateSamples, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult,
+    RasterExport, RasterExportFormat, RasterExportResult, RasterTileFormat, RasterTileOutput,
+    RasterValueSample, TableFormat, VectorExport, VectorExportFormat, WorkflowAliasTarget,
+    WorkflowSubstitutions, WorkflowTemplateValues,
+};
+use crate::handlers::ErrorResponse;
+use crate::layers::external::{ProviderCapabilities, ProviderHealth, ProviderHealthStatus};
 use crate::layers::layer::{
     CollectionItem, Layer, LayerCollection, LayerCollectionListing, LayerListing, Property,
     ProviderLayerCollectionId, ProviderLayerId,
 };
 use crate::layers::listing::LayerCollectionId;
+use crate::layers::search::LayerSearchResult;
 use crate::ogc::util::OgcBoundingBox;
 use crate::ogc::{wcs, wfs, wms};
 use crate::projects::{
-    ColorParam, DerivedColor, DerivedNumber, LineSymbology, NumberParam, PointSymbology,
-    PolygonSymbology, ProjectId, RasterSymbology, STRectangle, StrokeParam, Symbology,
-    TextSymbology,
+    CategorizedColor, CategoryValue, ColorCategory, ColorClass, ColorParam, CreateProject,
+    DerivedColor, DerivedNumber, GraduatedColor, GraduatedNumber, Layer as ProjectLayer,
+    LayerVisibility, LineSymbology, NumberClass, NumberParam, OrderBy, Plot, PointSymbology,
+    PolygonSymbology, Project, ProjectFilter, ProjectId, ProjectListing, ProjectVersion,
+    ProjectVersionId, RasterSymbology, STRectangle, StrokeParam, Symbology, TextSymbology,
+    UpdateProject,
 };
+use crate::reports::{ReportFormat, ReportPlot, ReportRequest};
 use crate::tasks::{TaskFilter, TaskId, TaskListOptions, TaskStatus};
-use crate::util::{apidoc::OpenApiServerInfo, server::ServerInfo, IdResponse};
-use crate::workflows::workflow::{Workflow, WorkflowId};
+use crate::util::{
+    apidoc::{ErrorResponseAddon, OpenApiServerInfo},
+    preflight::{DefinitionKind, PreflightIssue, PreflightReport, PreflightSeverity},
+    server::{GdalDatasetPoolMetrics, MemoryMetrics, ServerInfo},
+    IdResponse,
+};
+use crate::workflows::style_preset::{StylePreset, StylePresetName};
+use crate::workflows::subscription::{
+    SubscriptionAggregate, SubscriptionComparison, SubscriptionCondition, SubscriptionNotification,
+    SubscriptionStatus, WorkflowSubscription,
+};
+use crate::workflows::template::{
+    TemplateParameter, TemplateParameterType, WorkflowTemplate, WorkflowTemplateId,
+    WorkflowTemplateListing,
+};
+use crate::workflows::workflow::{SourceSubstitution, Workflow, WorkflowAlias, WorkflowId};
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 
@@ -40,16 +76,47 @@ use utoipa::{Modify, OpenApi};
 #[openapi(
     paths(
         crate::util::server::server_info_handler,
+        crate::util::server::memory_metrics_handler,
+        crate::util::server::gdal_dataset_pool_metrics_handler,
+        handlers::datasets::list_datasets_handler,
+        handlers::datasets::dataset_usage_handler,
+        handlers::datasets::get_dataset_handler,
+        handlers::datasets::list_dependent_workflows_handler,
+        handlers::datasets::append_time_slices_handler,
+        handlers::datasets::get_auxiliary_metadata_handler,
+        handlers::datasets::set_auxiliary_metadata_handler,
+        handlers::datasets::validate_dataset_handler,
+        handlers::datasets::create_dataset_handler,
+        handlers::datasets::bulk_create_datasets_handler,
+        handlers::datasets::auto_create_dataset_handler,
+        handlers::datasets::suggest_meta_data_handler,
         handlers::layers::layer_handler,
         handlers::layers::list_collection_handler,
+        handlers::layers::search_layers_handler,
         handlers::layers::list_root_collections_handler,
+        handlers::layers::provider_capabilities_handler,
+        handlers::layers::provider_health_handler,
+        handlers::layers::provider_definition_handler,
+        handlers::layers::update_provider_secrets_handler,
+        handlers::layers::invalidate_provider_cache_handler,
+        handlers::plots::get_plot_handler,
+        handlers::preflight::preflight_report_handler,
+        handlers::projects::create_project_handler,
+        handlers::projects::list_projects_handler,
+        handlers::projects::load_project_handler,
+        handlers::projects::update_project_handler,
+        handlers::projects::delete_project_handler,
+        handlers::reports::project_report_handler,
         handlers::session::anonymous_handler,
         handlers::session::session_handler,
         handlers::session::session_project_handler,
         handlers::session::session_view_handler,
+        handlers::spatial_references::get_spatial_reference_specification_handler,
         handlers::tasks::abort_handler,
         handlers::tasks::list_handler,
         handlers::tasks::status_handler,
+        handlers::upload::upload_handler,
+        handlers::upload::download_upload_file_handler,
         handlers::wcs::wcs_capabilities_handler,
         handlers::wcs::wcs_describe_coverage_handler,
         handlers::wcs::wcs_get_coverage_handler,
@@ -61,12 +128,35 @@ use utoipa::{Modify, OpenApi};
         handlers::workflows::dataset_from_workflow_handler,
         handlers::workflows::get_workflow_metadata_handler,
         handlers::workflows::get_workflow_provenance_handler,
+        handlers::workflows::get_workflow_all_metadata_zip_handler,
         handlers::workflows::load_workflow_handler,
+        handlers::workflows::substitute_workflow_sources_handler,
         handlers::workflows::register_workflow_handler,
+        handlers::workflows::register_workflow_version_handler,
+        handlers::workflows::list_workflow_versions_handler,
+        handlers::workflows::set_workflow_alias_handler,
+        handlers::workflows::resolve_workflow_alias_handler,
+        handlers::workflows::set_workflow_style_preset_handler,
+        handlers::workflows::load_workflow_style_preset_handler,
+        handlers::workflows::list_workflow_style_presets_handler,
+        handlers::workflows::remove_workflow_style_preset_handler,
+        handlers::workflows::register_workflow_template_handler,
+        handlers::workflows::load_workflow_template_handler,
+        handlers::workflows::list_workflow_templates_handler,
+        handlers::workflows::instantiate_workflow_template_handler,
+        handlers::workflows::sample_raster_workflow_handler,
+        handlers::workflows::raster_tile_workflow_handler,
+        handlers::workflows::estimate_workflow_query_handler,
+        handlers::workflows::export_vector_workflow_handler,
+        handlers::workflows::export_features_workflow_handler,
+        handlers::workflows::table_workflow_handler,
+        handlers::workflows::export_raster_workflow_handler,
+        handlers::workflows::subscribe_workflow_handler,
     ),
     components(
         schemas(
             SimpleSession,
+            ErrorResponse,
 
             DataId,
             DataProviderId,
@@ -75,16 +165,24 @@ use utoipa::{Modify, OpenApi};
             IdResponse<WorkflowId>,
             LayerId,
             ProjectId,
+            ProjectVersionId,
             SessionId,
             TaskId,
             UploadId,
             WorkflowId,
+            WorkflowAlias,
+            StylePresetName,
+            StylePreset,
+            WorkflowTemplateId,
             ProviderLayerId,
             ProviderLayerCollectionId,
             LayerCollectionId,
 
             TimeInstance,
             TimeInterval,
+            TimeStep,
+            TimeGranularity,
+            DateTime,
 
             Coordinate2D,
             BoundingBox2D,
@@ -101,11 +199,45 @@ use utoipa::{Modify, OpenApi};
             ProvenanceOutput,
             Provenance,
 
+            Dataset,
+            DatasetListing,
+            DatasetUsage,
+            DatasetUsageReport,
+            DatasetDefinition,
+            AddDataset,
+            CreateDataset,
+            BulkCreateDatasets,
+            BulkCreateDatasetsReport,
+            BulkCreateDatasetResult,
+            AppendTimeSlices,
+            AutoCreateDataset,
+            MetaDataSuggestion,
+
+            Project,
+            ProjectVersion,
+            ProjectLayer,
+            LayerVisibility,
+            Plot,
+            ProjectListing,
+            ProjectFilter,
+            OrderBy,
+            CreateProject,
+            UpdateProject,
+            ReportRequest,
+            ReportPlot,
+            ReportFormat,
+
+            WrappedPlotOutput,
+            SpatialReferenceSpecification,
+            AxisOrder,
+
             VectorDataType,
             FeatureDataType,
             RasterDataType,
 
             ServerInfo,
+            MemoryMetrics,
+            GdalDatasetPoolMetrics,
 
             Workflow,
             TypedOperator,
@@ -116,8 +248,37 @@ use utoipa::{Modify, OpenApi};
             VectorColumnInfo,
             RasterDatasetFromWorkflow,
             RasterDatasetFromWorkflowResult,
+            RasterCoordinateSamples,
+            RasterValueSample,
+            RasterTileOutput,
+            RasterTileFormat,
+            QueryEstimate,
+            VectorExport,
+            VectorExportFormat,
+            FeatureExport,
+            FeatureExportFormat,
+            FeatureExportRaster,
+            TableFormat,
+            RasterExport,
+            RasterExportFormat,
+            RasterExportResult,
+            TaskResponse,
+            WorkflowAliasTarget,
+            WorkflowTemplate,
+            TemplateParameter,
+            TemplateParameterType,
+            WorkflowTemplateListing,
+            WorkflowTemplateValues,
+            WorkflowSubstitutions,
+            SourceSubstitution,
+            WorkflowSubscription,
+            SubscriptionCondition,
+            SubscriptionAggregate,
+            SubscriptionComparison,
+            SubscriptionStatus,
+            SubscriptionNotification,
             RasterQueryRectangle,
-            // VectorQueryRectangle,
+            VectorQueryRectangle,
             // PlotQueryRectangle,
 
             TaskAbortOptions,
@@ -131,13 +292,30 @@ use utoipa::{Modify, OpenApi};
             LayerCollectionListing,
             Property,
             CollectionItem,
+            ProviderCapabilities,
+            ProviderHealth,
+            LayerSearchResult,
+            ProviderHealthStatus,
+            ProviderSecrets,
+
+            PreflightReport,
+            PreflightIssue,
+            PreflightSeverity,
+            DefinitionKind,
 
             Breakpoint,
+            CategorizedColor,
+            CategoryValue,
+            ColorCategory,
+            ColorClass,
             ColorParam,
             Colorizer,
             DerivedColor,
             DerivedNumber,
+            GraduatedColor,
+            GraduatedNumber,
             LineSymbology,
+            NumberClass,
             NumberParam,
             Palette,
             PointSymbology,
@@ -184,7 +362,7 @@ use utoipa::{Modify, OpenApi};
 
         ),
     ),
-    modifiers(&SecurityAddon, &ApiDocInfo, &OpenApiServerInfo),
+    modifiers(&SecurityAddon, &ApiDocInfo, &OpenApiServerInfo, &ErrorResponseAddon),
     external_docs(url = "https://docs.geoengine.io", description = "Geo Engine Docs")
 )]
 pub struct ApiDoc;
@@ -204,6 +382,18 @@ impl Modify for SecurityAddon {
                     .build(),
             ),
         );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("UUID")
+                    .description(Some(
+                        "Use the configured admin session token to authenticate.",
+                    ))
+                    .build(),
+            ),
+        );
     }
 }
 
@@ -0,0 +1,81 @@
+//! Replays the queries of a query log on this instance, e.g., on a development build, and prints a report
+//! that compares the recorded and replayed durations as JSON.
+//!
+//! Usage: `replay_queries <query log file>`
+
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
+use geoengine_operators::util::gdal::register_gdal_drivers_from_list;
+use geoengine_services::contexts::AdminSession;
+use geoengine_services::error::Result;
+use geoengine_services::util::config::{self, get_config_element};
+use geoengine_services::workflows::query_log::read_query_log;
+use geoengine_services::workflows::replay::{replay_query_log, ReplayReport};
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    let path: PathBuf = std::env::args_os()
+        .nth(1)
+        .expect("usage: replay_queries <query log file>")
+        .into();
+
+    let report = replay(path).await.unwrap();
+
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+#[cfg(not(feature = "pro"))]
+async fn replay(path: PathBuf) -> Result<ReplayReport> {
+    use geoengine_services::contexts::{Context, InMemoryContext};
+
+    let entries = read_query_log(&path).await?;
+
+    let data_path_config: config::DataProvider = get_config_element()?;
+    let query_context_config: config::QueryContext = get_config_element()?;
+    MEMORY_ACCOUNTANT.set_budget_bytes(query_context_config.memory_budget_bytes);
+
+    register_gdal_drivers_from_list(get_config_element::<config::Gdal>()?.allowed_drivers);
+
+    let ctx = InMemoryContext::new_with_data(
+        data_path_config.dataset_defs_path,
+        data_path_config.provider_defs_path,
+        data_path_config.layer_defs_path,
+        data_path_config.layer_collection_defs_path,
+        get_config_element::<config::TilingSpecification>()?.into(),
+        query_context_config.chunk_byte_size.into(),
+    )
+    .await;
+
+    let session = <InMemoryContext as Context>::Session::from(AdminSession::default());
+
+    Ok(replay_query_log(&ctx, session, &entries).await)
+}
+
+#[cfg(feature = "pro")]
+async fn replay(path: PathBuf) -> Result<ReplayReport> {
+    use geoengine_services::contexts::Context;
+    use geoengine_services::pro::contexts::ProInMemoryContext;
+
+    let entries = read_query_log(&path).await?;
+
+    let data_path_config: config::DataProvider = get_config_element()?;
+    let query_context_config: config::QueryContext = get_config_element()?;
+    MEMORY_ACCOUNTANT.set_budget_bytes(query_context_config.memory_budget_bytes);
+
+    register_gdal_drivers_from_list(get_config_element::<config::Gdal>()?.allowed_drivers);
+
+    let ctx = ProInMemoryContext::new_with_data(
+        data_path_config.dataset_defs_path,
+        data_path_config.provider_defs_path,
+        data_path_config.layer_defs_path,
+        data_path_config.layer_collection_defs_path,
+        get_config_element::<config::TilingSpecification>()?.into(),
+        query_context_config.chunk_byte_size.into(),
+        get_config_element()?,
+    )
+    .await;
+
+    let session = <ProInMemoryContext as Context>::Session::from(AdminSession::default());
+
+    Ok(replay_query_log(&ctx, session, &entries).await)
+}
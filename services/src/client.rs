@@ -0,0 +1,349 @@
+//! A typed client for the REST API of a Geo Engine server, e.g., for data pipelines written in Rust.
+//!
+//! The client uses the same request and response types as the handlers of the server.
+
+use crate::api::model::datatypes::DatasetId;
+use crate::api::model::operators::TypedResultDescriptor;
+use crate::contexts::{Session, SessionId, SimpleSession};
+use crate::datasets::listing::{DatasetListOptions, DatasetListing};
+use crate::datasets::storage::Dataset;
+use crate::error::{self, Result};
+use crate::handlers::ErrorResponse;
+use crate::util::IdResponse;
+use crate::workflows::workflow::{Workflow, WorkflowId};
+use geoengine_datatypes::primitives::TimeInterval;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+use url::Url;
+
+/// A client that sends its requests on behalf of a session
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: Url,
+    session_id: Option<SessionId>,
+    http: reqwest::Client,
+}
+
+/// The parameters of a WMS `GetMap` request
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapRequest {
+    /// The bounds in the axis order of the `crs`, e.g., `[min_lat, min_lon, max_lat, max_lon]` for `EPSG:4326`
+    pub bbox: [f64; 4],
+    pub crs: SpatialReference,
+    pub width: u32,
+    pub height: u32,
+    pub time: Option<TimeInterval>,
+    /// The style of the map, e.g., `custom:` followed by a colorizer as JSON
+    pub styles: String,
+}
+
+/// The parameters of a WFS `GetFeature` request
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureRequest {
+    /// The bounds in the axis order of the `srs_name` or the workflow's spatial reference
+    pub bbox: [f64; 4],
+    pub srs_name: Option<SpatialReference>,
+    pub time: Option<TimeInterval>,
+}
+
+impl Client {
+    /// Creates a client without a session for the API at `base_url`, e.g., `http://localhost:3030/api/`
+    pub fn new(mut base_url: Url) -> Self {
+        // otherwise, joining paths would replace the last path segment
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        Self {
+            base_url,
+            session_id: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Uses the session with the given id for all requests
+    #[must_use]
+    pub fn with_session(mut self, session_id: SessionId) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Creates a client with a new anonymous session
+    pub async fn anonymous(base_url: Url) -> Result<Self> {
+        let client = Self::new(base_url);
+
+        let session: SimpleSession = client
+            .json(client.request(Method::POST, "anonymous")?)
+            .await?;
+
+        Ok(client.with_session(session.id()))
+    }
+
+    pub fn session_id(&self) -> Option<SessionId> {
+        self.session_id
+    }
+
+    pub async fn session(&self) -> Result<SimpleSession> {
+        self.json(self.request(Method::GET, "session")?).await
+    }
+
+    /// Registers a workflow and returns its id
+    pub async fn register_workflow(&self, workflow: &Workflow) -> Result<WorkflowId> {
+        let response: IdResponse<WorkflowId> = self
+            .json(self.request(Method::POST, "workflow")?.json(workflow))
+            .await?;
+
+        Ok(response.id)
+    }
+
+    pub async fn workflow(&self, id: WorkflowId) -> Result<Workflow> {
+        self.json(self.request(Method::GET, &format!("workflow/{}", id))?)
+            .await
+    }
+
+    /// Returns the result descriptor of a workflow
+    pub async fn workflow_metadata(&self, id: WorkflowId) -> Result<TypedResultDescriptor> {
+        self.json(self.request(Method::GET, &format!("workflow/{}/metadata", id))?)
+            .await
+    }
+
+    pub async fn datasets(&self, options: &DatasetListOptions) -> Result<Vec<DatasetListing>> {
+        self.json(self.request(Method::GET, "datasets")?.query(options))
+            .await
+    }
+
+    pub async fn dataset(&self, id: DatasetId) -> Result<Dataset> {
+        self.json(self.request(Method::GET, &format!("dataset/{}", id))?)
+            .await
+    }
+
+    /// Renders a workflow as PNG image
+    pub async fn wms_map(&self, workflow: WorkflowId, request: &MapRequest) -> Result<Vec<u8>> {
+        let mut query = vec![
+            ("service", "WMS".to_string()),
+            ("version", "1.3.0".to_string()),
+            ("request", "GetMap".to_string()),
+            ("layers", workflow.to_string()),
+            ("bbox", ogc_bbox(request.bbox)),
+            ("crs", request.crs.to_string()),
+            ("width", request.width.to_string()),
+            ("height", request.height.to_string()),
+            ("format", "image/png".to_string()),
+            ("styles", request.styles.clone()),
+        ];
+        if let Some(time) = request.time {
+            query.push(("time", ogc_time(time)));
+        }
+
+        let response = self
+            .send(
+                self.request(Method::GET, &format!("wms/{}", workflow))?
+                    .query(&query),
+            )
+            .await?;
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Queries the features of a workflow as GeoJSON feature collection
+    pub async fn wfs_features(
+        &self,
+        workflow: WorkflowId,
+        request: &FeatureRequest,
+    ) -> Result<serde_json::Value> {
+        let mut query = vec![
+            ("service", "WFS".to_string()),
+            ("version", "2.0.0".to_string()),
+            ("request", "GetFeature".to_string()),
+            ("typeNames", workflow.to_string()),
+            ("bbox", ogc_bbox(request.bbox)),
+        ];
+        if let Some(srs_name) = request.srs_name {
+            query.push(("srsName", srs_name.to_string()));
+        }
+        if let Some(time) = request.time {
+            query.push(("time", ogc_time(time)));
+        }
+
+        self.json(
+            self.request(Method::GET, &format!("wfs/{}", workflow))?
+                .query(&query),
+        )
+        .await
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let url = self.base_url.join(path).context(error::Url)?;
+
+        let request = self.http.request(method, url);
+
+        Ok(match self.session_id {
+            Some(session_id) => request.bearer_auth(session_id),
+            None => request,
+        })
+    }
+
+    /// Sends the request and turns error responses of the server into errors
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await?;
+        let ErrorResponse { error, message } =
+            serde_json::from_str(&body).unwrap_or(ErrorResponse {
+                error: status.to_string(),
+                message: body,
+            });
+
+        Err(error::Error::ApiRequestFailed {
+            status: status.as_u16(),
+            error,
+            message,
+        })
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        Ok(self.send(request).await?.json().await?)
+    }
+}
+
+fn ogc_bbox(bbox: [f64; 4]) -> String {
+    bbox.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn ogc_time(time: TimeInterval) -> String {
+    format!("{}/{}", time.start().as_rfc3339(), time.end().as_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::Coordinate2D;
+    use geoengine_operators::engine::{TypedOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+    use httptest::{
+        all_of,
+        matchers::{contains, request, url_decoded},
+        responders::{json_encoded, status_code},
+        Expectation, Server,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn it_registers_workflows_with_an_anonymous_session() {
+        let session = SimpleSession::default();
+        let workflow_id = WorkflowId::new();
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/api/anonymous"))
+                .respond_with(json_encoded(&session)),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/api/workflow"),
+                request::headers(contains((
+                    "authorization",
+                    format!("Bearer {}", session.id())
+                ))),
+            ])
+            .respond_with(json_encoded(json!({ "id": workflow_id }))),
+        );
+
+        let client = Client::anonymous(Url::parse(&server.url_str("/api")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(client.session_id(), Some(session.id()));
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![Coordinate2D::new(1., 2.)],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        assert_eq!(
+            client.register_workflow(&workflow).await.unwrap(),
+            workflow_id
+        );
+    }
+
+    #[tokio::test]
+    async fn it_queries_features() {
+        let workflow_id = WorkflowId::new();
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", format!("/wfs/{}", workflow_id)),
+                request::query(url_decoded(contains(("request", "GetFeature")))),
+                request::query(url_decoded(contains(("bbox", "-90,-180,90,180")))),
+                request::query(url_decoded(contains(("srsName", "EPSG:4326")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "type": "FeatureCollection",
+                "features": []
+            }))),
+        );
+
+        let client = Client::new(Url::parse(&server.url_str("/")).unwrap());
+
+        let features = client
+            .wfs_features(
+                workflow_id,
+                &FeatureRequest {
+                    bbox: [-90., -180., 90., 180.],
+                    srs_name: Some(SpatialReference::epsg_4326()),
+                    time: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            features,
+            json!({
+                "type": "FeatureCollection",
+                "features": []
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_returns_server_errors() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/session")).respond_with(
+                status_code(401).body(
+                    json!({
+                        "error": "Authorization",
+                        "message": "Authorization error: Header with authorization token not provided."
+                    })
+                    .to_string(),
+                ),
+            ),
+        );
+
+        let client = Client::new(Url::parse(&server.url_str("/")).unwrap());
+
+        let result = client.session().await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::ApiRequestFailed { status: 401, error, .. }) if error == "Authorization"
+        ));
+    }
+}
@@ -3,7 +3,10 @@ use std::sync::Arc;
 
 use super::{Context, Db, SimpleSession};
 use super::{Session, SimpleContext};
-use crate::contexts::{ExecutionContextImpl, QueryContextImpl, SessionId};
+use crate::contexts::{
+    create_cpu_thread_pool, create_io_thread_pool, create_statistics_cache, ExecutionContextImpl,
+    QueryContextImpl, SessionId,
+};
 use crate::datasets::in_memory::HashMapDatasetDb;
 use crate::error::Error;
 use crate::layers::add_from_directory::{
@@ -19,8 +22,7 @@ use crate::{projects::hashmap_projectdb::HashMapProjectDb, workflows::registry::
 use async_trait::async_trait;
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_datatypes::util::test::TestDefault;
-use geoengine_operators::engine::ChunkByteSize;
-use geoengine_operators::util::create_rayon_thread_pool;
+use geoengine_operators::engine::{ChunkByteSize, InMemoryStatisticsCache};
 use rayon::ThreadPool;
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
@@ -35,6 +37,8 @@ pub struct InMemoryContext {
     task_manager: Arc<SimpleTaskManager>,
     session: Db<SimpleSession>,
     thread_pool: Arc<ThreadPool>,
+    io_thread_pool: Arc<ThreadPool>,
+    statistics_cache: Arc<InMemoryStatisticsCache>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
 }
@@ -49,7 +53,9 @@ impl TestDefault for InMemoryContext {
             layer_provider_db: Default::default(),
             task_manager: Default::default(),
             session: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
         }
@@ -73,7 +79,12 @@ impl InMemoryContext {
         add_datasets_from_directory(&mut dataset_db, dataset_defs_path).await;
 
         let mut layer_proivder_db = HashMapLayerProviderDb::default();
-        add_providers_from_directory(&mut layer_proivder_db, provider_defs_path).await;
+        add_providers_from_directory(
+            &SimpleSession::default(),
+            &mut layer_proivder_db,
+            provider_defs_path,
+        )
+        .await;
 
         Self {
             project_db: Default::default(),
@@ -82,7 +93,9 @@ impl InMemoryContext {
             layer_provider_db: Arc::new(layer_proivder_db),
             task_manager: Default::default(),
             session: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             dataset_db: Arc::new(dataset_db),
@@ -101,7 +114,9 @@ impl InMemoryContext {
             layer_provider_db: Default::default(),
             task_manager: Default::default(),
             session: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
         }
@@ -180,8 +195,11 @@ impl Context for InMemoryContext {
             self.dataset_db.clone(),
             self.layer_provider_db.clone(),
             self.thread_pool.clone(),
+            self.io_thread_pool.clone(),
             session,
             self.exe_ctx_tiling_spec,
+            self.statistics_cache.clone(),
+            self.workflow_registry.clone(),
         ))
     }
 
@@ -13,35 +13,67 @@ mod session;
 mod simple_context;
 
 use crate::datasets::storage::DatasetDb;
+use crate::datasets::usage::record_dataset_access;
 
 use geoengine_datatypes::dataset::DataId;
 
 use geoengine_datatypes::raster::TilingSpecification;
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
 use geoengine_operators::engine::{
-    ChunkByteSize, CreateSpan, ExecutionContext, InitializedPlotOperator,
-    InitializedVectorOperator, MetaData, MetaDataProvider, QueryAbortRegistration,
-    QueryAbortTrigger, QueryContext, RasterResultDescriptor, VectorResultDescriptor,
+    AuxiliaryMetadata, AuxiliaryMetadataProvider, ChunkByteSize, CreateSpan, ExecutionContext,
+    InMemoryStatisticsCache, InitializedPlotOperator, InitializedVectorOperator, MetaData,
+    MetaDataProvider, PathSandbox, QueryAbortRegistration, QueryAbortTrigger, QueryContext,
+    QueryMessageReceiver, QueryMessages, QueryStarted, RasterResultDescriptor, StatisticsCache,
+    TypedOperator, VectorResultDescriptor, WorkflowProvider,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
 
-use crate::datasets::listing::SessionMetaDataProvider;
+use crate::datasets::listing::{DatasetProvider, SessionMetaDataProvider};
+use crate::util::config::{get_config_element, ThreadPools, WorkflowStatistics};
+use crate::util::operators::{OPERATOR_POLICY, PATH_SANDBOX};
+use crate::workflows::workflow::WorkflowId;
+use geoengine_operators::util::create_rayon_thread_pool;
 pub use in_memory::InMemoryContext;
 pub use session::{AdminSession, MockableSession, Session, SessionId, SimpleSession};
 pub use simple_context::SimpleContext;
 
 pub type Db<T> = Arc<RwLock<T>>;
 
+/// Creates the cache for statistics of workflow results with the capacity of the `workflow_statistics` config
+pub fn create_statistics_cache() -> Arc<InMemoryStatisticsCache> {
+    let capacity = get_config_element::<WorkflowStatistics>()
+        .map_or(0, |workflow_statistics| workflow_statistics.cache_capacity);
+
+    Arc::new(InMemoryStatisticsCache::new(capacity))
+}
+
+/// Creates the thread pool for computations with the size of the `thread_pools` config
+pub fn create_cpu_thread_pool() -> Arc<ThreadPool> {
+    let num_threads =
+        get_config_element::<ThreadPools>().map_or(0, |thread_pools| thread_pools.cpu_threads);
+
+    create_rayon_thread_pool(num_threads)
+}
+
+/// Creates the thread pool for blocking IO with the size of the `thread_pools` config
+pub fn create_io_thread_pool() -> Arc<ThreadPool> {
+    let num_threads =
+        get_config_element::<ThreadPools>().map_or(0, |thread_pools| thread_pools.io_threads);
+
+    create_rayon_thread_pool(num_threads)
+}
+
 /// A context bundles access to shared resources like databases and session specific information
 /// about the user to pass to the services handlers.
 #[async_trait]
 pub trait Context: 'static + Send + Sync + Clone {
     type Session: MockableSession + Clone + From<AdminSession>; // TODO: change to `[Session]` when workarounds are gone
     type ProjectDB: ProjectDb<Self::Session>;
-    type WorkflowRegistry: WorkflowRegistry;
+    type WorkflowRegistry: WorkflowRegistry<Self::Session>;
     type DatasetDB: DatasetDb<Self::Session>;
     type LayerDB: LayerDb;
-    type LayerProviderDB: LayerProviderDb;
+    type LayerProviderDB: LayerProviderDb<Self::Session>;
     type QueryContext: QueryContext;
     type ExecutionContext: ExecutionContext;
     type TaskContext: TaskContext;
@@ -77,6 +109,8 @@ pub struct QueryContextImpl {
     thread_pool: Arc<ThreadPool>,
     abort_registration: QueryAbortRegistration,
     abort_trigger: Option<QueryAbortTrigger>,
+    messages: QueryMessages,
+    query_started: QueryStarted,
 }
 
 impl QueryContextImpl {
@@ -87,6 +121,8 @@ impl QueryContextImpl {
             thread_pool,
             abort_registration,
             abort_trigger: Some(abort_trigger),
+            messages: QueryMessages::default(),
+            query_started: QueryStarted::default(),
         }
     }
 }
@@ -94,6 +130,7 @@ impl QueryContextImpl {
 impl QueryContext for QueryContextImpl {
     fn chunk_byte_size(&self) -> ChunkByteSize {
         self.chunk_byte_size
+            .adapt_to_memory_pressure(MEMORY_ACCOUNTANT.pressure())
     }
 
     fn thread_pool(&self) -> &Arc<ThreadPool> {
@@ -109,40 +146,64 @@ impl QueryContext for QueryContextImpl {
             .take()
             .ok_or(geoengine_operators::error::Error::AbortTriggerAlreadyUsed)
     }
+
+    fn messages(&self) -> &QueryMessages {
+        &self.messages
+    }
+
+    fn subscribe_messages(&mut self) -> QueryMessageReceiver {
+        let (messages, receiver) = QueryMessages::new();
+        self.messages = messages;
+        receiver
+    }
+
+    fn query_started(&self) -> &QueryStarted {
+        &self.query_started
+    }
 }
 
 pub struct ExecutionContextImpl<S, D, L>
 where
     D: DatasetDb<S>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     dataset_db: Arc<D>,
     layer_provider_db: Arc<L>,
     thread_pool: Arc<ThreadPool>,
+    io_thread_pool: Arc<ThreadPool>,
     session: S,
     tiling_specification: TilingSpecification,
+    statistics_cache: Arc<dyn StatisticsCache>,
+    workflow_registry: Arc<dyn WorkflowRegistry<S>>,
 }
 
 impl<S, D, L> ExecutionContextImpl<S, D, L>
 where
     D: DatasetDb<S>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         dataset_db: Arc<D>,
         layer_provider_db: Arc<L>,
         thread_pool: Arc<ThreadPool>,
+        io_thread_pool: Arc<ThreadPool>,
         session: S,
         tiling_specification: TilingSpecification,
+        statistics_cache: Arc<dyn StatisticsCache>,
+        workflow_registry: Arc<dyn WorkflowRegistry<S>>,
     ) -> Self {
         Self {
             dataset_db,
             layer_provider_db,
             thread_pool,
+            io_thread_pool,
             session,
             tiling_specification,
+            statistics_cache,
+            workflow_registry,
         }
     }
 }
@@ -157,13 +218,17 @@ where
             VectorQueryRectangle,
         > + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     fn thread_pool(&self) -> &Arc<ThreadPool> {
         &self.thread_pool
     }
 
+    fn io_thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.io_thread_pool
+    }
+
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
@@ -191,6 +256,65 @@ where
     ) -> Box<dyn InitializedPlotOperator> {
         op
     }
+
+    fn statistics_cache(&self) -> Option<Arc<dyn StatisticsCache>> {
+        Some(self.statistics_cache.clone())
+    }
+
+    fn ensure_operator_is_allowed(&self, operator: &str) -> geoengine_operators::util::Result<()> {
+        OPERATOR_POLICY.ensure_is_allowed(operator)
+    }
+
+    fn path_sandbox(&self) -> &PathSandbox {
+        &PATH_SANDBOX
+    }
+}
+
+#[async_trait]
+impl<S, D, L> AuxiliaryMetadataProvider for ExecutionContextImpl<S, D, L>
+where
+    D: DatasetDb<S>,
+    L: LayerProviderDb<S>,
+    S: Session,
+{
+    async fn auxiliary_metadata(
+        &self,
+        data_id: &DataId,
+    ) -> Result<Option<AuxiliaryMetadata>, geoengine_operators::error::Error> {
+        match data_id {
+            DataId::Internal { dataset_id } => self
+                .dataset_db
+                .auxiliary_metadata(&self.session, &(*dataset_id).into())
+                .await
+                .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
+                    source: Box::new(e),
+                }),
+            // external providers do not offer auxiliary metadata
+            DataId::External(_) => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl<S, D, L> WorkflowProvider for ExecutionContextImpl<S, D, L>
+where
+    D: DatasetDb<S>,
+    L: LayerProviderDb<S>,
+    S: Session,
+{
+    async fn workflow(
+        &self,
+        id: &uuid::Uuid,
+    ) -> Result<TypedOperator, geoengine_operators::error::Error> {
+        self.workflow_registry
+            .load(&self.session, &WorkflowId(*id))
+            .await
+            .map(|workflow| workflow.operator)
+            .map_err(|e| geoengine_operators::error::Error::WorkflowReference {
+                workflow: *id,
+                source: Box::new(e),
+            })
+    }
 }
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
@@ -206,7 +330,7 @@ where
             VectorResultDescriptor,
             VectorQueryRectangle,
         >,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -223,16 +347,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
@@ -251,7 +381,7 @@ impl<S, D, L> MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQ
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -262,16 +392,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
@@ -290,7 +426,7 @@ impl<S, D, L> MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQu
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -301,16 +437,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
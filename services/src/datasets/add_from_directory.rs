@@ -9,7 +9,10 @@ use crate::error::Result;
 use crate::layers::external::DataProviderDefinition;
 use crate::layers::storage::LayerProviderDb;
 use crate::util::user_input::UserInput;
-use crate::{contexts::MockableSession, datasets::storage::DatasetDb};
+use crate::{
+    contexts::{MockableSession, Session},
+    datasets::storage::DatasetDb,
+};
 
 use super::storage::DatasetDefinition;
 
@@ -62,15 +65,20 @@ pub async fn add_datasets_from_directory<S: MockableSession, D: DatasetDb<S>>(
 }
 
 // TODO: move to layers source dir
-pub async fn add_providers_from_directory<D: LayerProviderDb>(db: &mut D, file_path: PathBuf) {
-    async fn add_provider_definition_from_dir_entry<D: LayerProviderDb>(
+pub async fn add_providers_from_directory<S: Session, D: LayerProviderDb<S>>(
+    session: &S,
+    db: &mut D,
+    file_path: PathBuf,
+) {
+    async fn add_provider_definition_from_dir_entry<S: Session, D: LayerProviderDb<S>>(
+        session: &S,
         db: &mut D,
         entry: &DirEntry,
     ) -> Result<()> {
         let def: Box<dyn DataProviderDefinition> =
             serde_json::from_reader(BufReader::new(File::open(entry.path())?))?;
 
-        db.add_layer_provider(def).await?; // TODO: add as system user
+        db.add_layer_provider(session, def).await?;
         Ok(())
     }
 
@@ -86,7 +94,7 @@ pub async fn add_providers_from_directory<D: LayerProviderDb>(db: &mut D, file_p
             if entry.path().is_dir() {
                 continue;
             }
-            if let Err(e) = add_provider_definition_from_dir_entry(db, &entry).await {
+            if let Err(e) = add_provider_definition_from_dir_entry(session, db, &entry).await {
                 // TODO: log
                 warn!(
                     "Skipped adding provider from directory entry: {:?} error: {}",
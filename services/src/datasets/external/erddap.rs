@@ -0,0 +1,1541 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::api::model::datatypes::{DataId, DataProviderId, ExternalDataId, LayerId};
+use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::error::{self, Error, Result};
+use crate::layers::cache::CacheTtlSeconds;
+use crate::layers::external::{DataProvider, DataProviderDefinition, ProviderCapabilities};
+use crate::layers::layer::{
+    CollectionItem, Layer, LayerCollection, LayerCollectionListOptions, LayerCollectionListing,
+    LayerListing, ProviderLayerCollectionId, ProviderLayerId,
+};
+use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::util::parsing::deserialize_base_url;
+use crate::util::user_input::Validated;
+use crate::workflows::workflow::Workflow;
+use async_trait::async_trait;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::primitives::{
+    Coordinate2D, DateTime, DateTimeParseFormat, FeatureDataType, Measurement,
+    RasterQueryRectangle, SpatialPartition2D, SpatialResolution, TimeInstance, TimeInterval,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::RasterDataType;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterOperator, RasterResultDescriptor, StaticMetaData,
+    TypedOperator, VectorColumnInfo, VectorOperator, VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    CsvHeader, FileNotFoundHandling, FormatSpecifics, GdalDatasetGeoTransform,
+    GdalDatasetParameters, GdalLoadingInfo, GdalLoadingInfoTemporalSlice, GdalMetaDataList,
+    GdalMetaDataStatic, GdalSource, GdalSourceParameters, OgrSource, OgrSourceColumnSpec,
+    OgrSourceDataset, OgrSourceDatasetTimeType, OgrSourceDurationSpec, OgrSourceErrorSpec,
+    OgrSourceParameters, OgrSourceTimeFormat,
+};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use url::Url;
+
+const TIME_DIMENSION: &str = "time";
+const LATITUDE_DIMENSION: &str = "latitude";
+const LONGITUDE_DIMENSION: &str = "longitude";
+
+/// The ERDDAP provider allows to include gridded (`griddap`) and tabular (`tabledap`)
+/// datasets from an ERDDAP server, e.g. <https://coastwatch.pfeg.noaa.gov/erddap/>.
+/// Gridded variables are mapped to raster layers, tabular datasets to point layers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErddapDataProviderDefinition {
+    id: DataProviderId,
+    name: String,
+    #[serde(deserialize_with = "deserialize_base_url")]
+    base_url: Url,
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl DataProviderDefinition for ErddapDataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn DataProvider>> {
+        Ok(Box::new(ErddapDataProvider {
+            id: self.id,
+            name: self.name,
+            base_url: self.base_url,
+            client: Client::new(),
+        }))
+    }
+
+    fn type_name(&self) -> &'static str {
+        "ERDDAP"
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DataProviderId {
+        self.id
+    }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
+}
+
+#[derive(Debug)]
+pub struct ErddapDataProvider {
+    id: DataProviderId,
+    name: String,
+    base_url: Url,
+    client: Client,
+}
+
+/// The two ERDDAP protocols for accessing data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErddapProtocol {
+    Griddap,
+    Tabledap,
+}
+
+impl ErddapProtocol {
+    fn as_str(self) -> &'static str {
+        match self {
+            ErddapProtocol::Griddap => "griddap",
+            ErddapProtocol::Tabledap => "tabledap",
+        }
+    }
+}
+
+/// Identifies collections and layers of the provider:
+///  - `griddap/{dataset}` is a collection of the variables of a gridded dataset
+///  - `griddap/{dataset}/{variable}` is a raster layer, or a collection of levels
+///    if the variable has an additional (e.g. depth) dimension
+///  - `griddap/{dataset}/{variable}/{level}` is a raster layer at the given level
+///  - `tabledap/{dataset}` is a point layer
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ErddapLayerId {
+    Root,
+    Dataset {
+        protocol: ErddapProtocol,
+        dataset: String,
+    },
+    Variable {
+        dataset: String,
+        variable: String,
+    },
+    Level {
+        dataset: String,
+        variable: String,
+        level: String,
+    },
+}
+
+impl FromStr for ErddapLayerId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let split = s.split('/').collect::<Vec<_>>();
+
+        Ok(match *split.as_slice() {
+            ["root"] => ErddapLayerId::Root,
+            ["griddap", dataset] => ErddapLayerId::Dataset {
+                protocol: ErddapProtocol::Griddap,
+                dataset: dataset.to_string(),
+            },
+            ["tabledap", dataset] => ErddapLayerId::Dataset {
+                protocol: ErddapProtocol::Tabledap,
+                dataset: dataset.to_string(),
+            },
+            ["griddap", dataset, variable] => ErddapLayerId::Variable {
+                dataset: dataset.to_string(),
+                variable: variable.to_string(),
+            },
+            ["griddap", dataset, variable, level] => ErddapLayerId::Level {
+                dataset: dataset.to_string(),
+                variable: variable.to_string(),
+                level: level.to_string(),
+            },
+            _ => return Err(Error::InvalidLayerId),
+        })
+    }
+}
+
+impl std::fmt::Display for ErddapLayerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErddapLayerId::Root => write!(f, "root"),
+            ErddapLayerId::Dataset { protocol, dataset } => {
+                write!(f, "{}/{}", protocol.as_str(), dataset)
+            }
+            ErddapLayerId::Variable { dataset, variable } => {
+                write!(f, "griddap/{}/{}", dataset, variable)
+            }
+            ErddapLayerId::Level {
+                dataset,
+                variable,
+                level,
+            } => write!(f, "griddap/{}/{}/{}", dataset, variable, level),
+        }
+    }
+}
+
+/// The JSON table format that ERDDAP uses for all of its `.json` responses
+#[derive(Debug, Deserialize)]
+struct ErddapTableResponse {
+    table: ErddapTable,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ErddapTable {
+    column_names: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl ErddapTable {
+    /// Returns the rows as strings, restricted to the `columns` in the given order
+    fn select(&self, columns: &[&str]) -> Result<Vec<Vec<String>>> {
+        let indices = columns
+            .iter()
+            .map(|column| {
+                self.column_names
+                    .iter()
+                    .position(|name| name == column)
+                    .ok_or_else(|| Error::ErddapInvalidMetadata {
+                        reason: format!("missing column `{}`", column),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(self
+            .rows
+            .iter()
+            .map(|row| {
+                indices
+                    .iter()
+                    .map(|&i| row.get(i).map(value_to_string).unwrap_or_default())
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ErddapDatasetListing {
+    dataset_id: String,
+    title: String,
+    summary: String,
+    protocol: ErddapProtocol,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ErddapVariable {
+    name: String,
+    data_type: String,
+    /// For variables the names of the dimensions, for dimensions the dimension info
+    /// (e.g. `nValues=4, evenlySpaced=true`)
+    value: String,
+    attributes: HashMap<String, String>,
+}
+
+impl ErddapVariable {
+    fn dimensions(&self) -> Vec<&str> {
+        self.value
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .collect()
+    }
+
+    /// Additional dimensions of a gridded variable beside time, latitude and longitude
+    fn level_dimensions(&self) -> Vec<&str> {
+        self.dimensions()
+            .into_iter()
+            .filter(|d| ![TIME_DIMENSION, LATITUDE_DIMENSION, LONGITUDE_DIMENSION].contains(d))
+            .collect()
+    }
+
+    fn number_of_values(&self) -> Option<usize> {
+        self.value
+            .split(',')
+            .map(str::trim)
+            .find_map(|info| info.strip_prefix("nValues="))
+            .and_then(|n| n.parse().ok())
+    }
+
+    fn actual_range(&self) -> Option<(f64, f64)> {
+        let range = self.attributes.get("actual_range")?;
+        let values: Vec<f64> = range
+            .split(',')
+            .map(|v| v.trim().parse())
+            .collect::<Result<_, _>>()
+            .ok()?;
+
+        match *values.as_slice() {
+            [a, b] => Some((a.min(b), a.max(b))),
+            _ => None,
+        }
+    }
+
+    fn description(&self) -> String {
+        self.attributes
+            .get("long_name")
+            .cloned()
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    fn measurement(&self) -> Measurement {
+        match self.attributes.get("units") {
+            Some(unit) => Measurement::continuous(self.description(), Some(unit.clone())),
+            None => Measurement::Unitless,
+        }
+    }
+
+    fn no_data_value(&self) -> Option<f64> {
+        self.attributes
+            .get("_FillValue")
+            .or_else(|| self.attributes.get("missing_value"))
+            .and_then(|v| v.trim().parse().ok())
+    }
+
+    fn raster_data_type(&self) -> Result<RasterDataType> {
+        Ok(match self.data_type.as_str() {
+            "byte" => RasterDataType::I8,
+            "ubyte" => RasterDataType::U8,
+            "short" => RasterDataType::I16,
+            "ushort" => RasterDataType::U16,
+            "int" => RasterDataType::I32,
+            "uint" => RasterDataType::U32,
+            "long" => RasterDataType::I64,
+            "ulong" => RasterDataType::U64,
+            "float" => RasterDataType::F32,
+            "double" => RasterDataType::F64,
+            _ => {
+                return Err(Error::ErddapInvalidMetadata {
+                    reason: format!(
+                        "variable `{}` has unsupported data type `{}`",
+                        self.name, self.data_type
+                    ),
+                })
+            }
+        })
+    }
+
+    fn feature_data_type(&self) -> FeatureDataType {
+        match self.data_type.as_str() {
+            "byte" | "ubyte" | "short" | "ushort" | "int" | "uint" | "long" | "ulong" => {
+                FeatureDataType::Int
+            }
+            "float" | "double" => FeatureDataType::Float,
+            _ => FeatureDataType::Text,
+        }
+    }
+}
+
+/// The metadata of a dataset as returned by `info/{dataset}/index.json`
+#[derive(Debug, Clone, Default)]
+struct ErddapDatasetInfo {
+    global: HashMap<String, String>,
+    dimensions: Vec<ErddapVariable>,
+    variables: Vec<ErddapVariable>,
+}
+
+impl ErddapDatasetInfo {
+    fn from_table(table: &ErddapTable) -> Result<Self> {
+        let rows = table.select(&[
+            "Row Type",
+            "Variable Name",
+            "Attribute Name",
+            "Data Type",
+            "Value",
+        ])?;
+
+        let mut info = ErddapDatasetInfo::default();
+
+        for row in rows {
+            let [row_type, variable, attribute, data_type, value]: [String; 5] =
+                row.try_into().map_err(|_| Error::ErddapInvalidMetadata {
+                    reason: "invalid dataset info row".to_owned(),
+                })?;
+
+            let entry = ErddapVariable {
+                name: variable.clone(),
+                data_type,
+                value,
+                attributes: HashMap::new(),
+            };
+
+            match row_type.as_str() {
+                "dimension" => info.dimensions.push(entry),
+                "variable" => info.variables.push(entry),
+                "attribute" if variable == "NC_GLOBAL" => {
+                    info.global.insert(attribute, entry.value);
+                }
+                "attribute" => {
+                    if let Some(v) = info
+                        .dimensions
+                        .iter_mut()
+                        .chain(info.variables.iter_mut())
+                        .find(|v| v.name == variable)
+                    {
+                        v.attributes.insert(attribute, entry.value);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(info)
+    }
+
+    fn dimension(&self, dataset: &str, name: &str) -> Result<&ErddapVariable> {
+        self.dimensions
+            .iter()
+            .find(|d| d.name == name)
+            .ok_or_else(|| Error::ErddapMissingDimension {
+                dataset: dataset.to_owned(),
+                dimension: name.to_owned(),
+            })
+    }
+
+    fn variable(&self, name: &str) -> Option<&ErddapVariable> {
+        self.variables.iter().find(|v| v.name == name)
+    }
+
+    fn title(&self, dataset: &str) -> String {
+        self.global
+            .get("title")
+            .cloned()
+            .unwrap_or_else(|| dataset.to_owned())
+    }
+}
+
+/// A regularly spaced latitude or longitude axis of a gridded dataset
+#[derive(Debug, Clone, Copy)]
+struct ErddapAxis {
+    min: f64,
+    max: f64,
+    number_of_values: usize,
+    pixel_size: f64,
+}
+
+impl ErddapAxis {
+    fn from_dimension(dataset: &str, dimension: &ErddapVariable) -> Result<Self> {
+        let (min, max) = dimension
+            .actual_range()
+            .ok_or_else(|| Error::ErddapInvalidMetadata {
+                reason: format!("dimension `{}` has no actual_range", dimension.name),
+            })?;
+        let number_of_values =
+            dimension
+                .number_of_values()
+                .ok_or_else(|| Error::ErddapInvalidMetadata {
+                    reason: format!("dimension `{}` has no nValues", dimension.name),
+                })?;
+
+        ensure!(
+            number_of_values > 1,
+            error::ErddapInvalidMetadata {
+                reason: format!(
+                    "dimension `{}` of dataset `{}` must have more than one value",
+                    dimension.name, dataset
+                ),
+            }
+        );
+
+        Ok(Self {
+            min,
+            max,
+            number_of_values,
+            pixel_size: (max - min) / (number_of_values - 1) as f64,
+        })
+    }
+
+    /// The `[(start):(end)]` constraint that selects the whole axis
+    fn constraint(&self) -> String {
+        format!("[({}):({})]", self.min, self.max)
+    }
+}
+
+impl ErddapDataProvider {
+    async fn get_table(&self, path: &str) -> Result<ErddapTable> {
+        let response: ErddapTableResponse = self.get_json(path).await?;
+        Ok(response.table)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Ok(self
+            .client
+            .get(self.base_url.join(path)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    async fn load_datasets(&self) -> Result<Vec<ErddapDatasetListing>> {
+        let table = self.get_table("info/index.json").await?;
+
+        let mut datasets: Vec<ErddapDatasetListing> = table
+            .select(&["griddap", "tabledap", "Title", "Summary", "Dataset ID"])?
+            .into_iter()
+            .filter_map(|row| {
+                let [griddap, tabledap, title, summary, dataset_id]: [String; 5] =
+                    row.try_into().ok()?;
+
+                let protocol = if !griddap.is_empty() {
+                    ErddapProtocol::Griddap
+                } else if !tabledap.is_empty() {
+                    ErddapProtocol::Tabledap
+                } else {
+                    return None;
+                };
+
+                Some(ErddapDatasetListing {
+                    dataset_id,
+                    title,
+                    summary,
+                    protocol,
+                })
+            })
+            .collect();
+
+        datasets.sort_by(|a, b| a.title.cmp(&b.title));
+
+        Ok(datasets)
+    }
+
+    async fn load_dataset_info(&self, dataset: &str) -> Result<ErddapDatasetInfo> {
+        let table = self
+            .get_table(&format!("info/{}/index.json", dataset))
+            .await?;
+        ErddapDatasetInfo::from_table(&table)
+    }
+
+    /// Loads all values of a dimension of a gridded dataset, e.g. the time steps.
+    async fn load_dimension_values(&self, dataset: &str, dimension: &str) -> Result<Vec<String>> {
+        let table = self
+            .get_table(&format!("griddap/{}.json?{}", dataset, dimension))
+            .await?;
+
+        Ok(table.select(&[dimension])?.into_iter().flatten().collect())
+    }
+
+    fn layer_collection_id(&self, id: &ErddapLayerId) -> ProviderLayerCollectionId {
+        ProviderLayerCollectionId {
+            provider_id: self.id,
+            collection_id: LayerCollectionId(id.to_string()),
+        }
+    }
+
+    fn provider_layer_id(&self, id: &ErddapLayerId) -> ProviderLayerId {
+        ProviderLayerId {
+            provider_id: self.id,
+            layer_id: LayerId(id.to_string()),
+        }
+    }
+
+    async fn root_collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: &LayerCollectionListOptions,
+    ) -> Result<LayerCollection> {
+        let items = self
+            .load_datasets()
+            .await?
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|dataset| {
+                let id = ErddapLayerId::Dataset {
+                    protocol: dataset.protocol,
+                    dataset: dataset.dataset_id,
+                };
+
+                match dataset.protocol {
+                    ErddapProtocol::Griddap => CollectionItem::Collection(LayerCollectionListing {
+                        id: self.layer_collection_id(&id),
+                        name: dataset.title,
+                        description: dataset.summary,
+                    }),
+                    ErddapProtocol::Tabledap => CollectionItem::Layer(LayerListing {
+                        id: self.provider_layer_id(&id),
+                        name: dataset.title,
+                        description: dataset.summary,
+                    }),
+                }
+            })
+            .collect();
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name: self.name.clone(),
+            description: "ERDDAP".to_owned(),
+            items,
+            entry_label: Some("Dataset".to_owned()),
+            properties: vec![],
+        })
+    }
+
+    async fn griddap_collection(
+        &self,
+        collection: &LayerCollectionId,
+        dataset: &str,
+        options: &LayerCollectionListOptions,
+    ) -> Result<LayerCollection> {
+        let info = self.load_dataset_info(dataset).await?;
+
+        let items = info
+            .variables
+            .iter()
+            // only a single additional dimension can be selected by the layer id
+            .filter(|variable| variable.level_dimensions().len() <= 1)
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|variable| {
+                let id = ErddapLayerId::Variable {
+                    dataset: dataset.to_owned(),
+                    variable: variable.name.clone(),
+                };
+
+                if variable.level_dimensions().is_empty() {
+                    CollectionItem::Layer(LayerListing {
+                        id: self.provider_layer_id(&id),
+                        name: variable.name.clone(),
+                        description: variable.description(),
+                    })
+                } else {
+                    CollectionItem::Collection(LayerCollectionListing {
+                        id: self.layer_collection_id(&id),
+                        name: variable.name.clone(),
+                        description: variable.description(),
+                    })
+                }
+            })
+            .collect();
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name: info.title(dataset),
+            description: info.global.get("summary").cloned().unwrap_or_default(),
+            items,
+            entry_label: Some("Variable".to_owned()),
+            properties: vec![],
+        })
+    }
+
+    async fn levels_collection(
+        &self,
+        collection: &LayerCollectionId,
+        dataset: &str,
+        variable: &str,
+        options: &LayerCollectionListOptions,
+    ) -> Result<LayerCollection> {
+        let info = self.load_dataset_info(dataset).await?;
+        let variable = info
+            .variable(variable)
+            .ok_or_else(|| Error::UnknownLayerCollectionId {
+                id: collection.clone(),
+            })?;
+
+        let level_dimension = match *variable.level_dimensions().as_slice() {
+            [level_dimension] => level_dimension,
+            _ => {
+                return Err(Error::UnknownLayerCollectionId {
+                    id: collection.clone(),
+                })
+            }
+        };
+
+        let items = self
+            .load_dimension_values(dataset, level_dimension)
+            .await?
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|level| {
+                let name = format!("{} = {}", level_dimension, level);
+                CollectionItem::Layer(LayerListing {
+                    id: self.provider_layer_id(&ErddapLayerId::Level {
+                        dataset: dataset.to_owned(),
+                        variable: variable.name.clone(),
+                        level,
+                    }),
+                    name,
+                    description: variable.description(),
+                })
+            })
+            .collect();
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name: variable.name.clone(),
+            description: variable.description(),
+            items,
+            entry_label: Some(level_dimension.to_owned()),
+            properties: vec![],
+        })
+    }
+
+    /// Creates the meta data for a gridded variable. Each time step is loaded as a `GeoTIFF`
+    /// slice of the variable from the `griddap` service.
+    async fn griddap_meta_data(
+        &self,
+        dataset: &str,
+        variable: &str,
+        level: Option<&str>,
+    ) -> Result<Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>>
+    {
+        let info = self.load_dataset_info(dataset).await?;
+        let variable = info.variable(variable).ok_or(Error::InvalidDataId)?;
+
+        let lat =
+            ErddapAxis::from_dimension(dataset, info.dimension(dataset, LATITUDE_DIMENSION)?)?;
+        let lon =
+            ErddapAxis::from_dimension(dataset, info.dimension(dataset, LONGITUDE_DIMENSION)?)?;
+
+        // ERDDAP returns the cell centers, so the raster extends half a pixel beyond the range
+        let upper_left =
+            Coordinate2D::new(lon.min - lon.pixel_size / 2., lat.max + lat.pixel_size / 2.);
+        let lower_right =
+            Coordinate2D::new(lon.max + lon.pixel_size / 2., lat.min - lat.pixel_size / 2.);
+
+        let has_time = variable.dimensions().contains(&TIME_DIMENSION);
+        let time_steps = if has_time {
+            self.load_dimension_values(dataset, TIME_DIMENSION)
+                .await?
+                .iter()
+                .map(|t| {
+                    DateTime::parse_from_rfc3339(t)
+                        .map(TimeInstance::from)
+                        .map_err(|_| Error::ErddapInvalidMetadata {
+                            reason: format!("invalid time value `{}`", t),
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+
+        let slice_url = |time: Option<TimeInstance>| -> Result<String> {
+            let mut constraints = String::new();
+            for dimension in variable.dimensions() {
+                match dimension {
+                    TIME_DIMENSION => {
+                        let time = time.ok_or(Error::InvalidDataId)?;
+                        constraints.push_str(&format!("[({})]", time.as_rfc3339_with_millis()));
+                    }
+                    LATITUDE_DIMENSION => constraints.push_str(&lat.constraint()),
+                    LONGITUDE_DIMENSION => constraints.push_str(&lon.constraint()),
+                    _ => {
+                        let level = level.ok_or(Error::InvalidDataId)?;
+                        constraints.push_str(&format!("[({})]", level));
+                    }
+                }
+            }
+
+            let url = self.base_url.join(&format!(
+                "griddap/{}.geotif?{}{}",
+                dataset, variable.name, constraints
+            ))?;
+
+            Ok(format!("/vsicurl/{}", url))
+        };
+
+        let params = |file_path: String| GdalDatasetParameters {
+            file_path: PathBuf::from(file_path),
+            rasterband_channel: 1,
+            geo_transform: GdalDatasetGeoTransform {
+                origin_coordinate: upper_left,
+                x_pixel_size: lon.pixel_size,
+                y_pixel_size: -lat.pixel_size,
+            },
+            width: lon.number_of_values,
+            height: lat.number_of_values,
+            file_not_found_handling: FileNotFoundHandling::NoData,
+            no_data_value: variable.no_data_value(),
+            properties_mapping: None,
+            gdal_open_options: None,
+            gdal_config_options: None,
+            allow_alphaband_as_mask: false,
+        };
+
+        let slices = time_steps
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                // the last time step is assumed to be as long as the previous one
+                let end = match (time_steps.get(i + 1), i.checked_sub(1)) {
+                    (Some(&next), _) => next,
+                    (None, Some(previous)) => {
+                        start + (start.inner() - time_steps[previous].inner())
+                    }
+                    (None, None) => start,
+                };
+
+                Ok(GdalLoadingInfoTemporalSlice {
+                    time: TimeInterval::new(start, end)?,
+                    params: Some(params(slice_url(Some(start))?)),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: variable.raster_data_type()?,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            measurement: variable.measurement(),
+            time: match (slices.first(), slices.last()) {
+                (Some(first), Some(last)) => {
+                    Some(TimeInterval::new(first.time.start(), last.time.end())?)
+                }
+                _ => None,
+            },
+            bbox: Some(SpatialPartition2D::new(upper_left, lower_right)?),
+            resolution: Some(SpatialResolution::new(lon.pixel_size, lat.pixel_size)?),
+        };
+
+        if has_time {
+            Ok(Box::new(GdalMetaDataList {
+                result_descriptor,
+                params: slices,
+            }))
+        } else {
+            Ok(Box::new(GdalMetaDataStatic {
+                time: None,
+                params: params(slice_url(None)?),
+                result_descriptor,
+            }))
+        }
+    }
+
+    /// Creates the meta data for a tabular dataset that is loaded as CSV from `tabledap`.
+    async fn tabledap_meta_data(
+        &self,
+        dataset: &str,
+    ) -> Result<StaticMetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>
+    {
+        let info = self.load_dataset_info(dataset).await?;
+
+        // `.csv0` files have no header, so the columns are named `field_1`, `field_2`, …
+        let field_name = |name: &str| {
+            info.variables
+                .iter()
+                .position(|v| v.name == name)
+                .map(|i| format!("field_{}", i + 1))
+                .ok_or_else(|| Error::ErddapMissingDimension {
+                    dataset: dataset.to_owned(),
+                    dimension: name.to_owned(),
+                })
+        };
+
+        let x = field_name(LONGITUDE_DIMENSION)?;
+        let y = field_name(LATITUDE_DIMENSION)?;
+        let time_field = field_name(TIME_DIMENSION).ok();
+
+        let attributes = || {
+            info.variables.iter().enumerate().filter(|(_, v)| {
+                ![TIME_DIMENSION, LATITUDE_DIMENSION, LONGITUDE_DIMENSION]
+                    .contains(&v.name.as_str())
+            })
+        };
+        let fields_of_type = |data_type: FeatureDataType| {
+            attributes()
+                .filter(|(_, v)| v.feature_data_type() == data_type)
+                .map(|(i, _)| format!("field_{}", i + 1))
+                .collect::<Vec<_>>()
+        };
+
+        let variable_names = info
+            .variables
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let url = self
+            .base_url
+            .join(&format!("tabledap/{}.csv0?{}", dataset, variable_names))?;
+
+        let loading_info = OgrSourceDataset {
+            file_name: PathBuf::from(format!("CSV:/vsicurl_streaming/{}", url)),
+            layer_name: dataset.to_owned(),
+            data_type: Some(VectorDataType::MultiPoint),
+            time: match time_field {
+                Some(start_field) => OgrSourceDatasetTimeType::Start {
+                    start_field,
+                    start_format: OgrSourceTimeFormat::Custom {
+                        custom_format: DateTimeParseFormat::custom("%Y-%m-%dT%H:%M:%SZ".to_owned()),
+                    },
+                    duration: OgrSourceDurationSpec::Zero,
+                },
+                None => OgrSourceDatasetTimeType::None,
+            },
+            default_geometry: None,
+            columns: Some(OgrSourceColumnSpec {
+                format_specifics: Some(FormatSpecifics::Csv {
+                    header: CsvHeader::No,
+                }),
+                x,
+                y: Some(y),
+                int: fields_of_type(FeatureDataType::Int),
+                float: fields_of_type(FeatureDataType::Float),
+                text: fields_of_type(FeatureDataType::Text),
+                bool: vec![],
+                datetime: vec![],
+                rename: Some(
+                    attributes()
+                        .map(|(i, v)| (format!("field_{}", i + 1), v.name.clone()))
+                        .collect(),
+                ),
+            }),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            encoding: None,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: attributes()
+                .map(|(_, v)| {
+                    (
+                        v.name.clone(),
+                        VectorColumnInfo {
+                            data_type: v.feature_data_type(),
+                            measurement: v.measurement(),
+                        },
+                    )
+                })
+                .collect(),
+            time: None,
+            bbox: None,
+        };
+
+        Ok(StaticMetaData {
+            loading_info,
+            result_descriptor,
+            phantom: Default::default(),
+        })
+    }
+
+    fn erddap_layer_id(&self, id: &geoengine_datatypes::dataset::DataId) -> Result<ErddapLayerId> {
+        let id: DataId = id.clone().into();
+        id.external()
+            .ok_or(Error::InvalidDataId)?
+            .layer_id
+            .0
+            .parse()
+    }
+}
+
+#[async_trait]
+impl DataProvider for ErddapDataProvider {
+    async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput> {
+        let layer_id: ErddapLayerId = id
+            .external()
+            .ok_or(Error::InvalidDataId)?
+            .layer_id
+            .0
+            .parse()?;
+
+        let dataset = match &layer_id {
+            ErddapLayerId::Dataset { dataset, .. }
+            | ErddapLayerId::Variable { dataset, .. }
+            | ErddapLayerId::Level { dataset, .. } => dataset,
+            ErddapLayerId::Root => return Err(Error::InvalidDataId),
+        };
+
+        let info = self.load_dataset_info(dataset).await?;
+
+        let citation = ["creator_name", "institution", "acknowledgement"]
+            .iter()
+            .find_map(|attribute| info.global.get(*attribute))
+            .map_or_else(|| info.title(dataset), Clone::clone);
+
+        Ok(ProvenanceOutput {
+            data: id.clone(),
+            provenance: Some(Provenance {
+                citation,
+                license: info.global.get("license").cloned().unwrap_or_default(),
+                uri: info
+                    .global
+                    .get("infoUrl")
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}info/{}/index.html", self.base_url, dataset)),
+            }),
+        })
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            spatial_filter: false,
+            time_filter: true,
+            attribute_filter: false,
+        }
+    }
+}
+
+#[async_trait]
+impl LayerCollectionProvider for ErddapDataProvider {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        let options = options.user_input;
+
+        match collection.0.parse::<ErddapLayerId>() {
+            Ok(ErddapLayerId::Root) => self.root_collection(collection, &options).await,
+            Ok(ErddapLayerId::Dataset {
+                protocol: ErddapProtocol::Griddap,
+                dataset,
+            }) => {
+                self.griddap_collection(collection, &dataset, &options)
+                    .await
+            }
+            Ok(ErddapLayerId::Variable { dataset, variable }) => {
+                self.levels_collection(collection, &dataset, &variable, &options)
+                    .await
+            }
+            _ => Err(Error::UnknownLayerCollectionId {
+                id: collection.clone(),
+            }),
+        }
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId(ErddapLayerId::Root.to_string()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        let layer_id: ErddapLayerId = id.0.parse()?;
+
+        let data = DataId::External(ExternalDataId {
+            provider_id: self.id,
+            layer_id: id.clone(),
+        })
+        .into();
+
+        let (dataset, variable, level) = match &layer_id {
+            ErddapLayerId::Dataset {
+                protocol: ErddapProtocol::Tabledap,
+                dataset,
+            } => (dataset, None, None),
+            ErddapLayerId::Variable { dataset, variable } => (dataset, Some(variable), None),
+            ErddapLayerId::Level {
+                dataset,
+                variable,
+                level,
+            } => (dataset, Some(variable), Some(level)),
+            _ => return Err(Error::UnknownLayerId { id: id.clone() }),
+        };
+
+        let info = self.load_dataset_info(dataset).await?;
+
+        let (name, description, operator) = if let Some(variable) = variable {
+            let variable = info
+                .variable(variable)
+                .ok_or_else(|| Error::UnknownLayerId { id: id.clone() })?;
+
+            // a layer must select exactly the additional dimensions the variable has
+            ensure!(
+                variable.level_dimensions().len() == usize::from(level.is_some()),
+                error::UnknownLayerId { id: id.clone() }
+            );
+
+            let name = match (level, variable.level_dimensions().first()) {
+                (Some(level), Some(dimension)) => {
+                    format!("{} ({} = {})", variable.name, dimension, level)
+                }
+                _ => variable.name.clone(),
+            };
+
+            (
+                name,
+                variable.description(),
+                TypedOperator::Raster(
+                    GdalSource {
+                        params: GdalSourceParameters { data },
+                    }
+                    .boxed(),
+                ),
+            )
+        } else {
+            (
+                info.title(dataset),
+                info.global.get("summary").cloned().unwrap_or_default(),
+                TypedOperator::Vector(
+                    OgrSource {
+                        params: OgrSourceParameters {
+                            data,
+                            attribute_projection: None,
+                            attribute_filters: None,
+                            schema_merging: None,
+                        },
+                    }
+                    .boxed(),
+                ),
+            )
+        };
+
+        Ok(Layer {
+            id: ProviderLayerId {
+                provider_id: self.id,
+                layer_id: id.clone(),
+            },
+            name,
+            description,
+            workflow: Workflow { operator },
+            symbology: None,
+            properties: vec![],
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for ErddapDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let meta_data = match self.erddap_layer_id(id) {
+            Ok(ErddapLayerId::Variable { dataset, variable }) => {
+                self.griddap_meta_data(&dataset, &variable, None).await
+            }
+            Ok(ErddapLayerId::Level {
+                dataset,
+                variable,
+                level,
+            }) => {
+                self.griddap_meta_data(&dataset, &variable, Some(&level))
+                    .await
+            }
+            Ok(_) => Err(Error::InvalidDataId),
+            Err(e) => Err(e),
+        };
+
+        meta_data.map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+            source: Box::new(e),
+        })
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for ErddapDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let meta_data = match self.erddap_layer_id(id) {
+            Ok(ErddapLayerId::Dataset {
+                protocol: ErddapProtocol::Tabledap,
+                dataset,
+            }) => self.tabledap_meta_data(&dataset).await,
+            Ok(_) => Err(Error::InvalidDataId),
+            Err(e) => Err(e),
+        };
+
+        meta_data
+            .map(|m| Box::new(m) as Box<dyn MetaData<_, _, _>>)
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for ErddapDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::primitives::BoundingBox2D;
+    use httptest::{matchers::request, responders::json_encoded, Expectation, Server};
+    use serde_json::json;
+
+    async fn create_provider(server: &Server) -> Box<dyn DataProvider> {
+        Box::new(ErddapDataProviderDefinition {
+            id: DataProviderId::from_u128(0x1c01_dbb9_e3ab_f9a2_06f5_228b_a4b6_bf7a),
+            name: "ERDDAP".to_owned(),
+            base_url: Url::parse(&server.url_str("/erddap/")).unwrap(),
+            cache_ttl: Default::default(),
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    fn info_row(
+        row_type: &str,
+        variable: &str,
+        attribute: &str,
+        data_type: &str,
+        value: &str,
+    ) -> serde_json::Value {
+        json!([row_type, variable, attribute, data_type, value])
+    }
+
+    fn sst_info() -> serde_json::Value {
+        json!({
+            "table": {
+                "columnNames": ["Row Type", "Variable Name", "Attribute Name", "Data Type", "Value"],
+                "rows": [
+                    info_row("attribute", "NC_GLOBAL", "title", "String", "Sea Surface Temperature"),
+                    info_row("attribute", "NC_GLOBAL", "license", "String", "CC-BY"),
+                    info_row("attribute", "NC_GLOBAL", "institution", "String", "NOAA"),
+                    info_row("dimension", "time", "", "double", "nValues=2, evenlySpaced=true"),
+                    info_row("dimension", "depth", "", "float", "nValues=2, evenlySpaced=true"),
+                    info_row("dimension", "latitude", "", "float", "nValues=3, evenlySpaced=true"),
+                    info_row("attribute", "latitude", "actual_range", "float", "-10.0, 10.0"),
+                    info_row("dimension", "longitude", "", "float", "nValues=5, evenlySpaced=true"),
+                    info_row("attribute", "longitude", "actual_range", "float", "0.0, 20.0"),
+                    info_row("variable", "sst", "", "float", "time, latitude, longitude"),
+                    info_row("attribute", "sst", "_FillValue", "float", "-999.0"),
+                    info_row("attribute", "sst", "long_name", "String", "Sea Surface Temperature"),
+                    info_row("attribute", "sst", "units", "String", "degree_C"),
+                    info_row("variable", "salinity", "", "float", "time, depth, latitude, longitude"),
+                ]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn it_lists_datasets_and_variables() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/info/index.json"))
+                .respond_with(json_encoded(json!({
+                    "table": {
+                        "columnNames": ["griddap", "Subset", "tabledap", "Title", "Summary", "Dataset ID"],
+                        "rows": [
+                            ["http://localhost/erddap/griddap/sst", "", "", "SST", "Gridded SST", "sst"],
+                            ["", "", "http://localhost/erddap/tabledap/buoys", "Buoys", "Buoy measurements", "buoys"],
+                        ]
+                    }
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/info/sst/index.json"))
+                .times(2)
+                .respond_with(json_encoded(sst_info())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/griddap/sst.json"))
+                .respond_with(json_encoded(json!({
+                    "table": {"columnNames": ["depth"], "rows": [[5.0], [10.0]]}
+                }))),
+        );
+
+        let provider = create_provider(&server).await;
+
+        let root = provider
+            .collection(
+                &provider.root_collection_id().await.unwrap(),
+                LayerCollectionListOptions::default().validated().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let provider_id = DataProviderId::from_u128(0x1c01_dbb9_e3ab_f9a2_06f5_228b_a4b6_bf7a);
+
+        assert_eq!(
+            root.items,
+            vec![
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id,
+                        layer_id: LayerId("tabledap/buoys".to_owned()),
+                    },
+                    name: "Buoys".to_owned(),
+                    description: "Buoy measurements".to_owned(),
+                }),
+                CollectionItem::Collection(LayerCollectionListing {
+                    id: ProviderLayerCollectionId {
+                        provider_id,
+                        collection_id: LayerCollectionId("griddap/sst".to_owned()),
+                    },
+                    name: "SST".to_owned(),
+                    description: "Gridded SST".to_owned(),
+                }),
+            ]
+        );
+
+        let variables = provider
+            .collection(
+                &LayerCollectionId("griddap/sst".to_owned()),
+                LayerCollectionListOptions::default().validated().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            variables.items,
+            vec![
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id,
+                        layer_id: LayerId("griddap/sst/sst".to_owned()),
+                    },
+                    name: "sst".to_owned(),
+                    description: "Sea Surface Temperature".to_owned(),
+                }),
+                CollectionItem::Collection(LayerCollectionListing {
+                    id: ProviderLayerCollectionId {
+                        provider_id,
+                        collection_id: LayerCollectionId("griddap/sst/salinity".to_owned()),
+                    },
+                    name: "salinity".to_owned(),
+                    description: "salinity".to_owned(),
+                }),
+            ]
+        );
+
+        let levels = provider
+            .collection(
+                &LayerCollectionId("griddap/sst/salinity".to_owned()),
+                LayerCollectionListOptions::default().validated().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            levels.items,
+            vec![
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id,
+                        layer_id: LayerId("griddap/sst/salinity/5.0".to_owned()),
+                    },
+                    name: "depth = 5.0".to_owned(),
+                    description: "salinity".to_owned(),
+                }),
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id,
+                        layer_id: LayerId("griddap/sst/salinity/10.0".to_owned()),
+                    },
+                    name: "depth = 10.0".to_owned(),
+                    description: "salinity".to_owned(),
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_creates_griddap_meta_data() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/info/sst/index.json"))
+                .respond_with(json_encoded(sst_info())),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/griddap/sst.json"))
+                .respond_with(json_encoded(json!({
+                    "table": {
+                        "columnNames": ["time"],
+                        "rows": [["2020-01-01T00:00:00Z"], ["2020-01-02T00:00:00Z"]]
+                    }
+                }))),
+        );
+
+        let provider = create_provider(&server).await;
+        let provider_id = DataProviderId::from_u128(0x1c01_dbb9_e3ab_f9a2_06f5_228b_a4b6_bf7a);
+
+        let meta: Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> =
+            provider
+                .meta_data(
+                    &DataId::External(ExternalDataId {
+                        provider_id,
+                        layer_id: LayerId("griddap/sst/salinity/5.0".to_owned()),
+                    })
+                    .into(),
+                )
+                .await
+                .unwrap();
+
+        let result_descriptor = meta.result_descriptor().await.unwrap();
+        assert_eq!(result_descriptor.data_type, RasterDataType::F32);
+        assert_eq!(
+            result_descriptor.bbox,
+            Some(SpatialPartition2D::new_unchecked(
+                (-2.5, 15.).into(),
+                (22.5, -15.).into()
+            ))
+        );
+        assert_eq!(
+            result_descriptor.time,
+            Some(TimeInterval::new_unchecked(
+                DateTime::new_utc(2020, 1, 1, 0, 0, 0),
+                DateTime::new_utc(2020, 1, 3, 0, 0, 0)
+            ))
+        );
+
+        let loading_info = meta
+            .loading_info(RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked(
+                    (0., 10.).into(),
+                    (20., -10.).into(),
+                ),
+                time_interval: TimeInterval::new_instant(DateTime::new_utc(2020, 1, 2, 12, 0, 0))
+                    .unwrap(),
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await
+            .unwrap();
+
+        let parts = loading_info.info.collect::<Vec<_>>();
+        assert_eq!(parts.len(), 1);
+
+        let part = parts[0].as_ref().unwrap();
+        let params = part.params.as_ref().unwrap();
+        assert_eq!(
+            params.file_path,
+            PathBuf::from(format!(
+                "/vsicurl/{}",
+                server.url_str(
+                    "/erddap/griddap/sst.geotif?salinity[(2020-01-02T00:00:00.000Z)][(5.0)][(-10):(10)][(0):(20)]"
+                )
+            ))
+        );
+        assert_eq!(params.width, 5);
+        assert_eq!(params.height, 3);
+        assert_eq!(params.no_data_value, None);
+    }
+
+    #[tokio::test]
+    async fn it_creates_tabledap_meta_data() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/erddap/info/buoys/index.json"))
+                .respond_with(json_encoded(json!({
+                    "table": {
+                        "columnNames": ["Row Type", "Variable Name", "Attribute Name", "Data Type", "Value"],
+                        "rows": [
+                            info_row("variable", "station", "", "String", ""),
+                            info_row("variable", "longitude", "", "double", ""),
+                            info_row("variable", "latitude", "", "double", ""),
+                            info_row("variable", "time", "", "double", ""),
+                            info_row("variable", "wtmp", "", "float", ""),
+                            info_row("attribute", "wtmp", "units", "String", "degree_C"),
+                        ]
+                    }
+                }))),
+        );
+
+        let provider = create_provider(&server).await;
+        let provider_id = DataProviderId::from_u128(0x1c01_dbb9_e3ab_f9a2_06f5_228b_a4b6_bf7a);
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id,
+                    layer_id: LayerId("tabledap/buoys".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let loading_info = meta
+            .loading_info(VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked(
+                    (-180., -90.).into(),
+                    (180., 90.).into(),
+                ),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            loading_info.file_name,
+            PathBuf::from(format!(
+                "CSV:/vsicurl_streaming/{}",
+                server.url_str("/erddap/tabledap/buoys.csv0?station,longitude,latitude,time,wtmp")
+            ))
+        );
+
+        let columns = loading_info.columns.unwrap();
+        assert_eq!(columns.x, "field_2");
+        assert_eq!(columns.y, Some("field_3".to_owned()));
+        assert_eq!(columns.text, vec!["field_1".to_owned()]);
+        assert_eq!(columns.float, vec!["field_5".to_owned()]);
+        assert!(matches!(
+            loading_info.time,
+            OgrSourceDatasetTimeType::Start { start_field, .. } if start_field == "field_4"
+        ));
+
+        let result_descriptor = meta.result_descriptor().await.unwrap();
+        assert_eq!(
+            result_descriptor.columns.get("wtmp"),
+            Some(&VectorColumnInfo {
+                data_type: FeatureDataType::Float,
+                measurement: Measurement::continuous(
+                    "wtmp".to_owned(),
+                    Some("degree_C".to_owned())
+                ),
+            })
+        );
+    }
+}
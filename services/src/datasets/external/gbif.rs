@@ -0,0 +1,1061 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::api::model::datatypes::{DataId, DataProviderId, ExternalDataId, LayerId};
+use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::error::{Error, Result};
+use crate::layers::cache::CacheTtlSeconds;
+use crate::layers::external::{DataProvider, DataProviderDefinition, ProviderCapabilities};
+use crate::layers::layer::{
+    CollectionItem, Layer, LayerCollection, LayerCollectionListOptions, LayerCollectionListing,
+    LayerListing, ProviderLayerCollectionId, ProviderLayerId,
+};
+use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::util::disk_cache::{DiskCache, DiskCacheLimits};
+use crate::util::parsing::deserialize_base_url;
+use crate::util::user_input::Validated;
+use crate::workflows::workflow::Workflow;
+use async_trait::async_trait;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::primitives::{
+    DateTimeParseFormat, FeatureDataType, Measurement, RasterQueryRectangle, TimeInstance,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, TypedOperator, VectorColumnInfo,
+    VectorOperator, VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    GdalLoadingInfo, OgrSource, OgrSourceColumnSpec, OgrSourceDataset, OgrSourceDatasetTimeType,
+    OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceParameters, OgrSourceTimeFormat,
+};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+
+/// GBIF does not allow paging beyond this number of records
+const GBIF_MAX_OFFSET: usize = 100_000;
+const GBIF_MAX_PAGE_SIZE: usize = 300;
+const GBIF_BACKBONE_DATASET: &str = "d7dddbf4-2cf0-4f39-9b2a-bb099caae36c";
+
+/// The GBIF provider allows to include species occurrences from <https://www.gbif.org/>.
+/// Taxa can be browsed along the GBIF backbone taxonomy or searched by name (collection id
+/// `search/{name}`). Each taxon is a point layer of its occurrences.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GbifDataProviderDefinition {
+    id: DataProviderId,
+    name: String,
+    #[serde(deserialize_with = "deserialize_base_url")]
+    base_url: Url,
+    /// Directory where downloaded occurrences are cached
+    cache_path: PathBuf,
+    /// Expiry and size limit of the cache directory
+    #[serde(default)]
+    disk_cache: DiskCacheLimits,
+    #[serde(default)]
+    paging: GbifPaging,
+    /// The occurrence fields that are exposed as text columns
+    #[serde(default = "GbifDataProviderDefinition::default_columns")]
+    columns: Vec<String>,
+    /// How long taxon listings and layer meta data are cached
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
+}
+
+impl GbifDataProviderDefinition {
+    fn default_columns() -> Vec<String> {
+        [
+            "scientificName",
+            "basisOfRecord",
+            "countryCode",
+            "datasetKey",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GbifPaging {
+    /// Number of occurrences per request, at most 300
+    page_size: usize,
+    /// Maximum number of occurrences per query, at most 100,000
+    max_records: usize,
+}
+
+impl Default for GbifPaging {
+    fn default() -> Self {
+        Self {
+            page_size: GBIF_MAX_PAGE_SIZE,
+            max_records: 10_000,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl DataProviderDefinition for GbifDataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn DataProvider>> {
+        Ok(Box::new(GbifDataProvider {
+            id: self.id,
+            name: self.name,
+            client: GbifClient {
+                client: Client::new(),
+                base_url: self.base_url,
+                cache: DiskCache::new(self.cache_path, self.disk_cache),
+                paging: GbifPaging {
+                    page_size: self.paging.page_size.clamp(1, GBIF_MAX_PAGE_SIZE),
+                    max_records: self.paging.max_records.min(GBIF_MAX_OFFSET),
+                },
+            },
+            columns: self.columns,
+        }))
+    }
+
+    fn type_name(&self) -> &'static str {
+        "GBIF"
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DataProviderId {
+        self.id
+    }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
+}
+
+#[derive(Debug)]
+pub struct GbifDataProvider {
+    id: DataProviderId,
+    name: String,
+    client: GbifClient,
+    columns: Vec<String>,
+}
+
+/// A page of results of the GBIF API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GbifPage<T> {
+    end_of_records: bool,
+    results: Vec<T>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GbifTaxon {
+    key: u64,
+    scientific_name: String,
+    #[serde(default)]
+    rank: Option<String>,
+    #[serde(default)]
+    num_descendants: u64,
+}
+
+impl GbifTaxon {
+    fn description(&self) -> String {
+        self.rank
+            .as_ref()
+            .map_or_else(String::new, |rank| rank.to_lowercase())
+    }
+}
+
+/// Identifies the collections of the provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GbifCollectionId {
+    /// The kingdoms of the backbone taxonomy
+    Root,
+    /// A taxon and its children
+    Taxon(u64),
+    /// Taxa whose name matches the query
+    Search(String),
+}
+
+impl std::str::FromStr for GbifCollectionId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let split = s.splitn(2, '/').collect::<Vec<_>>();
+
+        Ok(match *split.as_slice() {
+            ["root"] => GbifCollectionId::Root,
+            ["taxon", key] => {
+                GbifCollectionId::Taxon(key.parse().map_err(|_| Error::InvalidLayerCollectionId)?)
+            }
+            ["search", query] if !query.is_empty() => GbifCollectionId::Search(query.to_owned()),
+            _ => return Err(Error::InvalidLayerCollectionId),
+        })
+    }
+}
+
+impl std::fmt::Display for GbifCollectionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GbifCollectionId::Root => write!(f, "root"),
+            GbifCollectionId::Taxon(key) => write!(f, "taxon/{}", key),
+            GbifCollectionId::Search(query) => write!(f, "search/{}", query),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GbifClient {
+    client: Client,
+    base_url: Url,
+    cache: DiskCache,
+    paging: GbifPaging,
+}
+
+impl GbifClient {
+    async fn get<T: DeserializeOwned>(&self, path: &str, params: &[(&str, String)]) -> Result<T> {
+        Ok(self
+            .client
+            .get(self.base_url.join(path)?)
+            .query(params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    async fn taxon(&self, key: u64) -> Result<GbifTaxon> {
+        self.get(&format!("species/{}", key), &[]).await
+    }
+
+    async fn taxa(
+        &self,
+        path: &str,
+        mut params: Vec<(&str, String)>,
+        options: &LayerCollectionListOptions,
+    ) -> Result<Vec<GbifTaxon>> {
+        params.push(("offset", options.offset.to_string()));
+        params.push(("limit", options.limit.to_string()));
+
+        let page: GbifPage<GbifTaxon> = self.get(path, &params).await?;
+        Ok(page.results)
+    }
+
+    /// Downloads all occurrences that match the `params` page by page and writes them into a
+    /// `GeoJSON` file. Returns the path of the (possibly cached) file.
+    async fn occurrences(
+        &self,
+        params: Vec<(&str, String)>,
+        columns: &[String],
+    ) -> Result<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        self.base_url.hash(&mut hasher);
+        params.hash(&mut hasher);
+        columns.hash(&mut hasher);
+        self.paging.max_records.hash(&mut hasher);
+        let file_name = format!("{:016x}.geojson", hasher.finish());
+
+        if let Some(file_path) = self.cache.get(&file_name).await {
+            return Ok(file_path);
+        }
+
+        let mut features = Vec::new();
+        let mut offset = 0;
+        // stays `true` if the loop stops at `max_records` before the end of the records
+        let mut truncated = true;
+
+        while offset < self.paging.max_records {
+            let limit = self.paging.page_size.min(self.paging.max_records - offset);
+
+            let mut page_params = params.clone();
+            page_params.push(("offset", offset.to_string()));
+            page_params.push(("limit", limit.to_string()));
+
+            let page: GbifPage<serde_json::Map<String, serde_json::Value>> =
+                self.get("occurrence/search", &page_params).await?;
+
+            features.extend(
+                page.results
+                    .iter()
+                    .filter_map(|occurrence| occurrence_to_feature(occurrence, columns)),
+            );
+
+            if page.end_of_records || page.results.is_empty() {
+                truncated = false;
+                break;
+            }
+
+            offset += page.results.len();
+        }
+
+        if truncated {
+            log::warn!(
+                "GBIF occurrences were truncated at {} records, narrow the query to load all of them",
+                self.paging.max_records
+            );
+        }
+
+        let feature_collection = json!({
+            "type": "FeatureCollection",
+            "name": "occurrences",
+            "truncated": truncated,
+            "features": features,
+        });
+
+        self.cache
+            .insert(&file_name, feature_collection.to_string())
+            .await
+    }
+}
+
+/// Maps a GBIF occurrence record to a `GeoJSON` point feature. Records without coordinates
+/// are skipped.
+fn occurrence_to_feature(
+    occurrence: &serde_json::Map<String, serde_json::Value>,
+    columns: &[String],
+) -> Option<serde_json::Value> {
+    let longitude = occurrence.get("decimalLongitude")?.as_f64()?;
+    let latitude = occurrence.get("decimalLatitude")?.as_f64()?;
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(
+        "gbifID".to_owned(),
+        occurrence.get("key").cloned().unwrap_or_default(),
+    );
+    properties.insert(
+        "eventDate".to_owned(),
+        occurrence
+            .get("eventDate")
+            .and_then(serde_json::Value::as_str)
+            .and_then(normalize_event_date)
+            .map_or(serde_json::Value::Null, serde_json::Value::String),
+    );
+    for column in columns {
+        let value = match occurrence.get(column) {
+            Some(serde_json::Value::String(s)) => serde_json::Value::String(s.clone()),
+            Some(serde_json::Value::Null) | None => serde_json::Value::Null,
+            Some(other) => serde_json::Value::String(other.to_string()),
+        };
+        properties.insert(column.clone(), value);
+    }
+
+    Some(json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [longitude, latitude],
+        },
+        "properties": properties,
+    }))
+}
+
+/// GBIF event dates are ISO 8601 dates, date times or intervals of those.
+/// The start is returned as a full UTC date time.
+fn normalize_event_date(event_date: &str) -> Option<String> {
+    let start = event_date.split('/').next()?;
+
+    let date_time = chrono::DateTime::parse_from_rfc3339(start)
+        .map(|d| d.naive_utc())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(start, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(start, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+
+    Some(date_time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+/// Formats a time bound of a query as a GBIF date or `*` if it is unbounded.
+fn gbif_date(time: TimeInstance) -> String {
+    if time.is_min() || time.is_max() {
+        return "*".to_owned();
+    }
+
+    time.as_date_time().map_or_else(
+        || "*".to_owned(),
+        |d| d.format(&DateTimeParseFormat::custom("%Y-%m-%d".to_owned())),
+    )
+}
+
+/// Meta data that downloads the occurrences of a taxon within the query's bounds
+#[derive(Debug, Clone)]
+struct GbifMetaData {
+    client: GbifClient,
+    taxon_key: u64,
+    columns: Vec<String>,
+}
+
+impl GbifMetaData {
+    fn occurrence_params(&self, query: &VectorQueryRectangle) -> Vec<(&'static str, String)> {
+        let bbox = query.spatial_bounds;
+
+        vec![
+            ("taxonKey", self.taxon_key.to_string()),
+            ("hasCoordinate", "true".to_owned()),
+            ("hasGeospatialIssue", "false".to_owned()),
+            (
+                "decimalLatitude",
+                format!(
+                    "{},{}",
+                    bbox.lower_left().y.max(-90.),
+                    bbox.upper_right().y.min(90.)
+                ),
+            ),
+            (
+                "decimalLongitude",
+                format!(
+                    "{},{}",
+                    bbox.lower_left().x.max(-180.),
+                    bbox.upper_right().x.min(180.)
+                ),
+            ),
+            (
+                "eventDate",
+                format!(
+                    "{},{}",
+                    gbif_date(query.time_interval.start()),
+                    gbif_date(query.time_interval.end())
+                ),
+            ),
+        ]
+    }
+}
+
+#[async_trait]
+impl MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle> for GbifMetaData {
+    async fn loading_info(
+        &self,
+        query: VectorQueryRectangle,
+    ) -> Result<OgrSourceDataset, geoengine_operators::error::Error> {
+        let file_path = self
+            .client
+            .occurrences(self.occurrence_params(&query), &self.columns)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        Ok(OgrSourceDataset {
+            file_name: file_path,
+            layer_name: "occurrences".to_owned(),
+            data_type: Some(VectorDataType::MultiPoint),
+            time: OgrSourceDatasetTimeType::Start {
+                start_field: "eventDate".to_owned(),
+                start_format: OgrSourceTimeFormat::Auto,
+                duration: OgrSourceDurationSpec::Zero,
+            },
+            default_geometry: None,
+            columns: Some(OgrSourceColumnSpec {
+                format_specifics: None,
+                x: String::new(),
+                y: None,
+                int: vec!["gbifID".to_owned()],
+                float: vec![],
+                text: self.columns.clone(),
+                bool: vec![],
+                datetime: vec![],
+                rename: None,
+            }),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            encoding: None,
+        })
+    }
+
+    async fn result_descriptor(
+        &self,
+    ) -> Result<VectorResultDescriptor, geoengine_operators::error::Error> {
+        let mut columns: HashMap<String, VectorColumnInfo> = self
+            .columns
+            .iter()
+            .map(|column| {
+                (
+                    column.clone(),
+                    VectorColumnInfo {
+                        data_type: FeatureDataType::Text,
+                        measurement: Measurement::Unitless,
+                    },
+                )
+            })
+            .collect();
+        columns.insert(
+            "gbifID".to_owned(),
+            VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            },
+        );
+
+        Ok(VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns,
+            time: None,
+            bbox: None,
+        })
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+impl GbifDataProvider {
+    fn taxon_layer_listing(&self, taxon: &GbifTaxon) -> CollectionItem {
+        CollectionItem::Layer(LayerListing {
+            id: ProviderLayerId {
+                provider_id: self.id,
+                layer_id: LayerId(taxon.key.to_string()),
+            },
+            name: taxon.scientific_name.clone(),
+            description: taxon.description(),
+        })
+    }
+
+    fn taxon_collection_listing(&self, taxon: &GbifTaxon) -> CollectionItem {
+        CollectionItem::Collection(LayerCollectionListing {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: LayerCollectionId(GbifCollectionId::Taxon(taxon.key).to_string()),
+            },
+            name: taxon.scientific_name.clone(),
+            description: taxon.description(),
+        })
+    }
+
+    /// Taxa with descendants are listed as collections, all others as layers
+    fn taxon_listing(&self, taxon: &GbifTaxon) -> CollectionItem {
+        if taxon.num_descendants > 0 {
+            self.taxon_collection_listing(taxon)
+        } else {
+            self.taxon_layer_listing(taxon)
+        }
+    }
+
+    fn taxon_key(id: &LayerId) -> Result<u64> {
+        id.0.parse().map_err(|_| Error::InvalidLayerId)
+    }
+}
+
+#[async_trait]
+impl DataProvider for GbifDataProvider {
+    async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput> {
+        let taxon_key = Self::taxon_key(&id.external().ok_or(Error::InvalidDataId)?.layer_id)?;
+
+        Ok(ProvenanceOutput {
+            data: id.clone(),
+            provenance: Some(Provenance {
+                citation: format!(
+                    "GBIF.org ({}) GBIF Occurrence Search",
+                    chrono::Utc::now().format("%d %B %Y")
+                ),
+                license: "CC0, CC BY or CC BY-NC, as given by the dataset of each occurrence"
+                    .to_owned(),
+                uri: format!(
+                    "https://www.gbif.org/occurrence/search?taxon_key={}",
+                    taxon_key
+                ),
+            }),
+        })
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            spatial_filter: true,
+            time_filter: true,
+            attribute_filter: false,
+        }
+    }
+}
+
+#[async_trait]
+impl LayerCollectionProvider for GbifDataProvider {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        let options = options.user_input;
+
+        let (name, description, items) = match collection.0.parse::<GbifCollectionId>()? {
+            GbifCollectionId::Root => {
+                let kingdoms = self
+                    .client
+                    .taxa(
+                        "species/search",
+                        vec![
+                            ("datasetKey", GBIF_BACKBONE_DATASET.to_owned()),
+                            ("rank", "KINGDOM".to_owned()),
+                            ("status", "ACCEPTED".to_owned()),
+                        ],
+                        &options,
+                    )
+                    .await?;
+
+                (
+                    self.name.clone(),
+                    "GBIF backbone taxonomy".to_owned(),
+                    kingdoms
+                        .iter()
+                        .map(|taxon| self.taxon_collection_listing(taxon))
+                        .collect(),
+                )
+            }
+            GbifCollectionId::Taxon(key) => {
+                let taxon = self.client.taxon(key).await?;
+
+                // the taxon itself is the first item, followed by its children
+                let mut items = vec![];
+                let mut child_options = options.clone();
+                if options.offset == 0 && options.limit > 0 {
+                    items.push(self.taxon_layer_listing(&taxon));
+                    child_options.limit -= 1;
+                } else {
+                    // the taxon itself occupies the first position
+                    child_options.offset = options.offset.saturating_sub(1);
+                }
+
+                let children = self
+                    .client
+                    .taxa(&format!("species/{}/children", key), vec![], &child_options)
+                    .await?;
+                items.extend(children.iter().map(|taxon| self.taxon_listing(taxon)));
+
+                (taxon.scientific_name.clone(), taxon.description(), items)
+            }
+            GbifCollectionId::Search(query) => {
+                let taxa = self
+                    .client
+                    .taxa(
+                        "species/search",
+                        vec![
+                            ("datasetKey", GBIF_BACKBONE_DATASET.to_owned()),
+                            ("q", query.clone()),
+                        ],
+                        &options,
+                    )
+                    .await?;
+
+                (
+                    format!("Search: {}", query),
+                    String::new(),
+                    taxa.iter()
+                        .map(|taxon| self.taxon_layer_listing(taxon))
+                        .collect(),
+                )
+            }
+        };
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name,
+            description,
+            items,
+            entry_label: Some("Taxon".to_owned()),
+            properties: vec![],
+        })
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId(GbifCollectionId::Root.to_string()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        let taxon = self.client.taxon(Self::taxon_key(id)?).await?;
+
+        Ok(Layer {
+            id: ProviderLayerId {
+                provider_id: self.id,
+                layer_id: id.clone(),
+            },
+            name: taxon.scientific_name.clone(),
+            description: taxon.description(),
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    OgrSource {
+                        params: OgrSourceParameters {
+                            data: DataId::External(ExternalDataId {
+                                provider_id: self.id,
+                                layer_id: id.clone(),
+                            })
+                            .into(),
+                            attribute_projection: None,
+                            attribute_filters: None,
+                            schema_merging: None,
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+            properties: vec![],
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let id: DataId = id.clone().into();
+
+        let taxon_key = id
+            .external()
+            .ok_or(Error::InvalidDataId)
+            .and_then(|id| Self::taxon_key(&id.layer_id))
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        Ok(Box::new(GbifMetaData {
+            client: self.client.clone(),
+            taxon_key,
+            columns: self.columns.clone(),
+        }))
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, DateTime, SpatialResolution, TimeInterval,
+    };
+    use httptest::{
+        all_of,
+        matchers::{contains, request, url_decoded},
+        responders::json_encoded,
+        Expectation, Server,
+    };
+
+    const PROVIDER_ID: DataProviderId =
+        DataProviderId::from_u128(0x4a8f_0c1e_6d3b_4e59_8a77_1f2b_3c4d_5e6f);
+
+    async fn create_provider(server: &Server, cache_path: PathBuf) -> Box<dyn DataProvider> {
+        Box::new(GbifDataProviderDefinition {
+            id: PROVIDER_ID,
+            name: "GBIF".to_owned(),
+            base_url: Url::parse(&server.url_str("/v1/")).unwrap(),
+            cache_path,
+            disk_cache: Default::default(),
+            paging: GbifPaging {
+                page_size: 2,
+                max_records: 10,
+            },
+            columns: vec!["scientificName".to_owned()],
+            cache_ttl: Default::default(),
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn it_normalizes_event_dates() {
+        assert_eq!(
+            normalize_event_date("2019-06-14T10:30:00"),
+            Some("2019-06-14T10:30:00Z".to_owned())
+        );
+        assert_eq!(
+            normalize_event_date("2019-06-14T10:30:00+02:00"),
+            Some("2019-06-14T08:30:00Z".to_owned())
+        );
+        assert_eq!(
+            normalize_event_date("2019-06-14/2019-06-20"),
+            Some("2019-06-14T00:00:00Z".to_owned())
+        );
+        assert_eq!(normalize_event_date("2019"), None);
+    }
+
+    #[tokio::test]
+    async fn it_lists_taxon_with_children() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/species/212")).respond_with(
+                json_encoded(json!({
+                    "key": 212,
+                    "scientificName": "Aves",
+                    "rank": "CLASS",
+                    "numDescendants": 2
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1/species/212/children"),
+                request::query(url_decoded(contains(("offset", "0")))),
+                request::query(url_decoded(contains(("limit", "19")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "offset": 0,
+                "limit": 19,
+                "endOfRecords": true,
+                "results": [
+                    {"key": 729, "scientificName": "Passeriformes", "rank": "ORDER", "numDescendants": 5},
+                    {"key": 1, "scientificName": "Extinctus", "rank": "ORDER", "numDescendants": 0}
+                ]
+            }))),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let collection = provider
+            .collection(
+                &LayerCollectionId("taxon/212".to_owned()),
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 20,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(collection.name, "Aves");
+        assert_eq!(
+            collection.items,
+            vec![
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id: PROVIDER_ID,
+                        layer_id: LayerId("212".to_owned()),
+                    },
+                    name: "Aves".to_owned(),
+                    description: "class".to_owned(),
+                }),
+                CollectionItem::Collection(LayerCollectionListing {
+                    id: ProviderLayerCollectionId {
+                        provider_id: PROVIDER_ID,
+                        collection_id: LayerCollectionId("taxon/729".to_owned()),
+                    },
+                    name: "Passeriformes".to_owned(),
+                    description: "order".to_owned(),
+                }),
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id: PROVIDER_ID,
+                        layer_id: LayerId("1".to_owned()),
+                    },
+                    name: "Extinctus".to_owned(),
+                    description: "order".to_owned(),
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_pages_through_occurrences() {
+        let server = Server::run();
+
+        let occurrence_query = |offset: &'static str| {
+            all_of![
+                request::method_path("GET", "/v1/occurrence/search"),
+                request::query(url_decoded(contains(("taxonKey", "212")))),
+                request::query(url_decoded(contains(("decimalLatitude", "50,51")))),
+                request::query(url_decoded(contains(("decimalLongitude", "8,9")))),
+                request::query(url_decoded(contains(("eventDate", "2020-01-01,*")))),
+                request::query(url_decoded(contains(("offset", offset)))),
+            ]
+        };
+
+        server.expect(
+            Expectation::matching(occurrence_query("0"))
+                .times(1)
+                .respond_with(json_encoded(json!({
+                "endOfRecords": false,
+                "results": [
+                    {"key": 1, "decimalLatitude": 50.5, "decimalLongitude": 8.5, "eventDate": "2020-05-01", "scientificName": "Parus major"},
+                    {"key": 2, "decimalLatitude": 50.6, "decimalLongitude": 8.6, "eventDate": "2020-05-02T10:00:00", "scientificName": "Parus major"}
+                ]
+            }))),
+        );
+        server.expect(
+            Expectation::matching(occurrence_query("2"))
+                .times(1)
+                .respond_with(json_encoded(json!({
+                "endOfRecords": true,
+                "results": [
+                    {"key": 3, "decimalLatitude": 50.7, "decimalLongitude": 8.7, "scientificName": "Parus major"}
+                ]
+            }))),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id: PROVIDER_ID,
+                    layer_id: LayerId("212".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((8., 50.).into(), (9., 51.).into()),
+            time_interval: TimeInterval::new_unchecked(
+                DateTime::new_utc(2020, 1, 1, 0, 0, 0),
+                TimeInstance::MAX,
+            ),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let loading_info = meta.loading_info(query).await.unwrap();
+
+        let feature_collection: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&loading_info.file_name)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        let features = feature_collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 3);
+        assert_eq!(feature_collection["truncated"], false);
+        assert_eq!(
+            features[1],
+            json!({
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [8.6, 50.6]},
+                "properties": {
+                    "gbifID": 2,
+                    "eventDate": "2020-05-02T10:00:00Z",
+                    "scientificName": "Parus major"
+                }
+            })
+        );
+        assert_eq!(
+            features[2]["properties"]["eventDate"],
+            serde_json::Value::Null
+        );
+
+        // the second query is answered from the cache
+        let cached_loading_info = meta.loading_info(query).await.unwrap();
+        assert_eq!(cached_loading_info.file_name, loading_info.file_name);
+    }
+
+    #[tokio::test]
+    async fn it_reports_truncated_occurrences() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1/occurrence/search"))
+                .times(5)
+                .respond_with(json_encoded(json!({
+                "endOfRecords": false,
+                "results": [
+                    {"key": 1, "decimalLatitude": 50.5, "decimalLongitude": 8.5, "scientificName": "Parus major"},
+                    {"key": 2, "decimalLatitude": 50.6, "decimalLongitude": 8.6, "scientificName": "Parus major"}
+                ]
+            }))),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id: PROVIDER_ID,
+                    layer_id: LayerId("212".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let loading_info = meta
+            .loading_info(VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked((8., 50.).into(), (9., 51.).into()),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await
+            .unwrap();
+
+        let feature_collection: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&loading_info.file_name)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        // `max_records` is 10 with two records per page
+        assert_eq!(feature_collection["features"].as_array().unwrap().len(), 10);
+        assert_eq!(feature_collection["truncated"], true);
+    }
+}
@@ -11,6 +11,7 @@ use crate::layers::layer::{
     ProviderLayerCollectionId, ProviderLayerId,
 };
 use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::util::secrets::Secret;
 use crate::util::user_input::Validated;
 use crate::workflows::workflow::Workflow;
 use async_trait::async_trait;
@@ -47,7 +48,7 @@ struct DatabaseConnectionConfig {
     database: String,
     schema: String,
     user: String,
-    password: String,
+    password: Secret,
 }
 
 impl DatabaseConnectionConfig {
@@ -55,7 +56,7 @@ impl DatabaseConnectionConfig {
         let mut config = Config::new();
         config
             .user(&self.user)
-            .password(&self.password)
+            .password(self.password.expose())
             .host(&self.host)
             .dbname(&self.database);
         config
@@ -64,7 +65,11 @@ impl DatabaseConnectionConfig {
     fn ogr_pg_config(&self) -> String {
         format!(
             "PG:host={} port={} dbname={} user={} password={}",
-            self.host, self.port, self.database, self.user, self.password
+            self.host,
+            self.port,
+            self.database,
+            self.user,
+            self.password.expose()
         )
     }
 }
@@ -292,6 +297,7 @@ impl LayerCollectionProvider for GfbioDataProvider {
                             .into(),
                             attribute_projection: None,
                             attribute_filters: None,
+                            schema_merging: None,
                         },
                     }
                     .boxed(),
@@ -418,6 +424,7 @@ impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRecta
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: Some(GfbioDataProvider::build_attribute_query(surrogate_key)),
+                encoding: None,
             },
             result_descriptor: VectorResultDescriptor {
                 data_type: VectorDataType::MultiPoint,
@@ -564,7 +571,7 @@ mod tests {
                 database: db_config.database.clone(),
                 schema: test_schema.clone(),
                 user: db_config.user.clone(),
-                password: db_config.password.clone(),
+                password: db_config.password.clone().into(),
             },
         })
         .initialize()
@@ -622,7 +629,7 @@ mod tests {
                 database: db_config.database.clone(),
                 schema: test_schema.to_owned(),
                 user: db_config.user.clone(),
-                password: db_config.password.clone(),
+                password: db_config.password.clone().into(),
             };
 
             let ogr_pg_string = provider_db_config.ogr_pg_config();
@@ -777,6 +784,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: Some("surrogate_key = 1".to_string()),
+                encoding: None,
             };
 
             if loading_info != expected {
@@ -808,7 +816,7 @@ mod tests {
                     database: db_config.database.clone(),
                     schema: test_schema.to_owned(),
                     user: db_config.user.clone(),
-                    password: db_config.password.clone(),
+                    password: db_config.password.clone().into(),
                 },
             })
             .initialize()
@@ -828,14 +836,16 @@ mod tests {
                 .await
                 .map_err(|e| e.to_string())?;
 
-            let processor: OgrSourceProcessor<MultiPoint> = OgrSourceProcessor::new(meta, vec![]);
+            let ctx = MockQueryContext::test_default();
+
+            let processor: OgrSourceProcessor<MultiPoint> =
+                OgrSourceProcessor::new(meta, vec![], ctx.thread_pool.clone());
 
             let query_rectangle = VectorQueryRectangle {
                 spatial_bounds: BoundingBox2D::new((0., -90.).into(), (180., 90.).into()).unwrap(),
                 time_interval: TimeInterval::default(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
             };
-            let ctx = MockQueryContext::test_default();
 
             let result: Vec<_> = processor
                 .query(query_rectangle, &ctx)
@@ -914,7 +924,7 @@ mod tests {
                     database: db_config.database.clone(),
                     schema: test_schema.to_owned(),
                     user: db_config.user.clone(),
-                    password: db_config.password.clone(),
+                    password: db_config.password.clone().into(),
                 },
             })
             .initialize()
@@ -1,3 +1,5 @@
+pub mod erddap;
+pub mod gbif;
 #[cfg(feature = "nfdi")]
 pub mod gfbio;
 pub mod mock;
@@ -7,5 +9,7 @@ pub mod nature40;
 pub mod netcdfcf;
 #[cfg(feature = "nfdi")]
 pub mod nfdi;
+pub mod overpass;
 #[cfg(feature = "nfdi")]
 pub mod pangaea;
+pub mod sensorthings;
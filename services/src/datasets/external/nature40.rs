@@ -2,6 +2,7 @@ use crate::api::model::datatypes::{DataId, DataProviderId, ExternalDataId, Layer
 use crate::datasets::listing::ProvenanceOutput;
 use crate::error::Error;
 use crate::error::Result;
+use crate::layers::cache::CacheTtlSeconds;
 use crate::layers::external::{DataProvider, DataProviderDefinition};
 use crate::layers::layer::LayerCollection;
 use crate::layers::layer::ProviderLayerCollectionId;
@@ -11,6 +12,7 @@ use crate::layers::layer::{
 use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
 use crate::util::parsing::{deserialize_base_url, string_or_string_array};
 use crate::util::retry::retry;
+use crate::util::secrets::Secret;
 use crate::workflows::workflow::Workflow;
 use crate::{error, util::user_input::Validated};
 use async_trait::async_trait;
@@ -51,9 +53,11 @@ pub struct Nature40DataProviderDefinition {
     #[serde(deserialize_with = "deserialize_base_url")]
     base_url: Url,
     user: String,
-    password: String,
+    password: Secret,
     #[serde(default)]
     request_retries: RequestRetries,
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -99,6 +103,10 @@ impl DataProviderDefinition for Nature40DataProviderDefinition {
     fn id(&self) -> DataProviderId {
         self.id
     }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
 }
 
 #[derive(Debug)]
@@ -106,7 +114,7 @@ pub struct Nature40DataProvider {
     id: DataProviderId,
     base_url: Url,
     user: String,
-    password: String,
+    password: Secret,
     request_retries: RequestRetries,
 }
 
@@ -292,7 +300,7 @@ impl LayerCollectionProvider for Nature40DataProvider {
 impl Nature40DataProvider {
     fn auth(&self) -> [String; 2] {
         [
-            format!("UserPwd={}:{}", self.user, self.password),
+            format!("UserPwd={}:{}", self.user, self.password.expose()),
             "HttpAuth=BASIC".to_owned(),
         ]
     }
@@ -326,7 +334,7 @@ impl Nature40DataProvider {
     async fn load_raster_dbs(&self) -> Result<RasterDbs> {
         Client::new()
             .get(self.base_url.join("rasterdbs.json")?)
-            .basic_auth(&self.user, Some(&self.password))
+            .basic_auth(&self.user, Some(self.password.expose()))
             .send()
             .await?
             .json()
@@ -789,8 +797,9 @@ mod tests {
             name: "Nature40".to_owned(),
             base_url: Url::parse(&server.url_str("")).unwrap(),
             user: "geoengine".to_owned(),
-            password: "pwd".to_owned(),
+            password: "pwd".into(),
             request_retries: Default::default(),
+            cache_ttl: Default::default(),
         })
         .initialize()
         .await
@@ -883,8 +892,9 @@ mod tests {
             name: "Nature40".to_owned(),
             base_url: Url::parse(&server.url_str("")).unwrap(),
             user: "geoengine".to_owned(),
-            password: "pwd".to_owned(),
+            password: "pwd".into(),
             request_retries: Default::default(),
+            cache_ttl: Default::default(),
         })
         .initialize()
         .await
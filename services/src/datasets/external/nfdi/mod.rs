@@ -7,6 +7,7 @@ use crate::layers::external::{DataProviderDefinition, DataProvider};
 use crate::layers::layer::{LayerCollectionListOptions, CollectionItem, Layer, LayerListing, ProviderLayerId, LayerCollection, ProviderLayerCollectionId};
 use crate::layers::listing::{LayerCollectionProvider, LayerCollectionId};
 use crate::util::operators::source_operator_from_dataset;
+use crate::util::secrets::Secret;
 use crate::util::user_input::Validated;
 use crate::workflows::workflow::Workflow;
 use geoengine_datatypes::collections::VectorDataType;
@@ -58,7 +59,7 @@ pub struct NFDIDataProviderDefinition {
     name: String,
     api_url: String,
     project_id: String,
-    api_token: String,
+    api_token: Secret,
 }
 
 #[typetag::serde]
@@ -131,7 +132,7 @@ impl NFDIDataProvider {
             .connect()
             .await?;
 
-        let interceptor = APITokenInterceptor::new(&def.api_token[..])?;
+        let interceptor = APITokenInterceptor::new(def.api_token.expose())?;
 
         let project_stub =
             ProjectServiceClient::with_interceptor(channel.clone(), interceptor.clone());
@@ -391,6 +392,7 @@ impl NFDIDataProvider {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         }
     }
 
@@ -633,6 +635,7 @@ impl LayerCollectionProvider for NFDIDataProvider {
                         .into(),
                         attribute_projection: None,
                         attribute_filters: None,
+                        schema_merging: None,
                     },
                 }
                 .boxed(),
@@ -706,6 +709,7 @@ impl ExpiringDownloadLink for OgrSourceDataset {
             on_error: self.on_error,
             sql_query: self.sql_query.clone(),
             attribute_query: self.attribute_query.clone(),
+            encoding: self.encoding.clone(),
         })
     }
 }
@@ -855,7 +859,7 @@ mod tests {
     async fn new_provider_with_url(url: String) -> NFDIDataProvider {
         let def = NFDIDataProviderDefinition {
             id: DataProviderId::from_str(PROVIDER_ID).unwrap(),
-            api_token: TOKEN.to_string(),
+            api_token: TOKEN.into(),
             api_url: url,
             project_id: PROJECT_ID.to_string(),
             name: "NFDI".to_string(),
@@ -1459,6 +1463,7 @@ mod tests {
                 data: id.into(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed();
@@ -0,0 +1,704 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::api::model::datatypes::{DataId, DataProviderId, ExternalDataId, LayerId};
+use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::error::{self, Error, Result};
+use crate::layers::cache::CacheTtlSeconds;
+use crate::layers::external::{DataProvider, DataProviderDefinition, ProviderCapabilities};
+use crate::layers::layer::{
+    CollectionItem, Layer, LayerCollection, LayerCollectionListOptions, LayerListing,
+    ProviderLayerCollectionId, ProviderLayerId,
+};
+use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::util::disk_cache::{DiskCache, DiskCacheLimits};
+use crate::util::parsing::deserialize_base_url;
+use crate::util::retry::retry;
+use crate::util::user_input::Validated;
+use crate::workflows::workflow::Workflow;
+use async_trait::async_trait;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, Measurement, RasterQueryRectangle, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, TypedOperator, VectorColumnInfo,
+    VectorOperator, VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    GdalLoadingInfo, OgrSource, OgrSourceColumnSpec, OgrSourceDataset, OgrSourceDatasetTimeType,
+    OgrSourceErrorSpec, OgrSourceParameters,
+};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use tokio::sync::Mutex;
+use url::Url;
+
+/// The Overpass provider allows to include `OpenStreetMap` data via the Overpass API
+/// (<https://overpass-api.de/>). Each configured layer selects the OSM elements that match a
+/// tag filter within the bounding box of a query.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverpassDataProviderDefinition {
+    id: DataProviderId,
+    name: String,
+    #[serde(deserialize_with = "deserialize_base_url")]
+    base_url: Url,
+    /// Directory where the responses of the Overpass API are cached
+    cache_path: PathBuf,
+    /// Expiry and size limit of the cache directory
+    #[serde(default)]
+    disk_cache: DiskCacheLimits,
+    #[serde(default)]
+    request_limits: OverpassRequestLimits,
+    layers: Vec<OverpassLayerDefinition>,
+    /// How long layer listings and meta data are cached
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
+}
+
+/// Settings that keep the usage of the Overpass API within its rate limits
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverpassRequestLimits {
+    /// Minimum time between two requests
+    min_request_interval_ms: u64,
+    /// Server side timeout of a query
+    timeout_seconds: u32,
+    /// Retries after the server responded with `429 Too Many Requests`
+    number_of_retries: usize,
+    initial_retry_delay_ms: u64,
+}
+
+impl Default for OverpassRequestLimits {
+    fn default() -> Self {
+        Self {
+            min_request_interval_ms: 1000,
+            timeout_seconds: 25,
+            number_of_retries: 3,
+            initial_retry_delay_ms: 2000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverpassLayerDefinition {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    /// An Overpass QL tag filter, e.g. `["highway"]` or `["building"="yes"]`
+    filter: String,
+    geometry: OverpassGeometry,
+    /// The tags that are exposed as columns
+    #[serde(default = "OverpassLayerDefinition::default_columns")]
+    columns: Vec<String>,
+}
+
+impl OverpassLayerDefinition {
+    fn default_columns() -> Vec<String> {
+        vec!["osm_id".to_owned(), "name".to_owned()]
+    }
+
+    /// Builds the Overpass QL query that selects the layer's elements within the `bbox`.
+    fn query(&self, bbox: BoundingBox2D, timeout_seconds: u32) -> String {
+        // Overpass expects (south, west, north, east)
+        let bbox = format!(
+            "{},{},{},{}",
+            bbox.lower_left().y.max(-90.),
+            bbox.lower_left().x.max(-180.),
+            bbox.upper_right().y.min(90.),
+            bbox.upper_right().x.min(180.)
+        );
+
+        let elements = match self.geometry {
+            OverpassGeometry::Point => format!("node{}({});", self.filter, bbox),
+            OverpassGeometry::Line => format!("way{}({});", self.filter, bbox),
+            OverpassGeometry::Polygon => format!(
+                "way{filter}({bbox});relation{filter}[\"type\"=\"multipolygon\"]({bbox});",
+                filter = self.filter,
+                bbox = bbox
+            ),
+        };
+
+        // `(._;>;);` adds the nodes that are required to construct ways and relations
+        format!(
+            "[out:xml][timeout:{}];({});(._;>;);out body;",
+            timeout_seconds, elements
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverpassGeometry {
+    Point,
+    Line,
+    Polygon,
+}
+
+impl OverpassGeometry {
+    /// The layer of GDAL's OSM driver that contains the geometry type
+    fn ogr_layer_name(self) -> &'static str {
+        match self {
+            OverpassGeometry::Point => "points",
+            OverpassGeometry::Line => "lines",
+            OverpassGeometry::Polygon => "multipolygons",
+        }
+    }
+
+    fn vector_data_type(self) -> VectorDataType {
+        match self {
+            OverpassGeometry::Point => VectorDataType::MultiPoint,
+            OverpassGeometry::Line => VectorDataType::MultiLineString,
+            OverpassGeometry::Polygon => VectorDataType::MultiPolygon,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl DataProviderDefinition for OverpassDataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn DataProvider>> {
+        Ok(Box::new(OverpassDataProvider {
+            id: self.id,
+            name: self.name,
+            client: OverpassClient {
+                client: Client::new(),
+                base_url: self.base_url,
+                cache: DiskCache::new(self.cache_path, self.disk_cache),
+                request_limits: self.request_limits,
+                last_request: Arc::new(Mutex::new(None)),
+            },
+            layers: self.layers,
+        }))
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Overpass"
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DataProviderId {
+        self.id
+    }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
+}
+
+#[derive(Debug)]
+pub struct OverpassDataProvider {
+    id: DataProviderId,
+    name: String,
+    client: OverpassClient,
+    layers: Vec<OverpassLayerDefinition>,
+}
+
+/// Downloads query results from the Overpass API and caches them on disk.
+/// Requests of all clones are serialized and spaced by the configured minimum interval.
+#[derive(Debug, Clone)]
+struct OverpassClient {
+    client: Client,
+    base_url: Url,
+    cache: DiskCache,
+    request_limits: OverpassRequestLimits,
+    last_request: Arc<Mutex<Option<Instant>>>,
+}
+
+impl OverpassClient {
+    /// Returns the path of an `.osm` file that contains the result of the `query`.
+    async fn fetch(&self, query: &str) -> Result<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        self.base_url.hash(&mut hasher);
+        query.hash(&mut hasher);
+        let file_name = format!("{:016x}.osm", hasher.finish());
+
+        if let Some(file_path) = self.cache.get(&file_name).await {
+            return Ok(file_path);
+        }
+
+        let data = retry(
+            self.request_limits.number_of_retries,
+            self.request_limits.initial_retry_delay_ms,
+            2.,
+            || self.request(query),
+        )
+        .await?;
+
+        self.cache.insert(&file_name, &data).await
+    }
+
+    async fn request(&self, query: &str) -> Result<bytes::Bytes> {
+        let mut last_request = self.last_request.lock().await;
+
+        if let Some(last_request) = *last_request {
+            let min_interval = Duration::from_millis(self.request_limits.min_request_interval_ms);
+            let elapsed = last_request.elapsed();
+            if elapsed < min_interval {
+                tokio::time::sleep(min_interval - elapsed).await;
+            }
+        }
+
+        let response = self
+            .client
+            .post(self.base_url.join("interpreter")?)
+            .form(&[("data", query)])
+            .send()
+            .await;
+
+        *last_request = Some(Instant::now());
+
+        let response = response?;
+
+        ensure!(
+            response.status() != StatusCode::TOO_MANY_REQUESTS,
+            error::OverpassRateLimitExceeded
+        );
+
+        Ok(response.error_for_status()?.bytes().await?)
+    }
+}
+
+/// Meta data that loads the elements of an Overpass layer within the query's bounding box
+#[derive(Debug, Clone)]
+struct OverpassMetaData {
+    client: OverpassClient,
+    layer: OverpassLayerDefinition,
+}
+
+#[async_trait]
+impl MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle> for OverpassMetaData {
+    async fn loading_info(
+        &self,
+        query: VectorQueryRectangle,
+    ) -> Result<OgrSourceDataset, geoengine_operators::error::Error> {
+        let file_path = self
+            .client
+            .fetch(&self.layer.query(
+                query.spatial_bounds,
+                self.client.request_limits.timeout_seconds,
+            ))
+            .await
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        Ok(OgrSourceDataset {
+            file_name: file_path,
+            layer_name: self.layer.geometry.ogr_layer_name().to_owned(),
+            data_type: Some(self.layer.geometry.vector_data_type()),
+            time: OgrSourceDatasetTimeType::None,
+            default_geometry: None,
+            columns: Some(OgrSourceColumnSpec {
+                format_specifics: None,
+                x: String::new(),
+                y: None,
+                int: vec![],
+                float: vec![],
+                text: self.layer.columns.clone(),
+                bool: vec![],
+                datetime: vec![],
+                rename: None,
+            }),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            encoding: None,
+        })
+    }
+
+    async fn result_descriptor(
+        &self,
+    ) -> Result<VectorResultDescriptor, geoengine_operators::error::Error> {
+        Ok(VectorResultDescriptor {
+            data_type: self.layer.geometry.vector_data_type(),
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: self
+                .layer
+                .columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.clone(),
+                        VectorColumnInfo {
+                            data_type: FeatureDataType::Text,
+                            measurement: Measurement::Unitless,
+                        },
+                    )
+                })
+                .collect(),
+            time: None,
+            bbox: None,
+        })
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+impl OverpassDataProvider {
+    fn layer(&self, id: &LayerId) -> Result<&OverpassLayerDefinition> {
+        self.layers
+            .iter()
+            .find(|layer| layer.id == id.0)
+            .ok_or_else(|| Error::UnknownLayerId { id: id.clone() })
+    }
+}
+
+#[async_trait]
+impl DataProvider for OverpassDataProvider {
+    async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput> {
+        Ok(ProvenanceOutput {
+            data: id.clone(),
+            provenance: Some(Provenance {
+                citation: "© OpenStreetMap contributors".to_owned(),
+                license: "Open Data Commons Open Database License (ODbL)".to_owned(),
+                uri: "https://www.openstreetmap.org/copyright".to_owned(),
+            }),
+        })
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            spatial_filter: true,
+            time_filter: false,
+            attribute_filter: false,
+        }
+    }
+}
+
+#[async_trait]
+impl LayerCollectionProvider for OverpassDataProvider {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        ensure!(
+            *collection == self.root_collection_id().await?,
+            error::UnknownLayerCollectionId {
+                id: collection.clone()
+            }
+        );
+
+        let options = options.user_input;
+
+        let items = self
+            .layers
+            .iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|layer| {
+                CollectionItem::Layer(LayerListing {
+                    id: ProviderLayerId {
+                        provider_id: self.id,
+                        layer_id: LayerId(layer.id.clone()),
+                    },
+                    name: layer.name.clone(),
+                    description: layer.description.clone(),
+                })
+            })
+            .collect();
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name: self.name.clone(),
+            description: "OpenStreetMap data via the Overpass API".to_owned(),
+            items,
+            entry_label: None,
+            properties: vec![],
+        })
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId("root".to_owned()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        let layer = self.layer(id)?;
+
+        Ok(Layer {
+            id: ProviderLayerId {
+                provider_id: self.id,
+                layer_id: id.clone(),
+            },
+            name: layer.name.clone(),
+            description: layer.description.clone(),
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    OgrSource {
+                        params: OgrSourceParameters {
+                            data: DataId::External(ExternalDataId {
+                                provider_id: self.id,
+                                layer_id: id.clone(),
+                            })
+                            .into(),
+                            attribute_projection: None,
+                            attribute_filters: None,
+                            schema_merging: None,
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+            properties: vec![],
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for OverpassDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let id: DataId = id.clone().into();
+
+        let layer = id
+            .external()
+            .ok_or(Error::InvalidDataId)
+            .and_then(|id| self.layer(&id.layer_id).map(Clone::clone))
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        Ok(Box::new(OverpassMetaData {
+            client: self.client.clone(),
+            layer,
+        }))
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for OverpassDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for OverpassDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use httptest::{
+        all_of,
+        matchers::{contains, request, url_decoded},
+        responders::status_code,
+        Expectation, Server,
+    };
+
+    const PROVIDER_ID: DataProviderId =
+        DataProviderId::from_u128(0x2c2f_9f2a_5a4b_4c7e_9a4e_7d1b_9c4e_2f01);
+
+    fn roads() -> OverpassLayerDefinition {
+        OverpassLayerDefinition {
+            id: "roads".to_owned(),
+            name: "Roads".to_owned(),
+            description: "All highways".to_owned(),
+            filter: "[\"highway\"]".to_owned(),
+            geometry: OverpassGeometry::Line,
+            columns: vec!["osm_id".to_owned(), "name".to_owned(), "highway".to_owned()],
+        }
+    }
+
+    async fn create_provider(server: &Server, cache_path: PathBuf) -> Box<dyn DataProvider> {
+        Box::new(OverpassDataProviderDefinition {
+            id: PROVIDER_ID,
+            name: "OpenStreetMap".to_owned(),
+            base_url: Url::parse(&server.url_str("/api/")).unwrap(),
+            cache_path,
+            disk_cache: Default::default(),
+            request_limits: OverpassRequestLimits {
+                min_request_interval_ms: 0,
+                timeout_seconds: 25,
+                number_of_retries: 1,
+                initial_retry_delay_ms: 0,
+            },
+            layers: vec![roads()],
+            cache_ttl: Default::default(),
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn it_builds_queries() {
+        let bbox = BoundingBox2D::new_unchecked((8.0, 50.0).into(), (9.0, 51.0).into());
+
+        assert_eq!(
+            roads().query(bbox, 25),
+            "[out:xml][timeout:25];(way[\"highway\"](50,8,51,9););(._;>;);out body;"
+        );
+
+        let buildings = OverpassLayerDefinition {
+            filter: "[\"building\"]".to_owned(),
+            geometry: OverpassGeometry::Polygon,
+            ..roads()
+        };
+
+        assert_eq!(
+            buildings.query(
+                BoundingBox2D::new_unchecked((-200.0, -100.0).into(), (200.0, 100.0).into()),
+                10
+            ),
+            "[out:xml][timeout:10];(way[\"building\"](-90,-180,90,180);relation[\"building\"][\"type\"=\"multipolygon\"](-90,-180,90,180););(._;>;);out body;"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_caches_query_results() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/api/interpreter"),
+                request::body(url_decoded(contains((
+                    "data",
+                    "[out:xml][timeout:25];(way[\"highway\"](50,8,51,9););(._;>;);out body;"
+                )))),
+            ])
+            .times(1)
+            .respond_with(
+                status_code(200)
+                    .body(r#"<?xml version="1.0" encoding="UTF-8"?><osm version="0.6"></osm>"#),
+            ),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id: PROVIDER_ID,
+                    layer_id: LayerId("roads".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((8.0, 50.0).into(), (9.0, 51.0).into()),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let loading_info = meta.loading_info(query).await.unwrap();
+        assert!(loading_info.file_name.starts_with(cache_dir.path()));
+        assert_eq!(loading_info.layer_name, "lines");
+        assert_eq!(
+            loading_info.data_type,
+            Some(VectorDataType::MultiLineString)
+        );
+        assert!(tokio::fs::read_to_string(&loading_info.file_name)
+            .await
+            .unwrap()
+            .contains("<osm"));
+
+        // the second query is answered from the cache
+        let cached_loading_info = meta.loading_info(query).await.unwrap();
+        assert_eq!(cached_loading_info.file_name, loading_info.file_name);
+
+        assert_eq!(meta.result_descriptor().await.unwrap().columns.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_reports_exceeded_rate_limits() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/api/interpreter"))
+                .times(2)
+                .respond_with(status_code(429)),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id: PROVIDER_ID,
+                    layer_id: LayerId("roads".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let result = meta
+            .loading_info(VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked(
+                    (8.0, 50.0).into(),
+                    (9.0, 51.0).into(),
+                ),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+}
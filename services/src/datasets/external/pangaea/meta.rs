@@ -149,6 +149,7 @@ impl PangeaMetaData {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            encoding: None,
         }
     }
 
@@ -1,6 +1,7 @@
 use crate::api::model::datatypes::{DataId, DataProviderId, LayerId};
 use crate::datasets::external::pangaea::meta::PangeaMetaData;
 use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::layers::cache::CacheTtlSeconds;
 use crate::layers::external::{DataProvider, DataProviderDefinition};
 use crate::layers::layer::{Layer, LayerCollection, LayerCollectionListOptions};
 use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
@@ -29,6 +30,8 @@ pub const PANGAEA_PROVIDER_ID: DataProviderId =
 pub struct PangaeaDataProviderDefinition {
     name: String,
     base_url: String,
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
 }
 
 #[typetag::serde]
@@ -49,6 +52,10 @@ impl DataProviderDefinition for PangaeaDataProviderDefinition {
     fn id(&self) -> DataProviderId {
         PANGAEA_PROVIDER_ID
     }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
 }
 
 #[derive(Debug)]
@@ -248,6 +255,7 @@ mod tests {
         Box::new(PangaeaDataProviderDefinition {
             name: "Pangaea".to_string(),
             base_url: server.url_str("").strip_suffix('/').unwrap().to_owned(),
+            cache_ttl: Default::default(),
         })
         .initialize()
         .await
@@ -435,6 +443,7 @@ mod tests {
                 data: id.into(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed();
@@ -491,6 +500,7 @@ mod tests {
                 data: id.into(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed();
@@ -559,6 +569,7 @@ mod tests {
                 data: id.into(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed();
@@ -623,6 +634,7 @@ mod tests {
                 data: id.into(),
                 attribute_projection: None,
                 attribute_filters: None,
+                schema_merging: None,
             },
         }
         .boxed();
@@ -0,0 +1,1020 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::api::model::datatypes::{DataId, DataProviderId, ExternalDataId, LayerId};
+use crate::datasets::listing::{Provenance, ProvenanceOutput};
+use crate::error::{self, Error, Result};
+use crate::layers::cache::CacheTtlSeconds;
+use crate::layers::external::{DataProvider, DataProviderDefinition, ProviderCapabilities};
+use crate::layers::layer::{
+    CollectionItem, Layer, LayerCollection, LayerCollectionListOptions, LayerListing,
+    ProviderLayerCollectionId, ProviderLayerId,
+};
+use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::util::disk_cache::{DiskCache, DiskCacheLimits};
+use crate::util::parsing::deserialize_base_url;
+use crate::util::user_input::Validated;
+use crate::workflows::workflow::Workflow;
+use async_trait::async_trait;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, Measurement, RasterQueryRectangle, TimeInterval,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, TypedOperator, VectorColumnInfo,
+    VectorOperator, VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    GdalLoadingInfo, OgrSource, OgrSourceColumnSpec, OgrSourceDataset, OgrSourceDatasetTimeType,
+    OgrSourceErrorSpec, OgrSourceParameters, OgrSourceTimeFormat,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use snafu::ensure;
+use url::Url;
+
+const STATIONS_LAYER_ID: &str = "stations";
+const OBSERVED_PROPERTY_PREFIX: &str = "observedProperties/";
+/// The maximum number of pages that are followed when loading an entity set
+const MAX_PAGES: usize = 100;
+
+/// The `SensorThings` provider allows to include stations and their observations from an
+/// OGC `SensorThings` API endpoint (e.g. a FROST server). The stations (`Things`) are a point
+/// layer and each `ObservedProperty` is a point layer of time-stamped observations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorThingsDataProviderDefinition {
+    id: DataProviderId,
+    name: String,
+    /// The versioned service root, e.g. `https://example.org/FROST-Server/v1.1/`
+    #[serde(deserialize_with = "deserialize_base_url")]
+    base_url: Url,
+    /// Directory where downloaded observations are cached
+    cache_path: PathBuf,
+    /// Expiry and size limit of the cache directory
+    #[serde(default)]
+    disk_cache: DiskCacheLimits,
+    /// Maximum number of observations or stations per query
+    #[serde(default = "SensorThingsDataProviderDefinition::default_max_observations")]
+    max_observations: usize,
+    /// How long the listing of observed properties is cached
+    #[serde(default)]
+    cache_ttl: CacheTtlSeconds,
+}
+
+impl SensorThingsDataProviderDefinition {
+    fn default_max_observations() -> usize {
+        100_000
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl DataProviderDefinition for SensorThingsDataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn DataProvider>> {
+        Ok(Box::new(SensorThingsDataProvider {
+            id: self.id,
+            name: self.name,
+            client: SensorThingsClient {
+                client: Client::new(),
+                base_url: self.base_url,
+                cache: DiskCache::new(self.cache_path, self.disk_cache),
+                max_observations: self.max_observations,
+            },
+        }))
+    }
+
+    fn type_name(&self) -> &'static str {
+        "SensorThings"
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DataProviderId {
+        self.id
+    }
+
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        self.cache_ttl
+    }
+}
+
+#[derive(Debug)]
+pub struct SensorThingsDataProvider {
+    id: DataProviderId,
+    name: String,
+    client: SensorThingsClient,
+}
+
+/// A (paged) collection of entities
+#[derive(Debug, Deserialize)]
+struct EntitySet<T> {
+    value: Vec<T>,
+    #[serde(rename = "@iot.nextLink")]
+    next_link: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ObservedProperty {
+    #[serde(rename = "@iot.id")]
+    id: serde_json::Value,
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Thing {
+    #[serde(rename = "@iot.id")]
+    id: serde_json::Value,
+    name: String,
+    #[serde(default, rename = "Locations")]
+    locations: Vec<Location>,
+}
+
+impl Thing {
+    /// The first point location of the thing
+    fn coordinate(&self) -> Option<Coordinate2D> {
+        self.locations.iter().find_map(Location::coordinate)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Location {
+    location: serde_json::Value,
+}
+
+impl Location {
+    fn coordinate(&self) -> Option<Coordinate2D> {
+        // the location is either a `GeoJSON` geometry or a feature that contains one
+        let geometry = match self.location.get("geometry") {
+            Some(geometry) => geometry,
+            None => &self.location,
+        };
+
+        if geometry.get("type")?.as_str()? != "Point" {
+            return None;
+        }
+
+        let coordinates = geometry.get("coordinates")?.as_array()?;
+        Some(Coordinate2D::new(
+            coordinates.get(0)?.as_f64()?,
+            coordinates.get(1)?.as_f64()?,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Datastream {
+    #[serde(rename = "@iot.id")]
+    id: serde_json::Value,
+    name: String,
+    #[serde(default)]
+    unit_of_measurement: Option<UnitOfMeasurement>,
+    #[serde(rename = "Thing")]
+    thing: Thing,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UnitOfMeasurement {
+    #[serde(default)]
+    symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Observation {
+    phenomenon_time: String,
+    result: serde_json::Value,
+}
+
+/// Formats an entity id as used in paths, e.g. `Things(1)` or `Things('a')`
+fn entity_key(id: &serde_json::Value) -> String {
+    match id {
+        serde_json::Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        other => other.to_string(),
+    }
+}
+
+/// The layers of the provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SensorThingsLayer {
+    Stations,
+    /// The observations of an observed property, identified by its entity key
+    ObservedProperty(String),
+}
+
+impl SensorThingsLayer {
+    fn from_layer_id(id: &LayerId) -> Result<Self> {
+        if id.0 == STATIONS_LAYER_ID {
+            return Ok(SensorThingsLayer::Stations);
+        }
+
+        id.0.strip_prefix(OBSERVED_PROPERTY_PREFIX)
+            .filter(|key| !key.is_empty())
+            .map(|key| SensorThingsLayer::ObservedProperty(key.to_owned()))
+            .ok_or(Error::InvalidLayerId)
+    }
+
+    fn layer_id(&self) -> LayerId {
+        match self {
+            SensorThingsLayer::Stations => LayerId(STATIONS_LAYER_ID.to_owned()),
+            SensorThingsLayer::ObservedProperty(key) => {
+                LayerId(format!("{}{}", OBSERVED_PROPERTY_PREFIX, key))
+            }
+        }
+    }
+
+    fn columns(&self) -> Vec<(&'static str, FeatureDataType)> {
+        match self {
+            SensorThingsLayer::Stations => vec![("station", FeatureDataType::Text)],
+            SensorThingsLayer::ObservedProperty(_) => vec![
+                ("station", FeatureDataType::Text),
+                ("datastream", FeatureDataType::Text),
+                ("result", FeatureDataType::Float),
+                ("unit", FeatureDataType::Text),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SensorThingsClient {
+    client: Client,
+    base_url: Url,
+    cache: DiskCache,
+    max_observations: usize,
+}
+
+impl SensorThingsClient {
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<EntitySet<T>> {
+        Ok(self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Loads all entities of `path` by following the `@iot.nextLink`s, stopping after `limit` entities or `MAX_PAGES` pages
+    async fn get_all<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        limit: usize,
+    ) -> Result<Vec<T>> {
+        let mut url = self.base_url.join(path)?;
+        url.query_pairs_mut().extend_pairs(params);
+
+        let mut entities = Vec::new();
+        let mut pages = 0;
+        loop {
+            let page = self.get::<T>(url).await?;
+            entities.extend(page.value);
+            pages += 1;
+
+            match page.next_link {
+                Some(next_link) if entities.len() < limit => {
+                    if pages == MAX_PAGES {
+                        log::warn!("Stopped loading `{path}` after {MAX_PAGES} pages");
+                        break;
+                    }
+
+                    url = Url::parse(&next_link)?;
+                }
+                _ => break,
+            }
+        }
+
+        entities.truncate(limit);
+        Ok(entities)
+    }
+
+    async fn observed_property(&self, key: &str) -> Result<ObservedProperty> {
+        Ok(self
+            .client
+            .get(
+                self.base_url
+                    .join(&format!("ObservedProperties({})", key))?,
+            )
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    async fn observed_properties(
+        &self,
+        options: &LayerCollectionListOptions,
+    ) -> Result<Vec<ObservedProperty>> {
+        let mut url = self.base_url.join("ObservedProperties")?;
+        url.query_pairs_mut()
+            .append_pair("$orderby", "name")
+            .append_pair("$skip", &options.offset.to_string())
+            .append_pair("$top", &options.limit.to_string());
+
+        Ok(self.get(url).await?.value)
+    }
+
+    /// Writes the features of the `layer` within the query's bounds into a `GeoJSON` file.
+    /// Returns the path of the (possibly cached) file.
+    async fn features(
+        &self,
+        layer: &SensorThingsLayer,
+        query: &VectorQueryRectangle,
+    ) -> Result<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        self.base_url.hash(&mut hasher);
+        format!("{:?}{:?}", layer, query).hash(&mut hasher);
+        self.max_observations.hash(&mut hasher);
+        let file_name = format!("{:016x}.geojson", hasher.finish());
+
+        if let Some(file_path) = self.cache.get(&file_name).await {
+            return Ok(file_path);
+        }
+
+        let features = match layer {
+            SensorThingsLayer::Stations => self.station_features(query.spatial_bounds).await?,
+            SensorThingsLayer::ObservedProperty(key) => {
+                self.observation_features(key, query).await?
+            }
+        };
+
+        let feature_collection = json!({
+            "type": "FeatureCollection",
+            "name": "features",
+            "features": features,
+        });
+
+        self.cache
+            .insert(&file_name, feature_collection.to_string())
+            .await
+    }
+
+    async fn station_features(&self, bbox: BoundingBox2D) -> Result<Vec<serde_json::Value>> {
+        let filter = location_filter("Locations/location", bbox);
+        let things: Vec<Thing> = self
+            .get_all(
+                "Things",
+                &[("$expand", "Locations"), ("$filter", filter.as_str())],
+                self.max_observations,
+            )
+            .await?;
+
+        Ok(things
+            .iter()
+            .filter_map(|thing| {
+                let coordinate = thing.coordinate()?;
+                bbox.contains_coordinate(&coordinate)
+                    .then(|| point_feature(coordinate, json!({ "station": thing.name })))
+            })
+            .collect())
+    }
+
+    async fn observation_features(
+        &self,
+        observed_property: &str,
+        query: &VectorQueryRectangle,
+    ) -> Result<Vec<serde_json::Value>> {
+        let filter = location_filter("Thing/Locations/location", query.spatial_bounds);
+        let datastreams: Vec<Datastream> = self
+            .get_all(
+                &format!("ObservedProperties({})/Datastreams", observed_property),
+                &[
+                    ("$expand", "Thing($expand=Locations)"),
+                    ("$filter", filter.as_str()),
+                ],
+                self.max_observations,
+            )
+            .await?;
+
+        let time_filter = phenomenon_time_filter(query.time_interval);
+
+        let mut features = Vec::new();
+
+        for datastream in datastreams {
+            let coordinate = match datastream.thing.coordinate() {
+                Some(coordinate) if query.spatial_bounds.contains_coordinate(&coordinate) => {
+                    coordinate
+                }
+                _ => continue,
+            };
+
+            let mut params = vec![
+                ("$select", "phenomenonTime,result"),
+                ("$orderby", "phenomenonTime"),
+            ];
+            if let Some(time_filter) = &time_filter {
+                params.push(("$filter", time_filter));
+            }
+
+            let observations: Vec<Observation> = self
+                .get_all(
+                    &format!("Datastreams({})/Observations", entity_key(&datastream.id)),
+                    &params,
+                    self.max_observations - features.len(),
+                )
+                .await?;
+
+            let unit = datastream
+                .unit_of_measurement
+                .as_ref()
+                .and_then(|u| u.symbol.clone());
+
+            features.extend(observations.into_iter().map(|observation| {
+                // the phenomenon time is either an instant or an interval `start/end`
+                let mut time = observation.phenomenon_time.splitn(2, '/');
+                let start = time.next().unwrap_or_default().to_owned();
+                let end = time.next().map_or_else(|| start.clone(), ToOwned::to_owned);
+
+                point_feature(
+                    coordinate,
+                    json!({
+                        "station": datastream.thing.name,
+                        "datastream": datastream.name,
+                        "result": observation.result.as_f64(),
+                        "unit": unit,
+                        "start": start,
+                        "end": end,
+                    }),
+                )
+            }));
+
+            if features.len() >= self.max_observations {
+                break;
+            }
+        }
+
+        Ok(features)
+    }
+}
+
+fn point_feature(coordinate: Coordinate2D, properties: serde_json::Value) -> serde_json::Value {
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [coordinate.x, coordinate.y],
+        },
+        "properties": properties,
+    })
+}
+
+/// Creates a `$filter` that selects the observations within the `time` interval
+/// Selects the entities whose `location` lies within the `bbox`.
+/// The locations are filtered again after loading, since servers may not support geospatial functions.
+fn location_filter(location: &str, bbox: BoundingBox2D) -> String {
+    let (x1, y1) = (bbox.lower_left().x, bbox.lower_left().y);
+    let (x2, y2) = (bbox.upper_right().x, bbox.upper_right().y);
+
+    format!(
+        "st_within({location}, geography'POLYGON(({x1} {y1}, {x2} {y1}, {x2} {y2}, {x1} {y2}, {x1} {y1}))')"
+    )
+}
+
+fn phenomenon_time_filter(time: TimeInterval) -> Option<String> {
+    let mut filters = vec![];
+
+    if !time.start().is_min() {
+        filters.push(format!(
+            "phenomenonTime ge {}",
+            time.start().as_rfc3339_with_millis()
+        ));
+    }
+    if !time.end().is_max() {
+        filters.push(format!(
+            "phenomenonTime lt {}",
+            time.end().as_rfc3339_with_millis()
+        ));
+    }
+
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters.join(" and "))
+    }
+}
+
+/// Meta data that loads the stations or observations within the query's bounds
+#[derive(Debug, Clone)]
+struct SensorThingsMetaData {
+    client: SensorThingsClient,
+    layer: SensorThingsLayer,
+}
+
+#[async_trait]
+impl MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for SensorThingsMetaData
+{
+    async fn loading_info(
+        &self,
+        query: VectorQueryRectangle,
+    ) -> Result<OgrSourceDataset, geoengine_operators::error::Error> {
+        let file_path = self
+            .client
+            .features(&self.layer, &query)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        let columns = self.layer.columns();
+        let columns_of_type = |data_type: FeatureDataType| {
+            columns
+                .iter()
+                .filter(|(_, t)| *t == data_type)
+                .map(|(name, _)| (*name).to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        Ok(OgrSourceDataset {
+            file_name: file_path,
+            layer_name: "features".to_owned(),
+            data_type: Some(VectorDataType::MultiPoint),
+            time: match self.layer {
+                SensorThingsLayer::Stations => OgrSourceDatasetTimeType::None,
+                SensorThingsLayer::ObservedProperty(_) => OgrSourceDatasetTimeType::StartEnd {
+                    start_field: "start".to_owned(),
+                    start_format: OgrSourceTimeFormat::Auto,
+                    end_field: "end".to_owned(),
+                    end_format: OgrSourceTimeFormat::Auto,
+                },
+            },
+            default_geometry: None,
+            columns: Some(OgrSourceColumnSpec {
+                format_specifics: None,
+                x: String::new(),
+                y: None,
+                int: vec![],
+                float: columns_of_type(FeatureDataType::Float),
+                text: columns_of_type(FeatureDataType::Text),
+                bool: vec![],
+                datetime: vec![],
+                rename: None,
+            }),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            encoding: None,
+        })
+    }
+
+    async fn result_descriptor(
+        &self,
+    ) -> Result<VectorResultDescriptor, geoengine_operators::error::Error> {
+        Ok(VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            columns: self
+                .layer
+                .columns()
+                .into_iter()
+                .map(|(name, data_type)| {
+                    (
+                        name.to_owned(),
+                        VectorColumnInfo {
+                            data_type,
+                            measurement: Measurement::Unitless,
+                        },
+                    )
+                })
+                .collect(),
+            time: None,
+            bbox: None,
+        })
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl DataProvider for SensorThingsDataProvider {
+    async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput> {
+        Ok(ProvenanceOutput {
+            data: id.clone(),
+            provenance: Some(Provenance {
+                citation: self.name.clone(),
+                license: String::new(),
+                uri: self.client.base_url.to_string(),
+            }),
+        })
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            spatial_filter: false,
+            time_filter: true,
+            attribute_filter: false,
+        }
+    }
+}
+
+#[async_trait]
+impl LayerCollectionProvider for SensorThingsDataProvider {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        ensure!(
+            *collection == self.root_collection_id().await?,
+            error::UnknownLayerCollectionId {
+                id: collection.clone()
+            }
+        );
+
+        let mut options = options.user_input;
+
+        // the stations layer is the first item, followed by the observed properties
+        let mut items = vec![];
+        if options.offset == 0 && options.limit > 0 {
+            items.push(CollectionItem::Layer(LayerListing {
+                id: ProviderLayerId {
+                    provider_id: self.id,
+                    layer_id: SensorThingsLayer::Stations.layer_id(),
+                },
+                name: "Stations".to_owned(),
+                description: "The locations of all stations".to_owned(),
+            }));
+            options.limit -= 1;
+        } else {
+            options.offset -= 1;
+        }
+
+        let observed_properties = self.client.observed_properties(&options).await?;
+        items.extend(observed_properties.into_iter().map(|observed_property| {
+            CollectionItem::Layer(LayerListing {
+                id: ProviderLayerId {
+                    provider_id: self.id,
+                    layer_id: SensorThingsLayer::ObservedProperty(entity_key(
+                        &observed_property.id,
+                    ))
+                    .layer_id(),
+                },
+                name: observed_property.name,
+                description: observed_property.description,
+            })
+        }));
+
+        Ok(LayerCollection {
+            id: ProviderLayerCollectionId {
+                provider_id: self.id,
+                collection_id: collection.clone(),
+            },
+            name: self.name.clone(),
+            description: "SensorThings API".to_owned(),
+            items,
+            entry_label: None,
+            properties: vec![],
+        })
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId("root".to_owned()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        let (name, description) = match SensorThingsLayer::from_layer_id(id)? {
+            SensorThingsLayer::Stations => (
+                "Stations".to_owned(),
+                "The locations of all stations".to_owned(),
+            ),
+            SensorThingsLayer::ObservedProperty(key) => {
+                let observed_property = self.client.observed_property(&key).await?;
+                (observed_property.name, observed_property.description)
+            }
+        };
+
+        Ok(Layer {
+            id: ProviderLayerId {
+                provider_id: self.id,
+                layer_id: id.clone(),
+            },
+            name,
+            description,
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    OgrSource {
+                        params: OgrSourceParameters {
+                            data: DataId::External(ExternalDataId {
+                                provider_id: self.id,
+                                layer_id: id.clone(),
+                            })
+                            .into(),
+                            attribute_projection: None,
+                            attribute_filters: None,
+                            schema_merging: None,
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+            properties: vec![],
+            metadata: HashMap::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for SensorThingsDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let id: DataId = id.clone().into();
+
+        let layer = id
+            .external()
+            .ok_or(Error::InvalidDataId)
+            .and_then(|id| SensorThingsLayer::from_layer_id(&id.layer_id))
+            .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            })?;
+
+        Ok(Box::new(SensorThingsMetaData {
+            client: self.client.clone(),
+            layer,
+        }))
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for SensorThingsDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for SensorThingsDataProvider
+{
+    async fn meta_data(
+        &self,
+        _id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::primitives::{DateTime, SpatialResolution};
+    use httptest::{
+        all_of,
+        matchers::{contains, request, url_decoded},
+        responders::json_encoded,
+        Expectation, Server,
+    };
+
+    const PROVIDER_ID: DataProviderId =
+        DataProviderId::from_u128(0x7b1e_2a44_0f3c_4d1a_b6e2_91c3_5d7f_8a20);
+
+    async fn create_provider(server: &Server, cache_path: PathBuf) -> Box<dyn DataProvider> {
+        Box::new(SensorThingsDataProviderDefinition {
+            id: PROVIDER_ID,
+            name: "Sensors".to_owned(),
+            base_url: Url::parse(&server.url_str("/v1.1/")).unwrap(),
+            cache_path,
+            disk_cache: Default::default(),
+            max_observations: 100,
+            cache_ttl: Default::default(),
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn it_formats_entity_keys() {
+        assert_eq!(entity_key(&json!(42)), "42");
+        assert_eq!(entity_key(&json!("it's")), "'it''s'");
+    }
+
+    #[tokio::test]
+    async fn it_lists_stations_and_observed_properties() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1.1/ObservedProperties"),
+                request::query(url_decoded(contains(("$skip", "0")))),
+                request::query(url_decoded(contains(("$top", "19")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "value": [
+                    {"@iot.id": 1, "name": "Water Temperature", "description": "Temperature of the water"},
+                    {"@iot.id": "pH", "name": "pH", "description": ""}
+                ]
+            }))),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let collection = provider
+            .collection(
+                &provider.root_collection_id().await.unwrap(),
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 20,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let layer_ids = collection
+            .items
+            .iter()
+            .map(|item| match item {
+                CollectionItem::Layer(layer) => layer.id.layer_id.0.clone(),
+                CollectionItem::Collection(_) => panic!("unexpected collection"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            layer_ids,
+            vec![
+                "stations",
+                "observedProperties/1",
+                "observedProperties/'pH'"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_loads_observations() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1.1/ObservedProperties(1)/Datastreams"),
+                request::query(url_decoded(contains((
+                    "$filter",
+                    "st_within(Thing/Locations/location, geography'POLYGON((0 50, 10 50, 10 55, 0 55, 0 50))')"
+                )))),
+            ])
+            .respond_with(json_encoded(json!({
+                "value": [
+                    {
+                        "@iot.id": 7,
+                        "name": "Buoy A temperature",
+                        "unitOfMeasurement": {"symbol": "°C"},
+                        "Thing": {
+                            "@iot.id": 3,
+                            "name": "Buoy A",
+                            "Locations": [{"location": {"type": "Point", "coordinates": [8.5, 54.1]}}]
+                        }
+                    },
+                    {
+                        "@iot.id": 8,
+                        "name": "Buoy B temperature",
+                        "Thing": {
+                            "@iot.id": 4,
+                            "name": "Buoy B",
+                            "Locations": [{"location": {"type": "Point", "coordinates": [20.0, 60.0]}}]
+                        }
+                    }
+                ]
+            }))),
+        );
+
+        let next_link = server.url_str("/v1.1/Datastreams(7)/Observations?$skip=1");
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1.1/Datastreams(7)/Observations"),
+                request::query(url_decoded(contains((
+                    "$filter",
+                    "phenomenonTime ge 2020-01-01T00:00:00.000Z and phenomenonTime lt 2020-02-01T00:00:00.000Z"
+                )))),
+            ])
+            .respond_with(json_encoded(json!({
+                "value": [
+                    {"phenomenonTime": "2020-01-01T10:00:00Z", "result": 4.2}
+                ],
+                "@iot.nextLink": next_link
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/v1.1/Datastreams(7)/Observations"),
+                request::query(url_decoded(contains(("$skip", "1")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "value": [
+                    {"phenomenonTime": "2020-01-01T11:00:00Z/2020-01-01T12:00:00Z", "result": 4.5}
+                ]
+            }))),
+        );
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let provider = create_provider(&server, cache_dir.path().to_owned()).await;
+
+        let meta: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider
+            .meta_data(
+                &DataId::External(ExternalDataId {
+                    provider_id: PROVIDER_ID,
+                    layer_id: LayerId("observedProperties/1".to_owned()),
+                })
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        let loading_info = meta
+            .loading_info(VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked((0., 50.).into(), (10., 55.).into()),
+                time_interval: TimeInterval::new_unchecked(
+                    DateTime::new_utc(2020, 1, 1, 0, 0, 0),
+                    DateTime::new_utc(2020, 2, 1, 0, 0, 0),
+                ),
+                spatial_resolution: SpatialResolution::one(),
+            })
+            .await
+            .unwrap();
+
+        let feature_collection: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&loading_info.file_name)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            feature_collection["features"],
+            json!([
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "Point", "coordinates": [8.5, 54.1]},
+                    "properties": {
+                        "station": "Buoy A",
+                        "datastream": "Buoy A temperature",
+                        "result": 4.2,
+                        "unit": "°C",
+                        "start": "2020-01-01T10:00:00Z",
+                        "end": "2020-01-01T10:00:00Z"
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "Point", "coordinates": [8.5, 54.1]},
+                    "properties": {
+                        "station": "Buoy A",
+                        "datastream": "Buoy A temperature",
+                        "result": 4.5,
+                        "unit": "°C",
+                        "start": "2020-01-01T11:00:00Z",
+                        "end": "2020-01-01T12:00:00Z"
+                    }
+                }
+            ])
+        );
+    }
+}
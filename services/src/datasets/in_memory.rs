@@ -1,7 +1,11 @@
 use crate::api::model::datatypes::{DataId, DatasetId, LayerId};
 use crate::contexts::{Db, SimpleSession};
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider, OrderBy};
-use crate::datasets::storage::{AddDataset, Dataset, DatasetDb, DatasetStore, DatasetStorer};
+use crate::datasets::storage::{
+    append_time_slices, AddDataset, AppendTimeSlices, Dataset, DatasetDb,
+    DatasetLayerCollectionProvider, DatasetStore, DatasetStorer, TemporaryDatasetSession,
+};
+use crate::datasets::usage::{DatasetAccess, DatasetUsageDb, DatasetUsageTracker};
 use crate::error;
 use crate::error::Result;
 use crate::layers::layer::{
@@ -13,10 +17,11 @@ use crate::util::operators::source_operator_from_dataset;
 use crate::util::user_input::Validated;
 use crate::workflows::workflow::Workflow;
 use async_trait::async_trait;
-use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
+use geoengine_datatypes::primitives::{DateTime, RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_datatypes::util::Identifier;
 use geoengine_operators::engine::{
-    MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor, VectorResultDescriptor,
+    AuxiliaryMetadata, MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor,
+    VectorResultDescriptor,
 };
 
 use geoengine_operators::source::{
@@ -32,7 +37,7 @@ use super::storage::{DATASET_DB_LAYER_PROVIDER_ID, DATASET_DB_ROOT_COLLECTION_ID
 use super::{
     listing::SessionMetaDataProvider,
     storage::MetaDataDefinition,
-    upload::{Upload, UploadDb, UploadId},
+    upload::{StorageUsage, Upload, UploadDb, UploadId},
 };
 
 #[derive(Default)]
@@ -54,16 +59,67 @@ struct HashMapDatasetDbBackend {
         DatasetId,
         Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
     >,
+    /// the lists of Gdal files, which are kept to allow appending time slices
+    gdal_list_datasets: HashMap<DatasetId, GdalMetaDataList>,
+    auxiliary_metadata: HashMap<DatasetId, AuxiliaryMetadata>,
+    /// the sessions that added temporary datasets
+    temporary_datasets: HashMap<DatasetId, TemporaryDatasetSession>,
     uploads: HashMap<UploadId, Upload>,
 }
 
+impl HashMapDatasetDbBackend {
+    /// temporary datasets are only visible to the session that added them until it expires
+    fn is_visible(&self, session: &SimpleSession, dataset: &DatasetId) -> bool {
+        self.temporary_datasets
+            .get(dataset)
+            .map_or(true, |temporary| {
+                temporary.is_visible_to(session) && !temporary.is_expired(&DateTime::now())
+            })
+    }
+
+    fn remove_expired_datasets(&mut self) {
+        let now = DateTime::now();
+        let expired: Vec<DatasetId> = self
+            .temporary_datasets
+            .iter()
+            .filter(|(_, temporary)| temporary.is_expired(&now))
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in expired {
+            self.datasets.retain(|d| d.id != id);
+            self.ogr_datasets.remove(&id);
+            self.mock_datasets.remove(&id);
+            self.gdal_datasets.remove(&id);
+            self.gdal_list_datasets.remove(&id);
+            self.auxiliary_metadata.remove(&id);
+            self.temporary_datasets.remove(&id);
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct HashMapDatasetDb {
     backend: Db<HashMapDatasetDbBackend>,
+    usage: DatasetUsageTracker,
 }
 
 impl DatasetDb<SimpleSession> for HashMapDatasetDb {}
 
+impl DatasetLayerCollectionProvider<SimpleSession> for HashMapDatasetDb {}
+
+#[async_trait]
+impl DatasetUsageDb for HashMapDatasetDb {
+    async fn record_access(&self, dataset: DatasetId) -> Result<()> {
+        self.usage.record_access(dataset);
+        Ok(())
+    }
+
+    async fn dataset_accesses(&self) -> Result<HashMap<DatasetId, DatasetAccess>> {
+        Ok(self.usage.accesses())
+    }
+}
+
 #[async_trait]
 pub trait HashMapStorable: Send + Sync {
     async fn store(&self, id: DatasetId, db: &HashMapDatasetDb) -> TypedResultDescriptor;
@@ -158,11 +214,9 @@ impl HashMapStorable for GdalMetadataNetCdfCf {
 #[async_trait]
 impl HashMapStorable for GdalMetaDataList {
     async fn store(&self, id: DatasetId, db: &HashMapDatasetDb) -> TypedResultDescriptor {
-        db.backend
-            .write()
-            .await
-            .gdal_datasets
-            .insert(id, Box::new(self.clone()));
+        let mut backend = db.backend.write().await;
+        backend.gdal_datasets.insert(id, Box::new(self.clone()));
+        backend.gdal_list_datasets.insert(id, self.clone());
         self.result_descriptor.clone().into()
     }
 }
@@ -171,10 +225,12 @@ impl HashMapStorable for GdalMetaDataList {
 impl DatasetStore<SimpleSession> for HashMapDatasetDb {
     async fn add_dataset(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         dataset: Validated<AddDataset>,
         meta_data: Box<dyn HashMapStorable>,
     ) -> Result<DatasetId> {
+        self.backend.write().await.remove_expired_datasets();
+
         let dataset = dataset.user_input;
         let id = dataset.id.unwrap_or_else(DatasetId::new);
         let result_descriptor = meta_data.store(id, self).await;
@@ -188,11 +244,85 @@ impl DatasetStore<SimpleSession> for HashMapDatasetDb {
             symbology: dataset.symbology,
             provenance: dataset.provenance,
         };
-        self.backend.write().await.datasets.push(d);
+
+        let mut backend = self.backend.write().await;
+        backend.datasets.push(d);
+        if dataset.temporary {
+            backend
+                .temporary_datasets
+                .insert(id, TemporaryDatasetSession::new(session));
+        }
 
         Ok(id)
     }
 
+    async fn add_datasets(
+        &self,
+        session: &SimpleSession,
+        datasets: Vec<(Validated<AddDataset>, Box<dyn HashMapStorable>)>,
+    ) -> Result<Vec<DatasetId>> {
+        // adding datasets in memory cannot fail, so either all datasets are added or none
+        let mut ids = Vec::with_capacity(datasets.len());
+        for (dataset, meta_data) in datasets {
+            ids.push(self.add_dataset(session, dataset, meta_data).await?);
+        }
+
+        Ok(ids)
+    }
+
+    async fn append_time_slices(
+        &self,
+        session: &SimpleSession,
+        dataset: DatasetId,
+        slices: Validated<AppendTimeSlices>,
+    ) -> Result<()> {
+        let mut backend = self.backend.write().await;
+
+        ensure!(
+            backend.datasets.iter().any(|d| d.id == dataset)
+                && backend.is_visible(session, &dataset),
+            error::UnknownDatasetId
+        );
+
+        // modify a copy to leave the dataset untouched on error
+        let mut meta_data = backend
+            .gdal_list_datasets
+            .get(&dataset)
+            .ok_or(error::Error::DatasetNotAppendable)?
+            .clone();
+
+        append_time_slices(&mut meta_data, slices.user_input.slices)?;
+
+        if let Some(d) = backend.datasets.iter_mut().find(|d| d.id == dataset) {
+            d.result_descriptor = meta_data.result_descriptor.clone().into();
+        }
+        backend
+            .gdal_datasets
+            .insert(dataset, Box::new(meta_data.clone()));
+        backend.gdal_list_datasets.insert(dataset, meta_data);
+
+        Ok(())
+    }
+
+    async fn set_auxiliary_metadata(
+        &self,
+        session: &SimpleSession,
+        dataset: DatasetId,
+        metadata: AuxiliaryMetadata,
+    ) -> Result<()> {
+        let mut backend = self.backend.write().await;
+
+        ensure!(
+            backend.datasets.iter().any(|d| d.id == dataset)
+                && backend.is_visible(session, &dataset),
+            error::UnknownDatasetId
+        );
+
+        backend.auxiliary_metadata.insert(dataset, metadata);
+
+        Ok(())
+    }
+
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
         Box::new(meta)
     }
@@ -202,7 +332,7 @@ impl DatasetStore<SimpleSession> for HashMapDatasetDb {
 impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
     async fn list(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         options: Validated<DatasetListOptions>,
     ) -> Result<Vec<DatasetListing>> {
         // TODO: permissions
@@ -212,14 +342,17 @@ impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
 
         let backend = self.backend.read().await;
 
+        let visible = backend
+            .datasets
+            .iter()
+            .filter(|d| backend.is_visible(session, &d.id));
+
         let mut list: Vec<_> = if let Some(filter) = &options.filter {
-            backend
-                .datasets
-                .iter()
+            visible
                 .filter(|d| d.name.contains(filter) || d.description.contains(filter))
                 .collect()
         } else {
-            backend.datasets.iter().collect()
+            visible.collect()
         };
 
         match options.order {
@@ -237,12 +370,17 @@ impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
         Ok(list)
     }
 
-    async fn load(&self, _session: &SimpleSession, dataset: &DatasetId) -> Result<Dataset> {
+    async fn load(&self, session: &SimpleSession, dataset: &DatasetId) -> Result<Dataset> {
         // TODO: permissions
 
-        self.backend
-            .read()
-            .await
+        let backend = self.backend.read().await;
+
+        ensure!(
+            backend.is_visible(session, dataset),
+            error::UnknownDatasetId
+        );
+
+        backend
             .datasets
             .iter()
             .find(|d| d.id == *dataset)
@@ -252,12 +390,17 @@ impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
 
     async fn provenance(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         dataset: &DatasetId,
     ) -> Result<ProvenanceOutput> {
-        self.backend
-            .read()
-            .await
+        let backend = self.backend.read().await;
+
+        ensure!(
+            backend.is_visible(session, dataset),
+            error::UnknownDatasetId
+        );
+
+        backend
             .datasets
             .iter()
             .find(|d| d.id == *dataset)
@@ -267,6 +410,22 @@ impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
             })
             .ok_or(error::Error::UnknownDatasetId)
     }
+
+    async fn auxiliary_metadata(
+        &self,
+        session: &SimpleSession,
+        dataset: &DatasetId,
+    ) -> Result<Option<AuxiliaryMetadata>> {
+        let backend = self.backend.read().await;
+
+        ensure!(
+            backend.datasets.iter().any(|d| d.id == *dataset)
+                && backend.is_visible(session, dataset),
+            error::UnknownDatasetId
+        );
+
+        Ok(backend.auxiliary_metadata.get(dataset).cloned())
+    }
 }
 
 #[async_trait]
@@ -280,7 +439,7 @@ impl
 {
     async fn session_meta_data(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         id: &DataId,
     ) -> Result<
         Box<
@@ -291,12 +450,15 @@ impl
             >,
         >,
     > {
+        let id = id.internal().ok_or(error::Error::DataIdTypeMissMatch)?;
+
+        let backend = self.backend.read().await;
+        ensure!(backend.is_visible(session, &id), error::UnknownDataId);
+
         Ok(Box::new(
-            self.backend
-                .read()
-                .await
+            backend
                 .mock_datasets
-                .get(&id.internal().ok_or(error::Error::DataIdTypeMissMatch)?)
+                .get(&id)
                 .ok_or(error::Error::UnknownDataId)?
                 .clone(),
         ))
@@ -314,21 +476,23 @@ impl
 {
     async fn session_meta_data(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         id: &DataId,
     ) -> Result<Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>>
     {
+        let id = id
+            .internal()
+            .ok_or(geoengine_operators::error::Error::DatasetMetaData {
+                source: Box::new(error::Error::DataIdTypeMissMatch),
+            })?;
+
+        let backend = self.backend.read().await;
+
         Ok(Box::new(
-            self.backend
-                .read()
-                .await
+            backend
                 .ogr_datasets
-                .get(
-                    &id.internal()
-                        .ok_or(geoengine_operators::error::Error::DatasetMetaData {
-                            source: Box::new(error::Error::DataIdTypeMissMatch),
-                        })?,
-                )
+                .get(&id)
+                .filter(|_| backend.is_visible(session, &id))
                 .ok_or(geoengine_operators::error::Error::DatasetMetaData {
                     source: Box::new(error::Error::UnknownDatasetId),
                 })?
@@ -348,16 +512,16 @@ impl
 {
     async fn session_meta_data(
         &self,
-        _session: &SimpleSession,
+        session: &SimpleSession,
         id: &DataId,
     ) -> Result<Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>>
     {
         let id = id.internal().ok_or(error::Error::DataIdTypeMissMatch)?;
 
-        Ok(self
-            .backend
-            .read()
-            .await
+        let backend = self.backend.read().await;
+        ensure!(backend.is_visible(session, &id), error::UnknownDatasetId);
+
+        Ok(backend
             .gdal_datasets
             .get(&id)
             .ok_or(error::Error::UnknownDatasetId)?
@@ -381,6 +545,22 @@ impl UploadDb<SimpleSession> for HashMapDatasetDb {
         self.backend.write().await.uploads.insert(upload.id, upload);
         Ok(())
     }
+
+    async fn storage_usage(&self, _session: &SimpleSession) -> Result<StorageUsage> {
+        Ok(StorageUsage {
+            user_bytes: self
+                .backend
+                .read()
+                .await
+                .uploads
+                .values()
+                .map(Upload::byte_size)
+                .sum(),
+            user_quota_bytes: None,
+            tenant_bytes: None,
+            tenant_quota_bytes: None,
+        })
+    }
 }
 
 #[async_trait]
@@ -401,9 +581,11 @@ impl LayerCollectionProvider for HashMapDatasetDb {
 
         let backend = self.backend.read().await;
 
+        // the layer collection has no session, so it only lists datasets that are not temporary
         let items = backend
             .datasets
             .iter()
+            .filter(|d| !backend.temporary_datasets.contains_key(&d.id))
             .skip(options.offset as usize)
             .take(options.limit as usize)
             .map(|d| {
@@ -444,7 +626,7 @@ impl LayerCollectionProvider for HashMapDatasetDb {
         let dataset = backend
             .datasets
             .iter()
-            .find(|d| d.id == dataset_id)
+            .find(|d| d.id == dataset_id && !backend.temporary_datasets.contains_key(&d.id))
             .ok_or(error::Error::UnknownDatasetId)?;
 
         let operator = source_operator_from_dataset(&dataset.source_operator, &dataset.id.into())?;
@@ -497,6 +679,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -512,6 +695,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -9,16 +9,16 @@ use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_operators::engine::{
-    MetaData, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
+    AuxiliaryMetadata, MetaData, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
     VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DatasetListing {
     pub id: DatasetId,
@@ -31,12 +31,18 @@ pub struct DatasetListing {
     // TODO: meta data like bounds, resolution
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
 pub struct DatasetListOptions {
     // TODO: permissions
+    #[param(example = "Germany")]
     pub filter: Option<String>,
+    /// `NameAsc` or `NameDesc`
+    #[schema(value_type = String)]
+    #[param(value_type = String, example = "NameAsc")]
     pub order: OrderBy,
+    #[param(example = 0)]
     pub offset: u32,
+    #[param(example = 2)]
     pub limit: u32,
 }
 
@@ -108,6 +114,13 @@ pub trait DatasetProvider<S: Session>:
     async fn load(&self, session: &S, dataset: &DatasetId) -> Result<Dataset>;
 
     async fn provenance(&self, session: &S, dataset: &DatasetId) -> Result<ProvenanceOutput>;
+
+    /// Returns the auxiliary metadata of a dataset or `None` if it has none
+    async fn auxiliary_metadata(
+        &self,
+        session: &S,
+        dataset: &DatasetId,
+    ) -> Result<Option<AuxiliaryMetadata>>;
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
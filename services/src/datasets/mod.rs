@@ -4,3 +4,6 @@ pub mod in_memory;
 pub mod listing;
 pub mod storage;
 pub mod upload;
+pub mod upload_hooks;
+pub mod usage;
+pub mod validation;
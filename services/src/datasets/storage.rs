@@ -1,17 +1,21 @@
-use crate::api::model::datatypes::{DataProviderId, DatasetId};
-use crate::contexts::Session;
+use crate::api::model::datatypes::{DataProviderId, DatasetId, LayerId};
+use crate::contexts::{Session, SessionId};
 use crate::datasets::listing::{DatasetListing, DatasetProvider};
 use crate::datasets::upload::UploadDb;
 use crate::datasets::upload::UploadId;
+use crate::datasets::usage::DatasetUsageDb;
 use crate::error;
 use crate::error::Result;
-use crate::layers::listing::LayerCollectionProvider;
+use crate::layers::layer::{Layer, LayerCollection, LayerCollectionListOptions};
+use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
 use crate::projects::Symbology;
 use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
-use geoengine_datatypes::primitives::VectorQueryRectangle;
-use geoengine_operators::engine::MetaData;
-use geoengine_operators::source::{GdalMetaDataList, GdalMetadataNetCdfCf};
+use geoengine_datatypes::primitives::{DateTime, TimeInterval, VectorQueryRectangle};
+use geoengine_operators::engine::{AuxiliaryMetadata, MetaData};
+use geoengine_operators::source::{
+    GdalLoadingInfoTemporalSlice, GdalMetaDataList, GdalMetadataNetCdfCf,
+};
 use geoengine_operators::{engine::StaticMetaData, source::OgrSourceDataset};
 use geoengine_operators::{
     engine::TypedResultDescriptor, mock::MockDatasetDataSourceLoadingInfo,
@@ -21,6 +25,7 @@ use geoengine_operators::{engine::VectorResultDescriptor, source::GdalMetaDataRe
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 use std::fmt::Debug;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use super::listing::Provenance;
@@ -31,8 +36,22 @@ pub const DATASET_DB_LAYER_PROVIDER_ID: DataProviderId =
 pub const DATASET_DB_ROOT_COLLECTION_ID: Uuid =
     Uuid::from_u128(0x5460_73b6_d535_4205_b601_9967_5c9f_6dd7);
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "id": "9c874b9e-cea0-4553-b727-a13cb26ae4bb",
+    "name": "Germany",
+    "description": "Boundaries of Germany",
+    "resultDescriptor": {
+        "type": "vector",
+        "dataType": "MultiPolygon",
+        "spatialReference": "EPSG:4326",
+        "columns": {}
+    },
+    "sourceOperator": "OgrSource",
+    "symbology": null,
+    "provenance": null
+}))]
 pub struct Dataset {
     pub id: DatasetId,
     pub name: String,
@@ -57,7 +76,7 @@ impl Dataset {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AddDataset {
     pub id: Option<DatasetId>,
@@ -66,6 +85,10 @@ pub struct AddDataset {
     pub source_operator: String,
     pub symbology: Option<Symbology>,
     pub provenance: Option<Provenance>,
+    /// A temporary dataset is only visible to the session that adds it and it is deleted after the session expired,
+    /// e.g., for one-off sketches and intermediate results
+    #[serde(default)]
+    pub temporary: bool,
 }
 
 impl UserInput for AddDataset {
@@ -75,26 +98,192 @@ impl UserInput for AddDataset {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// The session that added a temporary dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporaryDatasetSession {
+    pub session: SessionId,
+    pub valid_until: DateTime,
+}
+
+impl TemporaryDatasetSession {
+    pub fn new<S: Session>(session: &S) -> Self {
+        Self {
+            session: session.id(),
+            valid_until: *session.valid_until(),
+        }
+    }
+
+    pub fn is_visible_to<S: Session>(&self, session: &S) -> bool {
+        self.session == session.id()
+    }
+
+    /// The dataset can be deleted because its session expired
+    pub fn is_expired(&self, now: &DateTime) -> bool {
+        self.valid_until < *now
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DatasetDefinition {
     pub properties: AddDataset,
+    /// the meta data of the source operator, tagged by its `type`, e.g., `OgrMetaData`
+    #[schema(value_type = Object)]
     pub meta_data: MetaDataDefinition,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 pub struct CreateDataset {
     pub upload: UploadId,
     pub definition: DatasetDefinition,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// Many datasets that are registered at once using previously uploaded files.
+/// The definitions are either given directly or read from a manifest file of the upload,
+/// which contains a JSON array of definitions.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
+pub struct BulkCreateDatasets {
+    pub upload: UploadId,
+    #[serde(default)]
+    pub definitions: Vec<DatasetDefinition>,
+    /// name of the manifest file inside the upload
+    #[serde(default)]
+    pub manifest: Option<String>,
+}
+
+impl UserInput for BulkCreateDatasets {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.definitions.is_empty() != self.manifest.is_none(),
+            error::InvalidBulkDatasetRegistration {
+                reason: "either definitions or a manifest must be given".to_string(),
+            }
+        );
+
+        if let Some(manifest) = &self.manifest {
+            ensure!(
+                !manifest.is_empty() && !manifest.contains('/') && !manifest.contains(".."),
+                error::InvalidUploadFileName
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of a bulk registration with one result per definition
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "registered": false,
+    "results": [
+        { "status": "valid", "name": "NDVI" },
+        { "status": "invalid", "name": "Germany", "error": "Upload file not found" }
+    ]
+}))]
+pub struct BulkCreateDatasetsReport {
+    /// `true` if all datasets were registered, otherwise none of them were
+    pub registered: bool,
+    pub results: Vec<BulkCreateDatasetResult>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum BulkCreateDatasetResult {
+    Registered {
+        name: String,
+        id: DatasetId,
+    },
+    /// The definition is valid, but it was not registered because other definitions are invalid
+    Valid {
+        name: String,
+    },
+    Invalid {
+        name: String,
+        error: String,
+    },
+}
+
+/// New time slices for a dataset with a list of Gdal files, e.g., to extend a rolling archive.
+/// The files are taken from a previous upload.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct AppendTimeSlices {
+    pub upload: UploadId,
+    /// the time slices of a `GdalMetaDataList` with paths relative to the upload
+    #[schema(value_type = Vec<Object>)]
+    pub slices: Vec<GdalLoadingInfoTemporalSlice>,
+}
+
+impl UserInput for AppendTimeSlices {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.slices.is_empty(),
+            error::InvalidTimeSlices {
+                reason: "no time slices given".to_string(),
+            }
+        );
+        ensure!(
+            self.slices
+                .windows(2)
+                .all(|slices| slices[0].time.end() <= slices[1].time.start()),
+            error::InvalidTimeSlices {
+                reason: "the time slices must be ordered and must not overlap".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// Appends `slices` to the `meta_data` and extends the time bounds of its result descriptor.
+///
+/// The slices must not start before the end of the last time slice of the `meta_data`.
+pub fn append_time_slices(
+    meta_data: &mut GdalMetaDataList,
+    slices: Vec<GdalLoadingInfoTemporalSlice>,
+) -> Result<()> {
+    if let (Some(last), Some(first_new)) = (meta_data.params.last(), slices.first()) {
+        ensure!(
+            last.time.end() <= first_new.time.start(),
+            error::InvalidTimeSlices {
+                reason: "the time slices must not start before the end of the dataset".to_string(),
+            }
+        );
+    }
+
+    meta_data.params.extend(slices);
+
+    if let (Some(first), Some(last)) = (meta_data.params.first(), meta_data.params.last()) {
+        let extent = TimeInterval::new_unchecked(first.time.start(), last.time.end());
+
+        meta_data.result_descriptor.time = Some(
+            meta_data
+                .result_descriptor
+                .time
+                .map_or(extent, |time| time.extend(&extent)),
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "upload": "420b06de-0a7e-45cb-9c1c-ea901b46ab69",
+    "datasetName": "Germany Border (auto)",
+    "datasetDescription": "The Outline of Germany (auto detected format)",
+    "mainFile": "germany_polygon.gpkg"
+}))]
 pub struct AutoCreateDataset {
     pub upload: UploadId,
     pub dataset_name: String,
     pub dataset_description: String,
     pub main_file: String,
+    /// see [`AddDataset::temporary`]
+    #[serde(default)]
+    pub temporary: bool,
 }
 
 impl UserInput for AutoCreateDataset {
@@ -112,17 +301,18 @@ impl UserInput for AutoCreateDataset {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub struct SuggestMetaData {
     pub upload: UploadId,
     pub main_file: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaDataSuggestion {
     pub main_file: String,
+    #[schema(value_type = Object)]
     pub meta_data: MetaDataDefinition,
 }
 
@@ -195,8 +385,73 @@ impl MetaDataDefinition {
 /// Handling of datasets provided by geo engine internally, staged and by external providers
 #[async_trait]
 pub trait DatasetDb<S: Session>:
-    DatasetStore<S> + DatasetProvider<S> + UploadDb<S> + LayerCollectionProvider + Send + Sync
+    DatasetStore<S>
+    + DatasetProvider<S>
+    + UploadDb<S>
+    + DatasetUsageDb
+    + LayerCollectionProvider
+    + DatasetLayerCollectionProvider<S>
+    + Send
+    + Sync
+{
+}
+
+/// Lists the datasets that are visible in a session as layers.
+///
+/// By default, all datasets of the [`LayerCollectionProvider`] are visible.
+#[async_trait]
+pub trait DatasetLayerCollectionProvider<S: Session>: LayerCollectionProvider + Sync {
+    /// get the given `collection` with the datasets that are visible in the `session`
+    async fn session_collection(
+        &self,
+        _session: &S,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.collection(collection, options).await
+    }
+
+    /// get the layer of a dataset that is visible in the `session`
+    async fn session_layer(&self, _session: &S, id: &LayerId) -> Result<Layer> {
+        self.get_layer(id).await
+    }
+}
+
+/// The datasets that are visible in a session as a [`LayerCollectionProvider`], e.g., for searching them
+pub struct SessionDatasetLayers<'a, D, S> {
+    db: &'a D,
+    session: &'a S,
+}
+
+impl<'a, D, S> SessionDatasetLayers<'a, D, S> {
+    pub fn new(db: &'a D, session: &'a S) -> Self {
+        Self { db, session }
+    }
+}
+
+#[async_trait]
+impl<D, S> LayerCollectionProvider for SessionDatasetLayers<'_, D, S>
+where
+    D: DatasetLayerCollectionProvider<S>,
+    S: Session,
 {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.db
+            .session_collection(self.session, collection, options)
+            .await
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        self.db.root_collection_id().await
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        self.db.session_layer(self.session, id).await
+    }
 }
 
 /// Defines the type of meta data a `DatasetDB` is able to store
@@ -215,6 +470,30 @@ pub trait DatasetStore<S: Session>: DatasetStorer {
         meta_data: Self::StorageType,
     ) -> Result<DatasetId>;
 
+    /// Adds all `datasets` or, if one of them cannot be added, none of them.
+    async fn add_datasets(
+        &self,
+        session: &S,
+        datasets: Vec<(Validated<AddDataset>, Self::StorageType)>,
+    ) -> Result<Vec<DatasetId>>;
+
+    /// Appends time slices to a dataset whose meta data is a list of Gdal files and extends the time bounds
+    /// of its result descriptor. Either all slices are appended or none.
+    async fn append_time_slices(
+        &self,
+        session: &S,
+        dataset: DatasetId,
+        slices: Validated<AppendTimeSlices>,
+    ) -> Result<()>;
+
+    /// Sets the auxiliary metadata of a dataset, e.g., its statistics, replacing any previous metadata.
+    async fn set_auxiliary_metadata(
+        &self,
+        session: &S,
+        dataset: DatasetId,
+        metadata: AuxiliaryMetadata,
+    ) -> Result<()>;
+
     /// turn given `meta` data definition into the corresponding `StorageType` for the `DatasetStore`
     /// for use in the `add_dataset` method
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType;
@@ -1,4 +1,6 @@
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
 
 use crate::contexts::Session;
 use crate::error::Result;
@@ -9,6 +11,9 @@ use crate::{
 };
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use utoipa::ToSchema;
+use walkdir::WalkDir;
 
 identifier!(UploadId);
 identifier!(FileId);
@@ -37,24 +42,285 @@ impl Upload {
 
         Ok(self.id.root_path()?.join(file_name))
     }
+
+    /// the storage that is occupied by all files of the upload
+    pub fn byte_size(&self) -> u64 {
+        self.files.iter().map(|file| file.byte_size).sum()
+    }
+
+    /// Creates an upload of all files that were written to the directory of the upload,
+    /// e.g., the outputs of a workflow. Files in subdirectories are named by their relative path.
+    pub async fn from_directory(id: UploadId) -> Result<Self> {
+        let root = id.root_path()?;
+
+        let files = crate::util::spawn_blocking(move || {
+            let mut files = Vec::new();
+
+            for entry in WalkDir::new(&root).sort_by_file_name() {
+                let entry = entry.map_err(|error| error::Error::Io {
+                    source: error.into(),
+                })?;
+
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                let byte_size = entry
+                    .metadata()
+                    .map_err(|error| error::Error::Io {
+                        source: error.into(),
+                    })?
+                    .len();
+
+                files.push(FileUpload {
+                    id: FileId::new(),
+                    name: entry
+                        .path()
+                        .strip_prefix(&root)
+                        .expect("entries are inside of `root`")
+                        .to_string_lossy()
+                        .to_string(),
+                    byte_size,
+                });
+            }
+
+            Result::<Vec<FileUpload>>::Ok(files)
+        })
+        .await??;
+
+        Ok(Self { id, files })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct FileUpload {
     pub id: FileId,
     pub name: String,
+    #[serde(alias = "byteSize")]
     pub byte_size: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UploadListing {
     pub id: UploadId,
+    #[serde(alias = "numFiles")]
     pub num_files: usize,
 }
 
+/// The storage that is occupied by uploads, i.e., uploaded files and materialized workflow outputs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    /// bytes that are occupied by the uploads of the user
+    pub user_bytes: u64,
+    /// the maximum number of bytes of the user, unlimited if not set
+    pub user_quota_bytes: Option<u64>,
+    /// bytes that are occupied by the uploads of the user's tenant, if there are tenants
+    pub tenant_bytes: Option<u64>,
+    /// the maximum number of bytes of the tenant, unlimited if not set
+    pub tenant_quota_bytes: Option<u64>,
+}
+
+impl StorageUsage {
+    /// Checks that `requested_bytes` more bytes fit into the quotas of the user and the tenant
+    pub fn ensure_available(&self, requested_bytes: u64) -> Result<()> {
+        if let Some(quota_bytes) = self.user_quota_bytes {
+            ensure!(
+                self.user_bytes.saturating_add(requested_bytes) <= quota_bytes,
+                error::StorageQuotaExceeded {
+                    owner: "user",
+                    used_bytes: self.user_bytes,
+                    quota_bytes,
+                    requested_bytes,
+                }
+            );
+        }
+
+        if let (Some(used_bytes), Some(quota_bytes)) = (self.tenant_bytes, self.tenant_quota_bytes)
+        {
+            ensure!(
+                used_bytes.saturating_add(requested_bytes) <= quota_bytes,
+                error::StorageQuotaExceeded {
+                    owner: "tenant",
+                    used_bytes,
+                    quota_bytes,
+                    requested_bytes,
+                }
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Watches the files of an upload while they are written, e.g., by an export of a workflow,
+/// so that the writing can be aborted as soon as they exceed the storage quota.
+pub struct UploadQuotaWatcher {
+    root: PathBuf,
+    usage: StorageUsage,
+    exceeded: Mutex<Option<error::Error>>,
+}
+
+impl UploadQuotaWatcher {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(upload: UploadId, usage: StorageUsage) -> Result<Self> {
+        Ok(Self {
+            root: upload.root_path()?,
+            usage,
+            exceeded: Mutex::new(None),
+        })
+    }
+
+    /// Resolves as soon as the files of the upload exceed the storage quota.
+    pub async fn exceeded(&self) {
+        loop {
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+
+            let byte_size = match measure_byte_size(self.root.clone()).await {
+                Ok(byte_size) => byte_size,
+                Err(error) => {
+                    // the writer may still be creating or moving files,
+                    // so the size is measured again in the next interval and after the writer has finished
+                    log::debug!("Could not measure the size of an upload: {error}");
+                    continue;
+                }
+            };
+
+            if let Err(error) = self.usage.ensure_available(byte_size) {
+                *self.exceeded.lock().expect("lock should not be poisoned") = Some(error);
+                return;
+            }
+        }
+    }
+
+    /// Returns the quota error instead of the `result` of a writer that was aborted because of
+    /// the quota and deletes the files of the upload in this case.
+    ///
+    /// Since the writer may finish before the size was polled, the files of a successful writer are measured once more.
+    pub async fn check<T>(&self, result: Result<T>) -> Result<T> {
+        let mut exceeded = self
+            .exceeded
+            .lock()
+            .expect("lock should not be poisoned")
+            .take();
+
+        if exceeded.is_none() && result.is_ok() {
+            exceeded = measure_byte_size(self.root.clone())
+                .await
+                .and_then(|byte_size| self.usage.ensure_available(byte_size))
+                .err();
+        }
+
+        if let Some(error) = exceeded {
+            tokio::fs::remove_dir_all(&self.root)
+                .await
+                .context(error::Io)?;
+            return Err(error);
+        }
+
+        result
+    }
+}
+
+async fn measure_byte_size(root: PathBuf) -> Result<u64> {
+    crate::util::spawn_blocking(move || directory_byte_size(&root)).await?
+}
+
+fn directory_byte_size(root: &Path) -> Result<u64> {
+    let mut byte_size = 0;
+
+    for entry in WalkDir::new(root) {
+        let entry = entry.map_err(|error| error::Error::Io {
+            source: error.into(),
+        })?;
+
+        if entry.file_type().is_file() {
+            byte_size += entry
+                .metadata()
+                .map_err(|error| error::Error::Io {
+                    source: error.into(),
+                })?
+                .len();
+        }
+    }
+
+    Ok(byte_size)
+}
+
 #[async_trait]
 pub trait UploadDb<S: Session> {
     async fn get_upload(&self, session: &S, upload: UploadId) -> Result<Upload>;
 
+    /// Stores the upload.
+    /// Fails if the upload exceeds the storage quota of the session's user.
     async fn create_upload(&self, session: &S, upload: Upload) -> Result<()>;
+
+    async fn storage_usage(&self, session: &S) -> Result<StorageUsage>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_storage_quotas() {
+        let usage = StorageUsage {
+            user_bytes: 60,
+            user_quota_bytes: Some(100),
+            tenant_bytes: Some(500),
+            tenant_quota_bytes: Some(1000),
+        };
+
+        assert!(usage.ensure_available(40).is_ok());
+        assert!(matches!(
+            usage.ensure_available(41),
+            Err(error::Error::StorageQuotaExceeded { owner: "user", .. })
+        ));
+
+        let usage = StorageUsage {
+            user_quota_bytes: None,
+            ..usage
+        };
+
+        assert!(usage.ensure_available(500).is_ok());
+        assert!(matches!(
+            usage.ensure_available(501),
+            Err(error::Error::StorageQuotaExceeded {
+                owner: "tenant",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_quota_after_fast_writers() {
+        let usage = StorageUsage {
+            user_bytes: 0,
+            user_quota_bytes: Some(10),
+            tenant_bytes: None,
+            tenant_quota_bytes: None,
+        };
+
+        let upload = UploadId::new();
+        let root = upload.root_path().unwrap();
+        tokio::fs::create_dir_all(&root).await.unwrap();
+
+        let quota = UploadQuotaWatcher::new(upload, usage).unwrap();
+
+        let start = std::time::Instant::now();
+
+        // the export finishes long before the quota is polled
+        let written = tokio::select! {
+            written = tokio::fs::write(root.join("export.tiff"), [0_u8; 100]) => written.context(error::Io),
+            _ = quota.exceeded() => unreachable!("the export should finish first"),
+        };
+
+        assert!(matches!(
+            quota.check(written).await,
+            Err(error::Error::StorageQuotaExceeded { owner: "user", .. })
+        ));
+        assert!(start.elapsed() < UploadQuotaWatcher::POLL_INTERVAL);
+        assert!(!root.exists());
+    }
 }
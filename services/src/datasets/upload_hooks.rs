@@ -0,0 +1,331 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use crate::datasets::upload::{FileUpload, UploadId, UploadRootPath};
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+
+/// The outcome of checking an uploaded file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadCheck {
+    Accepted,
+    Rejected { reason: String },
+}
+
+/// A check that is run on each uploaded file before the upload can be used for creating datasets
+pub trait UploadHook: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Checks the uploaded file at `path`.
+    /// An error means that the check could not be performed, which rejects the file as well.
+    fn check(&self, path: &Path) -> Result<UploadCheck>;
+}
+
+/// What happens to an upload that contains a rejected file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UploadHookPolicy {
+    /// delete the upload
+    Reject,
+    /// move the upload to the quarantine directory for later inspection
+    Quarantine,
+}
+
+/// Invokes an external virus scanner, e.g., `clamscan`, with the path of the file as last argument.
+/// Following the convention of `clamscan`, the exit code 0 means the file is clean and 1 means it is infected.
+pub struct VirusScanHook {
+    command: Vec<String>,
+}
+
+impl VirusScanHook {
+    pub fn new(command: Vec<String>) -> Self {
+        Self { command }
+    }
+}
+
+impl UploadHook for VirusScanHook {
+    fn name(&self) -> &'static str {
+        "virus scan"
+    }
+
+    fn check(&self, path: &Path) -> Result<UploadCheck> {
+        let (program, args) = if let Some((program, args)) = self.command.split_first() {
+            (program, args)
+        } else {
+            return Ok(UploadCheck::Accepted);
+        };
+
+        let output = Command::new(program)
+            .args(args)
+            .arg(path)
+            .output()
+            .context(error::Io)?;
+
+        Ok(match output.status.code() {
+            Some(0) => UploadCheck::Accepted,
+            Some(1) => UploadCheck::Rejected {
+                reason: format!(
+                    "the file is infected: {}",
+                    String::from_utf8_lossy(&output.stdout).trim()
+                ),
+            },
+            code => UploadCheck::Rejected {
+                reason: format!(
+                    "the virus scanner failed with exit code {:?}: {}",
+                    code,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            },
+        })
+    }
+}
+
+/// Checks that the extension of a file is allowed and that the contents of files with a known extension match their type
+pub struct FileTypeHook {
+    /// lower case extensions without a dot, all extensions are allowed if empty
+    allowed_extensions: Vec<String>,
+}
+
+impl FileTypeHook {
+    pub fn new(allowed_extensions: Vec<String>) -> Self {
+        Self {
+            allowed_extensions: allowed_extensions
+                .into_iter()
+                .map(|extension| extension.trim_start_matches('.').to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+/// The expected content of a file type
+enum FileSignature {
+    /// the file starts with one of the given byte sequences
+    Magic(&'static [&'static [u8]]),
+    Text,
+}
+
+const TIFF: &[&[u8]] = &[b"II*\0", b"MM\0*", b"II+\0", b"MM\0+"];
+const SQLITE: &[&[u8]] = &[b"SQLite format 3\0"];
+const ZIP: &[&[u8]] = &[b"PK\x03\x04", b"PK\x05\x06"];
+const NETCDF: &[&[u8]] = &[b"CDF\x01", b"CDF\x02", b"CDF\x05", b"\x89HDF\r\n\x1a\n"];
+const HDF: &[&[u8]] = &[b"\x89HDF\r\n\x1a\n"];
+const SHAPE: &[&[u8]] = &[b"\0\0\x27\x0a"];
+
+fn file_signature(extension: &str) -> Option<FileSignature> {
+    Some(match extension {
+        "tif" | "tiff" => FileSignature::Magic(TIFF),
+        "gpkg" | "sqlite" => FileSignature::Magic(SQLITE),
+        "zip" => FileSignature::Magic(ZIP),
+        "nc" => FileSignature::Magic(NETCDF),
+        "h5" | "hdf5" => FileSignature::Magic(HDF),
+        "shp" | "shx" => FileSignature::Magic(SHAPE),
+        "json" | "geojson" | "csv" | "txt" | "xml" | "prj" | "cpg" | "vrt" => FileSignature::Text,
+        _ => return None,
+    })
+}
+
+impl UploadHook for FileTypeHook {
+    fn name(&self) -> &'static str {
+        "file type validation"
+    }
+
+    fn check(&self, path: &Path) -> Result<UploadCheck> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if !self.allowed_extensions.is_empty() && !self.allowed_extensions.contains(&extension) {
+            return Ok(UploadCheck::Rejected {
+                reason: format!("the file extension `{}` is not allowed", extension),
+            });
+        }
+
+        let signature = if let Some(signature) = file_signature(&extension) {
+            signature
+        } else {
+            return Ok(UploadCheck::Accepted);
+        };
+
+        let mut head = Vec::with_capacity(4096);
+        File::open(path)
+            .context(error::Io)?
+            .take(4096)
+            .read_to_end(&mut head)
+            .context(error::Io)?;
+
+        let matches = match signature {
+            FileSignature::Magic(magic) => magic.iter().any(|magic| head.starts_with(magic)),
+            // the head may end within a multi-byte character
+            FileSignature::Text => {
+                !head.contains(&0)
+                    && std::str::from_utf8(&head).map_or_else(|e| e.error_len().is_none(), |_| true)
+            }
+        };
+
+        Ok(if matches {
+            UploadCheck::Accepted
+        } else {
+            UploadCheck::Rejected {
+                reason: format!(
+                    "the content of the file does not match its extension `{}`",
+                    extension
+                ),
+            }
+        })
+    }
+}
+
+/// Creates the hooks that are enabled in the config
+pub fn upload_hooks(config: &config::UploadHooks) -> Vec<Box<dyn UploadHook>> {
+    let mut hooks: Vec<Box<dyn UploadHook>> = Vec::new();
+
+    if !config.virus_scan_command.is_empty() {
+        hooks.push(Box::new(VirusScanHook::new(
+            config.virus_scan_command.clone(),
+        )));
+    }
+
+    if config.validate_file_types || !config.allowed_extensions.is_empty() {
+        hooks.push(Box::new(FileTypeHook::new(
+            config.allowed_extensions.clone(),
+        )));
+    }
+
+    hooks
+}
+
+/// Runs the configured hooks on all files of an upload.
+/// If a file is rejected, the upload is deleted or quarantined according to the configured policy.
+pub async fn check_upload(upload_id: UploadId, files: &[FileUpload]) -> Result<()> {
+    let config = get_config_element::<config::UploadHooks>()?;
+    let hooks = upload_hooks(&config);
+
+    if hooks.is_empty() {
+        return Ok(());
+    }
+
+    let root = upload_id.root_path()?;
+    let file_names = files
+        .iter()
+        .map(|file| file.name.clone())
+        .collect::<Vec<_>>();
+
+    let rejection = crate::util::spawn_blocking(move || {
+        file_names.into_iter().find_map(|file_name| {
+            let path = root.join(&file_name);
+
+            hooks.iter().find_map(|hook| {
+                let reason = match hook.check(&path) {
+                    Ok(UploadCheck::Accepted) => return None,
+                    Ok(UploadCheck::Rejected { reason }) => reason,
+                    Err(error) => format!("the check could not be performed: {}", error),
+                };
+
+                Some((file_name.clone(), format!("{}: {}", hook.name(), reason)))
+            })
+        })
+    })
+    .await?;
+
+    let (file, reason) = if let Some(rejection) = rejection {
+        rejection
+    } else {
+        return Ok(());
+    };
+
+    let root = upload_id.root_path()?;
+    match config.policy {
+        UploadHookPolicy::Reject => {
+            tokio::fs::remove_dir_all(&root).await.context(error::Io)?;
+        }
+        UploadHookPolicy::Quarantine => {
+            tokio::fs::create_dir_all(&config.quarantine_path)
+                .await
+                .context(error::Io)?;
+            tokio::fs::rename(&root, config.quarantine_path.join(upload_id.to_string()))
+                .await
+                .context(error::Io)?;
+        }
+    }
+
+    log::warn!(
+        "Upload {} was rejected ({:?}) because of file {}: {}",
+        upload_id,
+        config.policy,
+        file,
+        reason
+    );
+
+    Err(error::Error::UploadRejected { file, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data;
+
+    #[test]
+    fn it_validates_file_types() {
+        let hook = FileTypeHook::new(vec![]);
+
+        assert_eq!(
+            hook.check(test_data!(
+                "raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF"
+            ))
+            .unwrap(),
+            UploadCheck::Accepted
+        );
+        assert_eq!(
+            hook.check(test_data!("vector/data/ne_10m_ports/ne_10m_ports.shp"))
+                .unwrap(),
+            UploadCheck::Accepted
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let fake_tiff = dir.path().join("fake.tif");
+        std::fs::write(&fake_tiff, "not a tiff").unwrap();
+
+        assert_eq!(
+            hook.check(&fake_tiff).unwrap(),
+            UploadCheck::Rejected {
+                reason: "the content of the file does not match its extension `tif`".to_string()
+            }
+        );
+
+        let hook = FileTypeHook::new(vec![".gpkg".to_string()]);
+
+        assert_eq!(
+            hook.check(&fake_tiff).unwrap(),
+            UploadCheck::Rejected {
+                reason: "the file extension `tif` is not allowed".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn it_interprets_virus_scanner_exit_codes() {
+        let path = test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF").to_path_buf();
+
+        assert_eq!(
+            VirusScanHook::new(vec!["true".to_string()])
+                .check(&path)
+                .unwrap(),
+            UploadCheck::Accepted
+        );
+        assert!(matches!(
+            VirusScanHook::new(vec!["false".to_string()])
+                .check(&path)
+                .unwrap(),
+            UploadCheck::Rejected { .. }
+        ));
+        assert!(VirusScanHook::new(vec!["/does/not/exist".to_string()])
+            .check(&path)
+            .is_err());
+    }
+}
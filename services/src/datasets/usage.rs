@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use geoengine_datatypes::primitives::DateTime;
+
+use crate::api::model::datatypes::DatasetId;
+use crate::error::Result;
+
+/// How often a dataset was accessed and when it was accessed last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatasetAccess {
+    pub count: u64,
+    pub last_access: DateTime,
+}
+
+/// Storage of the usage statistics of datasets.
+/// A dataset is accessed whenever a workflow that reads it is initialized, i.e., when its meta data is loaded.
+#[async_trait]
+pub trait DatasetUsageDb: Send + Sync {
+    /// Records an access of the dataset, which must only be called after its meta data was loaded successfully
+    async fn record_access(&self, dataset: DatasetId) -> Result<()>;
+
+    /// The accesses of all datasets that were accessed at least once
+    async fn dataset_accesses(&self) -> Result<HashMap<DatasetId, DatasetAccess>>;
+}
+
+/// Records an access of the dataset, but only logs failures, since the statistics must not fail queries
+pub async fn record_dataset_access<D: DatasetUsageDb + ?Sized>(db: &D, dataset: DatasetId) {
+    if let Err(error) = db.record_access(dataset).await {
+        log::warn!("Could not record the access of the dataset {dataset}: {error}");
+    }
+}
+
+/// The `limit` most accessed datasets, in descending order of their access counts
+pub fn heavy_hitters(
+    accesses: impl IntoIterator<Item = (DatasetId, DatasetAccess)>,
+    limit: usize,
+) -> Vec<(DatasetId, DatasetAccess)> {
+    let mut accesses: Vec<(DatasetId, DatasetAccess)> = accesses.into_iter().collect();
+
+    accesses.sort_by(|(_, a), (_, b)| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.last_access.cmp(&a.last_access))
+    });
+    accesses.truncate(limit);
+
+    accesses
+}
+
+/// Counts the accesses of datasets in memory, for the dataset dbs that are not persisted either
+#[derive(Debug, Default)]
+pub struct DatasetUsageTracker {
+    accesses: Mutex<HashMap<DatasetId, DatasetAccess>>,
+}
+
+impl DatasetUsageTracker {
+    pub fn record_access(&self, dataset: DatasetId) {
+        self.record_access_at(dataset, DateTime::now());
+    }
+
+    fn record_access_at(&self, dataset: DatasetId, time: DateTime) {
+        self.lock()
+            .entry(dataset)
+            .and_modify(|access| {
+                access.count += 1;
+                access.last_access = access.last_access.max(time);
+            })
+            .or_insert(DatasetAccess {
+                count: 1,
+                last_access: time,
+            });
+    }
+
+    pub fn accesses(&self) -> HashMap<DatasetId, DatasetAccess> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<DatasetId, DatasetAccess>> {
+        // do not fail queries because of the statistics
+        match self.accesses.lock() {
+            Ok(accesses) => accesses,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Identifier;
+
+    #[test]
+    fn it_counts_accesses() {
+        let tracker = DatasetUsageTracker::default();
+
+        let rarely_used = DatasetId::new();
+        let often_used = DatasetId::new();
+        let unused = DatasetId::new();
+
+        tracker.record_access_at(rarely_used, DateTime::new_utc(2022, 1, 1, 0, 0, 0));
+        tracker.record_access_at(often_used, DateTime::new_utc(2022, 1, 1, 0, 0, 0));
+        tracker.record_access_at(often_used, DateTime::new_utc(2022, 2, 1, 0, 0, 0));
+
+        let accesses = tracker.accesses();
+
+        assert_eq!(
+            accesses.get(&often_used),
+            Some(&DatasetAccess {
+                count: 2,
+                last_access: DateTime::new_utc(2022, 2, 1, 0, 0, 0),
+            })
+        );
+        assert_eq!(accesses.get(&unused), None);
+
+        let heavy_hitters_of_two: Vec<DatasetId> = heavy_hitters(accesses.clone(), 2)
+            .into_iter()
+            .map(|(dataset, _)| dataset)
+            .collect();
+        assert_eq!(heavy_hitters_of_two, vec![often_used, rarely_used]);
+
+        assert_eq!(heavy_hitters(accesses, 1).len(), 1);
+    }
+}
@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use gdal::vector::LayerAccess;
+use gdal::{DatasetOptions, GdalOpenFlags};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, RasterQueryRectangle, SpatialPartition2D, SpatialResolution, TimeInterval,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use geoengine_operators::engine::{MetaData, RasterResultDescriptor, VectorResultDescriptor};
+use geoengine_operators::source::{GdalDatasetParameters, GdalLoadingInfo, OgrSourceDataset};
+use geoengine_operators::util::gdal::gdal_open_dataset_ex;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::api::model::datatypes::DatasetId;
+use crate::error::{self, Result};
+use crate::tasks::TaskStatusInfo;
+
+/// The result of checking the integrity of a dataset, i.e., whether its files can be read and match its meta data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetValidationReport {
+    pub dataset: DatasetId,
+    pub valid: bool,
+    /// the number of distinct files that were checked
+    pub checked_files: usize,
+    pub issues: Vec<DatasetValidationIssue>,
+}
+
+impl TaskStatusInfo for DatasetValidationReport {}
+
+impl DatasetValidationReport {
+    pub fn new(
+        dataset: DatasetId,
+        checked_files: usize,
+        issues: Vec<DatasetValidationIssue>,
+    ) -> Self {
+        Self {
+            dataset,
+            valid: issues.is_empty(),
+            checked_files,
+            issues,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum DatasetValidationIssue {
+    /// The time slices or files of the dataset cannot be determined from its meta data
+    InvalidMetaData {
+        error: String,
+    },
+    MissingFile {
+        path: PathBuf,
+    },
+    UnreadableFile {
+        path: PathBuf,
+        error: String,
+    },
+    MissingLayer {
+        path: PathBuf,
+        layer: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SpatialReferenceMismatch {
+        path: PathBuf,
+        expected: SpatialReferenceOption,
+        found: SpatialReferenceOption,
+    },
+    #[serde(rename_all = "camelCase")]
+    NoDataMismatch {
+        path: PathBuf,
+        expected: Option<f64>,
+        found: Option<f64>,
+    },
+    /// A part of the dataset's time extent that is not covered by any time slice
+    TimeGap {
+        time: TimeInterval,
+    },
+    /// Only the first `limit` time slices were checked
+    TimeSliceLimitExceeded {
+        limit: usize,
+    },
+}
+
+/// Checks the time slices and files of a raster dataset.
+/// Returns the number of checked files and the issues that were found.
+pub async fn validate_raster_meta_data(
+    meta_data: &dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
+    max_time_slices: usize,
+) -> Result<(usize, Vec<DatasetValidationIssue>)> {
+    let result_descriptor = meta_data
+        .result_descriptor()
+        .await
+        .context(error::Operator)?;
+
+    let query = RasterQueryRectangle {
+        spatial_bounds: result_descriptor.bbox.unwrap_or_else(|| {
+            SpatialPartition2D::new_unchecked(
+                (f64::MIN, f64::MAX).into(),
+                (f64::MAX, f64::MIN).into(),
+            )
+        }),
+        time_interval: result_descriptor.time.unwrap_or_default(),
+        spatial_resolution: result_descriptor
+            .resolution
+            .unwrap_or_else(SpatialResolution::one),
+    };
+
+    let slices = match meta_data.loading_info(query).await {
+        Ok(loading_info) => loading_info
+            .info
+            .take(max_time_slices + 1)
+            .collect::<std::result::Result<Vec<_>, _>>(),
+        Err(error) => Err(error),
+    };
+    let mut slices = match slices {
+        Ok(slices) => slices,
+        Err(error) => {
+            return Ok((
+                0,
+                vec![DatasetValidationIssue::InvalidMetaData {
+                    error: error.to_string(),
+                }],
+            ))
+        }
+    };
+
+    let mut issues = Vec::new();
+
+    let limit_exceeded = slices.len() > max_time_slices;
+    if limit_exceeded {
+        slices.truncate(max_time_slices);
+        issues.push(DatasetValidationIssue::TimeSliceLimitExceeded {
+            limit: max_time_slices,
+        });
+    }
+
+    let slice_times = slices.iter().map(|slice| slice.time).collect::<Vec<_>>();
+    issues.extend(
+        time_gaps(&slice_times, result_descriptor.time, !limit_exceeded)
+            .into_iter()
+            .map(|time| DatasetValidationIssue::TimeGap { time }),
+    );
+
+    // slices of regular time series may share files
+    let mut paths = HashSet::new();
+    let files = slices
+        .into_iter()
+        .filter_map(|slice| slice.params)
+        .filter(|params| paths.insert(params.file_path.clone()))
+        .collect::<Vec<_>>();
+    let checked_files = files.len();
+
+    let spatial_reference = result_descriptor.spatial_reference;
+    issues.extend(
+        crate::util::spawn_blocking(move || {
+            files
+                .iter()
+                .flat_map(|params| check_raster_file(params, spatial_reference))
+                .collect::<Vec<_>>()
+        })
+        .await?,
+    );
+
+    Ok((checked_files, issues))
+}
+
+/// Checks the file and layer of a vector dataset.
+/// Returns the number of checked files and the issues that were found.
+pub async fn validate_vector_meta_data(
+    meta_data: &dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+) -> Result<(usize, Vec<DatasetValidationIssue>)> {
+    let result_descriptor = meta_data
+        .result_descriptor()
+        .await
+        .context(error::Operator)?;
+
+    let query = VectorQueryRectangle {
+        spatial_bounds: result_descriptor.bbox.unwrap_or_else(|| {
+            BoundingBox2D::new_unchecked((f64::MIN, f64::MIN).into(), (f64::MAX, f64::MAX).into())
+        }),
+        time_interval: result_descriptor.time.unwrap_or_default(),
+        spatial_resolution: SpatialResolution::one(),
+    };
+
+    let dataset = match meta_data.loading_info(query).await {
+        Ok(dataset) => dataset,
+        Err(error) => {
+            return Ok((
+                0,
+                vec![DatasetValidationIssue::InvalidMetaData {
+                    error: error.to_string(),
+                }],
+            ))
+        }
+    };
+
+    let spatial_reference = result_descriptor.spatial_reference;
+    let issues =
+        crate::util::spawn_blocking(move || check_vector_file(&dataset, spatial_reference)).await?;
+
+    Ok((1, issues))
+}
+
+/// Computes the parts of the `extent` that are not covered by the sorted `slices`.
+/// The end of the extent is only considered if all slices are known.
+fn time_gaps(
+    slices: &[TimeInterval],
+    extent: Option<TimeInterval>,
+    all_slices: bool,
+) -> Vec<TimeInterval> {
+    let mut gaps = Vec::new();
+
+    let (first, last) = if let (Some(first), Some(last)) = (slices.first(), slices.last()) {
+        (first, last)
+    } else {
+        if let Some(extent) = extent {
+            gaps.push(extent);
+        }
+        return gaps;
+    };
+
+    if let Some(extent) = extent {
+        if extent.start() < first.start() {
+            gaps.push(TimeInterval::new_unchecked(extent.start(), first.start()));
+        }
+    }
+
+    gaps.extend(slices.windows(2).filter_map(|pair| {
+        (pair[0].end() < pair[1].start())
+            .then(|| TimeInterval::new_unchecked(pair[0].end(), pair[1].start()))
+    }));
+
+    if let Some(extent) = extent {
+        if all_slices && last.end() < extent.end() {
+            gaps.push(TimeInterval::new_unchecked(last.end(), extent.end()));
+        }
+    }
+
+    gaps
+}
+
+/// Paths of GDAL's virtual file systems, e.g., `/vsicurl/`, cannot be checked for existence
+fn is_local_file(path: &Path) -> bool {
+    !path.starts_with("/vsi")
+}
+
+fn check_raster_file(
+    params: &GdalDatasetParameters,
+    spatial_reference: SpatialReferenceOption,
+) -> Vec<DatasetValidationIssue> {
+    let path = &params.file_path;
+
+    if is_local_file(path) && !path.exists() {
+        return vec![DatasetValidationIssue::MissingFile { path: path.clone() }];
+    }
+
+    let unreadable = |error: String| {
+        vec![DatasetValidationIssue::UnreadableFile {
+            path: path.clone(),
+            error,
+        }]
+    };
+
+    let dataset = match gdal_open_dataset_ex(
+        path,
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_RASTER,
+            ..DatasetOptions::default()
+        },
+    ) {
+        Ok(dataset) => dataset,
+        Err(error) => return unreadable(error.to_string()),
+    };
+
+    let rasterband = match dataset.rasterband(params.rasterband_channel as isize) {
+        Ok(rasterband) => rasterband,
+        Err(error) => return unreadable(error.to_string()),
+    };
+
+    let mut issues = Vec::new();
+
+    if let SpatialReferenceOption::SpatialReference(_) = spatial_reference {
+        let found = dataset
+            .spatial_ref()
+            .ok()
+            .and_then(|spatial_ref| SpatialReference::try_from(spatial_ref).ok());
+
+        if SpatialReferenceOption::from(found) != spatial_reference {
+            issues.push(DatasetValidationIssue::SpatialReferenceMismatch {
+                path: path.clone(),
+                expected: spatial_reference,
+                found: found.into(),
+            });
+        }
+    }
+
+    let found_no_data = rasterband.no_data_value();
+    let no_data_matches = match (params.no_data_value, found_no_data) {
+        (Some(expected), Some(found)) => expected.total_cmp(&found).is_eq(),
+        (None, None) => true,
+        _ => false,
+    };
+    if !no_data_matches {
+        issues.push(DatasetValidationIssue::NoDataMismatch {
+            path: path.clone(),
+            expected: params.no_data_value,
+            found: found_no_data,
+        });
+    }
+
+    issues
+}
+
+fn check_vector_file(
+    dataset_information: &OgrSourceDataset,
+    spatial_reference: SpatialReferenceOption,
+) -> Vec<DatasetValidationIssue> {
+    let path = &dataset_information.file_name;
+
+    if is_local_file(path) && !path.exists() {
+        return vec![DatasetValidationIssue::MissingFile { path: path.clone() }];
+    }
+
+    let dataset = match gdal_open_dataset_ex(
+        path,
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
+            ..DatasetOptions::default()
+        },
+    ) {
+        Ok(dataset) => dataset,
+        Err(error) => {
+            return vec![DatasetValidationIssue::UnreadableFile {
+                path: path.clone(),
+                error: error.to_string(),
+            }]
+        }
+    };
+
+    // layers of SQL queries are not part of the file
+    if dataset_information.sql_query.is_some() {
+        return Vec::new();
+    }
+
+    let layer = if let Ok(layer) = dataset.layer_by_name(&dataset_information.layer_name) {
+        layer
+    } else {
+        return vec![DatasetValidationIssue::MissingLayer {
+            path: path.clone(),
+            layer: dataset_information.layer_name.clone(),
+        }];
+    };
+
+    if let SpatialReferenceOption::SpatialReference(_) = spatial_reference {
+        let found = layer
+            .defn()
+            .geom_fields()
+            .next()
+            .and_then(|field| field.spatial_ref().ok())
+            .and_then(|spatial_ref| SpatialReference::try_from(spatial_ref).ok());
+
+        if SpatialReferenceOption::from(found) != spatial_reference {
+            return vec![DatasetValidationIssue::SpatialReferenceMismatch {
+                path: path.clone(),
+                expected: spatial_reference,
+                found: found.into(),
+            }];
+        }
+    }
+
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_operators::util::gdal::create_ndvi_meta_data;
+
+    #[test]
+    fn it_finds_time_gaps() {
+        let interval = TimeInterval::new_unchecked::<i64, i64>;
+
+        let slices = [interval(10, 20), interval(20, 30), interval(40, 50)];
+
+        assert_eq!(
+            time_gaps(&slices, Some(interval(0, 60)), true),
+            vec![interval(0, 10), interval(30, 40), interval(50, 60)]
+        );
+        assert_eq!(
+            time_gaps(&slices, Some(interval(0, 60)), false),
+            vec![interval(0, 10), interval(30, 40)]
+        );
+        assert_eq!(time_gaps(&slices, None, true), vec![interval(30, 40)]);
+        assert_eq!(
+            time_gaps(&[], Some(interval(0, 60)), true),
+            vec![interval(0, 60)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_validates_raster_files() {
+        let (checked_files, issues) = validate_raster_meta_data(&create_ndvi_meta_data(), 100)
+            .await
+            .unwrap();
+
+        assert_eq!(checked_files, 6);
+        assert_eq!(issues, vec![]);
+
+        let mut meta_data = create_ndvi_meta_data();
+        meta_data.params.no_data_value = Some(42.);
+
+        let (_, issues) = validate_raster_meta_data(&meta_data, 2).await.unwrap();
+
+        assert_eq!(issues.len(), 3);
+        assert_eq!(
+            issues[0],
+            DatasetValidationIssue::TimeSliceLimitExceeded { limit: 2 }
+        );
+        assert!(issues[1..]
+            .iter()
+            .all(|issue| matches!(issue, DatasetValidationIssue::NoDataMismatch { .. })));
+    }
+}
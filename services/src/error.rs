@@ -85,6 +85,12 @@ pub enum Error {
     InvalidSession,
     #[snafu(display("Invalid admin token"))]
     InvalidAdminToken,
+    #[snafu(display("Invalid tenant name: {}", reason))]
+    InvalidTenantName {
+        reason: String,
+    },
+    UnknownTenantId,
+    UnknownUserId,
     #[snafu(display("Header with authorization token not provided."))]
     MissingAuthorizationHeader,
     #[snafu(display("Authentication scheme must be Bearer."))]
@@ -120,6 +126,71 @@ pub enum Error {
 
     NoWorkflowForGivenId,
 
+    #[snafu(display(
+        "Workflow alias `{}` is invalid. Only ASCII alphanumerics, `-`, `_` and `.` are allowed.",
+        alias
+    ))]
+    InvalidWorkflowAlias {
+        alias: String,
+    },
+    #[snafu(display("There is no workflow with alias `{}`", alias))]
+    UnknownWorkflowAlias {
+        alias: String,
+    },
+
+    #[snafu(display(
+        "Style preset name `{}` is invalid. Only ASCII alphanumerics, `-`, `_` and `.` are allowed.",
+        name
+    ))]
+    InvalidStylePresetName {
+        name: String,
+    },
+    #[snafu(display("Workflow {} has no style preset `{}`", workflow, name))]
+    UnknownStylePreset {
+        workflow: WorkflowId,
+        name: String,
+    },
+    #[snafu(display(
+        "Style preset `{}` cannot be used for a {} workflow",
+        name,
+        workflow_type
+    ))]
+    StylePresetMismatch {
+        name: String,
+        workflow_type: String,
+    },
+
+    #[snafu(display("Invalid workflow template: {}", reason))]
+    InvalidWorkflowTemplate {
+        reason: String,
+    },
+    #[snafu(display(
+        "Invalid value for workflow template parameter `{}`: {}",
+        parameter,
+        reason
+    ))]
+    InvalidWorkflowTemplateParameterValue {
+        parameter: String,
+        reason: String,
+    },
+    #[snafu(display("The instantiated workflow template is invalid: {}", reason))]
+    InvalidWorkflowTemplateInstance {
+        reason: String,
+    },
+    NoWorkflowTemplateForGivenId,
+
+    #[snafu(display("Invalid workflow substitution: {}", reason))]
+    InvalidWorkflowSubstitution {
+        reason: String,
+    },
+
+    #[snafu(display("Invalid workflow subscription: {}", reason))]
+    InvalidWorkflowSubscription {
+        reason: String,
+    },
+    #[snafu(display("Subscriptions require a raster workflow with a known time extent"))]
+    SubscriptionWithoutTimeExtent,
+
     #[cfg(feature = "postgres")]
     TokioPostgres {
         source: bb8_postgres::tokio_postgres::Error,
@@ -159,6 +230,11 @@ pub enum Error {
         dataset: DatasetId,
     },
 
+    #[snafu(display("Permission denied for layer provider with id {}", provider))]
+    LayerProviderPermissionDenied {
+        provider: DataProviderId,
+    },
+
     #[snafu(display("Updating permission ({}, {:?}, {}) denied", role, dataset, permission))]
     UpateDatasetPermission {
         role: String,
@@ -187,13 +263,42 @@ pub enum Error {
 
     UploadFieldMissingFileName,
     UnknownUploadId,
+    UnknownUploadFile,
     PathIsNotAFile,
     Multipart {
         source: actix_multipart::MultipartError,
     },
     InvalidUploadFileName,
+    #[snafu(display("The upload was rejected because of file {}: {}", file, reason))]
+    UploadRejected {
+        file: String,
+        reason: String,
+    },
+    #[snafu(display(
+        "The storage quota of the {} is exceeded: {} of {} bytes are used and {} more bytes were requested",
+        owner,
+        used_bytes,
+        quota_bytes,
+        requested_bytes
+    ))]
+    StorageQuotaExceeded {
+        owner: &'static str,
+        used_bytes: u64,
+        quota_bytes: u64,
+        requested_bytes: u64,
+    },
     InvalidDatasetName,
     DatasetHasNoAutoImportableLayer,
+    #[snafu(display("Invalid time slices: {}", reason))]
+    InvalidTimeSlices {
+        reason: String,
+    },
+    #[snafu(display("Time slices can only be appended to datasets with a list of Gdal files"))]
+    DatasetNotAppendable,
+    #[snafu(display("Invalid bulk dataset registration: {}", reason))]
+    InvalidBulkDatasetRegistration {
+        reason: String,
+    },
     #[snafu(display("GdalError: {}", source))]
     Gdal {
         source: gdal::errors::GdalError,
@@ -221,6 +326,60 @@ pub enum Error {
     RasterDataTypeNotSupportByGdal,
 
     MissingSpatialReference,
+    MissingSpatialResolution,
+
+    #[snafu(display("GeoParquet export requires a workflow with geometries"))]
+    GeoParquetRequiresGeometries,
+
+    #[snafu(display("Feature export requires a workflow with geometries"))]
+    FeatureExportRequiresGeometries,
+
+    #[snafu(display("Table output requires a workflow that produces data without geometries"))]
+    TableRequiresDataCollection,
+
+    #[snafu(display("Query estimates are only available for raster workflows"))]
+    QueryEstimateRequiresRasterWorkflow,
+
+    #[snafu(display("Rendering a vector workflow requires a workflow with geometries"))]
+    VectorRenderingRequiresGeometries,
+
+    #[snafu(display("The symbology does not match the geometry type of the workflow"))]
+    SymbologyDoesNotMatchGeometryType,
+
+    #[snafu(display("Invalid plot for the report: {}", reason))]
+    ReportPlotInvalid {
+        reason: String,
+    },
+
+    #[snafu(display("Could not cut the tile from its WMS meta-tile: {}", reason))]
+    WmsMetaTiling {
+        reason: String,
+    },
+
+    #[snafu(display(
+        "Plot workflows of reports must output images, but the output is {}",
+        output_format
+    ))]
+    ReportPlotMustBeAnImage {
+        output_format: String,
+    },
+
+    #[snafu(display(
+        "The map of a report must be between 1 and {} pixels wide and high, but it is {}x{}",
+        max_size,
+        width,
+        height
+    ))]
+    ReportMapSizeInvalid {
+        width: u32,
+        height: u32,
+        max_size: u32,
+    },
+
+    #[snafu(display("Encoding the report failed: {}", reason))]
+    ReportEncoding {
+        reason: String,
+    },
 
     WcsVersionNotSupported,
     WcsGridOriginMustEqualBoundingboxUpperLeft,
@@ -237,6 +396,23 @@ pub enum Error {
     },
     InvalidDataId,
 
+    #[snafu(display(
+        "ERDDAP dataset `{}` has no dimension or variable `{}`",
+        dataset,
+        dimension
+    ))]
+    ErddapMissingDimension {
+        dataset: String,
+        dimension: String,
+    },
+    #[snafu(display("Invalid ERDDAP metadata: {}", reason))]
+    ErddapInvalidMetadata {
+        reason: String,
+    },
+
+    #[snafu(display("The Overpass API rejected the request because of its rate limit"))]
+    OverpassRateLimitExceeded,
+
     #[cfg(feature = "nature40")]
     Nature40UnknownRasterDbname,
     #[cfg(feature = "nature40")]
@@ -319,6 +495,19 @@ pub enum Error {
     InvalidAPIToken {
         message: String,
     },
+
+    #[snafu(display(
+        "There is no key for encrypting secrets. Set `secrets.key` to a base64 encoded 256 bit key"
+    ))]
+    MissingSecretKey,
+    #[snafu(display("The configured secrets key must be a base64 encoded 256 bit key"))]
+    InvalidSecretKey,
+    SecretEncryption,
+    SecretDecryption,
+    #[snafu(display("There is no secret at `{}` in the provider definition", pointer))]
+    UnknownSecret {
+        pointer: String,
+    },
     MissingNFDIMetaData,
 
     #[cfg(feature = "ebv")]
@@ -415,6 +604,26 @@ pub enum Error {
         query_srs: SpatialReference,
         query_bbox: crate::api::model::datatypes::BoundingBox2D,
     },
+
+    #[snafu(display("API request failed with status {}: {}: {}", status, error, message))]
+    ApiRequestFailed {
+        status: u16,
+        error: String,
+        message: String,
+    },
+
+    #[snafu(display("The workflow produced no tile at position {:?}", tile_position))]
+    RasterTileNotFound {
+        tile_position: [isize; 2],
+    },
+
+    #[snafu(display(
+        "Refused to start because {} definitions cannot be loaded, see the preflight report",
+        num_critical
+    ))]
+    PreflightFailed {
+        num_critical: usize,
+    },
 }
 
 impl actix_web::error::ResponseError for Error {
@@ -436,6 +645,10 @@ impl actix_web::error::ResponseError for Error {
         match self {
             Error::Authorization { source: _ } => StatusCode::UNAUTHORIZED,
             Error::Duplicate { reason: _ } => StatusCode::CONFLICT,
+            Error::StorageQuotaExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::Operator {
+                source: geoengine_operators::error::Error::QueryPanicked { .. },
+            } => StatusCode::INTERNAL_SERVER_ERROR,
             _ => StatusCode::BAD_REQUEST,
         }
     }
@@ -121,7 +121,7 @@ where
 /// Otherwise an erorr is returned.
 async fn with_netcdfcf_provider<C: Context, T, F>(
     ctx: &C,
-    _session: &C::Session,
+    session: &C::Session,
     f: F,
 ) -> Result<T, NetCdfCf4DProviderError>
 where
@@ -130,12 +130,12 @@ where
 {
     let ebv_provider: Result<Box<dyn DataProvider>> = ctx
         .layer_provider_db_ref()
-        .layer_provider(EBV_PROVIDER_ID)
+        .layer_provider(session, EBV_PROVIDER_ID)
         .await;
 
     let netcdf_provider = ctx
         .layer_provider_db_ref()
-        .layer_provider(NETCDF_CF_PROVIDER_ID)
+        .layer_provider(session, NETCDF_CF_PROVIDER_ID)
         .await;
 
     match (ebv_provider, netcdf_provider) {
@@ -625,11 +625,14 @@ mod tests {
         let overview_folder = tempfile::tempdir().unwrap();
 
         ctx.layer_provider_db_ref()
-            .add_layer_provider(Box::new(NetCdfCfDataProviderDefinition {
-                name: "test".to_string(),
-                path: test_data!("netcdf4d").to_path_buf(),
-                overviews: overview_folder.path().to_path_buf(),
-            }))
+            .add_layer_provider(
+                &*ctx.default_session_ref().await,
+                Box::new(NetCdfCfDataProviderDefinition {
+                    name: "test".to_string(),
+                    path: test_data!("netcdf4d").to_path_buf(),
+                    overviews: overview_folder.path().to_path_buf(),
+                }),
+            )
             .await
             .unwrap();
 
@@ -719,11 +722,14 @@ mod tests {
         let overview_folder = tempfile::tempdir().unwrap();
 
         ctx.layer_provider_db_ref()
-            .add_layer_provider(Box::new(NetCdfCfDataProviderDefinition {
-                name: "test".to_string(),
-                path: test_data!("netcdf4d").to_path_buf(),
-                overviews: overview_folder.path().to_path_buf(),
-            }))
+            .add_layer_provider(
+                &*ctx.default_session_ref().await,
+                Box::new(NetCdfCfDataProviderDefinition {
+                    name: "test".to_string(),
+                    path: test_data!("netcdf4d").to_path_buf(),
+                    overviews: overview_folder.path().to_path_buf(),
+                }),
+            )
             .await
             .unwrap();
 
@@ -775,11 +781,14 @@ mod tests {
         let overview_folder = tempfile::tempdir().unwrap();
 
         ctx.layer_provider_db_ref()
-            .add_layer_provider(Box::new(NetCdfCfDataProviderDefinition {
-                name: "test".to_string(),
-                path: test_data!("netcdf4d").to_path_buf(),
-                overviews: overview_folder.path().to_path_buf(),
-            }))
+            .add_layer_provider(
+                &*ctx.default_session_ref().await,
+                Box::new(NetCdfCfDataProviderDefinition {
+                    name: "test".to_string(),
+                    path: test_data!("netcdf4d").to_path_buf(),
+                    overviews: overview_folder.path().to_path_buf(),
+                }),
+            )
             .await
             .unwrap();
 
@@ -2,6 +2,7 @@ use crate::api::model::datatypes::{DataId, ExternalDataId, LayerId};
 use crate::contexts::Context;
 use crate::error::Result;
 use crate::layers::storage::LayerProviderDb;
+use crate::util::apidoc::OpenApiServerInfo;
 use crate::util::config::{get_config_element, GFBio};
 use actix_web::{web, FromRequest, Responder};
 use futures::stream::FuturesUnordered;
@@ -15,6 +16,8 @@ use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 use crate::datasets::external::gfbio::{GfbioDataProvider, GFBIO_PROVIDER_ID};
 use crate::datasets::external::pangaea::PANGAEA_PROVIDER_ID;
@@ -23,6 +26,56 @@ use geoengine_operators::util::input::StringOrNumberRange;
 
 identifier!(BasketId);
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_basket_handler),
+    components(schemas(BasketId)),
+    modifiers(&SecurityAddon, &ApiDocInfo, &OpenApiServerInfo)
+)]
+pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.as_mut().unwrap();
+        components.add_security_scheme(
+            "session_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("UUID")
+                    .description(Some("A valid session token of the Geo Engine API."))
+                    .build(),
+            ),
+        );
+    }
+}
+
+struct ApiDocInfo;
+
+impl Modify for ApiDocInfo {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.info.title = "Geo Engine GFBio API".to_string();
+
+        openapi.info.contact = Some(
+            utoipa::openapi::ContactBuilder::new()
+                .name(Some("Geo Engine Developers"))
+                .email(Some("dev@geoengine.de"))
+                .build(),
+        );
+
+        openapi.info.license = Some(
+            utoipa::openapi::LicenseBuilder::new()
+                .name("Apache 2.0 (pro features excluded)")
+                .url(Some(
+                    "https://github.com/geo-engine/geoengine/blob/master/LICENSE",
+                ))
+                .build(),
+        );
+    }
+}
+
 pub(crate) fn init_gfbio_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
@@ -31,6 +84,32 @@ where
     cfg.service(web::resource("/gfbio/basket/{id}").route(web::get().to(get_basket_handler::<C>)));
 }
 
+/// Resolves the entries of a GFBio basket to the data and source operators that load them
+#[utoipa::path(
+    tag = "GFBio",
+    get,
+    path = "/gfbio/basket/{id}",
+    responses(
+        (status = 200, description = "The entries of the basket with their loading details or the reason why they are unavailable", body = Object,
+            example = json!({
+                "basket_id": "b6ed8e39-1a34-4ae5-a5a1-ac5bbb22ba48",
+                "content": [{
+                    "title": "Example Title",
+                    "status": "unavailable"
+                }],
+                "user_id": null,
+                "created": "2022-01-01T00:00:00.000Z",
+                "updated": "2022-01-01T00:00:00.000Z"
+            })
+        )
+    ),
+    params(
+        ("id" = BasketId, description = "Basket id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
 async fn get_basket_handler<C: Context>(
     id: web::Path<BasketId>,
     session: C::Session,
@@ -40,7 +119,7 @@ async fn get_basket_handler<C: Context>(
     let config = get_config_element::<GFBio>()?;
     let abcd_provider = ctx
         .layer_provider_db()
-        .layer_provider(GFBIO_PROVIDER_ID)
+        .layer_provider(&session, GFBIO_PROVIDER_ID)
         .await
         .ok();
 
@@ -307,6 +386,7 @@ impl Basket {
                 source_operator: "OgrSource".to_string(),
                 result_descriptor: TypedResultDescriptor::Vector(rd),
                 attribute_filters: filter,
+                schema_merging: None,
             }),
         };
         BasketEntry { title, status }
@@ -497,6 +577,7 @@ mod tests {
                     bbox: None,
                 }),
                 attribute_filters: None,
+                schema_merging: None,
             }),
         };
 
@@ -544,6 +625,7 @@ mod tests {
                     ranges: vec![StringOrNumberRange::Int(0..=1)],
                     keep_nulls: false,
                 }]),
+                schema_merging: None,
             }),
         };
 
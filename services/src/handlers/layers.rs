@@ -1,13 +1,29 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use crate::api::model::datatypes::{DataProviderId, LayerId};
-use actix_web::{web, FromRequest, Responder};
+use crate::contexts::AdminSession;
+use crate::datasets::storage::{DatasetLayerCollectionProvider, SessionDatasetLayers};
+use actix_web::{web, FromRequest, HttpResponse, Responder};
+use futures::future::join_all;
+use serde::Deserialize;
+use utoipa::ToSchema;
 
 use crate::error::Result;
 
+use crate::layers::external::{
+    check_root_collection, DataProviderDefinition, ProviderCapabilities, ProviderHealth,
+};
 use crate::layers::layer::{
     CollectionItem, LayerCollection, LayerCollectionListing, ProviderLayerCollectionId,
 };
 use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
+use crate::layers::search::{
+    rank_search_results, LayerSearchOptions, LayerSearchQuery, LayerSearchResult,
+};
 use crate::layers::storage::{LayerProviderDb, LayerProviderListingOptions};
+use crate::util::config::{self, get_config_element};
+use crate::util::secrets::{redact_secrets, replace_secrets};
 use crate::util::user_input::UserInput;
 use crate::{contexts::Context, layers::layer::LayerCollectionListOptions};
 
@@ -17,6 +33,9 @@ pub const ROOT_PROVIDER_ID: DataProviderId =
 pub const ROOT_COLLECTION_ID: DataProviderId =
     DataProviderId::from_u128(0xf242_4474_ef24_4c18_ab84_6859_2e12_ce48);
 
+/// The number of external providers that are loaded at once for a search
+const PROVIDER_PAGE_SIZE: u32 = 100;
+
 pub(crate) fn init_layer_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
@@ -26,10 +45,31 @@ where
         web::resource("/layers/collections")
             .route(web::get().to(list_root_collections_handler::<C>)),
     )
+    .service(web::resource("/layers/search").route(web::get().to(search_layers_handler::<C>)))
     .service(
         web::resource(r#"/layers/collections/{provider}/{collection:.+}"#)
             .route(web::get().to(list_collection_handler::<C>)),
     )
+    .service(
+        web::resource("/layers/providers/{provider}/capabilities")
+            .route(web::get().to(provider_capabilities_handler::<C>)),
+    )
+    .service(
+        web::resource("/layers/providers/{provider}/health")
+            .route(web::get().to(provider_health_handler::<C>)),
+    )
+    .service(
+        web::resource("/layers/providers/{provider}/definition")
+            .route(web::get().to(provider_definition_handler::<C>)),
+    )
+    .service(
+        web::resource("/layers/providers/{provider}/secrets")
+            .route(web::put().to(update_provider_secrets_handler::<C>)),
+    )
+    .service(
+        web::resource("/layers/providers/{provider}/cache")
+            .route(web::delete().to(invalidate_provider_cache_handler::<C>)),
+    )
     .service(
         web::resource("/layers/{provider}/{layer:.+}").route(web::get().to(layer_handler::<C>)),
     );
@@ -82,16 +122,17 @@ where
     )
 )]
 async fn list_root_collections_handler<C: Context>(
-    _session: C::Session,
+    session: C::Session,
     ctx: web::Data<C>,
     options: web::Query<LayerCollectionListOptions>,
 ) -> Result<impl Responder> {
-    let root_collection = get_layer_providers(options, ctx).await?;
+    let root_collection = get_layer_providers(&session, options, ctx).await?;
 
     Ok(web::Json(root_collection))
 }
 
 async fn get_layer_providers<C: Context>(
+    session: &C::Session,
     mut options: web::Query<LayerCollectionListOptions>,
     ctx: web::Data<C>,
 ) -> Result<LayerCollection> {
@@ -127,6 +168,7 @@ async fn get_layer_providers<C: Context>(
     let external = ctx.layer_provider_db_ref();
     for provider_listing in external
         .list_layer_providers(
+            session,
             LayerProviderListingOptions {
                 offset: options.offset,
                 limit: options.limit,
@@ -136,7 +178,7 @@ async fn get_layer_providers<C: Context>(
         .await?
     {
         // TODO: resolve providers in parallel
-        let provider = match external.layer_provider(provider_listing.id).await {
+        let provider = match external.layer_provider(session, provider_listing.id).await {
             Ok(provider) => provider,
             Err(err) => {
                 log::error!("Error loading provider: {err}");
@@ -178,6 +220,131 @@ async fn get_layer_providers<C: Context>(
     Ok(root_collection)
 }
 
+/// Searches the layers of all providers, i.e., the datasets, the Geo Engine layers and the external providers,
+/// for layers whose name or description contain all words of the query.
+/// Providers that fail or do not respond in time are left out of the results.
+#[utoipa::path(
+    tag = "Layers",
+    get,
+    path = "/layers/search",
+    responses(
+        (status = 200, description = "The matching layers, best matches first", body = [LayerSearchResult],
+            example = json!([
+                {
+                  "layer": {
+                    "id": {
+                      "providerId": "ac50ed0d-c9a0-41f8-9ce8-35fc9e38299b",
+                      "layerId": "36574dc3-560a-4b09-9d22-d5945f2b8093"
+                    },
+                    "name": "NDVI",
+                    "description": "NDVI data from MODIS"
+                  },
+                  "providerName": "Datasets",
+                  "score": 7
+                }
+              ])
+        )
+    ),
+    params(
+        LayerSearchOptions
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn search_layers_handler<C: Context>(
+    session: C::Session,
+    ctx: web::Data<C>,
+    options: web::Query<LayerSearchOptions>,
+) -> Result<impl Responder> {
+    let options = options.into_inner().validated()?.user_input;
+    let query = LayerSearchQuery::new(&options.query);
+    let timeout =
+        Duration::from_secs(get_config_element::<config::LayerSearch>()?.provider_timeout_seconds);
+
+    let external = ctx.layer_provider_db_ref();
+    let mut external_providers = vec![];
+    let mut offset = 0;
+    loop {
+        let listings = external
+            .list_layer_providers(
+                &session,
+                LayerProviderListingOptions {
+                    offset,
+                    limit: PROVIDER_PAGE_SIZE,
+                }
+                .validated()?,
+            )
+            .await?;
+        let page_size = listings.len();
+
+        for listing in listings {
+            match external.layer_provider(&session, listing.id).await {
+                Ok(provider) => external_providers.push((listing.name, provider)),
+                Err(err) => log::error!("Error loading provider {} for search: {err}", listing.id),
+            }
+        }
+
+        if page_size < PROVIDER_PAGE_SIZE as usize {
+            break;
+        }
+        offset += PROVIDER_PAGE_SIZE;
+    }
+
+    let (datasets, layers, external) = futures::join!(
+        search_provider(
+            "Datasets",
+            &SessionDatasetLayers::new(ctx.dataset_db_ref(), &session),
+            &query,
+            timeout
+        ),
+        search_provider("Layers", ctx.layer_db_ref(), &query, timeout),
+        join_all(
+            external_providers
+                .iter()
+                .map(|(name, provider)| search_provider(name, provider.as_ref(), &query, timeout))
+        )
+    );
+
+    let mut results: Vec<LayerSearchResult> = datasets
+        .into_iter()
+        .chain(layers)
+        .chain(external.into_iter().flatten())
+        .collect();
+    rank_search_results(&mut results, options.limit as usize);
+
+    Ok(web::Json(results))
+}
+
+async fn search_provider<P>(
+    provider_name: &str,
+    provider: &P,
+    query: &LayerSearchQuery,
+    timeout: Duration,
+) -> Vec<LayerSearchResult>
+where
+    P: LayerCollectionProvider + Sync + ?Sized,
+{
+    match tokio::time::timeout(timeout, provider.search(query)).await {
+        Ok(Ok(matches)) => matches
+            .into_iter()
+            .map(|m| LayerSearchResult {
+                layer: m.layer,
+                provider_name: provider_name.to_string(),
+                score: m.score,
+            })
+            .collect(),
+        Ok(Err(err)) => {
+            log::error!("Error searching provider {provider_name}: {err}");
+            vec![]
+        }
+        Err(_) => {
+            log::warn!("Search of provider {provider_name} timed out");
+            vec![]
+        }
+    }
+}
+
 /// List the contents of the collection of the given provider
 #[utoipa::path(
     tag = "Layers",
@@ -227,6 +394,7 @@ async fn get_layer_providers<C: Context>(
     )
 )]
 async fn list_collection_handler<C: Context>(
+    session: C::Session,
     ctx: web::Data<C>,
     path: web::Path<(DataProviderId, LayerCollectionId)>,
     options: web::Query<LayerCollectionListOptions>,
@@ -234,14 +402,14 @@ async fn list_collection_handler<C: Context>(
     let (provider, item) = path.into_inner();
 
     if provider == ROOT_PROVIDER_ID && item == LayerCollectionId(ROOT_COLLECTION_ID.to_string()) {
-        let collection = get_layer_providers(options, ctx).await?;
+        let collection = get_layer_providers(&session, options, ctx).await?;
         return Ok(web::Json(collection));
     }
 
     if provider == crate::datasets::storage::DATASET_DB_LAYER_PROVIDER_ID {
         let collection = ctx
             .dataset_db_ref()
-            .collection(&item, options.into_inner().validated()?)
+            .session_collection(&session, &item, options.into_inner().validated()?)
             .await?;
 
         return Ok(web::Json(collection));
@@ -258,7 +426,7 @@ async fn list_collection_handler<C: Context>(
 
     let collection = ctx
         .layer_provider_db_ref()
-        .layer_provider(provider)
+        .layer_provider(&session, provider)
         .await?
         .collection(&item, options.into_inner().validated()?)
         .await?;
@@ -429,13 +597,14 @@ async fn list_collection_handler<C: Context>(
     )
 )]
 async fn layer_handler<C: Context>(
+    session: C::Session,
     ctx: web::Data<C>,
     path: web::Path<(DataProviderId, LayerId)>,
 ) -> Result<impl Responder> {
     let (provider, item) = path.into_inner();
 
     if provider == crate::datasets::storage::DATASET_DB_LAYER_PROVIDER_ID {
-        let collection = ctx.dataset_db_ref().get_layer(&item).await?;
+        let collection = ctx.dataset_db_ref().session_layer(&session, &item).await?;
 
         return Ok(web::Json(collection));
     }
@@ -448,10 +617,228 @@ async fn layer_handler<C: Context>(
 
     let collection = ctx
         .layer_provider_db_ref()
-        .layer_provider(provider)
+        .layer_provider(&session, provider)
         .await?
         .get_layer(&item)
         .await?;
 
     Ok(web::Json(collection))
 }
+
+/// The filters that the provider evaluates at the external source.
+/// Clients can use this to hide filters that are not supported.
+#[utoipa::path(
+    tag = "Layers",
+    get,
+    path = "/layers/providers/{provider}/capabilities",
+    responses(
+        (status = 200, description = "OK", body = ProviderCapabilities,
+            example = json!({
+                "spatialFilter": true,
+                "timeFilter": true,
+                "attributeFilter": false
+            })
+        )
+    ),
+    params(
+        ("provider" = DataProviderId, description = "Data provider id"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn provider_capabilities_handler<C: Context>(
+    session: C::Session,
+    ctx: web::Data<C>,
+    provider: web::Path<DataProviderId>,
+) -> Result<impl Responder> {
+    let provider = provider.into_inner();
+
+    if provider == crate::datasets::storage::DATASET_DB_LAYER_PROVIDER_ID
+        || provider == crate::layers::storage::INTERNAL_PROVIDER_ID
+    {
+        return Ok(web::Json(ProviderCapabilities {
+            spatial_filter: true,
+            time_filter: true,
+            attribute_filter: false,
+        }));
+    }
+
+    let capabilities = ctx
+        .layer_provider_db_ref()
+        .layer_provider(&session, provider)
+        .await?
+        .capabilities();
+
+    Ok(web::Json(capabilities))
+}
+
+/// Checks the connectivity of the provider, e.g., whether the external source is reachable
+/// and accepts the configured credentials.
+/// Requires an admin session, since the error messages may reveal internal addresses of the providers.
+#[utoipa::path(
+    tag = "Layers",
+    get,
+    path = "/layers/providers/{provider}/health",
+    responses(
+        (status = 200, description = "OK", body = ProviderHealth,
+            example = json!({
+                "status": "error",
+                "message": "Reqwest: HTTP status client error (401 Unauthorized)",
+                "responseTimeMs": 125
+            })
+        )
+    ),
+    params(
+        ("provider" = DataProviderId, description = "Data provider id"),
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+async fn provider_health_handler<C: Context>(
+    session: AdminSession,
+    ctx: web::Data<C>,
+    provider: web::Path<DataProviderId>,
+) -> Result<impl Responder> {
+    let session = C::Session::from(session);
+    let provider = provider.into_inner();
+
+    if provider == crate::datasets::storage::DATASET_DB_LAYER_PROVIDER_ID {
+        let health = ProviderHealth::check(check_root_collection(&SessionDatasetLayers::new(
+            ctx.dataset_db_ref(),
+            &session,
+        )))
+        .await;
+        return Ok(web::Json(health));
+    }
+
+    if provider == crate::layers::storage::INTERNAL_PROVIDER_ID {
+        let health = ProviderHealth::check(check_root_collection(ctx.layer_db_ref())).await;
+        return Ok(web::Json(health));
+    }
+
+    // a provider that cannot be initialized is unhealthy as well
+    let health = ProviderHealth::check(async {
+        ctx.layer_provider_db_ref()
+            .layer_provider(&session, provider)
+            .await?
+            .check_health()
+            .await
+    })
+    .await;
+
+    Ok(web::Json(health))
+}
+
+/// Retrieves the definition of the provider. Its secrets are redacted.
+#[utoipa::path(
+    tag = "Layers",
+    get,
+    path = "/layers/providers/{provider}/definition",
+    responses(
+        (status = 200, description = "OK", content_type = "application/json",
+            example = json!({
+                "type": "Nature40DataProviderDefinition",
+                "id": "2cb964d5-b9fa-4f8f-ab6f-f6c7fb47d4cd",
+                "name": "Nature4.0",
+                "baseUrl": "http://localhost:8081/",
+                "user": "geoengine",
+                "password": "********"
+            })
+        )
+    ),
+    params(
+        ("provider" = DataProviderId, description = "Data provider id"),
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+async fn provider_definition_handler<C: Context>(
+    session: AdminSession,
+    ctx: web::Data<C>,
+    provider: web::Path<DataProviderId>,
+) -> Result<impl Responder> {
+    let definition = ctx
+        .layer_provider_db_ref()
+        .layer_provider_definition(&C::Session::from(session), provider.into_inner())
+        .await?;
+
+    let mut definition = serde_json::to_value(definition)?;
+    redact_secrets(&mut definition);
+
+    Ok(web::Json(definition))
+}
+
+/// New plaintexts of provider secrets, addressed by their JSON pointer in the definition
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({"/password": "new password"}))]
+pub struct ProviderSecrets(HashMap<String, String>);
+
+/// Replaces secrets of the provider definition, e.g., passwords or API tokens.
+/// The secrets are addressed by their JSON pointer in the definition.
+/// Secrets can only be written, they are never part of a response.
+#[utoipa::path(
+    tag = "Layers",
+    put,
+    path = "/layers/providers/{provider}/secrets",
+    request_body = ProviderSecrets,
+    responses(
+        (status = 200, description = "OK")
+    ),
+    params(
+        ("provider" = DataProviderId, description = "Data provider id"),
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+async fn update_provider_secrets_handler<C: Context>(
+    session: AdminSession,
+    ctx: web::Data<C>,
+    provider: web::Path<DataProviderId>,
+    secrets: web::Json<ProviderSecrets>,
+) -> Result<HttpResponse> {
+    let session = C::Session::from(session);
+    let db = ctx.layer_provider_db_ref();
+
+    let mut definition = serde_json::to_value(
+        db.layer_provider_definition(&session, provider.into_inner())
+            .await?,
+    )?;
+    replace_secrets(&mut definition, secrets.into_inner().0)?;
+
+    let definition: Box<dyn DataProviderDefinition> = serde_json::from_value(definition)?;
+    db.update_layer_provider(&session, definition).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Removes the cached listings and meta data of the provider,
+/// e.g., after the external source has changed.
+#[utoipa::path(
+    tag = "Layers",
+    delete,
+    path = "/layers/providers/{provider}/cache",
+    responses(
+        (status = 200, description = "OK")
+    ),
+    params(
+        ("provider" = DataProviderId, description = "Data provider id"),
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+async fn invalidate_provider_cache_handler<C: Context>(
+    session: AdminSession,
+    ctx: web::Data<C>,
+    provider: web::Path<DataProviderId>,
+) -> Result<HttpResponse> {
+    ctx.layer_provider_db_ref()
+        .invalidate_layer_provider_cache(&C::Session::from(session), provider.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -8,6 +8,7 @@ use actix_web_httpauth::headers::authorization::{Bearer, Scheme};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use utoipa::ToSchema;
 
 pub mod datasets;
 #[cfg(feature = "ebv")]
@@ -16,7 +17,9 @@ pub mod ebv;
 pub mod gfbio;
 pub mod layers;
 pub mod plots;
+pub mod preflight;
 pub mod projects;
+pub mod reports;
 pub mod session;
 pub mod spatial_references;
 pub mod tasks;
@@ -26,7 +29,12 @@ pub mod wfs;
 pub mod wms;
 pub mod workflows;
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// The body of all error responses of the API
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[schema(example = json!({
+    "error": "Authorization",
+    "message": "Authorization error: Header with authorization token not provided."
+}))]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
@@ -6,21 +6,35 @@ use crate::error::Result;
 use crate::handlers::Context;
 use crate::ogc::util::{parse_bbox, parse_time};
 use crate::util::config;
+use crate::util::defaults::default_spatial_reference;
 use crate::util::parsing::parse_spatial_resolution;
 use crate::util::server::connection_closed;
+use crate::workflows::plot_cache::{PlotCacheKey, PLOT_CACHE};
+use crate::workflows::query_log::{RecordedQuery, QUERY_LOG};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 use actix_web::{web, FromRequest, HttpRequest, Responder};
+use futures::future::BoxFuture;
 use geoengine_datatypes::operations::reproject::reproject_query;
 use geoengine_datatypes::plots::PlotOutputFormat;
 use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, VectorQueryRectangle};
 use geoengine_datatypes::spatial_reference::SpatialReference;
-use geoengine_operators::engine::{QueryContext, ResultDescriptor, TypedPlotQueryProcessor};
+use geoengine_operators::engine::{
+    QueryAbortTrigger, QueryContext, ResultDescriptor, TypedOperator, TypedPlotQueryProcessor,
+};
 use geoengine_operators::util::abortable_query_execution;
+use geoengine_operators::util::coalescing::QueryCoalescer;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+lazy_static::lazy_static! {
+    /// The plot queries that are currently computed
+    static ref PLOT_QUERIES: QueryCoalescer<PlotCacheKey, serde_json::Value> =
+        QueryCoalescer::new();
+}
+
 pub(crate) fn init_plot_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
@@ -29,15 +43,19 @@ where
     cfg.service(web::resource("/plot/{id}").route(web::get().to(get_plot_handler::<C>)));
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GetPlot {
     #[serde(deserialize_with = "parse_bbox")]
+    #[param(value_type = String, example = "0,-0.3,0.2,0")]
     pub bbox: BoundingBox2D,
+    #[param(value_type = Option<String>, example = "EPSG:4326")]
     pub crs: Option<SpatialReference>,
     #[serde(deserialize_with = "parse_time")]
+    #[param(value_type = String, example = "2020-01-01T00:00:00.0Z")]
     pub time: TimeInterval,
     #[serde(deserialize_with = "parse_spatial_resolution")]
+    #[param(value_type = String, example = "0.1,0.1")]
     pub spatial_resolution: SpatialResolution,
 }
 
@@ -121,6 +139,36 @@ pub(crate) struct GetPlot {
 ///   ]
 /// }
 /// ```
+#[utoipa::path(
+    tag = "Plots",
+    get,
+    path = "/plot/{id}",
+    responses(
+        (status = 200, description = "The plot", body = WrappedPlotOutput,
+            example = json!({
+                "outputFormat": "JsonPlain",
+                "plotType": "Statistics",
+                "data": [
+                    {
+                        "pixelCount": 6,
+                        "nanCount": 0,
+                        "min": 1.0,
+                        "max": 6.0,
+                        "mean": 3.5,
+                        "stddev": 1.707_825_127_659_933
+                    }
+                ]
+            })
+        )
+    ),
+    params(
+        ("id" = WorkflowId, description = "Workflow id"),
+        GetPlot
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
 async fn get_plot_handler<C: Context>(
     req: HttpRequest,
     id: web::Path<Uuid>,
@@ -135,13 +183,19 @@ async fn get_plot_handler<C: Context>(
             .map(Duration::from_secs),
     );
 
+    let workflow_id = WorkflowId(id.into_inner());
+
     let workflow = ctx
         .workflow_registry_ref()
-        .load(&WorkflowId(id.into_inner()))
+        .load(&session, &workflow_id)
         .await?;
 
     let operator = workflow.operator.get_plot().context(error::Operator)?;
 
+    let recording = QUERY_LOG.start_recording(|| TypedOperator::Plot(operator.clone()));
+
+    let default_spatial_ref = default_spatial_reference(&session);
+
     let execution_context = ctx.execution_context(session)?;
 
     let initialized = operator
@@ -154,9 +208,7 @@ async fn get_plot_handler<C: Context>(
         initialized.result_descriptor().spatial_reference().into();
     let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
 
-    // TODO: use a default spatial reference if it is not set?
-    let request_spatial_ref: SpatialReference =
-        params.crs.ok_or(error::Error::MissingSpatialReference)?;
+    let request_spatial_ref: SpatialReference = params.crs.unwrap_or(default_spatial_ref);
 
     let query_rect = VectorQueryRectangle {
         spatial_bounds: params.bbox,
@@ -191,21 +243,67 @@ async fn get_plot_handler<C: Context>(
     let output_format = PlotOutputFormat::from(&processor);
     let plot_type = processor.plot_type();
 
-    let data = match processor {
+    let query_key = PlotCacheKey::new(workflow_id, &query_rect)?;
+
+    let data = if let Some(data) = PLOT_CACHE.get(&query_key).await {
+        data
+    } else {
+        // identical plots that are requested at the same time, e.g., by refreshing dashboards, are computed once
+        let data = PLOT_QUERIES
+            .execute(query_key.clone(), || {
+                plot_query_data(
+                    processor,
+                    query_rect,
+                    &query_ctx,
+                    conn_closed,
+                    query_abort_trigger,
+                )
+            })
+            .await?;
+
+        PLOT_CACHE.insert(query_key, data.clone()).await;
+
+        data
+    };
+
+    if let Some(recording) = recording {
+        recording
+            .finish(workflow_spatial_ref, RecordedQuery::Plot(query_rect))
+            .await;
+    }
+
+    let output = WrappedPlotOutput {
+        output_format,
+        plot_type,
+        data,
+    };
+
+    Ok(web::Json(output))
+}
+
+/// Computes the plot and converts it to JSON
+async fn plot_query_data<Q: QueryContext>(
+    processor: TypedPlotQueryProcessor,
+    query_rect: VectorQueryRectangle,
+    query_ctx: &Q,
+    conn_closed: BoxFuture<'_, ()>,
+    query_abort_trigger: QueryAbortTrigger,
+) -> Result<serde_json::Value> {
+    Ok(match processor {
         TypedPlotQueryProcessor::JsonPlain(processor) => {
-            let json = processor.plot_query(query_rect, &query_ctx);
+            let json = processor.plot_query(query_rect, query_ctx);
             let result = abortable_query_execution(json, conn_closed, query_abort_trigger).await;
             result.context(error::Operator)?
         }
         TypedPlotQueryProcessor::JsonVega(processor) => {
-            let chart = processor.plot_query(query_rect, &query_ctx);
+            let chart = processor.plot_query(query_rect, query_ctx);
             let chart = abortable_query_execution(chart, conn_closed, query_abort_trigger).await;
             let chart = chart.context(error::Operator)?;
 
             serde_json::to_value(&chart).context(error::SerdeJson)?
         }
         TypedPlotQueryProcessor::ImagePng(processor) => {
-            let png_bytes = processor.plot_query(query_rect, &query_ctx);
+            let png_bytes = processor.plot_query(query_rect, query_ctx);
             let png_bytes =
                 abortable_query_execution(png_bytes, conn_closed, query_abort_trigger).await;
             let png_bytes = png_bytes.context(error::Operator)?;
@@ -214,22 +312,17 @@ async fn get_plot_handler<C: Context>(
 
             serde_json::to_value(&data_uri).context(error::SerdeJson)?
         }
-    };
-
-    let output = WrappedPlotOutput {
-        output_format,
-        plot_type,
-        data,
-    };
-
-    Ok(web::Json(output))
+    })
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-struct WrappedPlotOutput {
+pub(crate) struct WrappedPlotOutput {
+    /// `JsonPlain`, `JsonVega` or `ImagePng`
+    #[schema(value_type = String)]
     output_format: PlotOutputFormat,
     plot_type: &'static str,
+    #[schema(value_type = Object)]
     data: serde_json::Value,
 }
 
@@ -245,6 +338,7 @@ mod tests {
     use actix_web::dev::ServiceResponse;
     use actix_web::http::{header, Method};
     use actix_web_httpauth::headers::authorization::Bearer;
+    use geoengine_datatypes::plots::HistogramBucketing;
     use geoengine_datatypes::primitives::{DateTime, Measurement};
     use geoengine_datatypes::raster::{
         Grid2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
@@ -309,7 +403,7 @@ mod tests {
 
         let id = ctx
             .workflow_registry_ref()
-            .register(workflow)
+            .register(&*ctx.default_session_ref().await, workflow)
             .await
             .unwrap();
 
@@ -349,6 +443,52 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn it_uses_default_spatial_references_and_time_zones() {
+        let tiling_specification = TilingSpecification::new([0.0, 0.0].into(), [3, 2].into());
+        let ctx = InMemoryContext::new_with_context_spec(
+            tiling_specification,
+            ChunkByteSize::test_default(),
+        );
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: Statistics {
+                params: StatisticsParams {
+                    column_names: vec![],
+                },
+                sources: vec![example_raster_source()].into(),
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = ctx
+            .workflow_registry_ref()
+            .register(&*ctx.default_session_ref().await, workflow)
+            .await
+            .unwrap();
+
+        let params = &[
+            ("bbox", "0,-0.3,0.2,0"),
+            ("time", "2020-01-01T00:00:00"),
+            ("spatialResolution", "0.1,0.1"),
+        ];
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/plot/{}?{}",
+                id,
+                &serde_urlencoded::to_string(params).unwrap()
+            ))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let json = read_body_json(res).await;
+        assert_eq!(json["data"]["Raster-1"]["valueCount"], json!(6));
+    }
+
     #[tokio::test]
     async fn json_vega() {
         let tiling_specification = TilingSpecification::new([0.0, 0.0].into(), [3, 2].into());
@@ -367,6 +507,7 @@ mod tests {
                         max: 10.0,
                     },
                     buckets: Some(4),
+                    bucketing: HistogramBucketing::Linear,
                     interactive: false,
                 },
                 sources: example_raster_source().into(),
@@ -375,7 +516,11 @@ mod tests {
             .into(),
         };
 
-        let id = ctx.workflow_registry().register(workflow).await.unwrap();
+        let id = ctx
+            .workflow_registry()
+            .register(&*ctx.default_session_ref().await, workflow)
+            .await
+            .unwrap();
 
         let params = &[
             ("bbox", "0,-0.3,0.2,0"),
@@ -495,7 +640,7 @@ mod tests {
 
             let id = ctx
                 .workflow_registry_ref()
-                .register(workflow)
+                .register(&*ctx.default_session_ref().await, workflow)
                 .await
                 .unwrap();
 
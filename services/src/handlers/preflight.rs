@@ -0,0 +1,105 @@
+use actix_web::{web, Responder};
+
+use crate::contexts::AdminSession;
+use crate::util::preflight::PreflightReport;
+
+pub(crate) fn init_preflight_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/admin/preflight").route(web::get().to(preflight_report_handler)));
+}
+
+/// Shows the definitions of datasets, providers, layers and layer collections and the workflows that were broken at startup.
+///
+/// Definitions with critical issues could not be loaded or initialized, definitions with warnings reference data that was unknown at startup.
+#[utoipa::path(
+    tag = "Preflight",
+    get,
+    path = "/admin/preflight",
+    responses(
+        (status = 200, description = "OK", body = PreflightReport,
+            example = json!({
+                "checked": 42,
+                "issues": [
+                    {
+                        "kind": "dataset",
+                        "path": "./test_data/dataset_defs/broken.json",
+                        "severity": "critical",
+                        "message": "missing field `metaData` at line 12 column 1"
+                    }
+                ]
+            })
+        )
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+async fn preflight_report_handler(
+    _session: AdminSession,
+    report: web::Data<PreflightReport>,
+) -> impl Responder {
+    web::Json(report.get_ref().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::Session;
+    use crate::util::preflight::{DefinitionKind, PreflightIssue, PreflightSeverity};
+    use actix_web::http::header;
+    use actix_web::{test, App};
+    use actix_web_httpauth::headers::authorization::Bearer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn it_shows_the_report_to_admins() {
+        crate::util::config::set_config(
+            "session.admin_session_token",
+            "8aca8875-425a-4ef1-8ee6-cdfc62dd7525",
+        )
+        .unwrap();
+
+        let report = PreflightReport {
+            checked: 1,
+            issues: vec![PreflightIssue {
+                kind: DefinitionKind::Provider,
+                path: "provider.json".into(),
+                severity: PreflightSeverity::Critical,
+                message: "broken".to_string(),
+            }],
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(report))
+                .configure(init_preflight_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/admin/preflight");
+        let res = test::call_service(&app, req.to_request()).await;
+        assert_eq!(res.status(), 401);
+
+        let req = test::TestRequest::get()
+            .uri("/admin/preflight")
+            .append_header((
+                header::AUTHORIZATION,
+                Bearer::new(AdminSession::default().id().to_string()),
+            ));
+        let res = test::call_service(&app, req.to_request()).await;
+        assert_eq!(res.status(), 200);
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(
+            body,
+            json!({
+                "checked": 1,
+                "issues": [{
+                    "kind": "provider",
+                    "path": "provider.json",
+                    "severity": "critical",
+                    "message": "broken"
+                }]
+            })
+        );
+    }
+}
@@ -9,6 +9,7 @@ use proj_sys::PJ_PROJ_STRING_TYPE_PJ_PROJ_4;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::str::FromStr;
+use utoipa::ToSchema;
 
 pub(crate) fn init_spatial_reference_routes<C>(cfg: &mut web::ServiceConfig)
 where
@@ -23,13 +24,26 @@ where
 
 /// The specification of a spatial reference, where extent and axis labels are given
 /// in natural order (x, y) = (east, north)
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "name": "WGS 84",
+    "spatialReference": "EPSG:4326",
+    "projString": "+proj=longlat +datum=WGS84 +no_defs +type=crs",
+    "extent": {
+        "lowerLeftCoordinate": { "x": -180.0, "y": -90.0 },
+        "upperRightCoordinate": { "x": 180.0, "y": 90.0 }
+    },
+    "axisLabels": ["Geodetic longitude", "Geodetic latitude"],
+    "axisOrder": "northEast"
+}))]
 pub struct SpatialReferenceSpecification {
     pub name: String,
     pub spatial_reference: SpatialReference,
     pub proj_string: String,
     pub extent: BoundingBox2D,
+    /// the labels of the x and y axis
+    #[schema(value_type = Option<Vec<String>>)]
     pub axis_labels: Option<(String, String)>,
     pub axis_order: Option<AxisOrder>,
 }
@@ -86,7 +100,7 @@ pub enum ProjJsonAxisDirection {
     North,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum AxisOrder {
     NorthEast,
@@ -94,8 +108,13 @@ pub enum AxisOrder {
 }
 
 /// Get the proj json information for the given `srs_string` if it is known.
-// TODO: expose method in proj crate instead
 fn proj_json(srs_string: &str) -> Option<ProjJson> {
+    proj_json_value(srs_string).and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// Get the PROJJSON document for the given `srs_string` if it is known.
+// TODO: expose method in proj crate instead
+pub(crate) fn proj_json_value(srs_string: &str) -> Option<serde_json::Value> {
     unsafe {
         let c_definition = std::ffi::CString::new(srs_string).ok()?;
 
@@ -154,6 +173,21 @@ fn proj_proj_string(srs_string: &str) -> Option<String> {
     }
 }
 
+/// Retrieves the specification of a spatial reference, e.g., its extent and axis order.
+#[utoipa::path(
+    tag = "Spatial References",
+    get,
+    path = "/spatialReferenceSpecification/{srs_string}",
+    responses(
+        (status = 200, description = "The specification of the spatial reference", body = SpatialReferenceSpecification)
+    ),
+    params(
+        ("srs_string" = String, description = "The spatial reference, e.g., `EPSG:4326`", example = "EPSG:4326")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
 #[allow(clippy::unused_async)] // the function signature of request handlers requires it
 pub(crate) async fn get_spatial_reference_specification_handler<C: Context>(
     srs_string: web::Path<String>,
@@ -26,6 +26,7 @@ where
 /// Create a task somewhere and respond with a task id to query the task status.
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct TaskResponse {
+    #[serde(alias = "taskId")]
     pub task_id: TaskId,
 }
 
@@ -313,6 +314,27 @@ mod tests {
         }
     }
 
+    struct PanickingTask;
+
+    #[async_trait::async_trait]
+    impl<C: TaskContext + 'static> Task<C> for PanickingTask {
+        async fn run(&self, _ctx: C) -> Result<Box<dyn TaskStatusInfo>, Box<dyn ErrorSource>> {
+            panic!("unexpected state")
+        }
+
+        async fn cleanup_on_error(&self, _ctx: C) -> Result<(), Box<dyn ErrorSource>> {
+            Ok(())
+        }
+
+        fn task_type(&self) -> &'static str {
+            "PanickingTask"
+        }
+
+        fn task_unique_id(&self) -> Option<String> {
+            None
+        }
+    }
+
     #[tokio::test]
     async fn test_get_status() {
         let ctx = InMemoryContext::test_default();
@@ -814,4 +836,26 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    async fn test_panicking_task() {
+        let ctx = InMemoryContext::test_default();
+
+        let task_id = ctx
+            .tasks_ref()
+            .schedule(PanickingTask.boxed(), None)
+            .await
+            .unwrap();
+
+        wait_for_task_to_finish(ctx.tasks(), task_id).await;
+
+        assert_eq!(
+            serde_json::to_value(ctx.tasks_ref().status(task_id).await.unwrap()).unwrap(),
+            serde_json::json!({
+                "status": "failed",
+                "error": "Task panicked: unexpected state",
+                "cleanUp": {"status": "completed", "info": null}
+            })
+        );
+    }
 }
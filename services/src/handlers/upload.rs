@@ -1,27 +1,41 @@
+use std::path::Path;
+
 use tokio::{fs, io::AsyncWriteExt};
 
+use actix_files::NamedFile;
 use actix_multipart::Multipart;
 use actix_web::{web, FromRequest, Responder};
 use futures::StreamExt;
 use geoengine_datatypes::util::Identifier;
 
-use crate::datasets::upload::{FileId, FileUpload, Upload, UploadDb, UploadId, UploadRootPath};
+use crate::datasets::upload::{
+    FileId, FileUpload, StorageUsage, Upload, UploadDb, UploadId, UploadRootPath,
+};
+use crate::datasets::upload_hooks::check_upload;
 use crate::error;
 use crate::error::Result;
 use crate::handlers::Context;
 use crate::util::IdResponse;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 
 pub(crate) fn init_upload_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
     C::Session: FromRequest,
 {
-    cfg.service(web::resource("/upload").route(web::post().to(upload_handler::<C>)));
+    cfg.service(web::resource("/upload").route(web::post().to(upload_handler::<C>)))
+        .service(
+            web::resource("/upload/{upload_id}/{path:.*}")
+                .route(web::get().to(download_upload_file_handler::<C>)),
+        );
 }
 
 /// Uploads files.
 ///
+/// The files are checked by the configured [upload hooks](crate::datasets::upload_hooks), e.g., a virus scanner.
+/// If any file is rejected, the upload is deleted or quarantined and cannot be used for creating datasets.
+/// Uploads that exceed the storage quota of the user are rejected as well.
+///
 /// # Example
 ///
 /// ```text
@@ -40,17 +54,113 @@ where
 ///   "id": "420b06de-0a7e-45cb-9c1c-ea901b46ab69"
 /// }
 /// ```
+#[utoipa::path(
+    tag = "Uploads",
+    post,
+    path = "/upload",
+    request_body(content = String, description = "The files as `files[]` fields of a multipart form", content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The id of the upload", body = IdResponse<UploadId>,
+            example = json!({
+                "id": "420b06de-0a7e-45cb-9c1c-ea901b46ab69"
+            })
+        )
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
 async fn upload_handler<C: Context>(
     session: C::Session,
     ctx: web::Data<C>,
     mut body: Multipart,
 ) -> Result<impl Responder> {
+    // reject uploads right away if there is no storage left
+    let usage = ctx.dataset_db_ref().storage_usage(&session).await?;
+    usage.ensure_available(1)?;
+
     let upload_id = UploadId::new();
 
     let root = upload_id.root_path()?;
 
     fs::create_dir_all(&root).await.context(error::Io)?;
 
+    let files = match write_files(&root, &mut body, usage).await {
+        Ok(files) => files,
+        Err(error) => {
+            fs::remove_dir_all(&root).await.context(error::Io)?;
+            return Err(error);
+        }
+    };
+
+    check_upload(upload_id, &files).await?;
+
+    let created = ctx
+        .dataset_db_ref()
+        .create_upload(
+            &session,
+            Upload {
+                id: upload_id,
+                files,
+            },
+        )
+        .await;
+
+    if created.is_err() {
+        fs::remove_dir_all(&root).await.context(error::Io)?;
+    }
+    created?;
+
+    Ok(web::Json(IdResponse::from(upload_id)))
+}
+
+/// Downloads a file of an upload, e.g., the result of a raster export.
+///
+/// Files in subdirectories, e.g., of a Zarr store, are addressed by their path relative to the upload.
+#[utoipa::path(
+    tag = "Uploads",
+    get,
+    path = "/upload/{upload_id}/{path}",
+    responses(
+        (status = 200, description = "The file", content_type = "application/octet-stream", body = String)
+    ),
+    params(
+        ("upload_id" = UploadId, description = "Upload id"),
+        ("path" = String, description = "Path of the file inside of the upload", example = "raster.tiff")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn download_upload_file_handler<C: Context>(
+    path: web::Path<(UploadId, String)>,
+    session: C::Session,
+    ctx: web::Data<C>,
+) -> Result<NamedFile> {
+    let (upload_id, file_path) = path.into_inner();
+
+    let upload = ctx.dataset_db_ref().get_upload(&session, upload_id).await?;
+
+    // only serve the files of the upload and nothing outside of its directory
+    ensure!(
+        upload.files.iter().any(|file| file.name == file_path),
+        error::UnknownUploadFile
+    );
+
+    NamedFile::open_async(upload_id.root_path()?.join(file_path))
+        .await
+        .context(error::Io)
+}
+
+/// Writes the files of the multipart `body` to `root`.
+/// Fails as soon as the written bytes exceed the available storage.
+async fn write_files(
+    root: &Path,
+    body: &mut Multipart,
+    usage: StorageUsage,
+) -> Result<Vec<FileUpload>> {
+    let mut total_byte_size = 0_u64;
+
     let mut files: Vec<FileUpload> = vec![];
     while let Some(item) = body.next().await {
         let mut field = item?;
@@ -68,6 +178,10 @@ async fn upload_handler<C: Context>(
         let mut byte_size = 0_u64;
         while let Some(chunk) = field.next().await {
             let bytes = chunk?;
+
+            total_byte_size += bytes.len() as u64;
+            usage.ensure_available(total_byte_size)?;
+
             file.write_all(&bytes).await.context(error::Io)?;
             byte_size += bytes.len() as u64;
         }
@@ -80,17 +194,7 @@ async fn upload_handler<C: Context>(
         });
     }
 
-    ctx.dataset_db_ref()
-        .create_upload(
-            &session,
-            Upload {
-                id: upload_id,
-                files,
-            },
-        )
-        .await?;
-
-    Ok(web::Json(IdResponse::from(upload_id)))
+    Ok(files)
 }
 
 #[cfg(test)]
@@ -126,4 +230,35 @@ mod tests {
         let root = upload.id.root_path().unwrap();
         assert!(root.join("foo.txt").exists() && root.join("bar.txt").exists());
     }
+
+    #[tokio::test]
+    async fn download() {
+        let mut test_data = TestDataUploads::default(); // remember created folder and remove them on drop
+
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let req = test::TestRequest::post()
+            .uri("/upload")
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_multipart(vec![("foo.txt", "foo")]);
+        let res = send_test_request(req, ctx.clone()).await;
+        let upload: IdResponse<UploadId> = test::read_body_json(res).await;
+        test_data.uploads.push(upload.id);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/upload/{}/foo.txt", upload.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(test::read_body(res).await.as_ref(), b"foo");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/upload/{}/..%2Fbar.txt", upload.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 400);
+    }
 }
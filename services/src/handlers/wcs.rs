@@ -27,11 +27,13 @@ use crate::ogc::wcs::request::{DescribeCoverage, GetCapabilities, GetCoverage, W
 use crate::util::config;
 use crate::util::config::get_config_element;
 use crate::util::server::{connection_closed, not_implemented_handler};
+use crate::workflows::query_log::{RecordedQuery, QUERY_LOG};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
 use geoengine_operators::engine::ExecutionContext;
 use geoengine_operators::engine::ResultDescriptor;
+use geoengine_operators::engine::TypedOperator;
 use geoengine_operators::processing::{InitializedRasterReprojection, ReprojectionParams};
 
 pub(crate) fn init_wcs_routes<C>(cfg: &mut web::ServiceConfig)
@@ -205,7 +207,10 @@ async fn wcs_describe_coverage_handler<C: Context>(
 
     let wcs_url = wcs_url(identifiers)?;
 
-    let workflow = ctx.workflow_registry_ref().load(&identifiers).await?;
+    let workflow = ctx
+        .workflow_registry_ref()
+        .load(&session, &identifiers)
+        .await?;
 
     let exe_ctx = ctx.execution_context(session)?;
     let operator = workflow
@@ -357,10 +362,15 @@ async fn wcs_get_coverage_handler<C: Context>(
         );
     }
 
-    let workflow = ctx.workflow_registry_ref().load(&identifier).await?;
+    let workflow = ctx
+        .workflow_registry_ref()
+        .load(&session, &identifier)
+        .await?;
 
     let operator = workflow.operator.get_raster().context(error::Operator)?;
 
+    let recording = QUERY_LOG.start_recording(|| TypedOperator::Raster(operator.clone()));
+
     let execution_context = ctx.execution_context(session)?;
 
     let initialized = operator
@@ -398,7 +408,10 @@ async fn wcs_get_coverage_handler<C: Context>(
         Box::new(irp)
     };
 
-    let processor = initialized.query_processor().context(error::Operator)?;
+    let processor = initialized
+        .query_processor()
+        .context(error::Operator)?
+        .coalesced(format!("{identifier}:{request_spatial_ref}"));
 
     let spatial_resolution: SpatialResolution =
         if let Some(spatial_resolution) = request.spatial_resolution() {
@@ -440,6 +453,12 @@ async fn wcs_get_coverage_handler<C: Context>(
         .await)?
     .map_err(error::Error::from)?;
 
+    if let Some(recording) = recording {
+        recording
+            .finish(request_spatial_ref, RecordedQuery::Raster(query_rect))
+            .await;
+    }
+
     Ok(HttpResponse::Ok().content_type("image/tiff").body(bytes))
 }
 
@@ -1,7 +1,7 @@
 use actix_web::{web, FromRequest, HttpRequest, HttpResponse};
 use futures::future::BoxFuture;
 use geoengine_datatypes::primitives::VectorQueryRectangle;
-use geoengine_operators::util::abortable_query_execution;
+use geoengine_operators::util::{abortable_query_execution, catch_panic};
 use reqwest::Url;
 use serde::Deserialize;
 use snafu::{ensure, ResultExt};
@@ -9,35 +9,62 @@ use utoipa::openapi::ArrayBuilder;
 use utoipa::ToSchema;
 
 use crate::api::model::datatypes::TimeInterval;
+use crate::contexts::Session;
 use crate::error;
 use crate::error::Result;
 use crate::handlers::Context;
 use crate::ogc::util::{ogc_endpoint_url, OgcProtocol, OgcRequestGuard};
+use crate::ogc::wfs::generalization::{
+    GeneralizationCacheKey, GeneralizationLevel, GENERALIZATION_CACHE,
+};
+use crate::ogc::wfs::paging::{PagingSpool, PagingSpoolKey};
 use crate::ogc::wfs::request::{GetCapabilities, GetFeature};
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::defaults::default_spatial_reference;
 use crate::util::server::{connection_closed, not_implemented_handler};
+use crate::workflows::query_log::{QueryRecording, RecordedQuery, QUERY_LOG};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::{Workflow, WorkflowId};
 use futures::StreamExt;
-use geoengine_datatypes::collections::ToGeoJson;
+use geoengine_datatypes::collections::{SpooledFeatureCollections, ToGeoJson};
+use geoengine_datatypes::operations::reproject::{
+    CoordinateProjection, CoordinateProjector, Reproject,
+};
+use geoengine_datatypes::operations::Simplify;
+use geoengine_datatypes::util::arrow::ArrowTyped;
 use geoengine_datatypes::{
-    collections::{FeatureCollection, MultiPointCollection},
+    collections::{
+        DataCollection, FeatureCollection, MultiLineStringCollection, MultiPointCollection,
+        MultiPolygonCollection,
+    },
     primitives::SpatialResolution,
 };
 use geoengine_datatypes::{
-    primitives::{FeatureData, Geometry, MultiPoint},
+    primitives::{FeatureData, Geometry, MultiLineString, MultiPoint, MultiPolygon, NoGeometry},
     spatial_reference::SpatialReference,
 };
+use geoengine_operators::adapters::FeatureCollectionStreamExt;
 use geoengine_operators::engine::QueryProcessor;
 use geoengine_operators::engine::{
-    QueryContext, ResultDescriptor, TypedVectorQueryProcessor, VectorQueryProcessor,
+    QueryContext, QueryMetadata, QueryStreamItem, ResultDescriptor, TypedOperator,
+    TypedVectorQueryProcessor, VectorQueryProcessor,
 };
 use geoengine_operators::processing::{InitializedVectorReprojection, ReprojectionParams};
+use geoengine_operators::util::vector_stream_to_spool::vector_stream_to_spool;
 use serde_json::json;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// Marks responses whose features were cut off by the `maxFeatures` limit
+const TRUNCATED_HEADER: &str = "x-geoengine-truncated";
+
+lazy_static::lazy_static! {
+    /// The results of paged `GetFeature` requests
+    static ref PAGING_SPOOL: PagingSpool<SpooledFeatures> = PagingSpool::from_config();
+}
+
 pub(crate) fn init_wfs_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
@@ -177,7 +204,10 @@ where
     let workflow_id = workflow_id.into_inner();
     let wfs_url = wfs_url(workflow_id)?;
 
-    let workflow = ctx.workflow_registry_ref().load(&workflow_id).await?;
+    let workflow = ctx
+        .workflow_registry_ref()
+        .load(&session, &workflow_id)
+        .await?;
 
     let exe_ctx = ctx.execution_context(session)?;
     let operator = workflow
@@ -444,17 +474,84 @@ async fn wfs_feature_handler<C: Context>(
         return get_feature_mock(&request);
     }
 
-    let conn_closed = connection_closed(
-        &req,
-        config::get_config_element::<config::Wfs>()?
-            .request_timeout_seconds
-            .map(Duration::from_secs),
-    );
+    // pages are cut from a spooled result, so that they are consistent with each other
+    if request.startIndex.is_some() || request.count.is_some() {
+        let spool_key = PagingSpoolKey::new(session.id(), type_names, req.query_string());
+
+        let spooled = if let Some(spooled) = PAGING_SPOOL.get(&spool_key).await {
+            spooled
+        } else {
+            let spooled =
+                spool_feature_collection(&req, type_names, &request, ctx.get_ref(), session)
+                    .await?;
 
-    let workflow: Workflow = ctx.workflow_registry_ref().load(&type_names).await?;
+            PAGING_SPOOL.insert(spool_key, spooled.clone()).await;
+
+            spooled
+        };
+
+        let start_index =
+            usize::try_from(request.startIndex.unwrap_or_default()).unwrap_or(usize::MAX);
+        let count = request.count.map_or(usize::MAX, |count| {
+            usize::try_from(count).unwrap_or(usize::MAX)
+        });
+
+        // spilled results are read from disk
+        let page =
+            crate::util::spawn_blocking(move || spooled.geojson_page(start_index, count)).await??;
+
+        return Ok(HttpResponse::Ok().json(page));
+    }
+
+    let json = query_feature_collection(&req, type_names, &request, ctx.get_ref(), session).await?;
+
+    let mut response = HttpResponse::Ok();
+    if json.get("truncated") == Some(&serde_json::Value::Bool(true)) {
+        response.insert_header((TRUNCATED_HEADER, "true"));
+    }
+
+    Ok(response.json(json.as_ref()))
+}
+
+/// An initialized feature query of a `GetFeature` request
+struct FeatureQuery<Q> {
+    processor: TypedVectorQueryProcessor,
+    query_rect: VectorQueryRectangle,
+    query_ctx: Q,
+    request_spatial_ref: SpatialReference,
+    output_spatial_ref: SpatialReference,
+    /// only lines and polygons of overview requests are generalized
+    generalization_level: Option<GeneralizationLevel>,
+    recording: Option<QueryRecording<'static>>,
+}
+
+impl<Q> FeatureQuery<Q> {
+    fn output(&self, precision: Option<u8>) -> GeoJsonOutput {
+        GeoJsonOutput {
+            reprojection: (self.output_spatial_ref != self.request_spatial_ref)
+                .then_some((self.request_spatial_ref, self.output_spatial_ref)),
+            precision,
+        }
+    }
+}
+
+async fn initialize_feature_query<C: Context>(
+    type_names: WorkflowId,
+    request: &GetFeature,
+    ctx: &C,
+    session: C::Session,
+) -> Result<FeatureQuery<C::QueryContext>> {
+    let workflow: Workflow = ctx
+        .workflow_registry_ref()
+        .load(&session, &type_names)
+        .await?;
 
     let operator = workflow.operator.get_vector().context(error::Operator)?;
 
+    let recording = QUERY_LOG.start_recording(|| TypedOperator::Vector(operator.clone()));
+
+    let default_spatial_ref = default_spatial_reference(&session);
+
     let execution_context = ctx.execution_context(session)?;
     let initialized = operator
         .clone()
@@ -467,10 +564,8 @@ async fn wfs_feature_handler<C: Context>(
         initialized.result_descriptor().spatial_reference().into();
     let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
 
-    // TODO: use a default spatial reference if it is not set?
-    let request_spatial_ref: SpatialReference = request
-        .srsName
-        .ok_or(error::Error::InvalidSpatialReference)?;
+    let request_spatial_ref: SpatialReference = request.srsName.unwrap_or(default_spatial_ref);
+    let output_spatial_ref = request.outputCrs.unwrap_or(request_spatial_ref);
 
     // perform reprojection if necessary
     let initialized = if request_spatial_ref == workflow_spatial_ref {
@@ -492,7 +587,10 @@ async fn wfs_feature_handler<C: Context>(
         Box::new(ivp)
     };
 
-    let processor = initialized.query_processor().context(error::Operator)?;
+    let processor = initialized
+        .query_processor()
+        .context(error::Operator)?
+        .coalesced(format!("{type_names}:{request_spatial_ref}"));
 
     let query_rect = VectorQueryRectangle {
         spatial_bounds: request.bbox.bounds_naive()?,
@@ -500,26 +598,296 @@ async fn wfs_feature_handler<C: Context>(
         // TODO: find reasonable default
         spatial_resolution: request
             .queryResolution
+            .as_ref()
             .map_or_else(SpatialResolution::zero_point_one, |r| r.0),
     };
     let query_ctx = ctx.query_context()?;
 
-    let json = match processor {
-        TypedVectorQueryProcessor::Data(p) => {
-            vector_stream_to_geojson(p, query_rect, query_ctx, conn_closed).await
+    let generalization_level = match processor {
+        TypedVectorQueryProcessor::MultiLineString(_)
+        | TypedVectorQueryProcessor::MultiPolygon(_) => request
+            .queryResolution
+            .as_ref()
+            .map(|resolution| GeneralizationLevel::from_resolution(resolution.0)),
+        TypedVectorQueryProcessor::Data(_) | TypedVectorQueryProcessor::MultiPoint(_) => None,
+    };
+
+    Ok(FeatureQuery {
+        processor,
+        query_rect,
+        query_ctx,
+        request_spatial_ref,
+        output_spatial_ref,
+        generalization_level,
+        recording,
+    })
+}
+
+/// Queries the workflow and returns all features as a `GeoJSON` feature collection
+async fn query_feature_collection<C: Context>(
+    req: &HttpRequest,
+    type_names: WorkflowId,
+    request: &GetFeature,
+    ctx: &C,
+    session: C::Session,
+) -> Result<Arc<serde_json::Value>> {
+    let conn_closed = connection_closed(
+        req,
+        config::get_config_element::<config::Wfs>()?
+            .request_timeout_seconds
+            .map(Duration::from_secs),
+    );
+
+    let query = initialize_feature_query(type_names, request, ctx, session).await?;
+
+    let max_features = request
+        .maxFeatures
+        .map(|max_features| usize::try_from(max_features).unwrap_or(usize::MAX));
+
+    // truncated results are not cached, since they depend on the limit
+    let cache_key = query
+        .generalization_level
+        .filter(|_| max_features.is_none())
+        .map(|level| {
+            GeneralizationCacheKey::new(
+                type_names,
+                level,
+                query.request_spatial_ref,
+                query.query_rect.spatial_bounds,
+                query.query_rect.time_interval,
+                query.output_spatial_ref,
+                request.precision,
+            )
+        });
+
+    if let Some(cache_key) = &cache_key {
+        if let Some(json) = GENERALIZATION_CACHE.get(cache_key).await {
+            return Ok(json);
         }
-        TypedVectorQueryProcessor::MultiPoint(p) => {
-            vector_stream_to_geojson(p, query_rect, query_ctx, conn_closed).await
+    }
+
+    let tolerance = query
+        .generalization_level
+        .map(GeneralizationLevel::tolerance);
+    let output = query.output(request.precision);
+    let query_rect = query.query_rect;
+    let query_ctx = query.query_ctx;
+    let processor = query.processor;
+    let request_spatial_ref = query.request_spatial_ref;
+    let recording = query.recording;
+
+    let json = catch_panic(async move {
+        match processor {
+            TypedVectorQueryProcessor::Data(p) => {
+                vector_stream_to_geojson(
+                    p,
+                    query_rect,
+                    query_ctx,
+                    tolerance,
+                    output,
+                    max_features,
+                    conn_closed,
+                )
+                .await
+            }
+            TypedVectorQueryProcessor::MultiPoint(p) => {
+                vector_stream_to_geojson(
+                    p,
+                    query_rect,
+                    query_ctx,
+                    tolerance,
+                    output,
+                    max_features,
+                    conn_closed,
+                )
+                .await
+            }
+            TypedVectorQueryProcessor::MultiLineString(p) => {
+                vector_stream_to_geojson(
+                    p,
+                    query_rect,
+                    query_ctx,
+                    tolerance,
+                    output,
+                    max_features,
+                    conn_closed,
+                )
+                .await
+            }
+            TypedVectorQueryProcessor::MultiPolygon(p) => {
+                vector_stream_to_geojson(
+                    p,
+                    query_rect,
+                    query_ctx,
+                    tolerance,
+                    output,
+                    max_features,
+                    conn_closed,
+                )
+                .await
+            }
         }
-        TypedVectorQueryProcessor::MultiLineString(p) => {
-            vector_stream_to_geojson(p, query_rect, query_ctx, conn_closed).await
+    })
+    .await?;
+    let json = Arc::new(json);
+
+    if let Some(recording) = recording {
+        recording
+            .finish(request_spatial_ref, RecordedQuery::Vector(query_rect))
+            .await;
+    }
+
+    if let Some(cache_key) = cache_key {
+        GENERALIZATION_CACHE.insert(cache_key, json.clone()).await;
+    }
+
+    Ok(json)
+}
+
+/// Queries the workflow and spools all features for paging
+async fn spool_feature_collection<C: Context>(
+    req: &HttpRequest,
+    type_names: WorkflowId,
+    request: &GetFeature,
+    ctx: &C,
+    session: C::Session,
+) -> Result<Arc<SpooledFeatures>> {
+    let conn_closed = connection_closed(
+        req,
+        config::get_config_element::<config::Wfs>()?
+            .request_timeout_seconds
+            .map(Duration::from_secs),
+    );
+    let spooling = config::get_config_element::<config::ResultSpooling>()?;
+
+    let query = initialize_feature_query(type_names, request, ctx, session).await?;
+
+    let simplification_tolerance = query
+        .generalization_level
+        .map(GeneralizationLevel::tolerance);
+    let output = query.output(request.precision);
+    let query_rect = query.query_rect;
+    let query_ctx = query.query_ctx;
+    let request_spatial_ref = query.request_spatial_ref;
+    let recording = query.recording;
+    let threshold = spooling.memory_threshold_bytes;
+    let directory = spooling.directory;
+
+    let collections = match query.processor {
+        TypedVectorQueryProcessor::Data(p) => TypedSpooledCollections::Data(
+            vector_stream_to_spool(p, query_rect, query_ctx, threshold, directory, conn_closed)
+                .await
+                .context(error::Operator)?,
+        ),
+        TypedVectorQueryProcessor::MultiPoint(p) => TypedSpooledCollections::MultiPoint(
+            vector_stream_to_spool(p, query_rect, query_ctx, threshold, directory, conn_closed)
+                .await
+                .context(error::Operator)?,
+        ),
+        TypedVectorQueryProcessor::MultiLineString(p) => TypedSpooledCollections::MultiLineString(
+            vector_stream_to_spool(p, query_rect, query_ctx, threshold, directory, conn_closed)
+                .await
+                .context(error::Operator)?,
+        ),
+        TypedVectorQueryProcessor::MultiPolygon(p) => TypedSpooledCollections::MultiPolygon(
+            vector_stream_to_spool(p, query_rect, query_ctx, threshold, directory, conn_closed)
+                .await
+                .context(error::Operator)?,
+        ),
+    };
+
+    if let Some(recording) = recording {
+        recording
+            .finish(request_spatial_ref, RecordedQuery::Vector(query_rect))
+            .await;
+    }
+
+    Ok(Arc::new(SpooledFeatures {
+        collections,
+        simplification_tolerance,
+        output,
+    }))
+}
+
+/// The collections of a spooled query result
+enum TypedSpooledCollections {
+    Data(SpooledFeatureCollections<NoGeometry>),
+    MultiPoint(SpooledFeatureCollections<MultiPoint>),
+    MultiLineString(SpooledFeatureCollections<MultiLineString>),
+    MultiPolygon(SpooledFeatureCollections<MultiPolygon>),
+}
+
+/// A query result that is spooled for paging, together with how its features are written as `GeoJSON`
+struct SpooledFeatures {
+    collections: TypedSpooledCollections,
+    simplification_tolerance: Option<f64>,
+    output: GeoJsonOutput,
+}
+
+impl SpooledFeatures {
+    fn len(&self) -> usize {
+        match &self.collections {
+            TypedSpooledCollections::Data(collections) => collections.len(),
+            TypedSpooledCollections::MultiPoint(collections) => collections.len(),
+            TypedSpooledCollections::MultiLineString(collections) => collections.len(),
+            TypedSpooledCollections::MultiPolygon(collections) => collections.len(),
         }
-        TypedVectorQueryProcessor::MultiPolygon(p) => {
-            vector_stream_to_geojson(p, query_rect, query_ctx, conn_closed).await
+    }
+
+    /// Writes the `count` features beginning at `start_index` as a `GeoJSON` feature collection.
+    /// The number of features of the whole result is stated in `numberMatched`.
+    fn geojson_page(&self, start_index: usize, count: usize) -> Result<serde_json::Value> {
+        let tolerance = self.simplification_tolerance;
+        let output = self.output;
+
+        let features = match &self.collections {
+            TypedSpooledCollections::Data(collections) => {
+                geojson_page_features(collections, start_index, count, tolerance, output)
+            }
+            TypedSpooledCollections::MultiPoint(collections) => {
+                geojson_page_features(collections, start_index, count, tolerance, output)
+            }
+            TypedSpooledCollections::MultiLineString(collections) => {
+                geojson_page_features(collections, start_index, count, tolerance, output)
+            }
+            TypedSpooledCollections::MultiPolygon(collections) => {
+                geojson_page_features(collections, start_index, count, tolerance, output)
+            }
         }
-    }?;
+        .context(error::Operator)?;
 
-    Ok(HttpResponse::Ok().json(json))
+        Ok(json!({
+            "type": "FeatureCollection",
+            "numberMatched": self.len(),
+            "numberReturned": features.len(),
+            "features": features,
+        }))
+    }
+}
+
+fn geojson_page_features<G>(
+    collections: &SpooledFeatureCollections<G>,
+    start_index: usize,
+    count: usize,
+    simplification_tolerance: Option<f64>,
+    output: GeoJsonOutput,
+) -> geoengine_operators::util::Result<Vec<serde_json::Value>>
+where
+    G: Geometry + ArrowTyped,
+    for<'c> FeatureCollection<G>:
+        ToGeoJson<'c> + Simplify<Out = FeatureCollection<G>> + ReprojectOutput,
+{
+    let mut features = Vec::new();
+
+    for collection in collections.page(start_index, count)? {
+        features.append(&mut collection_to_geojson_features(
+            &collection,
+            simplification_tolerance,
+            output,
+        )?);
+    }
+
+    Ok(features)
 }
 
 // Define GeoJson types purely for modelling the output of the WFS handler for OpenAPI
@@ -561,56 +929,193 @@ pub enum FeatureType {
     MultiPolygon,
 }
 
+/// How the coordinates of the features are written to the `GeoJSON` output
+#[derive(Debug, Clone, Copy)]
+struct GeoJsonOutput {
+    /// the spatial references of the query and of the output, if they differ
+    reprojection: Option<(SpatialReference, SpatialReference)>,
+    /// the number of decimal places of the coordinates
+    precision: Option<u8>,
+}
+
+/// Reprojects the features from the spatial reference of the query to the spatial reference of the output
+trait ReprojectOutput: Sized {
+    fn reproject_output(
+        self,
+        from: SpatialReference,
+        to: SpatialReference,
+    ) -> geoengine_datatypes::util::Result<Self>;
+}
+
+impl ReprojectOutput for DataCollection {
+    fn reproject_output(
+        self,
+        _from: SpatialReference,
+        _to: SpatialReference,
+    ) -> geoengine_datatypes::util::Result<Self> {
+        Ok(self)
+    }
+}
+
+macro_rules! impl_reproject_output {
+    ($($collection:ty),*) => {
+        $(
+            impl ReprojectOutput for $collection {
+                fn reproject_output(
+                    self,
+                    from: SpatialReference,
+                    to: SpatialReference,
+                ) -> geoengine_datatypes::util::Result<Self> {
+                    CoordinateProjector::from_known_srs(from, to)
+                        .and_then(|projector| self.reproject(&projector))
+                }
+            }
+        )*
+    };
+}
+
+impl_reproject_output!(
+    MultiPointCollection,
+    MultiLineStringCollection,
+    MultiPolygonCollection
+);
+
+/// Rounds all numbers of the (nested) coordinate arrays to the decimal places of `factor`, e.g., `1000` for three places
+fn round_coordinates(coordinates: &mut serde_json::Value, factor: f64) {
+    match coordinates {
+        serde_json::Value::Number(number) => {
+            if let Some(rounded) = number
+                .as_f64()
+                .and_then(|value| serde_json::Number::from_f64((value * factor).round() / factor))
+            {
+                *number = rounded;
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                round_coordinates(value, factor);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Converts the features of a collection to `GeoJSON` and simplifies the geometries if a `simplification_tolerance` is given.
+/// The simplification is performed before the features are reprojected to the output spatial reference.
+fn collection_to_geojson_features<G>(
+    collection: &FeatureCollection<G>,
+    simplification_tolerance: Option<f64>,
+    output: GeoJsonOutput,
+) -> geoengine_operators::util::Result<Vec<serde_json::Value>>
+where
+    G: Geometry,
+    for<'c> FeatureCollection<G>:
+        ToGeoJson<'c> + Simplify<Out = FeatureCollection<G>> + ReprojectOutput,
+{
+    let collection = match simplification_tolerance {
+        Some(tolerance) => collection.simplify(tolerance)?,
+        None => collection.clone(),
+    };
+    let collection = match output.reprojection {
+        Some((from, to)) => collection.reproject_output(from, to)?,
+        None => collection,
+    };
+
+    // TODO: avoid parsing the generated json
+    let mut json: serde_json::Value =
+        serde_json::from_str(&collection.to_geo_json()).expect("to_geojson is correct");
+    let features = json
+        .get_mut("features")
+        .and_then(serde_json::Value::as_array_mut)
+        .expect("to geojson is correct");
+
+    if let Some(precision) = output.precision {
+        let factor = 10_f64.powi(i32::from(precision));
+
+        for coordinates in features
+            .iter_mut()
+            .filter_map(|feature| feature.pointer_mut("/geometry/coordinates"))
+        {
+            round_coordinates(coordinates, factor);
+        }
+    }
+
+    Ok(std::mem::take(features))
+}
+
+/// Collects the query result as `GeoJSON`
 async fn vector_stream_to_geojson<G, C: QueryContext + 'static>(
     processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
     query_rect: VectorQueryRectangle,
     mut query_ctx: C,
+    simplification_tolerance: Option<f64>,
+    output: GeoJsonOutput,
+    max_features: Option<usize>,
     conn_closed: BoxFuture<'_, ()>,
 ) -> Result<serde_json::Value>
 where
-    G: Geometry + 'static,
-    for<'c> FeatureCollection<G>: ToGeoJson<'c>,
+    G: Geometry + ArrowTyped + 'static,
+    for<'c> FeatureCollection<G>:
+        ToGeoJson<'c> + Simplify<Out = FeatureCollection<G>> + ReprojectOutput,
 {
     let query_abort_trigger = query_ctx.abort_trigger()?;
+    let messages = query_ctx.subscribe_messages();
 
     let features: Vec<serde_json::Value> = Vec::new();
+    let warnings: Vec<String> = Vec::new();
     // TODO: more efficient merging of the partial feature collections
-    let stream = processor.query(query_rect, &query_ctx).await?;
-
-    let features: BoxFuture<geoengine_operators::util::Result<Vec<serde_json::Value>>> =
-        Box::pin(stream.fold(
-            geoengine_operators::util::Result::<Vec<serde_json::Value>>::Ok(features),
-            |output, collection| async move {
-                match (output, collection) {
-                    (Ok(mut output), Ok(collection)) => {
-                        // TODO: avoid parsing the generated json
-                        let mut json: serde_json::Value =
-                            serde_json::from_str(&collection.to_geo_json())
-                                .expect("to_geojson is correct");
-                        let more_features = json
-                            .get_mut("features")
-                            .expect("to_geojson is correct")
-                            .as_array_mut()
-                            .expect("to geojson is correct");
-
-                        output.append(more_features);
-                        Ok(output)
-                    }
-                    (Err(error), _) | (_, Err(error)) => Err(error),
+    let stream = processor
+        .query(query_rect, &query_ctx)
+        .await?
+        .limit_features(max_features.unwrap_or(usize::MAX));
+    let truncated = stream.truncation_flag();
+    let stream = messages.enrich(stream);
+
+    let features: BoxFuture<
+        geoengine_operators::util::Result<(Vec<serde_json::Value>, Vec<String>)>,
+    > = Box::pin(stream.fold(
+        geoengine_operators::util::Result::<(Vec<serde_json::Value>, Vec<String>)>::Ok((
+            features, warnings,
+        )),
+        |result, item| async move {
+            let (mut features, mut warnings) = result?;
+            match item? {
+                QueryStreamItem::Data(collection) => {
+                    features.append(&mut collection_to_geojson_features(
+                        &collection,
+                        simplification_tolerance,
+                        output,
+                    )?);
                 }
-            },
-        ));
+                QueryStreamItem::Metadata(QueryMetadata::Warning { message }) => {
+                    warnings.push(message);
+                }
+                QueryStreamItem::Metadata(QueryMetadata::ColumnsChanged { .. })
+                | QueryStreamItem::Progress(_) => {}
+            }
+            Ok((features, warnings))
+        },
+    ));
 
-    let features = abortable_query_execution(features, conn_closed, query_abort_trigger).await?;
+    let (features, warnings) =
+        abortable_query_execution(features, conn_closed, query_abort_trigger).await?;
 
     let mut output = json!({
         "type": "FeatureCollection"
     });
 
-    output
-        .as_object_mut()
-        .expect("as defined")
-        .insert("features".into(), serde_json::Value::Array(features));
+    let output_object = output.as_object_mut().expect("as defined");
+    output_object.insert("features".into(), serde_json::Value::Array(features));
+
+    // warnings of the query are forwarded as a foreign member of the feature collection
+    if !warnings.is_empty() {
+        output_object.insert("warnings".into(), json!(warnings));
+    }
+
+    // the client is informed that there are more features than `maxFeatures`
+    if truncated.is_truncated() {
+        output_object.insert("truncated".into(), json!(true));
+    }
 
     Ok(output)
 }
@@ -671,10 +1176,12 @@ mod tests {
     use super::*;
 
     use crate::api::model::datatypes::{DataId, DatasetId};
-    use crate::contexts::{Session, SimpleContext};
+    use crate::contexts::SimpleContext;
     use crate::datasets::storage::{DatasetDefinition, DatasetStore};
     use crate::handlers::ErrorResponse;
-    use crate::util::tests::{check_allowed_http_methods, read_body_string, send_test_request};
+    use crate::util::tests::{
+        check_allowed_http_methods, read_body_json, read_body_string, send_test_request,
+    };
     use crate::util::user_input::UserInput;
     use crate::{contexts::InMemoryContext, workflows::workflow::Workflow};
     use actix_web::dev::ServiceResponse;
@@ -684,7 +1191,8 @@ mod tests {
     use geoengine_datatypes::raster::{GridShape2D, TilingSpecification};
     use geoengine_datatypes::test_data;
     use geoengine_datatypes::util::test::TestDefault;
-    use geoengine_operators::engine::TypedOperator;
+    use geoengine_operators::engine::{TypedOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
     use geoengine_operators::source::CsvSourceParameters;
     use geoengine_operators::source::{CsvGeometrySpecification, CsvSource, CsvTimeSpecification};
     use serde_json::json;
@@ -818,7 +1326,7 @@ x;y
 
         let workflow_id = ctx
             .workflow_registry_ref()
-            .register(workflow)
+            .register(&*ctx.default_session_ref().await, workflow)
             .await
             .unwrap();
 
@@ -884,7 +1392,7 @@ x;y
 
         let id = ctx
             .workflow_registry_ref()
-            .register(workflow.clone())
+            .register(&*ctx.default_session_ref().await, workflow.clone())
             .await
             .unwrap();
 
@@ -943,6 +1451,100 @@ x;y
         );
     }
 
+    #[tokio::test]
+    async fn get_feature_with_output_crs_and_precision() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(1.123_456, 2.987_654).into()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let id = ctx
+            .workflow_registry_ref()
+            .register(&*ctx.default_session_ref().await, workflow)
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/wfs/{id}?request=GetFeature&service=WFS&version=2.0.0&typeNames={id}&bbox=-90,-180,90,180&srsName=EPSG:4326&outputCrs=EPSG:3857&precision=2", id = id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(
+            body.pointer("/features/0/geometry/coordinates"),
+            Some(&json!([125_062.55, 332_734.94]))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_feature_pages() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![
+                            (0.0, 0.0).into(),
+                            (1.0, 1.0).into(),
+                            (2.0, 2.0).into(),
+                            (3.0, 3.0).into(),
+                            (4.0, 4.0).into(),
+                        ],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let id = ctx
+            .workflow_registry_ref()
+            .register(&*ctx.default_session_ref().await, workflow)
+            .await
+            .unwrap();
+
+        let mut coordinates = vec![];
+
+        for start_index in [0, 2, 4] {
+            let req = test::TestRequest::get()
+                .uri(&format!("/wfs/{id}?request=GetFeature&service=WFS&version=2.0.0&typeNames={id}&bbox=-90,-180,90,180&srsName=EPSG:4326&startIndex={start_index}&count=2", id = id, start_index = start_index))
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+            let res = send_test_request(req, ctx.clone()).await;
+
+            assert_eq!(res.status(), 200);
+
+            let body: serde_json::Value = test::read_body_json(res).await;
+            assert_eq!(body["numberMatched"], json!(5));
+
+            for feature in body["features"].as_array().unwrap() {
+                coordinates.push(feature["geometry"]["coordinates"].clone());
+            }
+        }
+
+        assert_eq!(
+            coordinates,
+            vec![
+                json!([0.0, 0.0]),
+                json!([1.0, 1.0]),
+                json!([2.0, 2.0]),
+                json!([3.0, 3.0]),
+                json!([4.0, 4.0]),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn get_feature_registry_invalid_method() {
         check_allowed_http_methods(get_feature_registry_test_helper, &[Method::GET]).await;
@@ -968,6 +1570,13 @@ x;y
     }
 
     async fn get_feature_json_test_helper(method: Method) -> ServiceResponse {
+        get_feature_csv_test_helper(method, &[]).await
+    }
+
+    async fn get_feature_csv_test_helper(
+        method: Method,
+        additional_params: &[(&str, &str)],
+    ) -> ServiceResponse {
         let mut temp_file = tempfile::NamedTempFile::new().unwrap();
         write!(
             temp_file,
@@ -1000,7 +1609,7 @@ x;y
 
         let workflow_id = ctx
             .workflow_registry_ref()
-            .register(workflow)
+            .register(&*ctx.default_session_ref().await, workflow)
             .await
             .unwrap();
 
@@ -1012,6 +1621,7 @@ x;y
             ("bbox", "-90,-180,90,180"),
             ("srsName", "EPSG:4326"),
         ];
+        let params = params.iter().chain(additional_params).collect::<Vec<_>>();
         let req = test::TestRequest::with_uri(&format!(
             "/wfs/{}?{}",
             workflow_id,
@@ -1073,6 +1683,42 @@ x;y
         );
     }
 
+    #[tokio::test]
+    async fn get_feature_json_max_features() {
+        let res = get_feature_csv_test_helper(Method::GET, &[("maxFeatures", "2")]).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers()
+                .get(TRUNCATED_HEADER)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "true"
+        );
+
+        let body = read_body_json(res).await;
+        assert_eq!(body["truncated"], true);
+        assert_eq!(
+            body["features"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|feature| feature["geometry"]["coordinates"].clone())
+                .collect::<Vec<_>>(),
+            vec![json!([0.0, 1.0]), json!([2.0, 3.0])]
+        );
+
+        let res = get_feature_csv_test_helper(Method::GET, &[("maxFeatures", "3")]).await;
+
+        assert_eq!(res.status(), 200);
+        assert!(res.headers().get(TRUNCATED_HEADER).is_none());
+
+        let body = read_body_json(res).await;
+        assert!(body.get("truncated").is_none());
+        assert_eq!(body["features"].as_array().unwrap().len(), 3);
+    }
+
     #[tokio::test]
     async fn get_feature_json_invalid_method() {
         check_allowed_http_methods(get_feature_json_test_helper, &[Method::GET]).await;
@@ -1187,7 +1833,7 @@ x;y
 
         let workflow_id = ctx
             .workflow_registry_ref()
-            .register(workflow)
+            .register(&*ctx.default_session_ref().await, workflow)
             .await
             .unwrap();
 
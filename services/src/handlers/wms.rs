@@ -2,29 +2,47 @@ use actix_web::{web, FromRequest, HttpRequest, HttpResponse};
 use reqwest::Url;
 use snafu::{ensure, ResultExt};
 
+use futures::future::BoxFuture;
+use geoengine_datatypes::collections::{
+    MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+};
 use geoengine_datatypes::primitives::{
-    AxisAlignedRectangle, RasterQueryRectangle, SpatialPartition2D,
+    AxisAlignedRectangle, BoundingBox2D, RasterQueryRectangle, SpatialPartition2D,
+    VectorQueryRectangle,
 };
 use geoengine_datatypes::{operations::image::Colorizer, primitives::SpatialResolution};
 use utoipa::openapi::{ObjectBuilder, SchemaFormat, SchemaType};
 use utoipa::ToSchema;
 
 use crate::api::model::datatypes::{SpatialReference, SpatialReferenceOption, TimeInterval};
+use crate::contexts::Session;
 use crate::error;
 use crate::error::Result;
 use crate::handlers::Context;
 use crate::ogc::util::{ogc_endpoint_url, OgcProtocol, OgcRequestGuard};
+use crate::ogc::wms::meta_tiling::{decode_meta_tile, MetaTile, MetaTileKey, META_TILE_CACHE};
 use crate::ogc::wms::request::{GetCapabilities, GetLegendGraphic, GetMap};
+use crate::ogc::wms::vector_rendering::{vector_stream_to_png_bytes, RenderCollection};
+use crate::projects::Symbology;
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::defaults::default_spatial_reference;
 use crate::util::server::{connection_closed, not_implemented_handler};
+use crate::workflows::query_log::{RecordedQuery, QUERY_LOG};
 use crate::workflows::registry::WorkflowRegistry;
-use crate::workflows::workflow::WorkflowId;
+use crate::workflows::style_preset::StylePresetName;
+use crate::workflows::workflow::{Workflow, WorkflowId};
 
-use geoengine_operators::engine::{ExecutionContext, ResultDescriptor};
-use geoengine_operators::processing::{InitializedRasterReprojection, ReprojectionParams};
+use geoengine_operators::engine::{
+    ExecutionContext, RasterOperator, ResultDescriptor, TypedOperator, TypedVectorQueryProcessor,
+    VectorOperator,
+};
+use geoengine_operators::processing::{
+    InitializedRasterReprojection, InitializedVectorReprojection, ReprojectionParams,
+};
 use geoengine_operators::{
-    call_on_generic_raster_processor, util::raster_stream_to_png::raster_stream_to_png_bytes,
+    call_on_generic_raster_processor,
+    util::{catch_panic, raster_stream_to_png::raster_stream_to_png_bytes},
 };
 use std::str::FromStr;
 use std::time::Duration;
@@ -138,20 +156,32 @@ where
     let workflow_id = workflow.into_inner();
     let wms_url = wms_url(workflow_id)?;
 
-    let workflow = ctx.workflow_registry_ref().load(&workflow_id).await?;
+    let workflow = ctx
+        .workflow_registry_ref()
+        .load(&session, &workflow_id)
+        .await?;
 
     let exe_ctx = ctx.execution_context(session)?;
-    let operator = workflow
-        .operator
-        .get_raster()
-        .context(error::Operator)?
-        .initialize(&exe_ctx)
-        .await
-        .context(error::Operator)?;
-
-    let result_descriptor = operator.result_descriptor();
 
-    let spatial_reference: SpatialReferenceOption = result_descriptor.spatial_reference.into();
+    // vector workflows are rendered with a symbology
+    let spatial_reference: SpatialReferenceOption = match workflow.operator {
+        TypedOperator::Vector(operator) => operator
+            .initialize(&exe_ctx)
+            .await
+            .context(error::Operator)?
+            .result_descriptor()
+            .spatial_reference
+            .into(),
+        operator => operator
+            .get_raster()
+            .context(error::Operator)?
+            .initialize(&exe_ctx)
+            .await
+            .context(error::Operator)?
+            .result_descriptor()
+            .spatial_reference
+            .into(),
+    };
     let spatial_reference: Option<SpatialReference> = spatial_reference.into();
     let spatial_reference = spatial_reference.ok_or(error::Error::MissingSpatialReference)?;
 
@@ -257,24 +287,162 @@ async fn wms_map_handler<C: Context>(
 
     // TODO: validate request further
 
+    let wms_config = config::get_config_element::<config::Wms>()?;
+
     let conn_closed = connection_closed(
         &req,
-        config::get_config_element::<config::Wms>()?
-            .request_timeout_seconds
-            .map(Duration::from_secs),
+        wms_config.request_timeout_seconds.map(Duration::from_secs),
     );
 
+    let workflow_id = WorkflowId::from_str(&request.layers)?;
+
     let workflow = ctx
         .workflow_registry_ref()
-        .load(&WorkflowId::from_str(&request.layers)?)
+        .load(&session, &workflow_id)
         .await?;
 
-    let operator = workflow.operator.get_raster().context(error::Operator)?;
+    let request_spatial_ref: SpatialReference = request
+        .crs
+        .unwrap_or_else(|| default_spatial_reference(&session).into());
+
+    let extent = MapExtent {
+        spatial_reference: request_spatial_ref,
+        bbox: request.bbox.bounds(request_spatial_ref)?,
+        time: request.time.unwrap_or_else(default_time_from_config).into(),
+        width: request.width,
+        height: request.height,
+    };
+
+    // tiled clients request many small tiles of the same area, so they are cut from larger, cached meta-tiles
+    let meta_tile = if META_TILE_CACHE.is_enabled() {
+        MetaTile::for_request(
+            extent.bbox,
+            extent.width,
+            extent.height,
+            extent.spatial_reference.into(),
+            wms_config.meta_tile_size,
+        )
+    } else {
+        None
+    };
+
+    let image_bytes = if let Some(meta_tile) = meta_tile {
+        let key = MetaTileKey::new(
+            session.id(),
+            workflow_id,
+            &request.styles,
+            extent.spatial_reference.into(),
+            &meta_tile,
+            extent.time,
+        );
+        let meta_extent = MapExtent {
+            bbox: meta_tile.bbox,
+            width: meta_tile.width,
+            height: meta_tile.height,
+            ..extent
+        };
+
+        let image = META_TILE_CACHE
+            .get_or_render(key, || async {
+                let meta_tile_bytes = map_png(
+                    workflow.operator,
+                    &meta_extent,
+                    &request.styles,
+                    &workflow_id,
+                    ctx.get_ref(),
+                    session,
+                    conn_closed,
+                )
+                .await?;
+                decode_meta_tile(&meta_tile_bytes)
+            })
+            .await?;
+
+        meta_tile.crop_png(&image)?
+    } else {
+        map_png(
+            workflow.operator,
+            &extent,
+            &request.styles,
+            &workflow_id,
+            ctx.get_ref(),
+            session,
+            conn_closed,
+        )
+        .await?
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(mime::IMAGE_PNG)
+        .body(image_bytes))
+}
+
+/// Renders a workflow as PNG with the symbology or colorizer of `styles`
+async fn map_png<C: Context>(
+    operator: TypedOperator,
+    extent: &MapExtent,
+    styles: &str,
+    workflow_id: &WorkflowId,
+    ctx: &C,
+    session: C::Session,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>> {
+    match operator {
+        TypedOperator::Vector(operator) => {
+            let symbology = symbology_from_style(styles, ctx, &session, workflow_id).await?;
+            catch_panic(vector_map_png(
+                operator,
+                extent,
+                symbology,
+                ctx,
+                session,
+                conn_closed,
+            ))
+            .await
+        }
+        operator => {
+            let operator = operator.get_raster().context(error::Operator)?;
+            let colorizer = colorizer_from_style(styles, ctx, &session, workflow_id).await?;
+            catch_panic(raster_map_png(
+                operator,
+                extent,
+                colorizer,
+                ctx,
+                session,
+                conn_closed,
+            ))
+            .await
+        }
+    }
+}
+
+/// The area, time and image size of a rendered map
+pub(crate) struct MapExtent {
+    pub spatial_reference: SpatialReference,
+    pub bbox: BoundingBox2D,
+    pub time: geoengine_datatypes::primitives::TimeInterval,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Renders a raster workflow as PNG using the `colorizer` or a default one
+pub(crate) async fn raster_map_png<C: Context>(
+    operator: Box<dyn RasterOperator>,
+    extent: &MapExtent,
+    colorizer: Option<Colorizer>,
+    ctx: &C,
+    session: C::Session,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>> {
+    let recording = QUERY_LOG.start_recording(|| TypedOperator::Raster(operator.clone()));
+
+    let workflow_id = WorkflowId::from_hash(&Workflow {
+        operator: TypedOperator::Raster(operator.clone()),
+    });
 
     let execution_context = ctx.execution_context(session)?;
 
     let initialized = operator
-        .clone()
         .initialize(&execution_context)
         .await
         .context(error::Operator)?;
@@ -285,22 +453,18 @@ async fn wms_map_handler<C: Context>(
     let workflow_spatial_ref: Option<SpatialReference> = workflow_spatial_ref.into();
     let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
 
-    // TODO: use a default spatial reference if it is not set?
-    let request_spatial_ref: SpatialReference =
-        request.crs.ok_or(error::Error::MissingSpatialReference)?;
-
     // perform reprojection if necessary
-    let initialized = if request_spatial_ref == workflow_spatial_ref {
+    let initialized = if extent.spatial_reference == workflow_spatial_ref {
         initialized
     } else {
         log::debug!(
             "WMS query srs: {}, workflow srs: {} --> injecting reprojection",
-            request_spatial_ref,
+            extent.spatial_reference,
             workflow_spatial_ref
         );
         let irp = InitializedRasterReprojection::try_new_with_input(
             ReprojectionParams {
-                target_spatial_reference: request_spatial_ref.into(),
+                target_spatial_reference: extent.spatial_reference.into(),
             },
             initialized,
             execution_context.tiling_specification(),
@@ -310,34 +474,166 @@ async fn wms_map_handler<C: Context>(
         Box::new(irp)
     };
 
-    let processor = initialized.query_processor().context(error::Operator)?;
+    // identical tiles that are requested at the same time, e.g., by several map clients, are computed once
+    let processor = initialized
+        .query_processor()
+        .context(error::Operator)?
+        .coalesced(format!("{workflow_id}:{}", extent.spatial_reference));
 
-    let query_bbox: SpatialPartition2D = request.bbox.bounds(request_spatial_ref)?;
-    let x_query_resolution = query_bbox.size_x() / f64::from(request.width);
-    let y_query_resolution = query_bbox.size_y() / f64::from(request.height);
+    let query_bbox =
+        SpatialPartition2D::from_min_max(extent.bbox.lower_left(), extent.bbox.upper_right())?;
+    let x_query_resolution = query_bbox.size_x() / f64::from(extent.width);
+    let y_query_resolution = query_bbox.size_y() / f64::from(extent.height);
 
     let query_rect = RasterQueryRectangle {
         spatial_bounds: query_bbox,
-        time_interval: request.time.unwrap_or_else(default_time_from_config).into(),
+        time_interval: extent.time,
         spatial_resolution: SpatialResolution::new_unchecked(
             x_query_resolution,
             y_query_resolution,
         ),
     };
 
-    let colorizer = colorizer_from_style(&request.styles)?;
-
     let query_ctx = ctx.query_context()?;
 
     let image_bytes = call_on_generic_raster_processor!(
         processor,
         p =>
-            raster_stream_to_png_bytes(p, query_rect, query_ctx, request.width, request.height, request.time.map(Into::into), colorizer, conn_closed).await
+            raster_stream_to_png_bytes(p, query_rect, query_ctx, extent.width, extent.height, Some(extent.time), colorizer, conn_closed).await
     ).map_err(error::Error::from)?;
 
-    Ok(HttpResponse::Ok()
-        .content_type(mime::IMAGE_PNG)
-        .body(image_bytes))
+    if let Some(recording) = recording {
+        recording
+            .finish(
+                extent.spatial_reference.into(),
+                RecordedQuery::Raster(query_rect),
+            )
+            .await;
+    }
+
+    Ok(image_bytes)
+}
+
+/// Renders the features of a vector workflow as PNG using the `symbology` or a default one
+pub(crate) async fn vector_map_png<C: Context>(
+    operator: Box<dyn VectorOperator>,
+    extent: &MapExtent,
+    symbology: Option<Symbology>,
+    ctx: &C,
+    session: C::Session,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>> {
+    let recording = QUERY_LOG.start_recording(|| TypedOperator::Vector(operator.clone()));
+
+    let workflow_id = WorkflowId::from_hash(&Workflow {
+        operator: TypedOperator::Vector(operator.clone()),
+    });
+
+    let execution_context = ctx.execution_context(session)?;
+
+    let initialized = operator
+        .initialize(&execution_context)
+        .await
+        .context(error::Operator)?;
+
+    // handle request and workflow crs matching
+    let workflow_spatial_ref: SpatialReferenceOption =
+        initialized.result_descriptor().spatial_reference().into();
+    let workflow_spatial_ref: Option<SpatialReference> = workflow_spatial_ref.into();
+    let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
+
+    // perform reprojection if necessary
+    let initialized = if extent.spatial_reference == workflow_spatial_ref {
+        initialized
+    } else {
+        log::debug!(
+            "WMS query srs: {}, workflow srs: {} --> injecting reprojection",
+            extent.spatial_reference,
+            workflow_spatial_ref
+        );
+        let ivp = InitializedVectorReprojection::try_new_with_input(
+            ReprojectionParams {
+                target_spatial_reference: extent.spatial_reference.into(),
+            },
+            initialized,
+        )
+        .context(error::Operator)?;
+
+        Box::new(ivp)
+    };
+
+    // identical tiles that are requested at the same time, e.g., by several map clients, are computed once
+    let processor = initialized
+        .query_processor()
+        .context(error::Operator)?
+        .coalesced(format!("{workflow_id}:{}", extent.spatial_reference));
+
+    let x_query_resolution = extent.bbox.size_x() / f64::from(extent.width);
+    let y_query_resolution = extent.bbox.size_y() / f64::from(extent.height);
+
+    let query_rect = VectorQueryRectangle {
+        spatial_bounds: extent.bbox,
+        time_interval: extent.time,
+        spatial_resolution: SpatialResolution::new_unchecked(
+            x_query_resolution,
+            y_query_resolution,
+        ),
+    };
+
+    let query_ctx = ctx.query_context()?;
+
+    let image_bytes = match processor {
+        TypedVectorQueryProcessor::Data(_) => {
+            return Err(error::Error::VectorRenderingRequiresGeometries);
+        }
+        TypedVectorQueryProcessor::MultiPoint(p) => {
+            vector_stream_to_png_bytes(
+                p,
+                query_rect,
+                query_ctx,
+                extent.width,
+                extent.height,
+                MultiPointCollection::symbology(symbology)?,
+                conn_closed,
+            )
+            .await?
+        }
+        TypedVectorQueryProcessor::MultiLineString(p) => {
+            vector_stream_to_png_bytes(
+                p,
+                query_rect,
+                query_ctx,
+                extent.width,
+                extent.height,
+                MultiLineStringCollection::symbology(symbology)?,
+                conn_closed,
+            )
+            .await?
+        }
+        TypedVectorQueryProcessor::MultiPolygon(p) => {
+            vector_stream_to_png_bytes(
+                p,
+                query_rect,
+                query_ctx,
+                extent.width,
+                extent.height,
+                MultiPolygonCollection::symbology(symbology)?,
+                conn_closed,
+            )
+            .await?
+        }
+    };
+
+    if let Some(recording) = recording {
+        recording
+            .finish(
+                extent.spatial_reference.into(),
+                RecordedQuery::Vector(query_rect),
+            )
+            .await;
+    }
+
+    Ok(image_bytes)
 }
 
 pub struct MapResponse {}
@@ -351,13 +647,63 @@ impl ToSchema for MapResponse {
     }
 }
 
-fn colorizer_from_style(styles: &str) -> Result<Option<Colorizer>> {
+/// Parses the colorizer of a `custom:{json}` style or loads it from a `preset:{name}` style of the workflow
+async fn colorizer_from_style<C: Context>(
+    styles: &str,
+    ctx: &C,
+    session: &C::Session,
+    workflow: &WorkflowId,
+) -> Result<Option<Colorizer>> {
+    if let Some(name) = styles.strip_prefix("preset:") {
+        return match load_style_preset(name, ctx, session, workflow).await? {
+            Symbology::Raster(symbology) => Ok(Some(symbology.colorizer.try_into()?)),
+            _ => Err(error::Error::StylePresetMismatch {
+                name: name.to_string(),
+                workflow_type: "raster".to_string(),
+            }),
+        };
+    }
+
+    match styles.strip_prefix("custom:") {
+        None => Ok(None),
+        Some(suffix) => serde_json::from_str(suffix).map_err(error::Error::from),
+    }
+}
+
+/// Parses the symbology of a `custom:{json}` style or loads it from a `preset:{name}` style of the workflow
+async fn symbology_from_style<C: Context>(
+    styles: &str,
+    ctx: &C,
+    session: &C::Session,
+    workflow: &WorkflowId,
+) -> Result<Option<Symbology>> {
+    if let Some(name) = styles.strip_prefix("preset:") {
+        return match load_style_preset(name, ctx, session, workflow).await? {
+            Symbology::Raster(_) => Err(error::Error::StylePresetMismatch {
+                name: name.to_string(),
+                workflow_type: "vector".to_string(),
+            }),
+            symbology => Ok(Some(symbology)),
+        };
+    }
+
     match styles.strip_prefix("custom:") {
         None => Ok(None),
         Some(suffix) => serde_json::from_str(suffix).map_err(error::Error::from),
     }
 }
 
+async fn load_style_preset<C: Context>(
+    name: &str,
+    ctx: &C,
+    session: &C::Session,
+    workflow: &WorkflowId,
+) -> Result<Symbology> {
+    ctx.workflow_registry_ref()
+        .style_preset(session, workflow, &StylePresetName::from(name))
+        .await
+}
+
 /// Get WMS Legend Graphic
 #[utoipa::path(
     tag = "OGC WMS",
@@ -415,9 +761,12 @@ mod tests {
         InMemoryContext, Session, SimpleContext, SimpleSession, /*SimpleSession*/
     };
     use crate::handlers::ErrorResponse;
+    use crate::projects::{ColorParam, NumberParam, PointSymbology, RasterSymbology, StrokeParam};
     use crate::util::tests::{
         check_allowed_http_methods, register_ndvi_workflow_helper, send_test_request,
     };
+    use crate::util::user_input::UserInput;
+    use crate::workflows::workflow::Workflow;
     use actix_web::dev::ServiceResponse;
     use actix_web::http::header;
     use actix_web::http::Method;
@@ -426,6 +775,7 @@ mod tests {
     use geoengine_datatypes::raster::{GridShape2D, TilingSpecification};
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_operators::engine::{ExecutionContext, RasterQueryProcessor};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
     use geoengine_operators::source::GdalSourceProcessor;
     use geoengine_operators::util::gdal::create_ndvi_meta_data;
     use std::convert::TryInto;
@@ -518,6 +868,7 @@ mod tests {
         let gdal_source = GdalSourceProcessor::<u8> {
             tiling_specification: exe_ctx.tiling_specification(),
             meta_data: Box::new(create_ndvi_meta_data()),
+            io_thread_pool: exe_ctx.io_thread_pool().clone(),
             _phantom_data: PhantomData,
         };
 
@@ -735,6 +1086,78 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_map_style_preset() {
+        let exe_ctx_tiling_spec = TilingSpecification {
+            origin_coordinate: (0., 0.).into(),
+            tile_size_in_pixels: GridShape2D::new([600, 600]),
+        };
+
+        let ctx = InMemoryContext::new_with_context_spec(
+            exe_ctx_tiling_spec,
+            TestDefault::test_default(),
+        );
+
+        let session = ctx.default_session_ref().await.clone();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let colorizer = Colorizer::linear_gradient(
+            vec![
+                (0.0, RgbaColor::white()).try_into().unwrap(),
+                (1.0, RgbaColor::black()).try_into().unwrap(),
+            ],
+            RgbaColor::transparent(),
+            RgbaColor::pink(),
+        )
+        .unwrap();
+
+        ctx.workflow_registry_ref()
+            .set_style_preset(
+                &session,
+                &id,
+                StylePresetName::from("ndvi").validated().unwrap(),
+                Symbology::Raster(RasterSymbology {
+                    opacity: 1.0,
+                    colorizer: colorizer.into(),
+                }),
+            )
+            .await
+            .unwrap();
+
+        let params = &[
+            ("request", "GetMap"),
+            ("service", "WMS"),
+            ("version", "1.3.0"),
+            ("layers", &id.to_string()),
+            ("bbox", "20,-10,80,50"),
+            ("width", "600"),
+            ("height", "600"),
+            ("crs", "EPSG:4326"),
+            ("styles", "preset:ndvi"),
+            ("format", "image/png"),
+            ("time", "2014-01-01T00:00:00.0Z"),
+        ];
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/wms/{}?{}",
+                id,
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let image_bytes = actix_web::test::read_body(res).await;
+
+        assert_eq!(
+            include_bytes!("../../../test_data/wms/get_map_colorizer.png") as &[u8],
+            image_bytes
+        );
+    }
+
     #[tokio::test]
     async fn it_zoomes_very_far() {
         let ctx = InMemoryContext::test_default();
@@ -783,4 +1206,123 @@ mod tests {
 
         assert_eq!(res.status(), 200);
     }
+
+    #[tokio::test]
+    async fn it_cuts_aligned_tiles_from_meta_tiles() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let get_map = |bbox: &str, size: &str| {
+            let params = &[
+                ("request", "GetMap"),
+                ("service", "WMS"),
+                ("version", "1.3.0"),
+                ("layers", &id.to_string()),
+                ("bbox", bbox),
+                ("width", size),
+                ("height", size),
+                ("crs", "EPSG:4326"),
+                ("styles", ""),
+                ("format", "image/png"),
+                ("time", "2014-04-01T12:00:00.0Z"),
+            ];
+
+            actix_web::test::TestRequest::get()
+                .uri(&format!(
+                    "/wms/{}?{}",
+                    id,
+                    serde_urlencoded::to_string(params).unwrap()
+                ))
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+        };
+
+        // the meta-tile of this area would exceed the area of use, so it is rendered directly
+        let res = send_test_request(get_map("0,0,90,90", "256"), ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        let whole_area = decode_meta_tile(&actix_web::test::read_body(res).await).unwrap();
+
+        // the lower left tile of the area
+        let res = send_test_request(get_map("0,0,22.5,22.5", "64"), ctx).await;
+        assert_eq!(res.status(), 200);
+        let tile = decode_meta_tile(&actix_web::test::read_body(res).await).unwrap();
+
+        assert_eq!(
+            tile,
+            image::imageops::crop_imm(&whole_area, 0, 192, 64, 64).to_image()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_map_vector() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let id = ctx
+            .workflow_registry_ref()
+            .register(
+                &*ctx.default_session_ref().await,
+                Workflow {
+                    operator: MockPointSource {
+                        params: MockPointSourceParams {
+                            points: vec![(0.0, 0.1).into(), (1.0, 1.1).into()],
+                        },
+                    }
+                    .boxed()
+                    .into(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let symbology = Symbology::Point(PointSymbology {
+            radius: NumberParam::Static { value: 3 },
+            fill_color: ColorParam::Static {
+                color: RgbaColor::new(255, 0, 0, 255),
+            },
+            stroke: StrokeParam {
+                width: NumberParam::Static { value: 0 },
+                color: ColorParam::Static {
+                    color: RgbaColor::black(),
+                },
+            },
+            text: None,
+        });
+
+        let params = &[
+            ("request", "GetMap"),
+            ("service", "WMS"),
+            ("version", "1.3.0"),
+            ("layers", &id.to_string()),
+            ("bbox", "-1,-1,2,2"),
+            ("width", "30"),
+            ("height", "30"),
+            ("crs", "EPSG:4326"),
+            (
+                "styles",
+                &format!("custom:{}", serde_json::to_string(&symbology).unwrap()),
+            ),
+            ("format", "image/png"),
+        ];
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/wms/{}?{}",
+                id,
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let image_bytes = actix_web::test::read_body(res).await;
+        let image = image::load_from_memory_with_format(&image_bytes, image::ImageFormat::Png)
+            .unwrap()
+            .into_rgba8();
+
+        assert_eq!(image.get_pixel(10, 19).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+    }
 }
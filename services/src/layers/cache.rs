@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::api::model::datatypes::{DataId, DataProviderId, LayerId};
+use crate::datasets::listing::ProvenanceOutput;
+use crate::error::Result;
+use crate::util::user_input::Validated;
+
+use super::external::{DataProvider, DataProviderDefinition, ProviderCapabilities};
+use super::layer::{Layer, LayerCollection, LayerCollectionListOptions};
+use super::listing::{LayerCollectionId, LayerCollectionProvider};
+
+/// The time-to-live of cached provider responses in seconds. Zero disables the cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheTtlSeconds(pub u32);
+
+impl CacheTtlSeconds {
+    pub fn is_disabled(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<CacheTtlSeconds> for Duration {
+    fn from(ttl: CacheTtlSeconds) -> Self {
+        Duration::from_secs(u64::from(ttl.0))
+    }
+}
+
+/// Entries that expire after a fixed time
+#[derive(Debug)]
+struct TtlMap<K, V> {
+    entries: HashMap<K, (Instant, V)>,
+}
+
+impl<K, V> Default for TtlMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> TtlMap<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        self.entries
+            .get(key)
+            .filter(|(expires, _)| *expires > Instant::now())
+            .map(|(_, value)| value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V, ttl: Duration) {
+        let now = Instant::now();
+        self.entries.retain(|_, (expires, _)| *expires > now);
+        self.entries.insert(key, (now + ttl, value));
+    }
+}
+
+/// The cached responses of a single provider
+#[derive(Debug, Default)]
+struct ProviderCacheEntries {
+    collections: TtlMap<(LayerCollectionId, u32, u32), LayerCollection>,
+    layers: TtlMap<LayerId, Layer>,
+    mock_meta_data: TtlMap<
+        geoengine_datatypes::dataset::DataId,
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+    >,
+    ogr_meta_data: TtlMap<
+        geoengine_datatypes::dataset::DataId,
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+    >,
+    gdal_meta_data: TtlMap<
+        geoengine_datatypes::dataset::DataId,
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+    >,
+}
+
+/// Caches layer listings and meta data of providers whose definition specifies a time-to-live.
+/// Since providers are initialized for each request, the cache outlives the provider instances.
+#[derive(Debug, Default)]
+pub struct ProviderCache {
+    providers: RwLock<HashMap<DataProviderId, ProviderCacheEntries>>,
+}
+
+impl ProviderCache {
+    /// Initializes the provider and wraps it with the cache if the definition enables caching
+    pub async fn initialize(
+        self: &Arc<Self>,
+        definition: Box<dyn DataProviderDefinition>,
+    ) -> Result<Box<dyn DataProvider>> {
+        let id = definition.id();
+        let ttl = definition.cache_ttl();
+
+        let provider = definition.initialize().await?;
+
+        if ttl.is_disabled() {
+            return Ok(provider);
+        }
+
+        Ok(Box::new(CachedDataProvider {
+            id,
+            ttl: ttl.into(),
+            provider,
+            cache: self.clone(),
+        }))
+    }
+
+    /// Removes all cached responses of the provider
+    pub async fn invalidate(&self, id: DataProviderId) {
+        self.providers.write().await.remove(&id);
+    }
+
+    async fn get<T>(
+        &self,
+        id: DataProviderId,
+        f: impl FnOnce(&ProviderCacheEntries) -> Option<T>,
+    ) -> Option<T> {
+        self.providers.read().await.get(&id).and_then(f)
+    }
+
+    async fn insert(&self, id: DataProviderId, f: impl FnOnce(&mut ProviderCacheEntries)) {
+        f(self.providers.write().await.entry(id).or_default());
+    }
+}
+
+/// A provider that answers from the cache and only calls the wrapped provider on cache misses
+#[derive(Debug)]
+struct CachedDataProvider {
+    id: DataProviderId,
+    ttl: Duration,
+    provider: Box<dyn DataProvider>,
+    cache: Arc<ProviderCache>,
+}
+
+#[async_trait]
+impl DataProvider for CachedDataProvider {
+    async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput> {
+        self.provider.provenance(id).await
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.provider.capabilities()
+    }
+
+    async fn check_health(&self) -> Result<()> {
+        self.provider.check_health().await
+    }
+}
+
+#[async_trait]
+impl LayerCollectionProvider for CachedDataProvider {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        let key = (
+            collection.clone(),
+            options.user_input.offset,
+            options.user_input.limit,
+        );
+
+        if let Some(cached) = self.cache.get(self.id, |e| e.collections.get(&key)).await {
+            return Ok(cached);
+        }
+
+        let collection = self.provider.collection(collection, options).await?;
+
+        self.cache
+            .insert(self.id, |e| {
+                e.collections.insert(key, collection.clone(), self.ttl);
+            })
+            .await;
+
+        Ok(collection)
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        self.provider.root_collection_id().await
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        if let Some(cached) = self.cache.get(self.id, |e| e.layers.get(id)).await {
+            return Ok(cached);
+        }
+
+        let layer = self.provider.get_layer(id).await?;
+
+        self.cache
+            .insert(self.id, |e| {
+                e.layers.insert(id.clone(), layer.clone(), self.ttl);
+            })
+            .await;
+
+        Ok(layer)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for CachedDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        if let Some(cached) = self.cache.get(self.id, |e| e.mock_meta_data.get(id)).await {
+            return Ok(cached);
+        }
+
+        let meta_data: Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        > = self.provider.meta_data(id).await?;
+
+        self.cache
+            .insert(self.id, |e| {
+                e.mock_meta_data
+                    .insert(id.clone(), meta_data.clone(), self.ttl);
+            })
+            .await;
+
+        Ok(meta_data)
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for CachedDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        if let Some(cached) = self.cache.get(self.id, |e| e.ogr_meta_data.get(id)).await {
+            return Ok(cached);
+        }
+
+        let meta_data: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = self.provider.meta_data(id).await?;
+
+        self.cache
+            .insert(self.id, |e| {
+                e.ogr_meta_data
+                    .insert(id.clone(), meta_data.clone(), self.ttl);
+            })
+            .await;
+
+        Ok(meta_data)
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for CachedDataProvider
+{
+    async fn meta_data(
+        &self,
+        id: &geoengine_datatypes::dataset::DataId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        if let Some(cached) = self.cache.get(self.id, |e| e.gdal_meta_data.get(id)).await {
+            return Ok(cached);
+        }
+
+        let meta_data: Box<
+            dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
+        > = self.provider.meta_data(id).await?;
+
+        self.cache
+            .insert(self.id, |e| {
+                e.gdal_meta_data
+                    .insert(id.clone(), meta_data.clone(), self.ttl);
+            })
+            .await;
+
+        Ok(meta_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::user_input::UserInput;
+    use httptest::{matchers::request, responders::json_encoded, Expectation, Server};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn it_caches_collections_until_invalidated() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/v1.1/ObservedProperties"))
+                .times(2)
+                .respond_with(json_encoded(json!({
+                    "value": [{"@iot.id": 1, "name": "Temperature", "description": ""}]
+                }))),
+        );
+
+        let definition: Box<dyn DataProviderDefinition> = serde_json::from_value(json!({
+            "type": "SensorThingsDataProviderDefinition",
+            "id": "7b1e2a44-0f3c-4d1a-b6e2-91c35d7f8a20",
+            "name": "Sensors",
+            "baseUrl": server.url_str("/v1.1/"),
+            "cachePath": "sensorthings_cache",
+            "cacheTtl": 60
+        }))
+        .unwrap();
+        let id = definition.id();
+
+        let cache = Arc::new(ProviderCache::default());
+
+        let list = |provider: Box<dyn DataProvider>| async move {
+            provider
+                .collection(
+                    &provider.root_collection_id().await.unwrap(),
+                    LayerCollectionListOptions {
+                        offset: 0,
+                        limit: 20,
+                    }
+                    .validated()
+                    .unwrap(),
+                )
+                .await
+                .unwrap()
+        };
+
+        let first = list(cache.initialize(definition.clone()).await.unwrap()).await;
+        let second = list(cache.initialize(definition.clone()).await.unwrap()).await;
+        assert_eq!(first, second);
+
+        cache.invalidate(id).await;
+
+        // requests the server a second time
+        list(cache.initialize(definition).await.unwrap()).await;
+    }
+}
@@ -7,10 +7,15 @@ use geoengine_operators::engine::{
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::datasets::listing::ProvenanceOutput;
 use crate::error::Result;
+use crate::util::user_input::UserInput;
 
+use super::cache::CacheTtlSeconds;
+use super::layer::LayerCollectionListOptions;
 use super::listing::LayerCollectionProvider;
 
 #[typetag::serde(tag = "type")]
@@ -29,6 +34,11 @@ pub trait DataProviderDefinition:
 
     /// id of the provider
     fn id(&self) -> DataProviderId;
+
+    /// how long listings and meta data of the provider are cached
+    fn cache_ttl(&self) -> CacheTtlSeconds {
+        CacheTtlSeconds::default()
+    }
 }
 
 pub trait CloneableDataProviderDefinition {
@@ -68,4 +78,106 @@ pub trait DataProvider: LayerCollectionProvider
 {
     // TODO: unify provenance method for internal and external provider as a separate trait. We need to figure out session handling before, though.
     async fn provenance(&self, id: &DataId) -> Result<ProvenanceOutput>;
+
+    /// the filters that the external source evaluates when loading data
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// check that the external source is reachable and accepts the configured credentials
+    async fn check_health(&self) -> Result<()> {
+        check_root_collection(self).await
+    }
+}
+
+/// The filters that a provider pushes down to the external source.
+/// Filters that are not supported must be applied after loading the data, if at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCapabilities {
+    /// queries are restricted to the spatial bounds
+    pub spatial_filter: bool,
+    /// queries are restricted to the time interval
+    pub time_filter: bool,
+    /// queries can be restricted by attribute values
+    pub attribute_filter: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderHealthStatus {
+    Ok,
+    Error,
+}
+
+/// The result of a connectivity check of a provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealth {
+    pub status: ProviderHealthStatus,
+    /// the error message if the check failed
+    pub message: Option<String>,
+    /// the duration of the check in milliseconds
+    pub response_time_ms: u64,
+}
+
+impl ProviderHealth {
+    /// Runs the `check` and measures its duration
+    pub async fn check<F>(check: F) -> Self
+    where
+        F: std::future::Future<Output = Result<()>>,
+    {
+        let start = std::time::Instant::now();
+        let result = check.await;
+        let response_time_ms = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(()) => Self {
+                status: ProviderHealthStatus::Ok,
+                message: None,
+                response_time_ms,
+            },
+            Err(error) => Self {
+                status: ProviderHealthStatus::Error,
+                message: Some(error.to_string()),
+                response_time_ms,
+            },
+        }
+    }
+}
+
+/// Checks a provider by listing the first item of its root collection
+pub async fn check_root_collection<P>(provider: &P) -> Result<()>
+where
+    P: LayerCollectionProvider + Sync + ?Sized,
+{
+    let root = provider.root_collection_id().await?;
+    provider
+        .collection(
+            &root,
+            LayerCollectionListOptions {
+                offset: 0,
+                limit: 1,
+            }
+            .validated()?,
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn it_reports_health() {
+        let health = ProviderHealth::check(async { Ok(()) }).await;
+        assert_eq!(health.status, ProviderHealthStatus::Ok);
+        assert_eq!(health.message, None);
+
+        let health = ProviderHealth::check(async { Err(Error::InvalidLayerId) }).await;
+        assert_eq!(health.status, ProviderHealthStatus::Error);
+        assert_eq!(health.message, Some(Error::InvalidLayerId.to_string()));
+    }
 }
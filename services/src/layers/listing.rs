@@ -8,6 +8,7 @@ use crate::error::Result;
 use crate::util::user_input::Validated;
 
 use super::layer::{Layer, LayerCollection, LayerCollectionListOptions};
+use super::search::{search_collections, LayerSearchMatch, LayerSearchQuery};
 
 use serde::{Deserialize, Serialize};
 
@@ -35,4 +36,10 @@ pub trait LayerCollectionProvider {
 
     /// get the full contents of the layer with the given `id`
     async fn get_layer(&self, id: &LayerId) -> Result<Layer>;
+
+    /// search the layers whose name or description match the `query`,
+    /// by default by traversing the collections
+    async fn search(&self, query: &LayerSearchQuery) -> Result<Vec<LayerSearchMatch>> {
+        search_collections(self, query).await
+    }
 }
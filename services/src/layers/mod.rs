@@ -1,5 +1,7 @@
 pub mod add_from_directory;
+pub mod cache;
 pub mod external;
 pub mod layer;
 pub mod listing;
+pub mod search;
 pub mod storage;
@@ -0,0 +1,351 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::model::datatypes::LayerId;
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+use crate::util::user_input::UserInput;
+
+use super::layer::{CollectionItem, LayerCollectionListOptions, LayerListing};
+use super::listing::LayerCollectionProvider;
+
+/// Score of a term that is a word of the layer name
+const NAME_WORD_SCORE: u32 = 3;
+/// Score of a term that is part of the layer name
+const NAME_SCORE: u32 = 2;
+/// Score of a term that is part of the layer description
+const DESCRIPTION_SCORE: u32 = 1;
+/// Additional score if the whole query equals the layer name
+const EXACT_NAME_SCORE: u32 = 4;
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+pub struct LayerSearchOptions {
+    /// The words that the name or description of a layer must contain
+    #[param(example = "ndvi")]
+    pub query: String,
+    #[param(example = 20)]
+    #[serde(default = "default_search_limit")]
+    pub limit: u32,
+}
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+impl UserInput for LayerSearchOptions {
+    fn validate(&self) -> Result<()> {
+        let limit = get_config_element::<config::LayerSearch>()?.list_limit;
+        ensure!(
+            self.limit <= limit,
+            error::InvalidListLimit {
+                limit: limit as usize
+            }
+        );
+
+        let query_length = self.query.trim().len();
+        ensure!(
+            (1..=256).contains(&query_length),
+            error::InvalidStringLength {
+                parameter: "query".to_string(),
+                min: 1_usize,
+                max: 256_usize,
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// A search query that is split into case-insensitive terms.
+/// A layer matches if each term occurs in its name or description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerSearchQuery {
+    query: String,
+    terms: Vec<String>,
+}
+
+impl LayerSearchQuery {
+    pub fn new(query: &str) -> Self {
+        let query = query.trim().to_lowercase();
+        let terms = query.split_whitespace().map(ToString::to_string).collect();
+
+        Self { query, terms }
+    }
+
+    /// Ranks a layer by where the terms occur, terms in the name rank higher than terms in the description.
+    /// Returns `None` if the layer does not match.
+    pub fn score(&self, name: &str, description: &str) -> Option<u32> {
+        let name = name.to_lowercase();
+        let description = description.to_lowercase();
+
+        let mut score = if name == self.query {
+            EXACT_NAME_SCORE
+        } else {
+            0
+        };
+
+        for term in &self.terms {
+            score += if name
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word == term)
+            {
+                NAME_WORD_SCORE
+            } else if name.contains(term.as_str()) {
+                NAME_SCORE
+            } else if description.contains(term.as_str()) {
+                DESCRIPTION_SCORE
+            } else {
+                return None;
+            };
+        }
+
+        Some(score)
+    }
+}
+
+/// A layer of a provider that matches a search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerSearchMatch {
+    pub layer: LayerListing,
+    pub score: u32,
+}
+
+/// A layer that matches a search query together with the provider it stems from
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerSearchResult {
+    pub layer: LayerListing,
+    pub provider_name: String,
+    /// higher scores are better matches
+    pub score: u32,
+}
+
+/// Searches the layers of a provider by traversing its collections breadth-first.
+///
+/// At most `max_listings_per_provider` collection pages are loaded, so the layers of large providers are only searched partially.
+pub async fn search_collections<P>(
+    provider: &P,
+    query: &LayerSearchQuery,
+) -> Result<Vec<LayerSearchMatch>>
+where
+    P: LayerCollectionProvider + Sync + ?Sized,
+{
+    let config = get_config_element::<config::LayerSearch>()?;
+
+    let root = provider.root_collection_id().await?;
+
+    let mut visited_collections = HashSet::from([root.clone()]);
+    let mut matched_layers = HashSet::<LayerId>::new();
+    let mut pending = VecDeque::from([(root, 0)]);
+    let mut matches = vec![];
+
+    let mut listings = 0;
+    while let Some((collection_id, offset)) = pending.pop_front() {
+        if listings >= config.max_listings_per_provider {
+            break;
+        }
+        listings += 1;
+
+        let options = LayerCollectionListOptions {
+            offset,
+            limit: config.page_size,
+        }
+        .validated()?;
+
+        let collection = match provider.collection(&collection_id, options).await {
+            Ok(collection) => collection,
+            // the root collection must be readable, but a single broken sub-collection should not fail the search
+            Err(error) if listings == 1 => return Err(error),
+            Err(error) => {
+                log::debug!("Skipping collection {collection_id} in layer search: {error}");
+                continue;
+            }
+        };
+
+        if collection.items.len() >= config.page_size as usize {
+            pending.push_back((collection_id, offset + config.page_size));
+        }
+
+        for item in collection.items {
+            match item {
+                CollectionItem::Collection(listing) => {
+                    if visited_collections.insert(listing.id.collection_id.clone()) {
+                        pending.push_back((listing.id.collection_id, 0));
+                    }
+                }
+                CollectionItem::Layer(layer) => {
+                    if matched_layers.contains(&layer.id.layer_id) {
+                        continue;
+                    }
+
+                    if let Some(score) = query.score(&layer.name, &layer.description) {
+                        matched_layers.insert(layer.id.layer_id.clone());
+                        matches.push(LayerSearchMatch { layer, score });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Orders the results of all providers by their score and name and keeps the best `limit` ones
+pub fn rank_search_results(results: &mut Vec<LayerSearchResult>, limit: usize) {
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.layer.name.cmp(&b.layer.name))
+            .then_with(|| a.provider_name.cmp(&b.provider_name))
+    });
+    results.truncate(limit);
+}
+
+#[cfg(test)]
+mod tests {
+    use geoengine_datatypes::primitives::Coordinate2D;
+    use geoengine_operators::engine::{TypedOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+
+    use crate::layers::layer::{AddLayer, AddLayerCollection, ProviderLayerId};
+    use crate::layers::storage::{HashMapLayerDb, LayerDb, INTERNAL_PROVIDER_ID};
+    use crate::workflows::workflow::Workflow;
+
+    use super::*;
+
+    fn add_layer(name: &str, description: &str) -> AddLayer {
+        AddLayer {
+            name: name.to_string(),
+            description: description.to_string(),
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    MockPointSource {
+                        params: MockPointSourceParams {
+                            points: vec![Coordinate2D::new(1., 2.); 3],
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+        }
+    }
+
+    #[test]
+    fn it_scores_layers() {
+        let query = LayerSearchQuery::new("NDVI modis");
+
+        assert_eq!(query.score("NDVI", "from MODIS"), Some(4));
+        assert_eq!(query.score("MODIS NDVI", ""), Some(6));
+        assert_eq!(query.score("ndvi_modis", "monthly"), Some(6));
+        assert_eq!(query.score("NDVIs", "MODIS"), Some(3));
+        assert_eq!(query.score("NDVI", "Landsat"), None);
+
+        assert_eq!(
+            LayerSearchQuery::new(" land cover ").score("Land Cover", ""),
+            Some(6 + 4)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_searches_nested_collections() -> Result<()> {
+        let db = HashMapLayerDb::default();
+        let root = db.root_collection_id().await?;
+
+        let ndvi = db
+            .add_layer(add_layer("NDVI", "vegetation index").validated()?, &root)
+            .await?;
+
+        let collection = db
+            .add_collection(
+                AddLayerCollection {
+                    name: "Vegetation".to_string(),
+                    description: "description".to_string(),
+                }
+                .validated()?,
+                &root,
+            )
+            .await?;
+        let forest = db
+            .add_layer(
+                add_layer("Forest cover", "tree cover from vegetation maps").validated()?,
+                &collection,
+            )
+            .await?;
+        db.add_layer_to_collection(&ndvi, &collection).await?;
+        db.add_layer(
+            add_layer("Rivers", "water bodies").validated()?,
+            &collection,
+        )
+        .await?;
+
+        let matches = search_collections(&db, &LayerSearchQuery::new("vegetation")).await?;
+
+        assert_eq!(
+            matches,
+            vec![
+                LayerSearchMatch {
+                    layer: LayerListing {
+                        id: ProviderLayerId {
+                            provider_id: INTERNAL_PROVIDER_ID,
+                            layer_id: ndvi,
+                        },
+                        name: "NDVI".to_string(),
+                        description: "vegetation index".to_string(),
+                    },
+                    score: 1,
+                },
+                LayerSearchMatch {
+                    layer: LayerListing {
+                        id: ProviderLayerId {
+                            provider_id: INTERNAL_PROVIDER_ID,
+                            layer_id: forest,
+                        },
+                        name: "Forest cover".to_string(),
+                        description: "tree cover from vegetation maps".to_string(),
+                    },
+                    score: 1,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_ranks_results() {
+        let result = |name: &str, provider_name: &str, score: u32| LayerSearchResult {
+            layer: LayerListing {
+                id: ProviderLayerId {
+                    provider_id: INTERNAL_PROVIDER_ID,
+                    layer_id: LayerId(name.to_string()),
+                },
+                name: name.to_string(),
+                description: String::new(),
+            },
+            provider_name: provider_name.to_string(),
+            score,
+        };
+
+        let mut results = vec![
+            result("b", "Layers", 1),
+            result("a", "GBIF", 3),
+            result("c", "Datasets", 6),
+            result("a", "Datasets", 3),
+        ];
+
+        rank_search_results(&mut results, 3);
+
+        assert_eq!(
+            results,
+            vec![
+                result("c", "Datasets", 6),
+                result("a", "Datasets", 3),
+                result("a", "GBIF", 3),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::add_from_directory::UNSORTED_COLLECTION_ID;
+use super::cache::ProviderCache;
 use super::external::{DataProvider, DataProviderDefinition};
 use super::layer::{
     AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection,
@@ -13,7 +14,10 @@ use super::listing::{LayerCollectionId, LayerCollectionProvider};
 use crate::api::model::datatypes::{DataProviderId, LayerId};
 use crate::error::{Error, Result};
 use crate::util::user_input::UserInput;
-use crate::{contexts::Db, util::user_input::Validated};
+use crate::{
+    contexts::{Db, Session},
+    util::user_input::Validated,
+};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
@@ -109,20 +113,43 @@ impl UserInput for LayerProviderListingOptions {
 }
 
 #[async_trait]
-pub trait LayerProviderDb: Send + Sync + 'static {
+pub trait LayerProviderDb<S: Session>: Send + Sync + 'static {
     async fn add_layer_provider(
         &self,
+        session: &S,
         provider: Box<dyn DataProviderDefinition>,
     ) -> Result<DataProviderId>;
 
     async fn list_layer_providers(
         &self,
+        session: &S,
         options: Validated<LayerProviderListingOptions>,
     ) -> Result<Vec<LayerProviderListing>>;
 
-    async fn layer_provider(&self, id: DataProviderId) -> Result<Box<dyn DataProvider>>;
+    async fn layer_provider(
+        &self,
+        session: &S,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProvider>>;
+
+    /// get the stored definition of the provider, including its secrets
+    async fn layer_provider_definition(
+        &self,
+        session: &S,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProviderDefinition>>;
 
-    // TODO: share/remove/update layer providers
+    /// replace the definition of an existing provider with the same id
+    async fn update_layer_provider(
+        &self,
+        session: &S,
+        provider: Box<dyn DataProviderDefinition>,
+    ) -> Result<()>;
+
+    /// remove the cached listings and meta data of the provider
+    async fn invalidate_layer_provider_cache(&self, session: &S, id: DataProviderId) -> Result<()>;
+
+    // TODO: share/remove layer providers
 }
 
 #[derive(Default, Debug)]
@@ -406,12 +433,14 @@ impl LayerCollectionProvider for HashMapLayerDb {
 #[derive(Default)]
 pub struct HashMapLayerProviderDb {
     external_providers: Db<HashMap<DataProviderId, Box<dyn DataProviderDefinition>>>,
+    cache: Arc<ProviderCache>,
 }
 
 #[async_trait]
-impl LayerProviderDb for HashMapLayerProviderDb {
+impl<S: Session> LayerProviderDb<S> for HashMapLayerProviderDb {
     async fn add_layer_provider(
         &self,
+        _session: &S,
         provider: Box<dyn DataProviderDefinition>,
     ) -> Result<DataProviderId> {
         let id = provider.id();
@@ -423,6 +452,7 @@ impl LayerProviderDb for HashMapLayerProviderDb {
 
     async fn list_layer_providers(
         &self,
+        _session: &S,
         options: Validated<LayerProviderListingOptions>,
     ) -> Result<Vec<LayerProviderListing>> {
         let options = options.user_input;
@@ -449,26 +479,67 @@ impl LayerProviderDb for HashMapLayerProviderDb {
             .collect())
     }
 
-    async fn layer_provider(&self, id: DataProviderId) -> Result<Box<dyn DataProvider>> {
+    async fn layer_provider(
+        &self,
+        session: &S,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProvider>> {
+        let definition = self.layer_provider_definition(session, id).await?;
+        self.cache.initialize(definition).await
+    }
+
+    async fn layer_provider_definition(
+        &self,
+        _session: &S,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProviderDefinition>> {
         self.external_providers
             .read()
             .await
             .get(&id)
             .cloned()
-            .ok_or(Error::UnknownProviderId)?
-            .initialize()
-            .await
+            .ok_or(Error::UnknownProviderId)
+    }
+
+    async fn update_layer_provider(
+        &self,
+        _session: &S,
+        provider: Box<dyn DataProviderDefinition>,
+    ) -> Result<()> {
+        let mut external_providers = self.external_providers.write().await;
+
+        let id = provider.id();
+        let stored = external_providers
+            .get_mut(&id)
+            .ok_or(Error::UnknownProviderId)?;
+        *stored = provider;
+
+        self.cache.invalidate(id).await;
+
+        Ok(())
+    }
+
+    async fn invalidate_layer_provider_cache(
+        &self,
+        _session: &S,
+        id: DataProviderId,
+    ) -> Result<()> {
+        self.cache.invalidate(id).await;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use geoengine_datatypes::primitives::Coordinate2D;
+    use geoengine_datatypes::util::Identifier;
     use geoengine_operators::{
         engine::{TypedOperator, VectorOperator},
         mock::{MockPointSource, MockPointSourceParams},
     };
 
+    use crate::contexts::SimpleSession;
+    use crate::datasets::external::mock::MockExternalLayerProviderDefinition;
     use crate::{util::user_input::UserInput, workflows::workflow::Workflow};
 
     use super::*;
@@ -560,4 +631,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn it_updates_layer_providers() -> Result<()> {
+        let db = HashMapLayerProviderDb::default();
+        let session = SimpleSession::default();
+
+        let provider_id = DataProviderId::new();
+        db.add_layer_provider(
+            &session,
+            Box::new(MockExternalLayerProviderDefinition {
+                id: provider_id,
+                datasets: vec![],
+            }),
+        )
+        .await?;
+
+        assert!(db
+            .update_layer_provider(
+                &session,
+                Box::new(MockExternalLayerProviderDefinition {
+                    id: DataProviderId::new(),
+                    datasets: vec![],
+                })
+            )
+            .await
+            .is_err());
+
+        db.update_layer_provider(
+            &session,
+            Box::new(MockExternalLayerProviderDefinition {
+                id: provider_id,
+                datasets: vec![],
+            }),
+        )
+        .await?;
+
+        assert_eq!(
+            db.layer_provider_definition(&session, provider_id)
+                .await?
+                .id(),
+            provider_id
+        );
+
+        Ok(())
+    }
 }
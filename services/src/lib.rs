@@ -30,6 +30,7 @@
 pub mod api;
 #[cfg(not(feature = "pro"))]
 pub mod apidoc;
+pub mod client;
 pub mod contexts;
 pub mod datasets;
 pub mod error;
@@ -37,6 +38,7 @@ pub mod handlers;
 pub mod layers;
 pub mod ogc;
 pub mod projects;
+pub mod reports;
 #[cfg(not(feature = "pro"))]
 pub mod server;
 pub mod stac;
@@ -1,6 +1,6 @@
 use crate::api::model::datatypes::SpatialReference;
 use actix_web::guard::{Guard, GuardContext};
-use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D, DateTime};
+use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D};
 use geoengine_datatypes::primitives::{Coordinate2D, SpatialResolution};
 use reqwest::Url;
 use serde::de::Error;
@@ -15,6 +15,7 @@ use super::wfs::request::WfsResolution;
 use crate::api::model::datatypes::TimeInterval;
 use crate::error::{self, Result};
 use crate::handlers::spatial_references::{spatial_reference_specification, AxisOrder};
+use crate::util::defaults::parse_date_time;
 use crate::workflows::workflow::WorkflowId;
 
 #[derive(PartialEq, Debug, Deserialize, Serialize, Clone, Copy)]
@@ -122,7 +123,7 @@ fn parse_time_from_str<'de, D>(s: &str) -> Result<TimeInterval, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let split: Vec<_> = s.split('/').map(DateTime::from_str).collect();
+    let split: Vec<_> = s.split('/').map(parse_date_time).collect();
 
     match *split.as_slice() {
         [Ok(time)] => geoengine_datatypes::primitives::TimeInterval::new(time, time)
@@ -325,6 +326,7 @@ impl Guard for OgcRequestGuard<'_> {
 #[cfg(test)]
 mod tests {
     use crate::api::model::datatypes::SpatialReferenceAuthority;
+    use geoengine_datatypes::primitives::DateTime;
     use serde::de::value::StringDeserializer;
     use serde::de::IntoDeserializer;
 
@@ -440,6 +442,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_time_without_offset() {
+        assert_eq!(
+            parse_time(to_deserializer("2014-04-01T12:00:00/2014-04-02T12:00:00")).unwrap(),
+            geoengine_datatypes::primitives::TimeInterval::new(
+                DateTime::new_utc(2014, 4, 1, 12, 0, 0),
+                DateTime::new_utc(2014, 4, 2, 12, 0, 0)
+            )
+            .unwrap()
+            .into(),
+        );
+    }
+
     #[test]
     fn parse_time_with_offset() {
         assert_eq!(
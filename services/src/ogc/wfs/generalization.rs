@@ -0,0 +1,195 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, SpatialResolution, TimeInterval,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use tokio::sync::Mutex;
+
+use crate::util::config::{get_config_element, Wfs};
+use crate::workflows::workflow::WorkflowId;
+
+lazy_static::lazy_static! {
+    /// The process-wide cache of generalized WFS responses
+    pub static ref GENERALIZATION_CACHE: GeneralizationCache = GeneralizationCache::new(
+        get_config_element::<Wfs>().map_or(0, |wfs| wfs.generalization_cache_size)
+    );
+}
+
+/// A discrete zoom level that determines how much geometries are simplified.
+/// The level is the exponent of the power of two that is the simplification tolerance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GeneralizationLevel(i32);
+
+impl GeneralizationLevel {
+    /// Snaps the resolution of a query to the next finer level
+    pub fn from_resolution(resolution: SpatialResolution) -> Self {
+        Self(resolution.x.max(resolution.y).log2().floor() as i32)
+    }
+
+    /// The maximum distance of removed vertices in units of the spatial reference
+    pub fn tolerance(self) -> f64 {
+        2_f64.powi(self.0)
+    }
+}
+
+/// Identifies a generalized response by its query and by the format of its coordinates
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GeneralizationCacheKey {
+    workflow: WorkflowId,
+    level: GeneralizationLevel,
+    spatial_reference: String,
+    bbox: [u64; 4],
+    time: (i64, i64),
+    output_spatial_reference: String,
+    precision: Option<u8>,
+}
+
+impl GeneralizationCacheKey {
+    pub fn new(
+        workflow: WorkflowId,
+        level: GeneralizationLevel,
+        spatial_reference: SpatialReference,
+        bbox: BoundingBox2D,
+        time: TimeInterval,
+        output_spatial_reference: SpatialReference,
+        precision: Option<u8>,
+    ) -> Self {
+        let lower_left = bbox.lower_left();
+        let upper_right = bbox.upper_right();
+
+        Self {
+            workflow,
+            level,
+            spatial_reference: spatial_reference.to_string(),
+            bbox: [
+                lower_left.x.to_bits(),
+                lower_left.y.to_bits(),
+                upper_right.x.to_bits(),
+                upper_right.y.to_bits(),
+            ],
+            time: (time.start().inner(), time.end().inner()),
+            output_spatial_reference: output_spatial_reference.to_string(),
+            precision,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GeneralizationCacheEntries {
+    responses: HashMap<GeneralizationCacheKey, Arc<serde_json::Value>>,
+    insertion_order: VecDeque<GeneralizationCacheKey>,
+}
+
+/// Stores simplified `GeoJSON` responses of workflows per generalization level.
+/// If the capacity is exceeded, the oldest responses are evicted. A capacity of zero disables the cache.
+#[derive(Debug)]
+pub struct GeneralizationCache {
+    capacity: usize,
+    entries: Mutex<GeneralizationCacheEntries>,
+}
+
+impl GeneralizationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(GeneralizationCacheEntries::default()),
+        }
+    }
+
+    pub async fn get(&self, key: &GeneralizationCacheKey) -> Option<Arc<serde_json::Value>> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        self.entries.lock().await.responses.get(key).cloned()
+    }
+
+    pub async fn insert(&self, key: GeneralizationCacheKey, response: Arc<serde_json::Value>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().await;
+
+        if entries.responses.insert(key.clone(), response).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+
+        while entries.responses.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                entries.responses.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Removes all responses of the workflow, e.g., when it was superseded by a new version
+    pub async fn invalidate(&self, workflow: WorkflowId) {
+        let mut entries = self.entries.lock().await;
+
+        entries.responses.retain(|key, _| key.workflow != workflow);
+        entries
+            .insertion_order
+            .retain(|key| key.workflow != workflow);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_snaps_resolutions_to_levels() {
+        let level =
+            GeneralizationLevel::from_resolution(SpatialResolution::new_unchecked(0.3, 0.1));
+
+        assert_eq!(level, GeneralizationLevel(-2));
+        assert!((level.tolerance() - 0.25).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn it_evicts_and_invalidates_responses() {
+        let cache = GeneralizationCache::new(1);
+
+        let workflow = WorkflowId::new();
+        let level = GeneralizationLevel(0);
+        let bbox = BoundingBox2D::new((0., 0.).into(), (1., 1.).into()).unwrap();
+        let other_bbox = BoundingBox2D::new((0., 0.).into(), (2., 2.).into()).unwrap();
+        let time = TimeInterval::default();
+        let srs = SpatialReference::epsg_4326();
+
+        let key = GeneralizationCacheKey::new(workflow, level, srs, bbox, time, srs, None);
+        let other_key =
+            GeneralizationCacheKey::new(workflow, level, srs, other_bbox, time, srs, None);
+
+        cache
+            .insert(key.clone(), Arc::new(serde_json::json!(1)))
+            .await;
+        assert!(cache.get(&key).await.is_some());
+        assert!(cache
+            .get(&GeneralizationCacheKey::new(
+                workflow,
+                level,
+                srs,
+                bbox,
+                time,
+                srs,
+                Some(2)
+            ))
+            .await
+            .is_none());
+
+        cache
+            .insert(other_key.clone(), Arc::new(serde_json::json!(2)))
+            .await;
+        assert!(cache.get(&key).await.is_none());
+        assert!(cache.get(&other_key).await.is_some());
+
+        cache.invalidate(workflow).await;
+        assert!(cache.get(&other_key).await.is_none());
+    }
+}
@@ -1 +1,3 @@
+pub mod generalization;
+pub mod paging;
 pub mod request;
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::contexts::SessionId;
+use crate::util::config::{get_config_element, Wfs};
+use crate::workflows::workflow::WorkflowId;
+
+/// Identifies the result of a `GetFeature` request independently of the requested page.
+/// Results are spooled per session, so that sessions cannot read the results of other sessions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PagingSpoolKey {
+    session: SessionId,
+    workflow: WorkflowId,
+    /// the sorted query parameters without the paging parameters
+    query: Vec<(String, String)>,
+}
+
+impl PagingSpoolKey {
+    pub fn new(session: SessionId, workflow: WorkflowId, query_string: &str) -> Self {
+        let mut query: Vec<(String, String)> = url::form_urlencoded::parse(query_string.as_bytes())
+            .filter(|(key, _)| {
+                !key.eq_ignore_ascii_case("startIndex") && !key.eq_ignore_ascii_case("count")
+            })
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        query.sort();
+
+        Self {
+            session,
+            workflow,
+            query,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SpooledResult<T> {
+    created: Instant,
+    result: Arc<T>,
+}
+
+#[derive(Debug)]
+struct PagingSpoolEntries<T> {
+    results: HashMap<PagingSpoolKey, SpooledResult<T>>,
+    insertion_order: VecDeque<PagingSpoolKey>,
+}
+
+/// Keeps the complete results of paged queries, so that all pages are cut from the same result.
+/// This way, clients do not get duplicates or gaps, even if the order of the features is not deterministic.
+/// If the capacity is exceeded, the oldest results are evicted. A capacity of zero disables the spool.
+#[derive(Debug)]
+pub struct PagingSpool<T> {
+    capacity: usize,
+    time_to_live: Option<Duration>,
+    entries: Mutex<PagingSpoolEntries<T>>,
+}
+
+impl<T> PagingSpool<T> {
+    pub fn new(capacity: usize, time_to_live: Option<Duration>) -> Self {
+        Self {
+            capacity,
+            time_to_live,
+            entries: Mutex::new(PagingSpoolEntries {
+                results: HashMap::new(),
+                insertion_order: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Creates a spool with the capacity and time to live of the `wfs` config
+    pub fn from_config() -> Self {
+        get_config_element::<Wfs>().map_or_else(
+            |_| Self::new(0, None),
+            |wfs| {
+                Self::new(
+                    wfs.paging_spool_size,
+                    (wfs.paging_spool_ttl_seconds > 0)
+                        .then(|| Duration::from_secs(wfs.paging_spool_ttl_seconds)),
+                )
+            },
+        )
+    }
+
+    pub async fn get(&self, key: &PagingSpoolKey) -> Option<Arc<T>> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        let entries = self.entries.lock().await;
+
+        entries
+            .results
+            .get(key)
+            .filter(|result| {
+                self.time_to_live
+                    .map_or(true, |time_to_live| result.created.elapsed() < time_to_live)
+            })
+            .map(|result| result.result.clone())
+    }
+
+    pub async fn insert(&self, key: PagingSpoolKey, result: Arc<T>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().await;
+
+        let result = SpooledResult {
+            created: Instant::now(),
+            result,
+        };
+
+        if entries.results.insert(key.clone(), result).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+
+        while entries.results.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                entries.results.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_ignores_paging_parameters_in_keys() {
+        let session = SessionId::new();
+        let workflow = WorkflowId::new();
+
+        assert_eq!(
+            PagingSpoolKey::new(
+                session,
+                workflow,
+                "request=GetFeature&startIndex=10&count=10&bbox=1,2,3,4"
+            ),
+            PagingSpoolKey::new(session, workflow, "bbox=1,2,3,4&request=GetFeature&count=5")
+        );
+        assert_ne!(
+            PagingSpoolKey::new(session, workflow, "bbox=1,2,3,4&startIndex=10"),
+            PagingSpoolKey::new(session, workflow, "bbox=1,2,3,5&startIndex=10")
+        );
+        assert_ne!(
+            PagingSpoolKey::new(session, workflow, "bbox=1,2,3,4"),
+            PagingSpoolKey::new(SessionId::new(), workflow, "bbox=1,2,3,4")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_evicts_and_expires_results() {
+        let spool = PagingSpool::<u32>::new(1, None);
+
+        let key = PagingSpoolKey::new(SessionId::new(), WorkflowId::new(), "bbox=1,2,3,4");
+        let other_key = PagingSpoolKey::new(SessionId::new(), WorkflowId::new(), "bbox=1,2,3,4");
+
+        spool.insert(key.clone(), Arc::new(1)).await;
+        assert!(spool.get(&key).await.is_some());
+
+        spool.insert(other_key.clone(), Arc::new(2)).await;
+        assert!(spool.get(&key).await.is_none());
+        assert!(spool.get(&other_key).await.is_some());
+
+        let spool = PagingSpool::new(1, Some(Duration::ZERO));
+        spool.insert(key.clone(), Arc::new(1)).await;
+        assert!(spool.get(&key).await.is_none());
+    }
+}
@@ -73,6 +73,10 @@ pub struct GetFeature {
     #[serde(default)]
     #[serde(deserialize_with = "from_str_option")]
     pub count: Option<u64>,
+    /// The index of the first feature of the page, starting at zero
+    #[serde(default)]
+    #[serde(deserialize_with = "from_str_option")]
+    pub startIndex: Option<u64>,
     pub sortBy: Option<String>,       // TODO: Name[+A|+D] (asc/desc)
     pub resultType: Option<String>,   // TODO: enum: results/hits?
     pub filter: Option<String>,       // TODO: parse filters
@@ -82,6 +86,20 @@ pub struct GetFeature {
     #[serde(default)]
     #[serde(deserialize_with = "parse_wfs_resolution_option")]
     pub queryResolution: Option<WfsResolution>,
+    /// Vendor parameter for reprojecting the features to another spatial reference than `srsName`, which still applies to the `bbox`
+    #[param(example = "EPSG:3857")]
+    pub outputCrs: Option<SpatialReference>,
+    /// Vendor parameter for rounding the coordinates of the features to a number of decimal places
+    #[serde(default)]
+    #[serde(deserialize_with = "from_str_option")]
+    #[param(example = 6)]
+    pub precision: Option<u8>,
+    /// Vendor parameter for stopping the query after a number of features, the result is then marked as `truncated`.
+    /// It does not apply to paged requests, which are limited by `count`.
+    #[serde(default)]
+    #[serde(deserialize_with = "from_str_option")]
+    #[param(example = 10000)]
+    pub maxFeatures: Option<u64>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -139,6 +157,7 @@ mod tests {
             srsName: None,
             namespaces: None,
             count: None,
+            startIndex: None,
             sortBy: None,
             resultType: None,
             filter: None,
@@ -149,6 +168,9 @@ mod tests {
             },
             propertyName: None,
             queryResolution: None,
+            outputCrs: None,
+            precision: None,
+            maxFeatures: None,
         };
 
         assert_eq!(parsed, request);
@@ -167,6 +189,7 @@ mod tests {
             ("time", "2000-01-01T00:00:00.0Z/2000-01-02T00:00:00.0Z"),
             ("namespaces","xmlns(dog=http://www.example.com/namespaces/dog)"),
             ("count","10"),
+            ("startIndex","20"),
             ("sortBy","Name[+A]"),
             ("resultType","results"),
             ("filter","<Filter>
@@ -177,6 +200,8 @@ mod tests {
 </Filter>"),
             ("propertyName","P1,P2"),
             ("queryResolution","0.1,0.1"),
+            ("outputCrs", "EPSG:3857"),
+            ("precision", "6"),
         ];
         let query = serde_urlencoded::to_string(params).unwrap();
         let parsed: GetFeature = serde_urlencoded::from_str(&query).unwrap();
@@ -189,6 +214,7 @@ mod tests {
             srsName: Some(SpatialReference::new(SpatialReferenceAuthority::Epsg, 4326)),
             namespaces: Some("xmlns(dog=http://www.example.com/namespaces/dog)".into()),
             count: Some(10),
+            startIndex: Some(20),
             sortBy: Some("Name[+A]".into()),
             resultType: Some("results".into()),
             filter: Some("<Filter>
@@ -204,6 +230,9 @@ mod tests {
             },
             propertyName: Some("P1,P2".into()),
             queryResolution: Some(WfsResolution(SpatialResolution::zero_point_one())),
+            outputCrs: Some(SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857)),
+            precision: Some(6),
+            maxFeatures: None,
         };
 
         assert_eq!(parsed, request);
@@ -233,6 +262,7 @@ mod tests {
             srsName: None,
             namespaces: None,
             count: None,
+            startIndex: None,
             sortBy: None,
             resultType: None,
             filter: None,
@@ -243,6 +273,9 @@ mod tests {
             },
             propertyName: None,
             queryResolution: None,
+            outputCrs: None,
+            precision: None,
+            maxFeatures: None,
         };
 
         assert_eq!(parsed, request);
@@ -270,6 +303,7 @@ mod tests {
     //         srs_name: None,
     //         namespaces: None,
     //         count: None,
+    //         startIndex: None,
     //         sort_by: None,
     //         result_type: None,
     //         filter: None,
@@ -0,0 +1,383 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::sync::Arc;
+
+use futures::Future;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, TimeInterval,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use image::{imageops, DynamicImage, ImageFormat, ImageOutputFormat, RgbaImage};
+use tokio::sync::Mutex;
+
+use crate::contexts::SessionId;
+use crate::error::{self, Result};
+use crate::util::config::{get_config_element, Wms};
+use crate::workflows::workflow::WorkflowId;
+
+lazy_static::lazy_static! {
+    /// The process-wide cache of rendered WMS meta-tiles
+    pub static ref META_TILE_CACHE: MetaTileCache = MetaTileCache::new(
+        get_config_element::<Wms>().map_or(0, |wms| wms.meta_tile_cache_size)
+    );
+}
+
+/// Relative deviation from the tile grid that still counts as aligned, since clients compute the bbox in floating point
+const ALIGNMENT_TOLERANCE: f64 = 1e-6;
+
+/// The maximum number of pixels of a meta-tile, so that the cache holds at most `capacity` x 16 MiB of RGBA images.
+/// Larger requests are rendered directly.
+const MAX_META_TILE_PIXELS: u64 = 2048 * 2048;
+
+/// A block of `tiles_per_side` x `tiles_per_side` tiles that is rendered at once for tile-aligned `GetMap` requests.
+///
+/// The tile grid has the size of the requested tile and its origin at `(0, 0)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetaTile {
+    pub bbox: BoundingBox2D,
+    pub width: u32,
+    pub height: u32,
+    /// The index of the meta-tile in the grid of meta-tiles
+    index: [i64; 2],
+    /// The size of a single tile in units of the spatial reference
+    tile_size: [f64; 2],
+    /// The offset of the requested tile from the upper left pixel of the meta-tile
+    crop_offset: [u32; 2],
+    crop_size: [u32; 2],
+}
+
+impl MetaTile {
+    /// Returns the meta-tile that contains the requested tile.
+    /// Returns `None` if the request is not aligned to a grid of its own size, if the meta-tile leaves the area of use
+    /// or if it would exceed [`MAX_META_TILE_PIXELS`].
+    pub fn for_request(
+        bbox: BoundingBox2D,
+        width: u32,
+        height: u32,
+        spatial_reference: SpatialReference,
+        tiles_per_side: u32,
+    ) -> Option<Self> {
+        if tiles_per_side <= 1 || width == 0 || height == 0 {
+            return None;
+        }
+
+        let meta_tile_pixels = u64::from(width)
+            .saturating_mul(u64::from(height))
+            .saturating_mul(u64::from(tiles_per_side).pow(2));
+        if meta_tile_pixels > MAX_META_TILE_PIXELS {
+            return None;
+        }
+
+        let tile_size = [bbox.size_x(), bbox.size_y()];
+        let tile_x = aligned_index(bbox.lower_left().x, tile_size[0])?;
+        let tile_y = aligned_index(bbox.lower_left().y, tile_size[1])?;
+
+        let tiles_per_side_i64 = i64::from(tiles_per_side);
+        let index = [
+            tile_x.div_euclid(tiles_per_side_i64),
+            tile_y.div_euclid(tiles_per_side_i64),
+        ];
+
+        let meta_tile_size = [
+            tile_size[0] * f64::from(tiles_per_side),
+            tile_size[1] * f64::from(tiles_per_side),
+        ];
+        let lower_left = Coordinate2D::new(
+            index[0] as f64 * meta_tile_size[0],
+            index[1] as f64 * meta_tile_size[1],
+        );
+        let meta_bbox = BoundingBox2D::new(
+            lower_left,
+            lower_left + Coordinate2D::new(meta_tile_size[0], meta_tile_size[1]),
+        )
+        .ok()?;
+
+        // parts of meta-tiles outside of the spatial reference might not be renderable
+        let area_of_use = spatial_reference
+            .area_of_use_projected::<BoundingBox2D>()
+            .ok()?;
+        if !area_of_use.contains_bbox(&meta_bbox) {
+            return None;
+        }
+
+        // the y axis of the image points downwards
+        let column = (tile_x - index[0] * tiles_per_side_i64) as u32;
+        let row = (tiles_per_side_i64 - 1 - (tile_y - index[1] * tiles_per_side_i64)) as u32;
+
+        Some(Self {
+            bbox: meta_bbox,
+            width: width.checked_mul(tiles_per_side)?,
+            height: height.checked_mul(tiles_per_side)?,
+            index,
+            tile_size,
+            crop_offset: [column * width, row * height],
+            crop_size: [width, height],
+        })
+    }
+
+    /// Cuts the requested tile out of the rendered meta-tile and encodes it as PNG
+    pub fn crop_png(&self, image: &RgbaImage) -> Result<Vec<u8>> {
+        let tile = imageops::crop_imm(
+            image,
+            self.crop_offset[0],
+            self.crop_offset[1],
+            self.crop_size[0],
+            self.crop_size[1],
+        )
+        .to_image();
+
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(tile)
+            .write_to(&mut bytes, ImageOutputFormat::Png)
+            .map_err(|source| error::Error::WmsMetaTiling {
+                reason: source.to_string(),
+            })?;
+
+        Ok(bytes.into_inner())
+    }
+}
+
+/// The index of the tile whose lower left corner is `coordinate` or `None` if there is no such tile
+fn aligned_index(coordinate: f64, tile_size: f64) -> Option<i64> {
+    let index = coordinate / tile_size;
+    let rounded = index.round();
+
+    ((index - rounded).abs() <= ALIGNMENT_TOLERANCE && rounded.abs() < i64::MAX as f64)
+        .then_some(rounded as i64)
+}
+
+/// Decodes a rendered meta-tile
+pub fn decode_meta_tile(png_bytes: &[u8]) -> Result<RgbaImage> {
+    Ok(
+        image::load_from_memory_with_format(png_bytes, ImageFormat::Png)
+            .map_err(|source| error::Error::WmsMetaTiling {
+                reason: source.to_string(),
+            })?
+            .into_rgba8(),
+    )
+}
+
+/// Identifies a rendered meta-tile by its session, workflow, style and position.
+///
+/// Renderings are only shared within a session, since the permissions of its user and tenant,
+/// their datasets and their style presets were checked when the meta-tile was rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MetaTileKey {
+    session: SessionId,
+    workflow: WorkflowId,
+    styles: String,
+    spatial_reference: String,
+    tile_size: [u64; 2],
+    image_size: [u32; 2],
+    index: [i64; 2],
+    time: (i64, i64),
+}
+
+impl MetaTileKey {
+    pub fn new(
+        session: SessionId,
+        workflow: WorkflowId,
+        styles: &str,
+        spatial_reference: SpatialReference,
+        meta_tile: &MetaTile,
+        time: TimeInterval,
+    ) -> Self {
+        Self {
+            session,
+            workflow,
+            styles: styles.to_string(),
+            spatial_reference: spatial_reference.to_string(),
+            tile_size: [
+                meta_tile.tile_size[0].to_bits(),
+                meta_tile.tile_size[1].to_bits(),
+            ],
+            image_size: [meta_tile.width, meta_tile.height],
+            index: meta_tile.index,
+            time: (time.start().inner(), time.end().inner()),
+        }
+    }
+}
+
+/// A meta-tile that is rendered at most once, even if many requests for its tiles arrive at the same time
+type MetaTileSlot = Arc<Mutex<Option<Arc<RgbaImage>>>>;
+
+#[derive(Debug, Default)]
+struct MetaTileCacheEntries {
+    tiles: HashMap<MetaTileKey, MetaTileSlot>,
+    insertion_order: VecDeque<MetaTileKey>,
+}
+
+/// Stores rendered meta-tiles, so that all of their tiles are cut from a single rendering.
+/// If the capacity is exceeded, the oldest meta-tiles are evicted. A capacity of zero disables the cache.
+#[derive(Debug)]
+pub struct MetaTileCache {
+    capacity: usize,
+    entries: Mutex<MetaTileCacheEntries>,
+}
+
+impl MetaTileCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(MetaTileCacheEntries::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the cached meta-tile or renders it.
+    /// Concurrent requests for the same meta-tile wait for a single rendering.
+    /// A failed rendering is not cached, so that the next request retries it.
+    pub async fn get_or_render<F, Fut>(&self, key: MetaTileKey, render: F) -> Result<Arc<RgbaImage>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<RgbaImage>>,
+    {
+        if self.capacity == 0 {
+            return render().await.map(Arc::new);
+        }
+
+        let slot = self.slot(key).await;
+        let mut slot = slot.lock().await;
+
+        if let Some(image) = slot.as_ref() {
+            return Ok(image.clone());
+        }
+
+        let image = Arc::new(render().await?);
+        *slot = Some(image.clone());
+
+        Ok(image)
+    }
+
+    async fn slot(&self, key: MetaTileKey) -> MetaTileSlot {
+        let mut entries = self.entries.lock().await;
+
+        if let Some(slot) = entries.tiles.get(&key) {
+            return slot.clone();
+        }
+
+        let slot = MetaTileSlot::default();
+        entries.tiles.insert(key.clone(), slot.clone());
+        entries.insertion_order.push_back(key);
+
+        while entries.tiles.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                entries.tiles.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+
+        slot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::util::Identifier;
+    use image::Rgba;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn it_finds_the_meta_tile_of_aligned_requests() {
+        let bbox = BoundingBox2D::new((2.5, -5.).into(), (5., -2.5).into()).unwrap();
+
+        let meta_tile =
+            MetaTile::for_request(bbox, 256, 256, SpatialReference::epsg_4326(), 4).unwrap();
+
+        assert_eq!(
+            meta_tile.bbox,
+            BoundingBox2D::new((0., -10.).into(), (10., 0.).into()).unwrap()
+        );
+        assert_eq!((meta_tile.width, meta_tile.height), (1024, 1024));
+        assert_eq!(meta_tile.index, [0, -1]);
+        assert_eq!(meta_tile.crop_offset, [256, 256]);
+    }
+
+    #[test]
+    fn it_ignores_unaligned_requests() {
+        let srs = SpatialReference::epsg_4326();
+
+        let unaligned = BoundingBox2D::new((2., -5.).into(), (4.5, -2.5).into()).unwrap();
+        assert!(MetaTile::for_request(unaligned, 256, 256, srs, 4).is_none());
+
+        let aligned = BoundingBox2D::new((2.5, -5.).into(), (5., -2.5).into()).unwrap();
+        assert!(MetaTile::for_request(aligned, 256, 256, srs, 1).is_none());
+
+        let outside_area_of_use =
+            BoundingBox2D::new((180., 0.).into(), (270., 90.).into()).unwrap();
+        assert!(MetaTile::for_request(outside_area_of_use, 256, 256, srs, 2).is_none());
+
+        let too_large = BoundingBox2D::new((2.5, -5.).into(), (5., -2.5).into()).unwrap();
+        assert!(MetaTile::for_request(too_large, 1024, 1024, srs, 4).is_none());
+    }
+
+    #[test]
+    fn it_crops_tiles() {
+        let bbox = BoundingBox2D::new((1., 0.).into(), (2., 1.).into()).unwrap();
+        let meta_tile =
+            MetaTile::for_request(bbox, 2, 2, SpatialReference::epsg_4326(), 2).unwrap();
+
+        // the requested tile is the lower right one
+        let meta_image = RgbaImage::from_fn(4, 4, |x, y| {
+            if x >= 2 && y >= 2 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        });
+
+        let tile = decode_meta_tile(&meta_tile.crop_png(&meta_image).unwrap()).unwrap();
+
+        assert_eq!(tile.dimensions(), (2, 2));
+        assert!(tile.pixels().all(|pixel| *pixel == Rgba([255, 0, 0, 255])));
+    }
+
+    #[tokio::test]
+    async fn it_renders_meta_tiles_once() {
+        let cache = MetaTileCache::new(1);
+        let rendering_count = AtomicUsize::new(0);
+        let renderings = &rendering_count;
+
+        let session = SessionId::new();
+        let workflow = WorkflowId::new();
+        let srs = SpatialReference::epsg_4326();
+        let time = TimeInterval::default();
+        let meta_tile = |x: f64| {
+            MetaTile::for_request(
+                BoundingBox2D::new((x, 0.).into(), (x + 1., 1.).into()).unwrap(),
+                2,
+                2,
+                srs,
+                2,
+            )
+            .unwrap()
+        };
+
+        let render = || async move {
+            renderings.fetch_add(1, Ordering::SeqCst);
+            Ok(RgbaImage::new(4, 4))
+        };
+
+        let key = MetaTileKey::new(session, workflow, "", srs, &meta_tile(0.), time);
+        let same_key = MetaTileKey::new(session, workflow, "", srs, &meta_tile(1.), time);
+        let other_key = MetaTileKey::new(session, workflow, "", srs, &meta_tile(2.), time);
+        assert_eq!(key, same_key);
+        assert_ne!(
+            key,
+            MetaTileKey::new(SessionId::new(), workflow, "", srs, &meta_tile(0.), time)
+        );
+
+        cache.get_or_render(key.clone(), render).await.unwrap();
+        cache.get_or_render(same_key, render).await.unwrap();
+        assert_eq!(renderings.load(Ordering::SeqCst), 1);
+
+        cache.get_or_render(other_key, render).await.unwrap();
+        cache.get_or_render(key, render).await.unwrap();
+        assert_eq!(renderings.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1 +1,3 @@
+pub mod meta_tiling;
 pub mod request;
+pub mod vector_rendering;
@@ -0,0 +1,337 @@
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, IntoGeometryIterator, MultiLineStringCollection,
+    MultiPointCollection, MultiPolygonCollection,
+};
+use geoengine_datatypes::operations::image::{RgbaColor, VectorCanvas};
+use geoengine_datatypes::primitives::{
+    FeatureDataValue, Geometry, MultiLineStringAccess, MultiPointAccess, MultiPolygonAccess,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use geoengine_operators::engine::{QueryContext, VectorQueryProcessor};
+use geoengine_operators::util::abortable_query_execution;
+
+use crate::error::{self, Result};
+use crate::projects::{
+    CategoryValue, ColorParam, LineSymbology, NumberParam, PointSymbology, PolygonSymbology,
+    StrokeParam, Symbology,
+};
+
+/// Feature collections that can be drawn onto a [`VectorCanvas`]
+pub trait RenderCollection {
+    /// The symbology for the geometry type of the collection
+    type Symbology: Send + Sync;
+
+    /// Selects the symbology for the collection or a default one if there is none
+    fn symbology(symbology: Option<Symbology>) -> Result<Self::Symbology>;
+
+    /// Draws all features, resolving attribute-driven style parameters per feature
+    fn render(
+        &self,
+        canvas: &mut VectorCanvas,
+        symbology: &Self::Symbology,
+    ) -> geoengine_datatypes::util::Result<()>;
+}
+
+impl RenderCollection for MultiPointCollection {
+    type Symbology = PointSymbology;
+
+    fn symbology(symbology: Option<Symbology>) -> Result<Self::Symbology> {
+        match symbology {
+            None => Ok(PointSymbology::default()),
+            Some(Symbology::Point(symbology)) => Ok(symbology),
+            Some(_) => Err(error::Error::SymbologyDoesNotMatchGeometryType),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut VectorCanvas,
+        symbology: &Self::Symbology,
+    ) -> geoengine_datatypes::util::Result<()> {
+        let radii = numbers(&symbology.radius, self)?;
+        let fill_colors = colors(&symbology.fill_color, self)?;
+        let (stroke_widths, stroke_colors) = stroke(&symbology.stroke, self)?;
+
+        for (i, multi_point) in self.geometries().enumerate() {
+            for &point in multi_point.points() {
+                canvas.draw_point(
+                    point,
+                    radii[i],
+                    fill_colors[i],
+                    stroke_widths[i],
+                    stroke_colors[i],
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RenderCollection for MultiLineStringCollection {
+    type Symbology = LineSymbology;
+
+    fn symbology(symbology: Option<Symbology>) -> Result<Self::Symbology> {
+        match symbology {
+            None => Ok(LineSymbology {
+                stroke: default_stroke(),
+                text: None,
+            }),
+            Some(Symbology::Line(symbology)) => Ok(symbology),
+            Some(_) => Err(error::Error::SymbologyDoesNotMatchGeometryType),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut VectorCanvas,
+        symbology: &Self::Symbology,
+    ) -> geoengine_datatypes::util::Result<()> {
+        let (stroke_widths, stroke_colors) = stroke(&symbology.stroke, self)?;
+
+        for (i, multi_line_string) in self.geometries().enumerate() {
+            for line in multi_line_string.lines() {
+                canvas.draw_line(line.as_ref(), stroke_widths[i], stroke_colors[i]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RenderCollection for MultiPolygonCollection {
+    type Symbology = PolygonSymbology;
+
+    fn symbology(symbology: Option<Symbology>) -> Result<Self::Symbology> {
+        match symbology {
+            None => Ok(PolygonSymbology {
+                fill_color: ColorParam::Static {
+                    color: RgbaColor::white(),
+                },
+                stroke: default_stroke(),
+                text: None,
+            }),
+            Some(Symbology::Polygon(symbology)) => Ok(symbology),
+            Some(_) => Err(error::Error::SymbologyDoesNotMatchGeometryType),
+        }
+    }
+
+    fn render(
+        &self,
+        canvas: &mut VectorCanvas,
+        symbology: &Self::Symbology,
+    ) -> geoengine_datatypes::util::Result<()> {
+        let fill_colors = colors(&symbology.fill_color, self)?;
+        let (stroke_widths, stroke_colors) = stroke(&symbology.stroke, self)?;
+
+        for (i, multi_polygon) in self.geometries().enumerate() {
+            for polygon in multi_polygon.polygons() {
+                canvas.draw_polygon(
+                    polygon.as_ref(),
+                    fill_colors[i],
+                    stroke_widths[i],
+                    stroke_colors[i],
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Queries the processor and draws all resulting features into a PNG image of size `width` x `height`
+#[allow(clippy::too_many_arguments)]
+pub async fn vector_stream_to_png_bytes<G, C: QueryContext + 'static>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    mut query_ctx: C,
+    width: u32,
+    height: u32,
+    symbology: <FeatureCollection<G> as RenderCollection>::Symbology,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<Vec<u8>>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: RenderCollection,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let rendered = async {
+        let mut canvas = VectorCanvas::new(width, height, query_rect.spatial_bounds);
+
+        let mut stream = processor.query(query_rect, &query_ctx).await?;
+        while let Some(collection) = stream.try_next().await? {
+            collection.render(&mut canvas, &symbology)?;
+        }
+
+        Ok(canvas)
+    };
+
+    let canvas = abortable_query_execution(rendered, conn_closed, query_abort_trigger).await?;
+
+    Ok(canvas.to_png()?)
+}
+
+fn default_stroke() -> StrokeParam {
+    StrokeParam {
+        width: NumberParam::Static { value: 1 },
+        color: ColorParam::Static {
+            color: RgbaColor::black(),
+        },
+    }
+}
+
+fn stroke<C: FeatureCollectionInfos>(
+    stroke: &StrokeParam,
+    collection: &C,
+) -> geoengine_datatypes::util::Result<(Vec<f64>, Vec<RgbaColor>)> {
+    Ok((
+        numbers(&stroke.width, collection)?,
+        colors(&stroke.color, collection)?,
+    ))
+}
+
+/// Resolves the color of each feature of the collection
+fn colors<C: FeatureCollectionInfos>(
+    param: &ColorParam,
+    collection: &C,
+) -> geoengine_datatypes::util::Result<Vec<RgbaColor>> {
+    Ok(match param {
+        ColorParam::Static { color } => vec![*color; collection.len()],
+        ColorParam::Derived(derived) => {
+            let colorizer: geoengine_datatypes::operations::image::Colorizer =
+                derived.colorizer.clone().try_into()?;
+            let color_mapper = colorizer.create_color_mapper();
+
+            collection
+                .data(&derived.attribute)?
+                .float_options_iter()
+                .map(|value| value.map_or(colorizer.no_data_color(), |v| color_mapper.call(v)))
+                .collect()
+        }
+        ColorParam::Graduated(graduated) => collection
+            .data(&graduated.attribute)?
+            .float_options_iter()
+            .map(|value| graduated.color(value))
+            .collect(),
+        ColorParam::Categorized(categorized) => {
+            let data = collection.data(&categorized.attribute)?;
+
+            (0..collection.len())
+                .map(|i| categorized.color(category_value(data.get_unchecked(i)).as_ref()))
+                .collect()
+        }
+    })
+}
+
+/// Resolves the number, e.g., the radius, of each feature of the collection
+fn numbers<C: FeatureCollectionInfos>(
+    param: &NumberParam,
+    collection: &C,
+) -> geoengine_datatypes::util::Result<Vec<f64>> {
+    Ok(match param {
+        NumberParam::Static { value } => vec![*value as f64; collection.len()],
+        NumberParam::Derived(derived) => collection
+            .data(&derived.attribute)?
+            .float_options_iter()
+            .map(|value| value.map_or(derived.default_value, |v| v * derived.factor))
+            .collect(),
+        NumberParam::Graduated(graduated) => collection
+            .data(&graduated.attribute)?
+            .float_options_iter()
+            .map(|value| graduated.value(value) as f64)
+            .collect(),
+    })
+}
+
+fn category_value(value: FeatureDataValue) -> Option<CategoryValue> {
+    match value {
+        FeatureDataValue::Category(value) | FeatureDataValue::NullableCategory(Some(value)) => {
+            Some(CategoryValue::Int(value.into()))
+        }
+        FeatureDataValue::Int(value) | FeatureDataValue::NullableInt(Some(value)) => {
+            Some(CategoryValue::Int(value))
+        }
+        FeatureDataValue::Text(value) | FeatureDataValue::NullableText(Some(value)) => {
+            Some(CategoryValue::Text(value))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::{ColorCategory, GraduatedNumber, NumberClass};
+    use geoengine_datatypes::primitives::{FeatureData, MultiPoint, TimeInterval};
+
+    #[test]
+    fn it_resolves_attribute_driven_styles() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "kind".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("forest".to_string()),
+                        Some("water".to_string()),
+                        None,
+                    ]),
+                ),
+                (
+                    "population".to_string(),
+                    FeatureData::NullableFloat(vec![Some(10.), Some(1000.), None]),
+                ),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+        let fill_colors = colors(
+            &ColorParam::Categorized(crate::projects::CategorizedColor {
+                attribute: "kind".to_string(),
+                categories: vec![ColorCategory {
+                    value: CategoryValue::Text("forest".to_string()),
+                    color: RgbaColor::new(0, 255, 0, 255),
+                }],
+                default_color: RgbaColor::transparent(),
+            }),
+            &collection,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fill_colors,
+            vec![
+                RgbaColor::new(0, 255, 0, 255),
+                RgbaColor::transparent(),
+                RgbaColor::transparent()
+            ]
+        );
+
+        let radii = numbers(
+            &NumberParam::Graduated(GraduatedNumber {
+                attribute: "population".to_string(),
+                classes: vec![
+                    NumberClass {
+                        lower_bound: 0.,
+                        value: 2,
+                    },
+                    NumberClass {
+                        lower_bound: 100.,
+                        value: 8,
+                    },
+                ],
+                default_value: 1,
+            }),
+            &collection,
+        )
+        .unwrap();
+
+        assert_eq!(radii, vec![2., 8., 1.]);
+    }
+}
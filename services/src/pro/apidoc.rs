@@ -3,60 +3,127 @@ use crate::api::model::datatypes::{
     Coordinate2D, DataId, DataProviderId, DatasetId, DateTime, ExternalDataId, FeatureDataType,
     LayerId, Measurement, Palette, RasterDataType, RasterQueryRectangle, RgbaColor,
     SpatialPartition2D, SpatialReference, SpatialReferenceAuthority, SpatialReferenceOption,
-    SpatialResolution, TimeInstance, TimeInterval, VectorDataType,
+    SpatialResolution, TimeGranularity, TimeInstance, TimeInterval, TimeStep, VectorDataType,
+    VectorQueryRectangle,
 };
 use crate::api::model::operators::{
     PlotResultDescriptor, RasterResultDescriptor, TypedOperator, TypedResultDescriptor,
     VectorColumnInfo, VectorResultDescriptor,
 };
 use crate::contexts::SessionId;
-use crate::datasets::listing::{Provenance, ProvenanceOutput};
-use crate::datasets::upload::UploadId;
+use crate::datasets::listing::{DatasetListing, Provenance, ProvenanceOutput};
+use crate::datasets::storage::{
+    AddDataset, AppendTimeSlices, AutoCreateDataset, BulkCreateDatasetResult, BulkCreateDatasets,
+    BulkCreateDatasetsReport, CreateDataset, Dataset, DatasetDefinition, MetaDataSuggestion,
+};
+use crate::datasets::upload::{StorageUsage, UploadId};
 use crate::handlers;
-use crate::handlers::tasks::TaskAbortOptions;
+use crate::handlers::datasets::{DatasetUsage, DatasetUsageReport};
+use crate::handlers::layers::ProviderSecrets;
+use crate::handlers::plots::WrappedPlotOutput;
+use crate::handlers::spatial_references::{AxisOrder, SpatialReferenceSpecification};
+use crate::handlers::tasks::{TaskAbortOptions, TaskResponse};
 use crate::handlers::wcs::CoverageResponse;
 use crate::handlers::wfs::{CollectionType, Coordinates, Feature, FeatureType, GeoJson};
 use crate::handlers::wms::MapResponse;
-use crate::handlers::workflows::{RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult};
+use crate::handlers::workflows::{
+    FeatureExport, FeatureExportFormat, FeatureExportRaster, QueryEstimate,
+    RasterCoordinateSamples, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult,
+    RasterExport, RasterExportFormat, RasterExportResult, RasterTileFormat, RasterTileOutput,
+    RasterValueSample, TableFormat, VectorExport, VectorExportFormat, WorkflowAliasTarget,
+    WorkflowSubstitutions, WorkflowTemplateValues,
+};
+use crate::handlers::ErrorResponse;
+use crate::layers::external::{ProviderCapabilities, ProviderHealth, ProviderHealthStatus};
 use crate::layers::layer::{
     CollectionItem, Layer, LayerCollection, LayerCollectionListing, LayerListing, Property,
     ProviderLayerCollectionId, ProviderLayerId,
 };
 use crate::layers::listing::LayerCollectionId;
+use crate::layers::search::LayerSearchResult;
 use crate::ogc::util::OgcBoundingBox;
 
 use crate::ogc::{wcs, wfs, wms};
 use crate::pro;
 use crate::projects::{
-    ColorParam, DerivedColor, DerivedNumber, LineSymbology, NumberParam, PointSymbology,
-    PolygonSymbology, ProjectId, RasterSymbology, STRectangle, StrokeParam, Symbology,
-    TextSymbology,
+    CategorizedColor, CategoryValue, ColorCategory, ColorClass, ColorParam, CreateProject,
+    DerivedColor, DerivedNumber, GraduatedColor, GraduatedNumber, Layer as ProjectLayer,
+    LayerVisibility, LineSymbology, NumberClass, NumberParam, OrderBy, Plot, PointSymbology,
+    PolygonSymbology, Project, ProjectFilter, ProjectId, ProjectListing, ProjectVersion,
+    ProjectVersionId, RasterSymbology, STRectangle, StrokeParam, Symbology, TextSymbology,
+    UpdateProject,
 };
+use crate::reports::{ReportFormat, ReportPlot, ReportRequest};
 use crate::tasks::{TaskFilter, TaskId, TaskListOptions, TaskStatus};
-use crate::util::server::ServerInfo;
-use crate::util::{apidoc::OpenApiServerInfo, IdResponse};
-use crate::workflows::workflow::{Workflow, WorkflowId};
+use crate::util::server::{GdalDatasetPoolMetrics, MemoryMetrics, ServerInfo};
+use crate::util::{
+    apidoc::{ErrorResponseAddon, OpenApiServerInfo},
+    preflight::{DefinitionKind, PreflightIssue, PreflightReport, PreflightSeverity},
+    IdResponse,
+};
+use crate::workflows::style_preset::{StylePreset, StylePresetName};
+use crate::workflows::subscription::{
+    SubscriptionAggregate, SubscriptionComparison, SubscriptionCondition, SubscriptionNotification,
+    SubscriptionStatus, WorkflowSubscription,
+};
+use crate::workflows::template::{
+    TemplateParameter, TemplateParameterType, WorkflowTemplate, WorkflowTemplateId,
+    WorkflowTemplateListing,
+};
+use crate::workflows::workflow::{SourceSubstitution, Workflow, WorkflowAlias, WorkflowId};
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 
 use super::datasets::RoleId;
-use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSession};
+use super::handlers::users::UserDetails;
+use super::users::{
+    AddTenant, Tenant, TenantId, UserCredentials, UserId, UserInfo, UserRegistration, UserSession,
+};
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         crate::util::server::server_info_handler,
+        crate::util::server::memory_metrics_handler,
+        crate::util::server::gdal_dataset_pool_metrics_handler,
+        handlers::datasets::list_datasets_handler,
+        handlers::datasets::dataset_usage_handler,
+        handlers::datasets::get_dataset_handler,
+        handlers::datasets::list_dependent_workflows_handler,
+        handlers::datasets::append_time_slices_handler,
+        handlers::datasets::get_auxiliary_metadata_handler,
+        handlers::datasets::set_auxiliary_metadata_handler,
+        handlers::datasets::validate_dataset_handler,
+        handlers::datasets::create_dataset_handler,
+        handlers::datasets::bulk_create_datasets_handler,
+        handlers::datasets::auto_create_dataset_handler,
+        handlers::datasets::suggest_meta_data_handler,
         handlers::layers::layer_handler,
         handlers::layers::list_collection_handler,
+        handlers::layers::search_layers_handler,
         handlers::layers::list_root_collections_handler,
+        handlers::layers::provider_capabilities_handler,
+        handlers::layers::provider_health_handler,
+        handlers::layers::provider_definition_handler,
+        handlers::layers::update_provider_secrets_handler,
+        handlers::layers::invalidate_provider_cache_handler,
+        handlers::plots::get_plot_handler,
+        handlers::preflight::preflight_report_handler,
+        handlers::projects::create_project_handler,
+        handlers::projects::list_projects_handler,
+        handlers::projects::update_project_handler,
+        handlers::projects::delete_project_handler,
+        handlers::reports::project_report_handler,
+        handlers::spatial_references::get_spatial_reference_specification_handler,
         handlers::tasks::abort_handler,
         handlers::tasks::list_handler,
         handlers::tasks::status_handler,
+        handlers::upload::upload_handler,
+        handlers::upload::download_upload_file_handler,
         handlers::wcs::wcs_capabilities_handler,
         handlers::wcs::wcs_describe_coverage_handler,
         handlers::wcs::wcs_get_coverage_handler,
         handlers::wfs::wfs_capabilities_handler,
-        handlers::wfs::wfs_capabilities_handler,
         handlers::wfs::wfs_feature_handler,
         handlers::wms::wms_capabilities_handler,
         handlers::wms::wms_legend_graphic_handler,
@@ -64,21 +131,56 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
         handlers::workflows::dataset_from_workflow_handler,
         handlers::workflows::get_workflow_metadata_handler,
         handlers::workflows::get_workflow_provenance_handler,
+        handlers::workflows::get_workflow_all_metadata_zip_handler,
         handlers::workflows::load_workflow_handler,
+        handlers::workflows::substitute_workflow_sources_handler,
         handlers::workflows::register_workflow_handler,
+        handlers::workflows::register_workflow_version_handler,
+        handlers::workflows::list_workflow_versions_handler,
+        handlers::workflows::set_workflow_alias_handler,
+        handlers::workflows::resolve_workflow_alias_handler,
+        handlers::workflows::set_workflow_style_preset_handler,
+        handlers::workflows::load_workflow_style_preset_handler,
+        handlers::workflows::list_workflow_style_presets_handler,
+        handlers::workflows::remove_workflow_style_preset_handler,
+        handlers::workflows::register_workflow_template_handler,
+        handlers::workflows::load_workflow_template_handler,
+        handlers::workflows::list_workflow_templates_handler,
+        handlers::workflows::instantiate_workflow_template_handler,
+        handlers::workflows::sample_raster_workflow_handler,
+        handlers::workflows::raster_tile_workflow_handler,
+        handlers::workflows::estimate_workflow_query_handler,
+        handlers::workflows::export_vector_workflow_handler,
+        handlers::workflows::export_features_workflow_handler,
+        handlers::workflows::table_workflow_handler,
+        handlers::workflows::export_raster_workflow_handler,
+        handlers::workflows::subscribe_workflow_handler,
         pro::handlers::users::anonymous_handler,
         pro::handlers::users::login_handler,
         pro::handlers::users::logout_handler,
         pro::handlers::users::register_user_handler,
         pro::handlers::users::session_handler,
+        pro::handlers::users::session_project_handler,
+        pro::handlers::users::session_view_handler,
+        pro::handlers::users::user_handler,
+        pro::handlers::users::add_tenant_handler,
+        pro::handlers::users::list_tenants_handler,
+        pro::handlers::users::set_user_tenant_handler,
+        pro::handlers::projects::load_project_latest_handler,
     ),
     components(
         schemas(
+            ErrorResponse,
             UserSession,
             UserCredentials,
             UserRegistration,
             DateTime,
             UserInfo,
+            Tenant,
+            TenantId,
+            AddTenant,
+            UserDetails,
+            StorageUsage,
 
             DataId,
             DataProviderId,
@@ -87,18 +189,25 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
             IdResponse<WorkflowId>,
             LayerId,
             ProjectId,
+            ProjectVersionId,
             RoleId,
             SessionId,
             TaskId,
             UploadId,
             UserId,
             WorkflowId,
+            WorkflowAlias,
+            StylePresetName,
+            StylePreset,
+            WorkflowTemplateId,
             ProviderLayerId,
             ProviderLayerCollectionId,
             LayerCollectionId,
 
             TimeInstance,
             TimeInterval,
+            TimeStep,
+            TimeGranularity,
 
             Coordinate2D,
             BoundingBox2D,
@@ -115,11 +224,44 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
             ProvenanceOutput,
             Provenance,
 
+            Dataset,
+            DatasetListing,
+            DatasetUsage,
+            DatasetUsageReport,
+            DatasetDefinition,
+            AddDataset,
+            CreateDataset,
+            BulkCreateDatasets,
+            BulkCreateDatasetsReport,
+            BulkCreateDatasetResult,
+            AppendTimeSlices,
+            AutoCreateDataset,
+            MetaDataSuggestion,
+
+            Project,
+            ProjectVersion,
+            ProjectLayer,
+            LayerVisibility,
+            Plot,
+            ProjectListing,
+            ProjectFilter,
+            OrderBy,
+            CreateProject,
+            UpdateProject,
+            ReportRequest,
+            ReportPlot,
+            ReportFormat,
+            WrappedPlotOutput,
+            SpatialReferenceSpecification,
+            AxisOrder,
+
             VectorDataType,
             FeatureDataType,
             RasterDataType,
 
             ServerInfo,
+            MemoryMetrics,
+            GdalDatasetPoolMetrics,
 
             Workflow,
             TypedOperator,
@@ -130,8 +272,37 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
             VectorColumnInfo,
             RasterDatasetFromWorkflow,
             RasterDatasetFromWorkflowResult,
+            RasterCoordinateSamples,
+            RasterValueSample,
+            RasterTileOutput,
+            RasterTileFormat,
+            QueryEstimate,
+            VectorExport,
+            VectorExportFormat,
+            FeatureExport,
+            FeatureExportFormat,
+            FeatureExportRaster,
+            TableFormat,
+            RasterExport,
+            RasterExportFormat,
+            RasterExportResult,
+            TaskResponse,
+            WorkflowAliasTarget,
+            WorkflowTemplate,
+            TemplateParameter,
+            TemplateParameterType,
+            WorkflowTemplateListing,
+            WorkflowTemplateValues,
+            WorkflowSubstitutions,
+            SourceSubstitution,
+            WorkflowSubscription,
+            SubscriptionCondition,
+            SubscriptionAggregate,
+            SubscriptionComparison,
+            SubscriptionStatus,
+            SubscriptionNotification,
             RasterQueryRectangle,
-            // VectorQueryRectangle,
+            VectorQueryRectangle,
             // PlotQueryRectangle,
 
             TaskAbortOptions,
@@ -145,13 +316,30 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
             LayerCollectionListing,
             Property,
             CollectionItem,
+            ProviderCapabilities,
+            ProviderHealth,
+            LayerSearchResult,
+            ProviderHealthStatus,
+            ProviderSecrets,
+
+            PreflightReport,
+            PreflightIssue,
+            PreflightSeverity,
+            DefinitionKind,
 
             Breakpoint,
+            CategorizedColor,
+            CategoryValue,
+            ColorCategory,
+            ColorClass,
             ColorParam,
             Colorizer,
             DerivedColor,
             DerivedNumber,
+            GraduatedColor,
+            GraduatedNumber,
             LineSymbology,
+            NumberClass,
             NumberParam,
             Palette,
             PointSymbology,
@@ -197,7 +385,7 @@ use super::users::{UserCredentials, UserId, UserInfo, UserRegistration, UserSess
             Coordinates,
         ),
     ),
-    modifiers(&SecurityAddon, &ApiDocInfo, &OpenApiServerInfo),
+    modifiers(&SecurityAddon, &ApiDocInfo, &OpenApiServerInfo, &ErrorResponseAddon),
     external_docs(url = "https://docs.geoengine.io", description = "Geo Engine Docs")
 )]
 pub struct ApiDoc;
@@ -217,6 +405,18 @@ impl Modify for SecurityAddon {
                     .build(),
             ),
         );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("UUID")
+                    .description(Some(
+                        "Use the configured admin session token to authenticate.",
+                    ))
+                    .build(),
+            ),
+        );
     }
 }
 
@@ -243,3 +443,50 @@ impl Modify for ApiDocInfo {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::apidoc::ApiDocCheck;
+    use std::collections::BTreeSet;
+
+    fn paths(openapi: utoipa::openapi::OpenApi) -> BTreeSet<String> {
+        openapi.paths.paths.into_keys().collect()
+    }
+
+    #[test]
+    fn it_documents_the_paths_of_both_editions() {
+        let open_paths = paths(crate::apidoc::ApiDoc::openapi());
+        let pro_paths = paths(ApiDoc::openapi());
+
+        assert!(
+            open_paths.is_subset(&pro_paths),
+            "missing in the pro docs: {:?}",
+            open_paths.difference(&pro_paths).collect::<Vec<_>>()
+        );
+
+        // the user and tenant management is only available in the pro edition
+        assert_eq!(
+            pro_paths
+                .difference(&open_paths)
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            vec![
+                "/login",
+                "/logout",
+                "/tenant",
+                "/tenant/{tenant}/users/{user}",
+                "/tenants",
+                "/user"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_passes_the_self_check() {
+        for openapi in [crate::apidoc::ApiDoc::openapi(), ApiDoc::openapi()] {
+            let check = ApiDocCheck::new(&openapi);
+            assert!(check.valid, "{:?}", check.problems);
+        }
+    }
+}
@@ -1,22 +1,25 @@
-use crate::contexts::QueryContextImpl;
+use crate::contexts::{
+    create_cpu_thread_pool, create_io_thread_pool, create_statistics_cache, QueryContextImpl,
+};
 use crate::error;
 use crate::layers::add_from_directory::{
     add_layer_collections_from_directory, add_layers_from_directory,
 };
-use crate::layers::storage::{HashMapLayerDb, HashMapLayerProviderDb};
+use crate::layers::storage::HashMapLayerDb;
 use crate::pro::contexts::{Context, ProContext};
 use crate::pro::datasets::{add_datasets_from_directory, ProHashMapDatasetDb};
+use crate::pro::layers::hashmap_layer_provider_db::ProHashMapLayerProviderDb;
 use crate::pro::projects::ProHashMapProjectDb;
 use crate::pro::users::{HashMapUserDb, OidcRequestDb, UserDb, UserSession};
 use crate::pro::util::config::Oidc;
+use crate::pro::util::operators::operator_policies;
+use crate::pro::workflows::hashmap_workflow_registry::ProHashMapRegistry;
 use crate::tasks::{SimpleTaskManager, SimpleTaskManagerContext};
-use crate::workflows::registry::HashMapRegistry;
 use crate::{datasets::add_from_directory::add_providers_from_directory, error::Result};
 use async_trait::async_trait;
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_datatypes::util::test::TestDefault;
-use geoengine_operators::engine::ChunkByteSize;
-use geoengine_operators::util::create_rayon_thread_pool;
+use geoengine_operators::engine::{ChunkByteSize, InMemoryStatisticsCache};
 use rayon::ThreadPool;
 use snafu::ResultExt;
 use std::path::PathBuf;
@@ -29,11 +32,13 @@ use super::ExecutionContextImpl;
 pub struct ProInMemoryContext {
     user_db: Arc<HashMapUserDb>,
     project_db: Arc<ProHashMapProjectDb>,
-    workflow_registry: Arc<HashMapRegistry>,
+    workflow_registry: Arc<ProHashMapRegistry>,
     dataset_db: Arc<ProHashMapDatasetDb>,
     layer_db: Arc<HashMapLayerDb>,
-    layer_provider_db: Arc<HashMapLayerProviderDb>,
+    layer_provider_db: Arc<ProHashMapLayerProviderDb>,
     thread_pool: Arc<ThreadPool>,
+    io_thread_pool: Arc<ThreadPool>,
+    statistics_cache: Arc<InMemoryStatisticsCache>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
     task_manager: Arc<SimpleTaskManager>,
@@ -49,7 +54,9 @@ impl TestDefault for ProInMemoryContext {
             dataset_db: Default::default(),
             layer_db: Default::default(),
             layer_provider_db: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
             task_manager: Default::default(),
@@ -76,9 +83,21 @@ impl ProInMemoryContext {
         let mut dataset_db = ProHashMapDatasetDb::default();
         add_datasets_from_directory(&mut dataset_db, dataset_defs_path).await;
 
-        let mut layer_provider_db = HashMapLayerProviderDb::default();
-        add_providers_from_directory(&mut layer_provider_db, provider_defs_path.clone()).await;
-        add_providers_from_directory(&mut layer_provider_db, provider_defs_path.join("pro")).await;
+        let mut layer_provider_db = ProHashMapLayerProviderDb::default();
+        // providers from the directory are shared by all tenants
+        let system_session = UserSession::system_session();
+        add_providers_from_directory(
+            &system_session,
+            &mut layer_provider_db,
+            provider_defs_path.clone(),
+        )
+        .await;
+        add_providers_from_directory(
+            &system_session,
+            &mut layer_provider_db,
+            provider_defs_path.join("pro"),
+        )
+        .await;
 
         Self {
             user_db: Default::default(),
@@ -88,7 +107,9 @@ impl ProInMemoryContext {
             layer_db: Arc::new(layer_db),
             layer_provider_db: Arc::new(layer_provider_db),
             task_manager: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             oidc_request_db: Arc::new(OidcRequestDb::try_from(oidc_config).ok()),
@@ -107,7 +128,9 @@ impl ProInMemoryContext {
             layer_db: Default::default(),
             layer_provider_db: Default::default(),
             task_manager: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             oidc_request_db: Arc::new(None),
@@ -122,7 +145,9 @@ impl ProInMemoryContext {
             dataset_db: Default::default(),
             layer_db: Default::default(),
             layer_provider_db: Default::default(),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
             task_manager: Default::default(),
@@ -150,13 +175,13 @@ impl ProContext for ProInMemoryContext {
 impl Context for ProInMemoryContext {
     type Session = UserSession;
     type ProjectDB = ProHashMapProjectDb;
-    type WorkflowRegistry = HashMapRegistry;
+    type WorkflowRegistry = ProHashMapRegistry;
     type DatasetDB = ProHashMapDatasetDb;
     type LayerDB = HashMapLayerDb;
-    type LayerProviderDB = HashMapLayerProviderDb;
+    type LayerProviderDB = ProHashMapLayerProviderDb;
     type QueryContext = QueryContextImpl;
     type ExecutionContext =
-        ExecutionContextImpl<UserSession, ProHashMapDatasetDb, HashMapLayerProviderDb>;
+        ExecutionContextImpl<UserSession, ProHashMapDatasetDb, ProHashMapLayerProviderDb>;
     type TaskContext = SimpleTaskManagerContext;
     type TaskManager = SimpleTaskManager;
 
@@ -210,16 +235,22 @@ impl Context for ProInMemoryContext {
     }
 
     fn execution_context(&self, session: UserSession) -> Result<Self::ExecutionContext> {
+        let operator_policies = operator_policies(&session.roles);
+
         Ok(ExecutionContextImpl::<
             UserSession,
             ProHashMapDatasetDb,
-            HashMapLayerProviderDb,
+            ProHashMapLayerProviderDb,
         >::new(
             self.dataset_db.clone(),
             self.layer_provider_db.clone(),
             self.thread_pool.clone(),
+            self.io_thread_pool.clone(),
             session,
             self.exe_ctx_tiling_spec,
+            self.statistics_cache.clone(),
+            self.workflow_registry.clone(),
+            operator_policies,
         ))
     }
 
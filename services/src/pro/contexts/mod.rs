@@ -9,8 +9,10 @@ use geoengine_datatypes::dataset::DataId;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_operators::engine::{
-    CreateSpan, ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, MetaData,
-    MetaDataProvider, RasterResultDescriptor, VectorResultDescriptor,
+    AuxiliaryMetadata, AuxiliaryMetadataProvider, CreateSpan, ExecutionContext,
+    InitializedPlotOperator, InitializedVectorOperator, MetaData, MetaDataProvider, OperatorPolicy,
+    PathSandbox, RasterResultDescriptor, StatisticsCache, TypedOperator, VectorResultDescriptor,
+    WorkflowProvider,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::pro::meta::statistics::InitializedProcessorStatistics;
@@ -21,10 +23,14 @@ pub use postgres::PostgresContext;
 use rayon::ThreadPool;
 
 use crate::contexts::{Context, Session};
-use crate::datasets::listing::SessionMetaDataProvider;
+use crate::datasets::listing::{DatasetProvider, SessionMetaDataProvider};
 use crate::datasets::storage::DatasetDb;
+use crate::datasets::usage::record_dataset_access;
 use crate::layers::storage::LayerProviderDb;
 use crate::pro::users::{OidcRequestDb, UserDb, UserSession};
+use crate::util::operators::PATH_SANDBOX;
+use crate::workflows::registry::WorkflowRegistry;
+use crate::workflows::workflow::WorkflowId;
 
 use async_trait::async_trait;
 
@@ -42,35 +48,49 @@ pub trait ProContext: Context<Session = UserSession> {
 pub struct ExecutionContextImpl<S, D, L>
 where
     D: DatasetDb<S>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     dataset_db: Arc<D>,
     layer_provider_db: Arc<L>,
     thread_pool: Arc<ThreadPool>,
+    io_thread_pool: Arc<ThreadPool>,
     session: S,
     tiling_specification: TilingSpecification,
+    statistics_cache: Arc<dyn StatisticsCache>,
+    workflow_registry: Arc<dyn WorkflowRegistry<S>>,
+    /// an operator is allowed if any of the policies allows it
+    operator_policies: Vec<&'static OperatorPolicy>,
 }
 
 impl<S, D, L> ExecutionContextImpl<S, D, L>
 where
     D: DatasetDb<S>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         dataset_db: Arc<D>,
         layer_provider_db: Arc<L>,
         thread_pool: Arc<ThreadPool>,
+        io_thread_pool: Arc<ThreadPool>,
         session: S,
         tiling_specification: TilingSpecification,
+        statistics_cache: Arc<dyn StatisticsCache>,
+        workflow_registry: Arc<dyn WorkflowRegistry<S>>,
+        operator_policies: Vec<&'static OperatorPolicy>,
     ) -> Self {
         Self {
             dataset_db,
             layer_provider_db,
             thread_pool,
+            io_thread_pool,
             session,
             tiling_specification,
+            statistics_cache,
+            workflow_registry,
+            operator_policies,
         }
     }
 }
@@ -85,13 +105,17 @@ where
             VectorQueryRectangle,
         > + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     fn thread_pool(&self) -> &Arc<ThreadPool> {
         &self.thread_pool
     }
 
+    fn io_thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.io_thread_pool
+    }
+
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
@@ -120,6 +144,75 @@ where
         // as plots do not produce a stream of results, we have nothing to count for now
         op
     }
+
+    fn statistics_cache(&self) -> Option<Arc<dyn StatisticsCache>> {
+        Some(self.statistics_cache.clone())
+    }
+
+    fn ensure_operator_is_allowed(&self, operator: &str) -> geoengine_operators::util::Result<()> {
+        if self
+            .operator_policies
+            .iter()
+            .any(|policy| policy.is_allowed(operator))
+        {
+            Ok(())
+        } else {
+            Err(geoengine_operators::error::Error::OperatorNotAllowed {
+                operator: operator.to_string(),
+            })
+        }
+    }
+
+    fn path_sandbox(&self) -> &PathSandbox {
+        &PATH_SANDBOX
+    }
+}
+
+#[async_trait]
+impl<S, D, L> AuxiliaryMetadataProvider for ExecutionContextImpl<S, D, L>
+where
+    D: DatasetDb<S>,
+    L: LayerProviderDb<S>,
+    S: Session,
+{
+    async fn auxiliary_metadata(
+        &self,
+        data_id: &DataId,
+    ) -> Result<Option<AuxiliaryMetadata>, geoengine_operators::error::Error> {
+        match data_id {
+            DataId::Internal { dataset_id } => self
+                .dataset_db
+                .auxiliary_metadata(&self.session, &(*dataset_id).into())
+                .await
+                .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
+                    source: Box::new(e),
+                }),
+            // external providers do not offer auxiliary metadata
+            DataId::External(_) => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl<S, D, L> WorkflowProvider for ExecutionContextImpl<S, D, L>
+where
+    D: DatasetDb<S>,
+    L: LayerProviderDb<S>,
+    S: Session,
+{
+    async fn workflow(
+        &self,
+        id: &uuid::Uuid,
+    ) -> Result<TypedOperator, geoengine_operators::error::Error> {
+        self.workflow_registry
+            .load(&self.session, &WorkflowId(*id))
+            .await
+            .map(|workflow| workflow.operator)
+            .map_err(|e| geoengine_operators::error::Error::WorkflowReference {
+                workflow: *id,
+                source: Box::new(e),
+            })
+    }
 }
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
@@ -135,7 +228,7 @@ where
             VectorResultDescriptor,
             VectorQueryRectangle,
         >,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -152,16 +245,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
@@ -180,7 +279,7 @@ impl<S, D, L> MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQ
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -191,16 +290,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
@@ -219,7 +324,7 @@ impl<S, D, L> MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQu
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
-    L: LayerProviderDb,
+    L: LayerProviderDb<S>,
     S: Session,
 {
     async fn meta_data(
@@ -230,16 +335,22 @@ where
         geoengine_operators::error::Error,
     > {
         match data_id {
-            DataId::Internal { dataset_id: _ } => self
-                .dataset_db
-                .session_meta_data(&self.session, &data_id.clone().into())
-                .await
-                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
-                    source: Box::new(e),
-                }),
+            DataId::Internal { dataset_id } => {
+                let meta_data = self
+                    .dataset_db
+                    .session_meta_data(&self.session, &data_id.clone().into())
+                    .await
+                    .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                        source: Box::new(e),
+                    })?;
+
+                record_dataset_access(self.dataset_db.as_ref(), (*dataset_id).into()).await;
+
+                Ok(meta_data)
+            }
             DataId::External(external) => {
                 self.layer_provider_db
-                    .layer_provider(external.provider_id.into())
+                    .layer_provider(&self.session, external.provider_id.into())
                     .await
                     .map_err(|e| geoengine_operators::error::Error::DatasetMetaData {
                         source: Box::new(e),
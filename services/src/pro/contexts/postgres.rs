@@ -1,3 +1,4 @@
+use crate::contexts::{create_cpu_thread_pool, create_io_thread_pool, create_statistics_cache};
 use crate::datasets::add_from_directory::add_providers_from_directory;
 use crate::error::{self, Result};
 use crate::layers::add_from_directory::{
@@ -7,8 +8,9 @@ use crate::layers::storage::INTERNAL_LAYER_DB_ROOT_COLLECTION_ID;
 use crate::pro::datasets::{add_datasets_from_directory, PostgresDatasetDb, Role};
 use crate::pro::layers::postgres_layer_db::{PostgresLayerDb, PostgresLayerProviderDb};
 use crate::pro::projects::ProjectPermission;
-use crate::pro::users::{OidcRequestDb, UserDb, UserId, UserSession};
+use crate::pro::users::{OidcRequestDb, Tenant, UserDb, UserId, UserSession};
 use crate::pro::util::config::Oidc;
+use crate::pro::util::operators::operator_policies;
 use crate::pro::workflows::postgres_workflow_registry::PostgresWorkflowRegistry;
 use crate::projects::ProjectId;
 use crate::tasks::{SimpleTaskManager, SimpleTaskManagerContext};
@@ -22,8 +24,7 @@ use bb8_postgres::{
     PostgresConnectionManager,
 };
 use geoengine_datatypes::raster::TilingSpecification;
-use geoengine_operators::engine::ChunkByteSize;
-use geoengine_operators::util::create_rayon_thread_pool;
+use geoengine_operators::engine::{ChunkByteSize, InMemoryStatisticsCache};
 use log::{debug, warn};
 use rayon::ThreadPool;
 use snafu::ResultExt;
@@ -50,6 +51,8 @@ where
     layer_db: Arc<PostgresLayerDb<Tls>>,
     layer_provider_db: Arc<PostgresLayerProviderDb<Tls>>,
     thread_pool: Arc<ThreadPool>,
+    io_thread_pool: Arc<ThreadPool>,
+    statistics_cache: Arc<InMemoryStatisticsCache>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
     task_manager: Arc<SimpleTaskManager>,
@@ -83,7 +86,9 @@ where
             layer_db: Arc::new(PostgresLayerDb::new(pool.clone())),
             layer_provider_db: Arc::new(PostgresLayerProviderDb::new(pool.clone())),
             task_manager: Arc::new(SimpleTaskManager::default()),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             oidc_request_db: Arc::new(None),
@@ -121,8 +126,20 @@ where
 
         let mut layer_provider_db = PostgresLayerProviderDb::new(pool.clone());
 
-        add_providers_from_directory(&mut layer_provider_db, provider_defs_path.clone()).await;
-        add_providers_from_directory(&mut layer_provider_db, provider_defs_path.join("pro")).await;
+        // providers from the directory are shared by all tenants
+        let system_session = UserSession::system_session();
+        add_providers_from_directory(
+            &system_session,
+            &mut layer_provider_db,
+            provider_defs_path.clone(),
+        )
+        .await;
+        add_providers_from_directory(
+            &system_session,
+            &mut layer_provider_db,
+            provider_defs_path.join("pro"),
+        )
+        .await;
 
         Ok(Self {
             user_db: Arc::new(PostgresUserDb::new(pool.clone())),
@@ -132,7 +149,9 @@ where
             layer_db: Arc::new(layer_db),
             layer_provider_db: Arc::new(PostgresLayerProviderDb::new(pool.clone())),
             task_manager: Arc::new(SimpleTaskManager::default()),
-            thread_pool: create_rayon_thread_pool(0),
+            thread_pool: create_cpu_thread_pool(),
+            io_thread_pool: create_io_thread_pool(),
+            statistics_cache: create_statistics_cache(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             oidc_request_db: Arc::new(OidcRequestDb::try_from(oidc_config).ok()),
@@ -186,11 +205,21 @@ where
                             ('{user_role_id}', 'user'),
                             ('{anonymous_role_id}', 'anonymous');
 
+                        CREATE TABLE tenants (
+                            id UUID PRIMARY KEY,
+                            name text NOT NULL
+                        );
+
+                        INSERT INTO tenants (id, name) VALUES
+                            ('{system_tenant_id}', 'system'),
+                            ('{default_tenant_id}', 'default');
+
                         CREATE TABLE users (
                             id UUID PRIMARY KEY REFERENCES roles(id),
                             email character varying (256) UNIQUE,
                             password_hash character varying (256),
                             real_name character varying (256),
+                            tenant_id UUID REFERENCES tenants(id) NOT NULL DEFAULT '{default_tenant_id}',
                             active boolean NOT NULL
                             CONSTRAINT users_anonymous_ck CHECK (
                                (email IS NULL AND password_hash IS NULL AND real_name IS NULL) OR 
@@ -204,12 +233,14 @@ where
                             email,
                             password_hash,
                             real_name,
+                            tenant_id,
                             active)
                         VALUES (
                             '{system_role_id}', 
                             'system@geoengine.io',
                             '',
                             'system',
+                            '{system_tenant_id}',
                             true
                         );
 
@@ -332,13 +363,60 @@ where
                             PRIMARY KEY (user_id, project_id)
                         );
 
+                        -- workflows are registered per tenant
                         CREATE TABLE workflows (
+                            tenant_id UUID REFERENCES tenants(id) ON DELETE CASCADE NOT NULL,
+                            id UUID NOT NULL,
+                            workflow json NOT NULL,
+                            PRIMARY KEY (tenant_id, id)
+                        );
+
+                        -- index of the data that is used by a workflow
+                        CREATE TABLE workflow_data (
+                            tenant_id UUID NOT NULL,
+                            workflow_id UUID NOT NULL,
+                            data_id jsonb NOT NULL,
+                            PRIMARY KEY (tenant_id, workflow_id, data_id),
+                            FOREIGN KEY (tenant_id, workflow_id) REFERENCES workflows(tenant_id, id) ON DELETE CASCADE
+                        );
+
+                        CREATE TABLE workflow_versions (
+                            tenant_id UUID NOT NULL,
+                            workflow_id UUID NOT NULL,
+                            parent_id UUID NOT NULL,
+                            PRIMARY KEY (tenant_id, workflow_id),
+                            FOREIGN KEY (tenant_id, workflow_id) REFERENCES workflows(tenant_id, id) ON DELETE CASCADE,
+                            FOREIGN KEY (tenant_id, parent_id) REFERENCES workflows(tenant_id, id) ON DELETE CASCADE
+                        );
+
+                        CREATE TABLE workflow_aliases (
+                            tenant_id UUID NOT NULL,
+                            alias text NOT NULL,
+                            workflow_id UUID NOT NULL,
+                            PRIMARY KEY (tenant_id, alias),
+                            FOREIGN KEY (tenant_id, workflow_id) REFERENCES workflows(tenant_id, id) ON DELETE CASCADE
+                        );
+
+                        CREATE TABLE workflow_style_presets (
+                            tenant_id UUID NOT NULL,
+                            workflow_id UUID NOT NULL,
+                            name text NOT NULL,
+                            symbology json NOT NULL,
+                            PRIMARY KEY (tenant_id, workflow_id, name),
+                            FOREIGN KEY (tenant_id, workflow_id) REFERENCES workflows(tenant_id, id) ON DELETE CASCADE
+                        );
+
+                        CREATE TABLE workflow_templates (
                             id UUID PRIMARY KEY,
-                            workflow json NOT NULL
+                            tenant_id UUID REFERENCES tenants(id) ON DELETE CASCADE NOT NULL,
+                            name text NOT NULL,
+                            description text NOT NULL,
+                            template json NOT NULL
                         );
 
                         CREATE TABLE datasets (
                             id UUID PRIMARY KEY,
+                            tenant_id UUID REFERENCES tenants(id) NOT NULL,
                             name text NOT NULL,
                             description text NOT NULL, 
                             tags text[], 
@@ -348,7 +426,18 @@ where
                             meta_data json NOT NULL,
 
                             symbology json,
-                            provenance json
+                            provenance json,
+                            auxiliary_metadata json,
+
+                            -- temporary datasets are only visible to this session and are deleted after it expired
+                            temporary_session_id UUID REFERENCES sessions(id) ON DELETE CASCADE
+                        );
+
+                        -- the accesses of datasets, i.e., the loading of their meta data by workflows
+                        CREATE TABLE dataset_usage (
+                            dataset_id UUID PRIMARY KEY REFERENCES datasets(id) ON DELETE CASCADE,
+                            access_count bigint NOT NULL,
+                            last_access timestamp with time zone NOT NULL
                         );
 
                         -- TODO: add constraint not null
@@ -365,6 +454,8 @@ where
                         CREATE TABLE uploads (
                             id UUID PRIMARY KEY,
                             user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+                            -- the tenant of the user at the time of the upload, for accounting the storage of tenants
+                            tenant_id UUID REFERENCES tenants(id) NOT NULL,
                             files "FileUpload"[] NOT NULL
                         );
 
@@ -380,13 +471,18 @@ where
                             PRIMARY KEY (role_id, dataset_id)
                         );
 
+                        -- datasets of other tenants are never permitted, except for the datasets of the system tenant
                         CREATE VIEW user_permitted_datasets AS
                             SELECT 
                                 r.user_id,
                                 p.dataset_id,
                                 p.permission
                             FROM 
-                                user_roles r JOIN dataset_permissions p ON (r.role_id = p.role_id);
+                                user_roles r JOIN dataset_permissions p ON (r.role_id = p.role_id)
+                                    JOIN users u ON (r.user_id = u.id)
+                                    JOIN datasets d ON (p.dataset_id = d.id)
+                            WHERE
+                                d.tenant_id = u.tenant_id OR d.tenant_id = '{system_tenant_id}';
 
 
                         CREATE TABLE layer_collections (
@@ -445,6 +541,7 @@ where
                         -- TODO: should name be unique (per user)?
                         CREATE TABLE layer_providers (
                             id UUID PRIMARY KEY,
+                            tenant_id UUID REFERENCES tenants(id) NOT NULL,
                             type_name text NOT NULL,
                             name text NOT NULL,
 
@@ -467,6 +564,8 @@ where
                     system_role_id = Role::system_role_id(),
                     user_role_id = Role::user_role_id(),
                     anonymous_role_id = Role::anonymous_role_id(),
+                    system_tenant_id = Tenant::system_tenant_id(),
+                    default_tenant_id = Tenant::default_tenant_id(),
                     root_layer_collection_id = INTERNAL_LAYER_DB_ROOT_COLLECTION_ID,
                     unsorted_layer_collection_id = UNSORTED_COLLECTION_ID))
                     .await?;
@@ -605,6 +704,8 @@ where
     }
 
     fn execution_context(&self, session: UserSession) -> Result<Self::ExecutionContext> {
+        let operator_policies = operator_policies(&session.roles);
+
         Ok(ExecutionContextImpl::<
             UserSession,
             PostgresDatasetDb<Tls>,
@@ -613,8 +714,12 @@ where
             self.dataset_db.clone(),
             self.layer_provider_db.clone(),
             self.thread_pool.clone(),
+            self.io_thread_pool.clone(),
             session,
             self.exe_ctx_tiling_spec,
+            self.statistics_cache.clone(),
+            self.workflow_registry.clone(),
+            operator_policies,
         ))
     }
 
@@ -957,45 +1062,51 @@ mod tests {
 
         let layer_workflow_id = ctx
             .workflow_registry_ref()
-            .register(Workflow {
-                operator: TypedOperator::Vector(
-                    MockPointSource {
-                        params: MockPointSourceParams {
-                            points: vec![Coordinate2D::new(1., 2.); 3],
-                        },
-                    }
-                    .boxed(),
-                ),
-            })
+            .register(
+                session,
+                Workflow {
+                    operator: TypedOperator::Vector(
+                        MockPointSource {
+                            params: MockPointSourceParams {
+                                points: vec![Coordinate2D::new(1., 2.); 3],
+                            },
+                        }
+                        .boxed(),
+                    ),
+                },
+            )
             .await
             .unwrap();
 
         assert!(ctx
             .workflow_registry_ref()
-            .load(&layer_workflow_id)
+            .load(session, &layer_workflow_id)
             .await
             .is_ok());
 
         let plot_workflow_id = ctx
             .workflow_registry_ref()
-            .register(Workflow {
-                operator: Statistics {
-                    params: StatisticsParams {
-                        column_names: vec![],
-                    },
-                    sources: MultipleRasterOrSingleVectorSource {
-                        source: Raster(vec![]),
-                    },
-                }
-                .boxed()
-                .into(),
-            })
+            .register(
+                session,
+                Workflow {
+                    operator: Statistics {
+                        params: StatisticsParams {
+                            column_names: vec![],
+                        },
+                        sources: MultipleRasterOrSingleVectorSource {
+                            source: Raster(vec![]),
+                        },
+                    }
+                    .boxed()
+                    .into(),
+                },
+            )
             .await
             .unwrap();
 
         assert!(ctx
             .workflow_registry_ref()
-            .load(&plot_workflow_id)
+            .load(session, &plot_workflow_id)
             .await
             .is_ok());
 
@@ -1237,6 +1348,8 @@ mod tests {
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn it_persists_workflows() {
         with_temp_context(|ctx, pg_config| async move {
+            let session = ctx.user_db_ref().anonymous().await.unwrap();
+
             let workflow = Workflow {
                 operator: TypedOperator::Vector(
                     MockPointSource {
@@ -1250,7 +1363,7 @@ mod tests {
 
             let id = ctx
                 .workflow_registry_ref()
-                .register(workflow)
+                .register(&session, workflow)
                 .await
                 .unwrap();
 
@@ -1260,7 +1373,7 @@ mod tests {
                 .await
                 .unwrap();
 
-            let workflow = ctx.workflow_registry_ref().load(&id).await.unwrap();
+            let workflow = ctx.workflow_registry_ref().load(&session, &id).await.unwrap();
 
             let json = serde_json::to_string(&workflow).unwrap();
             assert_eq!(json, r#"{"type":"Vector","operator":{"type":"MockPointSource","params":{"points":[{"x":1.0,"y":2.0},{"x":1.0,"y":2.0},{"x":1.0,"y":2.0}]}}}"#);
@@ -1302,6 +1415,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             };
 
             let meta_data = MetaDataDefinition::OgrMetaData(StaticMetaData::<
@@ -1345,6 +1459,7 @@ mod tests {
                         license: "license".to_owned(),
                         uri: "uri".to_owned(),
                     }),
+                    temporary: false,
                 }
                 .validated()
                 .unwrap(),
@@ -1463,6 +1578,7 @@ mod tests {
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn it_persists_layer_providers() {
         with_temp_context(|ctx, _| async move {
+            let session = ctx.user_db_ref().anonymous().await.unwrap();
             let db = ctx.layer_provider_db_ref();
 
             let provider_id =
@@ -1496,6 +1612,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             };
 
             let meta_data = MetaDataDefinition::OgrMetaData(StaticMetaData::<
@@ -1532,15 +1649,19 @@ mod tests {
                         source_operator: "MockPointSource".to_owned(),
                         symbology: None,
                         provenance: None,
+                        temporary: false,
                     },
                     meta_data,
                 }],
             };
 
-            db.add_layer_provider(Box::new(provider)).await.unwrap();
+            db.add_layer_provider(&session, Box::new(provider))
+                .await
+                .unwrap();
 
             let providers = db
                 .list_layer_providers(
+                    &session,
                     LayerProviderListingOptions {
                         offset: 0,
                         limit: 10,
@@ -1562,7 +1683,7 @@ mod tests {
                 }
             );
 
-            let provider = db.layer_provider(provider_id).await.unwrap();
+            let provider = db.layer_provider(&session, provider_id).await.unwrap();
 
             let datasets = provider
                 .collection(
@@ -1603,6 +1724,7 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             };
 
             let meta = StaticMetaData {
@@ -1618,6 +1740,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1693,6 +1816,7 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             };
 
             let meta = StaticMetaData {
@@ -1708,6 +1832,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1759,6 +1884,7 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             };
 
             let meta = StaticMetaData {
@@ -1774,6 +1900,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1831,6 +1958,7 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             };
 
             let meta = StaticMetaData {
@@ -1846,6 +1974,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1903,6 +2032,7 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             };
 
             let meta = StaticMetaData {
@@ -1918,6 +2048,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    encoding: None,
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -6,10 +6,12 @@ use crate::datasets::listing::{
     DatasetListOptions, DatasetListing, DatasetProvider, OrderBy, ProvenanceOutput,
 };
 use crate::datasets::storage::{
-    AddDataset, Dataset, DatasetDb, DatasetStore, DatasetStorer, MetaDataDefinition,
-    DATASET_DB_LAYER_PROVIDER_ID, DATASET_DB_ROOT_COLLECTION_ID,
+    append_time_slices, AddDataset, AppendTimeSlices, Dataset, DatasetDb,
+    DatasetLayerCollectionProvider, DatasetStore, DatasetStorer, MetaDataDefinition,
+    TemporaryDatasetSession, DATASET_DB_LAYER_PROVIDER_ID, DATASET_DB_ROOT_COLLECTION_ID,
 };
-use crate::datasets::upload::{Upload, UploadDb, UploadId};
+use crate::datasets::upload::{StorageUsage, Upload, UploadDb, UploadId};
+use crate::datasets::usage::{DatasetAccess, DatasetUsageDb, DatasetUsageTracker};
 use crate::error;
 use crate::error::Result;
 use crate::layers::layer::{
@@ -19,15 +21,18 @@ use crate::layers::layer::{
 use crate::layers::listing::{LayerCollectionId, LayerCollectionProvider};
 use crate::layers::storage::INTERNAL_PROVIDER_ID;
 use crate::pro::datasets::Permission;
-use crate::pro::users::{UserId, UserSession};
+use crate::pro::users::{Tenant, TenantId, UserId, UserSession};
+use crate::pro::util::config::Quota;
+use crate::util::config::get_config_element;
 use crate::util::operators::source_operator_from_dataset;
 use crate::util::user_input::Validated;
 use crate::workflows::workflow::Workflow;
 use async_trait::async_trait;
-use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
+use geoengine_datatypes::primitives::{DateTime, RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_datatypes::util::Identifier;
 use geoengine_operators::engine::{
-    MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor, VectorResultDescriptor,
+    AuxiliaryMetadata, MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor,
+    VectorResultDescriptor,
 };
 
 use geoengine_operators::source::{
@@ -45,6 +50,8 @@ use super::DatasetPermission;
 #[derive(Default)]
 pub struct ProHashMapDatasetDbBackend {
     datasets: HashMap<DatasetId, Dataset>,
+    /// the tenant that added the dataset
+    dataset_tenants: HashMap<DatasetId, TenantId>,
     dataset_permissions: Vec<DatasetPermission>,
     ogr_datasets: HashMap<
         DatasetId,
@@ -62,16 +69,102 @@ pub struct ProHashMapDatasetDbBackend {
         DatasetId,
         Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
     >,
+    /// the lists of Gdal files, which are kept to allow appending time slices
+    gdal_list_datasets: HashMap<DatasetId, GdalMetaDataList>,
+    auxiliary_metadata: HashMap<DatasetId, AuxiliaryMetadata>,
+    /// the sessions that added temporary datasets
+    temporary_datasets: HashMap<DatasetId, TemporaryDatasetSession>,
     uploads: HashMap<UserId, HashMap<UploadId, Upload>>,
+    /// the tenant of the user at the time of the upload
+    upload_tenants: HashMap<UploadId, TenantId>,
+}
+
+impl ProHashMapDatasetDbBackend {
+    fn is_visible(&self, session: &UserSession, dataset: DatasetId) -> bool {
+        let temporary_visible = self
+            .temporary_datasets
+            .get(&dataset)
+            .map_or(true, |temporary| {
+                temporary.is_visible_to(session) && !temporary.is_expired(&DateTime::now())
+            });
+
+        temporary_visible
+            && self
+                .dataset_tenants
+                .get(&dataset)
+                .map_or(false, |tenant| session.is_tenant_visible(*tenant))
+    }
+
+    /// deletes the temporary datasets whose sessions expired
+    fn remove_expired_datasets(&mut self) {
+        let now = DateTime::now();
+        let expired: Vec<DatasetId> = self
+            .temporary_datasets
+            .iter()
+            .filter(|(_, temporary)| temporary.is_expired(&now))
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in expired {
+            info!("Remove expired temporary dataset {:?}", id);
+
+            self.datasets.remove(&id);
+            self.dataset_tenants.remove(&id);
+            self.dataset_permissions.retain(|p| p.dataset != id);
+            self.ogr_datasets.remove(&id);
+            self.mock_datasets.remove(&id);
+            self.gdal_datasets.remove(&id);
+            self.gdal_list_datasets.remove(&id);
+            self.auxiliary_metadata.remove(&id);
+            self.temporary_datasets.remove(&id);
+        }
+    }
+
+    fn storage_usage(&self, session: &UserSession) -> Result<StorageUsage> {
+        let quota = get_config_element::<Quota>()?;
+
+        let user_bytes = self
+            .uploads
+            .get(&session.user.id)
+            .map_or(0, |uploads| uploads.values().map(Upload::byte_size).sum());
+
+        let tenant_bytes = self
+            .uploads
+            .values()
+            .flat_map(HashMap::values)
+            .filter(|upload| self.upload_tenants.get(&upload.id) == Some(&session.tenant))
+            .map(Upload::byte_size)
+            .sum();
+
+        Ok(StorageUsage {
+            user_bytes,
+            user_quota_bytes: quota.user_storage_limit(),
+            tenant_bytes: Some(tenant_bytes),
+            tenant_quota_bytes: quota.tenant_storage_limit(),
+        })
+    }
 }
 
 #[derive(Default)]
 pub struct ProHashMapDatasetDb {
     pub backend: Db<ProHashMapDatasetDbBackend>,
+    usage: DatasetUsageTracker,
 }
 
 impl DatasetDb<UserSession> for ProHashMapDatasetDb {}
 
+#[async_trait]
+impl DatasetUsageDb for ProHashMapDatasetDb {
+    async fn record_access(&self, dataset: DatasetId) -> Result<()> {
+        self.usage.record_access(dataset);
+        Ok(())
+    }
+
+    async fn dataset_accesses(&self) -> Result<HashMap<DatasetId, DatasetAccess>> {
+        Ok(self.usage.accesses())
+    }
+}
+
 #[async_trait]
 pub trait ProHashMapStorable: Send + Sync {
     async fn store(&self, id: DatasetId, db: &ProHashMapDatasetDb) -> TypedResultDescriptor;
@@ -166,11 +259,9 @@ impl ProHashMapStorable for GdalMetadataNetCdfCf {
 #[async_trait]
 impl ProHashMapStorable for GdalMetaDataList {
     async fn store(&self, id: DatasetId, db: &ProHashMapDatasetDb) -> TypedResultDescriptor {
-        db.backend
-            .write()
-            .await
-            .gdal_datasets
-            .insert(id, Box::new(self.clone()));
+        let mut backend = db.backend.write().await;
+        backend.gdal_datasets.insert(id, Box::new(self.clone()));
+        backend.gdal_list_datasets.insert(id, self.clone());
         self.result_descriptor.clone().into()
     }
 }
@@ -185,6 +276,8 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
     ) -> Result<DatasetId> {
         info!("Add dataset {:?}", dataset.user_input.name);
 
+        self.backend.write().await.remove_expired_datasets();
+
         let dataset = dataset.user_input;
         let id = dataset.id.unwrap_or_else(DatasetId::new);
         let result_descriptor = meta_data.store(id, self).await;
@@ -198,21 +291,114 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
             symbology: dataset.symbology,
             provenance: dataset.provenance,
         };
-        self.backend.write().await.datasets.insert(id, d);
+        let mut backend = self.backend.write().await;
 
-        self.backend
-            .write()
-            .await
-            .dataset_permissions
-            .push(DatasetPermission {
-                role: session.user.id.into(),
-                dataset: id,
-                permission: Permission::Owner,
-            });
+        backend.datasets.insert(id, d);
+        backend.dataset_tenants.insert(id, session.tenant);
+        if dataset.temporary {
+            backend
+                .temporary_datasets
+                .insert(id, TemporaryDatasetSession::new(session));
+        }
+
+        backend.dataset_permissions.push(DatasetPermission {
+            role: session.user.id.into(),
+            dataset: id,
+            permission: Permission::Owner,
+        });
 
         Ok(id)
     }
 
+    async fn add_datasets(
+        &self,
+        session: &UserSession,
+        datasets: Vec<(Validated<AddDataset>, Box<dyn ProHashMapStorable>)>,
+    ) -> Result<Vec<DatasetId>> {
+        // adding datasets in memory cannot fail, so either all datasets are added or none
+        let mut ids = Vec::with_capacity(datasets.len());
+        for (dataset, meta_data) in datasets {
+            ids.push(self.add_dataset(session, dataset, meta_data).await?);
+        }
+
+        Ok(ids)
+    }
+
+    async fn append_time_slices(
+        &self,
+        session: &UserSession,
+        dataset: DatasetId,
+        slices: Validated<AppendTimeSlices>,
+    ) -> Result<()> {
+        info!("Append time slices to dataset {:?}", dataset);
+
+        let mut backend = self.backend.write().await;
+
+        ensure!(
+            backend.is_visible(session, dataset),
+            error::UnknownDatasetId
+        );
+
+        ensure!(
+            backend
+                .dataset_permissions
+                .iter()
+                .any(|p| p.dataset == dataset
+                    && session.roles.contains(&p.role)
+                    && p.permission == Permission::Owner),
+            error::DatasetPermissionDenied { dataset }
+        );
+
+        // modify a copy to leave the dataset untouched on error
+        let mut meta_data = backend
+            .gdal_list_datasets
+            .get(&dataset)
+            .ok_or(error::Error::DatasetNotAppendable)?
+            .clone();
+
+        append_time_slices(&mut meta_data, slices.user_input.slices)?;
+
+        if let Some(d) = backend.datasets.get_mut(&dataset) {
+            d.result_descriptor = meta_data.result_descriptor.clone().into();
+        }
+        backend
+            .gdal_datasets
+            .insert(dataset, Box::new(meta_data.clone()));
+        backend.gdal_list_datasets.insert(dataset, meta_data);
+
+        Ok(())
+    }
+
+    async fn set_auxiliary_metadata(
+        &self,
+        session: &UserSession,
+        dataset: DatasetId,
+        metadata: AuxiliaryMetadata,
+    ) -> Result<()> {
+        info!("Set auxiliary metadata of dataset {:?}", dataset);
+
+        let mut backend = self.backend.write().await;
+
+        ensure!(
+            backend.is_visible(session, dataset),
+            error::UnknownDatasetId
+        );
+
+        ensure!(
+            backend
+                .dataset_permissions
+                .iter()
+                .any(|p| p.dataset == dataset
+                    && session.roles.contains(&p.role)
+                    && p.permission == Permission::Owner),
+            error::DatasetPermissionDenied { dataset }
+        );
+
+        backend.auxiliary_metadata.insert(dataset, metadata);
+
+        Ok(())
+    }
+
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
         Box::new(meta)
     }
@@ -232,7 +418,7 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
         let iter = backend
             .dataset_permissions
             .iter()
-            .filter(|p| session.roles.contains(&p.role))
+            .filter(|p| session.roles.contains(&p.role) && backend.is_visible(session, p.dataset))
             .filter_map(|p| {
                 let matching_dataset = backend.datasets.get(&p.dataset);
 
@@ -267,6 +453,10 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
 
     async fn load(&self, session: &UserSession, dataset: &DatasetId) -> Result<Dataset> {
         let backend = self.backend.read().await;
+        ensure!(
+            backend.is_visible(session, *dataset),
+            error::UnknownDatasetId
+        );
         ensure!(
             backend
                 .dataset_permissions
@@ -289,6 +479,10 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
     ) -> Result<ProvenanceOutput> {
         let backend = self.backend.read().await;
 
+        ensure!(
+            backend.is_visible(session, *dataset),
+            error::UnknownDatasetId
+        );
         ensure!(
             backend
                 .dataset_permissions
@@ -306,6 +500,28 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
             })
             .ok_or(error::Error::UnknownDatasetId)
     }
+
+    async fn auxiliary_metadata(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<Option<AuxiliaryMetadata>> {
+        let backend = self.backend.read().await;
+
+        ensure!(
+            backend.is_visible(session, *dataset),
+            error::UnknownDatasetId
+        );
+        ensure!(
+            backend
+                .dataset_permissions
+                .iter()
+                .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
+            error::DatasetPermissionDenied { dataset: *dataset }
+        );
+
+        Ok(backend.auxiliary_metadata.get(dataset).cloned())
+    }
 }
 
 #[async_trait]
@@ -319,6 +535,11 @@ impl UpdateDatasetPermissions for ProHashMapDatasetDb {
 
         let mut backend = self.backend.write().await;
 
+        ensure!(
+            backend.is_visible(session, permission.dataset),
+            error::UnknownDatasetId
+        );
+
         ensure!(
             backend
                 .dataset_permissions
@@ -370,6 +591,7 @@ impl
     > {
         let backend = self.backend.read().await;
         let id = id.internal().ok_or(error::Error::DataIdTypeMissMatch)?;
+        ensure!(backend.is_visible(session, id), error::UnknownDatasetId);
         ensure!(
             backend
                 .dataset_permissions
@@ -406,6 +628,7 @@ impl
         let backend = self.backend.read().await;
 
         let id = id.internal().ok_or(error::Error::DataIdTypeMissMatch)?;
+        ensure!(backend.is_visible(session, id), error::UnknownDatasetId);
         ensure!(
             backend
                 .dataset_permissions
@@ -442,6 +665,7 @@ impl
         let backend = self.backend.read().await;
 
         let id = id.internal().ok_or(error::Error::DataIdTypeMissMatch)?;
+        ensure!(backend.is_visible(session, id), error::UnknownDatasetId);
         ensure!(
             backend
                 .dataset_permissions
@@ -471,23 +695,33 @@ impl UploadDb<UserSession> for ProHashMapDatasetDb {
     }
 
     async fn create_upload(&self, session: &UserSession, upload: Upload) -> Result<()> {
-        self.backend
-            .write()
-            .await
+        let mut backend = self.backend.write().await;
+
+        backend
+            .storage_usage(session)?
+            .ensure_available(upload.byte_size())?;
+
+        backend.upload_tenants.insert(upload.id, session.tenant);
+        backend
             .uploads
             .entry(session.user.id)
             .or_insert_with(HashMap::new)
             .insert(upload.id, upload);
         Ok(())
     }
+
+    async fn storage_usage(&self, session: &UserSession) -> Result<StorageUsage> {
+        self.backend.read().await.storage_usage(session)
+    }
 }
 
-#[async_trait]
-impl LayerCollectionProvider for ProHashMapDatasetDb {
-    async fn collection(
+impl ProHashMapDatasetDb {
+    /// Lists the datasets of the tenants that are `visible` as layers
+    async fn dataset_layer_collection(
         &self,
         collection: &LayerCollectionId,
         options: Validated<LayerCollectionListOptions>,
+        visible: impl Fn(TenantId) -> bool + Send,
     ) -> Result<LayerCollection> {
         ensure!(
             *collection == self.root_collection_id().await?,
@@ -503,6 +737,13 @@ impl LayerCollectionProvider for ProHashMapDatasetDb {
         let items = backend
             .datasets
             .iter()
+            .filter(|(id, _)| {
+                backend
+                    .dataset_tenants
+                    .get(id)
+                    .map_or(false, |tenant| visible(*tenant))
+                    && !backend.temporary_datasets.contains_key(id)
+            })
             .skip(options.offset as usize)
             .take(options.limit as usize)
             .map(|(_id, d)| {
@@ -531,11 +772,12 @@ impl LayerCollectionProvider for ProHashMapDatasetDb {
         })
     }
 
-    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
-        Ok(LayerCollectionId(DATASET_DB_ROOT_COLLECTION_ID.to_string()))
-    }
-
-    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+    /// Returns the layer of a dataset of a tenant that is `visible`
+    async fn dataset_layer(
+        &self,
+        id: &LayerId,
+        visible: impl Fn(TenantId) -> bool + Send,
+    ) -> Result<Layer> {
         let dataset_id = DatasetId::from_str(&id.0)?;
 
         let backend = self.backend.read().await;
@@ -543,7 +785,14 @@ impl LayerCollectionProvider for ProHashMapDatasetDb {
         let (_id, dataset) = backend
             .datasets
             .iter()
-            .find(|(_id, d)| d.id == dataset_id)
+            .find(|(id, d)| {
+                d.id == dataset_id
+                    && backend
+                        .dataset_tenants
+                        .get(id)
+                        .map_or(false, |tenant| visible(*tenant))
+                    && !backend.temporary_datasets.contains_key(id)
+            })
             .ok_or(error::Error::UnknownDatasetId)?;
 
         let operator = source_operator_from_dataset(&dataset.source_operator, &dataset.id.into())?;
@@ -563,10 +812,54 @@ impl LayerCollectionProvider for ProHashMapDatasetDb {
     }
 }
 
+/// Without a session, only the datasets that are visible to all tenants are listed
+#[async_trait]
+impl LayerCollectionProvider for ProHashMapDatasetDb {
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.dataset_layer_collection(collection, options, |tenant| {
+            tenant == Tenant::system_tenant_id()
+        })
+        .await
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId(DATASET_DB_ROOT_COLLECTION_ID.to_string()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        self.dataset_layer(id, |tenant| tenant == Tenant::system_tenant_id())
+            .await
+    }
+}
+
+#[async_trait]
+impl DatasetLayerCollectionProvider<UserSession> for ProHashMapDatasetDb {
+    async fn session_collection(
+        &self,
+        session: &UserSession,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.dataset_layer_collection(collection, options, |tenant| {
+            session.is_tenant_visible(tenant)
+        })
+        .await
+    }
+
+    async fn session_layer(&self, session: &UserSession, id: &LayerId) -> Result<Layer> {
+        self.dataset_layer(id, |tenant| session.is_tenant_visible(tenant))
+            .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::contexts::{Context, MockableSession};
+    use crate::contexts::{Context, MockableSession, SessionId};
     use crate::datasets::listing::OrderBy;
     use crate::datasets::upload::{FileId, FileUpload};
     use crate::pro::contexts::ProInMemoryContext;
@@ -599,6 +892,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -614,6 +908,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -670,6 +965,42 @@ mod tests {
             }
         );
 
+        let db = ctx.dataset_db_ref();
+        let root = db.root_collection_id().await?;
+        let options = LayerCollectionListOptions {
+            offset: 0,
+            limit: 10,
+        };
+
+        let layers = db
+            .session_collection(&session, &root, options.clone().validated()?)
+            .await?;
+        assert_eq!(layers.items.len(), 1);
+        assert!(db
+            .session_layer(&session, &LayerId(id.to_string()))
+            .await
+            .is_ok());
+
+        let other_tenant = UserSession {
+            tenant: TenantId::new(),
+            ..UserSession::mock()
+        };
+        let layers = db
+            .session_collection(&other_tenant, &root, options.clone().validated()?)
+            .await?;
+        assert!(layers.items.is_empty());
+        assert!(db
+            .session_layer(&other_tenant, &LayerId(id.to_string()))
+            .await
+            .is_err());
+
+        // without a session, only the datasets of the system tenant are listed
+        assert!(db
+            .collection(&root, options.validated()?)
+            .await?
+            .items
+            .is_empty());
+
         Ok(())
     }
 
@@ -695,6 +1026,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -710,6 +1042,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -755,6 +1088,93 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn it_removes_temporary_datasets_after_session_expired() -> Result<()> {
+        let ctx = ProInMemoryContext::test_default();
+
+        let mut session1 = UserSession::mock();
+        session1.valid_until =
+            DateTime::now() + geoengine_datatypes::primitives::Duration::hours(1);
+        let mut session2 = session1.clone();
+        session2.id = SessionId::new();
+
+        let descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: SpatialReferenceOption::Unreferenced,
+            columns: Default::default(),
+            time: None,
+            bbox: None,
+        };
+
+        let meta = StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: Default::default(),
+                layer_name: String::new(),
+                data_type: None,
+                time: Default::default(),
+                default_geometry: None,
+                columns: None,
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                attribute_query: None,
+                encoding: None,
+            },
+            result_descriptor: descriptor.clone(),
+            phantom: Default::default(),
+        };
+
+        let ds = AddDataset {
+            id: None,
+            name: "Sketch".to_string(),
+            description: "My temporary sketch".to_string(),
+            source_operator: "OgrSource".to_string(),
+            symbology: None,
+            provenance: None,
+            temporary: true,
+        };
+
+        let id = ctx
+            .dataset_db_ref()
+            .add_dataset(&session1, ds.validated()?, Box::new(meta.clone()))
+            .await?;
+
+        // only the creating session sees the dataset, not other sessions of the same user
+        assert!(ctx.dataset_db_ref().load(&session1, &id).await.is_ok());
+        assert!(ctx.dataset_db_ref().load(&session2, &id).await.is_err());
+
+        session1.valid_until =
+            DateTime::now() - geoengine_datatypes::primitives::Duration::seconds(1);
+        ctx.dataset_db_ref()
+            .backend
+            .write()
+            .await
+            .temporary_datasets
+            .insert(id, TemporaryDatasetSession::new(&session1));
+
+        // adding another dataset removes the expired one
+        let ds = AddDataset {
+            id: None,
+            name: "OgrDataset".to_string(),
+            description: "My Ogr dataset".to_string(),
+            source_operator: "OgrSource".to_string(),
+            symbology: None,
+            provenance: None,
+            temporary: false,
+        };
+        ctx.dataset_db_ref()
+            .add_dataset(&session2, ds.validated()?, Box::new(meta))
+            .await?;
+
+        let backend = ctx.dataset_db_ref().backend.read().await;
+        assert!(!backend.datasets.contains_key(&id));
+        assert!(!backend.ogr_datasets.contains_key(&id));
+        assert!(backend.temporary_datasets.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn it_shows_only_permitted_provenance() -> Result<()> {
         let ctx = ProInMemoryContext::test_default();
@@ -777,6 +1197,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -792,6 +1213,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -839,6 +1261,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -854,6 +1277,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -906,6 +1330,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -921,6 +1346,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -973,6 +1399,7 @@ mod tests {
             source_operator: "OgrSource".to_string(),
             symbology: None,
             provenance: None,
+            temporary: false,
         };
 
         let meta = StaticMetaData {
@@ -988,6 +1415,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                encoding: None,
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -1075,4 +1503,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn it_accounts_storage_usage() -> Result<()> {
+        let ctx = ProInMemoryContext::test_default();
+
+        let session1 = UserSession::mock();
+        let session2 = UserSession::mock();
+        let other_tenant_session = UserSession {
+            tenant: TenantId::new(),
+            ..UserSession::mock()
+        };
+
+        for (session, byte_size) in [(&session1, 1024), (&session2, 512)] {
+            ctx.dataset_db_ref()
+                .create_upload(
+                    session,
+                    Upload {
+                        id: UploadId::new(),
+                        files: vec![FileUpload {
+                            id: FileId::new(),
+                            name: "test.bin".to_owned(),
+                            byte_size,
+                        }],
+                    },
+                )
+                .await?;
+        }
+
+        let usage = ctx.dataset_db_ref().storage_usage(&session1).await?;
+        assert_eq!(usage.user_bytes, 1024);
+        assert_eq!(usage.tenant_bytes, Some(1536));
+
+        let usage = ctx
+            .dataset_db_ref()
+            .storage_usage(&other_tenant_session)
+            .await?;
+        assert_eq!(usage.user_bytes, 0);
+        assert_eq!(usage.tenant_bytes, Some(0));
+
+        Ok(())
+    }
 }
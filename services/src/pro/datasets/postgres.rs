@@ -7,10 +7,12 @@ use crate::datasets::listing::SessionMetaDataProvider;
 use crate::datasets::storage::DATASET_DB_LAYER_PROVIDER_ID;
 use crate::datasets::storage::DATASET_DB_ROOT_COLLECTION_ID;
 use crate::datasets::storage::{
-    AddDataset, Dataset, DatasetDb, DatasetStore, DatasetStorer, MetaDataDefinition,
+    append_time_slices, AddDataset, AppendTimeSlices, Dataset, DatasetDb,
+    DatasetLayerCollectionProvider, DatasetStore, DatasetStorer, MetaDataDefinition,
 };
 use crate::datasets::upload::FileId;
-use crate::datasets::upload::{Upload, UploadDb, UploadId};
+use crate::datasets::upload::{StorageUsage, Upload, UploadDb, UploadId};
+use crate::datasets::usage::{DatasetAccess, DatasetUsageDb};
 use crate::error::{self, Error, Result};
 use crate::layers::layer::CollectionItem;
 use crate::layers::layer::Layer;
@@ -24,24 +26,26 @@ use crate::layers::listing::LayerCollectionProvider;
 use crate::layers::storage::INTERNAL_PROVIDER_ID;
 use crate::pro::datasets::storage::UpdateDatasetPermissions;
 use crate::pro::datasets::RoleId;
+use crate::pro::util::config::Quota;
 use crate::projects::Symbology;
 use crate::util::operators::source_operator_from_dataset;
 use crate::util::user_input::Validated;
 use crate::workflows::workflow::Workflow;
 use crate::{
     datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider},
-    pro::users::UserSession,
+    pro::users::{Tenant, TenantId, UserSession},
 };
 use async_trait::async_trait;
 use bb8_postgres::bb8::Pool;
 use bb8_postgres::tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use bb8_postgres::tokio_postgres::Socket;
+use bb8_postgres::tokio_postgres::{Socket, Transaction};
 use bb8_postgres::PostgresConnectionManager;
 use geoengine_datatypes::primitives::RasterQueryRectangle;
 use geoengine_datatypes::primitives::VectorQueryRectangle;
 use geoengine_datatypes::util::Identifier;
 use geoengine_operators::engine::{
-    MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor, VectorResultDescriptor,
+    AuxiliaryMetadata, MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor,
+    VectorResultDescriptor,
 };
 
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
@@ -74,6 +78,125 @@ where
     pub fn new(conn_pool: Pool<PostgresConnectionManager<Tls>>) -> Self {
         Self { conn_pool }
     }
+
+    /// Sums up the files of the uploads of the session's user and of the session's tenant
+    async fn query_storage_usage(
+        tx: &Transaction<'_>,
+        session: &UserSession,
+    ) -> Result<StorageUsage> {
+        let quota = get_config_element::<Quota>()?;
+
+        let stmt = tx
+            .prepare(
+                "
+                SELECT
+                    COALESCE(SUM(f.byte_size) FILTER (WHERE u.user_id = $1), 0)::bigint,
+                    COALESCE(SUM(f.byte_size) FILTER (WHERE u.tenant_id = $2), 0)::bigint
+                FROM uploads u, unnest(u.files) f
+                WHERE u.user_id = $1 OR u.tenant_id = $2",
+            )
+            .await?;
+
+        let row = tx
+            .query_one(&stmt, &[&session.user.id, &session.tenant])
+            .await?;
+
+        Ok(StorageUsage {
+            user_bytes: row.get::<_, i64>(0) as u64,
+            user_quota_bytes: quota.user_storage_limit(),
+            tenant_bytes: Some(row.get::<_, i64>(1) as u64),
+            tenant_quota_bytes: quota.tenant_storage_limit(),
+        })
+    }
+
+    /// Inserts the dataset and makes the session's user its owner
+    async fn insert_dataset(
+        tx: &Transaction<'_>,
+        session: &UserSession,
+        dataset: AddDataset,
+        meta_data: &dyn PostgresStorable<Tls>,
+    ) -> Result<DatasetId> {
+        let id = dataset.id.unwrap_or_else(DatasetId::new);
+
+        let meta_data_json = meta_data.to_json()?;
+
+        Self::delete_expired_datasets(tx).await?;
+
+        let stmt = tx
+            .prepare(
+                "
+                INSERT INTO datasets (
+                    id,
+                    tenant_id,
+                    name,
+                    description,
+                    source_operator,
+                    result_descriptor,
+                    meta_data,
+                    symbology,
+                    provenance,
+                    temporary_session_id
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .await?;
+
+        tx.execute(
+            &stmt,
+            &[
+                &id,
+                &session.tenant,
+                &dataset.name,
+                &dataset.description,
+                &dataset.source_operator,
+                &meta_data_json.result_descriptor,
+                &meta_data_json.meta_data,
+                &serde_json::to_value(&dataset.symbology)?,
+                &serde_json::to_value(&dataset.provenance)?,
+                &dataset.temporary.then_some(session.id),
+            ],
+        )
+        .await?;
+
+        let stmt = tx
+            .prepare(
+                "
+            INSERT INTO dataset_permissions (
+                role_id,
+                dataset_id,
+                permission
+            )
+            VALUES ($1, $2, $3)",
+            )
+            .await?;
+
+        tx.execute(
+            &stmt,
+            &[&RoleId::from(session.user.id), &id, &Permission::Owner],
+        )
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Deletes the temporary datasets whose sessions expired
+    async fn delete_expired_datasets(tx: &Transaction<'_>) -> Result<()> {
+        let stmt = tx
+            .prepare(
+                "
+                DELETE FROM datasets d
+                USING sessions s
+                WHERE d.temporary_session_id = s.id AND s.valid_until < CURRENT_TIMESTAMP",
+            )
+            .await?;
+
+        let deleted = tx.execute(&stmt, &[]).await?;
+        if deleted > 0 {
+            info!("Deleted {} expired temporary datasets", deleted);
+        }
+
+        Ok(())
+    }
 }
 
 impl<Tls> DatasetDb<UserSession> for PostgresDatasetDb<Tls>
@@ -85,6 +208,55 @@ where
 {
 }
 
+#[async_trait]
+impl<Tls> DatasetUsageDb for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn record_access(&self, dataset: DatasetId) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            INSERT INTO dataset_usage (dataset_id, access_count, last_access)
+            VALUES ($1, 1, CURRENT_TIMESTAMP)
+            ON CONFLICT (dataset_id) DO UPDATE SET
+                access_count = dataset_usage.access_count + 1,
+                last_access = GREATEST(dataset_usage.last_access, EXCLUDED.last_access)",
+            )
+            .await?;
+
+        conn.execute(&stmt, &[&dataset]).await?;
+
+        Ok(())
+    }
+
+    async fn dataset_accesses(&self) -> Result<HashMap<DatasetId, DatasetAccess>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT dataset_id, access_count, last_access FROM dataset_usage")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let access = DatasetAccess {
+                    count: u64::try_from(row.get::<_, i64>(1)).unwrap_or_default(),
+                    last_access: row.get(2),
+                };
+                (row.get(0), access)
+            })
+            .collect())
+    }
+}
+
 #[async_trait]
 impl<Tls> DatasetProvider<UserSession> for PostgresDatasetDb<Tls>
 where
@@ -116,11 +288,12 @@ where
                 user_permitted_datasets p JOIN datasets d 
                     ON (p.dataset_id = d.id)
             WHERE 
-                p.user_id = $1",
+                p.user_id = $1
+                AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $2)",
             )
             .await?;
 
-        let rows = conn.query(&stmt, &[&session.user.id]).await?;
+        let rows = conn.query(&stmt, &[&session.user.id, &session.id]).await?;
 
         Ok(rows
             .iter()
@@ -157,13 +330,16 @@ where
                     ON (p.dataset_id = d.id)
             WHERE 
                 p.user_id = $1 AND d.id = $2
+                AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $3)
             LIMIT 
                 1",
             )
             .await?;
 
         // TODO: throw proper dataset does not exist/no permission error
-        let row = conn.query_one(&stmt, &[&session.user.id, dataset]).await?;
+        let row = conn
+            .query_one(&stmt, &[&session.user.id, dataset, &session.id])
+            .await?;
 
         Ok(Dataset {
             id: row.get(0),
@@ -192,17 +368,52 @@ where
                 user_permitted_datasets p JOIN datasets d
                     ON(p.dataset_id = d.id)
             WHERE 
-                p.user_id = $1 AND d.id = $2",
+                p.user_id = $1 AND d.id = $2
+                AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $3)",
             )
             .await?;
 
-        let row = conn.query_one(&stmt, &[&session.user.id, dataset]).await?;
+        let row = conn
+            .query_one(&stmt, &[&session.user.id, dataset, &session.id])
+            .await?;
 
         Ok(ProvenanceOutput {
             data: (*dataset).into(),
             provenance: serde_json::from_value(row.get(0)).context(error::SerdeJson)?,
         })
     }
+
+    async fn auxiliary_metadata(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<Option<AuxiliaryMetadata>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            SELECT 
+                d.auxiliary_metadata 
+            FROM 
+                user_permitted_datasets p JOIN datasets d
+                    ON(p.dataset_id = d.id)
+            WHERE 
+                p.user_id = $1 AND d.id = $2
+                AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $3)
+            LIMIT 1",
+            )
+            .await?;
+
+        let row = conn
+            .query_one(&stmt, &[&session.user.id, dataset, &session.id])
+            .await?;
+
+        row.get::<_, Option<serde_json::Value>>(0)
+            .map(serde_json::from_value)
+            .transpose()
+            .context(error::SerdeJson)
+    }
 }
 
 #[async_trait]
@@ -268,11 +479,14 @@ where
             user_permitted_datasets p JOIN datasets d 
                 ON (p.dataset_id = d.id)
         WHERE 
-            d.id = $1 AND p.user_id = $2",
+            d.id = $1 AND p.user_id = $2
+            AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $3)",
             )
             .await?;
 
-        let row = conn.query_one(&stmt, &[&id, &session.user.id]).await?;
+        let row = conn
+            .query_one(&stmt, &[&id, &session.user.id, &session.id])
+            .await?;
 
         let meta_data: StaticMetaData<
             OgrSourceDataset,
@@ -316,11 +530,14 @@ where
             user_permitted_datasets p JOIN datasets d 
                 ON (p.dataset_id = d.id)
         WHERE 
-            d.id = $1 AND p.user_id = $2",
+            d.id = $1 AND p.user_id = $2
+            AND (d.temporary_session_id IS NULL OR d.temporary_session_id = $3)",
             )
             .await?;
 
-        let row = conn.query_one(&stmt, &[&id, &session.user.id]).await?;
+        let row = conn
+            .query_one(&stmt, &[&id, &session.user.id, &session.id])
+            .await?;
 
         let meta_data: MetaDataDefinition = serde_json::from_value(row.get(0))?;
 
@@ -421,11 +638,44 @@ where
         dataset: Validated<AddDataset>,
         meta_data: Box<dyn PostgresStorable<Tls>>,
     ) -> Result<DatasetId> {
-        let dataset = dataset.user_input;
-        let id = dataset.id.unwrap_or_else(DatasetId::new);
+        let mut conn = self.conn_pool.get().await?;
 
-        let meta_data_json = meta_data.to_json()?;
+        let tx = conn.build_transaction().start().await?;
+
+        let id = Self::insert_dataset(&tx, session, dataset.user_input, meta_data.as_ref()).await?;
+
+        tx.commit().await?;
 
+        Ok(id)
+    }
+
+    async fn add_datasets(
+        &self,
+        session: &UserSession,
+        datasets: Vec<(Validated<AddDataset>, Box<dyn PostgresStorable<Tls>>)>,
+    ) -> Result<Vec<DatasetId>> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let mut ids = Vec::with_capacity(datasets.len());
+        for (dataset, meta_data) in datasets {
+            ids.push(
+                Self::insert_dataset(&tx, session, dataset.user_input, meta_data.as_ref()).await?,
+            );
+        }
+
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    async fn append_time_slices(
+        &self,
+        session: &UserSession,
+        dataset: DatasetId,
+        slices: Validated<AppendTimeSlices>,
+    ) -> Result<()> {
         let mut conn = self.conn_pool.get().await?;
 
         let tx = conn.build_transaction().start().await?;
@@ -433,56 +683,105 @@ where
         let stmt = tx
             .prepare(
                 "
-                INSERT INTO datasets (
-                    id,
-                    name,
-                    description,
-                    source_operator,
-                    result_descriptor,
-                    meta_data,
-                    symbology,
-                    provenance
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            SELECT
+                user_id
+            FROM
+                user_permitted_datasets
+            WHERE
+                user_id = $1 AND dataset_id = $2 AND permission = $3",
+            )
+            .await?;
+
+        let auth = tx
+            .query_one(
+                &stmt,
+                &[&RoleId::from(session.user.id), &dataset, &Permission::Owner],
+            )
+            .await;
+
+        ensure!(auth.is_ok(), error::DatasetPermissionDenied { dataset });
+
+        // lock the dataset until the transaction ends to not lose concurrent appends
+        let stmt = tx
+            .prepare("SELECT meta_data FROM datasets WHERE id = $1 FOR UPDATE")
+            .await?;
+
+        let row = tx.query_one(&stmt, &[&dataset]).await?;
+
+        let mut meta_data = match serde_json::from_value(row.get(0))? {
+            MetaDataDefinition::GdalMetaDataList(meta_data) => meta_data,
+            _ => return Err(Error::DatasetNotAppendable),
+        };
+
+        append_time_slices(&mut meta_data, slices.user_input.slices)?;
+
+        let meta_data_json = <MetaDataDefinition as PostgresStorable<Tls>>::to_json(
+            &MetaDataDefinition::GdalMetaDataList(meta_data),
+        )?;
+
+        let stmt = tx
+            .prepare(
+                "
+            UPDATE datasets
+            SET
+                meta_data = $2,
+                result_descriptor = $3
+            WHERE id = $1",
             )
             .await?;
 
         tx.execute(
             &stmt,
             &[
-                &id,
-                &dataset.name,
-                &dataset.description,
-                &dataset.source_operator,
-                &meta_data_json.result_descriptor,
+                &dataset,
                 &meta_data_json.meta_data,
-                &serde_json::to_value(&dataset.symbology)?,
-                &serde_json::to_value(&dataset.provenance)?,
+                &meta_data_json.result_descriptor,
             ],
         )
         .await?;
 
-        let stmt = tx
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_auxiliary_metadata(
+        &self,
+        session: &UserSession,
+        dataset: DatasetId,
+        metadata: AuxiliaryMetadata,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
             .prepare(
                 "
-            INSERT INTO dataset_permissions (
-                role_id,
-                dataset_id,
-                permission
-            )
-            VALUES ($1, $2, $3)",
+            SELECT
+                user_id
+            FROM
+                user_permitted_datasets
+            WHERE
+                user_id = $1 AND dataset_id = $2 AND permission = $3",
             )
             .await?;
 
-        tx.execute(
-            &stmt,
-            &[&RoleId::from(session.user.id), &id, &Permission::Owner],
-        )
-        .await?;
+        let auth = conn
+            .query_one(
+                &stmt,
+                &[&RoleId::from(session.user.id), &dataset, &Permission::Owner],
+            )
+            .await;
 
-        tx.commit().await?;
+        ensure!(auth.is_ok(), error::DatasetPermissionDenied { dataset });
 
-        Ok(id)
+        let stmt = conn
+            .prepare("UPDATE datasets SET auxiliary_metadata = $2 WHERE id = $1")
+            .await?;
+
+        conn.execute(&stmt, &[&dataset, &serde_json::to_value(&metadata)?])
+            .await?;
+
+        Ok(())
     }
 
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
@@ -616,17 +915,29 @@ where
     }
 
     async fn create_upload(&self, session: &UserSession, upload: Upload) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
 
-        let stmt = conn
-            .prepare("INSERT INTO uploads (id, user_id, files) VALUES ($1, $2, $3)")
+        // serialize the uploads of a tenant to prevent exceeding the quota with concurrent uploads
+        let stmt = tx
+            .prepare("SELECT id FROM tenants WHERE id = $1 FOR UPDATE")
+            .await?;
+        tx.execute(&stmt, &[&session.tenant]).await?;
+
+        Self::query_storage_usage(&tx, session)
+            .await?
+            .ensure_available(upload.byte_size())?;
+
+        let stmt = tx
+            .prepare("INSERT INTO uploads (id, user_id, tenant_id, files) VALUES ($1, $2, $3, $4)")
             .await?;
 
-        conn.execute(
+        tx.execute(
             &stmt,
             &[
                 &upload.id,
                 &session.user.id,
+                &session.tenant,
                 &upload
                     .files
                     .iter()
@@ -635,20 +946,35 @@ where
             ],
         )
         .await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
+
+    async fn storage_usage(&self, session: &UserSession) -> Result<StorageUsage> {
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let usage = Self::query_storage_usage(&tx, session).await?;
+
+        tx.commit().await?;
+
+        Ok(usage)
+    }
 }
 
-#[async_trait]
-impl<Tls> LayerCollectionProvider for PostgresDatasetDb<Tls>
+impl<Tls> PostgresDatasetDb<Tls>
 where
     Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
     <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
     <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
-    async fn collection(
+    /// Lists the datasets of the `tenant` and the system tenant as layers
+    async fn dataset_layer_collection(
         &self,
+        tenant: TenantId,
         collection: &LayerCollectionId,
         options: Validated<LayerCollectionListOptions>,
     ) -> Result<LayerCollection> {
@@ -674,6 +1000,7 @@ where
                     d.description
                 FROM 
                     datasets d
+                WHERE (d.tenant_id = $3 OR d.tenant_id = $4) AND d.temporary_session_id IS NULL
                 ORDER BY d.name ASC
                 LIMIT $1
                 OFFSET $2;",
@@ -683,7 +1010,12 @@ where
         let rows = conn
             .query(
                 &stmt,
-                &[&i64::from(options.limit), &i64::from(options.offset)],
+                &[
+                    &i64::from(options.limit),
+                    &i64::from(options.offset),
+                    &tenant,
+                    &Tenant::system_tenant_id(),
+                ],
             )
             .await?;
 
@@ -715,11 +1047,8 @@ where
         })
     }
 
-    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
-        Ok(LayerCollectionId(DATASET_DB_ROOT_COLLECTION_ID.to_string()))
-    }
-
-    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+    /// Returns the layer of a dataset of the `tenant` or the system tenant
+    async fn dataset_layer(&self, tenant: TenantId, id: &LayerId) -> Result<Layer> {
         let dataset_id = DatasetId::from_str(&id.0)?;
 
         let conn = self.conn_pool.get().await?;
@@ -736,20 +1065,23 @@ where
                     d.symbology
                 FROM 
                     datasets d
-                WHERE id = $1;",
+                WHERE id = $1 AND (tenant_id = $2 OR tenant_id = $3) AND temporary_session_id IS NULL;",
             )
             .await?;
 
         let row = conn
-            .query_one(
+            .query_opt(
                 &stmt,
                 &[
                     &Uuid::from_str(&id.0).map_err(|_| error::Error::IdStringMustBeUuid {
                         found: id.0.clone(),
                     })?,
+                    &tenant,
+                    &Tenant::system_tenant_id(),
                 ],
             )
-            .await?;
+            .await?
+            .ok_or(error::Error::UnknownDatasetId)?;
 
         let name: String = row.get(0);
         let description: String = row.get(1);
@@ -773,6 +1105,56 @@ where
     }
 }
 
+/// Without a session, only the datasets that are visible to all tenants are listed
+#[async_trait]
+impl<Tls> LayerCollectionProvider for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn collection(
+        &self,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.dataset_layer_collection(Tenant::system_tenant_id(), collection, options)
+            .await
+    }
+
+    async fn root_collection_id(&self) -> Result<LayerCollectionId> {
+        Ok(LayerCollectionId(DATASET_DB_ROOT_COLLECTION_ID.to_string()))
+    }
+
+    async fn get_layer(&self, id: &LayerId) -> Result<Layer> {
+        self.dataset_layer(Tenant::system_tenant_id(), id).await
+    }
+}
+
+#[async_trait]
+impl<Tls> DatasetLayerCollectionProvider<UserSession> for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn session_collection(
+        &self,
+        session: &UserSession,
+        collection: &LayerCollectionId,
+        options: Validated<LayerCollectionListOptions>,
+    ) -> Result<LayerCollection> {
+        self.dataset_layer_collection(session.tenant, collection, options)
+            .await
+    }
+
+    async fn session_layer(&self, session: &UserSession, id: &LayerId) -> Result<Layer> {
+        self.dataset_layer(session.tenant, id).await
+    }
+}
+
 #[derive(Debug, Clone, ToSql, FromSql)]
 pub struct FileUpload {
     pub id: FileId,
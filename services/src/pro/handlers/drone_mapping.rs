@@ -300,6 +300,7 @@ async fn dataset_definition_from_geotiff(
                 source_operator: "GdalSource".to_owned(),
                 symbology: None,
                 provenance: None,
+                temporary: false,
             },
             meta_data: MetaDataDefinition::GdalStatic(GdalMetaDataStatic {
                 time: None,
@@ -108,6 +108,21 @@ where
     Ok(web::Json(id))
 }
 
+/// Retrieves details about the latest version of a [project](crate::projects::project::Project).
+#[utoipa::path(
+    tag = "Projects",
+    get,
+    path = "/project/{project}",
+    responses(
+        (status = 200, description = "The latest version of the project", body = Project)
+    ),
+    params(
+        ("project" = ProjectId, description = "Project id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
 pub(crate) async fn load_project_latest_handler<C: ProContext>(
     project: web::Path<ProjectId>,
     session: C::Session,
@@ -1,9 +1,12 @@
+use crate::contexts::AdminSession;
+use crate::datasets::upload::{StorageUsage, UploadDb};
 use crate::error;
 use crate::error::Result;
 use crate::pro::contexts::ProContext;
 use crate::pro::users::UserDb;
 use crate::pro::users::UserRegistration;
 use crate::pro::users::UserSession;
+use crate::pro::users::{AddTenant, TenantId, UserId, UserInfo};
 use crate::pro::users::{AuthCodeResponse, UserCredentials};
 use crate::projects::ProjectId;
 use crate::projects::STRectangle;
@@ -13,25 +16,37 @@ use crate::util::IdResponse;
 
 use crate::pro::users::OidcError::OidcDisabled;
 use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
 use snafu::ensure;
 use snafu::ResultExt;
+use utoipa::ToSchema;
 
 pub(crate) fn init_user_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: ProContext,
 {
-    cfg.service(web::resource("/user").route(web::post().to(register_user_handler::<C>)))
-        .service(web::resource("/anonymous").route(web::post().to(anonymous_handler::<C>)))
-        .service(web::resource("/login").route(web::post().to(login_handler::<C>)))
-        .service(web::resource("/logout").route(web::post().to(logout_handler::<C>)))
-        .service(web::resource("/session").route(web::get().to(session_handler::<C>)))
-        .service(
-            web::resource("/session/project/{project}")
-                .route(web::post().to(session_project_handler::<C>)),
-        )
-        .service(web::resource("/session/view").route(web::post().to(session_view_handler::<C>)))
-        .service(web::resource("/oidcInit").route(web::post().to(oidc_init::<C>)))
-        .service(web::resource("/oidcLogin").route(web::post().to(oidc_login::<C>)));
+    cfg.service(
+        web::resource("/user")
+            .route(web::post().to(register_user_handler::<C>))
+            .route(web::get().to(user_handler::<C>)),
+    )
+    .service(web::resource("/anonymous").route(web::post().to(anonymous_handler::<C>)))
+    .service(web::resource("/login").route(web::post().to(login_handler::<C>)))
+    .service(web::resource("/logout").route(web::post().to(logout_handler::<C>)))
+    .service(web::resource("/session").route(web::get().to(session_handler::<C>)))
+    .service(
+        web::resource("/session/project/{project}")
+            .route(web::post().to(session_project_handler::<C>)),
+    )
+    .service(web::resource("/session/view").route(web::post().to(session_view_handler::<C>)))
+    .service(web::resource("/oidcInit").route(web::post().to(oidc_init::<C>)))
+    .service(web::resource("/oidcLogin").route(web::post().to(oidc_login::<C>)))
+    .service(web::resource("/tenant").route(web::post().to(add_tenant_handler::<C>)))
+    .service(web::resource("/tenants").route(web::get().to(list_tenants_handler::<C>)))
+    .service(
+        web::resource("/tenant/{tenant}/users/{user}")
+            .route(web::post().to(set_user_tenant_handler::<C>)),
+    );
 }
 
 /// Registers a user.
@@ -83,7 +98,8 @@ responses(
             "roles": [
                 "fa5be363-bc0d-4bfa-85c7-ebb5cd9a8783",
                 "4e8081b6-8aa6-4275-af0c-2fa2da557d28"
-            ]
+            ],
+            "tenant": "8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08"
         })
     )
 ))]
@@ -141,7 +157,8 @@ pub(crate) async fn logout_handler<C: ProContext>(
                 "roles": [
                     "fa5be363-bc0d-4bfa-85c7-ebb5cd9a8783",
                     "4e8081b6-8aa6-4275-af0c-2fa2da557d28"
-                ]
+                ],
+                "tenant": "8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08"
             })
         )
     ),
@@ -175,7 +192,8 @@ pub(crate) async fn session_handler<C: ProContext>(session: C::Session) -> impl
                 "roles": [
                     "8a27e61f-cc4d-4d0b-ae8c-4f1c91d07f5a",
                     "fd8e87bf-515c-4f36-8da6-1a53702ff102"
-                ]
+                ],
+                "tenant": "8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08"
             })
         )
     )
@@ -334,6 +352,137 @@ pub(crate) async fn oidc_login<C: ProContext>(
     Ok(web::Json(session))
 }
 
+/// The user of a session and the storage that is occupied by the user's uploads
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDetails {
+    pub user: UserInfo,
+    pub tenant: TenantId,
+    pub storage: StorageUsage,
+}
+
+/// Retrieves the user of the session and the storage of the user's uploads, including materialized workflow outputs.
+/// Uploads that would exceed the quota of the user or of the user's tenant are rejected.
+#[utoipa::path(
+    tag = "Session",
+    get,
+    path = "/user",
+    responses(
+        (status = 200, description = "The user and the storage usage", body = UserDetails,
+            example = json!({
+                "user": {
+                    "id": "5b4466d2-8bab-4ed8-a182-722af3c80958",
+                    "email": "foo@bar.de",
+                    "realName": "Foo Bar"
+                },
+                "tenant": "8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08",
+                "storage": {
+                    "userBytes": 1048576,
+                    "userQuotaBytes": 10737418240_u64,
+                    "tenantBytes": 5242880,
+                    "tenantQuotaBytes": null
+                }
+            })
+        )
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub(crate) async fn user_handler<C: ProContext>(
+    session: C::Session,
+    ctx: web::Data<C>,
+) -> Result<impl Responder> {
+    let storage = ctx.dataset_db_ref().storage_usage(&session).await?;
+
+    Ok(web::Json(UserDetails {
+        user: session.user,
+        tenant: session.tenant,
+        storage,
+    }))
+}
+
+/// Creates a new tenant. Users can be moved to the tenant afterwards.
+#[utoipa::path(
+    tag = "Tenants",
+    post,
+    path = "/tenant",
+    request_body = AddTenant,
+    responses(
+        (status = 200, description = "The id of the created tenant", body = TenantId,
+            example = json!({
+                "id": "b4a9c4a0-0b1f-4d3e-8c5a-9e2f7d6b1a3c"
+            })
+        )
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+pub(crate) async fn add_tenant_handler<C: ProContext>(
+    _session: AdminSession,
+    ctx: web::Data<C>,
+    tenant: web::Json<AddTenant>,
+) -> Result<impl Responder> {
+    let tenant = tenant.into_inner().validated()?;
+    let id = ctx.user_db_ref().add_tenant(tenant).await?;
+    Ok(web::Json(IdResponse::from(id)))
+}
+
+/// Lists the default tenant and all created tenants.
+#[utoipa::path(
+    tag = "Tenants",
+    get,
+    path = "/tenants",
+    responses(
+        (status = 200, description = "The tenants", body = [Tenant],
+            example = json!([
+                {
+                    "id": "8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08",
+                    "name": "Default"
+                }
+            ])
+        )
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+pub(crate) async fn list_tenants_handler<C: ProContext>(
+    _session: AdminSession,
+    ctx: web::Data<C>,
+) -> Result<impl Responder> {
+    let tenants = ctx.user_db_ref().list_tenants().await?;
+    Ok(web::Json(tenants))
+}
+
+/// Moves a user to a tenant. The user's existing sessions are moved as well.
+/// Datasets, workflows and layer providers that the user added before remain in the old tenant.
+#[utoipa::path(
+    tag = "Tenants",
+    post,
+    path = "/tenant/{tenant}/users/{user}",
+    responses(
+        (status = 200, description = "The user was moved to the tenant")
+    ),
+    params(
+        ("tenant" = TenantId, description = "Tenant id"),
+        ("user" = UserId, description = "User id"),
+    ),
+    security(
+        ("admin_token" = [])
+    )
+)]
+pub(crate) async fn set_user_tenant_handler<C: ProContext>(
+    _session: AdminSession,
+    ctx: web::Data<C>,
+    path: web::Path<(TenantId, UserId)>,
+) -> Result<impl Responder> {
+    let (tenant, user) = path.into_inner();
+    ctx.user_db_ref().set_user_tenant(user, tenant).await?;
+    Ok(HttpResponse::Ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,7 +499,7 @@ mod tests {
     use crate::util::tests::{check_allowed_http_methods, read_body_string};
     use crate::util::user_input::Validated;
 
-    use crate::pro::users::{AuthCodeRequestURL, OidcRequestDb};
+    use crate::pro::users::{AuthCodeRequestURL, OidcRequestDb, Tenant};
     use crate::pro::util::config::Oidc;
     use actix_web::dev::ServiceResponse;
     use actix_web::{http::header, http::Method, test};
@@ -435,7 +584,7 @@ mod tests {
 
         check_allowed_http_methods(
             |method| register_test_helper(ctx.clone(), method, "foo@example.com"),
-            &[Method::POST],
+            &[Method::POST, Method::GET],
         )
         .await;
     }
@@ -1154,4 +1303,78 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn it_moves_users_to_tenants() {
+        let ctx = ProInMemoryContext::test_default();
+        let admin_session_id = AdminSession::default().id();
+
+        let session = create_session_helper(&ctx).await;
+
+        let req = test::TestRequest::post()
+            .uri("/tenant")
+            .append_header((
+                header::AUTHORIZATION,
+                Bearer::new(admin_session_id.to_string()),
+            ))
+            .set_json(&AddTenant {
+                name: "Institute of Geography".to_string(),
+            });
+        let res = send_pro_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+        let tenant: IdResponse<TenantId> = test::read_body_json(res).await;
+
+        let req = test::TestRequest::get().uri("/tenants").append_header((
+            header::AUTHORIZATION,
+            Bearer::new(admin_session_id.to_string()),
+        ));
+        let res = send_pro_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+        let tenants: Vec<Tenant> = test::read_body_json(res).await;
+        assert!(tenants.iter().any(|t| t.id == tenant.id));
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/tenant/{}/users/{}", tenant.id, session.user.id))
+            .append_header((
+                header::AUTHORIZATION,
+                Bearer::new(admin_session_id.to_string()),
+            ));
+        let res = send_pro_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            ctx.user_db_ref().session(session.id).await.unwrap().tenant,
+            tenant.id
+        );
+
+        // users cannot manage tenants
+        let req = test::TestRequest::get()
+            .uri("/tenants")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id.to_string())));
+        let res = send_pro_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn it_shows_the_storage_usage() {
+        let ctx = ProInMemoryContext::test_default();
+
+        let session = create_session_helper(&ctx).await;
+
+        let req = test::TestRequest::get()
+            .uri("/user")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id.to_string())));
+        let res = send_pro_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let details: UserDetails = test::read_body_json(res).await;
+        assert_eq!(details.user, session.user);
+        assert_eq!(details.tenant, session.tenant);
+        assert_eq!(details.storage.user_bytes, 0);
+        assert_eq!(details.storage.tenant_bytes, Some(0));
+    }
 }
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use snafu::ensure;
+
+use crate::api::model::datatypes::DataProviderId;
+use crate::contexts::Db;
+use crate::error::{self, Error, Result};
+use crate::layers::cache::ProviderCache;
+use crate::layers::external::{DataProvider, DataProviderDefinition};
+use crate::layers::storage::{LayerProviderDb, LayerProviderListing, LayerProviderListingOptions};
+use crate::pro::users::{TenantId, UserSession};
+use crate::util::user_input::Validated;
+
+/// An in-memory layer provider db that stores the tenant of each provider.
+/// Providers of the system tenant, e.g., from the provider directory, are visible to all tenants,
+/// but only admins may change them.
+#[derive(Default)]
+pub struct ProHashMapLayerProviderDb {
+    external_providers: Db<HashMap<DataProviderId, (TenantId, Box<dyn DataProviderDefinition>)>>,
+    cache: Arc<ProviderCache>,
+}
+
+#[async_trait]
+impl LayerProviderDb<UserSession> for ProHashMapLayerProviderDb {
+    async fn add_layer_provider(
+        &self,
+        session: &UserSession,
+        provider: Box<dyn DataProviderDefinition>,
+    ) -> Result<DataProviderId> {
+        let id = provider.id();
+
+        let mut external_providers = self.external_providers.write().await;
+
+        let writable = external_providers
+            .get(&id)
+            .map_or(session.tenant, |(tenant, _)| *tenant);
+        ensure!(
+            session.is_tenant_writable(writable) && session.is_tenant_writable(session.tenant),
+            error::LayerProviderPermissionDenied { provider: id }
+        );
+
+        external_providers.insert(id, (session.tenant, provider));
+
+        Ok(id)
+    }
+
+    async fn list_layer_providers(
+        &self,
+        session: &UserSession,
+        options: Validated<LayerProviderListingOptions>,
+    ) -> Result<Vec<LayerProviderListing>> {
+        let options = options.user_input;
+
+        let mut listing = self
+            .external_providers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, (tenant, _))| session.is_tenant_visible(*tenant))
+            .map(|(id, (_, provider))| LayerProviderListing {
+                id: *id,
+                name: provider.name(),
+                description: provider.type_name().to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        listing.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(listing
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect())
+    }
+
+    async fn layer_provider(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProvider>> {
+        let definition = self.layer_provider_definition(session, id).await?;
+        self.cache.initialize(definition).await
+    }
+
+    async fn layer_provider_definition(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProviderDefinition>> {
+        self.external_providers
+            .read()
+            .await
+            .get(&id)
+            .filter(|(tenant, _)| session.is_tenant_visible(*tenant))
+            .map(|(_, provider)| provider.clone())
+            .ok_or(Error::UnknownProviderId)
+    }
+
+    async fn update_layer_provider(
+        &self,
+        session: &UserSession,
+        provider: Box<dyn DataProviderDefinition>,
+    ) -> Result<()> {
+        let mut external_providers = self.external_providers.write().await;
+
+        let id = provider.id();
+        let (tenant, stored) = external_providers
+            .get_mut(&id)
+            .filter(|(tenant, _)| session.is_tenant_visible(*tenant))
+            .ok_or(Error::UnknownProviderId)?;
+        ensure!(
+            session.is_tenant_writable(*tenant),
+            error::LayerProviderPermissionDenied { provider: id }
+        );
+        *stored = provider;
+
+        self.cache.invalidate(id).await;
+
+        Ok(())
+    }
+
+    async fn invalidate_layer_provider_cache(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<()> {
+        self.layer_provider_definition(session, id).await?;
+
+        self.cache.invalidate(id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::MockableSession;
+    use crate::datasets::external::mock::MockExternalLayerProviderDefinition;
+    use crate::util::user_input::UserInput;
+    use crate::util::Identifier;
+
+    #[tokio::test]
+    async fn it_isolates_tenants() -> Result<()> {
+        let db = ProHashMapLayerProviderDb::default();
+
+        let session = UserSession::mock();
+        let other_session = UserSession {
+            tenant: TenantId::new(),
+            ..UserSession::mock()
+        };
+
+        let shared_provider = DataProviderId::new();
+        db.add_layer_provider(
+            &UserSession::system_session(),
+            Box::new(MockExternalLayerProviderDefinition {
+                id: shared_provider,
+                datasets: vec![],
+            }),
+        )
+        .await?;
+
+        let provider = DataProviderId::new();
+        db.add_layer_provider(
+            &session,
+            Box::new(MockExternalLayerProviderDefinition {
+                id: provider,
+                datasets: vec![],
+            }),
+        )
+        .await?;
+
+        let options = LayerProviderListingOptions {
+            offset: 0,
+            limit: 10,
+        };
+
+        assert_eq!(
+            db.list_layer_providers(&session, options.clone().validated()?)
+                .await?
+                .len(),
+            2
+        );
+        assert_eq!(
+            db.list_layer_providers(&other_session, options.validated()?)
+                .await?
+                .len(),
+            1
+        );
+
+        assert!(db
+            .layer_provider_definition(&other_session, shared_provider)
+            .await
+            .is_ok());
+        assert!(db
+            .layer_provider_definition(&other_session, provider)
+            .await
+            .is_err());
+
+        assert!(matches!(
+            db.update_layer_provider(
+                &other_session,
+                Box::new(MockExternalLayerProviderDefinition {
+                    id: shared_provider,
+                    datasets: vec![],
+                }),
+            )
+            .await,
+            Err(Error::LayerProviderPermissionDenied { .. })
+        ));
+        assert!(matches!(
+            db.add_layer_provider(
+                &other_session,
+                Box::new(MockExternalLayerProviderDefinition {
+                    id: provider,
+                    datasets: vec![],
+                }),
+            )
+            .await,
+            Err(Error::LayerProviderPermissionDenied { .. })
+        ));
+        assert!(db
+            .update_layer_provider(
+                &UserSession::system_session(),
+                Box::new(MockExternalLayerProviderDefinition {
+                    id: shared_provider,
+                    datasets: vec![],
+                }),
+            )
+            .await
+            .is_ok());
+
+        Ok(())
+    }
+}
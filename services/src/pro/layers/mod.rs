@@ -1 +1,2 @@
+pub mod hashmap_layer_provider_db;
 pub mod postgres_layer_db;
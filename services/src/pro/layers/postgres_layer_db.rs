@@ -1,4 +1,4 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use crate::api::model::datatypes::{DataProviderId, LayerId};
 use async_trait::async_trait;
@@ -10,12 +10,13 @@ use bb8_postgres::{
     },
     PostgresConnectionManager,
 };
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use uuid::Uuid;
 
 use crate::{
     error::{self, Result},
     layers::{
+        cache::ProviderCache,
         external::{DataProvider, DataProviderDefinition},
         layer::{
             AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection,
@@ -29,6 +30,7 @@ use crate::{
             INTERNAL_PROVIDER_ID,
         },
     },
+    pro::users::{Tenant, UserSession},
     util::user_input::Validated,
 };
 
@@ -501,6 +503,7 @@ where
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
     pub(crate) conn_pool: Pool<PostgresConnectionManager<Tls>>,
+    cache: Arc<ProviderCache>,
 }
 
 impl<Tls> PostgresLayerProviderDb<Tls>
@@ -511,12 +514,15 @@ where
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
     pub fn new(conn_pool: Pool<PostgresConnectionManager<Tls>>) -> Self {
-        Self { conn_pool }
+        Self {
+            conn_pool,
+            cache: Arc::default(),
+        }
     }
 }
 
 #[async_trait]
-impl<Tls> LayerProviderDb for PostgresLayerProviderDb<Tls>
+impl<Tls> LayerProviderDb<UserSession> for PostgresLayerProviderDb<Tls>
 where
     Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
     <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
@@ -525,9 +531,16 @@ where
 {
     async fn add_layer_provider(
         &self,
+        session: &UserSession,
         provider: Box<dyn DataProviderDefinition>,
     ) -> Result<DataProviderId> {
-        // TODO: permissions
+        let id = provider.id();
+
+        ensure!(
+            session.is_tenant_writable(session.tenant),
+            error::LayerProviderPermissionDenied { provider: id }
+        );
+
         let conn = self.conn_pool.get().await?;
 
         let stmt = conn
@@ -535,19 +548,20 @@ where
                 "
               INSERT INTO layer_providers (
                   id, 
+                  tenant_id,
                   type_name, 
                   name,
                   definition
               )
-              VALUES ($1, $2, $3, $4)",
+              VALUES ($1, $2, $3, $4, $5)",
             )
             .await?;
 
-        let id = provider.id();
         conn.execute(
             &stmt,
             &[
                 &id,
+                &session.tenant,
                 &provider.type_name(),
                 &provider.name(),
                 &serde_json::to_value(provider)?,
@@ -559,9 +573,9 @@ where
 
     async fn list_layer_providers(
         &self,
+        session: &UserSession,
         options: Validated<LayerProviderListingOptions>,
     ) -> Result<Vec<LayerProviderListing>> {
-        // TODO: permission
         let conn = self.conn_pool.get().await?;
 
         let options = options.user_input;
@@ -575,6 +589,8 @@ where
                 type_name
             FROM 
                 layer_providers
+            WHERE
+                tenant_id = $3 OR tenant_id = $4
             ORDER BY name ASC
             LIMIT $1 
             OFFSET $2;",
@@ -584,7 +600,12 @@ where
         let rows = conn
             .query(
                 &stmt,
-                &[&i64::from(options.limit), &i64::from(options.offset)],
+                &[
+                    &i64::from(options.limit),
+                    &i64::from(options.offset),
+                    &session.tenant,
+                    &Tenant::system_tenant_id(),
+                ],
             )
             .await?;
 
@@ -598,8 +619,20 @@ where
             .collect())
     }
 
-    async fn layer_provider(&self, id: DataProviderId) -> Result<Box<dyn DataProvider>> {
-        // TODO: permissions
+    async fn layer_provider(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProvider>> {
+        let definition = self.layer_provider_definition(session, id).await?;
+        self.cache.initialize(definition).await
+    }
+
+    async fn layer_provider_definition(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<Box<dyn DataProviderDefinition>> {
         let conn = self.conn_pool.get().await?;
 
         let stmt = conn
@@ -610,14 +643,79 @@ where
                FROM 
                    layer_providers
                WHERE
-                   id = $1",
+                   id = $1 AND (tenant_id = $2 OR tenant_id = $3)",
             )
             .await?;
 
-        let row = conn.query_one(&stmt, &[&id]).await?;
+        let row = conn
+            .query_opt(&stmt, &[&id, &session.tenant, &Tenant::system_tenant_id()])
+            .await?
+            .ok_or(error::Error::UnknownProviderId)?;
 
         let definition = serde_json::from_value::<Box<dyn DataProviderDefinition>>(row.get(0))?;
 
-        definition.initialize().await
+        Ok(definition)
+    }
+
+    async fn update_layer_provider(
+        &self,
+        session: &UserSession,
+        provider: Box<dyn DataProviderDefinition>,
+    ) -> Result<()> {
+        let id = provider.id();
+
+        // fails if the provider is not visible to the session
+        self.layer_provider_definition(session, id).await?;
+
+        let conn = self.conn_pool.get().await?;
+
+        // the system tenant's providers may only be changed by admins
+        let stmt = conn
+            .prepare(
+                "
+              UPDATE layer_providers
+              SET 
+                  type_name = $2, 
+                  name = $3,
+                  definition = $4
+              WHERE id = $1 AND ((tenant_id = $5 AND tenant_id <> $6) OR (tenant_id = $6 AND $7))",
+            )
+            .await?;
+
+        let updated = conn
+            .execute(
+                &stmt,
+                &[
+                    &id,
+                    &provider.type_name(),
+                    &provider.name(),
+                    &serde_json::to_value(provider)?,
+                    &session.tenant,
+                    &Tenant::system_tenant_id(),
+                    &session.is_admin(),
+                ],
+            )
+            .await?;
+
+        ensure!(
+            updated == 1,
+            error::LayerProviderPermissionDenied { provider: id }
+        );
+
+        self.cache.invalidate(id).await;
+
+        Ok(())
+    }
+
+    async fn invalidate_layer_provider_cache(
+        &self,
+        session: &UserSession,
+        id: DataProviderId,
+    ) -> Result<()> {
+        // fails if the provider is not visible in the session
+        self.layer_provider_definition(session, id).await?;
+
+        self.cache.invalidate(id).await;
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+use crate::api::versioning::ApiVersioning;
 use crate::error::{Error, Result};
 use crate::handlers;
 use crate::pro;
@@ -6,17 +7,20 @@ use crate::pro::apidoc::ApiDoc;
 use crate::pro::contexts::PostgresContext;
 use crate::pro::contexts::{ProContext, ProInMemoryContext};
 use crate::util::config::{self, get_config_element, Backend};
+use crate::util::preflight::{run_preflight, PreflightReport};
 
 use super::projects::ProProjectDb;
 use crate::util::server::{
     calculate_max_blocking_threads_per_worker, configure_extractors, connection_init,
-    log_server_info, render_404, render_405, serve_openapi_json,
+    log_server_info, render_404, render_405, serve_openapi_check, serve_openapi_json,
 };
 use actix_files::Files;
 use actix_web::{http, middleware, web, App, HttpServer};
 #[cfg(feature = "postgres")]
 use bb8_postgres::tokio_postgres::NoTls;
+use chrono::NaiveDate;
 use geoengine_datatypes::raster::TilingSpecification;
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
 use geoengine_operators::engine::ChunkByteSize;
 use geoengine_operators::util::gdal::register_gdal_drivers_from_list;
 use log::{info, warn};
@@ -29,19 +33,24 @@ async fn start<C>(
     static_files_dir: Option<PathBuf>,
     bind_address: SocketAddr,
     version_api: bool,
+    deprecated_api_sunset: Option<NaiveDate>,
     ctx: C,
+    preflight_report: PreflightReport,
 ) -> Result<(), Error>
 where
     C: ProContext,
     C::ProjectDB: ProProjectDb,
 {
     let wrapped_ctx = web::Data::new(ctx);
+    let preflight_report = web::Data::new(preflight_report);
 
     let openapi = ApiDoc::openapi();
 
     HttpServer::new(move || {
         let mut app = App::new()
             .app_data(wrapped_ctx.clone())
+            .app_data(preflight_report.clone())
+            .wrap(ApiVersioning::new(deprecated_api_sunset))
             .wrap(
                 middleware::ErrorHandlers::default()
                     .handler(http::StatusCode::NOT_FOUND, render_404)
@@ -52,7 +61,9 @@ where
             .configure(handlers::datasets::init_dataset_routes::<C>)
             .configure(handlers::layers::init_layer_routes::<C>)
             .configure(handlers::plots::init_plot_routes::<C>)
+            .configure(handlers::preflight::init_preflight_routes)
             .configure(pro::handlers::projects::init_project_routes::<C>)
+            .configure(handlers::reports::init_report_routes::<C>)
             .configure(pro::handlers::users::init_user_routes::<C>)
             .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
             .configure(handlers::upload::init_upload_routes::<C>)
@@ -73,6 +84,8 @@ where
             openapi.clone(),
         );
 
+        app = serve_openapi_check(app, "/api-docs/check", openapi.clone());
+
         #[cfg(feature = "odm")]
         {
             app = app.configure(pro::handlers::drone_mapping::init_drone_mapping_routes::<C>);
@@ -90,11 +103,32 @@ where
                 "/api-docs/ebv/openapi.json",
                 crate::handlers::ebv::ApiDoc::openapi(),
             );
+
+            app = serve_openapi_check(
+                app,
+                "/api-docs/ebv/check",
+                crate::handlers::ebv::ApiDoc::openapi(),
+            );
         }
 
         #[cfg(feature = "nfdi")]
         {
             app = app.configure(handlers::gfbio::init_gfbio_routes::<C>);
+
+            app = serve_openapi_json(
+                app,
+                &mut api_urls,
+                "GFBio",
+                "../api-docs/gfbio/openapi.json",
+                "/api-docs/gfbio/openapi.json",
+                crate::handlers::gfbio::ApiDoc::openapi(),
+            );
+
+            app = serve_openapi_check(
+                app,
+                "/api-docs/gfbio/check",
+                crate::handlers::gfbio::ApiDoc::openapi(),
+            );
         }
 
         app = app.service(SwaggerUi::new("/swagger-ui/{_:.*}").urls(api_urls));
@@ -105,6 +139,14 @@ where
                 web::get().to(crate::util::server::server_info_handler),
             );
         }
+        app = app.route(
+            "/metrics/memory",
+            web::get().to(crate::util::server::memory_metrics_handler),
+        );
+        app = app.route(
+            "/metrics/gdal",
+            web::get().to(crate::util::server::gdal_dataset_pool_metrics_handler),
+        );
         if let Some(static_files_dir) = static_files_dir.clone() {
             app = app.service(Files::new("/static", static_files_dir));
         }
@@ -137,6 +179,10 @@ pub async fn start_pro_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     log_server_info()?;
 
+    crate::util::secrets::ensure_secret_key()?;
+    crate::util::operators::ensure_operator_config()?;
+    crate::pro::util::operators::ensure_role_operator_config()?;
+
     let user_config: crate::pro::util::config::User = get_config_element()?;
     let oidc_config: crate::pro::util::config::Oidc = get_config_element()?;
     let session_config: crate::util::config::Session = get_config_element()?;
@@ -171,13 +217,14 @@ pub async fn start_pro_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     let data_path_config: config::DataProvider = get_config_element()?;
 
-    let chunk_byte_size = config::get_config_element::<config::QueryContext>()?
-        .chunk_byte_size
-        .into();
+    let query_context_config = config::get_config_element::<config::QueryContext>()?;
+    let chunk_byte_size = query_context_config.chunk_byte_size.into();
+    MEMORY_ACCOUNTANT.set_budget_bytes(query_context_config.memory_budget_bytes);
 
     let tiling_spec = config::get_config_element::<config::TilingSpecification>()?.into();
 
     register_gdal_drivers_from_list(config::get_config_element::<config::Gdal>()?.allowed_drivers);
+    crate::util::server::configure_gdal_dataset_pool()?;
 
     match web_config.backend {
         Backend::InMemory => {
@@ -215,21 +262,25 @@ async fn start_in_memory(
 ) -> Result<()> {
     info!("Using in memory backend");
     let ctx = ProInMemoryContext::new_with_data(
-        data_path_config.dataset_defs_path,
-        data_path_config.provider_defs_path,
-        data_path_config.layer_defs_path,
-        data_path_config.layer_collection_defs_path,
+        data_path_config.dataset_defs_path.clone(),
+        data_path_config.provider_defs_path.clone(),
+        data_path_config.layer_defs_path.clone(),
+        data_path_config.layer_collection_defs_path.clone(),
         tiling_spec,
         chunk_byte_size,
         oidc_config,
     )
     .await;
 
+    let preflight_report = run_preflight(&data_path_config, &ctx).await?;
+
     start(
         static_files_dir,
         web_config.bind_address,
         web_config.version_api,
+        web_config.deprecated_api_sunset,
         ctx,
+        preflight_report,
     )
     .await
 }
@@ -259,21 +310,25 @@ async fn start_postgres(
         let ctx = PostgresContext::new_with_data(
             pg_config,
             NoTls,
-            data_path_config.dataset_defs_path,
-            data_path_config.provider_defs_path,
-            data_path_config.layer_defs_path,
-            data_path_config.layer_collection_defs_path,
+            data_path_config.dataset_defs_path.clone(),
+            data_path_config.provider_defs_path.clone(),
+            data_path_config.layer_defs_path.clone(),
+            data_path_config.layer_collection_defs_path.clone(),
             tiling_spec,
             chunk_byte_size,
             oidc_config,
         )
         .await?;
 
+        let preflight_report = run_preflight(&data_path_config, &ctx).await?;
+
         start(
             static_files_dir,
             web_config.bind_address,
             web_config.version_api,
+            web_config.deprecated_api_sunset,
             ctx,
+            preflight_report,
         )
         .await
     }
@@ -11,7 +11,8 @@ use crate::error::{self, Result};
 use crate::pro::datasets::Role;
 use crate::pro::users::oidc::{ExternalUser, ExternalUserClaims};
 use crate::pro::users::{
-    User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession,
+    AddTenant, Tenant, TenantId, User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration,
+    UserSession,
 };
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
@@ -22,6 +23,7 @@ pub struct HashMapUserDb {
     users: Db<HashMap<String, User>>,
     external_users: Db<HashMap<SubjectIdentifier, ExternalUser>>, //TODO: Key only works if a single identity provider is used
     sessions: Db<HashMap<SessionId, UserSession>>,
+    tenants: Db<HashMap<TenantId, Tenant>>,
 }
 
 #[async_trait]
@@ -51,6 +53,7 @@ impl UserDb for HashMapUserDb {
             password_hash: String::new(),
             real_name: String::new(),
             active: true,
+            tenant: Tenant::default_tenant_id(),
         };
 
         self.users.write().await.insert(id.to_string(), user);
@@ -67,6 +70,7 @@ impl UserDb for HashMapUserDb {
             project: None,
             view: None,
             roles: vec![id.into(), Role::anonymous_role_id()],
+            tenant: Tenant::default_tenant_id(),
         };
 
         self.sessions
@@ -93,6 +97,7 @@ impl UserDb for HashMapUserDb {
                     project: None,
                     view: None,
                     roles: vec![user.id.into(), Role::user_role_id()],
+                    tenant: user.tenant,
                 };
 
                 self.sessions
@@ -114,17 +119,18 @@ impl UserDb for HashMapUserDb {
 
         let external_id = user.external_id.clone();
 
-        let internal_id = match db.get(&external_id) {
-            Some(user) => user.id,
+        let (internal_id, tenant) = match db.get(&external_id) {
+            Some(user) => (user.id, user.tenant),
             None => {
                 let id = UserId::new();
                 let result = ExternalUser {
                     id,
                     claims: user.clone(),
                     active: true,
+                    tenant: Tenant::default_tenant_id(),
                 };
                 db.insert(external_id, result);
-                id
+                (id, Tenant::default_tenant_id())
             }
         };
 
@@ -142,6 +148,7 @@ impl UserDb for HashMapUserDb {
             project: None,
             view: None,
             roles: vec![internal_id.into(), Role::user_role_id()],
+            tenant,
         };
 
         self.sessions
@@ -186,6 +193,69 @@ impl UserDb for HashMapUserDb {
             None => Err(error::Error::InvalidSession),
         }
     }
+
+    async fn add_tenant(&self, tenant: Validated<AddTenant>) -> Result<TenantId> {
+        let tenant = Tenant {
+            id: TenantId::new(),
+            name: tenant.user_input.name,
+        };
+        let id = tenant.id;
+
+        self.tenants.write().await.insert(id, tenant);
+
+        Ok(id)
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<Tenant>> {
+        let mut tenants = vec![Tenant {
+            id: Tenant::default_tenant_id(),
+            name: "default".to_string(),
+        }];
+        tenants.extend(self.tenants.read().await.values().cloned());
+
+        Ok(tenants)
+    }
+
+    async fn set_user_tenant(&self, user: UserId, tenant: TenantId) -> Result<()> {
+        ensure!(
+            tenant == Tenant::default_tenant_id()
+                || self.tenants.read().await.contains_key(&tenant),
+            error::UnknownTenantId
+        );
+
+        let mut found = false;
+
+        if let Some(internal_user) = self.users.write().await.values_mut().find(|u| u.id == user) {
+            internal_user.tenant = tenant;
+            found = true;
+        }
+
+        if let Some(external_user) = self
+            .external_users
+            .write()
+            .await
+            .values_mut()
+            .find(|u| u.id == user)
+        {
+            external_user.tenant = tenant;
+            found = true;
+        }
+
+        ensure!(found, error::UnknownUserId);
+
+        // existing sessions of the user switch to the new tenant as well
+        for session in self
+            .sessions
+            .write()
+            .await
+            .values_mut()
+            .filter(|s| s.user.id == user)
+        {
+            session.tenant = tenant;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
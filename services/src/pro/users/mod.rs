@@ -3,6 +3,7 @@ mod oidc;
 #[cfg(feature = "postgres")]
 mod postgres_userdb;
 mod session;
+mod tenant;
 mod user;
 mod userdb;
 
@@ -16,5 +17,6 @@ pub(super) use oidc::{AuthCodeResponse, OidcDisabled, OidcRequestDb};
 #[cfg(feature = "postgres")]
 pub use postgres_userdb::PostgresUserDb;
 pub use session::{UserInfo, UserSession};
+pub use tenant::{AddTenant, Tenant, TenantId};
 pub use user::{User, UserCredentials, UserId, UserRegistration};
 pub use userdb::UserDb;
@@ -1,6 +1,6 @@
 use crate::contexts::Db;
 use crate::error::{Error, Result};
-use crate::pro::users::UserId;
+use crate::pro::users::{TenantId, UserId};
 use crate::pro::util::config::Oidc;
 #[cfg(test)]
 use crate::pro::util::tests::mock_oidc::{SINGLE_NONCE, SINGLE_STATE};
@@ -104,6 +104,7 @@ pub struct ExternalUser {
     pub id: UserId,
     pub claims: ExternalUserClaims,
     pub active: bool,
+    pub tenant: TenantId,
 }
 
 #[derive(Clone)]
@@ -4,7 +4,8 @@ use crate::pro::datasets::Role;
 use crate::pro::projects::ProjectPermission;
 use crate::pro::users::oidc::ExternalUserClaims;
 use crate::pro::users::{
-    User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession,
+    AddTenant, Tenant, TenantId, User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration,
+    UserSession,
 };
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
@@ -164,13 +165,16 @@ where
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::anonymous_role_id()],
+            tenant: Tenant::default_tenant_id(),
         })
     }
 
     async fn login(&self, user_credentials: UserCredentials) -> Result<UserSession> {
         let conn = self.conn_pool.get().await?;
         let stmt = conn
-            .prepare("SELECT id, password_hash, email, real_name FROM users WHERE email = $1;")
+            .prepare(
+                "SELECT id, password_hash, email, real_name, tenant_id FROM users WHERE email = $1;",
+            )
             .await?;
 
         let row = conn
@@ -182,6 +186,7 @@ where
         let password_hash = row.get(1);
         let email = row.get(2);
         let real_name = row.get(3);
+        let tenant = row.get(4);
 
         if bcrypt::verify(user_credentials.password, password_hash) {
             let session_id = SessionId::new();
@@ -230,6 +235,7 @@ where
                 project: None,
                 view: None,
                 roles,
+                tenant,
             })
         } else {
             Err(error::Error::LoginFailed)
@@ -333,6 +339,11 @@ where
 
         let roles = rows.into_iter().map(|row| row.get(0)).collect();
 
+        let stmt = conn
+            .prepare("SELECT tenant_id FROM users WHERE id = $1;")
+            .await?;
+        let tenant = conn.query_one(&stmt, &[&user_id]).await?.get(0);
+
         Ok(UserSession {
             id: session_id,
             user: UserInfo {
@@ -345,6 +356,7 @@ where
             project: None,
             view: None,
             roles,
+            tenant,
         })
     }
 
@@ -372,7 +384,8 @@ where
                 s.created, 
                 s.valid_until, 
                 s.project_id,
-                s.view           
+                s.view,
+                u.tenant_id
             FROM sessions s JOIN users u ON (s.user_id = u.id)
             WHERE s.id = $1 AND CURRENT_TIMESTAMP < s.valid_until;",
             )
@@ -395,6 +408,7 @@ where
             project: row.get::<usize, Option<Uuid>>(5).map(ProjectId),
             view: row.get(6),
             roles: vec![], // TODO
+            tenant: row.get(7),
         })
     }
 
@@ -432,4 +446,61 @@ where
 
         Ok(())
     }
+
+    async fn add_tenant(&self, tenant: Validated<AddTenant>) -> Result<TenantId> {
+        let conn = self.conn_pool.get().await?;
+
+        let id = TenantId::new();
+
+        let stmt = conn
+            .prepare("INSERT INTO tenants (id, name) VALUES ($1, $2);")
+            .await?;
+        conn.execute(&stmt, &[&id, &tenant.user_input.name]).await?;
+
+        Ok(id)
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<Tenant>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT id, name FROM tenants WHERE id <> $1 ORDER BY name;")
+            .await?;
+        let rows = conn.query(&stmt, &[&Tenant::system_tenant_id()]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Tenant {
+                id: row.get(0),
+                name: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn set_user_tenant(&self, user: UserId, tenant: TenantId) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT TRUE FROM tenants WHERE id = $1 AND id <> $2;")
+            .await?;
+        tx.query_opt(&stmt, &[&tenant, &Tenant::system_tenant_id()])
+            .await?
+            .ok_or(error::Error::UnknownTenantId)?;
+
+        // sessions read the tenant of their user, so they switch to the new tenant as well
+        let stmt = tx
+            .prepare("UPDATE users SET tenant_id = $2 WHERE id = $1;")
+            .await?;
+        let updated = tx.execute(&stmt, &[&user, &tenant]).await?;
+
+        if updated == 0 {
+            return Err(error::Error::UnknownUserId);
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
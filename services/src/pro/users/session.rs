@@ -3,7 +3,7 @@ use crate::error;
 use crate::handlers::get_token;
 use crate::pro::contexts::{PostgresContext, ProInMemoryContext};
 use crate::pro::datasets::{Role, RoleId};
-use crate::pro::users::UserId;
+use crate::pro::users::{Tenant, TenantId, UserId};
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::Identifier;
 use actix_http::Payload;
@@ -34,6 +34,7 @@ pub struct UserSession {
     pub project: Option<ProjectId>,
     pub view: Option<STRectangle>,
     pub roles: Vec<RoleId>, // a user has a default role (= its user id) and other additonal roles
+    pub tenant: TenantId,
 }
 
 impl UserSession {
@@ -52,8 +53,29 @@ impl UserSession {
             project: None,
             view: None,
             roles: vec![role],
+            tenant: Tenant::system_tenant_id(),
         }
     }
+
+    /// Whether data of the `tenant` is visible in this session.
+    /// This is the case for the session's own tenant and the system tenant.
+    pub fn is_tenant_visible(&self, tenant: TenantId) -> bool {
+        tenant == self.tenant || tenant == Tenant::system_tenant_id()
+    }
+
+    /// Whether data of the `tenant` may be changed in this session.
+    /// Data of the system tenant may only be changed by admins, other data only by its own tenant.
+    pub fn is_tenant_writable(&self, tenant: TenantId) -> bool {
+        if tenant == Tenant::system_tenant_id() {
+            self.is_admin()
+        } else {
+            tenant == self.tenant
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.roles.contains(&Role::system_role_id())
+    }
 }
 
 impl MockableSession for UserSession {
@@ -71,6 +93,7 @@ impl MockableSession for UserSession {
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::user_role_id()],
+            tenant: Tenant::default_tenant_id(),
         }
     }
 }
@@ -164,6 +187,7 @@ mod tests {
             created: DateTime::from_str("2020-01-01T00:00:00Z").unwrap(),
             valid_until: DateTime::from_str("2021-01-01T00:00:00Z").unwrap(),
             roles: vec![RoleId::from_str("da3825dd-6240-460d-a324-02bd06704aaa").unwrap()],
+            tenant: TenantId::from_str("5c8e3a1f-9d2b-4e6a-8f7c-0b1d2e3f4a5b").unwrap(),
         };
 
         assert_eq!(
@@ -195,7 +219,8 @@ mod tests {
                         "end": 1_609_459_200_000_i64
                     }
                 },
-                "roles": ["da3825dd-6240-460d-a324-02bd06704aaa"]
+                "roles": ["da3825dd-6240-460d-a324-02bd06704aaa"],
+                "tenant": "5c8e3a1f-9d2b-4e6a-8f7c-0b1d2e3f4a5b"
             })
         );
     }
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use utoipa::ToSchema;
+
+use crate::error::{self, Result};
+use crate::identifier;
+use crate::util::user_input::UserInput;
+
+identifier!(TenantId);
+
+/// A group of users whose datasets, workflows and layer providers are invisible to the users of other tenants
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema)]
+pub struct Tenant {
+    pub id: TenantId,
+    pub name: String,
+}
+
+impl Tenant {
+    /// The tenant of everything that is added by the system, e.g., from the data directories.
+    /// Its data is visible to the users of all tenants.
+    pub fn system_tenant_id() -> TenantId {
+        TenantId::from_str("3c6a0b8c-5f3e-4a3b-9a0c-1b7b2e0f9d41").expect("valid")
+    }
+
+    /// The tenant of all users that were not assigned to another tenant
+    pub fn default_tenant_id() -> TenantId {
+        TenantId::from_str("8a1f4b52-0e3d-4c7a-b6f5-2d9e7c4a1b08").expect("valid")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[schema(example = json!({
+    "name": "Institute of Geography"
+}))]
+pub struct AddTenant {
+    pub name: String,
+}
+
+impl UserInput for AddTenant {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.name.trim().is_empty(),
+            error::InvalidTenantName {
+                reason: "the name must not be empty".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+}
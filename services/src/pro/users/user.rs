@@ -6,6 +6,7 @@ use utoipa::ToSchema;
 use crate::error;
 use crate::error::{Error, Result};
 use crate::identifier;
+use crate::pro::users::{Tenant, TenantId};
 use crate::util::user_input::UserInput;
 use geoengine_datatypes::util::Identifier;
 
@@ -69,6 +70,7 @@ pub struct User {
     pub password_hash: String,
     pub real_name: String,
     pub active: bool,
+    pub tenant: TenantId,
 }
 
 impl From<UserRegistration> for User {
@@ -79,6 +81,7 @@ impl From<UserRegistration> for User {
             password_hash: bcrypt::hash(&user_registration.password).unwrap(),
             real_name: user_registration.real_name,
             active: true,
+            tenant: Tenant::default_tenant_id(),
         }
     }
 }
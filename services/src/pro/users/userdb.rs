@@ -1,7 +1,9 @@
 use crate::contexts::SessionId;
 use crate::error::Result;
 use crate::pro::users::oidc::ExternalUserClaims;
-use crate::pro::users::{UserCredentials, UserId, UserRegistration, UserSession};
+use crate::pro::users::{
+    AddTenant, Tenant, TenantId, UserCredentials, UserId, UserRegistration, UserSession,
+};
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
 use async_trait::async_trait;
@@ -77,4 +79,19 @@ pub trait UserDb: Send + Sync {
     /// This call fails if the session is invalid
     ///
     async fn set_session_view(&self, session: &UserSession, view: STRectangle) -> Result<()>;
+
+    /// Creates a new tenant without any users
+    async fn add_tenant(&self, tenant: Validated<AddTenant>) -> Result<TenantId>;
+
+    /// Lists the default tenant and all created tenants
+    async fn list_tenants(&self) -> Result<Vec<Tenant>>;
+
+    /// Moves a user to another tenant, which also applies to the user's existing sessions
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the user or the tenant is unknown.
+    /// Users cannot be moved to the system tenant.
+    ///
+    async fn set_user_tenant(&self, user: UserId, tenant: TenantId) -> Result<()>;
 }
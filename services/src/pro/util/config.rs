@@ -1,5 +1,7 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
 
+use geoengine_operators::engine::OperatorPolicy;
 use serde::Deserialize;
 
 use crate::util::config::ConfigElement;
@@ -14,6 +16,39 @@ impl ConfigElement for User {
     const KEY: &'static str = "user";
 }
 
+/// Limits of the storage for uploads and materialized workflow outputs in bytes, 0 means unlimited
+#[derive(Debug, Deserialize)]
+pub struct Quota {
+    pub user_storage_bytes: u64,
+    pub tenant_storage_bytes: u64,
+}
+
+impl Quota {
+    pub fn user_storage_limit(&self) -> Option<u64> {
+        (self.user_storage_bytes > 0).then_some(self.user_storage_bytes)
+    }
+
+    pub fn tenant_storage_limit(&self) -> Option<u64> {
+        (self.tenant_storage_bytes > 0).then_some(self.tenant_storage_bytes)
+    }
+}
+
+impl ConfigElement for Quota {
+    const KEY: &'static str = "quota";
+}
+
+/// Operator policies of roles, keyed by role id.
+/// They replace the `operators` policy of the instance for sessions with these roles.
+#[derive(Debug, Deserialize)]
+pub struct RoleOperators {
+    #[serde(flatten)]
+    pub roles: HashMap<String, OperatorPolicy>,
+}
+
+impl ConfigElement for RoleOperators {
+    const KEY: &'static str = "operators.roles";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Odm {
     #[serde(deserialize_with = "deserialize_base_url")]
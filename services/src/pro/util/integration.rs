@@ -0,0 +1,233 @@
+//! Fixtures for integration tests that run the full service against a Postgres database.
+//!
+//! Each test gets its own temporary schema that is dropped afterwards, so tests can run in parallel
+//! against the same database. The connection is configured by the `[postgres]` section of the
+//! settings, e.g., a Postgres container started by `services/tests/docker-compose.yml`.
+
+use crate::datasets::listing::Provenance;
+use crate::datasets::storage::{AddDataset, DatasetDefinition, DatasetStore, MetaDataDefinition};
+use crate::pro::contexts::{PostgresContext, ProContext};
+use crate::pro::users::UserSession;
+use crate::pro::util::tests::{create_session_helper, send_pro_test_request};
+use crate::util::config::{get_config_element, Postgres};
+use crate::util::user_input::UserInput;
+use crate::util::IdResponse;
+use crate::workflows::workflow::{Workflow, WorkflowId};
+use actix_web::dev::ServiceResponse;
+use actix_web::{http::header, test};
+use actix_web_httpauth::headers::authorization::Bearer;
+use bb8_postgres::bb8::ManageConnection;
+use bb8_postgres::tokio_postgres::{self, NoTls};
+use bb8_postgres::PostgresConnectionManager;
+use futures::Future;
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_datatypes::test_data;
+use geoengine_datatypes::util::test::TestDefault;
+use geoengine_operators::engine::{StaticMetaData, VectorResultDescriptor};
+use geoengine_operators::source::{OgrSourceDataset, OgrSourceDatasetTimeType, OgrSourceErrorSpec};
+use geoengine_operators::util::gdal::create_ndvi_meta_data;
+use tokio::runtime::Handle;
+use uuid::Uuid;
+
+/// A temporary schema in the configured Postgres database
+pub struct TestDatabase {
+    pub pg_config: tokio_postgres::Config,
+    pub schema: String,
+}
+
+impl TestDatabase {
+    /// Creates a new schema with a random name and returns a config that uses it as `search_path`
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn create() -> Self {
+        let db_config = get_config_element::<Postgres>().unwrap();
+        let schema = format!("geoengine_it_{}", Uuid::new_v4().simple());
+
+        let mut pg_config = tokio_postgres::Config::new();
+        pg_config
+            .user(&db_config.user)
+            .password(&db_config.password)
+            .host(&db_config.host)
+            .port(db_config.port)
+            .dbname(&db_config.database);
+
+        PostgresConnectionManager::new(pg_config.clone(), NoTls)
+            .connect()
+            .await
+            .unwrap()
+            .batch_execute(&format!("CREATE SCHEMA {schema};"))
+            .await
+            .unwrap();
+
+        pg_config.options(&format!("-c search_path={schema}"));
+
+        Self { pg_config, schema }
+    }
+
+    /// Drops the schema and all of its contents
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn drop_schema(self) {
+        PostgresConnectionManager::new(self.pg_config, NoTls)
+            .connect()
+            .await
+            .unwrap()
+            .batch_execute(&format!("DROP SCHEMA {} CASCADE;", self.schema))
+            .await
+            .unwrap();
+    }
+}
+
+/// The running service of an integration test together with a logged in user
+#[derive(Clone)]
+pub struct IntegrationApp {
+    pub ctx: PostgresContext<NoTls>,
+    pub session: UserSession,
+}
+
+impl IntegrationApp {
+    /// Sends a request on behalf of the user to an app with all routes of the service
+    pub async fn send(&self, req: test::TestRequest) -> ServiceResponse {
+        let req = req.append_header((
+            header::AUTHORIZATION,
+            Bearer::new(self.session.id.to_string()),
+        ));
+        send_pro_test_request(req, self.ctx.clone()).await
+    }
+
+    /// Adds a dataset that is owned by the user
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn add_dataset(&self, definition: DatasetDefinition) -> DatasetId {
+        self.ctx
+            .dataset_db_ref()
+            .add_dataset(
+                &self.session,
+                definition
+                    .properties
+                    .validated()
+                    .expect("valid dataset description"),
+                Box::new(definition.meta_data),
+            )
+            .await
+            .expect("dataset db access")
+            .into()
+    }
+
+    /// Registers a workflow through the API
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn register_workflow(&self, workflow: &Workflow) -> WorkflowId {
+        let res = self
+            .send(
+                test::TestRequest::post()
+                    .uri("/workflow")
+                    .set_json(workflow),
+            )
+            .await;
+
+        assert_eq!(res.status(), 200, "{:?}", test::read_body(res).await);
+
+        let response: IdResponse<WorkflowId> = test::read_body_json(res).await;
+        response.id
+    }
+}
+
+/// Runs `f` with a service that is backed by a temporary schema.
+/// The schema is dropped afterwards, even if `f` panics.
+///
+/// This requires a multi-threaded runtime, i.e., `#[tokio::test(flavor = "multi_thread")]`.
+#[allow(clippy::missing_panics_doc)]
+pub async fn with_integration_app<F, Fut>(f: F)
+where
+    F: FnOnce(IntegrationApp) -> Fut + std::panic::UnwindSafe + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let db = TestDatabase::create().await;
+
+    // catch all panics and clean up first…
+    let executed_fn = {
+        let pg_config = db.pg_config.clone();
+        std::panic::catch_unwind(move || {
+            tokio::task::block_in_place(move || {
+                Handle::current().block_on(async move {
+                    let ctx = PostgresContext::new_with_context_spec(
+                        pg_config,
+                        NoTls,
+                        TestDefault::test_default(),
+                        TestDefault::test_default(),
+                    )
+                    .await
+                    .unwrap();
+                    let session = create_session_helper(&ctx).await;
+
+                    f(IntegrationApp { ctx, session }).await;
+                });
+            });
+        })
+    };
+
+    db.drop_schema().await;
+
+    // then throw errors afterwards
+    if let Err(err) = executed_fn {
+        std::panic::resume_unwind(err);
+    }
+}
+
+/// The monthly MODIS NDVI raster of 2014 from the test data
+pub fn ndvi_dataset() -> DatasetDefinition {
+    DatasetDefinition {
+        properties: AddDataset {
+            id: None,
+            name: "NDVI".to_string(),
+            description: "NDVI data from MODIS".to_string(),
+            source_operator: "GdalSource".to_string(),
+            symbology: None,
+            provenance: Some(Provenance {
+                citation: "Sample Citation".to_owned(),
+                license: "Sample License".to_owned(),
+                uri: "http://example.org/".to_owned(),
+            }),
+            temporary: false,
+        },
+        meta_data: MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data()),
+    }
+}
+
+/// The Natural Earth ports from the test data
+pub fn ports_dataset() -> DatasetDefinition {
+    DatasetDefinition {
+        properties: AddDataset {
+            id: None,
+            name: "Ports".to_string(),
+            description: "Ports from Natural Earth".to_string(),
+            source_operator: "OgrSource".to_string(),
+            symbology: None,
+            provenance: None,
+            temporary: false,
+        },
+        meta_data: MetaDataDefinition::OgrMetaData(StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: test_data!("vector/data/ne_10m_ports/ne_10m_ports.shp").into(),
+                layer_name: "ne_10m_ports".to_string(),
+                data_type: Some(VectorDataType::MultiPoint),
+                time: OgrSourceDatasetTimeType::None,
+                default_geometry: None,
+                columns: None,
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                attribute_query: None,
+                encoding: None,
+            },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                columns: Default::default(),
+                time: None,
+                bbox: None,
+            },
+            phantom: Default::default(),
+        }),
+    }
+}
@@ -1,2 +1,5 @@
 pub mod config;
+#[cfg(feature = "integration-tests")]
+pub mod integration;
+pub mod operators;
 pub mod tests;
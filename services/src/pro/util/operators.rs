@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use config::ConfigError;
+use geoengine_operators::engine::OperatorPolicy;
+
+use crate::error::{Error, Result};
+use crate::pro::datasets::RoleId;
+use crate::pro::util::config::RoleOperators;
+use crate::util::config::get_config_element;
+use crate::util::operators::OPERATOR_POLICY;
+
+lazy_static::lazy_static! {
+    /// The operator policies of roles, configured by the `operators.roles` config,
+    /// which is checked at startup by [`ensure_role_operator_config`]
+    static ref ROLE_OPERATOR_POLICIES: HashMap<RoleId, OperatorPolicy> =
+        role_operator_policies_from_config()
+            .expect("the operators.roles config should be checked at startup");
+}
+
+/// Fails if the operator policies of roles are invalid, e.g., because of an invalid role id
+pub fn ensure_role_operator_config() -> Result<()> {
+    role_operator_policies_from_config()?;
+
+    Ok(())
+}
+
+fn role_operator_policies_from_config() -> Result<HashMap<RoleId, OperatorPolicy>> {
+    let roles = match get_config_element::<RoleOperators>() {
+        Ok(role_operators) => role_operators.roles,
+        // roles have no policies of their own unless the section exists
+        Err(Error::Config {
+            source: ConfigError::NotFound(_),
+        }) => return Ok(HashMap::new()),
+        Err(error) => return Err(error),
+    };
+
+    roles
+        .into_iter()
+        .map(|(role, policy)| Ok((RoleId::from_str(&role)?, policy)))
+        .collect()
+}
+
+/// The operator policies that apply to a session with the given roles.
+/// An operator is allowed if any of them allows it.
+pub fn operator_policies(roles: &[RoleId]) -> Vec<&'static OperatorPolicy> {
+    policies_of_roles(roles, &ROLE_OPERATOR_POLICIES, &OPERATOR_POLICY)
+}
+
+/// The policies of the roles replace the policy of the instance, so that roles can be more or less restricted
+fn policies_of_roles<'p>(
+    roles: &[RoleId],
+    role_policies: &'p HashMap<RoleId, OperatorPolicy>,
+    instance_policy: &'p OperatorPolicy,
+) -> Vec<&'p OperatorPolicy> {
+    let policies: Vec<&OperatorPolicy> = roles
+        .iter()
+        .filter_map(|role| role_policies.get(role))
+        .collect();
+
+    if policies.is_empty() {
+        vec![instance_policy]
+    } else {
+        policies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Identifier;
+    use std::collections::HashSet;
+
+    #[test]
+    fn it_replaces_the_instance_policy_by_role_policies() {
+        let instance_policy = OperatorPolicy {
+            allowlist: HashSet::new(),
+            denylist: ["OgrSource".to_string()].into(),
+        };
+
+        let internal_role = RoleId::new();
+        let internal_policy = OperatorPolicy::default();
+        let role_policies = [(internal_role, internal_policy.clone())].into();
+
+        assert_eq!(
+            policies_of_roles(&[RoleId::new()], &role_policies, &instance_policy),
+            vec![&instance_policy]
+        );
+        assert_eq!(
+            policies_of_roles(
+                &[RoleId::new(), internal_role],
+                &role_policies,
+                &instance_policy
+            ),
+            vec![&internal_policy]
+        );
+    }
+}
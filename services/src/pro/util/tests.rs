@@ -5,7 +5,7 @@ use crate::{
         contexts::ProContext,
         datasets::Role,
         projects::ProProjectDb,
-        users::{UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession},
+        users::{Tenant, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession},
     },
     projects::{CreateProject, ProjectDb, ProjectId, STRectangle},
     util::server::{configure_extractors, render_404, render_405},
@@ -56,6 +56,7 @@ pub fn create_random_user_session_helper() -> UserSession {
         project: None,
         view: None,
         roles: vec![user_id.into(), Role::user_role_id()],
+        tenant: Tenant::default_tenant_id(),
     }
 }
 
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::api::model::datatypes::DataId;
+use crate::contexts::Db;
+use crate::error::Result;
+use crate::pro::users::{TenantId, UserSession};
+use crate::projects::Symbology;
+use crate::util::user_input::Validated;
+use crate::workflows::registry::{HashMapRegistry, WorkflowRegistry};
+use crate::workflows::style_preset::{StylePreset, StylePresetName};
+use crate::workflows::template::{
+    WorkflowTemplate, WorkflowTemplateId, WorkflowTemplateListOptions, WorkflowTemplateListing,
+};
+use crate::workflows::workflow::{Workflow, WorkflowAlias, WorkflowId};
+
+/// A workflow registry that keeps a separate in-memory registry for each tenant
+#[derive(Default)]
+pub struct ProHashMapRegistry {
+    registries: Db<HashMap<TenantId, Arc<HashMapRegistry>>>,
+}
+
+impl ProHashMapRegistry {
+    async fn registry(&self, session: &UserSession) -> Arc<HashMapRegistry> {
+        if let Some(registry) = self.registries.read().await.get(&session.tenant) {
+            return registry.clone();
+        }
+
+        self.registries
+            .write()
+            .await
+            .entry(session.tenant)
+            .or_default()
+            .clone()
+    }
+}
+
+#[async_trait]
+impl WorkflowRegistry<UserSession> for ProHashMapRegistry {
+    async fn register(&self, session: &UserSession, workflow: Workflow) -> Result<WorkflowId> {
+        self.registry(session)
+            .await
+            .register(session, workflow)
+            .await
+    }
+
+    async fn load(&self, session: &UserSession, id: &WorkflowId) -> Result<Workflow> {
+        self.registry(session).await.load(session, id).await
+    }
+
+    async fn broken_workflows(&self) -> Result<Vec<(WorkflowId, String)>> {
+        // the workflows are kept deserialized in memory
+        Ok(vec![])
+    }
+
+    async fn register_transient(
+        &self,
+        session: &UserSession,
+        workflow: Workflow,
+    ) -> Result<WorkflowId> {
+        self.registry(session)
+            .await
+            .register_transient(session, workflow)
+            .await
+    }
+
+    async fn dependent_workflows(
+        &self,
+        session: &UserSession,
+        data_id: &DataId,
+    ) -> Result<Vec<WorkflowId>> {
+        self.registry(session)
+            .await
+            .dependent_workflows(session, data_id)
+            .await
+    }
+
+    async fn register_version(
+        &self,
+        session: &UserSession,
+        parent: &WorkflowId,
+        workflow: Workflow,
+    ) -> Result<WorkflowId> {
+        self.registry(session)
+            .await
+            .register_version(session, parent, workflow)
+            .await
+    }
+
+    async fn versions(&self, session: &UserSession, id: &WorkflowId) -> Result<Vec<WorkflowId>> {
+        self.registry(session).await.versions(session, id).await
+    }
+
+    async fn set_alias(
+        &self,
+        session: &UserSession,
+        alias: Validated<WorkflowAlias>,
+        id: &WorkflowId,
+    ) -> Result<()> {
+        self.registry(session)
+            .await
+            .set_alias(session, alias, id)
+            .await
+    }
+
+    async fn resolve_alias(
+        &self,
+        session: &UserSession,
+        alias: &WorkflowAlias,
+    ) -> Result<WorkflowId> {
+        self.registry(session)
+            .await
+            .resolve_alias(session, alias)
+            .await
+    }
+
+    async fn set_style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: Validated<StylePresetName>,
+        symbology: Symbology,
+    ) -> Result<()> {
+        self.registry(session)
+            .await
+            .set_style_preset(session, id, name, symbology)
+            .await
+    }
+
+    async fn style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<Symbology> {
+        self.registry(session)
+            .await
+            .style_preset(session, id, name)
+            .await
+    }
+
+    async fn list_style_presets(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+    ) -> Result<Vec<StylePreset>> {
+        self.registry(session)
+            .await
+            .list_style_presets(session, id)
+            .await
+    }
+
+    async fn remove_style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<()> {
+        self.registry(session)
+            .await
+            .remove_style_preset(session, id, name)
+            .await
+    }
+
+    async fn register_template(
+        &self,
+        session: &UserSession,
+        template: Validated<WorkflowTemplate>,
+    ) -> Result<WorkflowTemplateId> {
+        self.registry(session)
+            .await
+            .register_template(session, template)
+            .await
+    }
+
+    async fn load_template(
+        &self,
+        session: &UserSession,
+        id: &WorkflowTemplateId,
+    ) -> Result<WorkflowTemplate> {
+        self.registry(session)
+            .await
+            .load_template(session, id)
+            .await
+    }
+
+    async fn list_templates(
+        &self,
+        session: &UserSession,
+        options: Validated<WorkflowTemplateListOptions>,
+    ) -> Result<Vec<WorkflowTemplateListing>> {
+        self.registry(session)
+            .await
+            .list_templates(session, options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::MockableSession;
+    use crate::pro::users::Tenant;
+    use crate::util::Identifier;
+    use geoengine_operators::engine::{TypedOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+
+    #[tokio::test]
+    async fn it_isolates_tenants() {
+        let registry = ProHashMapRegistry::default();
+
+        let session = UserSession::mock();
+        let other_session = UserSession {
+            tenant: TenantId::new(),
+            ..UserSession::mock()
+        };
+        assert_eq!(session.tenant, Tenant::default_tenant_id());
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(0.0, 0.1).into()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let id = registry.register(&session, workflow.clone()).await.unwrap();
+
+        assert_eq!(registry.load(&session, &id).await.unwrap(), workflow);
+        assert!(registry.load(&other_session, &id).await.is_err());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod hashmap_workflow_registry;
 #[cfg(feature = "postgres")]
 pub mod postgres_workflow_registry;
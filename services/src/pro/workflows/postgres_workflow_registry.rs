@@ -1,6 +1,21 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::api::model::datatypes::DataId;
+use crate::contexts::Db;
+use crate::datasets::listing::OrderBy;
+use crate::error;
 use crate::error::Result;
-use crate::workflows::workflow::{Workflow, WorkflowId};
-use crate::{error, workflows::registry::WorkflowRegistry};
+use crate::pro::users::{TenantId, UserSession};
+use crate::projects::Symbology;
+use crate::util::user_input::Validated;
+use crate::util::Identifier;
+use crate::workflows::registry::{TransientWorkflows, WorkflowRegistry};
+use crate::workflows::style_preset::{StylePreset, StylePresetName};
+use crate::workflows::template::{
+    WorkflowTemplate, WorkflowTemplateId, WorkflowTemplateListOptions, WorkflowTemplateListing,
+};
+use crate::workflows::workflow::{Workflow, WorkflowAlias, WorkflowId};
 use async_trait::async_trait;
 use bb8_postgres::{
     bb8::Pool, tokio_postgres::tls::MakeTlsConnect, tokio_postgres::tls::TlsConnect,
@@ -16,6 +31,7 @@ where
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
     conn_pool: Pool<PostgresConnectionManager<Tls>>,
+    transient: Db<HashMap<TenantId, Arc<TransientWorkflows>>>,
 }
 
 impl<Tls> PostgresWorkflowRegistry<Tls>
@@ -26,23 +42,42 @@ where
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
     pub fn new(conn_pool: Pool<PostgresConnectionManager<Tls>>) -> Self {
-        Self { conn_pool }
+        Self {
+            conn_pool,
+            transient: Db::default(),
+        }
+    }
+
+    /// The transient workflows of the tenant of the `session`
+    async fn transient(&self, session: &UserSession) -> Arc<TransientWorkflows> {
+        if let Some(transient) = self.transient.read().await.get(&session.tenant) {
+            return transient.clone();
+        }
+
+        self.transient
+            .write()
+            .await
+            .entry(session.tenant)
+            .or_default()
+            .clone()
     }
 }
 
 #[async_trait]
-impl<Tls> WorkflowRegistry for PostgresWorkflowRegistry<Tls>
+impl<Tls> WorkflowRegistry<UserSession> for PostgresWorkflowRegistry<Tls>
 where
     Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
     <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
     <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
-    async fn register(&self, workflow: Workflow) -> Result<WorkflowId> {
+    async fn register(&self, session: &UserSession, workflow: Workflow) -> Result<WorkflowId> {
+        let data_ids = self.resolved_data_ids(session, &workflow).await?;
+
         let conn = self.conn_pool.get().await?;
         let stmt = conn
             .prepare(
-                "INSERT INTO workflows (id, workflow) VALUES ($1, $2) 
+                "INSERT INTO workflows (tenant_id, id, workflow) VALUES ($1, $2, $3) 
             ON CONFLICT DO NOTHING;",
             )
             .await?;
@@ -52,28 +87,440 @@ where
         conn.execute(
             &stmt,
             &[
+                &session.tenant,
                 &workflow_id,
                 &serde_json::to_value(&workflow).context(error::SerdeJson)?,
             ],
         )
         .await?;
 
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_data (tenant_id, workflow_id, data_id) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING;",
+            )
+            .await?;
+
+        for data_id in data_ids {
+            conn.execute(
+                &stmt,
+                &[
+                    &session.tenant,
+                    &workflow_id,
+                    &serde_json::to_value(&data_id).context(error::SerdeJson)?,
+                ],
+            )
+            .await?;
+        }
+
         Ok(workflow_id)
     }
 
-    async fn load(&self, id: &WorkflowId) -> Result<Workflow> {
+    async fn load(&self, session: &UserSession, id: &WorkflowId) -> Result<Workflow> {
         // TODO: authorization
         let conn = self.conn_pool.get().await?;
         let stmt = conn
-            .prepare("SELECT workflow FROM workflows WHERE id = $1")
+            .prepare("SELECT workflow FROM workflows WHERE tenant_id = $1 AND id = $2")
             .await?;
 
-        let row = conn.query(&stmt, &[&id]).await?;
+        let row = conn.query(&stmt, &[&session.tenant, &id]).await?;
 
         if row.is_empty() {
-            return Err(error::Error::NoWorkflowForGivenId);
+            return self
+                .transient(session)
+                .await
+                .get(id)
+                .await
+                .ok_or(error::Error::NoWorkflowForGivenId);
         }
 
         Ok(serde_json::from_value(row[0].get(0)).context(error::SerdeJson)?)
     }
+
+    async fn broken_workflows(&self) -> Result<Vec<(WorkflowId, String)>> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn.prepare("SELECT id, workflow FROM workflows").await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                serde_json::from_value::<Workflow>(row.get(1))
+                    .err()
+                    .map(|error| (row.get(0), error.to_string()))
+            })
+            .collect())
+    }
+
+    async fn register_transient(
+        &self,
+        session: &UserSession,
+        workflow: Workflow,
+    ) -> Result<WorkflowId> {
+        self.transient(session).await.insert(workflow).await
+    }
+
+    async fn dependent_workflows(
+        &self,
+        session: &UserSession,
+        data_id: &DataId,
+    ) -> Result<Vec<WorkflowId>> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT workflow_id FROM workflow_data WHERE tenant_id = $1 AND data_id = $2")
+            .await?;
+
+        let rows = conn
+            .query(
+                &stmt,
+                &[
+                    &session.tenant,
+                    &serde_json::to_value(data_id).context(error::SerdeJson)?,
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn register_version(
+        &self,
+        session: &UserSession,
+        parent: &WorkflowId,
+        workflow: Workflow,
+    ) -> Result<WorkflowId> {
+        // fails if the parent does not exist
+        let parent_versions = self.versions(session, parent).await?;
+
+        let workflow_id = self.register(session, workflow).await?;
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        // a workflow has exactly one parent and the chain must not contain cycles
+        if !parent_versions.contains(&workflow_id) {
+            let stmt = tx
+                .prepare(
+                    "INSERT INTO workflow_versions (tenant_id, workflow_id, parent_id) VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING;",
+                )
+                .await?;
+
+            tx.execute(&stmt, &[&session.tenant, &workflow_id, parent])
+                .await?;
+        }
+
+        let stmt = tx
+            .prepare(
+                "UPDATE workflow_aliases SET workflow_id = $2 WHERE tenant_id = $1 AND workflow_id = $3;",
+            )
+            .await?;
+
+        tx.execute(&stmt, &[&session.tenant, &workflow_id, parent])
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(workflow_id)
+    }
+
+    async fn versions(&self, session: &UserSession, id: &WorkflowId) -> Result<Vec<WorkflowId>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT TRUE FROM workflows WHERE tenant_id = $1 AND id = $2")
+            .await?;
+
+        if conn.query(&stmt, &[&session.tenant, &id]).await?.is_empty() {
+            return Err(error::Error::NoWorkflowForGivenId);
+        }
+
+        let stmt = conn
+            .prepare(
+                "
+                WITH RECURSIVE chain (id, depth) AS (
+                    SELECT $2::uuid, 0
+                    UNION ALL
+                    SELECT v.parent_id, c.depth + 1
+                    FROM chain c JOIN workflow_versions v ON (v.tenant_id = $1 AND v.workflow_id = c.id)
+                )
+                SELECT id FROM chain ORDER BY depth ASC;",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[&session.tenant, &id]).await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn set_alias(
+        &self,
+        session: &UserSession,
+        alias: Validated<WorkflowAlias>,
+        id: &WorkflowId,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT TRUE FROM workflows WHERE tenant_id = $1 AND id = $2")
+            .await?;
+
+        if conn.query(&stmt, &[&session.tenant, &id]).await?.is_empty() {
+            return Err(error::Error::NoWorkflowForGivenId);
+        }
+
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_aliases (tenant_id, alias, workflow_id) VALUES ($1, $2, $3)
+            ON CONFLICT (tenant_id, alias) DO UPDATE SET workflow_id = EXCLUDED.workflow_id;",
+            )
+            .await?;
+
+        conn.execute(&stmt, &[&session.tenant, &alias.user_input.as_ref(), &id])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn resolve_alias(
+        &self,
+        session: &UserSession,
+        alias: &WorkflowAlias,
+    ) -> Result<WorkflowId> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT workflow_id FROM workflow_aliases WHERE tenant_id = $1 AND alias = $2")
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&session.tenant, &alias.as_ref()])
+            .await?
+            .ok_or_else(|| error::Error::UnknownWorkflowAlias {
+                alias: alias.to_string(),
+            })?;
+
+        Ok(row.get(0))
+    }
+
+    async fn set_style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: Validated<StylePresetName>,
+        symbology: Symbology,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT TRUE FROM workflows WHERE tenant_id = $1 AND id = $2")
+            .await?;
+
+        if conn.query(&stmt, &[&session.tenant, &id]).await?.is_empty() {
+            return Err(error::Error::NoWorkflowForGivenId);
+        }
+
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_style_presets (tenant_id, workflow_id, name, symbology) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id, workflow_id, name) DO UPDATE SET symbology = EXCLUDED.symbology;",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &session.tenant,
+                &id,
+                &name.user_input.as_ref(),
+                &serde_json::to_value(&symbology).context(error::SerdeJson)?,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<Symbology> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "SELECT symbology FROM workflow_style_presets WHERE tenant_id = $1 AND workflow_id = $2 AND name = $3",
+            )
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&session.tenant, &id, &name.as_ref()])
+            .await?
+            .ok_or_else(|| error::Error::UnknownStylePreset {
+                workflow: *id,
+                name: name.to_string(),
+            })?;
+
+        Ok(serde_json::from_value(row.get(0)).context(error::SerdeJson)?)
+    }
+
+    async fn list_style_presets(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+    ) -> Result<Vec<StylePreset>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT TRUE FROM workflows WHERE tenant_id = $1 AND id = $2")
+            .await?;
+
+        if conn.query(&stmt, &[&session.tenant, &id]).await?.is_empty() {
+            return Err(error::Error::NoWorkflowForGivenId);
+        }
+
+        let stmt = conn
+            .prepare(
+                "SELECT name, symbology FROM workflow_style_presets WHERE tenant_id = $1 AND workflow_id = $2 ORDER BY name",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[&session.tenant, &id]).await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(StylePreset {
+                    name: StylePresetName::from(row.get::<_, &str>(0)),
+                    symbology: serde_json::from_value(row.get(1)).context(error::SerdeJson)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn remove_style_preset(
+        &self,
+        session: &UserSession,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "DELETE FROM workflow_style_presets WHERE tenant_id = $1 AND workflow_id = $2 AND name = $3",
+            )
+            .await?;
+
+        let deleted = conn
+            .execute(&stmt, &[&session.tenant, &id, &name.as_ref()])
+            .await?;
+
+        if deleted == 0 {
+            return Err(error::Error::UnknownStylePreset {
+                workflow: *id,
+                name: name.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn register_template(
+        &self,
+        session: &UserSession,
+        template: Validated<WorkflowTemplate>,
+    ) -> Result<WorkflowTemplateId> {
+        let template = template.user_input;
+
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_templates (id, tenant_id, name, description, template) VALUES ($1, $2, $3, $4, $5);",
+            )
+            .await?;
+
+        let id = WorkflowTemplateId::new();
+
+        conn.execute(
+            &stmt,
+            &[
+                &id,
+                &session.tenant,
+                &template.name,
+                &template.description,
+                &serde_json::to_value(&template).context(error::SerdeJson)?,
+            ],
+        )
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn load_template(
+        &self,
+        session: &UserSession,
+        id: &WorkflowTemplateId,
+    ) -> Result<WorkflowTemplate> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT template FROM workflow_templates WHERE id = $1 AND tenant_id = $2")
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&id, &session.tenant])
+            .await?
+            .ok_or(error::Error::NoWorkflowTemplateForGivenId)?;
+
+        Ok(serde_json::from_value(row.get(0)).context(error::SerdeJson)?)
+    }
+
+    async fn list_templates(
+        &self,
+        session: &UserSession,
+        options: Validated<WorkflowTemplateListOptions>,
+    ) -> Result<Vec<WorkflowTemplateListing>> {
+        let options = options.user_input;
+
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(&format!(
+                "
+                SELECT id, template
+                FROM workflow_templates
+                WHERE tenant_id = $1 AND (
+                    $2::text IS NULL OR strpos(name, $2) > 0 OR strpos(description, $2) > 0
+                )
+                ORDER BY name {}
+                LIMIT $3
+                OFFSET $4;",
+                match options.order {
+                    OrderBy::NameAsc => "ASC",
+                    OrderBy::NameDesc => "DESC",
+                }
+            ))
+            .await?;
+
+        let rows = conn
+            .query(
+                &stmt,
+                &[
+                    &session.tenant,
+                    &options.filter,
+                    &i64::from(options.limit),
+                    &i64::from(options.offset),
+                ],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let template: WorkflowTemplate =
+                    serde_json::from_value(row.get(1)).context(error::SerdeJson)?;
+                Ok(template.listing(row.get(0)))
+            })
+            .collect()
+    }
 }
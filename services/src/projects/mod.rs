@@ -3,10 +3,11 @@ mod project;
 mod projectdb;
 
 pub use project::{
-    ColorParam, CreateProject, DerivedColor, DerivedNumber, Layer, LayerType, LayerUpdate,
-    LayerVisibility, LineSymbology, NumberParam, OrderBy, Plot, PlotUpdate, PointSymbology,
-    PolygonSymbology, Project, ProjectFilter, ProjectId, ProjectListOptions, ProjectListing,
-    ProjectVersion, ProjectVersionId, RasterSymbology, STRectangle, StrokeParam, Symbology,
-    TextSymbology, UpdateProject,
+    CategorizedColor, CategoryValue, ColorCategory, ColorClass, ColorParam, CreateProject,
+    DerivedColor, DerivedNumber, GraduatedColor, GraduatedNumber, Layer, LayerType, LayerUpdate,
+    LayerVisibility, LineSymbology, NumberClass, NumberParam, OrderBy, Plot, PlotUpdate,
+    PointSymbology, PolygonSymbology, Project, ProjectFilter, ProjectId, ProjectListOptions,
+    ProjectListing, ProjectVersion, ProjectVersionId, RasterSymbology, STRectangle, StrokeParam,
+    Symbology, TextSymbology, UpdateProject,
 };
 pub use projectdb::ProjectDb;
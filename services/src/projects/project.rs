@@ -23,17 +23,19 @@ use geoengine_operators::string_token;
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 identifier!(ProjectId);
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Project {
     pub id: ProjectId,
     pub version: ProjectVersion,
     pub name: String,
     pub description: String,
+    // the schema name `Layer` is already taken by the layers of the layer collections
+    #[schema(value_type = Vec<ProjectLayer>)]
     pub layers: Vec<Layer>,
     pub plots: Vec<Plot>,
     pub bounds: STRectangle,
@@ -219,7 +221,7 @@ impl TemporalBounded for STRectangle {
 }
 
 // TODO: split into Raster and VectorLayer like in frontend?
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Layer {
     // TODO: check that workflow/operator output type fits to the type of LayerInfo
     // TODO: LayerId?
@@ -327,6 +329,7 @@ pub struct PolygonSymbology {
 pub enum NumberParam {
     Static { value: usize },
     Derived(DerivedNumber),
+    Graduated(GraduatedNumber),
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, ToSchema)]
@@ -346,11 +349,47 @@ pub struct StrokeParam {
 
 impl Eq for DerivedNumber {}
 
+/// Derives a number, e.g., the radius of points, from classes of a numeric attribute.
+/// A feature gets the value of the class with the greatest lower bound that is less than or equal to its value.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraduatedNumber {
+    pub attribute: String,
+    /// ordered by ascending lower bounds
+    pub classes: Vec<NumberClass>,
+    /// value for missing values and values below the first class
+    pub default_value: usize,
+}
+
+impl GraduatedNumber {
+    pub fn value(&self, value: Option<f64>) -> usize {
+        value
+            .and_then(|value| {
+                self.classes
+                    .iter()
+                    .rev()
+                    .find(|class| class.lower_bound <= value)
+            })
+            .map_or(self.default_value, |class| class.value)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberClass {
+    pub lower_bound: f64,
+    pub value: usize,
+}
+
+impl Eq for NumberClass {}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum ColorParam {
     Static { color: RgbaColor },
     Derived(DerivedColor),
+    Graduated(GraduatedColor),
+    Categorized(CategorizedColor),
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
@@ -359,7 +398,76 @@ pub struct DerivedColor {
     pub colorizer: Colorizer,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+/// Colors features by classes of a numeric attribute.
+/// A feature gets the color of the class with the greatest lower bound that is less than or equal to its value.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraduatedColor {
+    pub attribute: String,
+    /// ordered by ascending lower bounds
+    pub classes: Vec<ColorClass>,
+    /// color for missing values and values below the first class
+    pub default_color: RgbaColor,
+}
+
+impl GraduatedColor {
+    pub fn color(&self, value: Option<f64>) -> RgbaColor {
+        value
+            .and_then(|value| {
+                self.classes
+                    .iter()
+                    .rev()
+                    .find(|class| class.lower_bound <= value)
+            })
+            .map_or(self.default_color, |class| class.color)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorClass {
+    pub lower_bound: f64,
+    pub color: RgbaColor,
+}
+
+impl Eq for ColorClass {}
+
+/// Colors features by the distinct values of a text or integer attribute
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorizedColor {
+    pub attribute: String,
+    pub categories: Vec<ColorCategory>,
+    /// color for missing values and values without a category
+    pub default_color: RgbaColor,
+}
+
+impl CategorizedColor {
+    pub fn color(&self, value: Option<&CategoryValue>) -> RgbaColor {
+        value
+            .and_then(|value| {
+                self.categories
+                    .iter()
+                    .find(|category| category.value == *value)
+            })
+            .map_or(self.default_color, |category| category.color)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ColorCategory {
+    pub value: CategoryValue,
+    pub color: RgbaColor,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(untagged)]
+pub enum CategoryValue {
+    Int(i64),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema)]
 #[cfg_attr(feature = "postgres", derive(ToSql, FromSql))]
 pub struct LayerVisibility {
     pub data: bool,
@@ -375,13 +483,13 @@ impl Default for LayerVisibility {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct Plot {
     pub workflow: WorkflowId,
     pub name: String,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema)]
 pub enum OrderBy {
     DateAsc,
     DateDesc,
@@ -400,8 +508,16 @@ impl OrderBy {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "id": "df4ad02e-0d61-4e29-90eb-dc1259c1f5b9",
+    "name": "Test",
+    "description": "Foo",
+    "layerNames": [],
+    "plotNames": [],
+    "changed": "2021-04-26T14:03:51.984537900Z"
+}))]
 pub struct ProjectListing {
     pub id: ProjectId,
     pub name: String,
@@ -424,7 +540,7 @@ impl From<&Project> for ProjectListing {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema)]
 pub enum ProjectFilter {
     Name { term: String },
     Description { term: String },
@@ -437,8 +553,21 @@ impl Default for ProjectFilter {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "name": "Test",
+    "description": "Foo",
+    "bounds": {
+        "spatialReference": "EPSG:4326",
+        "boundingBox": {
+            "lowerLeftCoordinate": { "x": 0.0, "y": 0.0 },
+            "upperRightCoordinate": { "x": 1.0, "y": 1.0 }
+        },
+        "timeInterval": { "start": 0, "end": 1 }
+    },
+    "timeStep": { "step": 1, "granularity": "months" }
+}))]
 pub struct CreateProject {
     pub name: String,
     pub description: String,
@@ -457,13 +586,22 @@ impl UserInput for CreateProject {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "id": "df4ad02e-0d61-4e29-90eb-dc1259c1f5b9",
+    "name": "TestUpdate",
+    "layers": ["none", "delete"]
+}))]
 pub struct UpdateProject {
     pub id: ProjectId,
     pub name: Option<String>,
     pub description: Option<String>,
+    /// per layer either `"none"`, `"delete"` or the updated layer
+    #[schema(value_type = Option<Vec<Object>>)]
     pub layers: Option<Vec<LayerUpdate>>,
+    /// per plot either `"none"`, `"delete"` or the updated plot
+    #[schema(value_type = Option<Vec<Object>>)]
     pub plots: Option<Vec<PlotUpdate>>,
     pub bounds: Option<STRectangle>,
     pub time_step: Option<TimeStep>,
@@ -499,12 +637,16 @@ impl UserInput for UpdateProject {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema, IntoParams)]
 pub struct ProjectListOptions {
     #[serde(default)]
+    #[param(value_type = Option<ProjectFilter>)]
     pub filter: ProjectFilter,
+    #[param(example = "NameAsc")]
     pub order: OrderBy,
+    #[param(example = 0)]
     pub offset: u32,
+    #[param(example = 2)]
     pub limit: u32,
 }
 
@@ -521,7 +663,7 @@ impl UserInput for ProjectListOptions {
 
 identifier!(ProjectVersionId);
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy, ToSchema)]
 pub struct ProjectVersion {
     pub id: ProjectVersionId,
     pub changed: DateTime,
@@ -732,6 +874,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn attribute_driven_styles() {
+        let fill_color: ColorParam = serde_json::from_value(json!({
+            "type": "graduated",
+            "attribute": "population",
+            "classes": [
+                {"lowerBound": 0.0, "color": [0, 0, 255, 255]},
+                {"lowerBound": 1000.0, "color": [255, 0, 0, 255]}
+            ],
+            "defaultColor": [0, 0, 0, 0]
+        }))
+        .unwrap();
+
+        let graduated = if let ColorParam::Graduated(graduated) = fill_color {
+            graduated
+        } else {
+            panic!("expected graduated colors");
+        };
+        assert_eq!(graduated.color(Some(500.)), RgbaColor::new(0, 0, 255, 255));
+        assert_eq!(graduated.color(Some(1000.)), RgbaColor::new(255, 0, 0, 255));
+        assert_eq!(graduated.color(Some(-1.)), RgbaColor::transparent());
+        assert_eq!(graduated.color(None), RgbaColor::transparent());
+
+        let categorized = CategorizedColor {
+            attribute: "kind".to_owned(),
+            categories: vec![
+                ColorCategory {
+                    value: CategoryValue::Text("forest".to_owned()),
+                    color: RgbaColor::new(0, 255, 0, 255),
+                },
+                ColorCategory {
+                    value: CategoryValue::Int(1),
+                    color: RgbaColor::new(0, 0, 255, 255),
+                },
+            ],
+            default_color: RgbaColor::black(),
+        };
+        assert_eq!(
+            serde_json::to_value(&categorized).unwrap()["categories"][1]["value"],
+            json!(1)
+        );
+        assert_eq!(
+            categorized.color(Some(&CategoryValue::Text("forest".to_owned()))),
+            RgbaColor::new(0, 255, 0, 255)
+        );
+        assert_eq!(
+            categorized.color(Some(&CategoryValue::Int(2))),
+            RgbaColor::black()
+        );
+
+        let radius = GraduatedNumber {
+            attribute: "population".to_owned(),
+            classes: vec![
+                NumberClass {
+                    lower_bound: 0.,
+                    value: 5,
+                },
+                NumberClass {
+                    lower_bound: 1000.,
+                    value: 10,
+                },
+            ],
+            default_value: 1,
+        };
+        assert_eq!(radius.value(Some(2000.)), 10);
+        assert_eq!(radius.value(None), 1);
+    }
+
     #[test]
     fn serialize_derived_number_param() {
         assert_eq!(
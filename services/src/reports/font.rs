@@ -0,0 +1,118 @@
+use image::{Rgba, RgbaImage};
+
+/// The width of a glyph in font pixels
+const GLYPH_WIDTH: u32 = 3;
+/// The height of a glyph in font pixels
+pub const GLYPH_HEIGHT: u32 = 5;
+
+/// A minimal bitmap font with 3x5 pixel glyphs for labels of reports.
+/// Each row is a bit mask whose most significant of the three bits is the leftmost pixel.
+/// Lowercase letters are drawn as uppercase ones and unknown characters as `?`.
+fn glyph(character: char) -> [u8; 5] {
+    match character.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '>' => [0b100, 0b010, 0b001, 0b010, 0b100],
+        '<' => [0b001, 0b010, 0b100, 0b010, 0b001],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+/// The width of `text` in image pixels if it is drawn with `scale`
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    let characters = text.chars().count() as u32;
+    (characters * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// Draws `text` with its upper left corner at (`x`, `y`).
+/// Each font pixel becomes a square of `scale` x `scale` image pixels; parts outside of the image are clipped.
+pub fn draw_text(image: &mut RgbaImage, text: &str, x: u32, y: u32, scale: u32, color: Rgba<u8>) {
+    for (i, character) in text.chars().enumerate() {
+        let glyph_x = x + i as u32 * (GLYPH_WIDTH + 1) * scale;
+
+        for (row, mask) in glyph(character).into_iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if mask & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let pixel_x = glyph_x + column * scale + dx;
+                        let pixel_y = y + row as u32 * scale + dy;
+
+                        if pixel_x < image.width() && pixel_y < image.height() {
+                            image.put_pixel(pixel_x, pixel_y, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_draws_text() {
+        let mut image = RgbaImage::new(text_width("1-", 2), GLYPH_HEIGHT * 2);
+        assert_eq!(image.width(), 14);
+
+        draw_text(&mut image, "1-", 0, 0, 2, Rgba([0, 0, 0, 255]));
+
+        // top of the `1`
+        assert_eq!(image.get_pixel(2, 0).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        // middle of the `-`
+        assert_eq!(image.get_pixel(8, 4).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(8, 0).0, [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,639 @@
+use std::io::Cursor;
+
+use geoengine_datatypes::operations::image::{Colorizer, RgbaColor};
+use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, ImageOutputFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use utoipa::ToSchema;
+
+use crate::error::{self, Result};
+use crate::projects::{ColorParam, Layer, STRectangle, Symbology};
+use crate::workflows::workflow::WorkflowId;
+
+use self::font::{draw_text, text_width, GLYPH_HEIGHT};
+
+mod font;
+mod pdf;
+
+const MARGIN: u32 = 20;
+const TITLE_SCALE: u32 = 4;
+const TEXT_SCALE: u32 = 2;
+const LINE_HEIGHT: u32 = GLYPH_HEIGHT * TEXT_SCALE + 6;
+const LEGEND_WIDTH: u32 = 240;
+const SYMBOL_SIZE: u32 = GLYPH_HEIGHT * TEXT_SCALE;
+const GRADIENT_WIDTH: u32 = 160;
+const GRADIENT_STEPS: u32 = 64;
+const SCALE_BAR_HEIGHT: u32 = 6;
+const MAX_PLOT_HEIGHT: u32 = 300;
+/// The maximum width and height of the map, which bounds the size of the page
+const MAX_MAP_SIZE: u32 = 4096;
+
+const PAGE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Parameters for composing a report of a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "width": 800,
+    "height": 600,
+    "plots": [
+        {
+            "type": "image",
+            "data": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAA..."
+        }
+    ],
+    "format": "application/pdf"
+}))]
+pub struct ReportRequest {
+    /// the extent of the map, defaults to the bounds of the project
+    pub bounds: Option<STRectangle>,
+    /// the size of the map in pixels
+    #[serde(default = "default_map_width")]
+    pub width: u32,
+    #[serde(default = "default_map_height")]
+    pub height: u32,
+    #[serde(default)]
+    pub plots: Vec<ReportPlot>,
+    pub format: ReportFormat,
+}
+
+impl ReportRequest {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            (1..=MAX_MAP_SIZE).contains(&self.width) && (1..=MAX_MAP_SIZE).contains(&self.height),
+            error::ReportMapSizeInvalid {
+                width: self.width,
+                height: self.height,
+                max_size: MAX_MAP_SIZE,
+            }
+        );
+
+        Ok(())
+    }
+}
+
+fn default_map_width() -> u32 {
+    800
+}
+
+fn default_map_height() -> u32 {
+    600
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ReportFormat {
+    #[serde(rename = "image/png")]
+    Png,
+    #[serde(rename = "application/pdf")]
+    Pdf,
+}
+
+/// A plot of the report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ReportPlot {
+    /// A plot workflow that outputs an image, queried with the extent of the map
+    Workflow { workflow: WorkflowId },
+    /// A plot that was already rendered by the client, e.g., a Vega chart,
+    /// as base64-encoded PNG with or without a `data:image/png;base64,` prefix
+    Image { data: String },
+}
+
+impl ReportPlot {
+    /// Decodes the PNG data of an [`ReportPlot::Image`]
+    pub fn decode_image(data: &str) -> Result<RgbaImage> {
+        let data = data.strip_prefix("data:image/png;base64,").unwrap_or(data);
+        let bytes = base64::decode(data).map_err(|source| error::Error::ReportPlotInvalid {
+            reason: source.to_string(),
+        })?;
+
+        decode_png(&bytes)
+    }
+}
+
+pub(crate) fn decode_png(bytes: &[u8]) -> Result<RgbaImage> {
+    Ok(image::load_from_memory_with_format(bytes, ImageFormat::Png)
+        .map_err(|source| error::Error::ReportPlotInvalid {
+            reason: source.to_string(),
+        })?
+        .into_rgba8())
+}
+
+/// The legend of a project layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+    pub name: String,
+    pub symbols: Vec<LegendSymbol>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegendSymbol {
+    /// A color ramp between two labeled values
+    Gradient {
+        colors: Vec<RgbaColor>,
+        min_label: String,
+        max_label: String,
+    },
+    /// A single colored box
+    Swatch { color: RgbaColor, label: String },
+}
+
+impl LegendEntry {
+    pub fn from_layer(layer: &Layer) -> Result<Self> {
+        let symbols = match &layer.symbology {
+            Symbology::Raster(symbology) => {
+                colorizer_symbols(&symbology.colorizer.clone().try_into()?)
+            }
+            Symbology::Point(symbology) => color_param_symbols(&symbology.fill_color)?,
+            Symbology::Line(symbology) => color_param_symbols(&symbology.stroke.color)?,
+            Symbology::Polygon(symbology) => color_param_symbols(&symbology.fill_color)?,
+        };
+
+        Ok(Self {
+            name: layer.name.clone(),
+            symbols,
+        })
+    }
+
+    fn height(&self) -> u32 {
+        let symbols: u32 = self
+            .symbols
+            .iter()
+            .map(|symbol| match symbol {
+                LegendSymbol::Gradient { .. } => SYMBOL_SIZE + LINE_HEIGHT,
+                LegendSymbol::Swatch { .. } => LINE_HEIGHT,
+            })
+            .sum();
+
+        LINE_HEIGHT + symbols + LINE_HEIGHT / 2
+    }
+}
+
+fn colorizer_symbols(colorizer: &Colorizer) -> Vec<LegendSymbol> {
+    match colorizer {
+        Colorizer::LinearGradient { .. } | Colorizer::LogarithmicGradient { .. } => {
+            let (min, max) = (colorizer.min_value(), colorizer.max_value());
+            let color_mapper = colorizer.create_color_mapper();
+
+            vec![LegendSymbol::Gradient {
+                colors: (0..GRADIENT_STEPS)
+                    .map(|step| {
+                        let value =
+                            min + (max - min) * f64::from(step) / f64::from(GRADIENT_STEPS - 1);
+                        color_mapper.call(value)
+                    })
+                    .collect(),
+                min_label: format_number(min),
+                max_label: format_number(max),
+            }]
+        }
+        Colorizer::Palette { colors, .. } => {
+            let mut colors: Vec<_> = colors.clone().into_inner().into_iter().collect();
+            colors.sort_by_key(|(value, _)| *value);
+
+            colors
+                .into_iter()
+                .map(|(value, color)| LegendSymbol::Swatch {
+                    color,
+                    label: format_number(*value),
+                })
+                .collect()
+        }
+        Colorizer::Rgba => vec![],
+    }
+}
+
+fn color_param_symbols(param: &ColorParam) -> Result<Vec<LegendSymbol>> {
+    Ok(match param {
+        ColorParam::Static { color } => vec![LegendSymbol::Swatch {
+            color: *color,
+            label: String::new(),
+        }],
+        ColorParam::Derived(derived) => colorizer_symbols(&derived.colorizer.clone().try_into()?),
+        ColorParam::Graduated(graduated) => graduated
+            .classes
+            .iter()
+            .map(|class| LegendSymbol::Swatch {
+                color: class.color,
+                label: format!(">= {}", format_number(class.lower_bound)),
+            })
+            .collect(),
+        ColorParam::Categorized(categorized) => categorized
+            .categories
+            .iter()
+            .map(|category| LegendSymbol::Swatch {
+                color: category.color,
+                label: match &category.value {
+                    crate::projects::CategoryValue::Int(value) => value.to_string(),
+                    crate::projects::CategoryValue::Text(value) => value.clone(),
+                },
+            })
+            .collect(),
+    })
+}
+
+fn format_number(value: f64) -> String {
+    if value != 0. && (value.abs() >= 1e6 || value.abs() < 1e-3) {
+        format!("{:.2e}", value)
+    } else {
+        let formatted = format!("{:.3}", value);
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// A bar of `length` pixels that represents `label` on the ground
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaleBar {
+    pub length: u32,
+    pub label: String,
+}
+
+impl ScaleBar {
+    /// Chooses a round distance that covers about a quarter of the map.
+    /// The units of all spatial references except WGS 84 are assumed to be meters.
+    pub fn new(bbox: BoundingBox2D, spatial_reference: SpatialReference, map_width: u32) -> Self {
+        let meters_per_unit = if spatial_reference == SpatialReference::epsg_4326() {
+            // length of a degree of longitude at the center of the map
+            let center_latitude = (bbox.lower_left().y + bbox.upper_right().y) / 2.;
+            111_320. * center_latitude.to_radians().cos()
+        } else {
+            1.
+        };
+
+        let map_meters = bbox.size_x() * meters_per_unit;
+        let target_meters = map_meters / 4.;
+
+        let magnitude = 10_f64.powf(target_meters.log10().floor());
+        let meters = [5., 2., 1.]
+            .into_iter()
+            .map(|factor| factor * magnitude)
+            .find(|&meters| meters <= target_meters)
+            .unwrap_or(magnitude);
+
+        let label = if meters >= 1000. {
+            format!("{} km", format_number(meters / 1000.))
+        } else {
+            format!("{} m", format_number(meters))
+        };
+
+        Self {
+            length: (meters / map_meters * f64::from(map_width)).round() as u32,
+            label,
+        }
+    }
+}
+
+/// The parts of a report page
+pub struct Report {
+    pub title: String,
+    pub map: RgbaImage,
+    pub scale_bar: ScaleBar,
+    pub legend: Vec<LegendEntry>,
+    pub plots: Vec<RgbaImage>,
+}
+
+impl Report {
+    /// Lays out the title above the map, the legend next to it, the scale bar below it and the plots underneath
+    pub fn compose(&self) -> RgbaImage {
+        let title_height = GLYPH_HEIGHT * TITLE_SCALE;
+        let map_top = MARGIN + title_height + MARGIN;
+        let legend_left = MARGIN + self.map.width() + MARGIN;
+
+        let page_width = legend_left + LEGEND_WIDTH + MARGIN;
+        let content_width = page_width - 2 * MARGIN;
+
+        let plots: Vec<RgbaImage> = self
+            .plots
+            .iter()
+            .map(|plot| fit_into(plot, content_width, MAX_PLOT_HEIGHT))
+            .collect();
+
+        let legend_height: u32 = self.legend.iter().map(LegendEntry::height).sum();
+        let scale_bar_bottom =
+            map_top + self.map.height() + MARGIN / 2 + LINE_HEIGHT + SCALE_BAR_HEIGHT;
+        let plots_top = u32::max(scale_bar_bottom, map_top + legend_height) + MARGIN;
+        let plots_height: u32 = plots.iter().map(|plot| plot.height() + MARGIN).sum();
+
+        let mut page = RgbaImage::from_pixel(page_width, plots_top + plots_height, PAGE_COLOR);
+
+        draw_text(
+            &mut page,
+            &self.title,
+            MARGIN,
+            MARGIN,
+            TITLE_SCALE,
+            TEXT_COLOR,
+        );
+
+        // map with a frame
+        fill_rectangle(
+            &mut page,
+            MARGIN - 1,
+            map_top - 1,
+            self.map.width() + 2,
+            self.map.height() + 2,
+            TEXT_COLOR,
+        );
+        fill_rectangle(
+            &mut page,
+            MARGIN,
+            map_top,
+            self.map.width(),
+            self.map.height(),
+            PAGE_COLOR,
+        );
+        imageops::overlay(&mut page, &self.map, i64::from(MARGIN), i64::from(map_top));
+
+        self.draw_scale_bar(&mut page, map_top + self.map.height() + MARGIN / 2);
+
+        let mut legend_top = map_top;
+        for entry in &self.legend {
+            draw_legend_entry(&mut page, entry, legend_left, legend_top);
+            legend_top += entry.height();
+        }
+
+        let mut plot_top = plots_top;
+        for plot in &plots {
+            imageops::overlay(&mut page, plot, i64::from(MARGIN), i64::from(plot_top));
+            plot_top += plot.height() + MARGIN;
+        }
+
+        page
+    }
+
+    fn draw_scale_bar(&self, page: &mut RgbaImage, top: u32) {
+        draw_text(
+            page,
+            &self.scale_bar.label,
+            MARGIN,
+            top,
+            TEXT_SCALE,
+            TEXT_COLOR,
+        );
+
+        let bar_top = top + LINE_HEIGHT;
+        let length = u32::min(self.scale_bar.length, self.map.width());
+        let half = length / 2;
+
+        // alternating black and white segments
+        fill_rectangle(page, MARGIN, bar_top, length, SCALE_BAR_HEIGHT, TEXT_COLOR);
+        fill_rectangle(
+            page,
+            MARGIN + half,
+            bar_top + 1,
+            length.saturating_sub(half + 1),
+            SCALE_BAR_HEIGHT - 2,
+            PAGE_COLOR,
+        );
+    }
+}
+
+fn draw_legend_entry(page: &mut RgbaImage, entry: &LegendEntry, left: u32, top: u32) {
+    draw_text(page, &entry.name, left, top, TEXT_SCALE, TEXT_COLOR);
+
+    let mut top = top + LINE_HEIGHT;
+    for symbol in &entry.symbols {
+        match symbol {
+            LegendSymbol::Gradient {
+                colors,
+                min_label,
+                max_label,
+            } => {
+                let step_width = GRADIENT_WIDTH / colors.len().max(1) as u32;
+                for (i, color) in colors.iter().enumerate() {
+                    fill_rectangle(
+                        page,
+                        left + i as u32 * step_width,
+                        top,
+                        step_width,
+                        SYMBOL_SIZE,
+                        (*color).into(),
+                    );
+                }
+
+                top += SYMBOL_SIZE + 4;
+                draw_text(page, min_label, left, top, TEXT_SCALE, TEXT_COLOR);
+
+                let gradient_width = step_width * colors.len() as u32;
+                let max_label_width = text_width(max_label, TEXT_SCALE);
+                draw_text(
+                    page,
+                    max_label,
+                    (left + gradient_width).saturating_sub(max_label_width),
+                    top,
+                    TEXT_SCALE,
+                    TEXT_COLOR,
+                );
+
+                top += LINE_HEIGHT - 4;
+            }
+            LegendSymbol::Swatch { color, label } => {
+                fill_rectangle(page, left, top, SYMBOL_SIZE, SYMBOL_SIZE, (*color).into());
+                draw_text(
+                    page,
+                    label,
+                    left + SYMBOL_SIZE + 6,
+                    top,
+                    TEXT_SCALE,
+                    TEXT_COLOR,
+                );
+
+                top += LINE_HEIGHT;
+            }
+        }
+    }
+}
+
+/// Blends a rectangle of `color` over the image
+fn fill_rectangle(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    let rectangle = RgbaImage::from_pixel(width, height, color);
+    imageops::overlay(image, &rectangle, i64::from(x), i64::from(y));
+}
+
+/// Scales down the image to fit into `max_width` x `max_height` while keeping its aspect ratio
+fn fit_into(image: &RgbaImage, max_width: u32, max_height: u32) -> RgbaImage {
+    let factor = f64::min(
+        f64::from(max_width) / f64::from(image.width()),
+        f64::from(max_height) / f64::from(image.height()),
+    );
+
+    if factor >= 1. {
+        return image.clone();
+    }
+
+    imageops::resize(
+        image,
+        (f64::from(image.width()) * factor).round().max(1.) as u32,
+        (f64::from(image.height()) * factor).round().max(1.) as u32,
+        FilterType::Triangle,
+    )
+}
+
+/// Encodes the composed report page
+pub fn encode_report(page: RgbaImage, format: ReportFormat) -> Result<Vec<u8>> {
+    let (width, height) = page.dimensions();
+    let mut bytes = Cursor::new(Vec::new());
+
+    match format {
+        ReportFormat::Png => {
+            DynamicImage::ImageRgba8(page)
+                .write_to(&mut bytes, ImageOutputFormat::Png)
+                .map_err(|source| error::Error::ReportEncoding {
+                    reason: source.to_string(),
+                })?;
+            Ok(bytes.into_inner())
+        }
+        ReportFormat::Pdf => {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(page).into_rgb8())
+                .write_to(&mut bytes, ImageOutputFormat::Jpeg(90))
+                .map_err(|source| error::Error::ReportEncoding {
+                    reason: source.to_string(),
+                })?;
+            Ok(pdf::jpeg_to_pdf(&bytes.into_inner(), width, height))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::{
+        ColorClass, GraduatedColor, LayerVisibility, NumberParam, PolygonSymbology, StrokeParam,
+    };
+
+    #[test]
+    fn it_chooses_round_scale_bars() {
+        let scale_bar = ScaleBar::new(
+            BoundingBox2D::new((0., 0.).into(), (10_000., 5_000.).into()).unwrap(),
+            SpatialReference::new(
+                geoengine_datatypes::spatial_reference::SpatialReferenceAuthority::Epsg,
+                32632,
+            ),
+            1000,
+        );
+
+        assert_eq!(
+            scale_bar,
+            ScaleBar {
+                length: 200,
+                label: "2 km".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_creates_legends_for_graduated_colors() {
+        let layer = Layer {
+            workflow: WorkflowId::new(),
+            name: "Population".to_string(),
+            visibility: LayerVisibility::default(),
+            symbology: Symbology::Polygon(PolygonSymbology {
+                fill_color: ColorParam::Graduated(GraduatedColor {
+                    attribute: "population".to_string(),
+                    classes: vec![
+                        ColorClass {
+                            lower_bound: 0.,
+                            color: RgbaColor::white(),
+                        },
+                        ColorClass {
+                            lower_bound: 1000.5,
+                            color: RgbaColor::black(),
+                        },
+                    ],
+                    default_color: RgbaColor::transparent(),
+                }),
+                stroke: StrokeParam {
+                    width: NumberParam::Static { value: 1 },
+                    color: ColorParam::Static {
+                        color: RgbaColor::black(),
+                    },
+                },
+                text: None,
+            }),
+        };
+
+        assert_eq!(
+            LegendEntry::from_layer(&layer).unwrap().symbols,
+            vec![
+                LegendSymbol::Swatch {
+                    color: RgbaColor::white(),
+                    label: ">= 0".to_string(),
+                },
+                LegendSymbol::Swatch {
+                    color: RgbaColor::black(),
+                    label: ">= 1000.5".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_composes_reports() {
+        let report = Report {
+            title: "Report".to_string(),
+            map: RgbaImage::from_pixel(100, 50, Rgba([255, 0, 0, 255])),
+            scale_bar: ScaleBar {
+                length: 25,
+                label: "1 km".to_string(),
+            },
+            legend: vec![],
+            plots: vec![RgbaImage::from_pixel(1000, 100, Rgba([0, 0, 255, 255]))],
+        };
+
+        let page = report.compose();
+
+        let content_width = MARGIN + 100 + MARGIN + LEGEND_WIDTH - MARGIN;
+        assert_eq!(page.width(), MARGIN + content_width + MARGIN);
+        assert_eq!(page.get_pixel(MARGIN, MARGIN * 2 + 20).0, [255, 0, 0, 255]);
+        assert_eq!(page.get_pixel(0, 0).0, [255, 255, 255, 255]);
+
+        // the plot is scaled down to the content width
+        let last_row = page.height() - MARGIN - 1;
+        assert_eq!(page.get_pixel(MARGIN, last_row).0, [0, 0, 255, 255]);
+        assert_eq!(
+            page.get_pixel(MARGIN + content_width, last_row).0,
+            [255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn it_composes_reports_with_tiny_maps() {
+        let report = Report {
+            title: "Report".to_string(),
+            map: RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255])),
+            scale_bar: ScaleBar {
+                length: 0,
+                label: "1 m".to_string(),
+            },
+            legend: vec![],
+            plots: vec![],
+        };
+
+        assert_eq!(
+            report.compose().width(),
+            MARGIN + 1 + MARGIN + LEGEND_WIDTH + MARGIN
+        );
+    }
+
+    #[test]
+    fn it_rejects_too_large_maps() {
+        let request = |width, height| ReportRequest {
+            bounds: None,
+            width,
+            height,
+            plots: vec![],
+            format: ReportFormat::Png,
+        };
+
+        assert!(request(800, 600).validate().is_ok());
+        assert!(request(0, 600).validate().is_err());
+        assert!(request(800, 100_000).validate().is_err());
+    }
+}
@@ -0,0 +1,94 @@
+/// Wraps a JPEG image into a single-page PDF document.
+/// The page has the size of the image with one pixel per point.
+pub fn jpeg_to_pdf(jpeg: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let content = format!("q {} 0 0 {} 0 0 cm /Im0 Do Q", width, height);
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(5);
+
+    let mut write_object = |pdf: &mut Vec<u8>, dictionary: String, stream: Option<&[u8]>| {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\n", offsets.len(), dictionary).as_bytes());
+        if let Some(stream) = stream {
+            pdf.extend_from_slice(b"stream\n");
+            pdf.extend_from_slice(stream);
+            pdf.extend_from_slice(b"\nendstream\n");
+        }
+        pdf.extend_from_slice(b"endobj\n");
+    };
+
+    write_object(
+        &mut pdf,
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        None,
+    );
+    write_object(
+        &mut pdf,
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        None,
+    );
+    write_object(
+        &mut pdf,
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /XObject << /Im0 4 0 R >> >> /Contents 5 0 R >>",
+            width, height
+        ),
+        None,
+    );
+    write_object(
+        &mut pdf,
+        format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>",
+            width,
+            height,
+            jpeg.len()
+        ),
+        Some(jpeg),
+    );
+    write_object(
+        &mut pdf,
+        format!("<< /Length {} >>", content.len()),
+        Some(content.as_bytes()),
+    );
+
+    let xref_offset = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes(),
+    );
+    for offset in &offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_a_cross_reference_table() {
+        let pdf = jpeg_to_pdf(b"jpeg", 2, 1);
+        let text = String::from_utf8(pdf).unwrap();
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+
+        let xref_offset: usize = text.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(text[xref_offset..].starts_with("xref\n0 6\n"));
+
+        // every entry points to the start of its object
+        for (i, entry) in text[xref_offset..].lines().skip(3).take(5).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+}
@@ -1,15 +1,20 @@
+use crate::api::versioning::ApiVersioning;
 use crate::apidoc::ApiDoc;
 use crate::contexts::{InMemoryContext, SimpleContext};
 use crate::error::{Error, Result};
 use crate::handlers;
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::preflight::{run_preflight, PreflightReport};
 use crate::util::server::{
     calculate_max_blocking_threads_per_worker, configure_extractors, connection_init,
-    log_server_info, render_404, render_405, serve_openapi_json, CustomRootSpanBuilder,
+    log_server_info, render_404, render_405, serve_openapi_check, serve_openapi_json,
+    CustomRootSpanBuilder,
 };
 use actix_files::Files;
 use actix_web::{http, middleware, web, App, HttpServer};
+use chrono::NaiveDate;
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
 use geoengine_operators::util::gdal::register_gdal_drivers_from_list;
 use log::info;
 use std::net::SocketAddr;
@@ -27,6 +32,9 @@ use utoipa_swagger_ui::SwaggerUi;
 pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
     log_server_info()?;
 
+    crate::util::secrets::ensure_secret_key()?;
+    crate::util::operators::ensure_operator_config()?;
+
     let web_config: crate::util::config::Web = get_config_element()?;
     let session_config: crate::util::config::Session = get_config_element()?;
 
@@ -38,29 +46,34 @@ pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     let data_path_config: config::DataProvider = get_config_element()?;
 
-    let chunk_byte_size = config::get_config_element::<config::QueryContext>()?
-        .chunk_byte_size
-        .into();
+    let query_context_config = config::get_config_element::<config::QueryContext>()?;
+    let chunk_byte_size = query_context_config.chunk_byte_size.into();
+    MEMORY_ACCOUNTANT.set_budget_bytes(query_context_config.memory_budget_bytes);
 
     let tiling_spec = config::get_config_element::<config::TilingSpecification>()?.into();
 
     register_gdal_drivers_from_list(config::get_config_element::<config::Gdal>()?.allowed_drivers);
+    crate::util::server::configure_gdal_dataset_pool()?;
 
     let ctx = InMemoryContext::new_with_data(
-        data_path_config.dataset_defs_path,
-        data_path_config.provider_defs_path,
-        data_path_config.layer_defs_path,
-        data_path_config.layer_collection_defs_path,
+        data_path_config.dataset_defs_path.clone(),
+        data_path_config.provider_defs_path.clone(),
+        data_path_config.layer_defs_path.clone(),
+        data_path_config.layer_collection_defs_path.clone(),
         tiling_spec,
         chunk_byte_size,
     )
     .await;
 
+    let preflight_report = run_preflight(&data_path_config, &ctx).await?;
+
     start(
         static_files_dir,
         web_config.bind_address,
         web_config.version_api,
+        web_config.deprecated_api_sunset,
         ctx,
+        preflight_report,
     )
     .await
 }
@@ -69,12 +82,15 @@ async fn start<C>(
     static_files_dir: Option<PathBuf>,
     bind_address: SocketAddr,
     version_api: bool,
+    deprecated_api_sunset: Option<NaiveDate>,
     ctx: C,
+    preflight_report: PreflightReport,
 ) -> Result<(), Error>
 where
     C: SimpleContext,
 {
     let wrapped_ctx = web::Data::new(ctx);
+    let preflight_report = web::Data::new(preflight_report);
 
     let openapi = ApiDoc::openapi();
 
@@ -82,6 +98,8 @@ where
         #[allow(unused_mut)]
         let mut app = App::new()
             .app_data(wrapped_ctx.clone())
+            .app_data(preflight_report.clone())
+            .wrap(ApiVersioning::new(deprecated_api_sunset))
             .wrap(
                 middleware::ErrorHandlers::default()
                     .handler(http::StatusCode::NOT_FOUND, render_404)
@@ -92,7 +110,9 @@ where
             .configure(handlers::datasets::init_dataset_routes::<C>)
             .configure(handlers::layers::init_layer_routes::<C>)
             .configure(handlers::plots::init_plot_routes::<C>)
+            .configure(handlers::preflight::init_preflight_routes)
             .configure(handlers::projects::init_project_routes::<C>)
+            .configure(handlers::reports::init_report_routes::<C>)
             .configure(handlers::session::init_session_routes::<C>)
             .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
             .configure(handlers::upload::init_upload_routes::<C>)
@@ -113,6 +133,8 @@ where
             openapi.clone(),
         );
 
+        app = serve_openapi_check(app, "/api-docs/check", openapi.clone());
+
         #[cfg(feature = "ebv")]
         {
             app = app.service(web::scope("/ebv").configure(handlers::ebv::init_ebv_routes::<C>()));
@@ -125,11 +147,32 @@ where
                 "/api-docs/ebv/openapi.json",
                 crate::handlers::ebv::ApiDoc::openapi(),
             );
+
+            app = serve_openapi_check(
+                app,
+                "/api-docs/ebv/check",
+                crate::handlers::ebv::ApiDoc::openapi(),
+            );
         }
 
         #[cfg(feature = "nfdi")]
         {
             app = app.configure(handlers::gfbio::init_gfbio_routes::<C>);
+
+            app = serve_openapi_json(
+                app,
+                &mut api_urls,
+                "GFBio",
+                "../api-docs/gfbio/openapi.json",
+                "/api-docs/gfbio/openapi.json",
+                crate::handlers::gfbio::ApiDoc::openapi(),
+            );
+
+            app = serve_openapi_check(
+                app,
+                "/api-docs/gfbio/check",
+                crate::handlers::gfbio::ApiDoc::openapi(),
+            );
         }
 
         app = app.service(SwaggerUi::new("/swagger-ui/{_:.*}").urls(api_urls));
@@ -140,6 +183,14 @@ where
                 web::get().to(crate::util::server::server_info_handler),
             );
         }
+        app = app.route(
+            "/metrics/memory",
+            web::get().to(crate::util::server::memory_metrics_handler),
+        );
+        app = app.route(
+            "/metrics/gdal",
+            web::get().to(crate::util::server::gdal_dataset_pool_metrics_handler),
+        );
         if let Some(static_files_dir) = static_files_dir.clone() {
             app.service(Files::new("/static", static_files_dir))
         } else {
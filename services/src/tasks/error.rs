@@ -23,4 +23,7 @@ pub enum TaskError {
         task_type: &'static str,
         task_unique_id: String,
     },
+
+    #[snafu(display("Task panicked: {message}"))]
+    TaskPanicked { message: String },
 }
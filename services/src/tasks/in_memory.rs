@@ -4,11 +4,13 @@ use super::{
 };
 use crate::{contexts::Db, error::Result, util::user_input::Validated};
 use futures::channel::oneshot;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use geoengine_datatypes::{error::ErrorSource, util::Identifier};
-use log::warn;
+use geoengine_operators::util::panic_message;
+use log::{error, warn};
 use std::{
     collections::{HashMap, HashSet, VecDeque},
+    panic::AssertUnwindSafe,
     sync::Arc,
 };
 use tokio::{
@@ -274,7 +276,18 @@ fn run_task(
     notify: Option<oneshot::Sender<TaskStatus>>,
 ) -> JoinHandle<()> {
     crate::util::spawn(async move {
-        let result = task.run(task_ctx.clone()).await;
+        // a panicking task must not stay running forever, so it fails like a task with an error
+        let result = match AssertUnwindSafe(task.run(task_ctx.clone()))
+            .catch_unwind()
+            .await
+        {
+            Ok(result) => result,
+            Err(panic) => {
+                let message = panic_message(panic.as_ref());
+                error!("Task {task_id} panicked: {message}");
+                Err(TaskError::TaskPanicked { message }.boxed())
+            }
+        };
 
         let mut update_lock = task_manager.write_lock_for_update().await;
 
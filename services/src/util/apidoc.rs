@@ -1,4 +1,9 @@
-use utoipa::Modify;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::openapi::{ContentBuilder, Ref, ResponseBuilder};
+use utoipa::{Modify, ToSchema};
 
 pub struct OpenApiServerInfo;
 
@@ -14,3 +19,250 @@ impl Modify for OpenApiServerInfo {
             .build()]);
     }
 }
+
+/// Documents the [`ErrorResponse`](crate::handlers::ErrorResponse) as default response of all operations
+pub struct ErrorResponseAddon;
+
+impl Modify for ErrorResponseAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for path_item in openapi.paths.paths.values_mut() {
+            for operation in path_item.operations.values_mut() {
+                operation
+                    .responses
+                    .responses
+                    .entry("default".to_string())
+                    .or_insert_with(|| {
+                        ResponseBuilder::new()
+                            .description(
+                                "An error, e.g., if the request is invalid or the session is not authorized",
+                            )
+                            .content(
+                                "application/json",
+                                ContentBuilder::new()
+                                    .schema(Ref::from_schema_name("ErrorResponse"))
+                                    .build(),
+                            )
+                            .build()
+                            .into()
+                    });
+            }
+        }
+    }
+}
+
+const OPERATION_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The result of checking an OpenAPI document for consistency,
+/// i.e., that all references resolve and that the operations match their paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiDocCheck {
+    pub valid: bool,
+    pub problems: Vec<String>,
+}
+
+impl ApiDocCheck {
+    pub fn new(openapi: &utoipa::openapi::OpenApi) -> Self {
+        let problems = match serde_json::to_value(openapi) {
+            Ok(document) => document_problems(&document),
+            Err(error) => vec![format!("The document cannot be serialized: {}", error)],
+        };
+
+        Self {
+            valid: problems.is_empty(),
+            problems,
+        }
+    }
+}
+
+fn document_problems(document: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let mut references = BTreeSet::new();
+    collect_references(document, &mut references);
+    for reference in references {
+        let resolves = reference
+            .strip_prefix('#')
+            .and_then(|pointer| document.pointer(pointer))
+            .is_some();
+        if !resolves {
+            problems.push(format!("Reference `{}` does not resolve", reference));
+        }
+    }
+
+    let security_schemes = document
+        .pointer("/components/securitySchemes")
+        .and_then(Value::as_object);
+
+    let paths = document
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten();
+    for (path, path_item) in paths {
+        for method in OPERATION_METHODS {
+            if let Some(operation) = path_item.get(method) {
+                problems.extend(operation_problems(
+                    path,
+                    method,
+                    path_item,
+                    operation,
+                    security_schemes,
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+fn collect_references(value: &Value, references: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        references.insert(reference.clone());
+                    }
+                    _ => collect_references(value, references),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_references(value, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn operation_problems(
+    path: &str,
+    method: &str,
+    path_item: &Value,
+    operation: &Value,
+    security_schemes: Option<&serde_json::Map<String, Value>>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let operation_name = format!("{} {}", method.to_uppercase(), path);
+
+    let has_responses = operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .map_or(false, |responses| !responses.is_empty());
+    if !has_responses {
+        problems.push(format!("`{}` has no responses", operation_name));
+    }
+
+    let template_parameters: BTreeSet<&str> = path
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .collect();
+
+    let declared_parameters: BTreeSet<&str> = [path_item, operation]
+        .into_iter()
+        .filter_map(|value| value.get("parameters").and_then(Value::as_array))
+        .flatten()
+        .filter(|parameter| parameter.get("in").and_then(Value::as_str) == Some("path"))
+        .filter_map(|parameter| parameter.get("name").and_then(Value::as_str))
+        .collect();
+
+    for parameter in template_parameters.difference(&declared_parameters) {
+        problems.push(format!(
+            "`{}` does not declare the path parameter `{}`",
+            operation_name, parameter
+        ));
+    }
+    for parameter in declared_parameters.difference(&template_parameters) {
+        problems.push(format!(
+            "`{}` declares the path parameter `{}` that is not part of its path",
+            operation_name, parameter
+        ));
+    }
+
+    let requirements = operation
+        .get("security")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .flat_map(|requirement| requirement.keys());
+    for scheme in requirements {
+        if !security_schemes.map_or(false, |schemes| schemes.contains_key(scheme)) {
+            problems.push(format!(
+                "`{}` requires the unknown security scheme `{}`",
+                operation_name, scheme
+            ));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_finds_inconsistencies() {
+        let document = json!({
+            "paths": {
+                "/workflow/{id}": {
+                    "get": {
+                        "parameters": [
+                            { "name": "workflow", "in": "path" }
+                        ],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Workflow" }
+                                    }
+                                }
+                            },
+                            "default": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/ErrorResponse" }
+                                    }
+                                }
+                            }
+                        },
+                        "security": [{ "session_token": [] }]
+                    },
+                    "post": {
+                        "parameters": [
+                            { "name": "id", "in": "path" }
+                        ],
+                        "responses": {},
+                        "security": [{ "admin_token": [] }]
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "ErrorResponse": { "type": "object" }
+                },
+                "securitySchemes": {
+                    "session_token": { "type": "http" }
+                }
+            }
+        });
+
+        assert_eq!(
+            document_problems(&document),
+            vec![
+                "Reference `#/components/schemas/Workflow` does not resolve",
+                "`GET /workflow/{id}` does not declare the path parameter `id`",
+                "`GET /workflow/{id}` declares the path parameter `workflow` that is not part of its path",
+                "`POST /workflow/{id}` has no responses",
+                "`POST /workflow/{id}` requires the unknown security scheme `admin_token`",
+            ]
+        );
+    }
+}
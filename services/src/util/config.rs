@@ -2,13 +2,20 @@ use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::RwLock;
+use std::time::Duration;
 
 use crate::contexts::SessionId;
+use crate::datasets::upload_hooks::UploadHookPolicy;
 use crate::error::{self, Result};
-use crate::util::parsing::{deserialize_base_url, deserialize_base_url_option};
+use crate::util::parsing::{
+    deserialize_base_url, deserialize_base_url_option, deserialize_utc_offset,
+};
 
 use crate::api::model::datatypes::TimeInterval;
+use chrono::FixedOffset;
 use config::{Config, Environment, File};
+use geoengine_operators::engine::{OperatorPolicy, PathSandbox};
+use geoengine_operators::util::gdal_dataset_pool::GdalDatasetPoolConfig;
 use geoengine_operators::util::raster_stream_to_geotiff::GdalCompressionNumThreads;
 use lazy_static::lazy_static;
 use serde::Deserialize;
@@ -121,6 +128,9 @@ pub struct Web {
     pub external_address: Option<url::Url>,
     pub backend: Backend,
     pub version_api: bool,
+    /// the date after which the unversioned and deprecated versions of the API will be removed
+    #[serde(default)]
+    pub deprecated_api_sunset: Option<chrono::NaiveDate>,
 }
 
 impl Web {
@@ -200,12 +210,96 @@ impl ConfigElement for TilingSpecification {
 #[derive(Debug, Deserialize)]
 pub struct QueryContext {
     pub chunk_byte_size: usize,
+    /// The memory of the process for raster grids and collections.
+    /// Chunks get smaller when their memory approaches the budget and new queries are rejected above it.
+    pub memory_budget_bytes: Option<usize>,
 }
 
 impl ConfigElement for QueryContext {
     const KEY: &'static str = "query_context";
 }
 
+/// Splits large export queries into sub-queries to avoid that a single export occupies the whole engine
+#[derive(Debug, Deserialize)]
+pub struct QuerySplitting {
+    /// The maximum number of tiles per time step of a sub-query
+    pub max_tiles_per_query: usize,
+    /// The number of sub-queries that are computed at once, `1` computes them sequentially
+    pub max_parallel_queries: usize,
+}
+
+impl ConfigElement for QuerySplitting {
+    const KEY: &'static str = "query_splitting";
+}
+
+/// Spills large vector results, e.g., of paged WFS requests and exports, to Arrow IPC files instead of keeping them in memory
+#[derive(Debug, Deserialize)]
+pub struct ResultSpooling {
+    /// Results are spilled to disk as soon as their collections exceed this size or the memory budget is exceeded
+    pub memory_threshold_bytes: usize,
+    /// The directory of the spool files, which are removed as soon as the results are dropped
+    pub directory: PathBuf,
+}
+
+impl ConfigElement for ResultSpooling {
+    const KEY: &'static str = "result_spooling";
+}
+
+/// Caches statistics of workflow results, e.g., the bounds of histograms, so that they are not recomputed
+#[derive(Debug, Deserialize)]
+pub struct WorkflowStatistics {
+    /// The number of cached statistics, zero disables the cache
+    pub cache_capacity: usize,
+}
+
+impl ConfigElement for WorkflowStatistics {
+    const KEY: &'static str = "workflow_statistics";
+}
+
+/// Searches the layers of all providers, e.g., by traversing the layer collections of providers without a native search
+#[derive(Debug, Deserialize)]
+pub struct LayerSearch {
+    /// The maximum number of results of a search
+    pub list_limit: u32,
+    /// The maximum number of collection pages that are loaded from a provider for a search
+    pub max_listings_per_provider: usize,
+    /// The number of items that are loaded per collection page
+    pub page_size: u32,
+    /// Providers that do not respond in time are left out of the results
+    pub provider_timeout_seconds: u64,
+}
+
+impl ConfigElement for LayerSearch {
+    const KEY: &'static str = "layer_search";
+}
+
+/// Records sampled queries with their workflows, e.g., for replaying them on another instance
+#[derive(Debug, Deserialize)]
+pub struct QueryLog {
+    pub enabled: bool,
+    /// The file to which the queries are appended as JSON lines
+    pub path: PathBuf,
+    /// Records every n-th query
+    pub sampling_interval: u64,
+}
+
+impl ConfigElement for QueryLog {
+    const KEY: &'static str = "query_log";
+}
+
+/// The sizes of the thread pools for computations and for blocking IO, e.g., reading files with GDAL
+#[derive(Debug, Deserialize)]
+pub struct ThreadPools {
+    /// The number of threads for computations, zero uses one thread per CPU core
+    pub cpu_threads: usize,
+    /// The number of threads for blocking IO, zero uses one thread per CPU core
+    pub io_threads: usize,
+}
+
+impl ConfigElement for ThreadPools {
+    const KEY: &'static str = "thread_pools";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DatasetService {
     pub list_limit: u32,
@@ -215,6 +309,47 @@ impl ConfigElement for DatasetService {
     const KEY: &'static str = "dataset_service";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WorkflowTemplateService {
+    pub list_limit: u32,
+}
+
+impl ConfigElement for WorkflowTemplateService {
+    const KEY: &'static str = "workflow_template_service";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowSubstitution {
+    /// how long a workflow with substituted sources can be queried
+    pub transient_lifetime_seconds: u64,
+}
+
+impl ConfigElement for WorkflowSubstitution {
+    const KEY: &'static str = "workflow_substitution";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowSubscription {
+    /// how often subscriptions check for new time slices if they do not specify an interval
+    pub poll_interval_seconds: u64,
+}
+
+impl ConfigElement for WorkflowSubscription {
+    const KEY: &'static str = "workflow_subscription";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DatasetValidation {
+    /// the maximum number of time slices that are checked per dataset
+    pub max_time_slices: usize,
+    /// whether newly registered datasets are validated in the background
+    pub validate_on_registration: bool,
+}
+
+impl ConfigElement for DatasetValidation {
+    const KEY: &'static str = "dataset_validation";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TaskManager {
     pub list_limit: u32,
@@ -234,6 +369,23 @@ impl ConfigElement for Upload {
     const KEY: &'static str = "upload";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UploadHooks {
+    pub policy: UploadHookPolicy,
+    /// where uploads with rejected files are moved to if the policy is to quarantine them
+    pub quarantine_path: PathBuf,
+    /// a virus scanner that is invoked with the path of each uploaded file, disabled if empty
+    pub virus_scan_command: Vec<String>,
+    /// whether the contents of files with known extensions must match their type
+    pub validate_file_types: bool,
+    /// the allowed file extensions, all extensions are allowed if empty
+    pub allowed_extensions: Vec<String>,
+}
+
+impl ConfigElement for UploadHooks {
+    const KEY: &'static str = "upload_hooks";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Logging {
     pub log_spec: String,
@@ -291,6 +443,41 @@ impl ConfigElement for Ogc {
     const KEY: &'static str = "ogc";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Defaults {
+    /// The spatial reference of requests that do not specify one, unless the session's view has one
+    pub spatial_reference: geoengine_datatypes::spatial_reference::SpatialReference,
+    /// The UTC offset of times that do not specify one, e.g., `+01:00`
+    #[serde(deserialize_with = "deserialize_utc_offset")]
+    pub time_zone: FixedOffset,
+}
+
+impl ConfigElement for Defaults {
+    const KEY: &'static str = "defaults";
+}
+
+/// The operators that workflows may use on this instance
+#[derive(Debug, Deserialize)]
+pub struct Operators {
+    #[serde(flatten)]
+    pub policy: OperatorPolicy,
+}
+
+impl ConfigElement for Operators {
+    const KEY: &'static str = "operators";
+}
+
+/// The files that operators may read on this instance
+#[derive(Debug, Deserialize)]
+pub struct Sandbox {
+    #[serde(flatten)]
+    pub paths: PathSandbox,
+}
+
+impl ConfigElement for Sandbox {
+    const KEY: &'static str = "sandbox";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Wcs {
     pub tile_limit: usize,
@@ -306,6 +493,15 @@ impl ConfigElement for Wcs {
 pub struct Wfs {
     pub default_time: Option<OgcDefaultTime>,
     pub request_timeout_seconds: Option<u64>,
+    /// The number of generalized responses that are cached, zero disables the cache
+    #[serde(default)]
+    pub generalization_cache_size: usize,
+    /// The number of query results that are kept for paging with `startIndex` and `count`, zero disables the spool
+    #[serde(default)]
+    pub paging_spool_size: usize,
+    /// How long query results are kept for paging, zero keeps them until they are evicted
+    #[serde(default)]
+    pub paging_spool_ttl_seconds: u64,
 }
 
 impl ConfigElement for Wfs {
@@ -316,6 +512,12 @@ impl ConfigElement for Wfs {
 pub struct Wms {
     pub default_time: Option<OgcDefaultTime>,
     pub request_timeout_seconds: Option<u64>,
+    /// The number of tiles per side of a meta-tile that is rendered for tile-aligned requests, one disables meta-tiling
+    #[serde(default)]
+    pub meta_tile_size: u32,
+    /// The number of rendered meta-tiles that are cached, zero disables meta-tiling
+    #[serde(default)]
+    pub meta_tile_cache_size: usize,
 }
 
 impl ConfigElement for Wms {
@@ -325,6 +527,12 @@ impl ConfigElement for Wms {
 #[derive(Debug, Deserialize)]
 pub struct Plots {
     pub request_timeout_seconds: Option<u64>,
+    /// The number of plot results that are cached, zero disables the cache
+    #[serde(default)]
+    pub cache_size: usize,
+    /// How long plot results are cached, zero keeps them until they are evicted or invalidated
+    #[serde(default)]
+    pub cache_ttl_seconds: u64,
 }
 
 impl ConfigElement for Plots {
@@ -353,6 +561,16 @@ impl ConfigElement for DataProvider {
     const KEY: &'static str = "dataprovider";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Preflight {
+    /// Refuse to start if a definition of the `dataprovider` directories cannot be loaded
+    pub refuse_startup_on_critical_errors: bool,
+}
+
+impl ConfigElement for Preflight {
+    const KEY: &'static str = "preflight";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Gdal {
     pub compression_num_threads: GdalCompressionNumThreads,
@@ -365,6 +583,38 @@ impl ConfigElement for Gdal {
     const KEY: &'static str = "gdal";
 }
 
+/// Keeps GDAL datasets open between reads, so that files are not opened for each tile
+#[derive(Debug, Deserialize)]
+pub struct GdalDatasetPool {
+    pub enabled: bool,
+    /// The maximum number of idle datasets for the same file
+    pub max_idle_datasets_per_path: usize,
+    /// The maximum number of idle datasets over all files
+    pub max_idle_datasets: usize,
+    /// Idle datasets are closed after this time
+    pub max_idle_seconds: u64,
+    /// Open options that are added to those of the datasets, e.g., `NUM_THREADS=2`
+    pub open_options: Vec<String>,
+    /// The size of the GDAL block cache (`GDAL_CACHEMAX`) that is shared by all open datasets
+    pub cache_max_megabytes: Option<usize>,
+}
+
+impl ConfigElement for GdalDatasetPool {
+    const KEY: &'static str = "gdal_dataset_pool";
+}
+
+impl From<GdalDatasetPool> for GdalDatasetPoolConfig {
+    fn from(config: GdalDatasetPool) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_idle_datasets_per_path: config.max_idle_datasets_per_path,
+            max_idle_datasets: config.max_idle_datasets,
+            max_idle_time: Duration::from_secs(config.max_idle_seconds),
+            open_options: config.open_options,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Session {
     pub anonymous_access: bool,
@@ -376,6 +626,16 @@ impl ConfigElement for Session {
     const KEY: &'static str = "session";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Secrets {
+    /// base64 encoded 256 bit key for encrypting credentials of provider definitions
+    pub key: String,
+}
+
+impl ConfigElement for Secrets {
+    const KEY: &'static str = "secrets";
+}
+
 #[cfg(feature = "nfdi")]
 #[derive(Debug, Deserialize)]
 pub struct GFBio {
@@ -0,0 +1,101 @@
+use std::str::FromStr;
+
+use chrono::{FixedOffset, NaiveDateTime, TimeZone};
+use geoengine_datatypes::primitives::{DateTime, DateTimeError};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+
+use crate::contexts::Session;
+use crate::util::config::{get_config_element, Defaults};
+
+lazy_static::lazy_static! {
+    /// The configured defaults, since they are needed for parsing every request
+    static ref DEFAULTS: (SpatialReference, FixedOffset) = get_config_element::<Defaults>()
+        .map_or_else(
+            |_| (SpatialReference::epsg_4326(), utc()),
+            |defaults| (defaults.spatial_reference, defaults.time_zone),
+        );
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("zero is a valid offset")
+}
+
+/// The spatial reference of requests that do not specify one.
+/// It is the one of the session's view if the user has set one, and the configured default otherwise.
+pub fn default_spatial_reference<S: Session>(session: &S) -> SpatialReference {
+    match session.view().map(|view| view.spatial_reference) {
+        Some(SpatialReferenceOption::SpatialReference(spatial_reference)) => spatial_reference,
+        _ => DEFAULTS.0,
+    }
+}
+
+/// The UTC offset of times that do not specify one
+pub fn default_time_zone() -> FixedOffset {
+    DEFAULTS.1
+}
+
+/// Parses an ISO 8601 date time. A date time without an offset is in the [default time zone](default_time_zone).
+pub fn parse_date_time(s: &str) -> Result<DateTime, DateTimeError> {
+    let error = match DateTime::from_str(s) {
+        Ok(date_time) => return Ok(date_time),
+        Err(error) => error,
+    };
+
+    NaiveDateTime::from_str(s)
+        .ok()
+        .and_then(|date_time| default_time_zone().from_local_datetime(&date_time).single())
+        .map(Into::into)
+        .ok_or(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::SimpleSession;
+    use crate::projects::STRectangle;
+
+    #[test]
+    fn it_uses_the_spatial_reference_of_the_view() {
+        let mut session = SimpleSession::default();
+        assert_eq!(
+            default_spatial_reference(&session),
+            SpatialReference::epsg_4326()
+        );
+
+        session.view = Some(
+            STRectangle::new(
+                SpatialReference::new(
+                    geoengine_datatypes::spatial_reference::SpatialReferenceAuthority::Epsg,
+                    3857,
+                ),
+                0.,
+                0.,
+                1.,
+                1.,
+                0,
+                1,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            default_spatial_reference(&session),
+            SpatialReference::new(
+                geoengine_datatypes::spatial_reference::SpatialReferenceAuthority::Epsg,
+                3857
+            )
+        );
+    }
+
+    #[test]
+    fn it_parses_date_times_without_offsets() {
+        assert_eq!(
+            parse_date_time("2014-04-01T12:00:00").unwrap(),
+            DateTime::new_utc(2014, 4, 1, 12, 0, 0)
+        );
+        assert_eq!(
+            parse_date_time("2014-04-01T12:00:00.5+01:00").unwrap(),
+            DateTime::new_utc_with_millis(2014, 4, 1, 11, 0, 0, 500)
+        );
+        assert!(parse_date_time("2014-04-01").is_err());
+    }
+}
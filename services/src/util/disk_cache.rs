@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Limits of a directory that caches the responses of a data provider
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskCacheLimits {
+    /// Time after which a cached response is requested again. Zero disables the cache.
+    pub ttl_seconds: u64,
+    /// Maximum size of the directory. The oldest files are removed first.
+    pub max_size_bytes: u64,
+}
+
+impl Default for DiskCacheLimits {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: 24 * 60 * 60,
+            max_size_bytes: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// A directory of cached provider responses that expire after a time-to-live and are evicted
+/// from the oldest to the newest when the directory exceeds its size limit.
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    path: PathBuf,
+    limits: DiskCacheLimits,
+}
+
+impl DiskCache {
+    pub fn new(path: PathBuf, limits: DiskCacheLimits) -> Self {
+        Self { path, limits }
+    }
+
+    /// Returns the path of the file `file_name` if it is cached and has not expired
+    pub async fn get(&self, file_name: &str) -> Option<PathBuf> {
+        let file_path = self.path.join(file_name);
+
+        let modified = tokio::fs::metadata(&file_path)
+            .await
+            .ok()?
+            .modified()
+            .ok()?;
+
+        if self.is_expired(modified) {
+            None
+        } else {
+            Some(file_path)
+        }
+    }
+
+    /// Stores `data` as the file `file_name` and returns its path.
+    /// Afterwards, expired files are removed and the oldest files are evicted until the directory fits its size limit.
+    pub async fn insert(&self, file_name: &str, data: impl AsRef<[u8]>) -> Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.path).await?;
+
+        let file_path = self.path.join(file_name);
+
+        // write to a temporary file first, so that concurrent queries never read partial results
+        let temp_file_path = self.path.join(format!("{file_name}.part"));
+        tokio::fs::write(&temp_file_path, data).await?;
+        tokio::fs::rename(&temp_file_path, &file_path).await?;
+
+        self.evict(&file_path).await?;
+
+        Ok(file_path)
+    }
+
+    fn is_expired(&self, modified: SystemTime) -> bool {
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+
+        age >= Duration::from_secs(self.limits.ttl_seconds)
+    }
+
+    /// Removes expired files and the oldest files above the size limit, but never the file `keep`
+    async fn evict(&self, keep: &Path) -> Result<()> {
+        let mut size = tokio::fs::metadata(keep).await?.len();
+        let mut files = Vec::new();
+
+        let mut entries = tokio::fs::read_dir(&self.path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            // files that are currently written by other queries are skipped
+            if path == keep
+                || path
+                    .extension()
+                    .map_or(false, |extension| extension == "part")
+            {
+                continue;
+            }
+
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let modified = metadata.modified()?;
+            if self.is_expired(modified) {
+                remove_cached_file(&path).await?;
+                continue;
+            }
+
+            size += metadata.len();
+            files.push((modified, metadata.len(), path));
+        }
+
+        files.sort_by_key(|(modified, _, _)| *modified);
+
+        for (_, len, path) in files {
+            if size <= self.limits.max_size_bytes {
+                break;
+            }
+
+            remove_cached_file(&path).await?;
+            size -= len;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes a cached file, ignoring files that were already removed by a concurrent eviction
+async fn remove_cached_file(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_expires_cached_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = DiskCache::new(
+            dir.path().to_owned(),
+            DiskCacheLimits {
+                ttl_seconds: 60,
+                max_size_bytes: 1024,
+            },
+        );
+
+        assert_eq!(cache.get("a.json").await, None);
+
+        let path = cache.insert("a.json", b"{}").await.unwrap();
+        assert_eq!(cache.get("a.json").await, Some(path));
+
+        let disabled_cache = DiskCache::new(
+            dir.path().to_owned(),
+            DiskCacheLimits {
+                ttl_seconds: 0,
+                max_size_bytes: 1024,
+            },
+        );
+
+        assert_eq!(disabled_cache.get("a.json").await, None);
+
+        // inserting into the disabled cache removes the expired file
+        disabled_cache.insert("b.json", b"{}").await.unwrap();
+        assert!(!dir.path().join("a.json").exists());
+        assert!(dir.path().join("b.json").exists());
+    }
+
+    #[tokio::test]
+    async fn it_evicts_the_oldest_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = DiskCache::new(
+            dir.path().to_owned(),
+            DiskCacheLimits {
+                ttl_seconds: 60,
+                max_size_bytes: 10,
+            },
+        );
+
+        for file_name in ["a.json", "b.json", "c.json"] {
+            cache.insert(file_name, b"1234").await.unwrap();
+            // keep the modification times apart
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(cache.get("a.json").await, None);
+        assert!(cache.get("b.json").await.is_some());
+        assert!(cache.get("c.json").await.is_some());
+
+        // a file above the limit is still cached until the next insertion
+        let big_file = cache.insert("d.json", b"12345678901").await.unwrap();
+        assert_eq!(cache.get("d.json").await, Some(big_file));
+        assert_eq!(cache.get("b.json").await, None);
+        assert_eq!(cache.get("c.json").await, None);
+    }
+}
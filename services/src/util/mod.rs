@@ -9,10 +9,14 @@ pub use geoengine_operators::util::{spawn, spawn_blocking, spawn_blocking_with_t
 
 pub mod apidoc;
 pub mod config;
+pub mod defaults;
+pub mod disk_cache;
 pub mod identifiers;
 pub mod operators;
 pub mod parsing;
+pub mod preflight;
 pub mod retry;
+pub mod secrets;
 pub mod server;
 pub mod tests;
 pub mod user_input;
@@ -1,11 +1,53 @@
 use crate::error::Result;
+use crate::util::config::{get_config_element, Operators, Sandbox, Upload};
 use geoengine_datatypes::dataset::DataId;
 use geoengine_operators::{
-    engine::{OperatorName, RasterOperator, TypedOperator, VectorOperator},
+    engine::{
+        OperatorName, OperatorPolicy, PathSandbox, RasterOperator, TypedOperator, VectorOperator,
+    },
     mock::{MockDatasetDataSource, MockDatasetDataSourceParams},
     source::{GdalSource, GdalSourceParameters, OgrSource, OgrSourceParameters},
 };
 
+lazy_static::lazy_static! {
+    /// The operators that workflows may use on this instance, configured by the `operators` config,
+    /// which is checked at startup by [`ensure_operator_config`]
+    pub static ref OPERATOR_POLICY: OperatorPolicy = get_config_element::<Operators>()
+        .map(|operators| operators.policy)
+        .expect("the operators config should be checked at startup");
+
+    /// The files that operators may read on this instance, configured by the `sandbox` config,
+    /// which is checked at startup by [`ensure_operator_config`]
+    pub static ref PATH_SANDBOX: PathSandbox =
+        path_sandbox_from_config().expect("the sandbox config should be checked at startup");
+}
+
+/// Fails if the configs that restrict the operators are invalid, so that the server does not start without the restrictions
+pub fn ensure_operator_config() -> Result<()> {
+    get_config_element::<Operators>()?;
+    path_sandbox_from_config()?;
+
+    Ok(())
+}
+
+/// The sandbox of the `sandbox` config, whose roots default to the data root and the upload directory
+fn path_sandbox_from_config() -> Result<PathSandbox> {
+    let mut sandbox = get_config_element::<Sandbox>()?.paths;
+
+    if sandbox.allowed_roots.is_empty() {
+        let upload_path = get_config_element::<Upload>()?.path;
+
+        sandbox.allowed_roots = sandbox
+            .data_root
+            .iter()
+            .cloned()
+            .chain(std::iter::once(upload_path))
+            .collect();
+    }
+
+    Ok(sandbox)
+}
+
 pub fn source_operator_from_dataset(
     source_operator_name: &str,
     id: &DataId,
@@ -17,6 +59,7 @@ pub fn source_operator_from_dataset(
                     data: id.clone(),
                     attribute_projection: None,
                     attribute_filters: None,
+                    schema_merging: None,
                 },
             }
             .boxed(),
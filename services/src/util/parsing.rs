@@ -1,4 +1,5 @@
-use geoengine_datatypes::primitives::SpatialResolution;
+use chrono::FixedOffset;
+use geoengine_datatypes::primitives::{Coordinate2D, SpatialResolution};
 use serde::de;
 use serde::de::Error;
 use serde::Deserialize;
@@ -23,6 +24,40 @@ where
     }
 }
 
+/// Parse an optional `SpatialResolution`, see [`parse_spatial_resolution`].
+pub fn parse_spatial_resolution_option<'de, D>(
+    deserializer: D,
+) -> Result<Option<SpatialResolution>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    parse_spatial_resolution(deserializer).map(Some)
+}
+
+/// Parse a list of coordinates of a request by parsing `x,y` pairs separated by `;`, e.g. `1.0,2.0;3.0,4.0`.
+pub fn parse_coordinates<'de, D>(deserializer: D) -> Result<Vec<Coordinate2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    s.split(';')
+        .map(|coordinate| {
+            let split: Result<Vec<f64>, <f64 as FromStr>::Err> =
+                coordinate.split(',').map(f64::from_str).collect();
+
+            match split.as_ref().map(Vec::as_slice) {
+                Ok(&[x, y]) => Ok(Coordinate2D::new(x, y)),
+                Err(error) => Err(D::Error::custom(error)),
+                Ok(..) => Err(D::Error::custom(format!(
+                    "Invalid coordinate {}",
+                    coordinate
+                ))),
+            }
+        })
+        .collect()
+}
+
 /// Parse a field as a string or array of strings. Always returns a `Vec<String>`.
 pub fn string_or_string_array<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
@@ -89,12 +124,86 @@ where
         .map_err(D::Error::custom)
 }
 
+/// Parse a UTC offset, e.g. `+01:00`, `-0530` or `Z`. `UTC` is accepted as well.
+pub fn parse_utc_offset(s: &str) -> Option<FixedOffset> {
+    let s = s.trim();
+
+    if s.eq_ignore_ascii_case("Z") || s.eq_ignore_ascii_case("UTC") {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, offset) = if let Some(offset) = s.strip_prefix('+') {
+        (1, offset)
+    } else if let Some(offset) = s.strip_prefix('-') {
+        (-1, offset)
+    } else {
+        return None;
+    };
+
+    let (hours, minutes) = match offset.split_once(':') {
+        Some(split) => split,
+        None if offset.len() == 4 && offset.is_ascii() => offset.split_at(2),
+        None => (offset, "0"),
+    };
+
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Deserialize a UTC offset, see [`parse_utc_offset`].
+pub fn deserialize_utc_offset<'de, D>(deserializer: D) -> Result<FixedOffset, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    parse_utc_offset(&s).ok_or_else(|| D::Error::custom(format!("Invalid UTC offset {}", s)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Display;
 
     use super::*;
 
+    #[test]
+    fn test_parse_coordinates() {
+        #[derive(Deserialize)]
+        struct Test {
+            #[serde(deserialize_with = "parse_coordinates")]
+            coords: Vec<Coordinate2D>,
+        }
+
+        assert_eq!(
+            serde_urlencoded::from_str::<Test>("coords=1.5,2%3B-3,4.25")
+                .unwrap()
+                .coords,
+            vec![Coordinate2D::new(1.5, 2.), Coordinate2D::new(-3., 4.25)]
+        );
+        assert!(serde_urlencoded::from_str::<Test>("coords=1,2,3").is_err());
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("Z"), FixedOffset::east_opt(0));
+        assert_eq!(parse_utc_offset("UTC"), FixedOffset::east_opt(0));
+        assert_eq!(parse_utc_offset("+01:00"), FixedOffset::east_opt(3600));
+        assert_eq!(
+            parse_utc_offset("-0530"),
+            FixedOffset::west_opt(5 * 3600 + 30 * 60)
+        );
+        assert_eq!(parse_utc_offset("+2"), FixedOffset::east_opt(2 * 3600));
+        assert_eq!(parse_utc_offset("01:00"), None);
+        assert_eq!(parse_utc_offset("+25:00"), None);
+        assert_eq!(parse_utc_offset(""), None);
+    }
+
     #[test]
     fn test_deserialize_base_url() {
         #[derive(Deserialize)]
@@ -0,0 +1,409 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use snafu::ensure;
+use utoipa::ToSchema;
+
+use crate::api::model::datatypes::{DataId, DataProviderId, DatasetId, LayerId};
+use crate::contexts::Context;
+use crate::datasets::storage::DatasetDefinition;
+use crate::error::{self, Result};
+use crate::layers::external::DataProviderDefinition;
+use crate::layers::layer::{AddLayer, LayerCollectionDefinition, LayerDefinition};
+use crate::layers::listing::LayerCollectionId;
+use crate::util::config::{get_config_element, DataProvider, Preflight};
+use crate::util::user_input::UserInput;
+use crate::workflows::registry::WorkflowRegistry;
+use crate::workflows::workflow::WorkflowId;
+
+/// The kinds of definition files that are loaded at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DefinitionKind {
+    Dataset,
+    Provider,
+    Layer,
+    LayerCollection,
+    Workflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PreflightSeverity {
+    /// The definition cannot be loaded or used
+    Critical,
+    /// The definition is loaded, but it references data that is unknown at startup
+    Warning,
+}
+
+/// A broken definition file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightIssue {
+    pub kind: DefinitionKind,
+    /// The definition file or, for workflows, the id of the registry entry
+    #[schema(value_type = String)]
+    pub path: PathBuf,
+    pub severity: PreflightSeverity,
+    pub message: String,
+}
+
+/// The result of validating the dataset, provider, layer and layer collection definitions
+/// and the workflow registry entries at startup,
+/// so that broken definitions are reported at once instead of failing on their first access
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    /// The number of definition files that were checked
+    pub checked: usize,
+    pub issues: Vec<PreflightIssue>,
+}
+
+/// The identifiers of the loaded definitions for checking the references between them
+#[derive(Default)]
+struct KnownIds {
+    datasets: HashSet<DatasetId>,
+    providers: HashSet<DataProviderId>,
+    layers: HashSet<LayerId>,
+    collections: HashSet<LayerCollectionId>,
+}
+
+impl PreflightReport {
+    /// Validates the definition files of the directories of the `dataprovider` config.
+    /// Providers are initialized to detect unreachable or misconfigured data sources.
+    pub async fn check(data_provider: &DataProvider) -> Self {
+        let mut report = Self::default();
+        let mut ids = KnownIds::default();
+
+        for (path, def) in report.definitions::<DatasetDefinition>(
+            DefinitionKind::Dataset,
+            &data_provider.dataset_defs_path,
+        ) {
+            let result = def
+                .properties
+                .validated()
+                .map(|properties| properties.user_input.id);
+
+            match result {
+                Ok(Some(id)) if !ids.datasets.insert(id) => report.critical(
+                    DefinitionKind::Dataset,
+                    path,
+                    format!("the dataset id {id} is already defined"),
+                ),
+                Ok(_) => (),
+                Err(e) => report.critical(DefinitionKind::Dataset, path, e.to_string()),
+            }
+        }
+
+        for (path, def) in report.definitions::<Box<dyn DataProviderDefinition>>(
+            DefinitionKind::Provider,
+            &data_provider.provider_defs_path,
+        ) {
+            let id = def.id();
+            if !ids.providers.insert(id) {
+                report.critical(
+                    DefinitionKind::Provider,
+                    path,
+                    format!("the provider id {id} is already defined"),
+                );
+                continue;
+            }
+
+            if let Err(e) = def.initialize().await {
+                report.critical(
+                    DefinitionKind::Provider,
+                    path,
+                    format!("the provider cannot be initialized: {e}"),
+                );
+            }
+        }
+
+        for (path, def) in report
+            .definitions::<LayerDefinition>(DefinitionKind::Layer, &data_provider.layer_defs_path)
+        {
+            report.check_layer(&mut ids, path, def);
+        }
+
+        let collections = report.definitions::<LayerCollectionDefinition>(
+            DefinitionKind::LayerCollection,
+            &data_provider.layer_collection_defs_path,
+        );
+
+        for (_, def) in &collections {
+            ids.collections.insert(def.id.clone());
+        }
+
+        for (path, def) in collections {
+            report.check_layer_collection(&ids, &path, &def);
+        }
+
+        report
+    }
+
+    /// Reports the workflow registry entries that cannot be loaded
+    pub fn check_workflows(&mut self, broken_workflows: Vec<(WorkflowId, String)>) {
+        for (id, message) in broken_workflows {
+            self.critical(
+                DefinitionKind::Workflow,
+                PathBuf::from(id.to_string()),
+                message,
+            );
+        }
+    }
+
+    pub fn num_critical(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == PreflightSeverity::Critical)
+            .count()
+    }
+
+    pub fn log(&self) {
+        for issue in &self.issues {
+            match issue.severity {
+                PreflightSeverity::Critical => error!(
+                    "Broken {:?} definition {:?}: {}",
+                    issue.kind, issue.path, issue.message
+                ),
+                PreflightSeverity::Warning => warn!(
+                    "Suspicious {:?} definition {:?}: {}",
+                    issue.kind, issue.path, issue.message
+                ),
+            }
+        }
+
+        info!(
+            "Preflight checked {} definitions: {} critical issues, {} warnings",
+            self.checked,
+            self.num_critical(),
+            self.issues.len() - self.num_critical()
+        );
+    }
+
+    /// Parses the JSON files of the `directory` and reports the files that cannot be parsed
+    fn definitions<T: DeserializeOwned>(
+        &mut self,
+        kind: DefinitionKind,
+        directory: &Path,
+    ) -> Vec<(PathBuf, T)> {
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.warning(
+                    kind,
+                    directory.to_path_buf(),
+                    format!("the directory cannot be read: {e}"),
+                );
+                return vec![];
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension() == Some(OsStr::new("json")))
+            .collect();
+        paths.sort();
+
+        let mut definitions = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            self.checked += 1;
+
+            let definition = File::open(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| {
+                    serde_json::from_reader(BufReader::new(file)).map_err(|e| e.to_string())
+                });
+
+            match definition {
+                Ok(definition) => definitions.push((path, definition)),
+                Err(message) => self.critical(kind, path, message),
+            }
+        }
+
+        definitions
+    }
+
+    fn check_layer(&mut self, ids: &mut KnownIds, path: PathBuf, def: LayerDefinition) {
+        if !ids.layers.insert(def.id.clone()) {
+            self.critical(
+                DefinitionKind::Layer,
+                path,
+                format!("the layer id {} is already defined", def.id.0),
+            );
+            return;
+        }
+
+        for data_id in def.workflow.data_ids() {
+            let known = match &data_id {
+                DataId::Internal { dataset_id } => ids.datasets.contains(dataset_id),
+                DataId::External(external_id) => ids.providers.contains(&external_id.provider_id),
+            };
+
+            if !known {
+                self.warning(
+                    DefinitionKind::Layer,
+                    path.clone(),
+                    format!("the workflow reads the undefined data {data_id:?}"),
+                );
+            }
+        }
+
+        let layer = AddLayer {
+            name: def.name,
+            description: def.description,
+            workflow: def.workflow,
+            symbology: def.symbology,
+        };
+
+        if let Err(e) = layer.validated() {
+            self.critical(DefinitionKind::Layer, path, e.to_string());
+        }
+    }
+
+    fn check_layer_collection(
+        &mut self,
+        ids: &KnownIds,
+        path: &Path,
+        def: &LayerCollectionDefinition,
+    ) {
+        for layer in def.layers.iter().filter(|l| !ids.layers.contains(l)) {
+            self.warning(
+                DefinitionKind::LayerCollection,
+                path.to_path_buf(),
+                format!("the layer {} is not defined", layer.0),
+            );
+        }
+
+        for collection in def
+            .collections
+            .iter()
+            .filter(|c| !ids.collections.contains(c))
+        {
+            self.warning(
+                DefinitionKind::LayerCollection,
+                path.to_path_buf(),
+                format!("the layer collection {} is not defined", collection.0),
+            );
+        }
+    }
+
+    fn critical(&mut self, kind: DefinitionKind, path: PathBuf, message: String) {
+        self.issues.push(PreflightIssue {
+            kind,
+            path,
+            severity: PreflightSeverity::Critical,
+            message,
+        });
+    }
+
+    fn warning(&mut self, kind: DefinitionKind, path: PathBuf, message: String) {
+        self.issues.push(PreflightIssue {
+            kind,
+            path,
+            severity: PreflightSeverity::Warning,
+            message,
+        });
+    }
+}
+
+/// Checks and logs the definitions of the `dataprovider` config and the workflow registry entries of the `ctx`.
+///
+/// Fails if a definition cannot be loaded and the `preflight` config refuses to start in that case.
+pub async fn run_preflight<C: Context>(
+    data_provider: &DataProvider,
+    ctx: &C,
+) -> Result<PreflightReport> {
+    let preflight: Preflight = get_config_element()?;
+
+    let mut report = PreflightReport::check(data_provider).await;
+    report.check_workflows(ctx.workflow_registry_ref().broken_workflows().await?);
+    report.log();
+
+    ensure!(
+        !preflight.refuse_startup_on_critical_errors || report.num_critical() == 0,
+        error::PreflightFailed {
+            num_critical: report.num_critical(),
+        }
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Identifier;
+
+    #[tokio::test]
+    async fn it_reports_broken_definitions() {
+        let directory = tempfile::tempdir().unwrap();
+        let dir = |name: &str| {
+            let path = directory.path().join(name);
+            fs::create_dir(&path).unwrap();
+            path
+        };
+
+        let data_provider = DataProvider {
+            dataset_defs_path: dir("datasets"),
+            provider_defs_path: dir("providers"),
+            layer_defs_path: dir("layers"),
+            layer_collection_defs_path: directory.path().join("missing"),
+        };
+
+        fs::write(
+            data_provider.dataset_defs_path.join("broken.json"),
+            r#"{"properties": {}}"#,
+        )
+        .unwrap();
+        fs::write(
+            data_provider.layer_defs_path.join("layer.json"),
+            serde_json::json!({
+                "id": "layer",
+                "name": "Layer",
+                "description": "A layer of an undefined dataset",
+                "workflow": {
+                    "type": "Vector",
+                    "operator": {
+                        "type": "OgrSource",
+                        "params": {
+                            "data": {
+                                "type": "internal",
+                                "datasetId": "a626c880-1c41-489b-9e19-9596d129859c"
+                            }
+                        }
+                    }
+                },
+                "symbology": null
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(data_provider.layer_defs_path.join("notes.txt"), "").unwrap();
+
+        let mut report = PreflightReport::check(&data_provider).await;
+        report.check_workflows(vec![(WorkflowId::new(), "unknown operator".to_string())]);
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.num_critical(), 2);
+        assert_eq!(
+            report
+                .issues
+                .iter()
+                .map(|issue| (issue.kind, issue.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (DefinitionKind::Dataset, PreflightSeverity::Critical),
+                (DefinitionKind::Layer, PreflightSeverity::Warning),
+                (DefinitionKind::LayerCollection, PreflightSeverity::Warning),
+                (DefinitionKind::Workflow, PreflightSeverity::Critical),
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use snafu::ensure;
+
+use crate::error::{self, Error, Result};
+use crate::util::config::{self, get_config_element};
+
+/// The field name that marks an encrypted secret in serialized definitions
+pub const SECRET_FIELD: &str = "$secret";
+
+/// The value that replaces secrets in listings
+pub const REDACTED_SECRET: &str = "********";
+
+const NONCE_LENGTH: usize = 12;
+
+/// A credential, e.g., a password or an API token, of a provider definition.
+///
+/// The plaintext is only available via [`Secret::expose`].
+/// It never appears in `Debug` output and is encrypted with the configured key when serialized,
+/// so that stored definitions do not contain it.
+/// For deserialization, both plaintext strings and encrypted secrets are accepted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(plaintext: String) -> Self {
+        Self(plaintext)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(plaintext: String) -> Self {
+        Self(plaintext)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(plaintext: &str) -> Self {
+        Self(plaintext.to_owned())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({REDACTED_SECRET})")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let encrypted = encrypt(&self.0).map_err(serde::ser::Error::custom)?;
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(SECRET_FIELD, &encrypted)?;
+        map.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecretRepresentation {
+    Plaintext(String),
+    Encrypted {
+        #[serde(rename = "$secret")]
+        encrypted: String,
+    },
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match SecretRepresentation::deserialize(deserializer)? {
+            SecretRepresentation::Plaintext(plaintext) => Ok(Self(plaintext)),
+            SecretRepresentation::Encrypted { encrypted } => decrypt(&encrypted)
+                .map(Self)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Fails if no valid key for encrypting secrets is configured, so that the server refuses to start
+/// instead of failing when credentials are stored or read
+pub fn ensure_secret_key() -> Result<()> {
+    cipher().map(|_| ())
+}
+
+fn cipher() -> Result<Aes256Gcm> {
+    let key = get_config_element::<config::Secrets>()
+        .map_err(|_| Error::MissingSecretKey)?
+        .key;
+    let key = base64::decode(key).map_err(|_| Error::InvalidSecretKey)?;
+
+    ensure!(key.len() == 32, error::InvalidSecretKey);
+
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+/// Encrypts the `plaintext` and returns the base64 encoded nonce and ciphertext
+fn encrypt(plaintext: &str) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher()?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| Error::SecretEncryption)?;
+
+    let mut encrypted = nonce.to_vec();
+    encrypted.extend(ciphertext);
+
+    Ok(base64::encode(encrypted))
+}
+
+fn decrypt(encrypted: &str) -> Result<String> {
+    let encrypted = base64::decode(encrypted).map_err(|_| Error::SecretDecryption)?;
+
+    ensure!(encrypted.len() > NONCE_LENGTH, error::SecretDecryption);
+    let (nonce, ciphertext) = encrypted.split_at(NONCE_LENGTH);
+
+    let plaintext = cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::SecretDecryption)?;
+
+    String::from_utf8(plaintext).map_err(|_| Error::SecretDecryption)
+}
+
+/// Replaces all secrets in the serialized definition with a placeholder
+pub fn redact_secrets(value: &mut serde_json::Value) {
+    if value.get(SECRET_FIELD).is_some() {
+        *value = serde_json::Value::String(REDACTED_SECRET.to_owned());
+        return;
+    }
+
+    match value {
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_secrets),
+        serde_json::Value::Array(array) => array.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Replaces the secrets of a serialized definition with new plaintexts.
+/// The secrets are addressed by their JSON pointer, e.g., `/dbConfig/password`.
+/// Only existing secrets can be replaced, other fields remain unchanged.
+pub fn replace_secrets(
+    value: &mut serde_json::Value,
+    secrets: HashMap<String, String>,
+) -> Result<()> {
+    for (pointer, plaintext) in secrets {
+        let field = value
+            .pointer_mut(&pointer)
+            .filter(|field| field.get(SECRET_FIELD).is_some())
+            .ok_or(Error::UnknownSecret {
+                pointer: pointer.clone(),
+            })?;
+
+        *field = serde_json::Value::String(plaintext);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Definition {
+        user: String,
+        password: Secret,
+    }
+
+    #[test]
+    fn it_encrypts_secrets() {
+        let definition: Definition =
+            serde_json::from_value(json!({"user": "geoengine", "password": "pwd"})).unwrap();
+        assert_eq!(definition.password.expose(), "pwd");
+        assert!(!format!("{:?}", definition).contains("pwd"));
+
+        let serialized = serde_json::to_value(&definition).unwrap();
+        assert!(!serialized.to_string().contains("pwd"));
+        assert!(serialized["password"][SECRET_FIELD].is_string());
+
+        let deserialized: Definition = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized.password, definition.password);
+    }
+
+    #[test]
+    fn it_redacts_and_replaces_secrets() {
+        let definition = Definition {
+            user: "geoengine".to_owned(),
+            password: "pwd".into(),
+        };
+
+        let mut redacted = serde_json::to_value(&definition).unwrap();
+        redact_secrets(&mut redacted);
+        assert_eq!(
+            redacted,
+            json!({"user": "geoengine", "password": REDACTED_SECRET})
+        );
+
+        let mut serialized = serde_json::to_value(&definition).unwrap();
+        assert!(replace_secrets(
+            &mut serialized,
+            [("/user".to_owned(), "admin".to_owned())].into()
+        )
+        .is_err());
+
+        replace_secrets(
+            &mut serialized,
+            [("/password".to_owned(), "new".to_owned())].into(),
+        )
+        .unwrap();
+        let updated: Definition = serde_json::from_value(serialized).unwrap();
+        assert_eq!(updated.user, "geoengine");
+        assert_eq!(updated.password.expose(), "new");
+    }
+}
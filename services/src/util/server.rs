@@ -1,6 +1,7 @@
 use crate::error::Result;
 use crate::handlers::ErrorResponse;
-use crate::util::config::get_config_element;
+use crate::util::apidoc::ApiDocCheck;
+use crate::util::config::{self, get_config_element};
 
 use actix_http::body::{BoxBody, EitherBody, MessageBody};
 use actix_http::uri::PathAndQuery;
@@ -10,6 +11,8 @@ use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
 use actix_web::error::{InternalError, JsonPayloadError, QueryPayloadError};
 use actix_web::{http, middleware, web, HttpRequest, HttpResponse};
 use futures::future::BoxFuture;
+use geoengine_datatypes::util::memory::MEMORY_ACCOUNTANT;
+use geoengine_operators::util::gdal_dataset_pool::GDAL_DATASET_POOL;
 use log::debug;
 
 use std::any::Any;
@@ -194,6 +197,96 @@ pub(crate) fn server_info() -> ServerInfo {
     }
 }
 
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MemoryMetrics {
+    /// The bytes that are held by raster grids and feature collections
+    pub(crate) allocated_bytes: usize,
+    /// Queries are rejected while the allocated bytes exceed the budget
+    pub(crate) budget_bytes: Option<usize>,
+}
+
+/// Shows the memory that is held by the data of running queries.
+#[utoipa::path(
+    tag = "General",
+    get,
+    path = "/metrics/memory",
+    responses(
+        (status = 200, description = "Memory metrics", body = MemoryMetrics,
+            example = json!({
+                "allocatedBytes": 268_435_456,
+                "budgetBytes": 4_294_967_296_u64
+              }))
+    )
+)]
+#[allow(clippy::unused_async)] // the function signature of request handlers requires it
+pub(crate) async fn memory_metrics_handler() -> impl actix_web::Responder {
+    web::Json(MemoryMetrics {
+        allocated_bytes: MEMORY_ACCOUNTANT.allocated_bytes(),
+        budget_bytes: MEMORY_ACCOUNTANT.budget_bytes(),
+    })
+}
+
+#[derive(serde::Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GdalDatasetPoolMetrics {
+    /// The number of datasets that were opened
+    pub(crate) opened: usize,
+    /// The number of reads that reused an open dataset
+    pub(crate) reused: usize,
+    /// The number of datasets that were closed because the pool was full or they were idle for too long
+    pub(crate) evicted: usize,
+    /// The number of datasets that were closed because their file changed or vanished
+    pub(crate) unhealthy: usize,
+    pub(crate) idle: usize,
+    pub(crate) in_use: usize,
+}
+
+/// Shows the usage of the pool of open GDAL datasets.
+#[utoipa::path(
+    tag = "General",
+    get,
+    path = "/metrics/gdal",
+    responses(
+        (status = 200, description = "GDAL dataset pool metrics", body = GdalDatasetPoolMetrics,
+            example = json!({
+                "opened": 12,
+                "reused": 4711,
+                "evicted": 3,
+                "unhealthy": 0,
+                "idle": 8,
+                "inUse": 1
+              }))
+    )
+)]
+#[allow(clippy::unused_async)] // the function signature of request handlers requires it
+pub(crate) async fn gdal_dataset_pool_metrics_handler() -> impl actix_web::Responder {
+    let metrics = GDAL_DATASET_POOL.metrics();
+
+    web::Json(GdalDatasetPoolMetrics {
+        opened: metrics.opened,
+        reused: metrics.reused,
+        evicted: metrics.evicted,
+        unhealthy: metrics.unhealthy,
+        idle: metrics.idle,
+        in_use: metrics.in_use,
+    })
+}
+
+/// Configures the pool of open GDAL datasets and the size of the GDAL block cache
+pub fn configure_gdal_dataset_pool() -> Result<()> {
+    let pool_config = get_config_element::<config::GdalDatasetPool>()?;
+
+    if let Some(cache_max_megabytes) = pool_config.cache_max_megabytes {
+        // values below 100000 are interpreted as megabytes
+        gdal::config::set_config_option("GDAL_CACHEMAX", &cache_max_megabytes.to_string())?;
+    }
+
+    GDAL_DATASET_POOL.configure(pool_config.into());
+
+    Ok(())
+}
+
 #[allow(clippy::unnecessary_wraps)]
 pub(crate) fn render_404(
     mut response: ServiceResponse,
@@ -256,6 +349,23 @@ pub fn serve_openapi_json<
     )
 }
 
+/// Serves a self-check of the `openapi` document, e.g., to detect handlers whose parameters do not match their paths
+pub fn serve_openapi_check<
+    T: ServiceFactory<ServiceRequest, Config = (), Error = actix_web::Error, InitError = ()>,
+>(
+    app: actix_web::App<T>,
+    serve_url: &str,
+    openapi: OpenApi,
+) -> actix_web::App<T> {
+    app.route(
+        serve_url,
+        web::get().to(move || {
+            let check = ApiDocCheck::new(&openapi);
+            async move { web::Json(check) }
+        }),
+    )
+}
+
 pub(crate) fn log_server_info() -> Result<()> {
     let web_config: crate::util::config::Web = get_config_element()?;
 
@@ -101,7 +101,7 @@ pub async fn register_ndvi_workflow_helper(ctx: &InMemoryContext) -> (Workflow,
 
     let id = ctx
         .workflow_registry_ref()
-        .register(workflow.clone())
+        .register(&SimpleSession::default(), workflow.clone())
         .await
         .unwrap();
 
@@ -121,6 +121,7 @@ pub async fn add_ndvi_to_datasets(ctx: &InMemoryContext) -> DatasetId {
                 license: "Sample License".to_owned(),
                 uri: "http://example.org/".to_owned(),
             }),
+            temporary: false,
         },
         meta_data: MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data()),
     };
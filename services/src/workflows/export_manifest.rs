@@ -0,0 +1,153 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use geoengine_datatypes::primitives::DateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+use walkdir::WalkDir;
+
+use crate::datasets::listing::ProvenanceOutput;
+use crate::error::{self, Result};
+use crate::workflows::workflow::{Workflow, WorkflowId};
+
+/// The file name of the manifest inside the export directory
+pub const EXPORT_MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Describes an export s.t. it can be audited and reproduced later:
+/// it contains the workflow, the provenance of its data, the export parameters and a checksum for each written file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub created: DateTime,
+    pub geoengine_version: String,
+    pub workflow_id: WorkflowId,
+    pub workflow: Workflow,
+    pub provenance: Vec<ProvenanceOutput>,
+    /// the parameters of the export request, including the query
+    pub parameters: serde_json::Value,
+    pub files: Vec<ExportedFile>,
+}
+
+/// A file of an export, e.g., a single chunk of a Zarr store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFile {
+    /// path relative to the export directory with `/` as separator
+    pub path: String,
+    pub size: u64,
+    /// hex-encoded SHA-256 digest of the file content
+    pub sha256: String,
+}
+
+impl ExportManifest {
+    /// Creates the manifest for all files in `export_dir`.
+    /// A manifest of a previous run is not part of the files.
+    pub fn new(
+        export_dir: &Path,
+        workflow_id: WorkflowId,
+        workflow: Workflow,
+        provenance: Vec<ProvenanceOutput>,
+        parameters: serde_json::Value,
+    ) -> Result<Self> {
+        Ok(Self {
+            created: DateTime::now(),
+            geoengine_version: env!("CARGO_PKG_VERSION").to_string(),
+            workflow_id,
+            workflow,
+            provenance,
+            parameters,
+            files: checksum_files(export_dir)?,
+        })
+    }
+
+    /// Writes the manifest as [`EXPORT_MANIFEST_FILE_NAME`] into `export_dir`
+    pub fn write(&self, export_dir: &Path) -> Result<()> {
+        let file = File::create(export_dir.join(EXPORT_MANIFEST_FILE_NAME)).context(error::Io)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
+/// Computes the checksums of all files in `dir` and its subdirectories, ordered by path
+fn checksum_files(dir: &Path) -> Result<Vec<ExportedFile>> {
+    let mut files = Vec::new();
+
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.map_err(|error| error::Error::Io {
+            source: error.into(),
+        })?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(dir)
+            .expect("entries are inside of `dir`");
+
+        if relative_path == Path::new(EXPORT_MANIFEST_FILE_NAME) {
+            continue;
+        }
+
+        let mut reader = BufReader::new(File::open(entry.path()).context(error::Io)?);
+        let mut hasher = Sha256::new();
+        let mut buffer = [0; 8192];
+        let mut size = 0;
+
+        loop {
+            let bytes_read = reader.read(&mut buffer).context(error::Io)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+            size += bytes_read as u64;
+        }
+
+        files.push(ExportedFile {
+            path: relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            size,
+            sha256: format!("{:x}", hasher.finalize()),
+        });
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checksums_all_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("raster.zarr")).unwrap();
+        std::fs::write(dir.path().join("raster.zarr/0.0.0"), b"abc").unwrap();
+        std::fs::write(dir.path().join("raster.zarr/.zarray"), b"").unwrap();
+        std::fs::write(dir.path().join(EXPORT_MANIFEST_FILE_NAME), b"{}").unwrap();
+
+        assert_eq!(
+            checksum_files(dir.path()).unwrap(),
+            vec![
+                ExportedFile {
+                    path: "raster.zarr/.zarray".to_string(),
+                    size: 0,
+                    sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                        .to_string(),
+                },
+                ExportedFile {
+                    path: "raster.zarr/0.0.0".to_string(),
+                    size: 3,
+                    sha256: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -1,2 +1,9 @@
+pub mod export_manifest;
+pub mod plot_cache;
+pub mod query_log;
 pub mod registry;
+pub mod replay;
+pub mod style_preset;
+pub mod subscription;
+pub mod template;
 pub mod workflow;
@@ -0,0 +1,189 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use geoengine_datatypes::primitives::VectorQueryRectangle;
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::util::config::{get_config_element, Plots};
+use crate::workflows::workflow::WorkflowId;
+
+lazy_static::lazy_static! {
+    /// The process-wide cache of plot results, configured by the `plots` config
+    pub static ref PLOT_CACHE: PlotCache = get_config_element::<Plots>().map_or_else(
+        |_| PlotCache::new(0, None),
+        |plots| {
+            PlotCache::new(
+                plots.cache_size,
+                (plots.cache_ttl_seconds > 0).then(|| Duration::from_secs(plots.cache_ttl_seconds)),
+            )
+        },
+    );
+}
+
+/// Identifies a plot by its workflow and the query rectangle in the spatial reference of the workflow
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlotCacheKey {
+    workflow: WorkflowId,
+    /// the serialized query rectangle, since it contains floats
+    query: String,
+}
+
+impl PlotCacheKey {
+    pub fn new(workflow: WorkflowId, query: &VectorQueryRectangle) -> Result<Self> {
+        Ok(Self {
+            workflow,
+            query: serde_json::to_string(query)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CachedPlot {
+    created: Instant,
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct PlotCacheEntries {
+    plots: HashMap<PlotCacheKey, CachedPlot>,
+    insertion_order: VecDeque<PlotCacheKey>,
+}
+
+/// Stores the data of computed plots, so that refreshing dashboards does not recompute them.
+/// Plots expire after the time to live and are invalidated when their workflow or its data changes.
+/// If the capacity is exceeded, the oldest plots are evicted. A capacity of zero disables the cache.
+#[derive(Debug)]
+pub struct PlotCache {
+    capacity: usize,
+    time_to_live: Option<Duration>,
+    entries: Mutex<PlotCacheEntries>,
+}
+
+impl PlotCache {
+    pub fn new(capacity: usize, time_to_live: Option<Duration>) -> Self {
+        Self {
+            capacity,
+            time_to_live,
+            entries: Mutex::new(PlotCacheEntries::default()),
+        }
+    }
+
+    pub async fn get(&self, key: &PlotCacheKey) -> Option<serde_json::Value> {
+        if self.capacity == 0 {
+            return None;
+        }
+
+        let entries = self.entries.lock().await;
+
+        entries
+            .plots
+            .get(key)
+            .filter(|plot| {
+                self.time_to_live
+                    .map_or(true, |time_to_live| plot.created.elapsed() < time_to_live)
+            })
+            .map(|plot| plot.data.clone())
+    }
+
+    pub async fn insert(&self, key: PlotCacheKey, data: serde_json::Value) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().await;
+
+        let plot = CachedPlot {
+            created: Instant::now(),
+            data,
+        };
+
+        if entries.plots.insert(key.clone(), plot).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+
+        while entries.plots.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                entries.plots.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Removes all plots of the workflow, e.g., when it was superseded by a new version or its data changed
+    pub async fn invalidate(&self, workflow: WorkflowId) {
+        let mut entries = self.entries.lock().await;
+
+        entries.plots.retain(|key, _| key.workflow != workflow);
+        entries
+            .insertion_order
+            .retain(|key| key.workflow != workflow);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::util::Identifier;
+
+    fn query(x: f64) -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (x, x).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_evicts_and_invalidates_plots() {
+        let cache = PlotCache::new(2, None);
+
+        let workflow = WorkflowId::new();
+        let other_workflow = WorkflowId::new();
+
+        let key = PlotCacheKey::new(workflow, &query(1.)).unwrap();
+        let other_query_key = PlotCacheKey::new(workflow, &query(2.)).unwrap();
+        let other_workflow_key = PlotCacheKey::new(other_workflow, &query(1.)).unwrap();
+
+        cache.insert(key.clone(), serde_json::json!(1)).await;
+        assert_eq!(cache.get(&key).await, Some(serde_json::json!(1)));
+        assert_eq!(cache.get(&other_query_key).await, None);
+
+        cache
+            .insert(other_query_key.clone(), serde_json::json!(2))
+            .await;
+        cache
+            .insert(other_workflow_key.clone(), serde_json::json!(3))
+            .await;
+        assert_eq!(cache.get(&key).await, None);
+
+        cache.invalidate(workflow).await;
+        assert_eq!(cache.get(&other_query_key).await, None);
+        assert_eq!(
+            cache.get(&other_workflow_key).await,
+            Some(serde_json::json!(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_expires_plots() {
+        let cache = PlotCache::new(1, Some(Duration::ZERO));
+
+        let key = PlotCacheKey::new(WorkflowId::new(), &query(1.)).unwrap();
+        cache.insert(key.clone(), serde_json::json!(1)).await;
+
+        assert_eq!(cache.get(&key).await, None);
+    }
+
+    #[tokio::test]
+    async fn it_is_disabled_without_capacity() {
+        let cache = PlotCache::new(0, None);
+
+        let key = PlotCacheKey::new(WorkflowId::new(), &query(1.)).unwrap();
+        cache.insert(key.clone(), serde_json::json!(1)).await;
+
+        assert_eq!(cache.get(&key).await, None);
+    }
+}
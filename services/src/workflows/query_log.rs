@@ -0,0 +1,220 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::TypedOperator;
+use geoengine_operators::util::safe_lock_mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::util::config::{self, get_config_element};
+use crate::workflows::workflow::Workflow;
+
+lazy_static::lazy_static! {
+    /// The process-wide log of sampled queries, configured by the `query_log` config
+    pub static ref QUERY_LOG: QueryLog = get_config_element::<config::QueryLog>()
+        .ok()
+        .filter(|config| config.enabled)
+        .map_or_else(QueryLog::disabled, |config| {
+            QueryLog::new(config.path, config.sampling_interval)
+        });
+}
+
+/// A query rectangle together with the kind of result that was queried
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "query")]
+pub enum RecordedQuery {
+    Raster(RasterQueryRectangle),
+    Vector(VectorQueryRectangle),
+    Plot(VectorQueryRectangle),
+}
+
+/// A recorded query of a workflow.
+/// The query rectangle is given in the `spatial_reference`, which may differ from the one of the workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLogEntry {
+    pub recorded_at: DateTime<Utc>,
+    pub workflow: Workflow,
+    pub spatial_reference: SpatialReference,
+    pub query: RecordedQuery,
+    /// The time for initializing the workflow and executing the query
+    pub duration_ms: u64,
+}
+
+/// Appends sampled queries as JSON lines to a file
+#[derive(Debug)]
+pub struct QueryLog {
+    path: Option<PathBuf>,
+    sampling_interval: u64,
+    query_count: AtomicU64,
+    file_lock: Arc<Mutex<()>>,
+}
+
+impl QueryLog {
+    /// Creates a log that records every `sampling_interval`-th query to the file at `path`
+    pub fn new(path: PathBuf, sampling_interval: u64) -> Self {
+        Self {
+            path: Some(path),
+            sampling_interval: sampling_interval.max(1),
+            query_count: AtomicU64::new(0),
+            file_lock: Default::default(),
+        }
+    }
+
+    /// Creates a log that records no queries
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            sampling_interval: 1,
+            query_count: AtomicU64::new(0),
+            file_lock: Default::default(),
+        }
+    }
+
+    /// Starts the recording of a query if it is sampled.
+    /// The workflow is only captured for sampled queries.
+    pub fn start_recording(
+        &self,
+        operator: impl FnOnce() -> TypedOperator,
+    ) -> Option<QueryRecording<'_>> {
+        self.path.as_ref()?;
+
+        let query_count = self.query_count.fetch_add(1, Ordering::Relaxed);
+        if query_count % self.sampling_interval != 0 {
+            return None;
+        }
+
+        Some(QueryRecording {
+            log: self,
+            workflow: Workflow {
+                operator: operator(),
+            },
+            recorded_at: Utc::now(),
+            started: Instant::now(),
+        })
+    }
+
+    pub async fn append(&self, entry: &QueryLogEntry) -> Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let file_lock = self.file_lock.clone();
+
+        crate::util::spawn_blocking(move || -> Result<()> {
+            let _guard = safe_lock_mutex(&file_lock);
+
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(&line)?;
+
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Reads all entries of a query log file
+pub async fn read_query_log(path: &Path) -> Result<Vec<QueryLogEntry>> {
+    let content = tokio::fs::read_to_string(path).await?;
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(Into::into))
+        .collect()
+}
+
+/// A sampled query that is written to the log when it is finished
+pub struct QueryRecording<'l> {
+    log: &'l QueryLog,
+    workflow: Workflow,
+    recorded_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl QueryRecording<'_> {
+    /// Appends the query to the log. Failures are only logged, so that they do not affect the query.
+    pub async fn finish(self, spatial_reference: SpatialReference, query: RecordedQuery) {
+        let entry = QueryLogEntry {
+            recorded_at: self.recorded_at,
+            workflow: self.workflow,
+            spatial_reference,
+            query,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+        };
+
+        if let Err(error) = self.log.append(&entry).await {
+            log::warn!("Could not record query in query log: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+    use geoengine_operators::engine::VectorOperator;
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+
+    #[tokio::test]
+    async fn it_records_sampled_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("query_log.jsonl");
+
+        let log = QueryLog::new(path.clone(), 2);
+
+        let query = RecordedQuery::Vector(VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        });
+
+        for _ in 0..3 {
+            let recording = log.start_recording(|| {
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(0.5, 0.5).into()],
+                    },
+                }
+                .boxed()
+                .into()
+            });
+
+            if let Some(recording) = recording {
+                recording.finish(SpatialReference::epsg_4326(), query).await;
+            }
+        }
+
+        let entries = read_query_log(&path).await.unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].query, query);
+        assert_eq!(entries[0].spatial_reference, SpatialReference::epsg_4326());
+        assert!(matches!(
+            entries[0].workflow.operator,
+            TypedOperator::Vector(_)
+        ));
+    }
+
+    #[test]
+    fn it_records_nothing_if_disabled() {
+        let log = QueryLog::disabled();
+
+        assert!(log
+            .start_recording(|| unreachable!("the workflow must not be captured"))
+            .is_none());
+    }
+}
@@ -1,36 +1,821 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
 
-use super::workflow::{Workflow, WorkflowId};
-use crate::contexts::Db;
+use super::style_preset::{StylePreset, StylePresetName};
+use super::template::{
+    WorkflowTemplate, WorkflowTemplateId, WorkflowTemplateListOptions, WorkflowTemplateListing,
+};
+use super::workflow::{Workflow, WorkflowAlias, WorkflowId};
+use crate::api::model::datatypes::DataId;
+use crate::contexts::{Db, Session};
+use crate::datasets::listing::OrderBy;
 use crate::error;
 use crate::error::Result;
+use crate::projects::Symbology;
+use crate::util::config::{self, get_config_element};
+use crate::util::user_input::Validated;
+use crate::util::Identifier;
 use async_trait::async_trait;
 
 #[async_trait]
-pub trait WorkflowRegistry: Send + Sync {
-    async fn register(&self, workflow: Workflow) -> Result<WorkflowId>;
-    async fn load(&self, id: &WorkflowId) -> Result<Workflow>;
+pub trait WorkflowRegistry<S: Session>: Send + Sync {
+    async fn register(&self, session: &S, workflow: Workflow) -> Result<WorkflowId>;
+    async fn load(&self, session: &S, id: &WorkflowId) -> Result<Workflow>;
+
+    /// Returns the ids of all data that is read by `workflow`, including the data of the workflows
+    /// that it embeds with `WorkflowReference` operators
+    async fn resolved_data_ids(&self, session: &S, workflow: &Workflow) -> Result<Vec<DataId>> {
+        let mut data_ids = workflow.data_ids();
+
+        let mut resolved = HashSet::new();
+        let mut references = workflow.workflow_references();
+        while let Some(reference) = references.pop() {
+            if !resolved.insert(reference) {
+                continue;
+            }
+
+            let referenced = self.load(session, &reference).await?;
+
+            for data_id in referenced.data_ids() {
+                if !data_ids.contains(&data_id) {
+                    data_ids.push(data_id);
+                }
+            }
+            references.extend(referenced.workflow_references());
+        }
+
+        Ok(data_ids)
+    }
+
+    /// Lists the persisted workflows of all tenants that cannot be loaded, e.g., because their operators changed,
+    /// together with the reason
+    async fn broken_workflows(&self) -> Result<Vec<(WorkflowId, String)>>;
+
+    /// Keeps `workflow` in memory for a limited time without persisting it.
+    ///
+    /// A transient workflow can be loaded and queried like a registered workflow,
+    /// e.g., for comparing the results of a workflow with substituted sources.
+    async fn register_transient(&self, session: &S, workflow: Workflow) -> Result<WorkflowId>;
+
+    /// Lists all registered workflows that read the data with the given `data_id`.
+    ///
+    /// This allows finding the workflows that are affected by updating or removing a dataset.
+    async fn dependent_workflows(&self, session: &S, data_id: &DataId) -> Result<Vec<WorkflowId>>;
+
+    /// Registers `workflow` as a new version of the workflow `parent`.
+    ///
+    /// All aliases that point to `parent` are moved to the new version.
+    async fn register_version(
+        &self,
+        session: &S,
+        parent: &WorkflowId,
+        workflow: Workflow,
+    ) -> Result<WorkflowId>;
+
+    /// Lists the version chain of the workflow `id`, starting with `id` itself and ending with its first version.
+    async fn versions(&self, session: &S, id: &WorkflowId) -> Result<Vec<WorkflowId>>;
+
+    /// Lets the (validated) `alias` point to the workflow `id`
+    async fn set_alias(
+        &self,
+        session: &S,
+        alias: Validated<WorkflowAlias>,
+        id: &WorkflowId,
+    ) -> Result<()>;
+
+    async fn resolve_alias(&self, session: &S, alias: &WorkflowAlias) -> Result<WorkflowId>;
+
+    /// Stores the `symbology` as the (validated) style preset `name` of the workflow `id`,
+    /// replacing an existing preset with the same name
+    async fn set_style_preset(
+        &self,
+        session: &S,
+        id: &WorkflowId,
+        name: Validated<StylePresetName>,
+        symbology: Symbology,
+    ) -> Result<()>;
+
+    async fn style_preset(
+        &self,
+        session: &S,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<Symbology>;
+
+    /// Lists the style presets of the workflow `id`, ordered by name
+    async fn list_style_presets(&self, session: &S, id: &WorkflowId) -> Result<Vec<StylePreset>>;
+
+    async fn remove_style_preset(
+        &self,
+        session: &S,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<()>;
+
+    /// Stores a (validated) workflow template under a new id
+    async fn register_template(
+        &self,
+        session: &S,
+        template: Validated<WorkflowTemplate>,
+    ) -> Result<WorkflowTemplateId>;
+
+    async fn load_template(&self, session: &S, id: &WorkflowTemplateId)
+        -> Result<WorkflowTemplate>;
+
+    /// Lists the templates whose name or description contains the filter of the `options`
+    async fn list_templates(
+        &self,
+        session: &S,
+        options: Validated<WorkflowTemplateListOptions>,
+    ) -> Result<Vec<WorkflowTemplateListing>>;
+}
+
+/// Workflows that are only kept in memory until their lifetime expires
+#[derive(Default)]
+pub struct TransientWorkflows {
+    workflows: Db<HashMap<WorkflowId, (Workflow, Instant)>>,
+}
+
+impl TransientWorkflows {
+    pub async fn insert(&self, workflow: Workflow) -> Result<WorkflowId> {
+        let lifetime = Duration::from_secs(
+            get_config_element::<config::WorkflowSubstitution>()?.transient_lifetime_seconds,
+        );
+
+        self.insert_with_lifetime(workflow, lifetime).await
+    }
+
+    async fn insert_with_lifetime(
+        &self,
+        workflow: Workflow,
+        lifetime: Duration,
+    ) -> Result<WorkflowId> {
+        let id = WorkflowId::from_hash(&workflow);
+        let now = Instant::now();
+
+        let mut workflows = self.workflows.write().await;
+        workflows.retain(|_, (_, expiration)| *expiration > now);
+        workflows.insert(id, (workflow, now + lifetime));
+
+        Ok(id)
+    }
+
+    pub async fn get(&self, id: &WorkflowId) -> Option<Workflow> {
+        self.workflows
+            .read()
+            .await
+            .get(id)
+            .filter(|(_, expiration)| *expiration > Instant::now())
+            .map(|(workflow, _)| workflow.clone())
+    }
 }
 
 #[derive(Default)]
 pub struct HashMapRegistry {
     map: Db<HashMap<WorkflowId, Workflow>>,
+    dependencies: Db<HashMap<DataId, HashSet<WorkflowId>>>,
+    parents: Db<HashMap<WorkflowId, WorkflowId>>,
+    aliases: Db<HashMap<WorkflowAlias, WorkflowId>>,
+    style_presets: Db<HashMap<WorkflowId, BTreeMap<StylePresetName, Symbology>>>,
+    templates: Db<HashMap<WorkflowTemplateId, WorkflowTemplate>>,
+    transient: TransientWorkflows,
+}
+
+impl HashMapRegistry {
+    fn version_chain(parents: &HashMap<WorkflowId, WorkflowId>, id: WorkflowId) -> Vec<WorkflowId> {
+        let mut versions = vec![id];
+        let mut current = id;
+
+        while let Some(parent) = parents.get(&current) {
+            versions.push(*parent);
+            current = *parent;
+        }
+
+        versions
+    }
 }
 
 #[async_trait]
-impl WorkflowRegistry for HashMapRegistry {
-    async fn register(&self, workflow: Workflow) -> Result<WorkflowId> {
+impl<S: Session> WorkflowRegistry<S> for HashMapRegistry {
+    async fn register(&self, session: &S, workflow: Workflow) -> Result<WorkflowId> {
         let id = WorkflowId::from_hash(&workflow);
+        let data_ids = self.resolved_data_ids(session, &workflow).await?;
+
+        let mut dependencies = self.dependencies.write().await;
+        for data_id in data_ids {
+            dependencies.entry(data_id).or_default().insert(id);
+        }
+
         self.map.write().await.insert(id, workflow);
         Ok(id)
     }
 
-    async fn load(&self, id: &WorkflowId) -> Result<Workflow> {
-        self.map
+    async fn load(&self, _session: &S, id: &WorkflowId) -> Result<Workflow> {
+        if let Some(workflow) = self.map.read().await.get(id) {
+            return Ok(workflow.clone());
+        }
+
+        self.transient
+            .get(id)
+            .await
+            .ok_or(error::Error::NoWorkflowForGivenId)
+    }
+
+    async fn broken_workflows(&self) -> Result<Vec<(WorkflowId, String)>> {
+        // the workflows are kept deserialized in memory
+        Ok(vec![])
+    }
+
+    async fn register_transient(&self, _session: &S, workflow: Workflow) -> Result<WorkflowId> {
+        self.transient.insert(workflow).await
+    }
+
+    async fn dependent_workflows(&self, _session: &S, data_id: &DataId) -> Result<Vec<WorkflowId>> {
+        Ok(self
+            .dependencies
+            .read()
+            .await
+            .get(data_id)
+            .map(|workflows| workflows.iter().copied().collect())
+            .unwrap_or_default())
+    }
+
+    async fn register_version(
+        &self,
+        session: &S,
+        parent: &WorkflowId,
+        workflow: Workflow,
+    ) -> Result<WorkflowId> {
+        self.load(session, parent).await?;
+
+        let id = self.register(session, workflow).await?;
+
+        let mut parents = self.parents.write().await;
+
+        // a workflow has exactly one parent and the chain must not contain cycles
+        if !parents.contains_key(&id) && !Self::version_chain(&parents, *parent).contains(&id) {
+            parents.insert(id, *parent);
+        }
+
+        for target in self.aliases.write().await.values_mut() {
+            if target == parent {
+                *target = id;
+            }
+        }
+
+        Ok(id)
+    }
+
+    async fn versions(&self, session: &S, id: &WorkflowId) -> Result<Vec<WorkflowId>> {
+        self.load(session, id).await?;
+
+        Ok(Self::version_chain(&*self.parents.read().await, *id))
+    }
+
+    async fn set_alias(
+        &self,
+        session: &S,
+        alias: Validated<WorkflowAlias>,
+        id: &WorkflowId,
+    ) -> Result<()> {
+        self.load(session, id).await?;
+
+        self.aliases.write().await.insert(alias.user_input, *id);
+
+        Ok(())
+    }
+
+    async fn resolve_alias(&self, _session: &S, alias: &WorkflowAlias) -> Result<WorkflowId> {
+        self.aliases
+            .read()
+            .await
+            .get(alias)
+            .copied()
+            .ok_or_else(|| error::Error::UnknownWorkflowAlias {
+                alias: alias.to_string(),
+            })
+    }
+
+    async fn set_style_preset(
+        &self,
+        session: &S,
+        id: &WorkflowId,
+        name: Validated<StylePresetName>,
+        symbology: Symbology,
+    ) -> Result<()> {
+        self.load(session, id).await?;
+
+        self.style_presets
+            .write()
+            .await
+            .entry(*id)
+            .or_default()
+            .insert(name.user_input, symbology);
+
+        Ok(())
+    }
+
+    async fn style_preset(
+        &self,
+        _session: &S,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<Symbology> {
+        self.style_presets
             .read()
             .await
             .get(id)
+            .and_then(|presets| presets.get(name))
             .cloned()
-            .ok_or(error::Error::NoWorkflowForGivenId)
+            .ok_or_else(|| error::Error::UnknownStylePreset {
+                workflow: *id,
+                name: name.to_string(),
+            })
+    }
+
+    async fn list_style_presets(&self, session: &S, id: &WorkflowId) -> Result<Vec<StylePreset>> {
+        self.load(session, id).await?;
+
+        Ok(self
+            .style_presets
+            .read()
+            .await
+            .get(id)
+            .map(|presets| {
+                presets
+                    .iter()
+                    .map(|(name, symbology)| StylePreset {
+                        name: name.clone(),
+                        symbology: symbology.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn remove_style_preset(
+        &self,
+        _session: &S,
+        id: &WorkflowId,
+        name: &StylePresetName,
+    ) -> Result<()> {
+        self.style_presets
+            .write()
+            .await
+            .get_mut(id)
+            .and_then(|presets| presets.remove(name))
+            .map(|_| ())
+            .ok_or_else(|| error::Error::UnknownStylePreset {
+                workflow: *id,
+                name: name.to_string(),
+            })
+    }
+
+    async fn register_template(
+        &self,
+        _session: &S,
+        template: Validated<WorkflowTemplate>,
+    ) -> Result<WorkflowTemplateId> {
+        let id = WorkflowTemplateId::new();
+
+        self.templates.write().await.insert(id, template.user_input);
+
+        Ok(id)
+    }
+
+    async fn load_template(
+        &self,
+        _session: &S,
+        id: &WorkflowTemplateId,
+    ) -> Result<WorkflowTemplate> {
+        self.templates
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or(error::Error::NoWorkflowTemplateForGivenId)
+    }
+
+    async fn list_templates(
+        &self,
+        _session: &S,
+        options: Validated<WorkflowTemplateListOptions>,
+    ) -> Result<Vec<WorkflowTemplateListing>> {
+        let options = options.user_input;
+
+        let templates = self.templates.read().await;
+
+        let mut list: Vec<_> = templates
+            .iter()
+            .filter(|(_, t)| {
+                options.filter.as_ref().map_or(true, |filter| {
+                    t.name.contains(filter) || t.description.contains(filter)
+                })
+            })
+            .collect();
+
+        match options.order {
+            OrderBy::NameAsc => list.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name)),
+            OrderBy::NameDesc => list.sort_by(|(_, a), (_, b)| b.name.cmp(&a.name)),
+        };
+
+        Ok(list
+            .into_iter()
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|(id, t)| t.listing(*id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::datatypes::Colorizer;
+    use crate::api::model::datatypes::DatasetId;
+    use crate::contexts::SimpleSession;
+    use crate::projects::RasterSymbology;
+    use crate::util::user_input::UserInput;
+    use geoengine_operators::engine::{RasterOperator, TypedOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+    use geoengine_operators::source::{
+        GdalSource, GdalSourceParameters, WorkflowReference, WorkflowReferenceParameters,
+    };
+
+    #[tokio::test]
+    async fn it_tracks_dependent_workflows() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let dataset_id = DatasetId::new();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(
+                GdalSource {
+                    params: GdalSourceParameters {
+                        data: dataset_id.into(),
+                    },
+                }
+                .boxed(),
+            ),
+        };
+        let workflow_id = registry.register(&session, workflow).await.unwrap();
+
+        let other_workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(0.0, 0.1).into()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+        registry.register(&session, other_workflow).await.unwrap();
+
+        assert_eq!(
+            registry
+                .dependent_workflows(&session, &dataset_id.into())
+                .await
+                .unwrap(),
+            vec![workflow_id]
+        );
+
+        assert!(registry
+            .dependent_workflows(&session, &DatasetId::new().into())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_tracks_the_data_of_referenced_workflows() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let dataset_id = DatasetId::new();
+
+        let referenced_id = registry
+            .register(
+                &session,
+                Workflow {
+                    operator: TypedOperator::Raster(
+                        GdalSource {
+                            params: GdalSourceParameters {
+                                data: dataset_id.into(),
+                            },
+                        }
+                        .boxed(),
+                    ),
+                },
+            )
+            .await
+            .unwrap();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(RasterOperator::boxed(WorkflowReference {
+                params: WorkflowReferenceParameters {
+                    workflow: referenced_id.0,
+                },
+            })),
+        };
+
+        assert_eq!(workflow.workflow_references(), vec![referenced_id]);
+        assert_eq!(
+            registry
+                .resolved_data_ids(&session, &workflow)
+                .await
+                .unwrap(),
+            vec![dataset_id.into()]
+        );
+
+        let workflow_id = registry.register(&session, workflow).await.unwrap();
+
+        let dependent_workflows = registry
+            .dependent_workflows(&session, &dataset_id.into())
+            .await
+            .unwrap();
+        assert_eq!(dependent_workflows.len(), 2);
+        assert!(dependent_workflows.contains(&referenced_id));
+        assert!(dependent_workflows.contains(&workflow_id));
+    }
+
+    fn mock_point_workflow(x: f64) -> Workflow {
+        Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(x, 0.1).into()],
+                    },
+                }
+                .boxed(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_expires_transient_workflows() {
+        let transient = TransientWorkflows::default();
+
+        let kept = transient
+            .insert_with_lifetime(mock_point_workflow(1.), Duration::from_secs(3600))
+            .await
+            .unwrap();
+        let expired = transient
+            .insert_with_lifetime(mock_point_workflow(2.), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(transient.get(&kept).await, Some(mock_point_workflow(1.)));
+        assert_eq!(transient.get(&expired).await, None);
+    }
+
+    #[tokio::test]
+    async fn it_loads_transient_workflows() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let id = registry
+            .register_transient(&session, mock_point_workflow(1.))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry.load(&session, &id).await.unwrap(),
+            mock_point_workflow(1.)
+        );
+        assert!(registry
+            .dependent_workflows(&session, &DatasetId::new().into())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_tracks_versions() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let v1 = registry
+            .register(&session, mock_point_workflow(1.))
+            .await
+            .unwrap();
+        let v2 = registry
+            .register_version(&session, &v1, mock_point_workflow(2.))
+            .await
+            .unwrap();
+        let v3 = registry
+            .register_version(&session, &v2, mock_point_workflow(3.))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry.versions(&session, &v3).await.unwrap(),
+            vec![v3, v2, v1]
+        );
+        assert_eq!(registry.versions(&session, &v1).await.unwrap(), vec![v1]);
+
+        // registering an old version again must not create a cycle
+        let v1_again = registry
+            .register_version(&session, &v3, mock_point_workflow(1.))
+            .await
+            .unwrap();
+        assert_eq!(v1_again, v1);
+        assert_eq!(
+            registry.versions(&session, &v3).await.unwrap(),
+            vec![v3, v2, v1]
+        );
+
+        assert!(registry
+            .register_version(&session, &WorkflowId::new(), mock_point_workflow(4.))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_moves_aliases_to_latest_version() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let alias = WorkflowAlias::from("my-analysis");
+
+        assert!(registry.resolve_alias(&session, &alias).await.is_err());
+
+        let v1 = registry
+            .register(&session, mock_point_workflow(1.))
+            .await
+            .unwrap();
+        registry
+            .set_alias(&session, alias.clone().validated().unwrap(), &v1)
+            .await
+            .unwrap();
+
+        assert_eq!(registry.resolve_alias(&session, &alias).await.unwrap(), v1);
+
+        let v2 = registry
+            .register_version(&session, &v1, mock_point_workflow(2.))
+            .await
+            .unwrap();
+
+        assert_eq!(registry.resolve_alias(&session, &alias).await.unwrap(), v2);
+    }
+
+    #[tokio::test]
+    async fn it_stores_style_presets() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let id = registry
+            .register(&session, mock_point_workflow(1.))
+            .await
+            .unwrap();
+
+        let rgba = Symbology::Raster(RasterSymbology {
+            opacity: 1.0,
+            colorizer: Colorizer::Rgba,
+        });
+        let transparent = Symbology::Raster(RasterSymbology {
+            opacity: 0.5,
+            colorizer: Colorizer::Rgba,
+        });
+
+        registry
+            .set_style_preset(
+                &session,
+                &id,
+                StylePresetName::from("rgba").validated().unwrap(),
+                rgba.clone(),
+            )
+            .await
+            .unwrap();
+        registry
+            .set_style_preset(
+                &session,
+                &id,
+                StylePresetName::from("half").validated().unwrap(),
+                rgba.clone(),
+            )
+            .await
+            .unwrap();
+        registry
+            .set_style_preset(
+                &session,
+                &id,
+                StylePresetName::from("half").validated().unwrap(),
+                transparent.clone(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry
+                .style_preset(&session, &id, &StylePresetName::from("rgba"))
+                .await
+                .unwrap(),
+            rgba
+        );
+        assert_eq!(
+            registry.list_style_presets(&session, &id).await.unwrap(),
+            vec![
+                StylePreset {
+                    name: StylePresetName::from("half"),
+                    symbology: transparent,
+                },
+                StylePreset {
+                    name: StylePresetName::from("rgba"),
+                    symbology: rgba.clone(),
+                },
+            ]
+        );
+
+        registry
+            .remove_style_preset(&session, &id, &StylePresetName::from("half"))
+            .await
+            .unwrap();
+        assert!(registry
+            .style_preset(&session, &id, &StylePresetName::from("half"))
+            .await
+            .is_err());
+
+        assert!(registry
+            .set_style_preset(
+                &session,
+                &WorkflowId::new(),
+                StylePresetName::from("rgba").validated().unwrap(),
+                rgba,
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_lists_and_searches_templates() {
+        let registry = HashMapRegistry::default();
+        let session = SimpleSession::default();
+
+        let template = |name: &str, description: &str| WorkflowTemplate {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: vec![],
+            workflow: serde_json::to_value(mock_point_workflow(1.)).unwrap(),
+        };
+
+        let ndvi = registry
+            .register_template(
+                &session,
+                template("NDVI", "vegetation index").validated().unwrap(),
+            )
+            .await
+            .unwrap();
+        let points = registry
+            .register_template(
+                &session,
+                template("Points", "mock points").validated().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry.load_template(&session, &ndvi).await.unwrap(),
+            template("NDVI", "vegetation index")
+        );
+        assert!(registry
+            .load_template(&session, &WorkflowTemplateId::new())
+            .await
+            .is_err());
+
+        let options = |filter: Option<&str>, order: OrderBy| WorkflowTemplateListOptions {
+            filter: filter.map(ToString::to_string),
+            order,
+            offset: 0,
+            limit: 10,
+        };
+
+        let ids = |listings: Vec<WorkflowTemplateListing>| {
+            listings.into_iter().map(|l| l.id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(registry
+                .list_templates(
+                    &session,
+                    options(None, OrderBy::NameDesc).validated().unwrap()
+                )
+                .await
+                .unwrap()),
+            vec![points, ndvi]
+        );
+        assert_eq!(
+            ids(registry
+                .list_templates(
+                    &session,
+                    options(Some("vegetation"), OrderBy::NameAsc)
+                        .validated()
+                        .unwrap()
+                )
+                .await
+                .unwrap()),
+            vec![ndvi]
+        );
     }
 }
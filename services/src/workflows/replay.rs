@@ -0,0 +1,314 @@
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::FeatureCollectionInfos;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, ResultDescriptor,
+    TypedPlotQueryProcessor,
+};
+use geoengine_operators::processing::{
+    InitializedRasterReprojection, InitializedVectorReprojection, ReprojectionParams,
+};
+use geoengine_operators::{call_on_generic_raster_processor, call_on_generic_vector_processor};
+use serde::Serialize;
+use snafu::ResultExt;
+
+use crate::contexts::Context;
+use crate::error::{self, Result};
+use crate::workflows::query_log::{QueryLogEntry, RecordedQuery};
+
+/// The outcome of replaying a recorded query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub recorded_at: DateTime<Utc>,
+    pub query: RecordedQuery,
+    pub recorded_duration_ms: u64,
+    pub replayed_duration_ms: u64,
+    /// The number of tiles, features or plots, `None` if the query failed
+    pub results: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// A comparison of the recorded and replayed durations of queries
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub queries: Vec<ReplayResult>,
+    pub failed: usize,
+    pub recorded_duration_ms: u64,
+    pub replayed_duration_ms: u64,
+}
+
+/// Replays the queries one after another, so that their durations are comparable to the recorded ones
+pub async fn replay_query_log<C: Context>(
+    ctx: &C,
+    session: C::Session,
+    entries: &[QueryLogEntry],
+) -> ReplayReport {
+    let mut queries = Vec::with_capacity(entries.len());
+    for entry in entries {
+        queries.push(replay_query(ctx, session.clone(), entry).await);
+    }
+
+    ReplayReport {
+        failed: queries.iter().filter(|query| query.error.is_some()).count(),
+        recorded_duration_ms: queries.iter().map(|query| query.recorded_duration_ms).sum(),
+        replayed_duration_ms: queries.iter().map(|query| query.replayed_duration_ms).sum(),
+        queries,
+    }
+}
+
+/// Initializes the workflow of the entry and executes its query
+pub async fn replay_query<C: Context>(
+    ctx: &C,
+    session: C::Session,
+    entry: &QueryLogEntry,
+) -> ReplayResult {
+    let started = Instant::now();
+
+    let result = execute_query(ctx, session, entry).await;
+
+    let replayed_duration_ms = started.elapsed().as_millis() as u64;
+
+    let (results, error) = match result {
+        Ok(results) => (Some(results), None),
+        Err(error) => (None, Some(error.to_string())),
+    };
+
+    ReplayResult {
+        recorded_at: entry.recorded_at,
+        query: entry.query,
+        recorded_duration_ms: entry.duration_ms,
+        replayed_duration_ms,
+        results,
+        error,
+    }
+}
+
+/// Executes the query like the handlers do, i.e., reprojects the workflow to the spatial reference of the query
+async fn execute_query<C: Context>(
+    ctx: &C,
+    session: C::Session,
+    entry: &QueryLogEntry,
+) -> Result<usize> {
+    let execution_context = ctx.execution_context(session)?;
+    let query_ctx = ctx.query_context()?;
+
+    let operator = entry.workflow.operator.clone();
+
+    match entry.query {
+        RecordedQuery::Raster(query) => {
+            let initialized = operator
+                .get_raster()
+                .context(error::Operator)?
+                .initialize(&execution_context)
+                .await
+                .context(error::Operator)?;
+
+            let initialized: Box<dyn InitializedRasterOperator> = if requires_reprojection(
+                initialized.result_descriptor(),
+                entry.spatial_reference,
+            ) {
+                Box::new(
+                    InitializedRasterReprojection::try_new_with_input(
+                        ReprojectionParams {
+                            target_spatial_reference: entry.spatial_reference,
+                        },
+                        initialized,
+                        execution_context.tiling_specification(),
+                    )
+                    .context(error::Operator)?,
+                )
+            } else {
+                initialized
+            };
+
+            let processor = initialized.query_processor().context(error::Operator)?;
+
+            call_on_generic_raster_processor!(processor, p => count_results(
+                p.raster_query(query, &query_ctx).await.context(error::Operator)?,
+                |_| 1,
+            ).await)
+        }
+        RecordedQuery::Vector(query) => {
+            let initialized = operator
+                .get_vector()
+                .context(error::Operator)?
+                .initialize(&execution_context)
+                .await
+                .context(error::Operator)?;
+
+            let initialized: Box<dyn InitializedVectorOperator> = if requires_reprojection(
+                initialized.result_descriptor(),
+                entry.spatial_reference,
+            ) {
+                Box::new(
+                    InitializedVectorReprojection::try_new_with_input(
+                        ReprojectionParams {
+                            target_spatial_reference: entry.spatial_reference,
+                        },
+                        initialized,
+                    )
+                    .context(error::Operator)?,
+                )
+            } else {
+                initialized
+            };
+
+            let processor = initialized.query_processor().context(error::Operator)?;
+
+            call_on_generic_vector_processor!(processor, p => count_results(
+                p.vector_query(query, &query_ctx).await.context(error::Operator)?,
+                FeatureCollectionInfos::len,
+            ).await)
+        }
+        RecordedQuery::Plot(query) => {
+            // plot queries are recorded in the spatial reference of the workflow
+            let processor = operator
+                .get_plot()
+                .context(error::Operator)?
+                .initialize(&execution_context)
+                .await
+                .context(error::Operator)?
+                .query_processor()
+                .context(error::Operator)?;
+
+            match processor {
+                TypedPlotQueryProcessor::JsonPlain(p) => {
+                    p.plot_query(query, &query_ctx)
+                        .await
+                        .context(error::Operator)?;
+                }
+                TypedPlotQueryProcessor::JsonVega(p) => {
+                    p.plot_query(query, &query_ctx)
+                        .await
+                        .context(error::Operator)?;
+                }
+                TypedPlotQueryProcessor::ImagePng(p) => {
+                    p.plot_query(query, &query_ctx)
+                        .await
+                        .context(error::Operator)?;
+                }
+            }
+
+            Ok(1)
+        }
+    }
+}
+
+fn requires_reprojection<R: ResultDescriptor>(
+    result_descriptor: &R,
+    query_spatial_reference: SpatialReference,
+) -> bool {
+    let workflow_spatial_reference: Option<SpatialReference> =
+        result_descriptor.spatial_reference().into();
+
+    workflow_spatial_reference.map_or(false, |spatial_reference| {
+        spatial_reference != query_spatial_reference
+    })
+}
+
+/// Consumes the result stream and sums up the number of results of its elements
+async fn count_results<T>(
+    stream: futures::stream::BoxStream<'_, geoengine_operators::util::Result<T>>,
+    count: impl Fn(&T) -> usize,
+) -> Result<usize> {
+    stream
+        .try_fold(0, |results, element| {
+            let results = results + count(&element);
+            async move { Ok(results) }
+        })
+        .await
+        .context(error::Operator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, SimpleContext};
+    use crate::workflows::workflow::Workflow;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, Measurement, RasterQueryRectangle, SpatialPartition2D, SpatialResolution,
+        TimeInterval, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::raster::RasterDataType;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::engine::{RasterOperator, VectorOperator};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
+    use geoengine_operators::source::{ConstantRasterSource, ConstantRasterSourceParameters};
+
+    #[tokio::test]
+    async fn it_replays_queries() {
+        let ctx = InMemoryContext::test_default();
+        let session = ctx.default_session_ref().await.clone();
+
+        let points = Workflow {
+            operator: MockPointSource {
+                params: MockPointSourceParams {
+                    points: vec![(1., 1.).into(), (2., 2.).into(), (3., 3.).into()],
+                },
+            }
+            .boxed()
+            .into(),
+        };
+
+        let raster = Workflow {
+            operator: ConstantRasterSource {
+                params: ConstantRasterSourceParameters {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326(),
+                    extent: Some(
+                        SpatialPartition2D::new((0., 10.).into(), (10., 0.).into()).unwrap(),
+                    ),
+                    time: TimeInterval::default(),
+                    resolution: Some(SpatialResolution::one()),
+                    measurement: Measurement::Unitless,
+                    value: 7.,
+                },
+            }
+            .boxed()
+            .into(),
+        };
+
+        let vector_query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+        };
+
+        let entry = |workflow: &Workflow, query| QueryLogEntry {
+            recorded_at: Utc::now(),
+            workflow: workflow.clone(),
+            spatial_reference: SpatialReference::epsg_4326(),
+            query,
+            duration_ms: 10,
+        };
+
+        let entries = vec![
+            entry(&points, RecordedQuery::Vector(vector_query)),
+            entry(
+                &raster,
+                RecordedQuery::Raster(RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 10.).into(), (10., 0.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                }),
+            ),
+            // the query does not match the workflow
+            entry(&raster, RecordedQuery::Vector(vector_query)),
+        ];
+
+        let report = replay_query_log(&ctx, session, &entries).await;
+
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.recorded_duration_ms, 30);
+        assert_eq!(report.queries[0].results, Some(3));
+        assert_eq!(report.queries[1].results, Some(1));
+        assert_eq!(report.queries[2].results, None);
+        assert!(report.queries[2].error.is_some());
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use utoipa::ToSchema;
+
+use crate::error::{self, Result};
+use crate::projects::Symbology;
+use crate::util::user_input::UserInput;
+
+/// The name of a style preset of a workflow.
+///
+/// WMS clients reference a preset with `styles=preset:{name}` in `GetMap` requests.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+pub struct StylePresetName(String);
+
+impl StylePresetName {
+    const MAX_LENGTH: usize = 256;
+}
+
+impl From<&str> for StylePresetName {
+    fn from(name: &str) -> Self {
+        Self(name.to_owned())
+    }
+}
+
+impl AsRef<str> for StylePresetName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for StylePresetName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl UserInput for StylePresetName {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.0.is_empty() && self.0.len() <= Self::MAX_LENGTH,
+            error::InvalidStringLength {
+                parameter: "name".to_string(),
+                min: 1_usize,
+                max: Self::MAX_LENGTH,
+            }
+        );
+
+        ensure!(
+            self.0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'),
+            error::InvalidStylePresetName {
+                name: self.0.clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// A named symbology of a workflow, e.g., a colorizer for a raster workflow
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StylePreset {
+    pub name: StylePresetName,
+    pub symbology: Symbology,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_names() {
+        assert!(StylePresetName::from("ndvi-2014_v1.2").validate().is_ok());
+        assert!(StylePresetName::from("").validate().is_err());
+        assert!(StylePresetName::from("with space").validate().is_err());
+        assert!(StylePresetName::from("preset:ndvi").validate().is_err());
+    }
+}
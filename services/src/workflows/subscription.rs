@@ -0,0 +1,255 @@
+use futures::TryStreamExt;
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, SpatialResolution, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{GridOrEmpty, GridSize, Pixel};
+use geoengine_operators::engine::{QueryContext, RasterQueryProcessor};
+use geoengine_operators::util::number_statistics::NumberStatistics;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use utoipa::ToSchema;
+
+use crate::error::{self, Result};
+use crate::tasks::TaskStatusInfo;
+use crate::util::user_input::UserInput;
+
+/// A standing query on a raster workflow.
+///
+/// The time extent of the workflow is checked periodically. Whenever it grows, e.g., because new time slices
+/// were added to an underlying dataset, the new time slices are aggregated over the spatial bounds and
+/// a notification is recorded for each time slice that meets the condition.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "spatialBounds": {"upperLeftCoordinate": {"x": -10.0, "y": 80.0}, "lowerRightCoordinate": {"x": 50.0, "y": 20.0}},
+    "spatialResolution": {"x": 0.1, "y": 0.1},
+    "condition": {"aggregate": "mean", "comparison": "below", "threshold": 0.3}
+}))]
+pub struct WorkflowSubscription {
+    pub spatial_bounds: SpatialPartition2D,
+    pub spatial_resolution: SpatialResolution,
+    pub condition: SubscriptionCondition,
+    /// only time slices that start at or after this instant are evaluated,
+    /// defaults to the start of the workflow's time extent
+    #[serde(default)]
+    pub start: Option<TimeInstance>,
+    /// the subscription finishes when all time slices before this instant are evaluated,
+    /// otherwise it runs until its task is aborted
+    #[serde(default)]
+    pub end: Option<TimeInstance>,
+    /// seconds between two checks of the workflow's time extent, defaults to the configured interval
+    #[serde(default)]
+    pub poll_interval_seconds: Option<u64>,
+}
+
+impl UserInput for WorkflowSubscription {
+    fn validate(&self) -> Result<()> {
+        if let Some(poll_interval_seconds) = self.poll_interval_seconds {
+            ensure!(
+                poll_interval_seconds > 0,
+                error::InvalidWorkflowSubscription {
+                    reason: "the poll interval must be greater than zero".to_string(),
+                }
+            );
+        }
+
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            ensure!(
+                start < end,
+                error::InvalidWorkflowSubscription {
+                    reason: "the start must be before the end".to_string(),
+                }
+            );
+        }
+
+        ensure!(
+            self.condition.threshold.is_finite(),
+            error::InvalidWorkflowSubscription {
+                reason: "the threshold must be a finite number".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// A condition on the aggregated pixel values of a time slice, e.g., mean NDVI below 0.3
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionCondition {
+    pub aggregate: SubscriptionAggregate,
+    pub comparison: SubscriptionComparison,
+    pub threshold: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionAggregate {
+    Min,
+    Max,
+    Mean,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionComparison {
+    Below,
+    Above,
+}
+
+impl SubscriptionCondition {
+    /// Returns the aggregated value if the condition is met.
+    /// Time slices without valid pixels never meet the condition.
+    pub fn evaluate(&self, statistics: &NumberStatistics) -> Option<f64> {
+        let value = match self.aggregate {
+            SubscriptionAggregate::Min => statistics.min(),
+            SubscriptionAggregate::Max => statistics.max(),
+            SubscriptionAggregate::Mean => statistics.mean(),
+        };
+
+        let is_met = match self.comparison {
+            SubscriptionComparison::Below => value < self.threshold,
+            SubscriptionComparison::Above => value > self.threshold,
+        };
+
+        is_met.then_some(value)
+    }
+}
+
+/// The state of a subscription, which is the status info of its task
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionStatus {
+    /// all time slices that start before this instant are evaluated
+    pub evaluated_until: Option<TimeInstance>,
+    pub notifications: Vec<SubscriptionNotification>,
+}
+
+impl TaskStatusInfo for SubscriptionStatus {}
+
+/// A time slice that met the condition of a subscription
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionNotification {
+    pub time: TimeInterval,
+    /// the aggregated value of the time slice
+    pub value: f64,
+}
+
+impl SubscriptionStatus {
+    /// Records a notification for each time slice that starts at or after `start` and meets the `condition`.
+    /// Afterwards, everything before `end` counts as evaluated.
+    pub fn record(
+        &mut self,
+        condition: &SubscriptionCondition,
+        time_slices: &[(TimeInterval, NumberStatistics)],
+        start: TimeInstance,
+        end: TimeInstance,
+    ) {
+        self.notifications.extend(
+            time_slices
+                .iter()
+                .filter(|(time, _)| time.start() >= start)
+                .filter_map(|(time, statistics)| {
+                    condition
+                        .evaluate(statistics)
+                        .map(|value| SubscriptionNotification { time: *time, value })
+                }),
+        );
+
+        self.evaluated_until = Some(end);
+    }
+}
+
+/// Computes the statistics of the valid pixels of each time slice of the query result.
+pub async fn time_slice_statistics<T: Pixel>(
+    processor: &dyn RasterQueryProcessor<RasterType = T>,
+    query: RasterQueryRectangle,
+    query_ctx: &dyn QueryContext,
+) -> Result<Vec<(TimeInterval, NumberStatistics)>> {
+    processor
+        .raster_query(query, query_ctx)
+        .await
+        .context(error::Operator)?
+        .map_err(|source| error::Error::Operator { source })
+        .try_fold(
+            Vec::<(TimeInterval, NumberStatistics)>::new(),
+            |mut time_slices, tile| async move {
+                // tiles are ordered by time
+                if time_slices.last().map(|(time, _)| *time) != Some(tile.time) {
+                    time_slices.push((tile.time, NumberStatistics::default()));
+                }
+
+                if let Some((_, statistics)) = time_slices.last_mut() {
+                    match &tile.grid_array {
+                        GridOrEmpty::Grid(grid) => {
+                            for value in grid.masked_element_deref_iterator() {
+                                if let Some(value) = value {
+                                    statistics.add(value);
+                                } else {
+                                    statistics.add_no_data();
+                                }
+                            }
+                        }
+                        GridOrEmpty::Empty(empty) => {
+                            statistics.add_no_data_batch(empty.number_of_elements());
+                        }
+                    }
+                }
+
+                Ok(time_slices)
+            },
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statistics(values: &[f64]) -> NumberStatistics {
+        let mut statistics = NumberStatistics::default();
+        for value in values {
+            statistics.add(*value);
+        }
+        statistics
+    }
+
+    #[test]
+    fn it_records_time_slices_that_meet_the_condition() {
+        let condition = SubscriptionCondition {
+            aggregate: SubscriptionAggregate::Mean,
+            comparison: SubscriptionComparison::Below,
+            threshold: 0.3,
+        };
+
+        let time_slices = vec![
+            (
+                TimeInterval::new_unchecked(0, 10),
+                statistics(&[0.1, 0.2, 0.3]),
+            ),
+            (TimeInterval::new_unchecked(10, 20), statistics(&[0.5, 0.6])),
+            (TimeInterval::new_unchecked(20, 30), statistics(&[0.2, 0.3])),
+            (TimeInterval::new_unchecked(30, 40), statistics(&[])),
+        ];
+
+        let mut status = SubscriptionStatus::default();
+        status.record(
+            &condition,
+            &time_slices,
+            TimeInstance::from_millis_unchecked(10),
+            TimeInstance::from_millis_unchecked(40),
+        );
+
+        assert_eq!(
+            status,
+            SubscriptionStatus {
+                evaluated_until: Some(TimeInstance::from_millis_unchecked(40)),
+                notifications: vec![SubscriptionNotification {
+                    time: TimeInterval::new_unchecked(20, 30),
+                    value: 0.25,
+                }],
+            }
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use std::collections::{HashMap, HashSet};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use snafu::ensure;
+use utoipa::{IntoParams, ToSchema};
+
+use super::workflow::Workflow;
+use crate::api::model::datatypes::DataId;
+use crate::datasets::listing::OrderBy;
+use crate::error::{self, Result};
+use crate::identifier;
+use crate::util::config::{get_config_element, WorkflowTemplateService};
+use crate::util::user_input::UserInput;
+
+identifier!(WorkflowTemplateId);
+
+lazy_static! {
+    /// A placeholder like `${threshold}` inside of a string of the template
+    static ref PLACEHOLDER: Regex =
+        Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("Expression is valid");
+    /// A string that consists of a single placeholder and is thus replaced by the typed value
+    static ref SINGLE_PLACEHOLDER: Regex =
+        Regex::new(r"^\$\{([A-Za-z_][A-Za-z0-9_]*)\}$").expect("Expression is valid");
+    static ref PARAMETER_NAME: Regex =
+        Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("Expression is valid");
+}
+
+/// A reusable workflow that contains placeholders like `${dataset}` or `${threshold}`.
+///
+/// A string that consists of a single placeholder is replaced by the (typed) value of the parameter,
+/// e.g., a number or a data id. Placeholders inside of longer strings are replaced by the textual value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "name": "Dataset points",
+    "description": "Points of a dataset",
+    "parameters": [
+        {"name": "dataset", "type": "dataId"},
+        {"name": "x", "type": "number", "default": 0.0}
+    ],
+    "workflow": {
+        "type": "Vector",
+        "operator": {
+            "type": "OgrSource",
+            "params": {"data": "${dataset}", "attributeProjection": null, "attributeFilters": null}
+        }
+    }
+}))]
+pub struct WorkflowTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
+    /// the workflow with placeholders
+    #[schema(value_type = Object)]
+    pub workflow: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateParameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub parameter_type: TemplateParameterType,
+    #[serde(default)]
+    pub description: String,
+    /// the value that is used if the parameter is not given on instantiation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    pub default: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TemplateParameterType {
+    Number,
+    Integer,
+    Boolean,
+    String,
+    DataId,
+}
+
+impl TemplateParameterType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            TemplateParameterType::Number => value.is_number(),
+            TemplateParameterType::Integer => value.is_i64() || value.is_u64(),
+            TemplateParameterType::Boolean => value.is_boolean(),
+            TemplateParameterType::String => value.is_string(),
+            TemplateParameterType::DataId => {
+                serde_json::from_value::<DataId>(value.clone()).is_ok()
+            }
+        }
+    }
+}
+
+impl TemplateParameter {
+    fn check(&self, value: &Value) -> Result<()> {
+        ensure!(
+            self.parameter_type.matches(value),
+            error::InvalidWorkflowTemplateParameterValue {
+                parameter: self.name.clone(),
+                reason: format!("expected a value of type {:?}", self.parameter_type),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+impl WorkflowTemplate {
+    const MAX_NAME_LENGTH: usize = 256;
+
+    /// Creates a workflow by replacing all placeholders with the given `values` or the defaults of the parameters.
+    /// The workflow is checked against the parameters of its operators when it is deserialized.
+    pub fn instantiate(&self, mut values: HashMap<String, Value>) -> Result<Workflow> {
+        if let Some(unknown) = values
+            .keys()
+            .find(|name| !self.parameters.iter().any(|p| &p.name == *name))
+        {
+            return Err(error::Error::InvalidWorkflowTemplateParameterValue {
+                parameter: unknown.clone(),
+                reason: "the template has no such parameter".to_string(),
+            });
+        }
+
+        let mut resolved = HashMap::with_capacity(self.parameters.len());
+        for parameter in &self.parameters {
+            let value = match values
+                .remove(&parameter.name)
+                .or_else(|| parameter.default.clone())
+            {
+                Some(value) => value,
+                None => {
+                    return Err(error::Error::InvalidWorkflowTemplateParameterValue {
+                        parameter: parameter.name.clone(),
+                        reason: "the parameter has no default and must be given".to_string(),
+                    })
+                }
+            };
+
+            parameter.check(&value)?;
+
+            resolved.insert(parameter.name.clone(), value);
+        }
+
+        serde_json::from_value(substitute(self.workflow.clone(), &resolved)).map_err(|source| {
+            error::Error::InvalidWorkflowTemplateInstance {
+                reason: source.to_string(),
+            }
+        })
+    }
+
+    pub fn listing(&self, id: WorkflowTemplateId) -> WorkflowTemplateListing {
+        WorkflowTemplateListing {
+            id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            parameters: self.parameters.clone(),
+        }
+    }
+}
+
+impl UserInput for WorkflowTemplate {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.name.is_empty() && self.name.len() <= Self::MAX_NAME_LENGTH,
+            error::InvalidStringLength {
+                parameter: "name".to_string(),
+                min: 1_usize,
+                max: Self::MAX_NAME_LENGTH,
+            }
+        );
+
+        let mut names = HashSet::with_capacity(self.parameters.len());
+        for parameter in &self.parameters {
+            ensure!(
+                PARAMETER_NAME.is_match(&parameter.name),
+                error::InvalidWorkflowTemplate {
+                    reason: format!(
+                        "parameter name `{}` must consist of ASCII alphanumerics and `_`",
+                        parameter.name
+                    ),
+                }
+            );
+            ensure!(
+                names.insert(parameter.name.as_str()),
+                error::InvalidWorkflowTemplate {
+                    reason: format!("parameter `{}` is declared twice", parameter.name),
+                }
+            );
+
+            if let Some(default) = &parameter.default {
+                parameter.check(default)?;
+            }
+        }
+
+        let mut placeholders = HashSet::new();
+        collect_placeholders(&self.workflow, &mut placeholders);
+
+        if let Some(undeclared) = placeholders
+            .iter()
+            .find(|placeholder| !names.contains(placeholder.as_str()))
+        {
+            return Err(error::Error::InvalidWorkflowTemplate {
+                reason: format!(
+                    "placeholder `${{{}}}` is not declared as parameter",
+                    undeclared
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn collect_placeholders(value: &Value, placeholders: &mut HashSet<String>) {
+    match value {
+        Value::String(s) => {
+            for captures in PLACEHOLDER.captures_iter(s) {
+                placeholders.insert(captures[1].to_string());
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_placeholders(value, placeholders);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values() {
+                collect_placeholders(value, placeholders);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn substitute(value: Value, values: &HashMap<String, Value>) -> Value {
+    match value {
+        Value::String(s) => {
+            if let Some(value) = SINGLE_PLACEHOLDER
+                .captures(&s)
+                .and_then(|captures| values.get(&captures[1]))
+            {
+                return value.clone();
+            }
+
+            Value::String(
+                PLACEHOLDER
+                    .replace_all(&s, |captures: &Captures| match values.get(&captures[1]) {
+                        Some(Value::String(value)) => value.clone(),
+                        Some(value) => value.to_string(),
+                        None => captures[0].to_string(),
+                    })
+                    .into_owned(),
+            )
+        }
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(|value| substitute(value, values))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, substitute(value, values)))
+                .collect(),
+        ),
+        Value::Null | Value::Bool(_) | Value::Number(_) => value,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTemplateListing {
+    pub id: WorkflowTemplateId,
+    pub name: String,
+    pub description: String,
+    pub parameters: Vec<TemplateParameter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTemplateListOptions {
+    /// only list templates whose name or description contains the filter
+    pub filter: Option<String>,
+    #[param(value_type = String, example = "NameAsc")]
+    pub order: OrderBy,
+    #[param(example = 0)]
+    pub offset: u32,
+    #[param(example = 20)]
+    pub limit: u32,
+}
+
+impl UserInput for WorkflowTemplateListOptions {
+    fn validate(&self) -> Result<()> {
+        let limit = get_config_element::<WorkflowTemplateService>()?.list_limit;
+        ensure!(
+            self.limit <= limit,
+            error::InvalidListLimit {
+                limit: limit as usize
+            }
+        );
+
+        if let Some(filter) = &self.filter {
+            ensure!(
+                filter.len() >= 3 && filter.len() <= 256,
+                error::InvalidStringLength {
+                    parameter: "filter".to_string(),
+                    min: 3_usize,
+                    max: 256_usize
+                }
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::model::datatypes::DatasetId;
+    use crate::util::Identifier;
+
+    fn template() -> WorkflowTemplate {
+        serde_json::from_value(serde_json::json!({
+            "name": "Points",
+            "parameters": [
+                {"name": "x", "type": "number"},
+                {"name": "y", "type": "number", "default": 0.5},
+            ],
+            "workflow": {
+                "type": "Vector",
+                "operator": {
+                    "type": "MockPointSource",
+                    "params": {
+                        "points": [{"x": "${x}", "y": "${y}"}]
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn it_validates_templates() {
+        assert!(template().validate().is_ok());
+
+        let mut undeclared = template();
+        undeclared.parameters.pop();
+        assert!(undeclared.validate().is_err());
+
+        let mut duplicate = template();
+        duplicate.parameters[1].name = "x".to_string();
+        assert!(duplicate.validate().is_err());
+
+        let mut wrong_default = template();
+        wrong_default.parameters[1].default = Some(Value::String("0.5".to_string()));
+        assert!(wrong_default.validate().is_err());
+    }
+
+    #[test]
+    fn it_instantiates_templates() {
+        let workflow = template()
+            .instantiate([("x".to_string(), serde_json::json!(1.0))].into())
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&workflow).unwrap(),
+            serde_json::json!({
+                "type": "Vector",
+                "operator": {
+                    "type": "MockPointSource",
+                    "params": {
+                        "points": [{"x": 1.0, "y": 0.5}]
+                    }
+                }
+            })
+        );
+
+        // missing parameter without default
+        assert!(template().instantiate(HashMap::new()).is_err());
+        // unknown parameter
+        assert!(template()
+            .instantiate(
+                [
+                    ("x".to_string(), serde_json::json!(1.0)),
+                    ("z".to_string(), serde_json::json!(1.0))
+                ]
+                .into()
+            )
+            .is_err());
+        // wrong type
+        assert!(template()
+            .instantiate([("x".to_string(), serde_json::json!("1.0"))].into())
+            .is_err());
+    }
+
+    #[test]
+    fn it_substitutes_data_ids_and_text() {
+        let template: WorkflowTemplate = serde_json::from_value(serde_json::json!({
+            "name": "Raster",
+            "parameters": [
+                {"name": "dataset", "type": "dataId"},
+                {"name": "band", "type": "integer"},
+            ],
+            "workflow": {
+                "type": "Raster",
+                "operator": {
+                    "type": "GdalSource",
+                    "params": {
+                        "data": "${dataset}",
+                        "comment": "band ${band}"
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        assert!(template.validate().is_ok());
+
+        let dataset_id = DatasetId::new();
+        let data_id: DataId = dataset_id.into();
+
+        let values: HashMap<String, Value> = [
+            (
+                "dataset".to_string(),
+                serde_json::to_value(&data_id).unwrap(),
+            ),
+            ("band".to_string(), serde_json::json!(2)),
+        ]
+        .into();
+
+        let substituted = substitute(template.workflow.clone(), &values);
+        assert_eq!(
+            substituted["operator"]["params"]["data"],
+            serde_json::to_value(&data_id).unwrap()
+        );
+        assert_eq!(
+            substituted["operator"]["params"]["comment"],
+            serde_json::json!("band 2")
+        );
+
+        assert!(matches!(
+            template
+                .instantiate(
+                    [
+                        ("dataset".to_string(), serde_json::json!("not an id")),
+                        ("band".to_string(), serde_json::json!(2)),
+                    ]
+                    .into()
+                )
+                .unwrap_err(),
+            error::Error::InvalidWorkflowTemplateParameterValue { .. }
+        ));
+    }
+
+    #[test]
+    fn it_checks_instances_against_operator_parameters() {
+        let template: WorkflowTemplate = serde_json::from_value(serde_json::json!({
+            "name": "Points",
+            "parameters": [{"name": "points", "type": "string"}],
+            "workflow": {
+                "type": "Vector",
+                "operator": {
+                    "type": "MockPointSource",
+                    "params": {"points": "${points}"}
+                }
+            }
+        }))
+        .unwrap();
+        assert!(template.validate().is_ok());
+
+        assert!(matches!(
+            template
+                .instantiate([("points".to_string(), serde_json::json!("1,2"))].into())
+                .unwrap_err(),
+            error::Error::InvalidWorkflowTemplateInstance { .. }
+        ));
+    }
+}
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use snafu::{ensure, ResultExt};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::api::model::datatypes::DataId;
+use crate::error::{self, Result};
 use crate::identifier;
-use geoengine_operators::engine::TypedOperator;
+use crate::util::user_input::UserInput;
+use geoengine_operators::engine::{OperatorData, OperatorName, TypedOperator};
+use geoengine_operators::source::WorkflowReference;
 
 identifier!(WorkflowId);
 
@@ -18,6 +24,59 @@ impl WorkflowId {
     }
 }
 
+/// A human-readable name that refers to a workflow.
+///
+/// An alias moves along when new versions of its workflow are registered.
+/// Thus, it always points to the latest version.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub struct WorkflowAlias(String);
+
+impl WorkflowAlias {
+    const MAX_LENGTH: usize = 256;
+}
+
+impl From<&str> for WorkflowAlias {
+    fn from(alias: &str) -> Self {
+        Self(alias.to_owned())
+    }
+}
+
+impl AsRef<str> for WorkflowAlias {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for WorkflowAlias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl UserInput for WorkflowAlias {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.0.is_empty() && self.0.len() <= Self::MAX_LENGTH,
+            error::InvalidStringLength {
+                parameter: "alias".to_string(),
+                min: 1_usize,
+                max: Self::MAX_LENGTH,
+            }
+        );
+
+        ensure!(
+            self.0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'),
+            error::InvalidWorkflowAlias {
+                alias: self.0.clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
     "type": "Vector",
@@ -36,6 +95,137 @@ pub struct Workflow {
     pub operator: TypedOperator,
 }
 
+impl Workflow {
+    /// Returns the ids of all data that is read by the source operators of this workflow
+    pub fn data_ids(&self) -> Vec<DataId> {
+        self.operator
+            .data_ids()
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Returns the ids of all workflows that are embedded by the `WorkflowReference` operators of this workflow.
+    /// Their data is only known after loading them, e.g., with [`WorkflowRegistry::resolved_data_ids`].
+    ///
+    /// [`WorkflowRegistry::resolved_data_ids`]: super::registry::WorkflowRegistry::resolved_data_ids
+    pub fn workflow_references(&self) -> Vec<WorkflowId> {
+        let mut references = Vec::new();
+
+        if let Ok(workflow) = serde_json::to_value(self) {
+            collect_workflow_references(&workflow, &mut references);
+        }
+
+        references
+    }
+}
+
+/// Collects the workflow ids of all `WorkflowReference` operators within a serialized workflow
+fn collect_workflow_references(value: &Value, references: &mut Vec<WorkflowId>) {
+    match value {
+        Value::Object(map) => {
+            let is_reference = map.get("type").map_or(false, |operator_type| {
+                operator_type == WorkflowReference::TYPE_NAME
+            });
+
+            let workflow = map
+                .get("params")
+                .and_then(|params| params.get("workflow"))
+                .and_then(|workflow| serde_json::from_value::<Uuid>(workflow.clone()).ok());
+
+            if let (true, Some(workflow)) = (is_reference, workflow) {
+                references.push(WorkflowId(workflow));
+                return;
+            }
+
+            for value in map.values() {
+                collect_workflow_references(value, references);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_workflow_references(value, references);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+    }
+}
+
+impl Workflow {
+    /// Creates a copy of this workflow where all source operators that read the data of a substitution
+    /// are replaced by the operator of the substitution.
+    ///
+    /// Fails if a substitution does not match any source or if a replacement does not fit into the workflow.
+    pub fn substitute_sources(&self, substitutions: &[SourceSubstitution]) -> Result<Workflow> {
+        let mut workflow = serde_json::to_value(self).context(error::SerdeJson)?;
+
+        for substitution in substitutions {
+            let data = serde_json::to_value(&substitution.data).context(error::SerdeJson)?;
+
+            ensure!(
+                replace_sources(&mut workflow, &data, &substitution.operator) > 0,
+                error::InvalidWorkflowSubstitution {
+                    reason: format!("the workflow does not read the data `{}`", data),
+                }
+            );
+        }
+
+        serde_json::from_value(workflow).map_err(|source| {
+            error::Error::InvalidWorkflowSubstitution {
+                reason: source.to_string(),
+            }
+        })
+    }
+}
+
+/// Replaces all operators with the parameter `data` equal to `data` and returns the number of replacements
+fn replace_sources(value: &mut Value, data: &Value, replacement: &Value) -> usize {
+    match value {
+        Value::Object(map) => {
+            let is_source = map
+                .get("params")
+                .and_then(|params| params.get("data"))
+                .map_or(false, |source_data| source_data == data);
+
+            if is_source {
+                *value = replacement.clone();
+                return 1;
+            }
+
+            map.values_mut()
+                .map(|value| replace_sources(value, data, replacement))
+                .sum()
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .map(|value| replace_sources(value, data, replacement))
+            .sum(),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => 0,
+    }
+}
+
+/// Replaces the source operators of a workflow that read `data` with another `operator`,
+/// e.g., to compare the results of a workflow for a dataset and an uploaded sketch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "data": {"type": "internal", "datasetId": "a626c880-1c41-489b-9e19-9596d129859c"},
+    "operator": {
+        "type": "OgrSource",
+        "params": {
+            "data": {"type": "internal", "datasetId": "bd8ce4a3-b3b5-4bc4-a4cb-1a6ee4b7ec1b"},
+            "attributeProjection": null,
+            "attributeFilters": null
+        }
+    }
+}))]
+pub struct SourceSubstitution {
+    pub data: DataId,
+    /// the operator that replaces the sources
+    #[schema(value_type = Object)]
+    pub operator: Value,
+}
+
 impl PartialEq for Workflow {
     fn eq(&self, other: &Self) -> bool {
         match (serde_json::to_string(self), serde_json::to_string(other)) {
@@ -91,4 +281,64 @@ mod tests {
 
         // TODO: check deserialization
     }
+
+    #[test]
+    fn it_substitutes_sources() {
+        let workflow: Workflow = serde_json::from_value(serde_json::json!({
+            "type": "Vector",
+            "operator": {
+                "type": "OgrSource",
+                "params": {
+                    "data": {"type": "internal", "datasetId": "a626c880-1c41-489b-9e19-9596d129859c"},
+                    "attributeProjection": null,
+                    "attributeFilters": null
+                }
+            }
+        }))
+        .unwrap();
+
+        let sketch = serde_json::json!({
+            "type": "MockPointSource",
+            "params": {"points": [{"x": 1.0, "y": 2.0}]}
+        });
+
+        let substituted = workflow
+            .substitute_sources(&[SourceSubstitution {
+                data: serde_json::from_value(serde_json::json!({
+                    "type": "internal",
+                    "datasetId": "a626c880-1c41-489b-9e19-9596d129859c"
+                }))
+                .unwrap(),
+                operator: sketch.clone(),
+            }])
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&substituted).unwrap(),
+            serde_json::json!({"type": "Vector", "operator": sketch})
+        );
+
+        // the workflow does not read this dataset
+        assert!(workflow
+            .substitute_sources(&[SourceSubstitution {
+                data: serde_json::from_value(serde_json::json!({
+                    "type": "internal",
+                    "datasetId": "bd8ce4a3-b3b5-4bc4-a4cb-1a6ee4b7ec1b"
+                }))
+                .unwrap(),
+                operator: sketch,
+            }])
+            .is_err());
+    }
+
+    #[test]
+    fn it_validates_aliases() {
+        assert!(WorkflowAlias::from("ndvi-analysis_v2.1").validate().is_ok());
+        assert!(WorkflowAlias::from("").validate().is_err());
+        assert!(WorkflowAlias::from("../foo").validate().is_err());
+        assert!(WorkflowAlias::from("with space").validate().is_err());
+        assert!(WorkflowAlias::from("a".repeat(257).as_str())
+            .validate()
+            .is_err());
+    }
 }
@@ -0,0 +1,131 @@
+//! End-to-end scenarios that run against a Postgres database.
+//!
+//! Start the database with `docker compose -f services/tests/docker-compose.yml up -d` and run
+//! `cargo test --features integration-tests --test integration`.
+#![cfg(feature = "integration-tests")]
+
+use actix_web::test;
+use geoengine_operators::engine::{PlotOperator, RasterOperator, TypedOperator, VectorOperator};
+use geoengine_operators::plot::{Statistics, StatisticsParams};
+use geoengine_operators::source::{
+    GdalSource, GdalSourceParameters, OgrSource, OgrSourceParameters,
+};
+use geoengine_services::pro::util::integration::{
+    ndvi_dataset, ports_dataset, with_integration_app, IntegrationApp,
+};
+use geoengine_services::util::tests::read_body_json;
+use geoengine_services::workflows::workflow::Workflow;
+
+async fn ndvi_source(app: &IntegrationApp) -> Box<dyn RasterOperator> {
+    let dataset = app.add_dataset(ndvi_dataset()).await;
+
+    GdalSource {
+        params: GdalSourceParameters {
+            data: dataset.into(),
+        },
+    }
+    .boxed()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn it_renders_a_raster_dataset_via_wms() {
+    with_integration_app(|app| async move {
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(ndvi_source(&app).await),
+        };
+        let id = app.register_workflow(&workflow).await;
+
+        let res = app
+            .send(test::TestRequest::get().uri(&format!("/wms/{id}?service=WMS&version=1.3.0&request=GetMap&layers={id}&styles=&width=335&height=168&crs=EPSG:4326&bbox=-90.0,-180.0,90.0,180.0&format=image/png&transparent=FALSE&bgcolor=0xFFFFFF&exceptions=application/json&time=2014-04-01T12%3A00%3A00.000%2B00%3A00")))
+            .await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            include_bytes!("../../test_data/wms/get_map_ndvi.png") as &[u8],
+            test::read_body(res).await
+        );
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn it_serves_a_vector_dataset_via_wfs() {
+    with_integration_app(|app| async move {
+        let dataset = app.add_dataset(ports_dataset()).await;
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                OgrSource {
+                    params: OgrSourceParameters {
+                        data: dataset.into(),
+                        attribute_projection: None,
+                        attribute_filters: None,
+                        schema_merging: None,
+                    },
+                }
+                .boxed(),
+            ),
+        };
+        let id = app.register_workflow(&workflow).await;
+
+        let params = &[
+            ("request", "GetFeature"),
+            ("service", "WFS"),
+            ("version", "2.0.0"),
+            ("typeNames", &id.to_string()),
+            ("bbox", "50.88,1.85,52.95,4.82"),
+            ("srsName", "EPSG:4326"),
+        ];
+        let res = app
+            .send(test::TestRequest::get().uri(&format!(
+                "/wfs/{id}?{}",
+                serde_urlencoded::to_string(params).unwrap()
+            )))
+            .await;
+
+        assert_eq!(res.status(), 200);
+
+        let collection = read_body_json(res).await;
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert!(!collection["features"].as_array().unwrap().is_empty());
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn it_computes_a_plot_of_a_raster_dataset() {
+    with_integration_app(|app| async move {
+        let workflow = Workflow {
+            operator: TypedOperator::Plot(
+                Statistics {
+                    params: StatisticsParams {
+                        column_names: vec![],
+                    },
+                    sources: vec![ndvi_source(&app).await].into(),
+                }
+                .boxed(),
+            ),
+        };
+        let id = app.register_workflow(&workflow).await;
+
+        let params = &[
+            ("bbox", "-180,-90,180,90"),
+            ("crs", "EPSG:4326"),
+            ("time", "2014-04-01T12:00:00.0Z"),
+            ("spatialResolution", "1,1"),
+        ];
+        let res = app
+            .send(test::TestRequest::get().uri(&format!(
+                "/plot/{id}?{}",
+                serde_urlencoded::to_string(params).unwrap()
+            )))
+            .await;
+
+        assert_eq!(res.status(), 200);
+
+        let plot = read_body_json(res).await;
+        assert_eq!(plot["plotType"], "Statistics");
+        assert!(plot["data"]["Raster-1"]["validCount"].as_u64().unwrap() > 0);
+    })
+    .await;
+}